@@ -0,0 +1,122 @@
+// Criterion benchmarks for the paths tests/bench/run_bench.sh times: checkout,
+// commit and push on a large store, and info --all, checkout --all and push
+// --all on many stores, plus store discovery through the library.
+//
+// Runs against the fixtures of tests/bench/generate_fixtures.sh: the directory
+// in TRUNK_BENCH_FIXTURES as is, or otherwise small fixtures generated afresh
+// under target/ (scale them with FILES=, COMMITS= and STORES=).
+//
+//   cargo bench
+//   TRUNK_BENCH_FIXTURES=/tmp/trunk-bench cargo bench -- checkout
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+const GIT_TRUNK: &str = env!("CARGO_BIN_EXE_git-trunk");
+
+// Generated at most once per run, shared by both groups
+fn fixtures() -> &'static Path {
+    static FIXTURES: OnceLock<PathBuf> = OnceLock::new();
+    FIXTURES.get_or_init(locate_fixtures)
+}
+
+fn locate_fixtures() -> PathBuf {
+    if let Some(dir) = env::var_os("TRUNK_BENCH_FIXTURES") {
+        return PathBuf::from(dir);
+    }
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("bench-fixtures");
+    let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/bench/generate_fixtures.sh");
+    let mut command = Command::new("bash");
+    command.arg(&script).arg(&dir).stdout(Stdio::null());
+    for (name, default) in [("FILES", "2000"), ("COMMITS", "500"), ("STORES", "20")] {
+        command.env(name, env::var(name).unwrap_or_else(|_| default.to_string()));
+    }
+    let status = command.status().expect("failed to run generate_fixtures.sh");
+    assert!(status.success(), "generate_fixtures.sh failed");
+    dir
+}
+
+// Runs git-trunk in `dir` and panics if it fails, so a broken command is not
+// timed as a fast one
+fn git_trunk(dir: &Path, args: &[&str]) {
+    let output = Command::new(GIT_TRUNK)
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_TRUNK_NONINTERACTIVE", "1")
+        .stdin(Stdio::null())
+        .output()
+        .expect("failed to run git-trunk");
+    assert!(
+        output.status.success(),
+        "git-trunk {} failed in {}: {}",
+        args.join(" "),
+        dir.display(),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+// Appends a line to one document of the large store's checkout
+fn edit_large(large: &Path) {
+    let doc = large.join(".trunk/large/docs/section-00/doc-00000.md");
+    let mut file = OpenOptions::new().append(true).open(&doc).expect("failed to open a document of the large store");
+    writeln!(file, "bench run").unwrap();
+}
+
+fn large_store(c: &mut Criterion) {
+    let large = fixtures().join("large");
+    // commit_large and push_large need the checkout even when run alone
+    git_trunk(&large, &["checkout", "--store", "large", "--force"]);
+    let mut group = c.benchmark_group("large");
+    group.sample_size(10);
+    group.bench_function("checkout_large", |b| {
+        b.iter(|| git_trunk(&large, &["checkout", "--store", "large", "--force"]))
+    });
+    group.bench_function("commit_large", |b| {
+        b.iter_batched(
+            || edit_large(&large),
+            |()| git_trunk(&large, &["commit", "--store", "large", "--force", "-m", "Bench run"]),
+            BatchSize::PerIteration,
+        )
+    });
+    group.bench_function("push_large", |b| {
+        b.iter_batched(
+            || {
+                edit_large(&large);
+                git_trunk(&large, &["commit", "--store", "large", "--force", "-m", "Bench run"]);
+            },
+            |()| git_trunk(&large, &["push", "--store", "large"]),
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+fn many_stores(c: &mut Criterion) {
+    let fixtures = fixtures();
+    let many = fixtures.join("many");
+    let many_clone = fixtures.join("many-clone");
+    let mut group = c.benchmark_group("many");
+    group.sample_size(10);
+    group.bench_function("info_all_many", |b| {
+        b.iter(|| git_trunk(&many, &["info", "--all", "--short", "--no-table"]))
+    });
+    group.bench_function("checkout_all_many", |b| {
+        b.iter(|| git_trunk(&many_clone, &["checkout", "--all", "--force", "--no-table"]))
+    });
+    group.bench_function("push_all_many", |b| {
+        b.iter(|| git_trunk(&many, &["push", "--all", "--no-table"]))
+    });
+    group.bench_function("stores_offline_many", |b| {
+        b.iter(|| git_trunk::stores::stores(&many).expect("failed to list the stores").offline().count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, large_store, many_stores);
+criterion_main!(benches);
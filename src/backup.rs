@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local};
+use log::debug;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+use crate::utils::{dry_run, run_git_command};
+
+// Safety copies `delete` makes of a store before removing its refs: a git
+// bundle per deletion in <common git dir>/trunk-backups, named
+// <store>-<YYYYmmdd-HHMMSS>.bundle so a store's backups sort by date.
+const BACKUP_DIR: &str = "trunk-backups";
+const BUNDLE_EXTENSION: &str = "bundle";
+const DATE_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub store: String,
+    pub created: DateTime<Local>,
+    pub size: u64,
+}
+
+pub fn backup_dir(repo: &GitRepo) -> Result<PathBuf, TrunkError> {
+    repo.common_dir().map(|dir| dir.join(BACKUP_DIR)).map_err(|e| TrunkError::Git(format!("Failed to find the git directory: {}", e)))
+}
+
+fn parse_name(path: &Path) -> Option<(String, DateTime<Local>)> {
+    if path.extension()? != BUNDLE_EXTENSION {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    // The date holds one dash and store names may hold more, so split twice from the end
+    let (rest, time) = stem.rsplit_once('-')?;
+    let (store, date) = rest.rsplit_once('-')?;
+    let created = chrono::NaiveDateTime::parse_from_str(&format!("{}-{}", date, time), DATE_FORMAT).ok()?.and_local_timezone(Local).earliest()?;
+    Some((store.to_string(), created))
+}
+
+// Every backup, newest first.
+pub fn list(repo: &GitRepo) -> Result<Vec<Backup>, TrunkError> {
+    let dir = backup_dir(repo)?;
+    let Ok(entries) = fs::read_dir(&dir) else { return Ok(Vec::new()) };
+    let mut backups: Vec<Backup> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let (store, created) = parse_name(&path)?;
+            let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            Some(Backup { path, store, created, size })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| a.store.cmp(&b.store)));
+    Ok(backups)
+}
+
+// Writes a bundle holding `refs` (full ref names in the main repository) and
+// returns its path. Nothing is written under --dry-run.
+pub fn create(repo: &GitRepo, store_name: &str, refs: &[String], verbose: bool) -> Result<PathBuf, TrunkError> {
+    let dir = backup_dir(repo)?;
+    let bundle = dir.join(format!("{}-{}.{}", store_name, Local::now().format(DATE_FORMAT), BUNDLE_EXTENSION));
+    if dry_run(&format!("write {} with {}", bundle.display(), refs.join(", "))) {
+        return Ok(bundle);
+    }
+    fs::create_dir_all(&dir).map_err(|e| TrunkError::Io(format!("Failed to create {}: {}", dir.display(), e)))?;
+    let output = run_git_command(repo.git().arg("bundle").arg("create").arg(&bundle).args(refs), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to run git bundle create: {}", e)))?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&bundle);
+        return Err(TrunkError::Git(format!("Failed to write {}: {}", bundle.display(), String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    debug!("📦 Wrote {}", bundle.display());
+    Ok(bundle)
+}
+
+// The refs a bundle holds, as (ref name, hash), after checking it is complete.
+pub fn heads(repo: &GitRepo, bundle: &Path, verbose: bool) -> Result<Vec<(String, String)>, TrunkError> {
+    let output = run_git_command(repo.git().arg("bundle").arg("verify").arg("--quiet").arg(bundle), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to run git bundle verify: {}", e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Failed(format!("{} is not a usable bundle: {}", bundle.display(), String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    let output = run_git_command(repo.git().arg("bundle").arg("list-heads").arg(bundle), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to run git bundle list-heads: {}", e)))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' ').map(|(hash, name)| (name.to_string(), hash.to_string())))
+        .collect())
+}
@@ -0,0 +1,207 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use log::debug;
+use crate::config;
+use crate::git::GitRepo;
+
+// Data git-trunk can recompute (remote listings, hook run times) lives in a
+// per-user cache directory, one subdirectory per repository, instead of
+// files in the git directory: `$XDG_CACHE_HOME/git-trunk/<repo-id>`, falling
+// back to `~/.cache/git-trunk/<repo-id>`. `[trunk] cache_dir` in the user
+// config replaces `$XDG_CACHE_HOME/git-trunk`.
+pub const CACHE_DIR_NAME: &str = "git-trunk";
+
+const LOCK_FILE_NAME: &str = ".lock";
+// How long an invocation waits for another one to release the cache lock
+// before going on without the cache.
+const LOCK_WAIT: Duration = Duration::from_secs(30);
+// A lock older than this is assumed to belong to a crashed invocation.
+const STALE_LOCK_AFTER: Duration = Duration::from_secs(120);
+
+// Root of all git-trunk caches, None when neither cache_dir, XDG_CACHE_HOME
+// nor HOME is set.
+pub fn cache_home() -> Option<PathBuf> {
+    if let Some(cache_dir) = config::load_user().ok().and_then(|user_config| user_config.get_string("trunk", "cache_dir")) {
+        return Some(PathBuf::from(cache_dir));
+    }
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env::var_os("HOME").filter(|home| !home.is_empty()).map(|home| PathBuf::from(home).join(".cache")))
+        .map(|dir| dir.join(CACHE_DIR_NAME))
+}
+
+// Keeps cache file names to characters that are safe on every filesystem.
+pub fn sanitize_key(text: &str) -> String {
+    text.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' }).collect()
+}
+
+// Names a repository by its common git directory, so every worktree shares
+// one cache: the directory name for humans plus a hash of the full path,
+// e.g. `project-3f2a9c0d41b7e865`.
+pub fn repo_id(common_dir: &Path) -> String {
+    let path = fs::canonicalize(common_dir).unwrap_or_else(|_| common_dir.to_path_buf());
+    let name_source = if path.file_name().is_some_and(|name| name == ".git") { path.parent().unwrap_or(&path) } else { &path };
+    let name = name_source.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let name = sanitize_key(name.strip_suffix(".git").unwrap_or(&name));
+    // FNV-1a: stable across builds, unlike std's hasher
+    let hash = path.to_string_lossy().bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    format!("{}-{:016x}", if name.is_empty() { "repo" } else { &name }, hash)
+}
+
+// One cached entry, as listed by `git trunk cache info`.
+pub struct CacheEntry {
+    pub key: String,
+    pub size: u64,
+    pub age: Option<Duration>,
+}
+
+pub struct Cache {
+    dir: PathBuf,
+}
+
+// Removes the cache lock when dropped.
+pub struct CacheLock {
+    path: PathBuf,
+}
+
+impl Drop for CacheLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            debug!("⚠️ Failed to remove cache lock {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl Cache {
+    // The cache of `repo`. Nothing is created until the first write.
+    pub fn open(repo: &GitRepo) -> io::Result<Cache> {
+        let common_dir = repo.common_dir()?;
+        let root = cache_home().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "neither XDG_CACHE_HOME nor HOME is set"))?;
+        Ok(Cache::at(root.join(repo_id(&common_dir))))
+    }
+
+    pub fn at(dir: PathBuf) -> Cache {
+        Cache { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // Contents of an entry and how long ago it was written.
+    pub fn read(&self, key: &str) -> Option<(String, Duration)> {
+        let path = self.dir.join(key);
+        let contents = fs::read_to_string(&path).ok()?;
+        let age = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?.elapsed().unwrap_or_default();
+        Some((contents, age))
+    }
+
+    // Age of an entry, None when it does not exist.
+    pub fn age(&self, key: &str) -> Option<Duration> {
+        fs::metadata(self.dir.join(key)).and_then(|meta| meta.modified()).ok().map(|modified| modified.elapsed().unwrap_or_default())
+    }
+
+    // Writes an entry through a temporary file and a rename, so concurrent
+    // readers see either the old or the new contents, never a partial file.
+    pub fn write(&self, key: &str, contents: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let tmp_path = self.dir.join(format!(".{}.{}.tmp", key, std::process::id()));
+        let result = fs::write(&tmp_path, contents).and_then(|_| fs::rename(&tmp_path, self.dir.join(key)));
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        result
+    }
+
+    // Records the current time as the contents of an entry.
+    pub fn touch(&self, key: &str) -> io::Result<()> {
+        self.write(key, &format!("{:?}\n", SystemTime::now()))
+    }
+
+    pub fn remove(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.dir.join(key)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    // Serializes invocations that read, refresh and write the same entries,
+    // waiting up to LOCK_WAIT for another git-trunk to finish.
+    pub fn lock(&self) -> io::Result<CacheLock> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(LOCK_FILE_NAME);
+        let started = SystemTime::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let lock = CacheLock { path: path.clone() };
+                    writeln!(file, "{}", std::process::id())?;
+                    return Ok(lock);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let is_stale = fs::metadata(&path)
+                        .and_then(|meta| meta.modified())
+                        .map(|modified| modified.elapsed().unwrap_or_default() > STALE_LOCK_AFTER)
+                        .unwrap_or(false);
+                    if is_stale {
+                        debug!("🧹 Removing stale cache lock {}", path.display());
+                        let _ = fs::remove_file(&path);
+                    } else if started.elapsed().unwrap_or_default() > LOCK_WAIT {
+                        return Err(io::Error::new(io::ErrorKind::TimedOut, format!("{} is held by another git-trunk", path.display())));
+                    } else {
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // Entries sorted by key, leaving out the lock and temporary files.
+    pub fn entries(&self) -> io::Result<Vec<CacheEntry>> {
+        let mut entries = Vec::new();
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(entries),
+            Err(e) => return Err(e),
+        };
+        for entry in dir.filter_map(|entry| entry.ok()) {
+            let key = entry.file_name().to_string_lossy().to_string();
+            if key.starts_with('.') {
+                continue;
+            }
+            let meta = entry.metadata()?;
+            entries.push(CacheEntry {
+                key,
+                size: meta.len(),
+                age: meta.modified().ok().map(|modified| modified.elapsed().unwrap_or_default()),
+            });
+        }
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(entries)
+    }
+
+    // Deletes every entry while holding the lock, then the directory itself.
+    // Returns the number of entries deleted.
+    pub fn clear(&self) -> io::Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+        let entries = {
+            let _lock = self.lock()?;
+            let entries = self.entries()?;
+            for entry in &entries {
+                fs::remove_file(self.dir.join(&entry.key))?;
+            }
+            entries
+        };
+        // Fails harmlessly when another invocation wrote in the meantime
+        let _ = fs::remove_dir(&self.dir);
+        Ok(entries.len())
+    }
+}
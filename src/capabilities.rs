@@ -0,0 +1,113 @@
+use std::env;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::Command;
+use log::debug;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+use crate::utils::{run_git_command, NONINTERACTIVE_ENV};
+
+// What a command needs from its environment besides a git repository.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Need {
+    // Store checkouts under .trunk, which a bare repository has no room for
+    WorkTree,
+    // Someone at stdin to answer a confirmation prompt the command will ask
+    Terminal,
+    // An editor to open a document in
+    Editor,
+}
+
+// The environment git-trunk runs in, probed before a command starts. On a
+// hosting server (bare repositories, no terminal, no editor) a command that
+// cannot finish there is refused up front, naming what is missing and what
+// to run instead, rather than failing part-way through its steps.
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    // None outside a repository, where commands report that themselves
+    pub bare: Option<bool>,
+    pub root: Option<String>,
+    pub terminal: bool,
+    // The editor git would run, or why there is none; only probed for
+    // commands that need one
+    pub editor: Option<Result<String, String>>,
+}
+
+impl Capabilities {
+    pub fn detect(needs: &[Need], verbose: bool) -> Capabilities {
+        let repo = GitRepo::discover(verbose).ok();
+        let terminal = std::io::stdin().is_terminal();
+        let capabilities = Capabilities {
+            bare: repo.as_ref().map(|repo| repo.is_bare()),
+            root: repo.as_ref().map(|repo| repo.root().display().to_string()),
+            terminal,
+            editor: needs.contains(&Need::Editor).then(|| find_editor(terminal, verbose)),
+        };
+        debug!("➡️ Environment: bare repository {:?}, terminal {}, editor {:?}", capabilities.bare, capabilities.terminal, capabilities.editor);
+        capabilities
+    }
+
+    // Refuses `git trunk <command>` when one of its needs is missing here;
+    // `alternative` says what to run instead, e.g. `put` for `commit`.
+    pub fn check(&self, command: &str, needs: &[Need], alternative: impl Fn(Need) -> Option<&'static str>) -> Result<(), TrunkError> {
+        for &need in needs {
+            let instead = alternative(need).map(|alternative| format!(" Instead, {}.", alternative)).unwrap_or_default();
+            match need {
+                Need::WorkTree if self.bare == Some(true) => {
+                    return Err(TrunkError::Unsupported(format!(
+                        "`git trunk {}` needs a work tree for .trunk, but {} is a bare repository.{}",
+                        command,
+                        self.root.as_deref().unwrap_or("this"),
+                        instead
+                    )));
+                }
+                Need::Terminal if !self.terminal => {
+                    return Err(TrunkError::Usage(format!(
+                        "`git trunk {}` asks for confirmation, but stdin is not a terminal. Pass --yes or set {}=1 to confirm non-interactively.",
+                        command, NONINTERACTIVE_ENV
+                    )));
+                }
+                Need::Editor => {
+                    if let Some(Err(missing)) = &self.editor {
+                        return Err(TrunkError::Unsupported(format!("`git trunk {}` opens an editor, but {}.{}", command, missing, instead)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// The editor `git var GIT_EDITOR` names (GIT_EDITOR, core.editor, VISUAL,
+// EDITOR, then vi), if its program can be found. git's own fallback is a
+// terminal editor, so without a terminal only a configured one will do;
+// configured ones may be graphical or scripted.
+fn find_editor(terminal: bool, verbose: bool) -> Result<String, String> {
+    const NOT_CONFIGURED: &str = "no editor is configured (GIT_EDITOR, core.editor, VISUAL or EDITOR)";
+    let output = run_git_command(Command::new("git").arg("var").arg("GIT_EDITOR"), verbose).map_err(|e| format!("git var GIT_EDITOR failed: {}", e))?;
+    let editor = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || editor.is_empty() {
+        return Err(NOT_CONFIGURED.to_string());
+    }
+    let configured = ["GIT_EDITOR", "VISUAL", "EDITOR"].iter().any(|name| env::var_os(name).is_some_and(|value| !value.is_empty()))
+        || run_git_command(Command::new("git").arg("config").arg("core.editor"), verbose).is_ok_and(|output| output.status.success());
+    if !configured && !terminal {
+        return Err(format!("{} and stdin is not a terminal to run '{}' in", NOT_CONFIGURED, editor));
+    }
+    // Only a plain program name or path can be checked; quoted commands are
+    // left to the shell
+    let program = editor.split_whitespace().next().unwrap_or_default();
+    if program.starts_with(['"', '\'']) || program_exists(program) {
+        Ok(editor)
+    } else {
+        Err(format!("the editor '{}' is not installed", program))
+    }
+}
+
+fn program_exists(program: &str) -> bool {
+    if program.contains('/') {
+        return Path::new(program).is_file();
+    }
+    env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(program).is_file() || dir.join(format!("{}.exe", program)).is_file()))
+}
@@ -0,0 +1,96 @@
+use clap::Parser;
+use log::{debug, info};
+use crate::config;
+use crate::frontmatter::{self, format_scalar, Value};
+use crate::ops;
+use crate::table::print_line;
+use crate::utils::{read_blobs, short_hash};
+use crate::error::TrunkError;
+
+// The store's self-description, created by `init`: frontmatter with the
+// fields below, then free text.
+pub const ABOUT_FILE: &str = "ABOUT.md";
+
+// The fields `about` always prints, in order, even when they are empty.
+const ABOUT_FIELDS: [&str; 4] = ["purpose", "owners", "contact", "conventions"];
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Print what a store is for and who maintains it, from its ABOUT.md, without a checkout",
+    after_help = "A store missing locally is fetched from the remote first, so stores found with `git ls-remote` can be looked up.\n\nExamples:\n  git trunk about\n  git trunk about incidents\n  git trunk about incidents --remote upstream"
+)]
+pub struct AboutArgs {
+    #[arg(value_name = "STORE", help = "Store to describe [default: --store or the default store]")]
+    store: Option<String>,
+}
+
+// The ABOUT.md `init` writes. Owners default to the person running it.
+pub fn template(store_name: &str, purpose: &str, owners: &[String], contact: &str) -> String {
+    let owners = if owners.is_empty() {
+        " []".to_string()
+    } else {
+        owners.iter().map(|owner| format!("\n  - {}", format_scalar(owner))).collect()
+    };
+    format!(
+        "---\npurpose: {}\nowners:{}\ncontact: {}\nconventions: \"\"\n---\n# About the '{}' store\n\nDescribe here what belongs in this store and how to contribute. `git trunk about {}` prints the fields above straight from refs/trunk/{}, so whoever finds the store on the remote learns what it is and who maintains it.\n",
+        format_scalar(purpose), owners, format_scalar(contact), store_name, store_name, store_name
+    )
+}
+
+// "conventions" -> "Conventions"
+fn label(key: &str) -> String {
+    let mut chars = key.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+pub fn run(args: &AboutArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let store_name = args.store.as_deref().unwrap_or(store_name);
+    config::check_store_name(store_name).map_err(|reason| TrunkError::Usage(format!("Invalid store name '{}': {}", store_name, reason)))?;
+
+    // Step 2: Find the store, here or else on the remote
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    debug!("➡️ Step 2: Resolving {}", trunk_ref_name);
+    let (source, commit) = match repo.resolve_commit(&trunk_ref_name) {
+        Some(commit) => (trunk_ref_name.clone(), commit),
+        None => {
+            let mirror_ref_name = format!("refs/trunk-remotes/{}/{}", remote_name, store_name);
+            debug!("📥 Step 2: {} is not here, fetching it from '{}'", trunk_ref_name, remote_name);
+            if ops::remote_store_tip(&repo, remote_name, store_name, verbose)?.is_none() {
+                return Err(TrunkError::NotFound(format!("Store '{}' is neither in this repository nor on remote '{}'", store_name, remote_name)));
+            }
+            ops::fetch_store_ref(&repo, remote_name, store_name, &mirror_ref_name, verbose)?;
+            let commit = repo.resolve_commit(&mirror_ref_name).ok_or_else(|| TrunkError::Git(format!("Failed to read {} after fetching it", mirror_ref_name)))?;
+            (format!("{} on '{}'", trunk_ref_name, remote_name), commit)
+        }
+    };
+    info!("✓ Step 2: Reading {} ({})", source, short_hash(repo.root(), &commit, verbose));
+
+    // Step 3: Read and print ABOUT.md
+    let content = read_blobs(repo.root(), &[format!("{}:{}", commit, ABOUT_FILE)], verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to read {} from {}: {}", ABOUT_FILE, source, e)))?
+        .pop()
+        .flatten()
+        .ok_or_else(|| TrunkError::NotFound(format!(
+            "Store '{}' has no {}. Describe it with `git trunk put <file> {} --store {}`; `git trunk init` creates one for new stores.",
+            store_name, ABOUT_FILE, ABOUT_FILE, store_name
+        )))?;
+    let text = String::from_utf8_lossy(&content);
+    let fields = frontmatter::parse(&text).unwrap_or_default();
+    print_line(&format!("Store: {}", store_name));
+    for key in ABOUT_FIELDS {
+        let value = fields.get(key).map(|value| value.to_string()).filter(|value| !value.trim().is_empty());
+        print_line(&format!("{}: {}", label(key), value.as_deref().unwrap_or("(not set)")));
+    }
+    for (key, value) in fields.fields.iter().filter(|(key, value)| !ABOUT_FIELDS.contains(&key.as_str()) && *value != Value::Null) {
+        print_line(&format!("{}: {}", label(key), value));
+    }
+    print_line(&format!("Commit: {}", short_hash(repo.root(), &commit, verbose)));
+    let body = frontmatter::body(&text);
+    if !body.trim().is_empty() {
+        print_line("");
+        print_line(body.trim());
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::PathBuf;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config;
+use crate::ops;
+use crate::quota;
+use crate::utils::{ensure_trunk_in_gitignore, run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Turn a directory tracked in the main repository into a store",
+    after_help = "The store gets the directory as committed at HEAD; uncommitted changes in it are not adopted. With --preserve-history, every commit on HEAD's first-parent line that changed the directory is replayed into the store with its author, date and message, like `git subtree split`.\n\nExamples:\n  git trunk adopt docs --store docs\n  git trunk adopt handbook --store handbook --preserve-history --remove"
+)]
+pub struct AdoptArgs {
+    #[arg(value_name = "DIR", help = "Directory tracked in the main repository, relative to the current directory")]
+    dir: PathBuf,
+    #[arg(long, help = "Bring along the history of the directory, not just its current files")]
+    preserve_history: bool,
+    #[arg(long, help = "Remove the directory from the main repository afterwards (`git rm -r`, left for you to commit)")]
+    remove: bool,
+}
+
+// One commit of the main repository that changed the adopted directory.
+struct Change {
+    hash: String,
+    author_name: String,
+    author_email: String,
+    author_date: String,
+    message: String,
+}
+
+// The commits on HEAD's first-parent line that changed `dir`, oldest first.
+fn changes(repo: &GitRepo, dir: &str, verbose: bool) -> Result<Vec<Change>, TrunkError> {
+    let output = run_git_command(
+        repo.git().arg("log").arg("--first-parent").arg("--reverse").arg("--date=raw").arg("--format=%H%x00%an%x00%ae%x00%ad%x00%B%x01").arg("HEAD").arg("--").arg(dir),
+        verbose,
+    )
+    .map_err(|e| TrunkError::Git(format!("git log failed for {}: {}", dir, e)))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\x01')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(5, '\0');
+            Some(Change {
+                hash: fields.next().filter(|hash| !hash.is_empty())?.to_string(),
+                author_name: fields.next()?.to_string(),
+                author_email: fields.next()?.to_string(),
+                author_date: fields.next()?.to_string(),
+                message: fields.next()?.trim_end().to_string(),
+            })
+        })
+        .collect())
+}
+
+fn git_line(repo: &GitRepo, args: &[&str], input: Option<&str>, verbose: bool) -> Option<String> {
+    let mut command = repo.git();
+    command.args(args);
+    if let Some(tree) = input {
+        command.arg(tree);
+    }
+    let output = run_git_command(&mut command, verbose).ok().filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|line| !line.is_empty())
+}
+
+// Replays the directory's history as commits whose tree is the directory,
+// on top of an empty "Initial commit for store" like every store starts with.
+// Returns the new tip and the number of commits replayed.
+fn replay_history(repo: &GitRepo, store_name: &str, dir: &str, verbose: bool) -> Result<(String, usize), TrunkError> {
+    let failed = |what: &str| TrunkError::Git(format!("Failed to {} while replaying the history of {}", what, dir));
+    let empty_tree = git_line(repo, &["hash-object", "-w", "-t", "tree", "--stdin"], None, verbose).ok_or_else(|| failed("write the empty tree"))?;
+    let mut tip = git_line(repo, &["commit-tree", "-m", &format!("Initial commit for store '{}'", store_name)], Some(&empty_tree), verbose)
+        .ok_or_else(|| failed("write the initial commit"))?;
+    let mut previous_tree = empty_tree.clone();
+    let mut replayed = 0;
+    for change in changes(repo, dir, verbose)? {
+        // The directory may be missing in commits that deleted it
+        let tree = git_line(repo, &["rev-parse", &format!("{}:{}", change.hash, dir)], None, verbose).unwrap_or_else(|| empty_tree.clone());
+        if tree == previous_tree {
+            continue;
+        }
+        let output = run_git_command(
+            repo.git()
+                .env("GIT_AUTHOR_NAME", &change.author_name)
+                .env("GIT_AUTHOR_EMAIL", &change.author_email)
+                .env("GIT_AUTHOR_DATE", &change.author_date)
+                .arg("commit-tree")
+                .arg(&tree)
+                .arg("-p")
+                .arg(&tip)
+                .arg("-m")
+                .arg(&change.message),
+            verbose,
+        )
+        .map_err(|e| TrunkError::Git(format!("git commit-tree failed: {}", e)))?;
+        if !output.status.success() {
+            return Err(failed(&format!("replay commit {}", &change.hash[..change.hash.len().min(12)])));
+        }
+        tip = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        previous_tree = tree;
+        replayed += 1;
+    }
+    Ok((tip, replayed))
+}
+
+pub fn run(args: &AdoptArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    // Step 2: Check the directory and that the store is new
+    debug!("➡️ Step 2: Checking {}", args.dir.display());
+    let absolute = fs::canonicalize(&args.dir).map_err(|e| TrunkError::NotFound(format!("{}: {}", args.dir.display(), e)))?;
+    let root = fs::canonicalize(repo_root).unwrap_or_else(|_| repo_root.to_path_buf());
+    let dir = match absolute.strip_prefix(&root) {
+        Ok(relative) if !relative.as_os_str().is_empty() && absolute.is_dir() => relative.to_string_lossy().replace('\\', "/"),
+        _ => return Err(TrunkError::Usage(format!("{} is not a directory inside the repository at {}", args.dir.display(), repo_root.display()))),
+    };
+    if dir == ".trunk" || dir.starts_with(".trunk/") || dir == ".git" || dir.starts_with(".git/") {
+        return Err(TrunkError::Usage(format!("{} cannot be adopted; pick a directory of the main repository", dir)));
+    }
+    let Some(tree) = git_line(&repo, &["rev-parse", "--verify", "--quiet", &format!("HEAD:{}", dir)], None, verbose) else {
+        return Err(TrunkError::NotFound(format!("{} is not committed at HEAD; commit it first, or use `git trunk import {}` for untracked files", dir, dir)));
+    };
+    if repo.ref_exists(&trunk_ref_name) || config::trunk_base_dir(repo_root, verbose).join(store_name).exists() {
+        return Err(TrunkError::Conflict(format!("Store '{}' already exists here ({} or .trunk/{}); adopt into another store with --store", store_name, trunk_ref_name, store_name)));
+    }
+    let uncommitted = run_git_command(repo.git().arg("status").arg("--porcelain").arg("--").arg(&dir), verbose)
+        .map(|output| !output.stdout.is_empty())
+        .unwrap_or(false);
+    if uncommitted {
+        if args.remove {
+            return Err(TrunkError::Conflict(format!("{} has uncommitted changes that --remove would lose; commit or stash them first", dir)));
+        }
+        error!("⚠️ Warning: {} has uncommitted changes; the store gets the files as committed at HEAD", dir);
+    }
+    info!("✓ Step 2: {} becomes store '{}'", dir, store_name);
+
+    // Step 3: Write the store's commits
+    let tip = if args.preserve_history {
+        debug!("➡️ Step 3: Replaying the history of {}", dir);
+        let (tip, replayed) = replay_history(&repo, store_name, &dir, verbose)?;
+        info!("✓ Step 3: Replayed {} commit(s) that changed {}", replayed, dir);
+        tip
+    } else {
+        debug!("➡️ Step 3: Committing {} as at HEAD", dir);
+        let head = short_hash(repo_root, "HEAD", verbose);
+        let message = format!("Initial commit for store '{}'\n\nAdopted from {} at {}", store_name, dir, head);
+        let tip = git_line(&repo, &["commit-tree", "-m", &message], Some(&tree), verbose)
+            .ok_or_else(|| TrunkError::Git(format!("Failed to commit the files of {}", dir)))?;
+        info!("✓ Step 3: Committed the files of {} at {}", dir, head);
+        tip
+    };
+
+    // Step 4: Point refs/trunk/<store> at it
+    debug!("➡️ Step 4: Creating {}", trunk_ref_name);
+    quota::enforce(&repo, store_name, quota::reachable_size(repo_root, &tip, verbose).unwrap_or(0), "Step 4", verbose)?;
+    repo.update_ref(&trunk_ref_name, &tip).map_err(|e| TrunkError::Git(format!("Failed to create {}: {}", trunk_ref_name, e)))?;
+    info!("✓ Step 4: Created {} at {}", trunk_ref_name, short_hash(repo_root, &tip, verbose));
+
+    // Steps 5-15: Check it out into .trunk/<store>
+    debug!("➡️ Step 5: Preparing .trunk for store '{}'", store_name);
+    ensure_trunk_in_gitignore(repo_root, "Step 5").map_err(|e| TrunkError::Io(format!("Failed to update .gitignore: {}", e)))?;
+    ops::ensure_trunk_dir(repo_root, "Step 5", verbose)?;
+    ops::materialize_store(&repo, store_name, verbose)?;
+
+    // Step 16: Take the directory out of the main repository
+    if args.remove {
+        debug!("🗑️ Step 16: Removing {} from the main repository", dir);
+        let output = run_git_command(repo.git().arg("rm").arg("-r").arg("-q").arg("--").arg(&dir), verbose)
+            .map_err(|e| TrunkError::Git(format!("Failed to run git rm: {}", e)))?;
+        if !output.status.success() {
+            return Err(TrunkError::Git(format!("Store '{}' was created, but `git rm -r {}` failed; remove the directory yourself", store_name, dir)));
+        }
+        info!("✓ Step 16: Staged the removal of {}; commit it to finish the move", dir);
+    }
+
+    info!("✅ Adopted {} as store '{}'. Run `git trunk push --store {}` to publish it.", dir, store_name, store_name);
+    Ok(())
+}
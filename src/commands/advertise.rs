@@ -0,0 +1,129 @@
+use std::fs;
+use std::process::Command;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config::{self, DEFAULT_REMOTE};
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Write a pointer to the available trunk stores into the main repository", after_help = "Examples:\n  git trunk advertise\n  git trunk advertise --file README.md")]
+pub struct AdvertiseArgs {
+    #[arg(long, default_value = "docs/TRUNK.md", help = "File (relative to the repository root) that receives the announcement; an existing file keeps everything outside the git-trunk markers")]
+    file: String,
+}
+
+const BEGIN_MARKER: &str = "<!-- git-trunk:advertise:begin -->";
+const END_MARKER: &str = "<!-- git-trunk:advertise:end -->";
+
+// Store names found under refs/trunk/ in a `for-each-ref` or `ls-remote` listing.
+fn store_names_from_refs(listing: &str) -> Vec<String> {
+    let mut stores: Vec<String> = Vec::new();
+    for line in listing.lines() {
+        let Some(refname) = line.split_whitespace().last() else { continue };
+        if let Some(name) = refname.strip_prefix("refs/trunk/") {
+            if config::is_store_name(name) && !stores.contains(&name.to_string()) {
+                stores.push(name.to_string());
+            }
+        }
+    }
+    stores
+}
+
+fn render_section(stores: &[(String, bool)], remote_name: &str) -> String {
+    let remote_arg = if remote_name == DEFAULT_REMOTE { String::new() } else { format!(" --remote {}", remote_name) };
+    let mut section = String::new();
+    section.push_str(BEGIN_MARKER);
+    section.push_str("\n## Trunk stores\n\n");
+    section.push_str("This repository keeps repository-wide documents in [git-trunk](https://github.com/ssr1ram/git-trunk) stores. ");
+    section.push_str("They live under `refs/trunk/<store>` rather than on any branch, so a plain clone does not show them.\n\n");
+    section.push_str("| Store | Get it |\n|-------|--------|\n");
+    for (store, published) in stores {
+        if *published {
+            section.push_str(&format!("| {} | `git trunk checkout --store {}{}` |\n", store, store, remote_arg));
+        } else {
+            section.push_str(&format!("| {} | not pushed to `{}` yet |\n", store, remote_name));
+        }
+    }
+    section.push_str("\nInstall git-trunk, run the command for a store, and its files appear in `.trunk/<store>`. ");
+    section.push_str("Run `git trunk hooks install` to keep them committed and pushed alongside your branches.\n\n");
+    section.push_str("_Generated by `git trunk advertise`; re-run it to refresh this list._\n");
+    section.push_str(END_MARKER);
+    section.push('\n');
+    section
+}
+
+// Replaces the marked section of `existing`, or appends it when there is none.
+fn splice_section(existing: &str, section: &str) -> String {
+    if let (Some(begin), Some(end)) = (existing.find(BEGIN_MARKER), existing.find(END_MARKER)) {
+        if begin < end {
+            let after = existing[end + END_MARKER.len()..].trim_start_matches(['\r', '\n']);
+            return format!("{}{}{}", &existing[..begin], section, after);
+        }
+    }
+    if existing.is_empty() {
+        return section.to_string();
+    }
+    let separator = if existing.ends_with("\n\n") { "" } else if existing.ends_with('\n') { "\n" } else { "\n\n" };
+    format!("{}{}{}", existing, separator, section)
+}
+
+pub fn run(args: &AdvertiseArgs, remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Collect local stores from refs/trunk/*
+    debug!("➡️ Step 2: Collecting local stores from refs/trunk/*");
+    let local_stores = match run_git_command(Command::new("git").arg("for-each-ref").arg("--format=%(refname)").arg("refs/trunk/").current_dir(repo_root), verbose) {
+        Ok(output) if output.status.success() => store_names_from_refs(&String::from_utf8_lossy(&output.stdout)),
+        _ => {
+            return Err(TrunkError::Git("Failed to list refs/trunk/* in the main repository".to_string()));
+        }
+    };
+    info!("✓ Step 2: Found {} local store(s)", local_stores.len());
+
+    // Step 3: Collect stores published on the remote
+    debug!("➡️ Step 3: Listing refs/trunk/* on remote '{}'", remote_name);
+    let remote_stores = match run_git_command(Command::new("git").arg("ls-remote").arg(remote_name).arg("refs/trunk/*").current_dir(repo_root), verbose) {
+        Ok(output) if output.status.success() => {
+            let stores = store_names_from_refs(&String::from_utf8_lossy(&output.stdout));
+            info!("✓ Step 3: Found {} store(s) on remote '{}'", stores.len(), remote_name);
+            stores
+        }
+        _ => {
+            error!("⚠️ Warning: Could not list refs/trunk/* on remote '{}'; only local stores will be advertised", remote_name);
+            Vec::new()
+        }
+    };
+
+    let mut stores: Vec<(String, bool)> = remote_stores.iter().map(|store| (store.clone(), true)).collect();
+    for store in local_stores.iter().filter(|store| !remote_stores.contains(store)) {
+        stores.push((store.clone(), false));
+    }
+    stores.sort();
+    if stores.is_empty() {
+        return Err(TrunkError::NotFound(format!("No trunk stores found locally or on remote '{}'. Create one with `git trunk init` first.", remote_name)));
+    }
+
+    // Step 4: Write the announcement
+    let target_path = repo_root.join(&args.file);
+    debug!("➡️ Step 4: Writing announcement to {}", args.file);
+    let existing = fs::read_to_string(&target_path).unwrap_or_default();
+    let updated = splice_section(&existing, &render_section(&stores, remote_name));
+    if updated == existing {
+        info!("= Step 4: {} is already up to date", args.file);
+    } else {
+        if let Some(parent) = target_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| TrunkError::Io(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        fs::write(&target_path, updated).map_err(|e| TrunkError::Io(format!("Failed to write {}: {}", args.file, e)))?;
+        info!("✓ Step 4: Announced {} store(s) in {}", stores.len(), args.file);
+    }
+
+    info!("✅ Advertise completed. Commit {} to the main branch so new contributors find the stores.", args.file);
+    Ok(())
+}
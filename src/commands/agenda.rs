@@ -0,0 +1,208 @@
+use std::path::Path;
+use std::process::Command;
+use chrono::{Duration, NaiveDate, Utc};
+use clap::Parser;
+use log::{debug, error, info};
+use regex::Regex;
+use crate::config;
+use crate::commands::lint_prose::PROSE_EXTENSIONS;
+use crate::table::{Cell, Color, Table};
+use crate::utils::{run_git_command, list_tree_files, read_blobs};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "List due dates and open tasks found in store documents", after_help = "Examples:\n  git trunk agenda --days 7\n  git trunk agenda --all --ical > trunk.ics")]
+pub struct AgendaArgs {
+    #[arg(long, help = "Scan every store under refs/trunk/ instead of only the current one")]
+    all: bool,
+    #[arg(long, default_value_t = 14, help = "Show items due within this many days (overdue items are always shown)")]
+    days: i64,
+    #[arg(long, help = "Also list open `- [ ]` tasks that have no @due date")]
+    undated: bool,
+    #[arg(long, help = "Print every dated item as an iCalendar (.ics) feed instead of a table")]
+    ical: bool,
+    #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+#[derive(Debug)]
+struct AgendaItem {
+    store: String,
+    path: String,
+    line: usize,
+    due: Option<NaiveDate>,
+    text: String,
+}
+
+// Open tasks (`- [ ] ...`) and any line carrying `@due(YYYY-MM-DD)`. Checked
+// tasks and fenced code blocks are skipped.
+fn extract_items(store: &str, path: &str, text: &str) -> Vec<AgendaItem> {
+    let task = Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+\[([ xX])\]\s*(.*)$").expect("valid task regex");
+    let due = Regex::new(r"\s*@due\(([^)]*)\)").expect("valid due regex");
+    let mut items = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let (is_task, body) = match task.captures(line) {
+            Some(caps) if caps[1].trim().is_empty() => (true, caps[2].to_string()),
+            Some(_) => continue,
+            None => (false, line.trim().trim_start_matches(['-', '*', '+', '#', '>']).trim().to_string()),
+        };
+        let due_date = match due.captures(&body) {
+            Some(caps) => match NaiveDate::parse_from_str(caps[1].trim(), "%Y-%m-%d") {
+                Ok(date) => Some(date),
+                Err(_) => {
+                    error!("⚠️ Warning: {}:{}:{}: ignoring invalid date in {}", store, path, index + 1, caps[0].trim());
+                    continue;
+                }
+            },
+            None if is_task => None,
+            None => continue,
+        };
+        let text = due.replace_all(&body, "").split_whitespace().collect::<Vec<_>>().join(" ");
+        items.push(AgendaItem { store: store.to_string(), path: path.to_string(), line: index + 1, due: due_date, text });
+    }
+    items
+}
+
+// Escapes TEXT values per RFC 5545.
+fn ical_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+// Folds a content line to 75 octets, continuing with a leading space.
+fn ical_fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    for c in line.chars() {
+        if used + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            used = 1;
+        }
+        out.push(c);
+        used += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+// Stable identifier so calendar clients update rather than duplicate events.
+fn ical_uid(item: &AgendaItem) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in format!("{}\0{}\0{}", item.store, item.path, item.text).bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}@git-trunk", hash)
+}
+
+fn render_ical(items: &[AgendaItem]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+    let mut out = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//git-trunk//agenda//EN", "CALSCALE:GREGORIAN"] {
+        out.push_str(&ical_fold(line));
+    }
+    for item in items {
+        let Some(due) = item.due else { continue };
+        out.push_str(&ical_fold("BEGIN:VEVENT"));
+        out.push_str(&ical_fold(&format!("UID:{}", ical_uid(item))));
+        out.push_str(&ical_fold(&format!("DTSTAMP:{}", stamp)));
+        out.push_str(&ical_fold(&format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d"))));
+        out.push_str(&ical_fold(&format!("DTEND;VALUE=DATE:{}", (due + Duration::days(1)).format("%Y%m%d"))));
+        out.push_str(&ical_fold(&format!("SUMMARY:{}", ical_escape(&item.text))));
+        out.push_str(&ical_fold(&format!("DESCRIPTION:{}", ical_escape(&format!("{}/{}:{}", item.store, item.path, item.line)))));
+        out.push_str(&ical_fold("END:VEVENT"));
+    }
+    out.push_str(&ical_fold("END:VCALENDAR"));
+    out
+}
+
+fn all_stores(repo_root: &Path, verbose: bool) -> Result<Vec<String>, TrunkError> {
+    let output = run_git_command(Command::new("git").arg("for-each-ref").arg("--format=%(refname)").arg("refs/trunk/").current_dir(repo_root), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to list refs/trunk/*: {}", e)))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("refs/trunk/"))
+        .filter(|name| config::is_store_name(name))
+        .map(str::to_string)
+        .collect())
+}
+
+pub fn run(args: &AgendaArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Collect items from each store's committed documents
+    let stores = if args.all { all_stores(repo_root, verbose)? } else { vec![store_name.to_string()] };
+    debug!("➡️ Step 2: Scanning {} store(s) for due dates and open tasks", stores.len());
+    let mut items: Vec<AgendaItem> = Vec::new();
+    for store in &stores {
+        let rev = format!("refs/trunk/{}", store);
+        let paths: Vec<String> = match list_tree_files(repo_root, &rev, verbose) {
+            Ok(paths) => paths.into_iter()
+                .filter(|path| Path::new(path).extension().and_then(|ext| ext.to_str()).is_some_and(|ext| PROSE_EXTENSIONS.contains(&ext.to_lowercase().as_str())))
+                .collect(),
+            Err(e) => {
+                return Err(TrunkError::Git(format!("Failed to read store '{}' at {}: {}. Commit the store with `git trunk commit --store {}` first.", store, rev, e, store)));
+            }
+        };
+        let objects: Vec<String> = paths.iter().map(|path| format!("{}:{}", rev, path)).collect();
+        let blobs = read_blobs(repo_root, &objects, verbose).map_err(|e| TrunkError::Io(format!("Failed to read documents of store '{}': {}", store, e)))?;
+        for (path, blob) in paths.iter().zip(blobs) {
+            if let Some(blob) = blob {
+                items.extend(extract_items(store, path, &String::from_utf8_lossy(&blob)));
+            }
+        }
+    }
+    items.sort_by(|a, b| (a.due.is_none(), a.due, &a.store, &a.path, a.line).cmp(&(b.due.is_none(), b.due, &b.store, &b.path, b.line)));
+    debug!("✓ Step 2: Found {} item(s)", items.len());
+
+    // Step 3: Output
+    if args.ical {
+        print!("{}", render_ical(&items));
+        return Ok(());
+    }
+    let today = chrono::Local::now().date_naive();
+    let horizon = today + Duration::days(args.days);
+    let shown: Vec<&AgendaItem> = items.iter()
+        .filter(|item| match item.due {
+            Some(due) => due <= horizon,
+            None => args.undated,
+        })
+        .collect();
+    if shown.is_empty() {
+        info!("= Nothing due in the next {} day(s)", args.days);
+        return Ok(());
+    }
+    println!("\n📅 Agenda ({} to {})", today, horizon);
+    let mut table = Table::new(&["Due", "When", "Store", "Location", "Item"]);
+    for item in shown {
+        let (due, when) = match item.due {
+            Some(due) => {
+                let days = (due - today).num_days();
+                let when = match days {
+                    0 => Cell::colored("today", Color::Yellow),
+                    1 => Cell::from("tomorrow"),
+                    d if d < 0 => Cell::colored(format!("{} day(s) overdue", -d), Color::Red),
+                    d => Cell::from(format!("in {} days", d)),
+                };
+                (Cell::from(due.to_string()), when)
+            }
+            None => (Cell::from("-"), Cell::from("-")),
+        };
+        table.add_row(vec![due, when, item.store.as_str().into(), format!("{}:{}", item.path, item.line).into(), item.text.as_str().into()]);
+    }
+    table.print(args.no_table);
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process::{Command, Stdio};
+use clap::Parser;
+use log::debug;
+use crate::json::{self, Json};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(about = "Run newline-delimited JSON commands from stdin, writing one JSON result per line", after_help = "Examples:\n  echo '{\"id\": 1, \"cmd\": \"status\", \"local\": true}' | git trunk batch")]
+pub struct BatchArgs {
+    #[arg(long, help = "Stop at the first command that fails")]
+    stop_on_error: bool,
+}
+
+// Request keys that are not passed on as command options.
+const RESERVED_KEYS: [&str; 5] = ["id", "cmd", "store", "remote", "args"];
+
+// Command-line arguments for one request, e.g.
+// {"cmd":"commit","store":"docs","force":true,"message":"x"}
+// → commit --force --message x (with --store docs as a global option).
+fn request_arguments(request: &Json) -> Result<Vec<String>, String> {
+    let Json::Object(fields) = request else { return Err("a request must be a JSON object".to_string()) };
+    let cmd = request.get("cmd").and_then(Json::as_str).ok_or("missing \"cmd\" string")?;
+    if cmd == "batch" {
+        return Err("batch requests cannot be nested".to_string());
+    }
+    let mut arguments: Vec<String> = Vec::new();
+    for global in ["store", "remote"] {
+        match request.get(global) {
+            None | Some(Json::Null) => {}
+            Some(Json::String(value)) => arguments.extend([format!("--{}", global), value.clone()]),
+            Some(_) => return Err(format!("\"{}\" must be a string", global)),
+        }
+    }
+    arguments.push(cmd.to_string());
+    for (key, value) in fields.iter().filter(|(key, _)| !RESERVED_KEYS.contains(&key.as_str())) {
+        let flag = format!("--{}", key.replace('_', "-"));
+        match value {
+            Json::Null | Json::Bool(false) => {}
+            Json::Bool(true) => arguments.push(flag),
+            Json::Array(items) => {
+                for item in items {
+                    arguments.extend([flag.clone(), scalar(item).ok_or(format!("\"{}\" must only contain strings or numbers", key))?]);
+                }
+            }
+            other => arguments.extend([flag, scalar(other).ok_or(format!("\"{}\" must be a string, number, boolean or array", key))?]),
+        }
+    }
+    match request.get("args") {
+        None | Some(Json::Null) => {}
+        Some(Json::Array(items)) => {
+            for item in items {
+                arguments.push(scalar(item).ok_or("\"args\" must only contain strings or numbers")?);
+            }
+        }
+        Some(_) => return Err("\"args\" must be an array".to_string()),
+    }
+    Ok(arguments)
+}
+
+fn scalar(value: &Json) -> Option<String> {
+    match value {
+        Json::String(text) => Some(text.clone()),
+        Json::Number(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn failure(id: &Json, message: &str) -> Json {
+    Json::Object(vec![
+        ("id".to_string(), id.clone()),
+        ("ok".to_string(), Json::Bool(false)),
+        ("error".to_string(), Json::from(message)),
+    ])
+}
+
+pub fn run(args: &BatchArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let exe = env::current_exe().map_err(|e| TrunkError::Failed(format!("Cannot locate the git-trunk executable: {}", e)))?;
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut failed = false;
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| TrunkError::Io(format!("Failed to read stdin: {}", e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match json::parse(&line) {
+            Err(e) => failure(&Json::Null, &format!("invalid JSON: {}", e)),
+            Ok(request) => {
+                let id = request.get("id").cloned().unwrap_or(Json::Null);
+                match request_arguments(&request) {
+                    Err(e) => failure(&id, &e),
+                    Ok(arguments) => {
+                        debug!("➡️ Running git-trunk {}", arguments.join(" "));
+                        let mut command = Command::new(&exe);
+                        if verbose {
+                            command.arg("--verbose");
+                        }
+                        // Commands never wait on a prompt: stdin is closed, so a
+                        // confirmation fails unless the request passes force or
+                        // batch itself runs with --yes.
+                        match command.args(&arguments).stdin(Stdio::null()).output() {
+                            Err(e) => failure(&id, &format!("failed to run command: {}", e)),
+                            Ok(output) => Json::Object(vec![
+                                ("id".to_string(), id),
+                                ("ok".to_string(), Json::Bool(output.status.success())),
+                                ("exit_code".to_string(), output.status.code().map(|code| Json::Number(code as f64)).unwrap_or(Json::Null)),
+                                ("stdout".to_string(), Json::from(String::from_utf8_lossy(&output.stdout).to_string())),
+                                ("stderr".to_string(), Json::from(String::from_utf8_lossy(&output.stderr).to_string())),
+                            ]),
+                        }
+                    }
+                }
+            }
+        };
+        let ok = response.get("ok") == Some(&Json::Bool(true));
+        writeln!(stdout, "{}", response).and_then(|_| stdout.flush()).map_err(|e| TrunkError::Io(format!("Failed to write result: {}", e)))?;
+        failed |= !ok;
+        if !ok && args.stop_on_error {
+            return Err(TrunkError::Failed("Batch stopped at the first failed request".to_string()));
+        }
+    }
+    if failed {
+        return Err(TrunkError::Failed("One or more batch requests failed".to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use chrono::{Local, TimeZone};
+use clap::Parser;
+use log::{debug, info};
+use crate::locks::normalize_path;
+use crate::ops;
+use crate::table::Table;
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Show who last changed each line, or each Markdown section, of a store file",
+    after_help = "Examples:\n  git trunk blame policies/travel.md\n  git trunk blame policies/travel.md --sections\n  git trunk blame handbook:policies/travel.md --ref 1a2b3c4"
+)]
+pub struct BlameArgs {
+    #[arg(help = "File to blame: relative to .trunk/<store>, or `store:path`")]
+    path: String,
+    #[arg(long = "ref", value_name = "COMMIT", help = "Blame the file as of this commit of the store instead of refs/trunk/<store>")]
+    rev: Option<String>,
+    #[arg(long, help = "Summarize by Markdown heading: the last change to each section")]
+    sections: bool,
+    #[arg(long, requires = "sections", help = "With --sections, print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+impl BlameArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        vec![&mut self.path]
+    }
+}
+
+// One line of the file and the commit that last changed it.
+struct BlameLine {
+    hash: String,
+    author: String,
+    time: i64,
+    summary: String,
+    content: String,
+}
+
+impl BlameLine {
+    fn date(&self) -> String {
+        Local.timestamp_opt(self.time, 0).single().map(|time| time.format("%Y-%m-%d").to_string()).unwrap_or_default()
+    }
+}
+
+// Parses `git blame --line-porcelain`: a header line per file line
+// ("<hash> <original line> <final line>"), "key value" lines, then the line
+// itself after a tab.
+fn blame(repo: &GitRepo, commit: &str, path: &str, verbose: bool) -> Result<Vec<BlameLine>, TrunkError> {
+    let output = run_git_command(repo.git().arg("blame").arg("--line-porcelain").arg(commit).arg("--").arg(path), verbose)
+        .map_err(|e| TrunkError::Git(format!("git blame failed for {}: {}", path, e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git blame failed for {}: {}", path, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    let mut lines = Vec::new();
+    let mut current: Option<BlameLine> = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some(mut blamed) = current.take() {
+                blamed.content = content.to_string();
+                lines.push(blamed);
+            }
+            continue;
+        }
+        let Some(blamed) = current.as_mut() else {
+            let hash = line.split_whitespace().next().unwrap_or_default();
+            current = Some(BlameLine { hash: hash.to_string(), author: String::new(), time: 0, summary: String::new(), content: String::new() });
+            continue;
+        };
+        match line.split_once(' ') {
+            Some(("author", author)) => blamed.author = author.to_string(),
+            Some(("author-time", time)) => blamed.time = time.parse().unwrap_or_default(),
+            Some(("summary", summary)) => blamed.summary = summary.to_string(),
+            _ => {}
+        }
+    }
+    Ok(lines)
+}
+
+// A Markdown heading ("## Travel" gives "Travel"), outside fenced code blocks.
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    ((1..=6).contains(&level) && text.starts_with(' ')).then(|| text.trim())
+}
+
+// The lines of each section: (heading, first line, last line), 1-based. Lines
+// before the first heading form a section of their own.
+fn sections(lines: &[BlameLine]) -> Vec<(String, usize, usize)> {
+    let mut sections: Vec<(String, usize, usize)> = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.content.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        match heading(&line.content).filter(|_| !in_fence) {
+            Some(title) => sections.push((title.to_string(), index + 1, index + 1)),
+            None => match sections.last_mut() {
+                Some(section) => section.2 = index + 1,
+                None => sections.push(("(before the first heading)".to_string(), 1, 1)),
+            },
+        }
+    }
+    sections
+}
+
+pub fn run(args: &BlameArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    debug!("✓ Step 1: Repository root found at {}", repo.root().display());
+    let path = ops::store_paths(&repo, std::slice::from_ref(&args.path), store_name, verbose).pop().unwrap_or_else(|| args.path.clone());
+    let path = normalize_path(store_name, &path)?;
+
+    // Step 2: Resolve the commit to blame
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let rev = args.rev.as_deref().unwrap_or(&trunk_ref_name);
+    debug!("➡️ Step 2: Resolving {}", rev);
+    let Some(commit) = repo.resolve_commit(rev) else {
+        return Err(TrunkError::NotFound(match &args.rev {
+            Some(rev) => format!("'{}' is not a commit in this repository", rev),
+            None => format!("{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.", trunk_ref_name, store_name, store_name),
+        }));
+    };
+    let object_type = run_git_command(repo.git().arg("cat-file").arg("-t").arg(format!("{}:{}", commit, path)), verbose)
+        .map_err(|e| TrunkError::Git(format!("git cat-file failed for {}: {}", path, e)))?;
+    match String::from_utf8_lossy(&object_type.stdout).trim() {
+        "blob" => {}
+        "tree" => return Err(TrunkError::Usage(format!("'{}' is a directory in {} of store '{}'; blame one of its files", path, rev, store_name))),
+        _ => return Err(TrunkError::NotFound(format!("'{}' is not in {} of store '{}'", path, rev, store_name))),
+    }
+    debug!("✓ Step 2: Blaming {} in {}", path, rev);
+
+    // Step 3: Blame every line
+    debug!("➡️ Step 3: Running git blame on {}", path);
+    let lines = blame(&repo, &commit, &path, verbose)?;
+    info!("✓ Step 3: {} line(s)", lines.len());
+
+    if args.sections {
+        let mut table = Table::new(&["Section", "Lines", "Changed", "Revision", "Author", "Message"]);
+        for (title, first, last) in sections(&lines) {
+            let Some(latest) = lines[first - 1..last].iter().max_by_key(|line| line.time) else { continue };
+            table.add_row(vec![
+                title.into(),
+                format!("{}-{}", first, last).into(),
+                latest.date().into(),
+                latest.hash[..latest.hash.len().min(7)].into(),
+                latest.author.as_str().into(),
+                latest.summary.as_str().into(),
+            ]);
+        }
+        table.print(args.no_table);
+        return Ok(());
+    }
+    // Lines are printed as they are, even under --plain, like `git blame`
+    let author_width = lines.iter().map(|line| line.author.chars().count()).max().unwrap_or(0);
+    let number_width = lines.len().to_string().len();
+    for (index, line) in lines.iter().enumerate() {
+        println!("{} ({:<author_width$} {} {:>number_width$}) {}", &line.hash[..line.hash.len().min(7)], line.author, line.date(), index + 1, line.content);
+    }
+    Ok(())
+}
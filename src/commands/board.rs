@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use clap::{Parser, Subcommand};
+use log::{debug, info};
+use crate::config::{self, TrunkConfig};
+use crate::commands::commit::{self, CommitArgs};
+use crate::commands::query::load_documents;
+use crate::frontmatter::{self, Value};
+use crate::table::{Cell, Table};
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Show a store's documents as a kanban board grouped by a frontmatter field", after_help = "Examples:\n  git trunk board\n  git trunk board move tasks/login.md done")]
+pub struct BoardArgs {
+    #[command(subcommand)]
+    action: Option<BoardAction>,
+    #[arg(long, help = "Frontmatter field that holds the column [default: config board_field or status]")]
+    field: Option<String>,
+    #[arg(long, help = "Print one line per item instead of side-by-side columns")]
+    no_table: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum BoardAction {
+    /// Moves a document to another column by editing its frontmatter, then commits the store
+    Move {
+        #[arg(help = "Document path inside the store (e.g. issues/login-bug.md)")]
+        path: String,
+        #[arg(help = "Target column")]
+        column: String,
+    },
+}
+
+impl BoardArgs {
+    pub fn moves_item(&self) -> bool {
+        self.action.is_some()
+    }
+}
+
+const DEFAULT_BOARD_FIELD: &str = "status";
+const NO_COLUMN: &str = "(none)";
+
+fn board_field(args: &BoardArgs, trunk_config: &TrunkConfig, store_name: &str) -> String {
+    args.field.clone()
+        .or_else(|| trunk_config.get_string(&format!("store.{}", store_name), "board_field"))
+        .unwrap_or_else(|| DEFAULT_BOARD_FIELD.to_string())
+}
+
+pub fn run(args: &BoardArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let field = board_field(args, &trunk_config, store_name);
+    let configured_columns = trunk_config.get_array(&format!("store.{}", store_name), "board_columns");
+
+    match &args.action {
+        None => show_board(repo_root, store_name, &field, &configured_columns, args.no_table, verbose)?,
+        Some(BoardAction::Move { path, column }) => {
+            if !configured_columns.is_empty() && !configured_columns.contains(column) {
+                return Err(TrunkError::Usage(format!("Unknown column '{}'. Columns for store '{}': {}", column, store_name, configured_columns.join(", "))));
+            }
+            move_item(repo_root, remote_name, store_name, &field, path, column, verbose)?;
+        }
+    }
+    Ok(())
+}
+
+fn show_board(repo_root: &Path, store_name: &str, field: &str, configured_columns: &[String], no_table: bool, verbose: bool) -> Result<(), TrunkError> {
+    // Step 2: Read documents at refs/trunk/<store>
+    let rev = format!("refs/trunk/{}", store_name);
+    debug!("➡️ Step 2: Reading documents at {}", rev);
+    let documents = load_documents(repo_root, &rev, verbose).map_err(|e| TrunkError::Io(format!("Failed to read store '{}' at {}: {}", store_name, rev, e)))?;
+
+    // Step 3: Group them into columns (configured ones first, in order)
+    let mut columns: Vec<(String, Vec<String>)> = configured_columns.iter().map(|c| (c.clone(), Vec::new())).collect();
+    for (path, frontmatter) in &documents {
+        let column = match frontmatter.get(field) {
+            None | Some(Value::Null) => NO_COLUMN.to_string(),
+            Some(value) => value.to_string(),
+        };
+        let label = match frontmatter.get("title") {
+            Some(Value::String(title)) if !title.is_empty() => format!("{} ({})", title, path),
+            _ => path.clone(),
+        };
+        match columns.iter_mut().find(|(name, _)| *name == column) {
+            Some((_, items)) => items.push(label),
+            None => columns.push((column, vec![label])),
+        }
+    }
+    if let Some(index) = columns.iter().position(|(name, _)| name == NO_COLUMN) {
+        let none = columns.remove(index);
+        columns.push(none);
+    }
+    if columns.is_empty() {
+        info!("= No documents with frontmatter in store '{}'", store_name);
+        return Ok(());
+    }
+    debug!("✓ Step 3: {} document(s) in {} column(s)", documents.len(), columns.len());
+
+    // Step 4: Render
+    println!("\n📋 Board for store '{}' (by {})", store_name, field);
+    if no_table {
+        for (name, items) in &columns {
+            println!("\n{} ({})", name, items.len());
+            for item in items {
+                println!("  - {}", item);
+            }
+        }
+        return Ok(());
+    }
+    let headers: Vec<String> = columns.iter().map(|(name, items)| format!("{} ({})", name, items.len())).collect();
+    let header_refs: Vec<&str> = headers.iter().map(String::as_str).collect();
+    let mut table = Table::new(&header_refs);
+    let depth = columns.iter().map(|(_, items)| items.len()).max().unwrap_or(0);
+    for row in 0..depth {
+        table.add_row(columns.iter().map(|(_, items)| Cell::from(items.get(row).cloned().unwrap_or_default())).collect());
+    }
+    table.print(false);
+    Ok(())
+}
+
+fn move_item(repo_root: &Path, remote_name: &str, store_name: &str, field: &str, path: &str, column: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 2: Locate the document in the store checkout
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    let document_path = trunk_store_dir.join(path);
+    debug!("➡️ Step 2: Checking {}/{}", store_dir_relative_path, path);
+    if !document_path.is_file() {
+        return Err(TrunkError::NotFound(format!("{}/{} not found. Run `git trunk checkout --store {}` if the store is not checked out.", store_dir_relative_path, path, store_name)));
+    }
+    let status_output = run_git_command(Command::new("git").arg("status").arg("--porcelain").current_dir(&trunk_store_dir), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to run git status in {}: {}", store_dir_relative_path, e)))?;
+    if !status_output.stdout.is_empty() {
+        return Err(TrunkError::Conflict(format!("{} has uncommitted changes. Commit them with `git trunk commit --store {}` before moving items.", store_dir_relative_path, store_name)));
+    }
+    info!("✓ Step 2: Found {}/{}", store_dir_relative_path, path);
+
+    // Step 3: Update the frontmatter field
+    let content = fs::read_to_string(&document_path).map_err(|e| TrunkError::Io(format!("Failed to read {}: {}", document_path.display(), e)))?;
+    let current = frontmatter::parse(&content).and_then(|fm| fm.get(field).map(|value| value.to_string()));
+    if current.as_deref() == Some(column) {
+        info!("= Step 3: {} is already in '{}'", path, column);
+        return Ok(());
+    }
+    fs::write(&document_path, frontmatter::set_field(&content, field, column)).map_err(|e| TrunkError::Io(format!("Failed to write {}: {}", document_path.display(), e)))?;
+    info!("✓ Step 3: Set {}: {} in {}", field, column, path);
+
+    // Step 4: Commit the store
+    let message = format!("Move {} from {} to {}", path, current.as_deref().unwrap_or(NO_COLUMN), column);
+    commit::run(&CommitArgs { force: true, message: Some(message), ..Default::default() }, remote_name, store_name, verbose)
+}
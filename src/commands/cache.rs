@@ -0,0 +1,90 @@
+use std::fs;
+use std::time::Duration;
+use clap::{Parser, Subcommand};
+use log::{debug, info};
+use crate::cache::{cache_home, Cache};
+use crate::ops;
+use crate::quota::format_size;
+use crate::table::{print_line, Table};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(about = "Show or clear the data git-trunk caches for this repository", after_help = "Examples:\n  git trunk cache info\n  git trunk cache clear\n  git trunk cache clear --all")]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    action: CacheAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheAction {
+    /// Shows where the cache of this repository lives and what it holds
+    Info {
+        #[arg(long, help = "Print one Field: value line per fact instead of a table")]
+        no_table: bool,
+    },
+    /// Deletes the cached data of this repository; it is rebuilt when needed
+    Clear {
+        #[arg(long, help = "Clear the caches of every repository, not only this one")]
+        all: bool,
+    },
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+fn no_cache_dir() -> TrunkError {
+    TrunkError::Config("No cache directory: neither XDG_CACHE_HOME nor HOME is set".to_string())
+}
+
+pub fn run(args: &CacheArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    if let CacheAction::Clear { all: true } = args.action {
+        let root = cache_home().ok_or_else(no_cache_dir)?;
+        debug!("➡️ Step 1: Clearing every cache under {}", root.display());
+        let mut cleared = 0;
+        if let Ok(dirs) = fs::read_dir(&root) {
+            for dir in dirs.filter_map(|entry| entry.ok()).filter(|entry| entry.path().is_dir()) {
+                cleared += Cache::at(dir.path()).clear().map_err(|e| TrunkError::Io(format!("Failed to clear {}: {}", dir.path().display(), e)))?;
+            }
+        }
+        info!("✅ Cleared {} cached entr{} under {}", cleared, if cleared == 1 { "y" } else { "ies" }, root.display());
+        return Ok(());
+    }
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let cache = Cache::open(&repo).map_err(|_| no_cache_dir())?;
+
+    match args.action {
+        CacheAction::Info { no_table } => {
+            print_line(&format!("\n💾 Git Trunk Cache ({})", cache.dir().display()));
+            let entries = cache.entries().map_err(|e| TrunkError::Io(format!("Failed to read {}: {}", cache.dir().display(), e)))?;
+            if entries.is_empty() {
+                print_line("The cache is empty");
+                return Ok(());
+            }
+            let mut table = Table::new(&["Entry", "Size", "Age"]);
+            for entry in &entries {
+                table.add_row(vec![
+                    entry.key.as_str().into(),
+                    format_size(entry.size).into(),
+                    entry.age.map(format_age).unwrap_or_else(|| "-".to_string()).into(),
+                ]);
+            }
+            table.print(no_table);
+        }
+        CacheAction::Clear { .. } => {
+            // Step 2: Delete the entries
+            debug!("➡️ Step 2: Clearing {}", cache.dir().display());
+            let cleared = cache.clear().map_err(|e| TrunkError::Io(format!("Failed to clear {}: {}", cache.dir().display(), e)))?;
+            info!("✅ Cleared {} cached entr{} from {}", cleared, if cleared == 1 { "y" } else { "ies" }, cache.dir().display());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::io::Write;
+use clap::Parser;
+use log::debug;
+use crate::locks::normalize_path;
+use crate::ops;
+use crate::utils::{read_blobs, run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Print a file of refs/trunk/<store> without checking it out", after_help = "Examples:\n  git trunk cat architecture.md\n  git trunk cat handbook:policies/travel.md\n  git trunk cat --ref 1a2b3c4 architecture.md > old-architecture.md")]
+pub struct CatArgs {
+    #[arg(help = "File to print: relative to .trunk/<store>, or `store:path`")]
+    path: String,
+    #[arg(long = "ref", value_name = "COMMIT", help = "Print the file as of this commit of the store, e.g. one shown by `git trunk history`, instead of refs/trunk/<store>")]
+    rev: Option<String>,
+}
+
+impl CatArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        vec![&mut self.path]
+    }
+}
+
+// The object type of `path` in the commit ("blob", "tree" or "commit" for a
+// submodule), or None when the commit does not have it.
+fn object_type(repo: &GitRepo, commit: &str, path: &str, verbose: bool) -> Result<Option<String>, TrunkError> {
+    let output = run_git_command(repo.git().arg("ls-tree").arg("-z").arg(commit).arg("--").arg(path), verbose)
+        .map_err(|e| TrunkError::Git(format!("git ls-tree failed for {}: {}", commit, e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git ls-tree failed for {}: {}", commit, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    // "<mode> <type> <object>\t<path>"
+    Ok(output
+        .stdout
+        .split(|b| *b == 0)
+        .map(|entry| String::from_utf8_lossy(entry).to_string())
+        .find(|entry| entry.split_once('\t').is_some_and(|(_, name)| name == path))
+        .and_then(|entry| entry.split_whitespace().nth(1).map(str::to_string)))
+}
+
+pub fn run(args: &CatArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    debug!("✓ Step 1: Repository root found at {}", repo.root().display());
+    let path = ops::store_paths(&repo, std::slice::from_ref(&args.path), store_name, verbose).pop().unwrap_or_else(|| args.path.clone());
+    let path = normalize_path(store_name, &path)?;
+
+    // Step 2: Resolve the commit to read
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let rev = args.rev.as_deref().unwrap_or(&trunk_ref_name);
+    debug!("➡️ Step 2: Resolving {}", rev);
+    let Some(commit) = repo.resolve_commit(rev) else {
+        return Err(TrunkError::NotFound(match &args.rev {
+            Some(rev) => format!("'{}' is not a commit in this repository", rev),
+            None => format!("{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.", trunk_ref_name, store_name, store_name),
+        }));
+    };
+    debug!("✓ Step 2: Reading {} ({})", rev, short_hash(repo.root(), &commit, verbose));
+
+    // Step 3: Print the file, byte for byte
+    match object_type(&repo, &commit, &path, verbose)?.as_deref() {
+        Some("blob") => {}
+        Some("tree") => return Err(TrunkError::Usage(format!("'{}' is a directory in {} of store '{}'; `git trunk ls {}` lists it", path, rev, store_name, path))),
+        Some(_) => return Err(TrunkError::Usage(format!("'{}' is a submodule in {} of store '{}', not a file", path, rev, store_name))),
+        None => return Err(TrunkError::NotFound(format!("'{}' is not in {} of store '{}'", path, rev, store_name))),
+    }
+    let content = read_blobs(repo.root(), &[format!("{}:{}", commit, path)], verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to read {} from {}: {}", path, rev, e)))?
+        .pop()
+        .flatten()
+        .ok_or_else(|| TrunkError::NotFound(format!("'{}' is not in {} of store '{}'", path, rev, store_name)))?;
+    let mut stdout = std::io::stdout().lock();
+    match stdout.write_all(&content).and_then(|_| stdout.flush()) {
+        // A reader such as `head` that stops early is not an error
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(TrunkError::Io(format!("Failed to write {}: {}", path, e))),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,224 @@
+use std::process::Command;
+use clap::{Parser, ValueEnum};
+use log::debug;
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Summarize which store documents were added, changed or removed between two revisions", after_help = "Examples:\n  git trunk changelog --from ~10 --group\n  git trunk changelog --from v1.0 --format md --messages")]
+pub struct ChangelogArgs {
+    #[arg(long, help = "Older store revision (e.g. a tag, a commit, or `~5` for five commits before --to)")]
+    from: String,
+    #[arg(long, help = "Newer store revision [default: refs/trunk/<store>]")]
+    to: Option<String>,
+    #[arg(long, value_enum, default_value_t = ChangelogFormat::Text, help = "Output format")]
+    format: ChangelogFormat,
+    #[arg(long, help = "Group documents by directory")]
+    group: bool,
+    #[arg(long, help = "Include the commit messages between the two revisions")]
+    messages: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum ChangelogFormat {
+    Text,
+    Md,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Added,
+    Changed,
+    Removed,
+    Renamed,
+}
+
+impl Change {
+    pub fn heading(&self) -> &'static str {
+        match self {
+            Change::Added => "Added",
+            Change::Changed => "Changed",
+            Change::Removed => "Removed",
+            Change::Renamed => "Renamed",
+        }
+    }
+
+    fn marker(&self) -> &'static str {
+        match self {
+            Change::Added => "+",
+            Change::Changed => "~",
+            Change::Removed => "-",
+            Change::Renamed => "»",
+        }
+    }
+}
+
+pub const CHANGE_ORDER: [Change; 4] = [Change::Added, Change::Changed, Change::Removed, Change::Renamed];
+
+#[derive(Debug)]
+pub struct Entry {
+    pub change: Change,
+    pub path: String,
+    pub old_path: Option<String>,
+}
+
+impl Entry {
+    fn label(&self, markdown: bool) -> String {
+        let quote = |path: &str| if markdown { format!("`{}`", path) } else { path.to_string() };
+        match &self.old_path {
+            Some(old_path) => format!("{} → {}", quote(old_path), quote(&self.path)),
+            None => quote(&self.path),
+        }
+    }
+
+    fn directory(&self) -> &str {
+        self.path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+    }
+}
+
+// Revisions starting with `~` or `^` are relative to the store ref.
+fn resolve_rev(rev: &str, store_ref: &str) -> String {
+    if rev.starts_with('~') || rev.starts_with('^') {
+        format!("{}{}", store_ref, rev)
+    } else {
+        rev.to_string()
+    }
+}
+
+pub fn parse_name_status(output: &[u8]) -> Vec<Entry> {
+    let fields: Vec<String> = output.split(|b| *b == 0).map(|field| String::from_utf8_lossy(field).to_string()).collect();
+    let mut entries = Vec::new();
+    let mut i = 0;
+    while i < fields.len() {
+        let status = &fields[i];
+        if status.is_empty() {
+            i += 1;
+            continue;
+        }
+        let (change, takes_two) = match status.chars().next() {
+            Some('A') => (Change::Added, false),
+            Some('D') => (Change::Removed, false),
+            Some('R') => (Change::Renamed, true),
+            Some('C') => (Change::Added, true),
+            _ => (Change::Changed, false),
+        };
+        if takes_two {
+            let (Some(old_path), Some(path)) = (fields.get(i + 1), fields.get(i + 2)) else { break };
+            let old_path = (change == Change::Renamed).then(|| old_path.clone());
+            entries.push(Entry { change, path: path.clone(), old_path });
+            i += 3;
+        } else {
+            let Some(path) = fields.get(i + 1) else { break };
+            entries.push(Entry { change, path: path.clone(), old_path: None });
+            i += 2;
+        }
+    }
+    entries
+}
+
+fn render(entries: &[Entry], commits: &[String], title: &str, args: &ChangelogArgs) -> String {
+    let markdown = args.format == ChangelogFormat::Md;
+    let mut out = String::new();
+    let count = |change: Change| entries.iter().filter(|entry| entry.change == change).count();
+    let summary = CHANGE_ORDER.iter()
+        .map(|change| format!("{} {}", count(*change), change.heading().to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if markdown {
+        out.push_str(&format!("## {}\n\n{}\n", title, summary));
+    } else {
+        out.push_str(&format!("{}: {}\n", title, summary));
+    }
+
+    // Sections are either change kinds (listing paths) or directories
+    // (listing paths prefixed by their change).
+    let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+    if args.group {
+        let mut directories: Vec<&str> = entries.iter().map(Entry::directory).collect();
+        directories.sort();
+        directories.dedup();
+        for directory in directories {
+            let items = entries.iter()
+                .filter(|entry| entry.directory() == directory)
+                .map(|entry| format!("{} {}", if markdown { entry.change.heading().to_lowercase() } else { entry.change.marker().to_string() }, entry.label(markdown)))
+                .collect();
+            sections.push((if directory.is_empty() { "/".to_string() } else { format!("{}/", directory) }, items));
+        }
+    } else {
+        for change in CHANGE_ORDER {
+            let items: Vec<String> = entries.iter()
+                .filter(|entry| entry.change == change)
+                .map(|entry| if markdown { entry.label(true) } else { format!("{} {}", change.marker(), entry.label(false)) })
+                .collect();
+            if !items.is_empty() {
+                sections.push((change.heading().to_string(), items));
+            }
+        }
+    }
+    if args.messages && !commits.is_empty() {
+        sections.push(("Commits".to_string(), commits.to_vec()));
+    }
+
+    for (heading, items) in sections {
+        if markdown {
+            out.push_str(&format!("\n### {}\n\n", heading));
+            for item in items {
+                out.push_str(&format!("- {}\n", item));
+            }
+        } else {
+            out.push_str(&format!("\n{}:\n", heading));
+            for item in items {
+                out.push_str(&format!("  {}\n", item));
+            }
+        }
+    }
+    out
+}
+
+pub fn run(args: &ChangelogArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Resolve both revisions in the main repository
+    let store_ref = format!("refs/trunk/{}", store_name);
+    let from = resolve_rev(&args.from, &store_ref);
+    let to = resolve_rev(args.to.as_deref().unwrap_or(&store_ref), &store_ref);
+    debug!("➡️ Step 2: Resolving {} and {}", from, to);
+    let short = |rev: &str| -> Result<String, TrunkError> {
+        match run_git_command(Command::new("git").arg("rev-parse").arg("--verify").arg("--quiet").arg("--short").arg(format!("{}^{{commit}}", rev)).current_dir(repo_root), verbose) {
+            Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            _ => Err(TrunkError::Usage(format!("Unknown store revision '{}'. Use a commit, a tag, or `~N` relative to {}.", rev, store_ref))),
+        }
+    };
+    let (from_short, to_short) = (short(&from)?, short(&to)?);
+    debug!("✓ Step 2: Comparing {}..{}", from_short, to_short);
+
+    // Step 3: Collect changed documents and commits
+    debug!("➡️ Step 3: Collecting changes");
+    let name_status = run_git_command(
+        Command::new("git").arg("diff").arg("--name-status").arg("-z").arg("-M").arg(&from).arg(&to).arg("--").current_dir(repo_root),
+        verbose,
+    )
+    .map_err(|e| TrunkError::Git(format!("Failed to run git diff: {}", e)))?;
+    if !name_status.status.success() {
+        return Err(TrunkError::Git(format!("git diff {} {} failed", from, to)));
+    }
+    let entries = parse_name_status(&name_status.stdout);
+    let commits: Vec<String> = if args.messages {
+        run_git_command(Command::new("git").arg("log").arg("--format=%h %s").arg(format!("{}..{}", from, to)).current_dir(repo_root), verbose)
+            .map(|output| String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    debug!("✓ Step 3: {} document(s) changed in {} commit(s)", entries.len(), commits.len());
+
+    // Step 4: Output
+    let title = format!("Changes to store '{}' ({}..{})", store_name, from_short, to_short);
+    print!("{}", render(&entries, &commits, &title, args));
+    Ok(())
+}
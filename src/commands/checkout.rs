@@ -1,55 +1,79 @@
 use std::fs;
-use std::io::{self, Write};
-use std::path::Path;
-use std::process::{Command, exit};
 use clap::Parser;
 use log::{debug, error, info};
-use crate::utils::{run_git_command, ensure_trunk_in_gitignore};
+use crate::config;
+use crate::commands::expose;
+use crate::ops;
+use crate::quota;
+use crate::utils::{check_dir_writable, confirm, dry_run, is_dry_run, ensure_trunk_in_gitignore, run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+use crate::table::{Cell, Table};
 
 #[derive(Parser, Debug)]
-#[command(about = "Checkout a trunk store from refs/trunk/<store> into .trunk/<store>")]
+#[command(about = "Checkout a trunk store from refs/trunk/<store> into .trunk/<store>", after_help = "Examples:\n  git trunk checkout\n  git trunk checkout --store blog --remote upstream\n  git trunk checkout --force\n  git trunk checkout --all")]
 pub struct CheckoutArgs {
-    #[arg(long, help = "Force cloning, overwriting existing .trunk/<store> directory")]
+    #[arg(long, help = "Delete an existing .trunk/<store> and clone it again, dropping uncommitted changes, instead of updating it in place")]
     force: bool,
+    #[arg(long, help = "Do not check for write access and free disk space before creating .trunk/<store>")]
+    no_preflight: bool,
+    #[arg(long, help = "Check out every store the remote has under refs/trunk/* and summarize the results")]
+    all: bool,
+    #[arg(long, requires = "all", help = "With --all, print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
 }
 
-pub fn run(args: &CheckoutArgs, remote_name: &str, store_name: &str, verbose: bool) {
+pub fn run(args: &CheckoutArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
     // Step 1: Get repository root
-    debug!("➡️ Step 1: Getting repository root");
-    let repo_root_output = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--show-toplevel"),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to get git repository root: {}", e);
-        exit(1);
-    });
-    let repo_root_str = String::from_utf8_lossy(&repo_root_output.stdout).trim().to_string();
-    if repo_root_str.is_empty() {
-        error!("❌ Git repository root is empty. Ensure you are in a valid Git repository.");
-        exit(1);
-    }
-    let repo_root = Path::new(&repo_root_str);
-    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    ops::check_foreign_refs(&repo, "Step 1b", verbose)?;
+    if !args.all {
+        return checkout_store(&repo, args, remote_name, store_name, verbose);
+    }
+
+    let stores = ops::remote_store_names(&repo, remote_name, verbose)?;
+    if stores.is_empty() {
+        info!("= Remote '{}' has no refs/trunk/* stores to check out", remote_name);
+        return Ok(());
+    }
+    info!("✓ Remote '{}' has {} store(s): {}", remote_name, stores.len(), stores.join(", "));
+    let mut table = Table::new(&["Store", "Path", "Result"]);
+    let mut failed = 0;
+    for store in &stores {
+        info!("➡️ Checking out store '{}'", store);
+        let result = checkout_store(&repo, args, remote_name, store, verbose);
+        if let Err(e) = &result {
+            error!("❌ Store '{}': {}", store, e);
+            failed += 1;
+        }
+        table.add_row(vec![
+            store.as_str().into(),
+            format!(".trunk/{}", store).into(),
+            match result {
+                Ok(()) => Cell::flag(true, "checked out"),
+                Err(e) => Cell::flag(false, &e.to_string()),
+            },
+        ]);
+    }
+    println!();
+    table.print(args.no_table);
+    if failed > 0 {
+        return Err(TrunkError::Failed(format!("{} of {} store(s) failed to check out", failed, stores.len())));
+    }
+    info!("✅ Checked out all {} store(s) from remote '{}'", stores.len(), remote_name);
+    Ok(())
+}
+
+fn checkout_store(repo: &GitRepo, args: &CheckoutArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let repo_root = repo.root();
 
     let trunk_ref_name = format!("refs/trunk/{}", store_name);
     let store_dir_relative_path = format!(".trunk/{}", store_name);
-    let trunk_store_dir = repo_root.join(&store_dir_relative_path);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
 
     // Step 2: Check if refs/trunk/<store_name> exists locally
     debug!("➡️ Step 2: Checking if {} exists locally", trunk_ref_name);
-    let local_ref_exists = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--verify")
-            .arg(&trunk_ref_name)
-            .current_dir(repo_root),
-        verbose,
-    )
-    .map(|output| output.status.success())
-    .unwrap_or(false);
+    let local_ref_exists = repo.ref_exists(&trunk_ref_name);
 
     if local_ref_exists {
         info!("✓ Step 2: {} found locally", trunk_ref_name);
@@ -58,182 +82,206 @@ pub fn run(args: &CheckoutArgs, remote_name: &str, store_name: &str, verbose: bo
 
         // Step 3: Check if refs/trunk/<store_name> exists on the remote
         debug!("➡️ Step 3: Checking if {} exists on remote '{}'", trunk_ref_name, remote_name);
-        let remote_ref_check = run_git_command(
-            Command::new("git")
-                .arg("ls-remote")
-                .arg(remote_name)
-                .arg(&trunk_ref_name)
-                .current_dir(repo_root),
-            verbose,
-        )
-        .unwrap_or_else(|e| {
-            error!("❌ Failed to check {} on remote '{}': {}", trunk_ref_name, remote_name, e);
-            exit(1);
-        });
-        if !remote_ref_check.status.success() || remote_ref_check.stdout.is_empty() {
-            error!("❌ {} for store '{}' does not exist on the remote '{}'. Ensure it was pushed with `git trunk push --store {} --remote {}`.", trunk_ref_name, store_name, remote_name, store_name, remote_name);
-            exit(1);
+        if !matches!(ops::remote_store_tip(repo, remote_name, store_name, verbose), Ok(Some(_))) {
+            return Err(TrunkError::NotFound(format!("{} for store '{}' does not exist on the remote '{}'. Ensure it was pushed with `git trunk push --store {} --remote {}`.", trunk_ref_name, store_name, remote_name, store_name, remote_name)));
         }
         info!("✓ Step 3: {} found on remote '{}'", trunk_ref_name, remote_name);
 
         // Step 4: Fetch refs/trunk/<store_name> from remote
         debug!("📥 Step 4: Fetching {} from remote '{}'", trunk_ref_name, remote_name);
-        let fetch_refspec = format!("{}:{}", trunk_ref_name, trunk_ref_name);
-        let fetch_status = run_git_command(
-            Command::new("git")
-                .arg("fetch")
-                .arg(remote_name)
-                .arg(&fetch_refspec)
-                .current_dir(repo_root),
-            verbose,
-        )
-        .unwrap_or_else(|e| {
-            error!("❌ Failed to fetch {} from remote '{}': {}", trunk_ref_name, remote_name, e);
-            exit(1);
-        })
-        .status;
-        if !fetch_status.success() {
-            error!("❌ Failed to fetch {} from remote '{}'. Check remote configuration and network connectivity.", trunk_ref_name, remote_name);
-            exit(1);
-        }
+        ops::fetch_store_ref(repo, remote_name, store_name, &trunk_ref_name, verbose)?;
         info!("✓ Step 4: Successfully fetched {} from remote '{}'", trunk_ref_name, remote_name);
     }
 
     // Step 5: Verify refs/trunk/<store_name> exists locally after fetch attempt
     debug!("🔍 Step 5: Verifying {} exists locally for store '{}'", trunk_ref_name, store_name);
-    let final_ref_check = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--verify")
-            .arg(&trunk_ref_name)
-            .current_dir(repo_root),
-        verbose,
-    );
-    if final_ref_check.map(|output| !output.status.success()).unwrap_or(true) {
-        error!("❌ {} for store '{}' is still missing after attempting to fetch. Ensure it was pushed to the remote.", trunk_ref_name, store_name);
-        exit(1);
+    if !repo.ref_exists(&trunk_ref_name) && !is_dry_run() {
+        return Err(TrunkError::NotFound(format!("{} for store '{}' is still missing after attempting to fetch. Ensure it was pushed to the remote.", trunk_ref_name, store_name)));
     }
     info!("✓ Step 5: {} verified locally for store '{}'", trunk_ref_name, store_name);
 
     // Step 6: Ensure .trunk is in .gitignore (parent directory)
     debug!("➡️ Step 6: Ensuring .trunk is in .gitignore");
     if let Err(e) = ensure_trunk_in_gitignore(repo_root, "Step 6") {
-        error!("❌ Failed to update .gitignore for Step 6: {}", e);
-        exit(1);
+        return Err(TrunkError::Git(format!("Failed to update .gitignore for Step 6: {}", e)));
     }
     // Detailed info/debug for Step 6 (added/already exists) is handled by ensure_trunk_in_gitignore
 
     // Step 7: Create .trunk parent directory if it doesn't exist
-    let parent_trunk_dir = repo_root.join(".trunk");
-    if !parent_trunk_dir.exists() {
-        debug!("✨ Step 7a: Creating parent .trunk directory");
-        fs::create_dir(&parent_trunk_dir).unwrap_or_else(|e| {
-            error!("❌ Failed to create .trunk parent directory: {}", e);
-            exit(1);
-        });
-        info!("✓ Step 7a: .trunk parent directory created at {:?}", parent_trunk_dir);
-    }
+    ops::ensure_trunk_dir(repo_root, "Step 7a", verbose)?;
     
+    let existing_checkout = if args.force { None } else { GitRepo::open(&trunk_store_dir, verbose).ok().filter(|store_repo| store_repo.resolve_commit("HEAD").is_some()) };
+
+    // Step 7b: Make sure a new checkout fits before anything is removed or written
+    if existing_checkout.is_none() {
+        if args.no_preflight {
+            info!("= Step 7b: --no-preflight, not checking space and permissions");
+        } else {
+            preflight(repo, store_name, verbose)?;
+        }
+    }
+
     // Step 8: Check if .trunk/<store_name> exists
     debug!("➡️ Step 8: Checking if {} directory exists for store '{}'", store_dir_relative_path, store_name);
+    if let Some(store_repo) = existing_checkout {
+        info!("✓ Step 8: {} is checked out, updating it in place", store_dir_relative_path);
+        // Steps 9-12: Move the checkout to refs/trunk/<store_name>, keeping uncommitted changes
+        update_checkout(repo, &store_repo, store_name, verbose)?;
+        expose::sync_exposed_branch(repo, store_name, verbose);
+        info!("✅ Trunk store '{}' in {} is at {}", store_name, store_dir_relative_path, trunk_ref_name);
+        return Ok(());
+    }
     if trunk_store_dir.exists() {
         let should_overwrite = if args.force {
             info!("🚀 Step 8: {} exists, --force specified, will overwrite for store '{}'", store_dir_relative_path, store_name);
             true
         } else {
             debug!("📍 Step 8: {} directory exists for store '{}'", store_dir_relative_path, store_name);
-            print!("🐘︖ Overwrite existing {} directory for store '{}'? [y/N]: ", store_dir_relative_path, store_name);
-            io::stdout().flush().expect("Failed to flush stdout");
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).expect("Failed to read user input");
-            let input = input.trim().to_lowercase();
-            if input == "y" || input == "yes" {
+            if confirm(&format!("Overwrite existing {} directory for store '{}'?", store_dir_relative_path, store_name))? {
                 debug!("👍 Step 8: User confirmed overwrite for store '{}'", store_name);
                 true
             } else {
                 info!("🚫 Step 8: Checkout for store '{}' aborted by user", store_name);
-                exit(0);
+                return Ok(());
             }
         };
-        if should_overwrite {
+        if should_overwrite && !dry_run(&format!("remove directory {}", trunk_store_dir.display())) {
             debug!("🗑️ Step 8: Removing existing {} directory for store '{}'", store_dir_relative_path, store_name);
-            fs::remove_dir_all(&trunk_store_dir).unwrap_or_else(|e| {
-                error!("❌ Failed to remove existing {} directory: {}", store_dir_relative_path, e);
-                exit(1);
-            });
+            fs::remove_dir_all(&trunk_store_dir).map_err(|e| TrunkError::Io(format!("Failed to remove existing {} directory: {}", store_dir_relative_path, e)))?;
             info!("✓ Step 8: Existing {} directory removed for store '{}'", store_dir_relative_path, store_name);
         }
     } else {
         debug!("∉ Step 8: {} directory does not exist for store '{}'", store_dir_relative_path, store_name);
     }
 
-    // Step 9: Create .trunk/<store_name> directory
-    debug!("✨ Step 9: Creating {} directory for store '{}'", store_dir_relative_path, store_name);
-    fs::create_dir_all(&trunk_store_dir).unwrap_or_else(|e| { // create_dir_all for parent .trunk too
-        error!("❌ Failed to create {} directory: {}", store_dir_relative_path, e);
-        exit(1);
-    });
-    info!("✓ Step 9: {} directory created for store '{}'", store_dir_relative_path, store_name);
-
-    // Step 10: Initialize Git repository in .trunk/<store_name>
-    debug!("⚙️ Step 10: Initializing Git repository in {}", store_dir_relative_path);
-    run_git_command(Command::new("git").arg("init").current_dir(&trunk_store_dir), verbose)
-        .and_then(|out| if !out.status.success() { Err(io::Error::new(io::ErrorKind::Other, "git init failed")) } else { Ok(()) })
-        .unwrap_or_else(|e| { error!("❌ Failed to run git init in {}: {}", store_dir_relative_path, e); exit(1); });
-    info!("✓ Step 10: Git repository initialized in {}", store_dir_relative_path);
-
-    // Step 11: Fetch history from main repo's refs/trunk/<store_name> into a temporary ref in .trunk/<store_name>
-    let temp_store_ref = "refs/temp/trunk_store_data";
-    debug!("📥 Step 11: Fetching {} from main repo into {} temporary ref '{}'", trunk_ref_name, store_dir_relative_path, temp_store_ref);
-    run_git_command(
-        Command::new("git")
-            .arg("fetch")
-            .arg(repo_root.as_os_str()) // Path to main repository
-            .arg(format!("{}:{}", trunk_ref_name, temp_store_ref))
-            .current_dir(&trunk_store_dir),
-        verbose,
-    )
-    .and_then(|out| if !out.status.success() { Err(io::Error::new(io::ErrorKind::Other, "git fetch failed")) } else { Ok(()) })
-    .unwrap_or_else(|e| { error!("❌ Failed to fetch {} into {}: {}", trunk_ref_name, store_dir_relative_path, e); exit(1); });
-    info!("✓ Step 11: Successfully fetched {} into temporary ref in {}", trunk_ref_name, store_dir_relative_path);
-
-    // Step 12: Get the fetched commit hash from the temporary ref
-    debug!("🔑 Step 12: Getting fetched commit hash from {} in {}", temp_store_ref, store_dir_relative_path);
-    let commit_hash_output = run_git_command(
-        Command::new("git").arg("rev-parse").arg(temp_store_ref).current_dir(&trunk_store_dir),
-        verbose,
-    ).unwrap_or_else(|e| { error!("❌ Failed to get fetched commit hash from {}: {}", temp_store_ref, e); exit(1); });
-    if !commit_hash_output.status.success() { error!("❌ {} not found after fetch in {}", temp_store_ref, store_dir_relative_path); exit(1); }
-    let commit_hash = String::from_utf8_lossy(&commit_hash_output.stdout).trim().to_string();
-    info!("✓ Step 12: Fetched commit hash for store '{}': {}", store_name, commit_hash);
-
-    // Step 13: Reset main branch in .trunk/<store_name> to the fetched commit
-    debug!("🔄 Step 13: Resetting {} main branch to fetched commit {}", store_dir_relative_path, commit_hash);
-    run_git_command(Command::new("git").arg("reset").arg("--hard").arg(&commit_hash).current_dir(&trunk_store_dir), verbose)
-        .and_then(|out| if !out.status.success() { Err(io::Error::new(io::ErrorKind::Other, "git reset failed")) } else { Ok(()) })
-        .unwrap_or_else(|e| { error!("❌ Failed to reset {} to fetched commit: {}", store_dir_relative_path, e); exit(1); });
-    info!("✓ Step 13: Main branch in {} reset to commit {}", store_dir_relative_path, commit_hash);
-
-    // Step 14: Update main branch ref in .trunk/<store_name> (git reset --hard might not update HEAD if not on a branch yet)
-    debug!("🔄 Step 14: Updating refs/heads/main in {}", store_dir_relative_path);
-    run_git_command(Command::new("git").arg("update-ref").arg("refs/heads/main").arg(&commit_hash).current_dir(&trunk_store_dir), verbose)
-        .and_then(|out| if !out.status.success() { Err(io::Error::new(io::ErrorKind::Other, "git update-ref failed")) } else { Ok(()) })
-        .unwrap_or_else(|e| { error!("❌ Failed to update refs/heads/main in {}: {}", store_dir_relative_path, e); exit(1); });
-    info!("✓ Step 14: refs/heads/main updated in {}", store_dir_relative_path);
-    
-    // Step 14b: Ensure .trunk/<store_name> is on the main branch
-    debug!("⤵️ Step 14b: Ensuring {} is on the main branch", store_dir_relative_path);
-    run_git_command(Command::new("git").arg("checkout").arg("main").current_dir(&trunk_store_dir), verbose)
-        .and_then(|out| if !out.status.success() { Err(io::Error::new(io::ErrorKind::Other, "git checkout main failed")) } else { Ok(()) })
-        .unwrap_or_else(|e| { error!("❌ Failed to checkout main in {}: {}", store_dir_relative_path, e); exit(1); });
+    // Steps 9-15: Create .trunk/<store_name> on the main branch at refs/trunk/<store_name>
+    ops::materialize_store(repo, store_name, verbose)?;
+
+    expose::sync_exposed_branch(repo, store_name, verbose);
+
+    info!("✅ Trunk store '{}' checked out successfully into {}", store_name, store_dir_relative_path);
+    Ok(())
+}
+// Checks that .trunk/<store_name> can be created and that its filesystem has
+// room for the files and history of refs/trunk/<store_name>, so a large store
+// fails up front instead of leaving a half-written directory.
+fn preflight(repo: &GitRepo, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let trunk_base_dir = config::trunk_base_dir(repo.root(), verbose);
+    // Under --dry-run .trunk may not exist yet; check where it would be created
+    let Some(target_dir) = trunk_base_dir.ancestors().find(|dir| dir.is_dir()) else {
+        return Ok(());
+    };
+    debug!("➡️ Step 7b: Checking write access and free space in {}", target_dir.display());
+    check_dir_writable(target_dir).map_err(|e| TrunkError::Io(format!("Cannot check out store '{}': {}. Fix its permissions, or pass --no-preflight to try anyway.", store_name, e)))?;
+    let required = quota::checkout_size(repo.root(), &trunk_ref_name, verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to measure {}: {}", trunk_ref_name, e)))?;
+    match quota::available_space(target_dir) {
+        Ok(Some(available)) if available < required => Err(TrunkError::Io(format!(
+            "Not enough disk space to check out store '{}': it needs about {} and {} has {} free. Free some space, or pass --no-preflight to try anyway.",
+            store_name,
+            quota::format_size(required),
+            target_dir.display(),
+            quota::format_size(available)
+        ))),
+        Ok(Some(available)) => {
+            info!("✓ Step 7b: Store '{}' needs about {}, {} free", store_name, quota::format_size(required), quota::format_size(available));
+            Ok(())
+        }
+        Ok(None) => {
+            info!("✓ Step 7b: Store '{}' needs about {}; free space is unknown here", store_name, quota::format_size(required));
+            Ok(())
+        }
+        Err(e) => {
+            error!("⚠️ Warning: Failed to read the free space in {} ({}); checking out anyway", target_dir.display(), e);
+            Ok(())
+        }
+    }
+}
 
+// Fast-forwards an existing checkout to refs/trunk/<store_name> instead of
+// deleting it: uncommitted changes are stashed first and applied again after,
+// and commits only the checkout has stop the update.
+pub fn update_checkout(repo: &GitRepo, store_repo: &GitRepo, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let repo_root = repo.root();
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let temp_store_ref = "refs/temp/trunk_checkout";
 
-    // Step 15: Clean up temporary ref in .trunk/<store_name>
-    debug!("🧹 Step 15: Cleaning up temporary ref {} in {}", temp_store_ref, store_dir_relative_path);
-    if let Err(e) = run_git_command(Command::new("git").arg("update-ref").arg("-d").arg(temp_store_ref).current_dir(&trunk_store_dir), verbose) {
-        error!("⚠️ Warning: Failed to delete temporary ref {} in {}: {}", temp_store_ref, store_dir_relative_path, e);
+    // Step 9: Bring refs/trunk/<store_name> into the checkout
+    debug!("📥 Step 9: Fetching {} into {}", trunk_ref_name, store_dir_relative_path);
+    store_repo
+        .fetch_local(repo_root, &format!("+{}:{}", trunk_ref_name, temp_store_ref))
+        .map_err(|e| TrunkError::Git(format!("Failed to fetch {} into {}: {}", trunk_ref_name, store_dir_relative_path, e)))?;
+    if is_dry_run() {
+        dry_run(&format!("stash uncommitted changes in {}, fast-forward it to {} and apply them again", store_dir_relative_path, trunk_ref_name));
+        return Ok(());
+    }
+    let cleanup = || {
+        let _ = store_repo.delete_ref(temp_store_ref);
+    };
+    if store_repo.is_ancestor(temp_store_ref, "HEAD") {
+        cleanup();
+        info!("= Step 9: {} already contains {}", store_dir_relative_path, trunk_ref_name);
+        return Ok(());
     }
-    info!("✓ Step 15: Temporary ref cleaned up in {}", store_dir_relative_path);
+    if !store_repo.is_ancestor("HEAD", temp_store_ref) {
+        cleanup();
+        return Err(TrunkError::Conflict(format!(
+            "{} has commits that are not on {}, which has others. Run `git trunk commit --store {}` or `git trunk pull --store {}` to bring them together, or `git trunk checkout --force --store {}` to replace the checkout and drop its commits.",
+            store_dir_relative_path, trunk_ref_name, store_name, store_name, store_name
+        )));
+    }
+    info!("✓ Step 9: Fetched {} into {}", trunk_ref_name, store_dir_relative_path);
 
-    info!("✅ Trunk store '{}' checked out successfully into {}", store_name, store_dir_relative_path);
-}
\ No newline at end of file
+    // Step 10: Stash uncommitted changes, untracked files included
+    let changes = store_repo.status_porcelain().map_err(|e| TrunkError::Git(format!("Failed to read the status of {}: {}", store_dir_relative_path, e)))?.len();
+    if changes > 0 {
+        debug!("📦 Step 10: Stashing {} uncommitted change(s) in {}", changes, store_dir_relative_path);
+        let output = run_git_command(store_repo.git().arg("stash").arg("push").arg("--include-untracked").arg("-m").arg("git trunk checkout"), verbose)
+            .map_err(|e| TrunkError::Git(format!("Failed to stash the changes in {}: {}", store_dir_relative_path, e)))?;
+        if !output.status.success() {
+            cleanup();
+            return Err(TrunkError::Git(format!("Failed to stash the changes in {}: {}", store_dir_relative_path, String::from_utf8_lossy(&output.stderr).trim())));
+        }
+        info!("✓ Step 10: Stashed {} uncommitted change(s)", changes);
+    } else {
+        info!("= Step 10: No uncommitted changes in {}", store_dir_relative_path);
+    }
+
+    // Step 11: Fast-forward the checkout
+    debug!("🔄 Step 11: Fast-forwarding {} to {}", store_dir_relative_path, trunk_ref_name);
+    let merge = run_git_command(store_repo.git().arg("merge").arg("--ff-only").arg(temp_store_ref), verbose);
+    cleanup();
+    let merge_error = match merge {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Some(e.to_string()),
+    };
+    if let Some(merge_error) = merge_error {
+        if changes > 0 {
+            let _ = run_git_command(store_repo.git().arg("stash").arg("pop"), verbose);
+        }
+        return Err(TrunkError::Git(format!("Failed to fast-forward {} to {}: {}", store_dir_relative_path, trunk_ref_name, merge_error)));
+    }
+    let head = store_repo.resolve_commit("HEAD").unwrap_or_default();
+    info!("✓ Step 11: {} fast-forwarded to {}", store_dir_relative_path, short_hash(store_repo.root(), &head, verbose));
+
+    // Step 12: Apply the stashed changes again
+    if changes == 0 {
+        return Ok(());
+    }
+    debug!("📦 Step 12: Applying the stashed changes to {}", store_dir_relative_path);
+    let output = run_git_command(store_repo.git().arg("stash").arg("pop"), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to apply the stashed changes in {}: {}", store_dir_relative_path, e)))?;
+    if !output.status.success() {
+        let conflicted = store_repo.conflicted_paths().unwrap_or_default();
+        let detail = if conflicted.is_empty() { String::from_utf8_lossy(&output.stderr).trim().to_string() } else { format!("conflicts in {}", conflicted.join(", ")) };
+        return Err(TrunkError::Conflict(format!(
+            "{} is at {}, but your uncommitted changes did not apply cleanly ({}). Resolve the conflict markers there (`git trunk conflicts --store {}` lists them); the changes are also kept in `git stash list`.",
+            store_dir_relative_path, trunk_ref_name, detail, store_name
+        )));
+    }
+    info!("✓ Step 12: Applied {} stashed change(s) again", changes);
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use std::fs;
+use std::path::PathBuf;
+use clap::{Parser, Subcommand, ValueEnum};
+use log::{debug, info};
+use crate::config::{self, DEFAULT_REMOTE};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Generate CI configuration that keeps trunk stores maintained without a daemon", after_help = "Examples:\n  git trunk ci refresh-job\n  git trunk ci refresh-job --output .github/workflows/trunk-refresh.yml\n  git trunk ci refresh-job --provider gitlab --cron \"0 4 * * 1\" --output .gitlab/trunk-refresh.yml")]
+pub struct CiArgs {
+    #[command(subcommand)]
+    action: CiAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum CiAction {
+    /// Prints a scheduled pipeline that checks out, checks, re-indexes, commits and pushes every store
+    RefreshJob {
+        #[arg(long, value_enum, default_value_t = Provider::Github, help = "CI system to generate the pipeline for")]
+        provider: Provider,
+        #[arg(long, default_value = "0 3 * * *", help = "Cron schedule (UTC) the pipeline runs on")]
+        cron: String,
+        #[arg(long, help = "Write the pipeline to this file (relative to the repository root) instead of stdout")]
+        output: Option<String>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Provider {
+    Github,
+    Gitlab,
+}
+
+const INSTALL_COMMAND: &str = "cargo install --locked --git https://github.com/ssr1ram/git-trunk.git";
+
+fn remote_arg(remote_name: &str) -> String {
+    if remote_name == DEFAULT_REMOTE { String::new() } else { format!(" --remote {}", remote_name) }
+}
+
+fn fetch_command(store_name: &str, remote_name: &str) -> String {
+    format!("git trunk checkout --store {}{} --force", store_name, remote_arg(remote_name))
+}
+
+// Without a terminal, doctor fails on the first inconsistency instead of
+// fixing it, which fails the pipeline before anything is pushed.
+const CHECK_COMMAND: &str = "git trunk doctor";
+
+// Shell commands refreshing one checked-out store: regenerate its index, then
+// commit and push whatever changed.
+fn refresh_commands(store_name: &str, remote_name: &str) -> Vec<String> {
+    let remote_arg = remote_arg(remote_name);
+    vec![
+        format!("git trunk index-md --store {}", store_name),
+        format!("git trunk commit --store {} --force -m \"Scheduled refresh of store '{}'\"", store_name, store_name),
+        format!("git trunk push --store {}{}", store_name, remote_arg),
+    ]
+}
+
+// The identity is set globally: store commits are made in the separate
+// repositories under .trunk/, which do not read the main repository's config.
+fn render_github(stores: &[String], remote_name: &str, cron: &str) -> String {
+    let mut pipeline = String::new();
+    pipeline.push_str("# Generated by `git trunk ci refresh-job`; re-run it to update.\n");
+    pipeline.push_str("name: Refresh trunk stores\n\n");
+    pipeline.push_str(&format!("on:\n  schedule:\n    - cron: \"{}\"\n  workflow_dispatch:\n\n", cron));
+    pipeline.push_str("permissions:\n  contents: write\n\n");
+    pipeline.push_str("jobs:\n  refresh:\n    runs-on: ubuntu-latest\n    steps:\n");
+    pipeline.push_str("      - uses: actions/checkout@v4\n");
+    pipeline.push_str(&format!("      - name: Install git-trunk\n        run: {}\n", INSTALL_COMMAND));
+    pipeline.push_str("      - name: Configure git\n        run: |\n");
+    pipeline.push_str("          git config --global user.name \"github-actions[bot]\"\n");
+    pipeline.push_str("          git config --global user.email \"41898282+github-actions[bot]@users.noreply.github.com\"\n");
+    pipeline.push_str("      - name: Fetch stores\n        run: |\n");
+    for store in stores {
+        pipeline.push_str(&format!("          {}\n", fetch_command(store, remote_name)));
+    }
+    pipeline.push_str(&format!("      - name: Check stores\n        run: {}\n", CHECK_COMMAND));
+    for store in stores {
+        pipeline.push_str(&format!("      - name: Refresh store '{}'\n        run: |\n", store));
+        for command in refresh_commands(store, remote_name) {
+            pipeline.push_str(&format!("          {}\n", command));
+        }
+    }
+    pipeline
+}
+
+fn render_gitlab(stores: &[String], remote_name: &str, cron: &str) -> String {
+    let mut pipeline = String::new();
+    pipeline.push_str("# Generated by `git trunk ci refresh-job --provider gitlab`; re-run it to update.\n");
+    pipeline.push_str("# Include this file from .gitlab-ci.yml, then add a pipeline schedule\n");
+    pipeline.push_str(&format!("# (Build > Pipeline schedules) with the cron expression \"{}\".\n", cron));
+    pipeline.push_str("# Pushing needs a project access token with write_repository scope,\n");
+    pipeline.push_str("# stored as the masked CI/CD variable TRUNK_PUSH_TOKEN.\n");
+    pipeline.push_str("trunk-refresh:\n  image: rust:latest\n");
+    pipeline.push_str("  rules:\n    - if: $CI_PIPELINE_SOURCE == \"schedule\"\n");
+    pipeline.push_str("  variables:\n    GIT_DEPTH: \"0\"\n");
+    pipeline.push_str("  before_script:\n");
+    pipeline.push_str(&format!("    - {}\n", INSTALL_COMMAND));
+    pipeline.push_str("    - git config --global user.name \"git-trunk refresh\"\n");
+    pipeline.push_str("    - git config --global user.email \"git-trunk@$CI_SERVER_HOST\"\n");
+    pipeline.push_str(&format!("    - git remote set-url {} \"https://oauth2:${{TRUNK_PUSH_TOKEN}}@${{CI_SERVER_HOST}}/${{CI_PROJECT_PATH}}.git\"\n", remote_name));
+    pipeline.push_str("  script:\n");
+    let mut script: Vec<String> = stores.iter().map(|store| fetch_command(store, remote_name)).collect();
+    script.push(CHECK_COMMAND.to_string());
+    for store in stores {
+        script.extend(refresh_commands(store, remote_name));
+    }
+    for command in script {
+        // Single-quoted YAML scalars only need their quotes doubled
+        pipeline.push_str(&format!("    - '{}'\n", command.replace('\'', "''")));
+    }
+    pipeline
+}
+
+pub fn run(args: &CiArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    match &args.action {
+        CiAction::RefreshJob { provider, cron, output } => {
+            // Step 2: Collect the stores to refresh
+            debug!("➡️ Step 2: Collecting stores from refs/trunk/* and the config");
+            let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+            let mut stores: Vec<String> = repo.ref_names("refs/trunk/").unwrap_or_default();
+            for config_store in trunk_config.store_names() {
+                if !stores.contains(&config_store) {
+                    stores.push(config_store);
+                }
+            }
+            stores.retain(|name| config::is_store_name(name));
+            if stores.is_empty() {
+                stores.push(store_name.to_string());
+            }
+            info!("✓ Step 2: Refreshing {} store(s): {}", stores.len(), stores.join(", "));
+
+            // Step 3: Render the pipeline
+            if cron.split_whitespace().count() != 5 {
+                return Err(TrunkError::Usage(format!("'{}' is not a cron expression with five fields", cron)));
+            }
+            let pipeline = match provider {
+                Provider::Github => render_github(&stores, remote_name, cron),
+                Provider::Gitlab => render_gitlab(&stores, remote_name, cron),
+            };
+            let Some(output) = output else {
+                print!("{}", pipeline);
+                return Ok(());
+            };
+            let output_path = repo_root.join(PathBuf::from(output));
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| TrunkError::Io(format!("Failed to create {}: {}", parent.display(), e)))?;
+            }
+            fs::write(&output_path, pipeline).map_err(|e| TrunkError::Io(format!("Failed to write {}: {}", output_path.display(), e)))?;
+            info!("✓ Step 3: Wrote {}", output_path.display());
+            info!("✅ Commit {} to the main repository to schedule the refresh", output);
+        }
+    }
+    Ok(())
+}
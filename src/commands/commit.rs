@@ -1,128 +1,233 @@
-use std::io::{self, Write};
-use std::path::Path;
-use std::process::{Command, exit};
 use clap::Parser;
 use log::{debug, error, info};
-use crate::utils::run_git_command;
+use crate::config::{self, StagePolicy};
+use crate::locks;
+use crate::owners;
+use crate::quota;
+use crate::commands::{index_md, lint_prose, push};
+use crate::ops;
+use crate::table::{Cell, Table};
+use crate::utils::{confirm, dry_run, is_dry_run, run_git_change, short_hash, tracked_trunk_paths, untrack_trunk_paths, object_format};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
 
-#[derive(Parser, Debug)]
-#[command(about = "Commit changes from .trunk/<store> to the main repository's refs/trunk/<store>")]
+#[derive(Parser, Debug, Default)]
+#[command(about = "Commit changes from .trunk/<store> to the main repository's refs/trunk/<store>", after_help = "Examples:\n  git trunk commit\n  git trunk commit --force -m \"Update roadmap\"\n  git trunk commit --store blog\n  git trunk commit --all --force")]
 pub struct CommitArgs {
-    #[arg(long, help = "Skip interactive prompts and stage all changes")]
-    force: bool,
-    #[arg(short = 'm', long, help = "Commit message")]
-    message: Option<String>,
+    #[arg(long, help = "Skip interactive prompts and stage all changes (only tracked files with stage = \"tracked\")")]
+    pub force: bool,
+    #[arg(short = 'm', long, help = "Commit message [default: \"Commit trunk changes for store '<store>'\"]")]
+    pub message: Option<String>,
+    #[arg(long, help = "Commit every store checked out under .trunk and summarize the results")]
+    pub all: bool,
+    #[arg(long, requires = "all", help = "With --all, print one \"Field: value\" line per fact instead of a table")]
+    pub no_table: bool,
 }
 
-pub fn run(args: &CommitArgs, _remote_name: &str, store_name: &str, verbose: bool) {
+pub fn run(args: &CommitArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
     // Step 1: Get repository root
-    debug!("➡️ Step 1: Getting repository root");
-    let repo_root_output = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--show-toplevel"),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to get git repository root: {}", e);
-        exit(1);
-    });
-    let repo_root = String::from_utf8_lossy(&repo_root_output.stdout).trim().to_string();
-    if repo_root.is_empty() {
-        error!("❌ Git repository root is empty. Ensure you are in a valid Git repository.");
-        exit(1);
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    // Step 1b: Make sure .trunk is not tracked by the main repository
+    debug!("➡️ Step 1b: Checking the main repository index for .trunk paths");
+    match tracked_trunk_paths(repo_root, verbose) {
+        Ok(paths) if !paths.is_empty() => {
+            error!("⚠️ Warning: .trunk is tracked by the main repository ({} path(s)). Store contents will leak into code branches.", paths.len());
+            if args.force {
+                info!("= Step 1b: Run `git trunk doctor` to untrack .trunk and repair .gitignore");
+            } else {
+                if confirm("Untrack .trunk (git rm --cached -r .trunk) and repair .gitignore?")? {
+                    if let Err(e) = untrack_trunk_paths(repo_root, "Step 1b", verbose) {
+                        return Err(TrunkError::Failed(format!("Failed to untrack .trunk: {}", e)));
+                    }
+                } else {
+                    info!("= Step 1b: Left .trunk tracked");
+                }
+            }
+        }
+        Ok(_) => debug!("= Step 1b: No .trunk paths tracked"),
+        Err(e) => debug!("⚠️ Step 1b: Could not check the main repository index: {}", e),
+    }
+    if !args.all {
+        return commit_store(&repo, args, remote_name, store_name, verbose);
+    }
+
+    // Like `push --all`, every store auto-pushes to its own configured remote
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let stores = ops::checked_out_stores(repo_root, verbose);
+    if stores.is_empty() {
+        info!("= No stores checked out under .trunk to commit");
+        return Ok(());
+    }
+    let mut table = Table::new(&["Store", "Ref", "Result"]);
+    let mut failed = 0;
+    for store in &stores {
+        let trunk_ref_name = format!("refs/trunk/{}", store);
+        let before = repo.resolve_commit(&trunk_ref_name);
+        info!("➡️ Committing store '{}'", store);
+        let result = commit_store(&repo, args, &trunk_config.remote_for_store(store), store, verbose);
+        let after = repo.resolve_commit(&trunk_ref_name);
+        let cell = match result {
+            Err(e) => {
+                error!("❌ Store '{}': {}", store, e);
+                failed += 1;
+                Cell::flag(false, &e.to_string())
+            }
+            Ok(()) if is_dry_run() => Cell::from("= dry run"),
+            Ok(()) => match after.filter(|after| Some(after) != before.as_ref()) {
+                Some(after) => Cell::flag(true, &format!("committed {}", short_hash(repo_root, &after, verbose))),
+                None => Cell::from("= unchanged"),
+            },
+        };
+        table.add_row(vec![store.as_str().into(), trunk_ref_name.into(), cell]);
+    }
+    println!();
+    table.print(args.no_table);
+    if failed > 0 {
+        return Err(TrunkError::Failed(format!("{} of {} store(s) failed to commit", failed, stores.len())));
     }
-    info!("✓ Step 1: Repository root found at {}", repo_root);
+    info!("✅ Committed all {} store(s)", stores.len());
+    Ok(())
+}
 
+fn commit_store(repo: &GitRepo, args: &CommitArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let repo_root = repo.root();
     let store_dir_path_str = format!(".trunk/{}", store_name);
-    let trunk_store_dir = Path::new(&repo_root).join(&store_dir_path_str);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
     let trunk_ref_name = format!("refs/trunk/{}", store_name);
 
     // Step 2: Check if .trunk/<store_name> exists
     debug!("➡️ Step 2: Checking for {} directory", store_dir_path_str);
     if !trunk_store_dir.exists() {
-        error!("❌ {} directory not found for store '{}'. Run `git trunk init --store {}` first.", store_dir_path_str, store_name, store_name);
-        exit(1);
+        return Err(TrunkError::NotFound(format!("{} directory not found for store '{}'. Run `git trunk init --store {}` first.", store_dir_path_str, store_name, store_name)));
     }
     info!("✓ Step 2: {} directory found", store_dir_path_str);
 
+    // Step 2b: The store repository must use the main repository's object format
+    let main_format = object_format(repo_root, verbose);
+    let store_format = object_format(&trunk_store_dir, verbose);
+    if main_format != store_format {
+        return Err(TrunkError::Conflict(format!("{} uses {} object names but the main repository uses {}. Commit its work elsewhere, remove it and run `git trunk checkout --store {}` to recreate it.", store_dir_path_str, store_format, main_format, store_name)));
+    }
+    debug!("✓ Step 2b: Store repository uses {} like the main repository", main_format);
+    let store_repo = GitRepo::open(&trunk_store_dir, verbose).map_err(|e| TrunkError::Git(format!("Failed to open {}: {}", store_dir_path_str, e)))?;
+
     // Step 3: Check if .trunk/<store_name> has files to be staged
     debug!("➡️ Step 3: Checking for changes in {}", store_dir_path_str);
-    let status_output = run_git_command(
-        Command::new("git")
-            .arg("status")
-            .arg("--porcelain")
-            .current_dir(&trunk_store_dir),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to run git status in {}: {}", store_dir_path_str, e);
-        exit(1);
-    });
-
-    let status = String::from_utf8_lossy(&status_output.stdout);
+    let status = store_repo
+        .status_porcelain()
+        .map_err(|e| TrunkError::Git(format!("Failed to run git status in {}: {}", store_dir_path_str, e)))?
+        .join("\n");
     if status.is_empty() {
         info!("= Step 3: No changes to stage in {}", store_dir_path_str);
     } else {
-        // Step 4: Ask user to stage all files (unless --force)
-        let should_stage = if args.force {
-            debug!("🚀 Step 4: --force specified, staging all changes in {}", store_dir_path_str);
+        // Step 3b: Prose-lint changed files when enabled for the store
+        let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+        if trunk_config.get_bool(&format!("store.{}", store_name), "lint_prose") == Some(true) {
+            debug!("➡️ Step 3b: Prose-linting changed files in {}", store_dir_path_str);
+            let dictionary = lint_prose::load_dictionary(&trunk_config, store_name, &trunk_store_dir);
+            let changed = lint_prose::changed_prose_files(&trunk_store_dir, verbose);
+            let issues = lint_prose::lint_files(&trunk_store_dir, &changed, &dictionary, false, false);
+            if issues.is_empty() {
+                info!("✓ Step 3b: No prose issues in {} changed file(s)", changed.len());
+            } else {
+                lint_prose::print_issues(&issues);
+                if args.force {
+                    error!("⚠️ Warning: {} prose issue(s) in store '{}'; run `git trunk lint-prose --store {} --fix` to correct the simple ones", issues.len(), store_name, store_name);
+                } else {
+                    if !confirm(&format!("{} prose issue(s) found. Commit anyway?", issues.len()))? {
+                        info!("🚫 Step 3b: Commit for store '{}' aborted; run `git trunk lint-prose --store {} --fix`", store_name, store_name);
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        // Step 3c: Regenerate the store index when configured
+        if let Some(index_file) = index_md::configured_index(&trunk_config, store_name) {
+            debug!("➡️ Step 3c: Regenerating {}/{}", store_dir_path_str, index_file);
+            if dry_run(&format!("regenerate {}/{}", store_dir_path_str, index_file)) {
+                debug!("= Step 3c: Skipped under --dry-run");
+            } else {
+                match index_md::regenerate(&trunk_store_dir, store_name, &index_file, verbose) {
+                    Ok(true) => info!("✓ Step 3c: Updated {}/{}", store_dir_path_str, index_file),
+                    Ok(false) => info!("= Step 3c: {}/{} is up to date", store_dir_path_str, index_file),
+                    Err(e) => error!("⚠️ Warning: Could not regenerate {}/{}: {}", store_dir_path_str, index_file, e),
+                }
+            }
+        }
+
+        // Step 3d: Check the store's size quota
+        if quota::store_quota(&trunk_config, store_name).map_err(TrunkError::Config)?.is_some() {
+            match quota::pending_size(&trunk_store_dir, verbose) {
+                Ok(pending) => quota::enforce(repo, store_name, pending, "Step 3d", verbose)?,
+                Err(e) => error!("⚠️ Warning: Could not compute the size of the changes in {}: {}", store_dir_path_str, e),
+            }
+        }
+
+        // Step 3e: Point out changes to files someone else locked, as of the
+        // last `lock`, `locks`, `fetch` or `pull`
+        let me = locks::identity(repo, verbose).ok();
+        let changed_paths: Vec<&str> = status.lines().filter_map(|line| line.get(3..)).map(|path| path.rsplit(" -> ").next().unwrap_or(path)).collect();
+        for lock in locks::read(repo, store_name, verbose) {
+            if changed_paths.contains(&lock.path.as_str()) && me.as_ref().is_none_or(|me| me.email != lock.email) {
+                error!("⚠️ Warning: {} is locked by {} since {}; check with them before committing your changes", lock.path, lock.owner(), lock.since.format("%Y-%m-%d %H:%M"));
+            }
+        }
+
+        // Step 3f: Point out changes to paths others own, by the TRUNKOWNERS
+        // of refs/trunk/<store>
+        let rules = owners::read(repo, store_name, verbose);
+        for path in &changed_paths {
+            let Some(rule) = owners::rule_for(&rules, path).filter(|rule| !rule.owners.is_empty()) else { continue };
+            if me.as_ref().is_none_or(|me| !rule.is_owner(me)) {
+                error!("⚠️ Warning: {} is owned by {} ({} line {}); ask them to review your changes", path.trim_end_matches('/'), rule.owners.join(", "), owners::OWNERS_FILE, rule.line);
+            }
+        }
+
+        // Step 4: Stage the changes the store's stage policy allows, asking
+        // first unless --force or the policy says not to
+        let stage_policy = trunk_config.stage_policy(store_name);
+        let should_stage = if stage_policy == StagePolicy::Tracked {
+            let untracked: Vec<&str> = status.lines().filter_map(|line| line.strip_prefix("?? ")).collect();
+            if untracked.is_empty() {
+                debug!("🚀 Step 4: stage = \"tracked\", staging changes to tracked files in {}", store_dir_path_str);
+            } else {
+                info!("➖ Step 4: stage = \"tracked\", leaving {} untracked path(s) out: {}", untracked.len(), untracked.join(", "));
+            }
+            true
+        } else if args.force || stage_policy == StagePolicy::All {
+            debug!("🚀 Step 4: {}, staging all changes in {}", if args.force { "--force specified" } else { "stage = \"all\"" }, store_dir_path_str);
             true
         } else {
             info!("≠ Step 4: Changes detected in {}:\n{}", store_dir_path_str, status);
-            print!("🐘︖ Stage all files for store '{}'? [y/N]: ", store_name);
-            io::stdout().flush().expect("Failed to flush stdout");
-
-            let mut input = String::new();
-            io::stdin()
-                .read_line(&mut input)
-                .expect("Failed to read user input");
-            let input = input.trim().to_lowercase();
-            if input == "y" || input == "yes" {
+            if confirm(&format!("Stage all files for store '{}'?", store_name))? {
                 debug!("👍 Step 4: User confirmed staging for store '{}'", store_name);
                 true
             } else {
                 info!("🚫 Step 4: Commit for store '{}' aborted by user", store_name);
-                exit(0);
+                return Ok(());
             }
         };
 
         if should_stage {
-            // Stage all files
-            debug!("➕ Step 4: Staging all files in {}", store_dir_path_str);
-            let stage_status = run_git_command(
-                Command::new("git")
-                    .arg("add")
-                    .arg("-A")
-                    .current_dir(&trunk_store_dir),
-                verbose,
-            )
-            .unwrap_or_else(|e| {
-                error!("❌ Failed to run git add in {}: {}", store_dir_path_str, e);
-                exit(1);
-            })
+            // `add -u` stages modifications and deletions of tracked files only
+            let add_mode = if stage_policy == StagePolicy::Tracked { "-u" } else { "-A" };
+            debug!("➕ Step 4: Staging files in {} (git add {})", store_dir_path_str, add_mode);
+            let stage_status = run_git_change(store_repo.git().arg("add").arg(add_mode), verbose)
+            .map_err(|e| TrunkError::Git(format!("Failed to run git add in {}: {}", store_dir_path_str, e)))?
             .status;
             if !stage_status.success() {
-                error!("❌ git add failed in {}", store_dir_path_str);
-                exit(1);
+                return Err(TrunkError::Git(format!("git add failed in {}", store_dir_path_str)));
             }
             info!("✓ Step 4: Files staged in {}", store_dir_path_str);
 
             // Step 5: Commit staged files
             debug!("💾 Step 5: Committing staged changes for store '{}'", store_name);
             let commit_message = args.message.clone().unwrap_or_else(|| format!("Commit trunk changes for store '{}'", store_name));
-            let commit_status = run_git_command(
-                Command::new("git")
-                    .arg("commit")
-                    .arg("-m")
-                    .arg(&commit_message)
-                    .current_dir(&trunk_store_dir),
-                verbose,
-            )
-            .unwrap_or_else(|e| {
-                error!("❌ Failed to run git commit in {}: {}", store_dir_path_str, e);
-                exit(1);
-            })
+            let commit_status = run_git_change(store_repo.git().arg("commit").arg("-m").arg(&commit_message), verbose)
+            .map_err(|e| TrunkError::Git(format!("Failed to run git commit in {}: {}", store_dir_path_str, e)))?
             .status;
 
             if !commit_status.success() {
@@ -135,102 +240,33 @@ pub fn run(args: &CommitArgs, _remote_name: &str, store_name: &str, verbose: boo
         }
     }
 
-    // Step 6: Get the latest commit hash from .trunk/<store_name>
-    debug!("🔑 Step 6: Getting latest commit hash from {}'s main branch", store_dir_path_str);
-    let commit_hash_output = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("main") // Assumes 'main' is the branch in the store's repo
-            .current_dir(&trunk_store_dir),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to get {} main commit hash: {}", store_dir_path_str, e);
-        exit(1);
-    });
-    let commit_hash = String::from_utf8_lossy(&commit_hash_output.stdout).trim().to_string();
-    if commit_hash.is_empty() {
-        error!("❌ Failed to get commit hash from {}. It might be empty or not have commits on 'main'.", store_dir_path_str);
-        exit(1);
-    }
-    debug!("🔑 Step 6: Commit hash for store '{}': {}", store_name, commit_hash);
-
-    // Step 7: Fetch objects from .trunk/<store_name> to main repo
-    let temp_branch_name = format!("trunk-temp-{}", store_name);
-    debug!("📥 Step 7: Fetching objects from {} into temporary branch '{}' in main repository", store_dir_path_str, temp_branch_name);
-    let fetch_status = run_git_command(
-        Command::new("git")
-            .arg("-C")
-            .arg(&repo_root)
-            .arg("fetch")
-            .arg(&trunk_store_dir)
-            .arg(format!("main:{}", temp_branch_name)), // Fetch main from store repo to temp branch
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to fetch objects from {}: {}", store_dir_path_str, e);
-        exit(1);
-    })
-    .status;
-    if !fetch_status.success() {
-        error!("❌ git fetch failed from {}", store_dir_path_str);
-        exit(1);
-    }
-    info!("✓ Step 7: Objects fetched from store '{}'", store_name);
-
-    // Step 8: Update refs/trunk/<store_name>
-    debug!("➡️ Step 8: Checking if {} exists", trunk_ref_name);
-    let ref_exists = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--verify")
-            .arg(&trunk_ref_name)
-            .current_dir(&repo_root),
-        verbose,
-    )
-    .map(|output| output.status.success())
-    .unwrap_or(false);
-
-    debug!("🔄 Step 8: Updating {} to commit {}", trunk_ref_name, commit_hash);
-    let update_ref_status = run_git_command(
-        Command::new("git")
-            .arg("update-ref")
-            .arg(&trunk_ref_name)
-            .arg(&commit_hash)
-            .current_dir(&repo_root),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to update {}: {}", trunk_ref_name, e);
-        exit(1);
-    })
-    .status;
-    if !update_ref_status.success() {
-        error!("❌ git update-ref failed for {}", trunk_ref_name);
-        exit(1);
-    }
-
-    // Step 9: Clean up temporary branch
-    debug!("🧹 Step 9: Cleaning up temporary branch {}", temp_branch_name);
-    let cleanup_status = run_git_command(
-        Command::new("git")
-            .arg("branch")
-            .arg("-D")
-            .arg(&temp_branch_name)
-            .current_dir(&repo_root),
-        verbose,
-    );
-    // Log warning if cleanup fails, but don't exit
-    if cleanup_status.is_err() || (cleanup_status.is_ok() && !cleanup_status.as_ref().unwrap().status.success()){
-        error!("⚠️ Warning: Failed to delete temporary branch {}. You may need to delete it manually: git branch -D {}", temp_branch_name, temp_branch_name);
+    if is_dry_run() && !status.is_empty() {
+        // The commit above was not made, so there is no hash to publish yet
+        dry_run(&format!("fetch main from {} and point {} at the new commit", store_dir_path_str, trunk_ref_name));
+        return Ok(());
     }
 
-
-    if ref_exists {
-        info!("✓ Step 8 & 9: Updated {} to commit {}", trunk_ref_name, commit_hash);
+    // Step 6: Point refs/trunk/<store_name> at the main branch of .trunk/<store_name>
+    debug!("🔄 Step 6: Updating {} from {}'s main branch", trunk_ref_name, store_dir_path_str);
+    let (previous_hash, commit_hash) = ops::update_store_ref(repo, &store_repo, store_name, verbose)?;
+    let ref_moved = previous_hash.as_deref() != Some(commit_hash.as_str());
+    if previous_hash.is_some() {
+        info!("✓ Step 6: Updated {} to commit {}", trunk_ref_name, commit_hash);
     } else {
-        info!("✓ Step 8 & 9: Created {} at commit {}", trunk_ref_name, commit_hash);
+        info!("✓ Step 6: Created {} at commit {}", trunk_ref_name, commit_hash);
     }
 
     info!("✅ Trunk store '{}' committed successfully to {}", store_name, trunk_ref_name);
+
+    // Step 7: Push the new commit when auto_push is configured
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    if trunk_config.auto_push(store_name) {
+        if !ref_moved {
+            info!("= Step 7: {} did not move, nothing to auto-push", trunk_ref_name);
+        } else {
+            debug!("📤 Step 7: auto_push is set, pushing store '{}' to '{}'", store_name, remote_name);
+            push::run(&push::PushArgs::default(), remote_name, store_name, verbose)?;
+        }
+    }
+    Ok(())
 }
\ No newline at end of file
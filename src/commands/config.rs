@@ -0,0 +1,181 @@
+use std::path::Path;
+use clap::{Parser, Subcommand};
+use log::{debug, error, info};
+use crate::config::{self, ConfigValue, TrunkConfig};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Read, change and validate the git-trunk config file (.git/trunk.toml, or .trunk/config.toml without it)", after_help = "With --user the commands act on the user config in $GIT_TRUNK_HOME (default ~/.config/git-trunk), whose keys apply in every repository that does not set them.\n\nExamples:\n  git trunk config list\n  git trunk config set trunk.store docs\n  git trunk config set --user trunk.prompt yes\n  git trunk config set --user alias.ci \"commit --all -m\"\n  git trunk config set store.docs.remote upstream\n  git trunk config set trunk.auto_push true\n  git trunk config get trunk.prompt\n  git trunk config unset store.docs.remote\n  git trunk config set remote.gerrit.review_ref 'refs/for/refs/trunk/{store}'\n  git trunk config validate")]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    action: ConfigAction,
+    #[arg(long, global = true, help = "Use the user config in the git-trunk home instead of .git/trunk.toml")]
+    user: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// Prints the value of a key such as trunk.remote or store.docs.remote, falling back to the user config
+    Get {
+        #[arg(help = "Key as <section>.<key>, e.g. trunk.store or store.docs.remote")]
+        key: String,
+    },
+    /// Sets a key, creating the file and section when needed
+    Set {
+        #[arg(help = "Key as <section>.<key>, e.g. trunk.store or store.docs.remote")]
+        key: String,
+        #[arg(allow_hyphen_values = true, help = "Value; strings need no quotes and arrays are comma-separated (post-commit,pre-push)")]
+        value: String,
+    },
+    /// Removes a key
+    Unset {
+        #[arg(help = "Key as <section>.<key>, e.g. trunk.store or store.docs.remote")]
+        key: String,
+    },
+    /// Prints every key set in the config file
+    List,
+    /// Checks the config file against the known sections and keys
+    Validate,
+}
+
+impl ConfigArgs {
+    pub fn writes_config(&self) -> bool {
+        !self.user && matches!(self.action, ConfigAction::Set { .. } | ConfigAction::Unset { .. })
+    }
+}
+
+// Splits `store.docs.remote` into the section `store.docs` and the key `remote`.
+fn split_key(full_key: &str) -> Result<(&str, &str), TrunkError> {
+    let usage = || TrunkError::Usage(format!("'{}' is not a config key; use <section>.<key>, e.g. trunk.store or store.<name>.remote", full_key));
+    let (section, key) = full_key.rsplit_once('.').ok_or_else(usage)?;
+    let valid_section = ["trunk", "user", "alias"].contains(&section)
+        || section.strip_prefix("store.").is_some_and(config::is_store_name)
+        || section.strip_prefix("remote.").is_some_and(|name| !name.is_empty());
+    if !valid_section || key.is_empty() {
+        return Err(usage());
+    }
+    config::key_kind(section, key).map_err(TrunkError::Usage)?;
+    Ok((section, key))
+}
+
+pub fn run(args: &ConfigArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root, unless the user config is asked for
+    let repo = if args.user {
+        None
+    } else {
+        debug!("➡️ Step 1: Getting repository root");
+        let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+        info!("✓ Step 1: Repository root found at {}", repo.root().display());
+        Some(repo)
+    };
+    let repo_root = repo.as_ref().map(|repo| repo.root());
+
+    match &args.action {
+        ConfigAction::Get { key } => get(repo_root, key, verbose),
+        ConfigAction::Set { key, value } => set(repo_root, key, Some(value), verbose),
+        ConfigAction::Unset { key } => set(repo_root, key, None, verbose),
+        ConfigAction::List => list(repo_root, verbose),
+        ConfigAction::Validate => validate(repo_root, verbose),
+    }
+}
+
+// The repository's config, or the user config without a repository root.
+fn load(repo_root: Option<&Path>, verbose: bool) -> Result<TrunkConfig, TrunkError> {
+    let Some(repo_root) = repo_root else {
+        let user_config = config::load_user_unvalidated().map_err(|e| TrunkError::Config(e.to_string()))?;
+        if user_config.path.as_os_str().is_empty() {
+            return Err(TrunkError::Config(format!("No git-trunk home: set {} or HOME", config::HOME_ENV)));
+        }
+        return Ok(user_config);
+    };
+    config::load_unvalidated(repo_root, verbose).map_err(|e| TrunkError::Config(e.to_string()))
+}
+
+fn get(repo_root: Option<&Path>, full_key: &str, verbose: bool) -> Result<(), TrunkError> {
+    let (section, key) = split_key(full_key)?;
+    let trunk_config = load(repo_root, verbose)?;
+    match trunk_config.get(section, key).map(|entry| &entry.value) {
+        // Print strings bare so scripts can use the output as is
+        Some(ConfigValue::String(value)) => println!("{}", value),
+        Some(value) => println!("{}", value),
+        None => return Err(TrunkError::NotFound(format!("{} is not set in {}", full_key, trunk_config.path.display()))),
+    }
+    Ok(())
+}
+
+fn set(repo_root: Option<&Path>, full_key: &str, raw_value: Option<&String>, verbose: bool) -> Result<(), TrunkError> {
+    // Step 2: Check the key and value against the schema
+    debug!("➡️ Step 2: Checking {}", full_key);
+    let (section, key) = split_key(full_key)?;
+    config::check_scope(section, key, repo_root.is_none()).map_err(TrunkError::Usage)?;
+    let value = match raw_value {
+        Some(raw) => {
+            let kind = config::key_kind(section, key).map_err(TrunkError::Usage)?;
+            let value = config::parse_cli_value(kind, raw).map_err(|e| TrunkError::Usage(format!("Invalid value for {}: {}", full_key, e)))?;
+            config::check_entry(section, key, &value).map_err(TrunkError::Usage)?;
+            Some(value)
+        }
+        None => None,
+    };
+    info!("✓ Step 2: {} is a known key", full_key);
+
+    // Step 3: Rewrite the key in place, leaving the rest of the file untouched
+    let trunk_config = load(repo_root, verbose)?;
+    let current = trunk_config.get_local(section, key).map(|entry| entry.value.clone());
+    if current == value {
+        info!("= Step 3: {} is already {}", full_key, value.map(|v| v.to_string()).unwrap_or_else(|| "unset".to_string()));
+        return Ok(());
+    }
+    debug!("📝 Step 3: Writing {} to {}", full_key, trunk_config.path.display());
+    config::set_value(&trunk_config, section, key, value.as_ref()).map_err(|e| TrunkError::Io(format!("Failed to update config: {}", e)))?;
+    match &value {
+        Some(value) => info!("✅ Set [{}] {} = {}", section, key, value),
+        None => info!("✅ Removed [{}] {}", section, key),
+    }
+    Ok(())
+}
+
+fn list(repo_root: Option<&Path>, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_config = load(repo_root, verbose)?;
+    if trunk_config.entries.is_empty() {
+        info!("= No keys set in {}", trunk_config.path.display());
+        return Ok(());
+    }
+    for entry in &trunk_config.entries {
+        println!("{}.{} = {}", entry.section, entry.key, entry.value);
+    }
+    Ok(())
+}
+
+fn validate(repo_root: Option<&Path>, verbose: bool) -> Result<(), TrunkError> {
+    // Step 2: Parse the config file
+    debug!("➡️ Step 2: Parsing config file");
+    let trunk_config = load(repo_root, verbose)?;
+    if !trunk_config.exists {
+        info!("= Step 2: No config file at {}, nothing to validate", trunk_config.path.display());
+        return Ok(());
+    }
+    info!("✓ Step 2: Parsed {} ({} section(s), {} key(s))", trunk_config.path.display(), trunk_config.sections.len(), trunk_config.entries.len());
+    if let Some(repo_root) = repo_root {
+        let trunk_dir_config = config::trunk_dir_config_path(repo_root);
+        if trunk_dir_config.is_file() && trunk_dir_config != trunk_config.path {
+            error!("⚠️ Warning: {} is ignored while {} exists; merge it into that file and remove it", trunk_dir_config.display(), trunk_config.path.display());
+        }
+    }
+
+    // Step 3: Check against the schema
+    debug!("➡️ Step 3: Validating sections and keys");
+    for entry in &trunk_config.entries {
+        debug!("🔍 Step 3: line {}: [{}] {} = {}", entry.line, entry.section, entry.key, entry.value);
+    }
+    let problems = config::validate(&trunk_config);
+    if !problems.is_empty() {
+        for problem in &problems {
+            error!("❌ {}", problem);
+        }
+        return Err(TrunkError::Config(format!("Config has {} problem(s)", problems.len())));
+    }
+    info!("✅ Config {} is valid", trunk_config.path.display());
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use clap::Parser;
+use log::{debug, info};
+use crate::ops;
+use crate::table::{Cell, Color, Table};
+use crate::json::Json;
+use crate::utils::short_hash;
+use crate::error::TrunkError;
+use crate::git::UnmergedPath;
+
+#[derive(Parser, Debug)]
+#[command(about = "List the unresolved merge conflicts in .trunk/<store>", after_help = "Examples:\n  git trunk conflicts\n  git trunk conflicts --store blog --json\n  git trunk conflicts --json || echo \"store has conflicts\"")]
+pub struct ConflictsArgs {
+    #[arg(long, help = "Output the conflicts as a JSON document instead of a table")]
+    json: bool,
+    #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+// Operations that leave conflicts behind, keyed by the pseudo-ref naming the
+// commit being brought in ("theirs").
+const OPERATION_HEADS: [(&str, &str); 4] = [
+    ("MERGE_HEAD", "merge"),
+    ("CHERRY_PICK_HEAD", "cherry-pick"),
+    ("REVERT_HEAD", "revert"),
+    ("REBASE_HEAD", "rebase"),
+];
+
+// The kind of conflict, named after `git status`: which sides still have the path.
+fn conflict_type(unmerged: &UnmergedPath) -> &'static str {
+    match (unmerged.base.is_some(), unmerged.ours.is_some(), unmerged.theirs.is_some()) {
+        (true, true, true) => "both_modified",
+        (false, true, true) => "both_added",
+        (true, false, true) => "deleted_by_us",
+        (true, true, false) => "deleted_by_them",
+        (false, true, false) => "added_by_us",
+        (false, false, true) => "added_by_them",
+        (true, false, false) => "both_deleted",
+        (false, false, false) => "unknown",
+    }
+}
+
+fn optional(value: Option<&str>) -> Json {
+    value.map(Json::from).unwrap_or(Json::Null)
+}
+
+pub fn run(args: &ConflictsArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+
+    // Step 2: Open the store checkout
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let store_repo = ops::ensure_store_checkout(repo.root(), store_name, "Step 2", verbose)?;
+    let trunk_store_dir = store_repo.root().to_path_buf();
+
+    // Step 3: Read the unmerged index entries and the operation that left them
+    debug!("➡️ Step 3: Reading the index of {}", store_dir_relative_path);
+    let unmerged = store_repo.unmerged_paths().map_err(|e| TrunkError::Git(format!("Failed to read the index of {}: {}", store_dir_relative_path, e)))?;
+    let ours = store_repo.resolve_commit("HEAD");
+    let operation = OPERATION_HEADS.iter().find_map(|(head, operation)| store_repo.resolve_commit(head).map(|theirs| (*operation, theirs)));
+    debug!("✓ Step 3: {} unmerged path(s), operation {:?}", unmerged.len(), operation);
+
+    if args.json {
+        let conflicts = unmerged.iter().map(|entry| Json::Object(vec![
+            ("path".to_string(), Json::from(entry.path.as_str())),
+            ("type".to_string(), Json::from(conflict_type(entry))),
+            ("base".to_string(), optional(entry.base.as_deref())),
+            ("ours".to_string(), optional(entry.ours.as_deref())),
+            ("theirs".to_string(), optional(entry.theirs.as_deref())),
+        ])).collect();
+        println!("{}", Json::Object(vec![
+            ("store".to_string(), Json::from(store_name)),
+            ("path".to_string(), Json::from(store_dir_relative_path.as_str())),
+            // null when nothing is in progress, e.g. after a conflicted `git stash pop`
+            ("operation".to_string(), optional(operation.as_ref().map(|(operation, _)| *operation))),
+            ("ours".to_string(), optional(ours.as_deref())),
+            ("theirs".to_string(), optional(operation.as_ref().map(|(_, theirs)| theirs.as_str()))),
+            ("conflicts".to_string(), Json::Array(conflicts)),
+        ]));
+    } else if !unmerged.is_empty() {
+        let short = |hash: &Option<String>| hash.as_deref().map(|hash| short_hash(&trunk_store_dir, hash, verbose)).unwrap_or_else(|| "-".to_string());
+        match &operation {
+            Some((operation, theirs)) => info!("≠ {} in {}: ours {}, theirs {}", operation, store_dir_relative_path, short(&ours), short(&Some(theirs.clone()))),
+            None => info!("≠ Conflicts in {} (no merge, cherry-pick, revert or rebase in progress)", store_dir_relative_path),
+        }
+        let mut table = Table::new(&["Path", "Conflict", "Base", "Ours", "Theirs"]);
+        for entry in &unmerged {
+            table.add_row(vec![
+                entry.path.as_str().into(),
+                Cell::colored(conflict_type(entry), Color::Yellow),
+                short(&entry.base).into(),
+                short(&entry.ours).into(),
+                short(&entry.theirs).into(),
+            ]);
+        }
+        table.print(args.no_table);
+    }
+
+    if unmerged.is_empty() {
+        info!("✅ No conflicts in {}", store_dir_relative_path);
+        return Ok(());
+    }
+    Err(TrunkError::Conflict(format!("{} conflicted path(s) in {}. Resolve them there (git add / git commit), then run `git trunk commit --store {}`.", unmerged.len(), store_dir_relative_path, store_name)))
+}
@@ -0,0 +1,81 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config;
+use crate::ops;
+use crate::quota;
+use crate::utils::{ensure_trunk_in_gitignore, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Create a store starting from another store's current commit, sharing its history",
+    after_help = "The copy starts at refs/trunk/<src>: uncommitted changes in .trunk/<src> and [store.<src>] settings are not copied. Publish it with `git trunk push --store <dst>`.\n\nExamples:\n  git trunk copy docs-template docs-payments\n  git trunk copy handbook handbook-2025 --no-checkout"
+)]
+pub struct CopyArgs {
+    #[arg(value_name = "SRC", help = "Store to copy")]
+    src: String,
+    #[arg(value_name = "DST", help = "Name of the new store")]
+    dst: String,
+    #[arg(long, help = "Only create refs/trunk/<dst>, without .trunk/<dst>")]
+    no_checkout: bool,
+}
+
+impl CopyArgs {
+    pub fn checks_out(&self) -> bool {
+        !self.no_checkout
+    }
+}
+
+pub fn run(args: &CopyArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let (src, dst) = (args.src.as_str(), args.dst.as_str());
+    let src_ref = format!("refs/trunk/{}", src);
+    let dst_ref = format!("refs/trunk/{}", dst);
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+    let trunk_base_dir = config::trunk_base_dir(repo_root, verbose);
+
+    // Step 2: Check both names before creating anything
+    debug!("➡️ Step 2: Checking that store '{}' can be copied to '{}'", src, dst);
+    config::check_store_name(dst).map_err(|reason| TrunkError::Usage(format!("Invalid store name '{}': {}", dst, reason)))?;
+    if src == dst {
+        return Err(TrunkError::Usage(format!("Store '{}' cannot be copied onto itself", src)));
+    }
+    let Some(tip) = repo.resolve_commit(&src_ref) else {
+        return Err(TrunkError::NotFound(format!(
+            "{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.",
+            src_ref, src, src
+        )));
+    };
+    if repo.ref_exists(&dst_ref) || trunk_base_dir.join(dst).exists() {
+        return Err(TrunkError::Conflict(format!("Store '{}' already exists here ({} or .trunk/{}); pick another name", dst, dst_ref, dst)));
+    }
+    // Only what refs/trunk/<src> holds is copied
+    if let Ok(src_repo) = GitRepo::open(&trunk_base_dir.join(src), verbose) {
+        if src_repo.status_porcelain().is_ok_and(|changes| !changes.is_empty()) {
+            error!("⚠️ Warning: .trunk/{} has uncommitted changes; they are not copied. Run `git trunk commit --store {}` first to include them.", src, src);
+        }
+    }
+    info!("✓ Step 2: Copying store '{}' at {}", src, short_hash(repo_root, &tip, verbose));
+
+    // Step 3: Start the new store's ref at the source's commit
+    debug!("➡️ Step 3: Creating {} at {}", dst_ref, src_ref);
+    quota::enforce(&repo, dst, quota::reachable_size(repo_root, &tip, verbose).unwrap_or(0), "Step 3", verbose)?;
+    repo.update_ref(&dst_ref, &tip).map_err(|e| TrunkError::Git(format!("Failed to create {}: {}", dst_ref, e)))?;
+    info!("✓ Step 3: Created {} at {}", dst_ref, short_hash(repo_root, &tip, verbose));
+
+    // Steps 4-15: Check it out into .trunk/<dst>
+    if args.no_checkout {
+        info!("= Step 4: --no-checkout, not creating .trunk/{}", dst);
+    } else {
+        debug!("➡️ Step 4: Preparing .trunk for store '{}'", dst);
+        ensure_trunk_in_gitignore(repo_root, "Step 4").map_err(|e| TrunkError::Io(format!("Failed to update .gitignore: {}", e)))?;
+        ops::ensure_trunk_dir(repo_root, "Step 4", verbose)?;
+        ops::materialize_store(&repo, dst, verbose)?;
+    }
+
+    info!("✅ Copied store '{}' to '{}'. Run `git trunk push --store {}` to publish it.", src, dst, dst);
+    Ok(())
+}
@@ -1,184 +1,223 @@
-use std::fs;
-use std::io::{self, Write};
-use std::path::Path;
-use std::process::{Command, exit};
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use log::{debug, error, info};
-use crate::utils::run_git_command;
+use crate::backup;
+use crate::config;
+use crate::ops;
+use crate::trash;
+use crate::utils::{confirm, is_dry_run, run_git_change};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
 
 #[derive(Parser, Debug)]
-#[command(about = "Remove all traces of a git-trunk store, including .trunk/<store> and refs/trunk/<store> locally and remotely")]
-pub struct DeleteArgs {}
+#[command(about = "Delete a git-trunk store: remove .trunk/<store> and move refs/trunk/<store> to the trash locally and remotely", after_help = "Examples:\n  git trunk delete --store scratch\n  git trunk delete --store scratch --yes\n  git trunk undelete scratch\n  git trunk delete --store scratch --local-only\n  git trunk delete --store scratch --remote-only --remote old-origin")]
+pub struct DeleteArgs {
+    #[arg(long, conflicts_with = "remote_only", help = "Only delete .trunk/<store> and the local ref, keeping the remote ref")]
+    local_only: bool,
+    #[arg(long, help = "Only delete the remote ref, keeping .trunk/<store> and the local ref")]
+    remote_only: bool,
+    #[arg(long, help = "Do not write a backup bundle of the store to .git/trunk-backups first")]
+    no_backup: bool,
+}
 
-pub fn run(_args: &DeleteArgs, remote_name: &str, store_name: &str, verbose: bool) {
+impl DeleteArgs {
+    // Only the remote ref changes with --remote-only, unless the backup is written
+    pub fn writes_git_dir(&self) -> bool {
+        !(self.remote_only && self.no_backup)
+    }
+}
+
+pub fn run(args: &DeleteArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
     let trunk_ref_name = format!("refs/trunk/{}", store_name);
     let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let delete_local = !args.remote_only;
+    let delete_remote = !args.local_only;
 
-    // Step 1: Prompt user for confirmation
+    // Step 1: Prompt user for confirmation, naming only what will be removed
     debug!("➡️ Step 1: Prompting user for confirmation to delete store '{}'", store_name);
-    print!("🐘︖ This will delete the local directory '{}', the local ref '{}', and the remote ref '{}' on remote '{}'. This operation is irreversible. Continue? [y/N]: ", store_dir_relative_path, trunk_ref_name, trunk_ref_name, remote_name);
-    io::stdout().flush().expect("Failed to flush stdout");
-    let mut input = String::new();
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read user input");
-    let input = input.trim().to_lowercase();
-    if input != "y" && input != "yes" {
+    let targets = match (delete_local, delete_remote) {
+        (true, true) => format!("the local directory '{}', the local ref '{}', and the remote ref '{}' on remote '{}'", store_dir_relative_path, trunk_ref_name, trunk_ref_name, remote_name),
+        (true, false) => format!("the local directory '{}' and the local ref '{}' (the remote ref on remote '{}' is kept)", store_dir_relative_path, trunk_ref_name, remote_name),
+        _ => format!("the remote ref '{}' on remote '{}' ('{}' and the local ref are kept)", trunk_ref_name, remote_name, store_dir_relative_path),
+    };
+    let recovery = if args.no_backup {
+        "The refs are moved to refs/trunk-trash until `git trunk trash purge` removes them."
+    } else {
+        "The refs are moved to refs/trunk-trash, and a backup is written to .git/trunk-backups first."
+    };
+    if !confirm(&format!("This will delete {}. {} Continue?", targets, recovery))? {
         info!("🚫 Delete operation for store '{}' aborted by user", store_name);
-        exit(0);
+        return Ok(());
     }
     info!("✓ Step 1: User confirmed deletion for store '{}'", store_name);
 
-    // Step 2: Check if we are in a Git repository
-    debug!("➡️ Step 2: Checking if inside a Git repository");
-    let git_check_output = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--is-inside-work-tree"),
-        verbose,
-    );
-    if git_check_output.map(|output| !output.status.success()).unwrap_or(true) {
-        error!("❌ delete can only be invoked inside a git repo");
-        exit(1);
-    }
-    info!("✓ Step 2: Confirmed inside a Git repository");
-
-    // Step 3: Get repository root
-    debug!("➡️ Step 3: Getting repository root");
-    let repo_root_output = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--show-toplevel"),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to get git repository root: {}", e);
-        exit(1);
-    });
-    let repo_root_str = String::from_utf8_lossy(&repo_root_output.stdout).trim().to_string();
-    if repo_root_str.is_empty() {
-        error!("❌ Git repository root is empty. Ensure you are in a valid Git repository.");
-        exit(1);
+    // Step 2 & 3: Check that we are in a Git repository and get its root
+    let repo = ops::ensure_repo_root("Step 2 & 3", verbose)?;
+
+    // Step 3b: Keep a bundle of the history about to be deleted
+    let backed_up = if args.no_backup {
+        info!("= Step 3b: --no-backup, not backing up store '{}'", store_name);
+        false
+    } else {
+        backup_store(&repo, remote_name, store_name, delete_local, delete_remote, verbose)?
+    };
+
+    // Local and remote trash refs of one deletion share their name
+    let deleted_at = Utc::now();
+    let mut trashed = false;
+    if delete_local {
+        trashed |= delete_local_store(&repo, store_name, deleted_at, verbose);
+    } else {
+        info!("= Steps 4-5: --remote-only, keeping {} and the local ref {}", store_dir_relative_path, trunk_ref_name);
     }
-    let repo_root = Path::new(&repo_root_str);
-    info!("✓ Step 3: Repository root found at {}", repo_root.display());
-
-    // Step 4: Remove .trunk/<store_name> directory
-    let trunk_store_dir = repo_root.join(&store_dir_relative_path);
-    debug!("➡️ Step 4: Checking for {} directory", store_dir_relative_path);
-    if trunk_store_dir.exists() {
-        debug!("🗑️ Step 4: Removing {} directory for store '{}'", store_dir_relative_path, store_name);
-        fs::remove_dir_all(&trunk_store_dir).unwrap_or_else(|e| {
-            error!("❌ Failed to remove {} directory: {}", store_dir_relative_path, e);
-            // Do not exit here, try to remove refs as well
-        });
-        info!("✓ Step 4: {} directory removed for store '{}'", store_dir_relative_path, store_name);
+    if delete_remote {
+        trashed |= delete_remote_ref(&repo, remote_name, store_name, deleted_at, verbose)?;
     } else {
-        debug!("🚫 Step 4: No {} directory found for store '{}'", store_dir_relative_path, store_name);
-        info!("= Step 4: No {} directory to remove for store '{}'", store_dir_relative_path, store_name);
+        info!("= Step 6: --local-only, keeping the remote ref {} on remote '{}'", trunk_ref_name, remote_name);
     }
-    
-    // Step 4b: Check if .trunk parent directory is empty, if so, remove it
-    let parent_trunk_dir = repo_root.join(".trunk");
-    if parent_trunk_dir.exists() {
-        match fs::read_dir(&parent_trunk_dir) {
-            Ok(mut entries) => {
-                if entries.next().is_none() { // Directory is empty
-                    debug!("🗑️ Step 4b: .trunk directory is empty, removing it.");
-                    if let Err(e) = fs::remove_dir(&parent_trunk_dir) {
-                        error!("⚠️ Warning: Failed to remove empty .trunk directory at {}: {}", parent_trunk_dir.display(), e);
-                    } else {
-                        info!("✓ Step 4b: Empty .trunk directory removed.");
-                    }
-                } else {
-                    debug!("ℹ️ Step 4b: .trunk directory is not empty, retaining it.");
-                }
-            },
-            Err(e) => {
-                error!("⚠️ Warning: Could not read .trunk directory contents at {}: {}", parent_trunk_dir.display(), e);
+    // Note: .gitignore entry for ".trunk" is not removed, as other stores might exist.
+
+    let targeted = match (delete_local, delete_remote) {
+        (true, true) => "Local directory (if existed), local ref (if existed), and remote ref (if existed) have",
+        (true, false) => "Local directory (if existed) and local ref (if existed) have",
+        _ => "Remote ref (if existed) has",
+    };
+    info!("✅ Delete for store '{}' completed. {} been targeted for removal.", store_name, targeted);
+    if trashed {
+        info!("♻️ Undo it with `git trunk undelete {}`", store_name);
+    } else if backed_up {
+        info!("💾 Undo it with `git trunk restore-backup --store {}`", store_name);
+    }
+    Ok(())
+}
+
+// Bundles the refs that are about to go: refs/trunk/<store_name>, and the
+// remote's ref when it has commits the local one lacks. Returns whether a
+// bundle was written.
+fn backup_store(repo: &GitRepo, remote_name: &str, store_name: &str, delete_local: bool, delete_remote: bool, verbose: bool) -> Result<bool, TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let mirror_ref_name = format!("refs/trunk-remotes/{}/{}", remote_name, store_name);
+    debug!("➡️ Step 3b: Backing up store '{}'", store_name);
+    let local_hash = repo.resolve_commit(&trunk_ref_name);
+    let mut refs = Vec::new();
+    if delete_local && local_hash.is_some() {
+        refs.push(trunk_ref_name);
+    }
+    if delete_remote {
+        // An unreachable remote is reported when deleting its ref
+        if let Ok(Some(remote_hash)) = ops::remote_store_tip(repo, remote_name, store_name, verbose) {
+            if !local_hash.as_ref().is_some_and(|local_hash| repo.is_ancestor(&remote_hash, local_hash)) {
+                ops::fetch_store_ref(repo, remote_name, store_name, &mirror_ref_name, verbose)?;
+                refs.push(mirror_ref_name);
             }
         }
     }
+    if refs.is_empty() {
+        info!("= Step 3b: Nothing to back up for store '{}'", store_name);
+        return Ok(false);
+    }
+    let bundle = backup::create(repo, store_name, &refs, verbose)?;
+    info!("✓ Step 3b: Backed up {} to {}", refs.join(" and "), bundle.display());
+    Ok(true)
+}
+
+// Returns whether the local ref was moved to the trash.
+fn delete_local_store(repo: &GitRepo, store_name: &str, deleted_at: DateTime<Utc>, verbose: bool) -> bool {
+    let repo_root = repo.root();
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let trash_ref_name = trash::trash_ref_name(store_name, deleted_at);
 
+    // Step 4: Remove .trunk/<store_name> directory, and .trunk once it is empty
+    ops::remove_store_dir(repo_root, store_name, "Step 4", verbose);
+    ops::remove_empty_trunk_dir(repo_root, &config::trunk_base_dir(repo_root, verbose).join(store_name), "Step 4b", verbose);
 
-    // Step 5: Delete local refs/trunk/<store_name>
+    // Step 5: Move local refs/trunk/<store_name> to the trash
     debug!("➡️ Step 5: Checking for local ref {}", trunk_ref_name);
-    let local_ref_exists = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--verify")
-            .arg(&trunk_ref_name)
-            .current_dir(repo_root),
-        verbose,
-    )
-    .map(|output| output.status.success())
-    .unwrap_or(false);
-
-    if local_ref_exists {
-        debug!("🗑️ Step 5: Deleting local ref {}", trunk_ref_name);
-        let delete_status = run_git_command(
-            Command::new("git")
-                .arg("update-ref")
-                .arg("-d")
-                .arg(&trunk_ref_name)
-                .current_dir(repo_root),
-            verbose,
-        )
-        .unwrap_or_else(|e| {
-            error!("❌ Failed to delete local ref {}: {}", trunk_ref_name, e);
-            exit(1); // Critical if ref deletion fails but we said we would
-        });
-        if !delete_status.status.success() {
-            error!("❌ Failed to delete local ref {}. It might not exist or another error occurred.", trunk_ref_name);
+    if let Some(hash) = repo.resolve_commit(&trunk_ref_name) {
+        debug!("🗑️ Step 5: Moving local ref {} to {}", trunk_ref_name, trash_ref_name);
+        if let Err(e) = repo.update_ref(&trash_ref_name, &hash).and_then(|_| repo.delete_ref(&trunk_ref_name)) {
+            error!("❌ Failed to move local ref {} to {}: {}", trunk_ref_name, trash_ref_name, e);
             // Continue to try remote deletion
+            false
         } else {
-            info!("✓ Step 5: Local ref {} deleted", trunk_ref_name);
+            info!("✓ Step 5: Local ref {} moved to {}", trunk_ref_name, trash_ref_name);
+            true
         }
     } else {
         debug!("🚫 Step 5: No local ref {} found for store '{}'", trunk_ref_name, store_name);
         info!("= Step 5: No local ref {} to delete for store '{}'", trunk_ref_name, store_name);
+        false
     }
+}
+
+// Moves the remote's refs/trunk/<store_name> to its trash in one atomic push,
+// or deletes it outright when the remote refuses the trash ref. Returns
+// whether the remote kept a copy in its trash.
+fn delete_remote_ref(repo: &GitRepo, remote_name: &str, store_name: &str, deleted_at: DateTime<Utc>, verbose: bool) -> Result<bool, TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let trash_ref_name = trash::trash_ref_name(store_name, deleted_at);
 
-    // Step 6: Delete remote refs/trunk/<store_name>
+    // Step 6: Move remote refs/trunk/<store_name> to the remote's trash
     debug!("➡️ Step 6: Checking for remote ref {} on remote '{}'", trunk_ref_name, remote_name);
-    let remote_ref_check = run_git_command(
-        Command::new("git")
-            .arg("ls-remote")
-            .arg(remote_name)
-            .arg(&trunk_ref_name)
-            .current_dir(repo_root),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to check {} on remote '{}': {}", trunk_ref_name, remote_name, e);
-        exit(1); // Critical if we can't check before trying to delete
-    });
-
-    if !remote_ref_check.stdout.is_empty() {
-        debug!("🗑️ Step 6: Deleting remote ref {} on remote '{}'", trunk_ref_name, remote_name);
-        let push_delete_status = run_git_command(
-            Command::new("git")
+    let remote_hash = match ops::remote_store_tip(repo, remote_name, store_name, verbose) {
+        Err(_) => {
+            error!("⚠️ Warning: Could not reach remote '{}'; remote ref {} was not deleted", remote_name, trunk_ref_name);
+            return Ok(false);
+        }
+        Ok(None) => {
+            debug!("🚫 Step 6: No remote ref {} found on remote '{}' for store '{}'", trunk_ref_name, remote_name, store_name);
+            info!("= Step 6: No remote ref {} to delete on remote '{}' for store '{}'", trunk_ref_name, remote_name, store_name);
+            return Ok(false);
+        }
+        Ok(Some(remote_hash)) => remote_hash,
+    };
+
+    // Pushing the trash ref needs the remote's commits here
+    if repo.resolve_commit(&remote_hash).is_none() {
+        let mirror_ref_name = format!("refs/trunk-remotes/{}/{}", remote_name, store_name);
+        if let Err(e) = ops::fetch_store_ref(repo, remote_name, store_name, &mirror_ref_name, verbose) {
+            debug!("⚠️ Step 6: {}", e);
+        }
+    }
+    if is_dry_run() || repo.resolve_commit(&remote_hash).is_some() {
+        debug!("🗑️ Step 6: Moving remote ref {} to {} on remote '{}'", trunk_ref_name, trash_ref_name, remote_name);
+        let output = run_git_change(
+            repo.git()
                 .arg("push")
+                .arg("--atomic")
                 .arg(remote_name)
-                .arg(format!(":{}", trunk_ref_name)) // Delete refspec
-                .current_dir(repo_root),
+                .arg(format!("{}:{}", remote_hash, trash_ref_name))
+                .arg(format!(":{}", trunk_ref_name)),
             verbose,
         )
-        .unwrap_or_else(|e| {
-            error!("❌ Failed to delete remote ref {}: {}", trunk_ref_name, e);
-            exit(1); // Critical
-        });
-        if !push_delete_status.status.success() {
-            error!("❌ Failed to delete remote ref {} on remote '{}'. Check remote configuration and permissions.", trunk_ref_name, remote_name);
-            // Don't exit, just report error
-        } else {
-             info!("✓ Step 6: Remote ref {} deleted on remote '{}'", trunk_ref_name, remote_name);
+        .map_err(|e| TrunkError::Failed(format!("Failed to delete remote ref {}: {}", trunk_ref_name, e)))?;
+        if output.status.success() {
+            if !is_dry_run() {
+                ops::forget_remote_listing(repo, remote_name);
+            }
+            info!("✓ Step 6: Remote ref {} moved to {} on remote '{}'", trunk_ref_name, trash_ref_name, remote_name);
+            return Ok(true);
         }
-    } else {
-        debug!("🚫 Step 6: No remote ref {} found on remote '{}' for store '{}'", trunk_ref_name, remote_name, store_name);
-        info!("= Step 6: No remote ref {} to delete on remote '{}' for store '{}'", trunk_ref_name, remote_name, store_name);
     }
-    // Note: .gitignore entry for ".trunk" is not removed, as other stores might exist.
+    error!("⚠️ Warning: Remote '{}' did not take {}; deleting {} there without a copy in its trash", remote_name, trash_ref_name, trunk_ref_name);
 
-    info!("✅ Delete for store '{}' completed. Local directory (if existed), local ref (if existed), and remote ref (if existed) have been targeted for removal.", store_name);
+    debug!("🗑️ Step 6: Deleting remote ref {} on remote '{}'", trunk_ref_name, remote_name);
+    let push_delete_status = run_git_change(
+        repo.git()
+            .arg("push")
+            .arg(remote_name)
+            .arg(format!(":{}", trunk_ref_name)), // Delete refspec
+        verbose,
+    )
+    .map_err(|e| TrunkError::Failed(format!("Failed to delete remote ref {}: {}", trunk_ref_name, e)))?;
+    if !push_delete_status.status.success() {
+        error!("❌ Failed to delete remote ref {} on remote '{}'. Check remote configuration and permissions.", trunk_ref_name, remote_name);
+        // Don't exit, just report error
+    } else {
+        if !is_dry_run() {
+            ops::forget_remote_listing(repo, remote_name);
+        }
+        info!("✓ Step 6: Remote ref {} deleted on remote '{}'", trunk_ref_name, remote_name);
+    }
+    Ok(false)
 }
\ No newline at end of file
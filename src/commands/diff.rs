@@ -0,0 +1,226 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use clap::Parser;
+use log::{debug, info};
+use crate::config;
+use crate::table::use_color;
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Show changes in .trunk/<store> against refs/trunk/<store> (or the remote ref), or between two store revisions", after_help = "Examples:\n  git trunk diff\n  git trunk diff --word HEAD~3 HEAD\n  git trunk diff --remote-ref --html review.html")]
+pub struct DiffArgs {
+    #[arg(help = "Store revisions to compare (e.g. HEAD~3 HEAD); with one, the working files are compared to it", num_args = 0..=2)]
+    revs: Vec<String>,
+    #[arg(long, conflicts_with = "revs", help = "Compare the working files to refs/trunk/<store> on the remote (see --remote) instead of the local ref")]
+    pub remote_ref: bool,
+    #[arg(long, help = "Diff words instead of lines, highlighting changes within each line")]
+    word: bool,
+    #[arg(long, value_name = "FILE", help = "Write a side-by-side word diff as HTML to FILE (`-` for stdout)")]
+    html: Option<String>,
+}
+
+// Splits prose into words and single punctuation marks, so "fix," → "fixed,"
+// highlights only the word.
+pub const WORD_REGEX: &str = "[[:alnum:]_'’-]+|[^[:space:]]";
+
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Untracked files, which `git diff` leaves out.
+fn untracked_files(store_dir: &Path, verbose: bool) -> Vec<String> {
+    run_git_command(Command::new("git").arg("ls-files").arg("-z").arg("--others").arg("--exclude-standard").current_dir(store_dir), verbose)
+        .map(|output| output.stdout.split(|b| *b == 0).filter(|path| !path.is_empty()).map(|path| String::from_utf8_lossy(path).to_string()).collect())
+        .unwrap_or_default()
+}
+
+// `git diff` invocations covering the requested comparison: one for tracked
+// changes plus, when the working files take part, one `--no-index` diff per
+// untracked file.
+fn diff_commands(store_dir: &Path, revs: &[String], diff_flags: &[String], verbose: bool) -> Vec<Command> {
+    let mut commands = Vec::new();
+    let mut tracked = Command::new("git");
+    tracked.arg("diff").args(diff_flags).args(revs).arg("--").current_dir(store_dir);
+    commands.push(tracked);
+    if revs.len() < 2 {
+        for path in untracked_files(store_dir, verbose) {
+            let mut untracked = Command::new("git");
+            untracked.arg("diff").arg("--no-index").args(diff_flags).arg("--").arg("/dev/null").arg(&path).current_dir(store_dir);
+            commands.push(untracked);
+        }
+    }
+    commands
+}
+
+// One side-by-side table per file from `git diff --word-diff=porcelain`:
+// the left column has removed words struck out, the right added words marked.
+pub fn render_html(porcelain: &str, title: &str) -> String {
+    let mut body = String::new();
+    let (mut left, mut right) = (String::new(), String::new());
+    let mut in_hunk = false;
+    let flush_row = |body: &mut String, left: &mut String, right: &mut String| {
+        let class = if left == right { "same" } else { "changed" };
+        body.push_str(&format!("<tr class=\"{}\"><td>{}</td><td>{}</td></tr>\n", class, left, right));
+        left.clear();
+        right.clear();
+    };
+    for line in porcelain.lines() {
+        if line.starts_with("diff --git") {
+            if in_hunk {
+                body.push_str("</table>\n");
+            }
+            in_hunk = false;
+            continue;
+        }
+        if let Some(path) = line.strip_prefix("+++ ") {
+            let path = path.strip_prefix("b/").unwrap_or(path);
+            body.push_str(&format!("<h2>{}</h2>\n<table>\n<tr><th>Before</th><th>After</th></tr>\n", html_escape(path)));
+            in_hunk = true;
+            continue;
+        }
+        if !in_hunk || line.starts_with("--- ") || line.starts_with("index ") || line.starts_with("new file") || line.starts_with("deleted file") {
+            continue;
+        }
+        if line.starts_with("@@") {
+            body.push_str("<tr class=\"hunk\"><td colspan=\"2\">…</td></tr>\n");
+        } else if line == "~" {
+            flush_row(&mut body, &mut left, &mut right);
+        } else if let Some(text) = line.strip_prefix(' ') {
+            left.push_str(&html_escape(text));
+            right.push_str(&html_escape(text));
+        } else if let Some(text) = line.strip_prefix('-') {
+            left.push_str(&format!("<del>{}</del>", html_escape(text)));
+        } else if let Some(text) = line.strip_prefix('+') {
+            right.push_str(&format!("<ins>{}</ins>", html_escape(text)));
+        }
+    }
+    if in_hunk {
+        body.push_str("</table>\n");
+    }
+    if body.is_empty() {
+        body.push_str("<p>No changes.</p>\n");
+    }
+    format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; table-layout: fixed; }}
+td, th {{ border: 1px solid #ddd; padding: 4px 8px; vertical-align: top; white-space: pre-wrap; word-wrap: break-word; }}
+tr.hunk td {{ background: #f0f4ff; color: #888; text-align: center; }}
+tr.changed td {{ background: #fffbe6; }}
+del {{ background: #ffd7d5; text-decoration: line-through; }}
+ins {{ background: #ccffd8; text-decoration: none; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+{body}</body>
+</html>
+"#, title = html_escape(title), body = body)
+}
+
+pub fn run(args: &DiffArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Check the store checkout
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    debug!("➡️ Step 2: Checking {}", store_dir_relative_path);
+    if !trunk_store_dir.join(".git").exists() {
+        return Err(TrunkError::NotFound(format!("{} is not checked out. Run `git trunk checkout --store {}` first.", store_dir_relative_path, store_name)));
+    }
+    debug!("✓ Step 2: {} found", store_dir_relative_path);
+
+    // Step 3: Work out what to compare against. Without revisions that is
+    // refs/trunk/<store> (local, or the remote one with --remote), copied into
+    // the checkout under a temporary ref.
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let fetched_ref_name = format!("refs/trunk-fetch/{}", store_name);
+    let temp_store_ref = "refs/temp/trunk_diff";
+    let mut source_ref = trunk_ref_name.clone();
+    if args.remote_ref {
+        debug!("📥 Step 3: Fetching {} from remote '{}'", trunk_ref_name, remote_name);
+        let fetched = run_git_command(Command::new("git").arg("fetch").arg(remote_name).arg(format!("+{}:{}", trunk_ref_name, fetched_ref_name)).current_dir(repo_root), verbose)
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+        if !fetched {
+            return Err(TrunkError::Git(format!("Failed to fetch {} from remote '{}'. Check that the store was pushed and the remote is reachable.", trunk_ref_name, remote_name)));
+        }
+        source_ref = fetched_ref_name.clone();
+    }
+    let revs: Vec<String> = if !args.revs.is_empty() {
+        args.revs.clone()
+    } else {
+        let copied = run_git_command(Command::new("git").arg("fetch").arg(repo_root.as_os_str()).arg(format!("+{}:{}", source_ref, temp_store_ref)).current_dir(&trunk_store_dir), verbose)
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+        if copied {
+            vec![temp_store_ref.to_string()]
+        } else if args.remote_ref {
+            return Err(TrunkError::Io(format!("Failed to copy the remote {} into {}", trunk_ref_name, store_dir_relative_path)));
+        } else {
+            debug!("= Step 3: {} does not exist yet; comparing to HEAD of {}", trunk_ref_name, store_dir_relative_path);
+            vec!["HEAD".to_string()]
+        }
+    };
+    let cleanup = || {
+        let _ = run_git_command(Command::new("git").arg("update-ref").arg("-d").arg(temp_store_ref).current_dir(&trunk_store_dir), verbose);
+        if args.remote_ref {
+            let _ = run_git_command(Command::new("git").arg("update-ref").arg("-d").arg(&fetched_ref_name).current_dir(repo_root), verbose);
+        }
+    };
+
+    // Step 4: Render the diff
+    if let Some(html_target) = &args.html {
+        let flags = vec!["--no-color".to_string(), "--word-diff=porcelain".to_string(), format!("--word-diff-regex={}", WORD_REGEX)];
+        let mut porcelain = String::new();
+        for mut command in diff_commands(&trunk_store_dir, &revs, &flags, verbose) {
+            let output = run_git_command(&mut command, verbose).map_err(|e| {
+                cleanup();
+                TrunkError::Git(format!("Failed to run git diff in {}: {}", store_dir_relative_path, e))
+            })?;
+            porcelain.push_str(&String::from_utf8_lossy(&output.stdout));
+        }
+        cleanup();
+        let compared = match args.revs.as_slice() {
+            [] if args.remote_ref => format!("working files against {} on {}", trunk_ref_name, remote_name),
+            [] => format!("working files against {}", trunk_ref_name),
+            [rev] => format!("working files against {}", rev),
+            revs => revs.join(" → "),
+        };
+        let html = render_html(&porcelain, &format!("Store '{}': {}", store_name, compared));
+        if html_target == "-" {
+            print!("{}", html);
+        } else {
+            fs::write(html_target, html).map_err(|e| TrunkError::Io(format!("Failed to write {}: {}", html_target, e)))?;
+            info!("✅ Wrote side-by-side diff to {}", html_target);
+        }
+        return Ok(());
+    }
+
+    let mut flags = vec![if use_color() { "--color=always".to_string() } else { "--color=never".to_string() }];
+    if args.word {
+        flags.push(if use_color() { "--word-diff=color".to_string() } else { "--word-diff=plain".to_string() });
+        flags.push(format!("--word-diff-regex={}", WORD_REGEX));
+    }
+    for mut command in diff_commands(&trunk_store_dir, &revs, &flags, verbose) {
+        let status = command.stdin(Stdio::null()).status();
+        // `git diff --no-index` exits 1 when the files differ.
+        if status.as_ref().map_or(true, |status| status.code().is_none_or(|code| code > 1)) {
+            cleanup();
+            return Err(TrunkError::Git(format!("git diff failed in {}", store_dir_relative_path)));
+        }
+    }
+    cleanup();
+    Ok(())
+}
@@ -0,0 +1,295 @@
+use chrono::{DateTime, Local};
+use clap::{Parser, ValueEnum};
+use log::{debug, error, info};
+use regex::Regex;
+use crate::commands::changelog::{self, Change, Entry, CHANGE_ORDER};
+use crate::commands::{put, report};
+use crate::config;
+use crate::frontmatter;
+use crate::ops;
+use crate::utils::{read_blobs, run_git_command};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Summarize recent activity across stores for a mailing list or chat",
+    after_help = "Run it on a schedule (cron, CI) and post the output, or keep it with --commit-to.\n\nExamples:\n  git trunk digest\n  git trunk digest --since 2w --stores handbook,design\n  git trunk digest --since \"2026-09-01\" --format text\n  git trunk digest --since 1w --stores 'docs-*' --commit-to handbook"
+)]
+pub struct DigestArgs {
+    #[arg(long, value_name = "WHEN", default_value = "1w", help = "Start of the period: hours, days, weeks or months back (`12h`, `3d`, `1w`, `1m`), or anything `git log --since` accepts")]
+    since: String,
+    #[arg(long, value_enum, default_value_t = DigestFormat::Md, help = "Output format")]
+    format: DigestFormat,
+    #[arg(long, value_name = "STORE", value_delimiter = ',', help = "Stores to include, by name or glob, comma-separated [default: every local store]")]
+    stores: Vec<String>,
+    #[arg(long, value_name = "STORE", help = "Also commit the digest into this store as digests/<date>.md")]
+    commit_to: Option<String>,
+}
+
+impl DigestArgs {
+    pub fn writes_git_dir(&self) -> bool {
+        self.commit_to.is_some()
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum DigestFormat {
+    Text,
+    Md,
+}
+
+// Folder of the store that --commit-to writes to. Links in earlier digests
+// are not new, so they are left out of the next one.
+const DIGESTS_DIR: &str = "digests";
+
+// Links listed per store, first seen first.
+const MAX_LINKS: usize = 20;
+
+// A file changed in the period: its title when it is a document with one,
+// who changed it and in how many commits.
+struct FileChange {
+    entry: Entry,
+    title: Option<String>,
+    authors: Vec<String>,
+    commits: usize,
+}
+
+struct StoreDigest {
+    store: String,
+    commits: usize,
+    authors: Vec<String>,
+    files: Vec<FileChange>,
+    // (URL, file it was added to)
+    links: Vec<(String, String)>,
+}
+
+// "1w" becomes "1 weeks ago"; other values go to git as they are.
+fn git_since(since: &str) -> String {
+    let Some(unit) = since.chars().last() else { return since.to_string() };
+    let number = &since[..since.len() - unit.len_utf8()];
+    let unit = match unit {
+        'h' => "hours",
+        'd' => "days",
+        'w' => "weeks",
+        'm' => "months",
+        'y' => "years",
+        _ => return since.to_string(),
+    };
+    if number.parse::<u32>().is_ok() { format!("{} {} ago", number, unit) } else { since.to_string() }
+}
+
+// The start of the period as git reads `since`, through `git rev-parse --since`.
+fn since_time(repo: &GitRepo, since: &str, verbose: bool) -> Result<DateTime<Local>, TrunkError> {
+    let output = run_git_command(repo.git().arg("rev-parse").arg(format!("--since={}", git_since(since))), verbose)
+        .map_err(|e| TrunkError::Git(format!("git rev-parse failed: {}", e)))?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .strip_prefix("--max-age=")
+        .and_then(|secs| secs.parse().ok())
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|time| time.with_timezone(&Local))
+        .ok_or_else(|| TrunkError::Usage(format!("Cannot read --since '{}'; use e.g. 1w, 3d or a date", since)))
+}
+
+// URLs on the lines `base..tip` added to documents, with the file each was added to.
+fn added_links(repo: &GitRepo, base: &str, tip: &str, verbose: bool) -> Vec<(String, String)> {
+    let url = Regex::new(r#"https?://[^\s<>()\[\]"'`]+"#).expect("valid URL regex");
+    let Ok(output) = run_git_command(repo.git().arg("diff").arg("-U0").arg("--no-color").arg("--no-ext-diff").arg(base).arg(tip).arg("--"), verbose) else {
+        return Vec::new();
+    };
+    let mut links: Vec<(String, String)> = Vec::new();
+    let mut path: Option<String> = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(new_path) = line.strip_prefix("+++ ") {
+            path = new_path.strip_prefix("b/").map(str::to_string).filter(|path| report::is_document(path) && !ops::is_under(path, DIGESTS_DIR));
+            continue;
+        }
+        let (Some(path), Some(added)) = (&path, line.strip_prefix('+')) else { continue };
+        for found in url.find_iter(added) {
+            let link = found.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            if links.len() < MAX_LINKS && !links.iter().any(|(known, _)| known == link) {
+                links.push((link.to_string(), path.clone()));
+            }
+        }
+    }
+    links
+}
+
+// What happened to a store since `since`, or None when nothing did.
+fn store_digest(repo: &GitRepo, store_name: &str, since: DateTime<Local>, verbose: bool) -> Option<StoreDigest> {
+    let tip = format!("refs/trunk/{}", store_name);
+    let history = report::history(repo, &tip, verbose);
+    let recent: Vec<&report::HistoryCommit> = history.iter().take_while(|commit| commit.time >= since).collect();
+    if recent.is_empty() {
+        return None;
+    }
+    // The store as it was when the period started; git knows the empty tree
+    // of a store created since
+    let base = match history.get(recent.len()) {
+        Some(commit) => commit.hash.clone(),
+        None => run_git_command(repo.git().arg("hash-object").arg("-t").arg("tree").arg("/dev/null"), verbose)
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .ok()?,
+    };
+    let name_status = run_git_command(repo.git().arg("diff").arg("--name-status").arg("-z").arg("-M").arg(&base).arg(&tip).arg("--"), verbose).ok()?;
+    let entries = changelog::parse_name_status(&name_status.stdout);
+
+    let titled: Vec<&Entry> = entries.iter().filter(|entry| entry.change != Change::Removed && entry.path.ends_with(".md")).collect();
+    let blobs = read_blobs(repo.root(), &titled.iter().map(|entry| format!("{}:{}", tip, entry.path)).collect::<Vec<_>>(), verbose).unwrap_or_default();
+    let titles: Vec<(String, String)> = titled
+        .iter()
+        .zip(blobs)
+        .filter_map(|(entry, blob)| Some((entry.path.clone(), frontmatter::title(&String::from_utf8_lossy(&blob?))?)))
+        .collect();
+
+    let mut authors: Vec<String> = Vec::new();
+    for commit in &recent {
+        if !authors.contains(&commit.author) {
+            authors.push(commit.author.clone());
+        }
+    }
+    let files = entries
+        .into_iter()
+        .map(|entry| {
+            let touched: Vec<&&report::HistoryCommit> = recent
+                .iter()
+                .filter(|commit| commit.paths.iter().any(|path| *path == entry.path || Some(path) == entry.old_path.as_ref()))
+                .collect();
+            let mut file_authors: Vec<String> = Vec::new();
+            for commit in &touched {
+                if !file_authors.contains(&commit.author) {
+                    file_authors.push(commit.author.clone());
+                }
+            }
+            let title = titles.iter().find(|(path, _)| *path == entry.path).map(|(_, title)| title.clone());
+            FileChange { title, authors: file_authors, commits: touched.len(), entry }
+        })
+        .collect();
+    Some(StoreDigest { store: store_name.to_string(), commits: recent.len(), authors, links: added_links(repo, &base, &tip, verbose), files })
+}
+
+fn render(digests: &[StoreDigest], quiet: &[String], since: DateTime<Local>, format: DigestFormat) -> String {
+    let markdown = format == DigestFormat::Md;
+    let code = |text: &str| if markdown { format!("`{}`", text) } else { text.to_string() };
+    let mut out = String::new();
+    let period = format!("{} to {}", since.format("%Y-%m-%d"), Local::now().format("%Y-%m-%d"));
+    let mut authors: Vec<&String> = digests.iter().flat_map(|digest| &digest.authors).collect();
+    authors.sort();
+    authors.dedup();
+    let summary = format!(
+        "{} commit(s) by {} author(s) in {} store(s).",
+        digests.iter().map(|digest| digest.commits).sum::<usize>(),
+        authors.len(),
+        digests.len()
+    );
+    if markdown {
+        out.push_str(&format!("# Trunk digest: {}\n\n{}\n", period, summary));
+    } else {
+        out.push_str(&format!("Trunk digest: {}\n{}\n", period, summary));
+    }
+
+    for digest in digests {
+        let header = format!("{} commit(s) by {}.", digest.commits, digest.authors.join(", "));
+        if markdown {
+            out.push_str(&format!("\n## Store `{}`\n\n{}\n", digest.store, header));
+        } else {
+            out.push_str(&format!("\n{}: {}\n", digest.store, header));
+        }
+        let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+        for change in CHANGE_ORDER {
+            let items: Vec<String> = digest
+                .files
+                .iter()
+                .filter(|file| file.entry.change == change)
+                .map(|file| {
+                    let path = match &file.entry.old_path {
+                        Some(old_path) => format!("{} → {}", code(old_path), code(&file.entry.path)),
+                        None => code(&file.entry.path),
+                    };
+                    let label = match &file.title {
+                        Some(title) if markdown => format!("**{}** {}", title, path),
+                        Some(title) => format!("{} ({})", title, path),
+                        None => path,
+                    };
+                    let commits = if file.commits > 1 { format!(" ({} commits)", file.commits) } else { String::new() };
+                    if file.authors.is_empty() { label } else { format!("{} by {}{}", label, file.authors.join(", "), commits) }
+                })
+                .collect();
+            if !items.is_empty() {
+                sections.push((change.heading().to_string(), items));
+            }
+        }
+        if !digest.links.is_empty() {
+            let items = digest
+                .links
+                .iter()
+                .map(|(link, path)| if markdown { format!("<{}> in {}", link, code(path)) } else { format!("{} ({})", link, path) })
+                .collect();
+            sections.push(("Links".to_string(), items));
+        }
+        for (heading, items) in sections {
+            if markdown {
+                out.push_str(&format!("\n### {}\n\n", heading));
+                for item in items {
+                    out.push_str(&format!("- {}\n", item));
+                }
+            } else {
+                out.push_str(&format!("  {}:\n", heading));
+                for item in items {
+                    out.push_str(&format!("    {}\n", item));
+                }
+            }
+        }
+    }
+    if !quiet.is_empty() {
+        let quiet: Vec<String> = quiet.iter().map(|store| code(store)).collect();
+        out.push_str(&format!("\nNo activity in {}.\n", quiet.join(", ")));
+    }
+    out
+}
+
+pub fn run(args: &DigestArgs, remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    if let Some(store) = &args.commit_to {
+        config::check_store_name(store).map_err(|reason| TrunkError::Usage(format!("Invalid store name '{}': {}", store, reason)))?;
+    }
+
+    // Step 2: Work out the period and the stores
+    debug!("➡️ Step 2: Reading --since {}", args.since);
+    let since = since_time(&repo, &args.since, verbose)?;
+    let stores = if args.stores.is_empty() { ops::local_stores(&repo, verbose) } else { ops::expand_store_patterns(&args.stores, verbose)? };
+    if stores.is_empty() {
+        return Err(TrunkError::NotFound("No stores in this repository. Run `git trunk fetch` to get the remote's stores.".to_string()));
+    }
+    info!("✓ Step 2: Summarizing {} store(s) since {}", stores.len(), since.format("%Y-%m-%d %H:%M"));
+
+    // Step 3: Collect each store's activity from refs/trunk/<store>
+    debug!("➡️ Step 3: Reading the history of {} store(s)", stores.len());
+    let mut digests = Vec::new();
+    let mut quiet = Vec::new();
+    for store in &stores {
+        if !repo.ref_exists(&format!("refs/trunk/{}", store)) {
+            error!("⚠️ Warning: refs/trunk/{} does not exist in the local repository; leaving store '{}' out", store, store);
+            continue;
+        }
+        match store_digest(&repo, store, since, verbose) {
+            Some(digest) => digests.push(digest),
+            None => quiet.push(store.clone()),
+        }
+    }
+    info!("✓ Step 3: {} store(s) with activity, {} without", digests.len(), quiet.len());
+
+    // Step 4: Output
+    let text = render(&digests, &quiet, since, args.format);
+    print!("{}", text);
+
+    // Step 5: Keep the digest in a store
+    if let Some(store) = &args.commit_to {
+        let dest = format!("{}/{}.{}", DIGESTS_DIR, Local::now().format("%Y-%m-%d"), if args.format == DigestFormat::Md { "md" } else { "txt" });
+        debug!("➡️ Step 5: Committing the digest to store '{}' as {}", store, dest);
+        put::put_content(&repo, remote_name, store, &dest, text.into_bytes(), &format!("Add the digest since {}", since.format("%Y-%m-%d")), verbose)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::process::Command;
+use clap::Parser;
+use log::{debug, info};
+use crate::config;
+use crate::commands::expose;
+use crate::ops;
+use crate::utils::{confirm, run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Throw away local store commits and changes and reset to refs/trunk/<store> on the remote", after_help = "Examples:\n  git trunk discard-local\n  git trunk discard-local --store blog --force")]
+pub struct DiscardLocalArgs {
+    #[arg(long, help = "Skip the confirmation prompt")]
+    force: bool,
+}
+
+fn git_lines(command: &mut Command, verbose: bool) -> Vec<String> {
+    run_git_command(command, verbose)
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).filter(|line| !line.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+pub fn run(args: &DiscardLocalArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let fetched_ref_name = format!("refs/trunk-fetch/{}", store_name);
+    let temp_store_ref = "refs/temp/trunk_discard";
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    let store_repo = GitRepo::open(&trunk_store_dir, verbose).ok();
+    let has_checkout = store_repo.is_some();
+
+    // Step 2: Fetch the remote store ref
+    debug!("📥 Step 2: Fetching {} from remote '{}'", trunk_ref_name, remote_name);
+    ops::fetch_store_ref(&repo, remote_name, store_name, &fetched_ref_name, verbose)
+        .map_err(|e| TrunkError::Git(format!("{}. There is no remote state to go back to.", e)))?;
+    let remote_hash = repo.resolve_commit(&fetched_ref_name).unwrap_or_default();
+    let remote_short = short_hash(repo_root, &remote_hash, verbose);
+    info!("✓ Step 2: Remote {} is at {}", trunk_ref_name, remote_short);
+    let cleanup = || {
+        let _ = repo.delete_ref(&fetched_ref_name);
+        if let Some(store_repo) = &store_repo {
+            let _ = store_repo.delete_ref(temp_store_ref);
+        }
+    };
+
+    // Step 3: Work out what would be lost
+    debug!("➡️ Step 3: Collecting local commits and changes that would be discarded");
+    let mut lost_commits = git_lines(
+        repo.git().arg("log").arg("--format=%h %s").arg(&trunk_ref_name).arg("--not").arg(&fetched_ref_name).arg("--"),
+        verbose,
+    );
+    let mut lost_changes: Vec<String> = Vec::new();
+    if let Some(store_repo) = &store_repo {
+        if store_repo.fetch_local(repo_root, &format!("+{}:{}", fetched_ref_name, temp_store_ref)).is_err() {
+            cleanup();
+            return Err(TrunkError::Io(format!("Failed to copy the remote {} into {}", trunk_ref_name, store_dir_relative_path)));
+        }
+        for commit in git_lines(store_repo.git().arg("log").arg("--format=%h %s").arg("HEAD").arg("--not").arg(temp_store_ref).arg("--"), verbose) {
+            if !lost_commits.contains(&commit) {
+                lost_commits.push(commit);
+            }
+        }
+        lost_changes = git_lines(store_repo.git().arg("status").arg("--porcelain").arg("--untracked-files=all"), verbose);
+    }
+    if lost_commits.is_empty() && lost_changes.is_empty() {
+        let in_sync = repo.resolve_commit(&trunk_ref_name) == Some(remote_hash.clone());
+        if in_sync {
+            cleanup();
+            info!("= Step 3: Store '{}' already matches remote '{}'; nothing to discard", store_name, remote_name);
+            return Ok(());
+        }
+    }
+
+    println!("\nResetting store '{}' to {} on '{}' ({}) discards:", store_name, trunk_ref_name, remote_name, remote_short);
+    if lost_commits.is_empty() {
+        println!("  No local commits.");
+    } else {
+        println!("  {} local commit(s):", lost_commits.len());
+        for commit in &lost_commits {
+            println!("    {}", commit);
+        }
+    }
+    if !has_checkout {
+        println!("  No checkout ({} will be left alone).", store_dir_relative_path);
+    } else if lost_changes.is_empty() {
+        println!("  No uncommitted changes in {}.", store_dir_relative_path);
+    } else {
+        println!("  {} uncommitted change(s) in {}:", lost_changes.len(), store_dir_relative_path);
+        for change in &lost_changes {
+            println!("    {}", change);
+        }
+    }
+    println!();
+
+    if !args.force {
+        let confirmed = confirm(&format!("Discard all of this for store '{}'? This cannot be undone.", store_name)).inspect_err(|_| cleanup())?;
+        if !confirmed {
+            cleanup();
+            info!("🚫 Step 3: Discard for store '{}' aborted by user", store_name);
+            return Ok(());
+        }
+    }
+
+    // Step 4: Reset refs/trunk/<store>
+    debug!("🔄 Step 4: Resetting {} to {}", trunk_ref_name, remote_hash);
+    if repo.update_ref(&trunk_ref_name, &remote_hash).is_err() {
+        cleanup();
+        return Err(TrunkError::Git(format!("Failed to reset {}", trunk_ref_name)));
+    }
+    info!("✓ Step 4: {} reset to the remote state", trunk_ref_name);
+    expose::sync_exposed_branch(&repo, store_name, verbose);
+
+    // Step 5: Reset the checkout
+    if let Some(store_repo) = &store_repo {
+        debug!("🔄 Step 5: Resetting {} to {}", store_dir_relative_path, remote_hash);
+        let reset_checkout = ["checkout -q -B main", "reset -q --hard", "clean -q -fd"].iter().all(|step| {
+            let mut command = store_repo.git();
+            command.args(step.split(' '));
+            if !step.starts_with("clean") {
+                command.arg(temp_store_ref);
+            }
+            run_git_command(&mut command, verbose).map(|output| output.status.success()).unwrap_or(false)
+        });
+        if !reset_checkout {
+            cleanup();
+            return Err(TrunkError::Git(format!("Failed to reset {}. Run `git trunk checkout --store {} --force` to recreate it.", store_dir_relative_path, store_name)));
+        }
+        info!("✓ Step 5: {} reset to the remote state", store_dir_relative_path);
+    }
+    cleanup();
+
+    info!("✅ Store '{}' now matches remote '{}'", store_name, remote_name);
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config;
+use crate::commands::hooks::{hooks_dir, managed_hook_stores, MANAGED_HOOK_NAMES};
+use crate::commands::init::{self, InitArgs};
+use crate::commands::maintenance;
+use crate::ops;
+use crate::utils::{confirm, list_worktrees, tracked_trunk_paths, untrack_trunk_paths};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Cross-check hooks, config and refs/trunk/* for inconsistencies", after_help = "Examples:\n  git trunk doctor\n  git trunk doctor --fix")]
+pub struct DoctorArgs {
+    #[arg(long, help = "Apply every suggested fix without prompting")]
+    pub fix: bool,
+}
+
+enum Fix {
+    UntrackTrunk,
+    RemoveHook { hook_name: String },
+    RemoveHookRegistration { store_name: String },
+    InitStore { store_name: String },
+    RemoveDuplicateCheckout { store_name: String, path: PathBuf },
+    AddConfigEntry { store_name: String },
+    RegisterMaintenance,
+    RestoreCheckout { store_name: String },
+}
+
+struct Issue {
+    description: String,
+    fix: Fix,
+}
+
+// True when a store checkout has no uncommitted changes and its HEAD is
+// already recorded in refs/trunk/<store>, so removing it loses nothing.
+fn checkout_is_disposable(repo: &GitRepo, store_dir: &Path, store_name: &str, verbose: bool) -> bool {
+    let Ok(store_repo) = GitRepo::open(store_dir, verbose) else { return false };
+    let clean = store_repo.status_porcelain().is_ok_and(|lines| lines.is_empty());
+    let published = store_repo
+        .resolve_commit("HEAD")
+        .is_some_and(|head| repo.is_ancestor(&head, &format!("refs/trunk/{}", store_name)));
+    clean && published
+}
+
+pub fn run(args: &DoctorArgs, remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Collect stores known to refs/trunk/*
+    debug!("➡️ Step 2: Collecting stores from refs/trunk/*");
+    let mut ref_stores: Vec<String> = Vec::new();
+    match repo.ref_names("refs/trunk/") {
+        Ok(names) => ref_stores.extend(names.into_iter().filter(|name| config::is_store_name(name))),
+        Err(e) => {
+            return Err(TrunkError::Git(format!("Failed to list refs/trunk/* in the main repository: {}", e)));
+        }
+    }
+    info!("✓ Step 2: Found {} store ref(s)", ref_stores.len());
+
+    // Step 3: Collect stores checked out under .trunk/
+    debug!("➡️ Step 3: Collecting stores from .trunk/");
+    let mut dir_stores: Vec<String> = Vec::new();
+    let trunk_base_dir = config::trunk_base_dir(repo_root, verbose);
+    if let Ok(entries) = fs::read_dir(&trunk_base_dir) {
+        for entry in entries.filter_map(Result::ok) {
+            if entry.path().is_dir() {
+                if let Some(name) = entry.file_name().to_str() {
+                    dir_stores.push(name.to_string());
+                }
+            }
+        }
+    }
+    info!("✓ Step 3: Found {} store directory(ies)", dir_stores.len());
+
+    // Step 4: Load config
+    debug!("➡️ Step 4: Loading config");
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let config_stores = trunk_config.store_names();
+    if trunk_config.exists {
+        info!("✓ Step 4: Config {} lists {} store(s)", trunk_config.path.display(), config_stores.len());
+    } else {
+        info!("= Step 4: No config file at {}", trunk_config.path.display());
+    }
+
+    let store_exists = |name: &String| ref_stores.contains(name) || dir_stores.contains(name);
+    let mut issues: Vec<Issue> = Vec::new();
+
+    // Step 5: Hooks referencing stores that no longer exist
+    debug!("➡️ Step 5: Checking git-trunk managed hooks");
+    let hooks_dir = hooks_dir(repo_root, verbose);
+    for hook_name in MANAGED_HOOK_NAMES {
+        if let Some(hook_stores) = managed_hook_stores(&hooks_dir.join(hook_name)) {
+            for hook_store in hook_stores.iter().filter(|s| !store_exists(s) && !config_stores.contains(s)) {
+                issues.push(Issue {
+                    description: format!("{} hook references store '{}' which has no ref, directory or config entry", hook_name, hook_store),
+                    fix: Fix::RemoveHook { hook_name: hook_name.to_string() },
+                });
+            }
+        }
+    }
+
+    for config_store in config_stores.iter().filter(|s| !store_exists(s)) {
+        let events = trunk_config.get_array(&format!("store.{}", config_store), "hooks");
+        if !events.is_empty() {
+            issues.push(Issue {
+                description: format!("hooks ({}) are registered for store '{}' which has no ref or directory", events.join(", "), config_store),
+                fix: Fix::RemoveHookRegistration { store_name: config_store.clone() },
+            });
+        }
+    }
+
+    // Step 6: Config listing stores that were never initialized
+    debug!("➡️ Step 6: Checking config stores against refs and directories");
+    for config_store in config_stores.iter().filter(|s| !store_exists(s)) {
+        issues.push(Issue {
+            description: format!("config lists store '{}' (line {}) but it was never initialized", config_store, trunk_config.section_line(&format!("store.{}", config_store)).unwrap_or(0)),
+            fix: Fix::InitStore { store_name: config_store.clone() },
+        });
+    }
+
+    // Step 7: Refs without a config entry (only meaningful once a config file is in use)
+    debug!("➡️ Step 7: Checking refs against config");
+    if trunk_config.exists {
+        for ref_store in ref_stores.iter().filter(|s| !config_stores.contains(s)) {
+            issues.push(Issue {
+                description: format!("refs/trunk/{} exists but has no [store.{}] config entry", ref_store, ref_store),
+                fix: Fix::AddConfigEntry { store_name: ref_store.clone() },
+            });
+        }
+    } else {
+        debug!("= Step 7: Skipped, no config file in use");
+    }
+
+    // Step 7a: Per-worktree store checkouts duplicating the shared one
+    debug!("➡️ Step 7a: Checking worktrees for duplicated store checkouts");
+    if trunk_config.get_string("trunk", "shared_trunk_dir").is_some() {
+        let shared_dir = fs::canonicalize(&trunk_base_dir).unwrap_or(trunk_base_dir.clone());
+        for worktree in list_worktrees(repo_root, verbose).unwrap_or_default() {
+            let worktree_trunk_dir = worktree.join(".trunk");
+            if !worktree_trunk_dir.is_dir() || fs::canonicalize(&worktree_trunk_dir).is_ok_and(|dir| dir == shared_dir) {
+                continue;
+            }
+            for entry in fs::read_dir(&worktree_trunk_dir).into_iter().flatten().filter_map(Result::ok) {
+                if !entry.path().is_dir() {
+                    continue;
+                }
+                let store_name = entry.file_name().to_string_lossy().to_string();
+                issues.push(Issue {
+                    description: format!("{} duplicates the shared checkout of store '{}' in {}", entry.path().display(), store_name, trunk_base_dir.display()),
+                    fix: Fix::RemoveDuplicateCheckout { store_name, path: entry.path() },
+                });
+            }
+        }
+    } else {
+        debug!("= Step 7a: Skipped, store checkouts are not shared across worktrees");
+    }
+
+    // Step 7b: .trunk paths tracked by the main repository
+    debug!("➡️ Step 7b: Checking the main repository index for .trunk paths");
+    match tracked_trunk_paths(repo_root, verbose) {
+        Ok(paths) if !paths.is_empty() => {
+            issues.push(Issue {
+                description: format!(".trunk is tracked by the main repository ({} path(s), e.g. {})", paths.len(), paths[0]),
+                fix: Fix::UntrackTrunk,
+            });
+        }
+        Ok(_) => debug!("= Step 7b: No .trunk paths tracked"),
+        Err(e) => error!("⚠️ Warning: Could not check the main repository index: {}", e),
+    }
+
+    // Step 7c: Trunk prefetch refspecs without a git maintenance registration
+    debug!("➡️ Step 7c: Checking git maintenance registration");
+    let prefetch_remotes = maintenance::prefetch_remotes(&repo, verbose);
+    if prefetch_remotes.is_empty() {
+        debug!("= Step 7c: Skipped, refs/trunk/* is not registered for prefetch");
+    } else if !maintenance::is_registered(&repo, verbose) {
+        issues.push(Issue {
+            description: format!("refs/trunk/* is set up for prefetch from {} but the repository is not registered with git maintenance", prefetch_remotes.join(", ")),
+            fix: Fix::RegisterMaintenance,
+        });
+    }
+
+    // Step 7d: Checkouts removed behind git-trunk's back whose refs survive
+    debug!("➡️ Step 7d: Checking for store checkouts that disappeared");
+    for store_name in ops::lost_checkouts(&repo, verbose) {
+        issues.push(Issue {
+            description: format!(".trunk/{} disappeared (e.g. `git stash --all` or `git clean -x`), but refs/trunk/{} still has its last commit", store_name, store_name),
+            fix: Fix::RestoreCheckout { store_name },
+        });
+    }
+
+    if issues.is_empty() {
+        info!("✅ Doctor found no inconsistencies");
+        return Ok(());
+    }
+
+    // Step 8: Report each issue and offer its fix
+    let mut fixed = 0;
+    let mut removed_hooks: Vec<String> = Vec::new();
+    for issue in &issues {
+        error!("⚠️ {}", issue.description);
+        let prompt = match &issue.fix {
+            Fix::UntrackTrunk => "Untrack .trunk (git rm --cached -r .trunk) and repair .gitignore?".to_string(),
+            Fix::RemoveHook { hook_name } => format!("Remove the git-trunk {} hook?", hook_name),
+            Fix::RemoveHookRegistration { store_name } => format!("Remove the hooks entry from [store.{}]?", store_name),
+            Fix::InitStore { store_name } => format!("Initialize store '{}'?", store_name),
+            Fix::RemoveDuplicateCheckout { path, .. } => format!("Remove the duplicate checkout {}?", path.display()),
+            Fix::AddConfigEntry { store_name } => format!("Add [store.{}] to the config?", store_name),
+            Fix::RegisterMaintenance => "Register the repository with git maintenance?".to_string(),
+            Fix::RestoreCheckout { store_name } => format!("Restore .trunk/{} from refs/trunk/{}?", store_name, store_name),
+        };
+        if !args.fix && !confirm(&prompt)? {
+            info!("= Step 8: Left as is");
+            continue;
+        }
+        match &issue.fix {
+            Fix::UntrackTrunk => {
+                if let Err(e) = untrack_trunk_paths(repo_root, "Step 8", verbose) {
+                    error!("❌ Failed to untrack .trunk: {}", e);
+                    continue;
+                }
+            }
+            Fix::RemoveHook { hook_name } => {
+                if !removed_hooks.contains(hook_name) {
+                    if let Err(e) = fs::remove_file(hooks_dir.join(hook_name)) {
+                        error!("❌ Failed to remove {} hook: {}", hook_name, e);
+                        continue;
+                    }
+                    removed_hooks.push(hook_name.clone());
+                }
+                info!("✓ Step 8: Removed {} hook", hook_name);
+            }
+            Fix::RemoveHookRegistration { store_name } => {
+                let current = config::load(repo_root, verbose).map_err(|e| TrunkError::Failed(format!("Failed to reload config: {}", e)))?;
+                if let Err(e) = config::set_value(&current, &format!("store.{}", store_name), "hooks", None) {
+                    error!("❌ Failed to update config: {}", e);
+                    continue;
+                }
+                info!("✓ Step 8: Removed hooks entry from [store.{}]", store_name);
+            }
+            Fix::InitStore { store_name } => {
+                if let Err(e) = init::run(&InitArgs { force: false, purpose: None, contact: None }, remote_name, store_name, verbose) {
+                    error!("❌ Failed to initialize store '{}': {}", store_name, e);
+                    continue;
+                }
+            }
+            Fix::RemoveDuplicateCheckout { store_name, path } => {
+                if !checkout_is_disposable(&repo, path, store_name, verbose) {
+                    error!("❌ {} has uncommitted or unpublished work; copy it into the shared checkout first, then remove it manually", path.display());
+                    continue;
+                }
+                if let Err(e) = fs::remove_dir_all(path) {
+                    error!("❌ Failed to remove {}: {}", path.display(), e);
+                    continue;
+                }
+                if let Some(parent) = path.parent() {
+                    let _ = fs::remove_dir(parent); // Only succeeds once the per-worktree .trunk is empty
+                }
+                info!("✓ Step 8: Removed duplicate checkout {}", path.display());
+            }
+            Fix::AddConfigEntry { store_name } => {
+                let current = config::load(repo_root, verbose).map_err(|e| TrunkError::Failed(format!("Failed to reload config: {}", e)))?;
+                if let Err(e) = config::add_store_section(&current, store_name) {
+                    error!("❌ Failed to update config: {}", e);
+                    continue;
+                }
+                info!("✓ Step 8: Added [store.{}] to {}", store_name, current.path.display());
+            }
+            Fix::RegisterMaintenance => {
+                if let Err(e) = maintenance::register_repository(&repo, verbose) {
+                    error!("❌ {}", e);
+                    continue;
+                }
+                info!("✓ Step 8: Registered the repository with git maintenance");
+            }
+            Fix::RestoreCheckout { store_name } => {
+                if let Err(e) = ops::restore_checkout(&repo, store_name, "Step 8", verbose) {
+                    error!("❌ Failed to restore .trunk/{}: {}", store_name, e);
+                    continue;
+                }
+                info!("✓ Step 8: Restored .trunk/{} from refs/trunk/{}", store_name, store_name);
+            }
+        }
+        fixed += 1;
+    }
+
+    if fixed < issues.len() {
+        return Err(TrunkError::Failed(format!("Doctor found {} issue(s), {} fixed", issues.len(), fixed)));
+    }
+    info!("✅ Doctor found {} issue(s), all fixed", issues.len());
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use clap::Parser;
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(about = "Print task-oriented recipes from the built-in cookbook", after_help = "Examples:\n  git trunk examples\n  git trunk examples diverged")]
+pub struct ExamplesArgs {
+    #[arg(help = "Recipe to print; omit to list the available topics")]
+    topic: Option<String>,
+}
+
+struct Recipe {
+    topic: &'static str,
+    aliases: &'static [&'static str],
+    summary: &'static str,
+    body: &'static str,
+}
+
+const COOKBOOK: &[Recipe] = &[
+    Recipe {
+        topic: "adopt",
+        aliases: &["adopt-docs", "existing"],
+        summary: "Move documents that already live on a branch into a store",
+        body: "\
+# Create the store and its checkout in .trunk/docs
+git trunk init --store docs
+
+# Move the documents over and take them off the branch
+mv notes .trunk/docs/
+git rm -r -q --cached notes
+git commit -m \"Move notes to the docs trunk store\"
+
+# Record them in refs/trunk/docs and publish the store
+git trunk commit --store docs -m \"Import notes\"
+git trunk push --store docs
+
+# Optional: tell readers of the branch where the documents went
+git trunk advertise",
+    },
+    Recipe {
+        topic: "share",
+        aliases: &["team", "clone"],
+        summary: "Publish a store and get it on a teammate's clone",
+        body: "\
+# On your machine: commit and push the store
+git trunk commit -m \"Update roadmap\"
+git trunk push
+
+# Keep it committed and pushed alongside your branches from now on
+git trunk hooks install
+
+# On a teammate's fresh clone: fetch refs/trunk/main into .trunk/main
+git trunk checkout
+# (or every store the remote has: git trunk checkout --all)
+git trunk hooks install
+
+# Later, pick up each other's changes
+git trunk pull
+git trunk status --all",
+    },
+    Recipe {
+        topic: "ci",
+        aliases: &["publish", "ci-publish"],
+        summary: "Publish generated files to a store from a CI job",
+        body: "\
+# The job needs push access to the remote. Never wait on a prompt
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Fetch the store into .trunk/site
+git trunk checkout --store site --force
+
+# Replace the store contents with the build output
+find .trunk/site -mindepth 1 -maxdepth 1 ! -name .git -exec rm -rf {} +
+cp -R build/site/. .trunk/site/
+
+# Commit without prompts and push
+git trunk commit --store site --force -m \"Publish site for $CI_COMMIT_SHA\"
+git trunk push --store site
+
+# For a nightly re-index of every store, generate a whole scheduled pipeline
+git trunk ci refresh-job --output .github/workflows/trunk-refresh.yml
+
+# Publish a dashboard of every store for readers without git-trunk
+git trunk report --output public/trunk-report.html",
+    },
+    Recipe {
+        topic: "diverged",
+        aliases: &["recover", "divergence"],
+        summary: "Recover when the local store and the remote have diverged",
+        body: "\
+# See where .trunk/<store>, refs/trunk/<store> and the remote stand
+git trunk status
+git trunk diff --remote-ref
+
+# Keep your work: merge the remote changes into the checkout
+git trunk pull
+# ...resolve conflicts in .trunk/<store> if asked, then
+git trunk commit
+git trunk push
+
+# Or replay your commits on top of the remote ones for a linear history
+git trunk pull --rebase
+# ...after resolving and `git add`ing conflicts in .trunk/<store>
+git trunk pull --continue
+git trunk push
+
+# Or give up local work and take what the team has
+git trunk discard-local",
+    },
+    Recipe {
+        topic: "review",
+        aliases: &["history"],
+        summary: "Review what changed in a store",
+        body: "\
+# Recent commits
+git trunk log --oneline -n 20 --since \"1 week ago\"
+
+# Documents added, changed or removed in the last 10 commits
+git trunk changelog --from ~10 --group --messages
+
+# Word-level prose diff between two store revisions, as HTML
+git trunk diff HEAD~3 HEAD --html review.html",
+    },
+];
+
+fn find(topic: &str) -> Option<&'static Recipe> {
+    let topic = topic.to_lowercase();
+    COOKBOOK.iter().find(|recipe| recipe.topic == topic || recipe.aliases.contains(&topic.as_str()))
+}
+
+fn print_topics() {
+    println!("Recipes (run `git trunk examples <topic>`):\n");
+    for recipe in COOKBOOK {
+        println!("  {:<10} {}", recipe.topic, recipe.summary);
+    }
+}
+
+pub fn run(args: &ExamplesArgs, _remote_name: &str, _store_name: &str, _verbose: bool) -> Result<(), TrunkError> {
+    let Some(topic) = &args.topic else {
+        print_topics();
+        return Ok(());
+    };
+    match find(topic) {
+        Some(recipe) => println!("# {}\n\n{}", recipe.summary, recipe.body),
+        None => {
+            print_topics();
+            return Err(TrunkError::NotFound(format!("No recipe named '{}'", topic)));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use clap::Parser;
+use crate::table::print_line;
+use crate::utils::take_plan;
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Print the git operations a command would perform here, with resolved refs and paths, without changing anything",
+    after_help = "Explains the commands --dry-run supports: init, commit, checkout, push, delete, stegano, hooks and release-notes.\n\nExamples:\n  git trunk explain checkout --store docs\n  git trunk explain push\n  git trunk explain delete --store scratch"
+)]
+pub struct ExplainArgs {
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true, value_name = "COMMAND", help = "Command to explain, with its arguments")]
+    command: Vec<String>,
+}
+
+// Prints the operation plan the dry run of `command_line` recorded. A failed
+// command stops where it failed, so the plan shows what would run up to there.
+pub fn print_plan(command_line: &str, result: &Result<(), TrunkError>) {
+    let plan = take_plan();
+    match (plan.is_empty(), result.is_ok()) {
+        (true, true) => print_line(&format!("`{}` would change nothing in this repository", command_line)),
+        (true, false) => print_line(&format!("`{}` would fail with the error below before changing anything", command_line)),
+        (false, _) => {
+            print_line(&format!("`{}` would, in order:", command_line));
+            for (number, step) in plan.iter().enumerate() {
+                print_line(&format!("{:>4}. {}", number + 1, step));
+            }
+            print_line(if result.is_ok() { "Nothing was changed." } else { "It would then fail with the error below; nothing was changed." });
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use clap::{Parser, ValueEnum};
+use log::{debug, info};
+use crate::ops;
+use crate::quota::format_size;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    // `git bundle` of refs/trunk/<store> with its whole history
+    Bundle,
+    // `git archive` of the files at refs/trunk/<store>
+    Tar,
+    Zip,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Bundle => "bundle",
+            ExportFormat::Tar => "tar",
+            ExportFormat::Zip => "zip",
+        }
+    }
+
+    // The format an output file name asks for, e.g. `handbook.zip`.
+    fn from_path(path: &Path) -> Option<ExportFormat> {
+        match path.extension()?.to_str()? {
+            "bundle" => Some(ExportFormat::Bundle),
+            "tar" => Some(ExportFormat::Tar),
+            "zip" => Some(ExportFormat::Zip),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Package a store into one file: its whole history as a git bundle, or its files as a tar or zip archive",
+    after_help = "Without --format, the format follows the extension of --output, else bundle. A bundle holds refs/trunk/<store> and can be brought back with `git trunk restore-backup <file>`; archives hold the files of the store's current commit under a <store>/ directory.\n\nExamples:\n  git trunk --store handbook export\n  git trunk --store handbook export --format zip --output handbook-2025.zip\n  git trunk --store 'docs-*' export --format tar"
+)]
+pub struct ExportArgs {
+    #[arg(long, value_enum, help = "What to write [default: from the --output extension, else bundle]")]
+    format: Option<ExportFormat>,
+    #[arg(long, short = 'o', value_name = "FILE", help = "File to write, relative to the current directory [default: <store>.<format> in the current directory]")]
+    output: Option<PathBuf>,
+    #[arg(long, help = "Overwrite the output file if it exists")]
+    force: bool,
+}
+
+pub fn run(args: &ExportArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    // Step 2: Check the store and the output file
+    debug!("➡️ Step 2: Checking {} and the output file", trunk_ref_name);
+    let Some(tip) = repo.resolve_commit(&trunk_ref_name) else {
+        return Err(TrunkError::NotFound(format!(
+            "{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.",
+            trunk_ref_name, store_name, store_name
+        )));
+    };
+    let format = args.format.or_else(|| args.output.as_deref().and_then(ExportFormat::from_path)).unwrap_or(ExportFormat::Bundle);
+    let output = args.output.clone().unwrap_or_else(|| PathBuf::from(format!("{}.{}", store_name, format.extension())));
+    // git runs in the repository root, so resolve the file where the user is
+    let output = std::env::current_dir().map(|cwd| cwd.join(&output)).unwrap_or(output);
+    if output.is_dir() {
+        return Err(TrunkError::Usage(format!("{} is a directory; give a file name with --output", output.display())));
+    }
+    if output.exists() && !args.force {
+        return Err(TrunkError::Conflict(format!("{} already exists; pass --force to overwrite it", output.display())));
+    }
+    info!("✓ Step 2: Exporting {} at {} as {}", trunk_ref_name, short_hash(repo_root, &tip, verbose), format.extension());
+
+    // Step 3: Write the file
+    debug!("📦 Step 3: Writing {}", output.display());
+    let mut command = repo.git();
+    match format {
+        ExportFormat::Bundle => command.arg("bundle").arg("create").arg(&output).arg(&trunk_ref_name),
+        ExportFormat::Tar | ExportFormat::Zip => command
+            .arg("archive")
+            .arg(format!("--format={}", format.extension()))
+            .arg(format!("--prefix={}/", store_name))
+            .arg("-o")
+            .arg(&output)
+            .arg(&tip),
+    };
+    let result = run_git_command(&mut command, verbose).map_err(|e| TrunkError::Git(format!("Failed to run git: {}", e)))?;
+    if !result.status.success() {
+        let _ = fs::remove_file(&output);
+        return Err(TrunkError::Git(format!("Failed to write {} (run with --verbose for git's message)", output.display())));
+    }
+    let size = fs::metadata(&output).map(|metadata| metadata.len()).unwrap_or(0);
+    info!("✓ Step 3: Wrote {} ({})", output.display(), format_size(size));
+
+    info!("✅ Exported store '{}' to {}", store_name, output.display());
+    Ok(())
+}
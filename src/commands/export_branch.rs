@@ -0,0 +1,125 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config::{self, ConfigValue};
+use crate::commands::expose;
+use crate::ops;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Publish a store's history as a regular branch, locally and on the remote",
+    after_help = "For hosting platforms and tools that cannot see refs/trunk/*, such as static-site publishing from a gh-pages branch. The branch is recorded as [store.<name>] export_branch, so later runs without --branch bring it up to date with the store. It only ever fast-forwards: commits made on the branch itself stop the export until they are moved into the store, or dropped with --force.\n\nExamples:\n  git trunk export-branch --store site --branch gh-pages\n  git trunk export-branch --store site\n  git trunk export-branch --store docs --branch docs --local-only"
+)]
+pub struct ExportBranchArgs {
+    #[arg(long, help = "Branch to publish the store as [default: the one recorded by the last run]")]
+    branch: Option<String>,
+    #[arg(long, help = "Only update the local branch, without pushing it")]
+    local_only: bool,
+    #[arg(long, help = "Replace the branch even if it has commits that are not in the store")]
+    force: bool,
+}
+
+pub fn run(args: &ExportBranchArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let section = format!("store.{}", store_name);
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    // Step 2: Check the store and the branch
+    debug!("➡️ Step 2: Checking {} and the branch to export it to", trunk_ref_name);
+    let Some(tip) = repo.resolve_commit(&trunk_ref_name) else {
+        return Err(TrunkError::NotFound(format!("{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` first.", trunk_ref_name, store_name, store_name)));
+    };
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let recorded = trunk_config.get_string(&section, "export_branch");
+    let Some(branch) = args.branch.clone().or_else(|| recorded.clone()) else {
+        return Err(TrunkError::Usage(format!("Store '{}' has no export branch yet; name one with --branch", store_name)));
+    };
+    let valid = run_git_command(repo.git().arg("check-ref-format").arg("--branch").arg(&branch), verbose)
+        .is_ok_and(|output| output.status.success());
+    if !valid {
+        return Err(TrunkError::Usage(format!("'{}' is not a valid branch name", branch)));
+    }
+    let branch_ref = format!("refs/heads/{}", branch);
+    let local = repo.resolve_commit(&branch_ref);
+    if let Some(local) = &local {
+        if *local != tip && !repo.is_ancestor(local, &tip) && !args.force {
+            return Err(TrunkError::Conflict(format!(
+                "Branch {} has commits that are not in store '{}' (branch at {}, store at {}). Move them into the store, or replace them with --force.",
+                branch, store_name, short_hash(repo_root, local, verbose), short_hash(repo_root, &tip, verbose)
+            )));
+        }
+    }
+    if local.as_deref() != Some(tip.as_str()) {
+        if let Some(worktree) = expose::checked_out_in(&repo, &branch, verbose) {
+            return Err(TrunkError::Conflict(format!("Branch {} is checked out in {}; switch that worktree to another branch first", branch, worktree)));
+        }
+    }
+    info!("✓ Step 2: Exporting {} at {} as branch {}", trunk_ref_name, short_hash(repo_root, &tip, verbose), branch);
+
+    // Step 3: Record the branch so later runs keep it in sync
+    if recorded.as_deref() == Some(branch.as_str()) {
+        info!("= Step 3: Branch {} is already recorded for store '{}'", branch, store_name);
+    } else {
+        debug!("📝 Step 3: Recording export_branch = {} for store '{}'", branch, store_name);
+        config::set_value(&trunk_config, &section, "export_branch", Some(&ConfigValue::String(branch.clone()))).map_err(|e| TrunkError::Io(format!("Failed to update config: {}", e)))?;
+        info!("✓ Step 3: Recorded [{}] export_branch = \"{}\"", section, branch);
+    }
+
+    // Step 4: Move the local branch
+    debug!("➡️ Step 4: Pointing {} at {}", branch_ref, trunk_ref_name);
+    if local.as_deref() == Some(tip.as_str()) {
+        info!("= Step 4: Branch {} is already at {}", branch, short_hash(repo_root, &tip, verbose));
+    } else {
+        repo.update_ref(&branch_ref, &tip).map_err(|e| TrunkError::Git(format!("Failed to update branch {}: {}", branch, e)))?;
+        info!("✓ Step 4: Branch {} is at {}", branch, short_hash(repo_root, &tip, verbose));
+    }
+
+    // Step 5: Push the branch
+    if args.local_only {
+        info!("= Step 5: --local-only, not pushing branch {}", branch);
+        info!("✅ Store '{}' is exported as local branch {}", store_name, branch);
+        return Ok(());
+    }
+    debug!("➡️ Step 5: Comparing branch {} with remote '{}'", branch, remote_name);
+    let listing = run_git_command(repo.git().arg("ls-remote").arg(remote_name).arg(&branch_ref), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to list remote '{}': {}", remote_name, e)))?;
+    if !listing.status.success() {
+        return Err(TrunkError::Git(format!("Failed to list branch {} on remote '{}' (run with --verbose for git's message)", branch, remote_name)));
+    }
+    let remote_tip = String::from_utf8_lossy(&listing.stdout)
+        .lines()
+        .find_map(|line| line.split_once('\t').filter(|(_, name)| *name == branch_ref).map(|(hash, _)| hash.to_string()));
+    match &remote_tip {
+        Some(remote_tip) if *remote_tip == tip => {
+            info!("= Step 5: Remote '{}' already has branch {} at {}", remote_name, branch, short_hash(repo_root, &tip, verbose));
+            info!("✅ Store '{}' is exported as branch {}", store_name, branch);
+            return Ok(());
+        }
+        // A remote commit missing locally cannot be an ancestor of the store
+        Some(remote_tip) if !repo.is_ancestor(remote_tip, &tip) => {
+            if !args.force {
+                return Err(TrunkError::Conflict(format!(
+                    "Branch {} on remote '{}' has commits that are not in store '{}'. Fetch it and move them into the store, or replace them with --force.",
+                    branch, remote_name, store_name
+                )));
+            }
+            error!("⚠️ Warning: --force: replacing branch {} on remote '{}' and the commits only it has", branch, remote_name);
+        }
+        _ => {}
+    }
+    debug!("📤 Step 5: Pushing {} to branch {} on remote '{}'", trunk_ref_name, branch, remote_name);
+    let refspec = format!("{}{}:{}", if args.force { "+" } else { "" }, trunk_ref_name, branch_ref);
+    let output = run_git_command(repo.git().arg("push").arg(remote_name).arg(&refspec), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to push branch {} to remote '{}': {}", branch, remote_name, e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("Failed to push branch {} to remote '{}' (run with --verbose for git's message)", branch, remote_name)));
+    }
+    info!("✓ Step 5: Pushed branch {} to remote '{}'", branch, remote_name);
+
+    info!("✅ Store '{}' is exported as branch {} on remote '{}'; run `git trunk export-branch --store {}` again after new commits", store_name, branch, remote_name, store_name);
+    Ok(())
+}
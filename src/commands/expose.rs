@@ -0,0 +1,104 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config::{self, ConfigValue};
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Mirror refs/trunk/<store> into a local branch for tools that only understand branches", after_help = "Examples:\n  git trunk expose\n  git trunk expose --store blog --as-branch docs/blog")]
+pub struct ExposeArgs {
+    #[arg(long = "as-branch", help = "Branch that mirrors the store [default: trunk/<store>]")]
+    as_branch: Option<String>,
+}
+
+// Worktree that has `branch` checked out, if any; moving such a branch would
+// change that worktree's HEAD under its feet.
+pub fn checked_out_in(repo: &GitRepo, branch: &str, verbose: bool) -> Option<String> {
+    let output = run_git_command(repo.git().arg("for-each-ref").arg("--format=%(worktreepath)").arg(format!("refs/heads/{}", branch)), verbose).ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!path.is_empty()).then_some(path)
+}
+
+// Points the branch exposing `store_name` (`[store.<name>] expose_branch`) at
+// refs/trunk/<store>. Called after every command that moves the store ref; a
+// failure only warns, since the store itself was updated.
+pub fn sync_exposed_branch(repo: &GitRepo, store_name: &str, verbose: bool) {
+    let Ok(trunk_config) = config::load(repo.root(), verbose) else { return };
+    let Some(branch) = trunk_config.get_string(&format!("store.{}", store_name), "expose_branch") else { return };
+    let branch_ref = format!("refs/heads/{}", branch);
+    let Some(store_hash) = repo.resolve_commit(&format!("refs/trunk/{}", store_name)) else { return };
+    if repo.resolve_commit(&branch_ref).as_deref() == Some(store_hash.as_str()) {
+        debug!("= Branch {} already mirrors refs/trunk/{}", branch, store_name);
+        return;
+    }
+    if let Some(worktree) = checked_out_in(repo, &branch, verbose) {
+        error!("⚠️ Warning: Branch {} is checked out in {}; not moving it to refs/trunk/{}", branch, worktree, store_name);
+        return;
+    }
+    match repo.update_ref(&branch_ref, &store_hash) {
+        Ok(()) => info!("✓ Branch {} now mirrors refs/trunk/{} at {}", branch, store_name, short_hash(repo.root(), &store_hash, verbose)),
+        Err(e) => error!("⚠️ Warning: Failed to update branch {}: {}", branch, e),
+    }
+}
+
+pub fn run(args: &ExposeArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let branch = args.as_branch.clone().unwrap_or_else(|| format!("trunk/{}", store_name));
+    let branch_ref = format!("refs/heads/{}", branch);
+
+    // Step 2: The store needs a ref to mirror
+    debug!("➡️ Step 2: Checking {}", trunk_ref_name);
+    if !repo.ref_exists(&trunk_ref_name) {
+        return Err(TrunkError::NotFound(format!("{} does not exist. Run `git trunk commit --store {}` or `git trunk checkout --store {}` first.", trunk_ref_name, store_name, store_name)));
+    }
+    info!("✓ Step 2: {} found", trunk_ref_name);
+
+    // Step 3: Check the branch name and that it is free to take over
+    debug!("➡️ Step 3: Checking branch name {}", branch);
+    let valid = run_git_command(repo.git().arg("check-ref-format").arg("--branch").arg(&branch), verbose)
+        .is_ok_and(|output| output.status.success());
+    if !valid {
+        return Err(TrunkError::Usage(format!("'{}' is not a valid branch name", branch)));
+    }
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let section = format!("store.{}", store_name);
+    let current = trunk_config.get_string(&section, "expose_branch");
+    for other_store in trunk_config.store_names().iter().filter(|name| name.as_str() != store_name) {
+        if trunk_config.get_string(&format!("store.{}", other_store), "expose_branch").as_deref() == Some(branch.as_str()) {
+            return Err(TrunkError::Conflict(format!("Branch {} already mirrors store '{}'", branch, other_store)));
+        }
+    }
+    if current.as_deref() != Some(branch.as_str()) && repo.ref_exists(&branch_ref) {
+        return Err(TrunkError::Conflict(format!("Branch {} already exists; pick another name with --as-branch", branch)));
+    }
+    info!("✓ Step 3: Branch {} can mirror store '{}'", branch, store_name);
+
+    // Step 4: Record the branch so commit, checkout, pull and discard-local keep it updated
+    if current.as_deref() == Some(branch.as_str()) {
+        info!("= Step 4: Store '{}' is already exposed as {}", store_name, branch);
+    } else {
+        if let Some(previous) = &current {
+            debug!("🧹 Step 4: Deleting previously exposed branch {}", previous);
+            if checked_out_in(&repo, previous, verbose).is_none() {
+                let _ = repo.delete_ref(&format!("refs/heads/{}", previous));
+            }
+        }
+        debug!("📝 Step 4: Recording expose_branch = {} for store '{}'", branch, store_name);
+        config::set_value(&trunk_config, &section, "expose_branch", Some(&ConfigValue::String(branch.clone()))).map_err(|e| TrunkError::Io(format!("Failed to update config: {}", e)))?;
+        info!("✓ Step 4: Recorded [{}] expose_branch = \"{}\"", section, branch);
+    }
+
+    // Step 5: Create or move the branch
+    debug!("➡️ Step 5: Pointing {} at {}", branch, trunk_ref_name);
+    sync_exposed_branch(&repo, store_name, verbose);
+
+    info!("✅ Store '{}' is exposed as branch {}; treat it as read-only, it follows {}", store_name, branch, trunk_ref_name);
+    Ok(())
+}
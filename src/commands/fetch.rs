@@ -0,0 +1,195 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::commands::maintenance::prefetch_refspec;
+use crate::config;
+use crate::ops;
+use crate::table::{Cell, Color, Table};
+use crate::utils::{dry_run, run_git_change, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Update refs/trunk/<store> from the remote without touching .trunk/<store>", after_help = "Examples:\n  git trunk fetch\n  git trunk fetch --store blog --remote upstream\n  git trunk fetch --all --prune")]
+pub struct FetchArgs {
+    #[arg(long, help = "Fetch every store the remote has")]
+    all: bool,
+    #[arg(long, help = "Delete local refs of stores the remote no longer has, unless they hold unpushed commits or are checked out")]
+    prune: bool,
+    #[arg(long, requires = "all", help = "With --all, print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+// What fetching did to one store's local ref.
+enum Change {
+    Created(String),
+    UpToDate,
+    FastForwarded(String, String),
+    // The remote moved on, but .trunk/<store> is checked out: `pull` has to
+    // merge the commits there, or the next `commit` would drop them.
+    CheckedOut(usize),
+    Ahead(usize),
+    Diverged(usize, usize),
+    NotOnRemote,
+    Pruned,
+    Kept(&'static str),
+}
+
+impl Change {
+    fn describe(&self) -> String {
+        match self {
+            Change::Created(to) => format!("new at {}", to),
+            Change::UpToDate => "up to date".to_string(),
+            Change::FastForwarded(from, to) => format!("fast-forwarded {}..{}", from, to),
+            Change::CheckedOut(behind) => format!("{} new commit(s); `git trunk pull` brings them into the checkout", behind),
+            Change::Ahead(ahead) => format!("{} unpushed commit(s), nothing new on the remote", ahead),
+            Change::Diverged(ahead, behind) => format!("diverged ({} local, {} remote commit(s)); `git trunk pull` merges them", ahead, behind),
+            Change::NotOnRemote => "not on the remote".to_string(),
+            Change::Pruned => "pruned".to_string(),
+            Change::Kept(reason) => format!("kept, {}", reason),
+        }
+    }
+
+    fn cell(&self) -> Cell {
+        let color = match self {
+            Change::Created(_) | Change::FastForwarded(..) | Change::Pruned => Color::Green,
+            Change::UpToDate | Change::Ahead(_) => return self.describe().into(),
+            Change::CheckedOut(_) | Change::Diverged(..) | Change::NotOnRemote | Change::Kept(_) => Color::Yellow,
+        };
+        Cell::colored(self.describe(), color)
+    }
+}
+
+// Moves refs/trunk/<store> to the fetched remote tip when that loses nothing.
+fn update_store(repo: &GitRepo, remote_name: &str, store_name: &str, verbose: bool) -> Result<Change, TrunkError> {
+    let repo_root = repo.root();
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let mirror_ref_name = format!("refs/trunk-remotes/{}/{}", remote_name, store_name);
+    let Some(remote_hash) = repo.resolve_commit(&mirror_ref_name) else {
+        // Only under --dry-run, where nothing was fetched
+        return Ok(Change::UpToDate);
+    };
+    let remote_short = short_hash(repo_root, &remote_hash, verbose);
+    let Some(local_hash) = repo.resolve_commit(&trunk_ref_name) else {
+        if !dry_run(&format!("create {} at {}", trunk_ref_name, remote_short)) {
+            repo.update_ref(&trunk_ref_name, &remote_hash).map_err(|e| TrunkError::Git(format!("Failed to create {}: {}", trunk_ref_name, e)))?;
+        }
+        return Ok(Change::Created(remote_short));
+    };
+    if local_hash == remote_hash {
+        return Ok(Change::UpToDate);
+    }
+    let (ahead, behind) = repo
+        .ahead_behind(&local_hash, &remote_hash)
+        .ok_or_else(|| TrunkError::Git(format!("Failed to compare {} with {}", trunk_ref_name, mirror_ref_name)))?;
+    if behind == 0 {
+        return Ok(Change::Ahead(ahead));
+    }
+    if ahead > 0 {
+        return Ok(Change::Diverged(ahead, behind));
+    }
+    if ops::checked_out_stores(repo_root, verbose).iter().any(|store| store == store_name) {
+        return Ok(Change::CheckedOut(behind));
+    }
+    let local_short = short_hash(repo_root, &local_hash, verbose);
+    if !dry_run(&format!("fast-forward {} to {}", trunk_ref_name, remote_short)) {
+        repo.update_ref(&trunk_ref_name, &remote_hash).map_err(|e| TrunkError::Git(format!("Failed to update {}: {}", trunk_ref_name, e)))?;
+    }
+    Ok(Change::FastForwarded(local_short, remote_short))
+}
+
+// Deletes the local ref of a store the remote no longer has, as long as every
+// commit on it was last seen on the remote and nothing is checked out.
+fn prune_store(repo: &GitRepo, remote_name: &str, store_name: &str, verbose: bool) -> Result<Change, TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let mirror_ref_name = format!("refs/trunk-remotes/{}/{}", remote_name, store_name);
+    let local_hash = repo.resolve_commit(&trunk_ref_name);
+    let last_seen = repo.resolve_commit(&mirror_ref_name);
+    if ops::checked_out_stores(repo.root(), verbose).iter().any(|store| store == store_name) {
+        return Ok(Change::Kept("checked out in .trunk"));
+    }
+    if let Some(local_hash) = &local_hash {
+        if !last_seen.as_ref().is_some_and(|seen| repo.is_ancestor(local_hash, seen)) {
+            return Ok(Change::Kept("has commits that were never on the remote"));
+        }
+    }
+    if !dry_run(&format!("delete {} and {}", trunk_ref_name, mirror_ref_name)) {
+        repo.delete_ref(&trunk_ref_name).map_err(|e| TrunkError::Git(format!("Failed to delete {}: {}", trunk_ref_name, e)))?;
+        repo.delete_ref(&mirror_ref_name).map_err(|e| TrunkError::Git(format!("Failed to delete {}: {}", mirror_ref_name, e)))?;
+    }
+    Ok(Change::Pruned)
+}
+
+pub fn run(args: &FetchArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    ops::check_foreign_refs(&repo, "Step 1b", verbose)?;
+
+    // Step 2: List the remote's stores
+    debug!("➡️ Step 2: Listing refs/trunk/* on remote '{}'", remote_name);
+    let remote_stores = ops::remote_store_names(&repo, remote_name, verbose)?;
+    info!("✓ Step 2: Remote '{}' has {} store(s)", remote_name, remote_stores.len());
+    let on_remote = |store: &String| remote_stores.contains(store);
+
+    let mut stores = if args.all { remote_stores.clone() } else { vec![store_name.to_string()] };
+    if args.all && args.prune {
+        stores.extend(repo.ref_names("refs/trunk/").unwrap_or_default().into_iter().filter(|name| config::is_store_name(name)));
+        stores.sort();
+        stores.dedup();
+    }
+    if !args.all && !on_remote(&stores[0]) && !args.prune {
+        return Err(TrunkError::NotFound(format!("refs/trunk/{} not found on remote '{}'", store_name, remote_name)));
+    }
+    if stores.is_empty() {
+        info!("= No stores on remote '{}'", remote_name);
+        return Ok(());
+    }
+
+    // Step 3: Fetch into refs/trunk-remotes/<remote>/<store>, the same refs
+    // background maintenance prefetches into
+    let mut refspecs: Vec<String> = stores
+        .iter()
+        .filter(|store| on_remote(store))
+        .map(|store| prefetch_refspec(remote_name).replace('*', store))
+        .collect();
+    let fetched = refspecs.len();
+    if fetched > 0 {
+        // The locks taken with `git trunk lock` come along; a pattern matching
+        // nothing is not an error
+        refspecs.push("+refs/trunk-locks/*:refs/trunk-locks/*".to_string());
+        debug!("📥 Step 3: Fetching {} store(s) from remote '{}'", fetched, remote_name);
+        let output = run_git_change(repo.git().arg("fetch").arg(remote_name).args(&refspecs), verbose)
+            .map_err(|e| TrunkError::Git(format!("Failed to fetch from remote '{}': {}", remote_name, e)))?;
+        if !output.status.success() {
+            return Err(TrunkError::Git(format!("Failed to fetch from remote '{}': {}", remote_name, String::from_utf8_lossy(&output.stderr).trim())));
+        }
+        info!("✓ Step 3: Fetched {} store(s) from remote '{}'", fetched, remote_name);
+    }
+
+    // Step 4: Move the local refs where that loses nothing
+    let mut table = Table::new(&["Store", "Change"]);
+    let mut updated = 0;
+    for store in &stores {
+        let change = if on_remote(store) {
+            update_store(&repo, remote_name, store, verbose)?
+        } else if args.prune {
+            prune_store(&repo, remote_name, store, verbose)?
+        } else {
+            Change::NotOnRemote
+        };
+        match &change {
+            Change::Created(_) | Change::FastForwarded(..) | Change::Pruned => {
+                updated += 1;
+                info!("✓ Step 4: refs/trunk/{}: {}", store, change.describe());
+            }
+            Change::UpToDate | Change::Ahead(_) => info!("= Step 4: refs/trunk/{}: {}", store, change.describe()),
+            _ => error!("⚠️ Warning: refs/trunk/{}: {}", store, change.describe()),
+        }
+        table.add_row(vec![store.as_str().into(), change.cell()]);
+    }
+    if args.all {
+        println!();
+        table.print(args.no_table);
+    }
+    info!("✅ Updated {} of {} local store ref(s) from remote '{}'", updated, stores.len(), remote_name);
+    Ok(())
+}
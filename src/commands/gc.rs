@@ -0,0 +1,140 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config;
+use crate::ops;
+use crate::quota::format_size;
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Drop objects that deleted or rewritten stores left behind, in the main repository and every store checkout",
+    after_help = "In the main repository --prune only applies to the reflogs of refs/trunk* refs; the reflogs of your branches follow gc.reflogExpire as usual. Stores in the trash (refs/trunk-trash) and backups (refs/trunk-backup) still hold their objects; run `git trunk trash purge` first to let them go.\n\nExamples:\n  git trunk gc\n  git trunk gc --aggressive\n  git trunk gc --prune now"
+)]
+pub struct GcArgs {
+    #[arg(long, help = "Pass --aggressive to git gc: slower, packs tighter")]
+    aggressive: bool,
+    #[arg(long, value_name = "DATE", help = "Prune unreachable objects, and the reflog entries of trunk refs and store checkouts, older than DATE, e.g. \"now\" or \"1.week.ago\" [default: git's gc.pruneExpire and gc.reflogExpireUnreachable]")]
+    prune: Option<String>,
+}
+
+// Loose and packed object bytes, as `git count-objects -v` reports them.
+fn object_bytes(repo: &GitRepo, verbose: bool) -> u64 {
+    let Ok(output) = run_git_command(repo.git().arg("count-objects").arg("-v"), verbose) else { return 0 };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .filter(|(key, _)| matches!(*key, "size" | "size-pack" | "size-garbage"))
+        .filter_map(|(_, kib)| kib.trim().parse::<u64>().ok())
+        .sum::<u64>()
+        * 1024
+}
+
+// Expires the reflog entries of `refs` that no longer point into their
+// history, which is what keeps rewritten and deleted commits alive. Without
+// --prune git's gc.reflogExpireUnreachable (30 days by default) decides.
+fn expire_reflogs(repo: &GitRepo, refs: &[String], prune: Option<&str>, verbose: bool) -> Result<(), TrunkError> {
+    if refs.is_empty() {
+        return Ok(());
+    }
+    let mut expire = repo.git();
+    expire.arg("reflog").arg("expire");
+    if let Some(prune) = prune {
+        expire.arg(format!("--expire-unreachable={}", prune));
+    }
+    let output = run_git_command(expire.args(refs), verbose).map_err(|e| TrunkError::Git(format!("Failed to run git reflog expire: {}", e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git reflog expire failed in {} (run with --verbose for git's message)", repo.root().display())));
+    }
+    Ok(())
+}
+
+fn run_gc(repo: &GitRepo, args: &GcArgs, verbose: bool) -> Result<(), TrunkError> {
+    let mut gc = repo.git();
+    gc.arg("gc").arg("--quiet");
+    if args.aggressive {
+        gc.arg("--aggressive");
+    }
+    if let Some(prune) = &args.prune {
+        gc.arg(format!("--prune={}", prune));
+    }
+    let output = run_git_command(&mut gc, verbose).map_err(|e| TrunkError::Git(format!("Failed to run git gc: {}", e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git gc failed in {} (run with --verbose for git's message)", repo.root().display())));
+    }
+    Ok(())
+}
+
+// Every ref of `repo` with a reflog whose name passes `keep`, for `git reflog
+// expire`, which stops at refs without one.
+fn refs_matching(repo: &GitRepo, keep: impl Fn(&str) -> bool, verbose: bool) -> Vec<String> {
+    let names: Vec<String> = run_git_command(repo.git().arg("for-each-ref").arg("--format=%(refname)"), verbose)
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().chain(["HEAD"]).filter(|name| keep(name)).map(str::to_string).collect())
+        .unwrap_or_default();
+    names
+        .into_iter()
+        .filter(|name| run_git_command(repo.git().arg("reflog").arg("exists").arg(name), verbose).is_ok_and(|output| output.status.success()))
+        .collect()
+}
+
+pub fn run(args: &GcArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // With --prune, reflog entries of rewritten refs are dropped at the same
+    // age as the objects they keep alive
+    let prune = args.prune.as_deref();
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+    let mut reclaimed: i64 = 0;
+
+    // Step 2: Expire the reflogs of refs/trunk* refs in the main repository
+    debug!("➡️ Step 2: Expiring the reflogs of refs/trunk* refs");
+    let trunk_refs = refs_matching(&repo, |name| name.starts_with("refs/trunk"), verbose);
+    expire_reflogs(&repo, &trunk_refs, prune, verbose)?;
+    info!("✓ Step 2: Expired unreachable reflog entries of {} trunk ref(s)", trunk_refs.len());
+
+    // Step 3: gc the main repository
+    debug!("🧹 Step 3: Running git gc in the main repository");
+    let before = object_bytes(&repo, verbose);
+    run_gc(&repo, args, verbose)?;
+    let after = object_bytes(&repo, verbose);
+    reclaimed += before as i64 - after as i64;
+    info!("✓ Step 3: Main repository objects {} -> {}", format_size(before), format_size(after));
+
+    // Step 4: gc every store checkout. Stashes are left alone: their reflog
+    // is the list of stashes.
+    let stores = ops::checked_out_stores(repo_root, verbose);
+    if stores.is_empty() {
+        info!("= Step 4: No store checkouts under .trunk");
+    }
+    let trunk_base_dir = config::trunk_base_dir(repo_root, verbose);
+    let mut failed = Vec::new();
+    for store in &stores {
+        debug!("🧹 Step 4: Running git gc in .trunk/{}", store);
+        let store_repo = match GitRepo::open(&trunk_base_dir.join(store), verbose) {
+            Ok(store_repo) => store_repo,
+            Err(e) => {
+                error!("⚠️ Warning: Cannot open .trunk/{}: {}", store, e);
+                failed.push(store.clone());
+                continue;
+            }
+        };
+        let refs = refs_matching(&store_repo, |name| name != "refs/stash", verbose);
+        let before = object_bytes(&store_repo, verbose);
+        if let Err(e) = expire_reflogs(&store_repo, &refs, prune, verbose).and_then(|()| run_gc(&store_repo, args, verbose)) {
+            error!("⚠️ Warning: {}", e);
+            failed.push(store.clone());
+            continue;
+        }
+        let after = object_bytes(&store_repo, verbose);
+        reclaimed += before as i64 - after as i64;
+        info!("✓ Step 4: .trunk/{} objects {} -> {}", store, format_size(before), format_size(after));
+    }
+
+    if !failed.is_empty() {
+        return Err(TrunkError::Failed(format!("git gc failed in .trunk/{} (run with --verbose for git's messages)", failed.join(", .trunk/"))));
+    }
+    info!("✅ Reclaimed {} across the main repository and {} store checkout(s)", format_size(reclaimed.max(0) as u64), stores.len());
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use std::collections::{HashMap, HashSet};
+use clap::Parser;
+use log::debug;
+use crate::config;
+use crate::locks::normalize_path;
+use crate::ops;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Search the files of refs/trunk/<store> with git grep, without checking it out",
+    after_help = "Matches print as <store>:<path>:<line>:<text>; with --history as <store>@<commit>:<path>:<line>:<text>, naming the newest commit holding each line. Exits with code 1 when nothing matches, like grep.\n\nExamples:\n  git trunk grep travel\n  git trunk grep -i -w todo --all\n  git trunk grep -E 'v[0-9]+\\.[0-9]+' handbook:policies\n  git trunk grep --history \"Forty days\" --store policies\n  git trunk grep -l budget --ref 1a2b3c4"
+)]
+pub struct GrepArgs {
+    #[arg(help = "Pattern, a basic regular expression unless -E or -F is given")]
+    pattern: String,
+    #[arg(help = "Files or directories to search: relative to .trunk/<store>, or `store:path` [default: the whole store]")]
+    paths: Vec<String>,
+    #[arg(long, help = "Search every store in refs/trunk/*")]
+    all: bool,
+    #[arg(long, help = "Search every commit of the store, also finding lines that were changed or removed since")]
+    history: bool,
+    #[arg(long = "ref", value_name = "COMMIT", conflicts_with_all = ["all", "history"], help = "Search this commit of the store, e.g. one shown by `git trunk log`, instead of refs/trunk/<store>")]
+    rev: Option<String>,
+    #[arg(short = 'i', long, help = "Ignore case")]
+    ignore_case: bool,
+    #[arg(short = 'w', long, help = "Match whole words only")]
+    word_regexp: bool,
+    #[arg(short = 'E', long, conflicts_with = "fixed_strings", help = "Pattern is an extended regular expression")]
+    extended_regexp: bool,
+    #[arg(short = 'F', long, help = "Pattern is a literal string")]
+    fixed_strings: bool,
+    #[arg(short = 'l', long, help = "Print only the names of files with matches")]
+    files_with_matches: bool,
+}
+
+impl GrepArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        self.paths.iter_mut().collect()
+    }
+}
+
+// git grep accepts this many commits per run, keeping the command line short.
+const COMMITS_PER_RUN: usize = 500;
+
+// One match: the line number and text are None with --files-with-matches.
+struct Match {
+    commit: String,
+    path: String,
+    line: Option<String>,
+    text: Option<String>,
+}
+
+// Runs git grep over `commits` and parses its `-z` output:
+// "<commit>:<path>\0<line>\0<text>", or "<commit>:<path>\0" with -l.
+fn grep_commits(repo: &GitRepo, args: &GrepArgs, commits: &[String], paths: &[String], verbose: bool) -> Result<Vec<Match>, TrunkError> {
+    let mut command = repo.git();
+    command.arg("grep").arg("-I").arg("-z").arg("--no-color").arg("--full-name");
+    command.arg(if args.files_with_matches { "-l" } else { "-n" });
+    for (set, flag) in [(args.ignore_case, "-i"), (args.word_regexp, "-w"), (args.extended_regexp, "-E"), (args.fixed_strings, "-F")] {
+        if set {
+            command.arg(flag);
+        }
+    }
+    command.arg("-e").arg(&args.pattern).args(commits).arg("--").args(paths);
+    let output = run_git_command(&mut command, verbose).map_err(|e| TrunkError::Git(format!("git grep failed: {}", e)))?;
+    // git grep exits with 1 when nothing matches and 128 on a bad pattern
+    if !output.status.success() && output.status.code() != Some(1) {
+        return Err(TrunkError::Usage(format!(
+            "git grep rejected the pattern '{}'; check its syntax, or use -F to search for it literally (run with --verbose for git's message)",
+            args.pattern
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let records: Vec<&str> = if args.files_with_matches { stdout.split('\0').collect() } else { stdout.lines().collect() };
+    Ok(records
+        .into_iter()
+        .filter_map(|record| {
+            let mut fields = record.splitn(3, '\0');
+            let (commit, path) = fields.next()?.split_once(':')?;
+            Some(Match { commit: commit.to_string(), path: path.to_string(), line: fields.next().map(str::to_string), text: fields.next().map(str::to_string) })
+        })
+        .collect())
+}
+
+// Prints the matches in one store and returns how many there were.
+fn grep_store(repo: &GitRepo, args: &GrepArgs, store_name: &str, verbose: bool) -> Result<usize, TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let rev = args.rev.as_deref().unwrap_or(&trunk_ref_name);
+    let Some(tip) = repo.resolve_commit(rev) else {
+        return Err(TrunkError::NotFound(match &args.rev {
+            Some(rev) => format!("'{}' is not a commit in this repository", rev),
+            None => format!("{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.", trunk_ref_name, store_name, store_name),
+        }));
+    };
+    // Paths of other stores were handed to their own run
+    let paths = if args.all {
+        Vec::new()
+    } else {
+        ops::store_paths(repo, &args.paths, store_name, verbose)
+            .iter()
+            .map(|path| match path.trim_end_matches('/') {
+                "" | "." => Ok(".".to_string()),
+                path => normalize_path(store_name, path),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    if !args.history {
+        debug!("➡️ Searching {} of store '{}'", rev, store_name);
+        let matches = grep_commits(repo, args, std::slice::from_ref(&tip), &paths, verbose)?;
+        for found in &matches {
+            match (&found.line, &found.text) {
+                (Some(line), Some(text)) => println!("{}:{}:{}:{}", store_name, found.path, line, text),
+                _ => println!("{}:{}", store_name, found.path),
+            }
+        }
+        return Ok(matches.len());
+    }
+
+    // --history: every commit, newest first; a line (or file with -l) found
+    // in several commits is printed once, for the newest of them
+    debug!("➡️ Searching the history of store '{}'", store_name);
+    let output = run_git_command(repo.git().arg("rev-list").arg(&tip), verbose).map_err(|e| TrunkError::Git(format!("git rev-list failed for {}: {}", trunk_ref_name, e)))?;
+    let commits: Vec<String> = String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect();
+    let mut seen: HashSet<(String, Option<String>)> = HashSet::new();
+    let mut short_hashes: HashMap<String, String> = HashMap::new();
+    let mut printed = 0;
+    for chunk in commits.chunks(COMMITS_PER_RUN) {
+        for found in grep_commits(repo, args, chunk, &paths, verbose)? {
+            if !seen.insert((found.path.clone(), found.text.clone())) {
+                continue;
+            }
+            let short = short_hashes.entry(found.commit.clone()).or_insert_with(|| short_hash(repo.root(), &found.commit, verbose));
+            let label = format!("{}@{}", store_name, short);
+            match (&found.line, &found.text) {
+                (Some(line), Some(text)) => println!("{}:{}:{}:{}", label, found.path, line, text),
+                _ => println!("{}:{}", label, found.path),
+            }
+            printed += 1;
+        }
+    }
+    Ok(printed)
+}
+
+pub fn run(args: &GrepArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    debug!("✓ Step 1: Repository root found at {}", repo.root().display());
+    if args.all && !args.paths.is_empty() {
+        return Err(TrunkError::Usage("--all searches whole stores; give paths with --store instead".to_string()));
+    }
+
+    // Step 2: Pick the stores
+    let stores = if args.all {
+        repo.ref_names("refs/trunk/").unwrap_or_default().into_iter().filter(|name| config::is_store_name(name)).collect()
+    } else {
+        vec![store_name.to_string()]
+    };
+    if stores.is_empty() {
+        return Err(TrunkError::NotFound("No stores in refs/trunk/*. Run `git trunk fetch` to get the remote's stores.".to_string()));
+    }
+    debug!("✓ Step 2: Searching {} store(s) for '{}'", stores.len(), args.pattern);
+
+    // Step 3: Search each store; matches go to stdout as they are found
+    let mut found = 0;
+    for store in &stores {
+        found += grep_store(&repo, args, store, verbose)?;
+    }
+    if found == 0 {
+        return Err(TrunkError::Failed(format!("No matches for '{}'", args.pattern)));
+    }
+    debug!("✓ Step 3: {} match(es)", found);
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use clap::Parser;
+use log::{debug, error, info};
+use crate::commands::diff::{html_escape, render_html, WORD_REGEX};
+use crate::locks::normalize_path;
+use crate::ops;
+use crate::table::Table;
+use crate::utils::{read_blobs, run_git_command};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "List every revision of one store file, optionally rendering each version or the changes between them", after_help = "Examples:\n  git trunk history policies/travel.md\n  git trunk history policies/travel.md --render\n  git trunk history handbook:policies/travel.md\n  git trunk history policies/travel.md --render --diffs --output travel-history --no-open")]
+pub struct HistoryArgs {
+    #[arg(help = "File to follow: relative to .trunk/<store>, `store:path`, or a path into .trunk from the current directory")]
+    path: String,
+    #[arg(long, help = "Write every version to a directory with an index.html and open it")]
+    render: bool,
+    #[arg(long, requires = "render", help = "With --render, write a side-by-side HTML diff per pair of consecutive versions instead of the versions themselves")]
+    diffs: bool,
+    #[arg(long, value_name = "DIR", requires = "render", help = "With --render, write into DIR instead of a new temporary directory")]
+    output: Option<PathBuf>,
+    #[arg(long, requires = "render", help = "With --render, only print where the files were written")]
+    no_open: bool,
+    #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+impl HistoryArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        vec![&mut self.path]
+    }
+}
+
+// One commit that touched the file, newest first as git log lists them.
+struct Revision {
+    hash: String,
+    short_hash: String,
+    date: String,
+    author: String,
+    subject: String,
+    // The file's path in this commit; it differs between revisions after a rename
+    path: String,
+    // None when the commit deleted the file
+    content: Option<Vec<u8>>,
+}
+
+impl Revision {
+    fn change(&self, older: Option<&Revision>) -> String {
+        match older {
+            _ if self.content.is_none() => "deleted".to_string(),
+            None => "added".to_string(),
+            Some(older) if older.content.is_none() => "restored".to_string(),
+            Some(older) if older.path != self.path => format!("renamed from {}", older.path),
+            Some(_) => "changed".to_string(),
+        }
+    }
+}
+
+// git log placeholders, separated by the unit separator; each commit starts
+// with \x01 and is followed by the file's path there (--name-only -z)
+const GIT_FORMAT: &str = "%x01%H%x1f%h%x1f%as%x1f%an%x1f%s";
+
+fn revisions(repo: &GitRepo, trunk_ref_name: &str, path: &str, verbose: bool) -> Result<Vec<Revision>, TrunkError> {
+    let output = run_git_command(repo.git().arg("log").arg("--follow").arg("--name-only").arg("-z").arg(format!("--format={}", GIT_FORMAT)).arg(trunk_ref_name).arg("--").arg(path), verbose)
+        .map_err(|e| TrunkError::Git(format!("git log failed for {}: {}", trunk_ref_name, e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git log failed for {}: {}", trunk_ref_name, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    let mut revisions: Vec<Revision> = Vec::new();
+    for field in String::from_utf8_lossy(&output.stdout).split('\0') {
+        let field = field.trim_matches('\n');
+        if field.is_empty() {
+            continue;
+        }
+        if let Some(header) = field.strip_prefix('\u{1}') {
+            let parts: Vec<&str> = header.splitn(5, '\u{1f}').collect();
+            let [hash, short_hash, date, author, subject] = parts[..] else { continue };
+            revisions.push(Revision {
+                hash: hash.to_string(),
+                short_hash: short_hash.to_string(),
+                date: date.to_string(),
+                author: author.to_string(),
+                subject: subject.to_string(),
+                path: path.to_string(),
+                content: None,
+            });
+        } else if let Some(revision) = revisions.last_mut() {
+            revision.path = field.to_string();
+        }
+    }
+    let objects: Vec<String> = revisions.iter().map(|revision| format!("{}:{}", revision.hash, revision.path)).collect();
+    let blobs = read_blobs(repo.root(), &objects, verbose).map_err(|e| TrunkError::Git(format!("Failed to read the versions of {}: {}", path, e)))?;
+    for (revision, blob) in revisions.iter_mut().zip(blobs) {
+        revision.content = blob;
+    }
+    Ok(revisions)
+}
+
+// `01-1a2b3c4-travel.md`: oldest first, so the directory listing reads in order.
+fn version_file_name(number: usize, revision: &Revision) -> String {
+    let base_name = Path::new(&revision.path).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    format!("{:02}-{}-{}", number, revision.short_hash, base_name)
+}
+
+fn word_diff(repo: &GitRepo, older: &Revision, newer: &Revision, verbose: bool) -> Result<String, TrunkError> {
+    let output = run_git_command(
+        repo.git()
+            .arg("diff")
+            .arg("--no-color")
+            .arg("-M")
+            .arg("--word-diff=porcelain")
+            .arg(format!("--word-diff-regex={}", WORD_REGEX))
+            .arg(&older.hash)
+            .arg(&newer.hash)
+            .arg("--")
+            .arg(&older.path)
+            .arg(&newer.path),
+        verbose,
+    )
+    .map_err(|e| TrunkError::Git(format!("git diff failed between {} and {}: {}", older.short_hash, newer.short_hash, e)))?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// Writes the versions (or the diffs between them) plus an index.html linking
+// them, and returns the index.
+fn render(repo: &GitRepo, args: &HistoryArgs, store_name: &str, path: &str, revisions: &[Revision], verbose: bool) -> Result<PathBuf, TrunkError> {
+    let output_dir = args.output.clone().unwrap_or_else(|| std::env::temp_dir().join(format!("git-trunk-history-{}-{}", store_name, std::process::id())));
+    fs::create_dir_all(&output_dir).map_err(|e| TrunkError::Io(format!("Failed to create {}: {}", output_dir.display(), e)))?;
+    let write = |name: &str, content: &[u8]| fs::write(output_dir.join(name), content).map_err(|e| TrunkError::Io(format!("Failed to write {}: {}", output_dir.join(name).display(), e)));
+
+    let mut rows = String::new();
+    let oldest_first: Vec<&Revision> = revisions.iter().rev().collect();
+    for (index, revision) in oldest_first.iter().enumerate() {
+        let older = index.checked_sub(1).map(|older| oldest_first[older]);
+        let link = if args.diffs {
+            match older {
+                Some(older) => {
+                    let name = format!("{:02}-{}..{}.html", index, older.short_hash, revision.short_hash);
+                    let title = format!("Store '{}': {} {} → {}", store_name, path, older.short_hash, revision.short_hash);
+                    write(&name, render_html(&word_diff(repo, older, revision, verbose)?, &title).as_bytes())?;
+                    Some(name)
+                }
+                None => None,
+            }
+        } else {
+            match &revision.content {
+                Some(content) => {
+                    let name = version_file_name(index + 1, revision);
+                    write(&name, content)?;
+                    Some(name)
+                }
+                None => None,
+            }
+        };
+        let change = html_escape(&revision.change(older));
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            html_escape(&revision.date),
+            html_escape(&revision.short_hash),
+            html_escape(&revision.author),
+            html_escape(&revision.subject),
+            match link {
+                Some(name) => format!("<a href=\"{}\">{}</a>", html_escape(&name), change),
+                None => change,
+            },
+            html_escape(&revision.path),
+        ));
+    }
+    let title = format!("Store '{}': history of {}", store_name, path);
+    let index = format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #ddd; padding: 4px 8px; text-align: left; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<table>
+<tr><th>Date</th><th>Revision</th><th>Author</th><th>Message</th><th>{link_header}</th><th>Path</th></tr>
+{rows}</table>
+</body>
+</html>
+"#, title = html_escape(&title), link_header = if args.diffs { "Changes" } else { "Version" }, rows = rows);
+    write("index.html", index.as_bytes())?;
+    Ok(output_dir.join("index.html"))
+}
+
+// Opens `file` with the desktop's default application.
+fn open_file(file: &Path) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg("start").arg("");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command.arg(file).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).spawn().map(|_| ())
+}
+
+pub fn run(args: &HistoryArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    debug!("✓ Step 1: Repository root found at {}", repo.root().display());
+    let path = ops::store_paths(&repo, std::slice::from_ref(&args.path), store_name, verbose).pop().unwrap_or_else(|| args.path.clone());
+    let path = normalize_path(store_name, &path)?;
+
+    // Step 2: Check the store ref
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    debug!("➡️ Step 2: Checking {}", trunk_ref_name);
+    if !repo.ref_exists(&trunk_ref_name) {
+        return Err(TrunkError::NotFound(format!("{} for store '{}' does not exist in the local repository. Commit changes first using `git trunk commit --store {}`.", trunk_ref_name, store_name, store_name)));
+    }
+    debug!("✓ Step 2: {} exists", trunk_ref_name);
+
+    // Step 3: Collect the revisions of the file, following renames
+    debug!("➡️ Step 3: Listing the revisions of {} in {}", path, trunk_ref_name);
+    let revisions = revisions(&repo, &trunk_ref_name, &path, verbose)?;
+    if revisions.is_empty() {
+        return Err(TrunkError::NotFound(format!("'{}' has no history in {}", path, trunk_ref_name)));
+    }
+    info!("✓ Step 3: {} has {} revision(s)", path, revisions.len());
+
+    let mut table = Table::new(&["Date", "Revision", "Author", "Message", "Change"]);
+    for (index, revision) in revisions.iter().enumerate() {
+        table.add_row(vec![
+            revision.date.as_str().into(),
+            revision.short_hash.as_str().into(),
+            revision.author.as_str().into(),
+            revision.subject.as_str().into(),
+            revision.change(revisions.get(index + 1)).into(),
+        ]);
+    }
+    table.print(args.no_table);
+    if !args.render {
+        return Ok(());
+    }
+
+    // Step 4: Write the versions or diffs and open the index
+    debug!("➡️ Step 4: Rendering the history of {}", path);
+    let index = render(&repo, args, store_name, &path, &revisions, verbose)?;
+    info!("✓ Step 4: Wrote the history of {} to {}", path, index.display());
+    if !args.no_open {
+        if let Err(e) = open_file(&index) {
+            error!("⚠️ Warning: Failed to open {} ({}); open it in a browser", index.display(), e);
+        }
+    }
+    info!("✅ History of {} written to {}", path, index.display());
+    Ok(())
+}
@@ -0,0 +1,297 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::cache::Cache;
+use crate::config;
+use crate::ops;
+use crate::utils::{glob_match, shared_repository, apply_shared_permissions, SharedRepository};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+// A lock older than this is assumed to belong to a crashed invocation.
+const STALE_LOCK_AFTER: Duration = Duration::from_secs(600);
+
+#[derive(Parser, Debug)]
+#[command(about = "Run the per-store actions registered for a git hook event (invoked by installed hooks)")]
+pub struct HookRunArgs {
+    #[arg(value_parser = ["post-commit", "pre-push", "post-merge", "post-checkout"], help = "The git hook event that fired")]
+    event: String,
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, help = "Arguments git passed to the hook")]
+    hook_args: Vec<String>,
+}
+
+// Removes the lock file when the hook run finishes, however it finishes.
+struct HookLock {
+    path: PathBuf,
+}
+
+impl Drop for HookLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            debug!("⚠️ Failed to remove hook lock {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+fn acquire_lock(path: &Path, shared: SharedRepository) -> io::Result<Option<HookLock>> {
+    let is_stale = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .map(|modified| modified.elapsed().unwrap_or_default() > STALE_LOCK_AFTER)
+        .unwrap_or(false);
+    if is_stale {
+        debug!("🧹 Removing stale hook lock {}", path.display());
+        fs::remove_file(path)?;
+    }
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(_) => {
+            let lock = HookLock { path: path.to_path_buf() };
+            apply_shared_permissions(path, shared)?;
+            Ok(Some(lock))
+        }
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// One line of pre-push stdin: <local ref> <local sha> <remote ref> <remote sha>
+struct PushedRef {
+    local_ref: String,
+    local_sha: String,
+    remote_ref: String,
+}
+
+impl PushedRef {
+    fn is_deletion(&self) -> bool {
+        self.local_ref == "(delete)" || self.local_sha.chars().all(|c| c == '0')
+    }
+
+    // Branch name on the remote side, if the destination is a branch.
+    fn remote_branch(&self) -> Option<&str> {
+        self.remote_ref.strip_prefix("refs/heads/")
+    }
+}
+
+fn read_pushed_refs() -> Vec<PushedRef> {
+    io::stdin()
+        .lock()
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 4 {
+                debug!("⚠️ Ignoring unexpected pre-push input line: {}", line);
+                return None;
+            }
+            Some(PushedRef {
+                local_ref: parts[0].to_string(),
+                local_sha: parts[1].to_string(),
+                remote_ref: parts[2].to_string(),
+            })
+        })
+        .collect()
+}
+
+// Runs a git-trunk subcommand for one store in a child process so that a
+// failing store never aborts the others or the git operation that fired the hook.
+fn run_store_action(repo_root: &Path, action: &[&str], store_name: &str, remote_name: &str, verbose: bool) -> bool {
+    let exe = env::current_exe().unwrap_or_else(|_| PathBuf::from("git-trunk"));
+    let mut command = Command::new(exe);
+    command
+        .args(action)
+        .arg("--store")
+        .arg(store_name)
+        .arg("--remote")
+        .arg(remote_name)
+        .current_dir(repo_root)
+        // Hooks run with GIT_DIR/GIT_INDEX_FILE pointing at the main repository,
+        // which would redirect git commands meant for .trunk/<store>.
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .env_remove("GIT_INDEX_FILE")
+        // Hooks are non-interactive: no prompt may wait on the user
+        .stdin(Stdio::null());
+    if verbose {
+        command.arg("--verbose");
+    }
+    match command.status() {
+        Ok(status) => status.success(),
+        Err(e) => {
+            error!("⚠️ Warning: Failed to run git-trunk {} for store '{}': {}", action.join(" "), store_name, e);
+            false
+        }
+    }
+}
+
+pub fn run(args: &HookRunArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Hooks must never block the git operation that fired them, so every
+    // failure below is reported and followed by a successful exit.
+
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = match GitRepo::discover(verbose) {
+        Ok(repo) => repo,
+        Err(e) => {
+            error!("⚠️ Warning: hook-run could not find the repository root, skipping: {}", e);
+            return Ok(());
+        }
+    };
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Find the stores registered for this event
+    debug!("➡️ Step 2: Loading hook registry for '{}'", args.event);
+    let trunk_config = match config::load(repo_root, verbose) {
+        Ok(trunk_config) => trunk_config,
+        Err(e) => {
+            error!("⚠️ Warning: hook-run could not load config, skipping: {}", e);
+            return Ok(());
+        }
+    };
+    // Step 2a: Bring back checkouts removed behind git-trunk's back, from their
+    // refs, before the stores checked out now are looked at
+    if trunk_config.get_bool("trunk", "auto_restore").unwrap_or(false) {
+        for store in ops::lost_checkouts(&repo, verbose) {
+            info!("➡️ git-trunk {}: .trunk/{} is gone, restoring it from refs/trunk/{} (auto_restore)", args.event, store, store);
+            if let Err(e) = ops::restore_checkout(&repo, &store, "Step 2a", verbose) {
+                error!("⚠️ Warning: Failed to restore .trunk/{}: {}. Restore it manually: git trunk checkout --store {}", store, e, store);
+            }
+        }
+    }
+
+    let mut stores = trunk_config.stores_with_hook(&args.event);
+    if trunk_config.get_array("trunk", "hooks").contains(&args.event) {
+        // Registered with `hooks install --all`: whatever is checked out now
+        for store in ops::checked_out_stores(repo_root, verbose) {
+            if !stores.contains(&store) {
+                stores.push(store);
+            }
+        }
+    }
+    if stores.is_empty() {
+        debug!("= Step 2: No stores registered for {}", args.event);
+        return Ok(());
+    }
+    debug!("✓ Step 2: Stores registered for {}: {}", args.event, stores.join(", "));
+
+    let git_dir = match repo.common_dir() {
+        Ok(git_dir) => git_dir,
+        Err(e) => {
+            error!("⚠️ Warning: hook-run could not locate the git directory, skipping: {}", e);
+            return Ok(());
+        }
+    };
+
+    // Step 3: Rate limiting, by the time of the last run kept in the cache
+    let cache = Cache::open(&repo).map_err(|e| debug!("⚠️ No cache directory, hook runs are not recorded: {}", e)).ok();
+    let stamp_key = format!("hook-{}.stamp", args.event);
+    let min_interval = trunk_config.get_integer("trunk", "hook_min_interval").unwrap_or(0).max(0) as u64;
+    if min_interval > 0 {
+        let elapsed = cache.as_ref().and_then(|cache| cache.age(&stamp_key));
+        if let Some(elapsed) = elapsed.filter(|elapsed| *elapsed < Duration::from_secs(min_interval)) {
+            info!("= git-trunk {}: last run {}s ago (hook_min_interval = {}), skipping", args.event, elapsed.as_secs(), min_interval);
+            return Ok(());
+        }
+    }
+
+    // Step 4: Lock against concurrent hook runs (including the nested pre-push
+    // fired by pushing refs/trunk/<store> from within this hook)
+    let lock_path = git_dir.join("trunk-hook.lock");
+    let shared = shared_repository(&git_dir, verbose);
+    let _lock = match acquire_lock(&lock_path, shared) {
+        Ok(Some(lock)) => lock,
+        Ok(None) => {
+            debug!("= Step 4: Another hook-run holds {}, skipping", lock_path.display());
+            return Ok(());
+        }
+        Err(e) => {
+            error!("⚠️ Warning: hook-run could not create {}, skipping: {}", lock_path.display(), e);
+            return Ok(());
+        }
+    };
+
+    // Step 5: Perform the per-store actions
+    let offline = env::var("GIT_TRUNK_OFFLINE").map(|v| v == "1" || v == "true").unwrap_or(false)
+        || trunk_config.get_bool("trunk", "offline").unwrap_or(false);
+    match args.event.as_str() {
+        "post-commit" => {
+            for store in &stores {
+                info!("➡️ git-trunk post-commit: committing store '{}'", store);
+                if !run_store_action(repo_root, &["commit", "--force"], store, &trunk_config.remote_for_store(store), verbose) {
+                    error!("⚠️ Warning: Failed to commit store '{}'", store);
+                }
+            }
+        }
+        "pre-push" => {
+            let hook_remote = args.hook_args.first().cloned().unwrap_or_else(|| config::DEFAULT_REMOTE.to_string());
+            if offline {
+                info!("= git-trunk pre-push: offline mode, not pushing stores");
+                return Ok(());
+            }
+            let pushed_branches: Vec<String> = read_pushed_refs()
+                .iter()
+                .filter(|pushed| !pushed.is_deletion())
+                .filter_map(|pushed| pushed.remote_branch().map(str::to_string))
+                .collect();
+            debug!("🔍 Step 5: Branches being pushed to '{}': {}", hook_remote, pushed_branches.join(", "));
+
+            for store in &stores {
+                // A store with an explicitly configured remote only follows pushes to that remote
+                if let Some(store_remote) = trunk_config.configured_remote_for_store(store) {
+                    if store_remote != hook_remote {
+                        debug!("= Step 5: Store '{}' uses remote '{}', not '{}', skipping", store, store_remote, hook_remote);
+                        continue;
+                    }
+                }
+                let triggers = trunk_config.trigger_branches(store);
+                let Some(branch) = pushed_branches.iter().find(|branch| triggers.iter().any(|pattern| glob_match(pattern, branch))) else {
+                    debug!("= Step 5: No trigger branch ({}) pushed for store '{}'", triggers.join(", "), store);
+                    continue;
+                };
+                info!("➡️ git-trunk pre-push: '{}' is being pushed, pushing store '{}' to '{}'", branch, store, hook_remote);
+                if !run_store_action(repo_root, &["push"], store, &hook_remote, verbose) {
+                    error!("⚠️ Warning: Failed to push store '{}'. Push it manually: git trunk push --store {} --remote {}", store, store, hook_remote);
+                }
+            }
+        }
+        "post-merge" | "post-checkout" => {
+            // post-checkout gets <previous HEAD> <new HEAD> <1 for a branch checkout, 0 for files>
+            if args.event == "post-checkout" && args.hook_args.get(2).map(String::as_str) == Some("0") {
+                debug!("= Step 5: File checkout, not syncing stores");
+                return Ok(());
+            }
+            if offline {
+                info!("= git-trunk {}: offline mode, not syncing stores", args.event);
+                return Ok(());
+            }
+            // Only fast-forwards: a store that diverged is left for the user to merge
+            let trunk_base_dir = config::trunk_base_dir(repo_root, verbose);
+            for store in &stores {
+                let store_remote = trunk_config.remote_for_store(store);
+                let (action, command): (&[&str], &str) = if trunk_base_dir.join(store).join(".git").exists() {
+                    (&["pull", "--ff-only"], "pull")
+                } else {
+                    (&["checkout"], "checkout")
+                };
+                info!("➡️ git-trunk {}: running {} for store '{}' from '{}'", args.event, command, store, store_remote);
+                if !run_store_action(repo_root, action, store, &store_remote, verbose) {
+                    error!("⚠️ Warning: Failed to sync store '{}'. Sync it manually: git trunk {} --store {}", store, command, store);
+                }
+            }
+        }
+        _ => unreachable!("event is restricted by the argument parser"),
+    }
+
+    if let Some(cache) = &cache {
+        if let Err(e) = cache.touch(&stamp_key) {
+            debug!("⚠️ Failed to record hook run time in {}: {}", cache.dir().display(), e);
+        }
+    }
+    // Older versions kept the stamp in the git directory
+    let _ = fs::remove_file(git_dir.join(format!("trunk-hook-{}.stamp", args.event)));
+    Ok(())
+}
@@ -1,37 +1,149 @@
 use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::Path;
-use std::process::{Command, exit};
-use clap::Parser;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use clap::{Parser, Subcommand};
 use log::{debug, error, info};
-use crate::utils::run_git_command;
+use regex::Regex;
+use crate::config::{self, ConfigValue};
+use crate::table::{print_line, Cell, Color, Table};
+use crate::utils::{confirm, dry_run, run_git_command, shared_repository, apply_shared_permissions};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
 
 #[derive(Parser, Debug)]
-#[command(about = "Manage Git hooks for a specific git-trunk store")]
+#[command(about = "Manage Git hooks for a specific git-trunk store", after_help = "Examples:\n  git trunk hooks install\n  git trunk hooks install --store blog --force\n  git trunk hooks install --all\n  git trunk hooks install --sync\n  git trunk hooks status\n  git trunk hooks uninstall --store blog\n  git trunk hooks uninstall --all")]
 pub struct HooksArgs {
-    #[arg(long, help = "Force installation of hooks, overwriting existing hooks")]
-    force: bool,
+    #[command(subcommand)]
+    action: HooksAction,
 }
 
-pub fn run(args: &HooksArgs, _remote_name: &str, store_name: &str, verbose: bool) {
+#[derive(Subcommand, Debug)]
+enum HooksAction {
+    /// Installs the post-commit and pre-push hooks and registers them for the store
+    Install {
+        #[arg(long, help = "Install without asking; existing hooks are chained, never overwritten")]
+        force: bool,
+        #[arg(long, help = "Register the hooks for every store checked out in .trunk, including stores added later")]
+        all: bool,
+        #[arg(long, help = "Also install post-merge and post-checkout hooks that pull the store (or check it out) after git pull, merge or switching branches")]
+        sync: bool,
+    },
+    /// Unregisters the store's hooks and removes git-trunk hooks no store uses any more
+    Uninstall {
+        #[arg(long, help = "Unregister every store, and [trunk] hooks, and remove all git-trunk hooks")]
+        all: bool,
+    },
+    /// Shows installed hooks and the events and trigger branches registered per store
+    Status {
+        #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+        no_table: bool,
+    },
+}
+
+impl HooksArgs {
+    pub fn writes_hooks(&self) -> bool {
+        !matches!(self.action, HooksAction::Status { .. })
+    }
+}
+
+pub const MANAGED_HOOK_MARKER: &str = "# This hook is managed by git-trunk.";
+pub const MANAGED_HOOK_NAMES: [&str; 4] = ["post-commit", "pre-push", "post-merge", "post-checkout"];
+// Hooks installed only with `hooks install --sync`.
+pub const SYNC_HOOK_NAMES: [&str; 2] = ["post-merge", "post-checkout"];
+
+// Suffix under which a hook that was there before git-trunk is kept, next to
+// the dispatcher that runs it first (`post-commit.pre-git-trunk`).
+pub const CHAINED_HOOK_SUFFIX: &str = "pre-git-trunk";
+
+// The directory git runs hooks from: core.hooksPath when set (as husky does),
+// otherwise the hooks directory of the common git directory.
+pub fn hooks_dir(repo_root: &Path, verbose: bool) -> PathBuf {
+    match run_git_command(Command::new("git").arg("rev-parse").arg("--git-path").arg("hooks").current_dir(repo_root), verbose) {
+        Ok(output) if output.status.success() => {
+            let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+            if path.is_absolute() { path } else { repo_root.join(path) }
+        }
+        _ => repo_root.join(".git").join("hooks"),
+    }
+}
+
+pub fn chained_hook_path(hooks_dir: &Path, hook_name: &str) -> PathBuf {
+    hooks_dir.join(format!("{}.{}", hook_name, CHAINED_HOOK_SUFFIX))
+}
+
+// The dispatcher script. It runs the chained hook first with the same
+// arguments, and stops with its exit code when it fails; pre-push gets its
+// ref list on stdin, so that is saved and given to both.
+fn dispatcher_hook_content(hook_name: &str) -> String {
+    let (save_stdin, from_stdin) = if hook_name == "pre-push" {
+        ("stdin_copy=$(mktemp) || exit 1\ntrap 'rm -f \"$stdin_copy\"' EXIT\ncat > \"$stdin_copy\"\n", " < \"$stdin_copy\"")
+    } else {
+        ("", "")
+    };
+    format!(r#"#!/bin/sh
+# {hook} hook dispatching to `git trunk hook-run`.
+{marker}
+# Per-store actions are configured in .git/trunk.toml ([store.<name>] hooks = [...],
+# or [trunk] hooks = [...] for every store checked out in .trunk).
+# A {hook} hook that was here before git-trunk is kept as {hook}.{suffix} and runs first.
+{save_stdin}chained="$(dirname "$0")/{hook}.{suffix}"
+if [ -x "$chained" ]; then
+    "$chained" "$@"{from_stdin} || exit $?
+fi
+git trunk hook-run {hook} "$@"{from_stdin}
+"#, hook = hook_name, marker = MANAGED_HOOK_MARKER, suffix = CHAINED_HOOK_SUFFIX, save_stdin = save_stdin, from_stdin = from_stdin)
+}
+
+// Rewrites `--store <old>` to `--store <new>` in legacy per-store hooks, and
+// returns the names of the hooks changed. Dispatcher hooks name no store.
+pub fn rename_store_in_hooks(repo_root: &Path, old: &str, new: &str, verbose: bool) -> io::Result<Vec<String>> {
+    let hooks_dir = hooks_dir(repo_root, verbose);
+    let store_option = Regex::new(&format!(r"--store\s+{}(\s|$)", regex::escape(old))).expect("valid store regex");
+    let mut renamed = Vec::new();
+    for hook_name in MANAGED_HOOK_NAMES {
+        let hook_path = hooks_dir.join(hook_name);
+        if !managed_hook_stores(&hook_path).is_some_and(|stores| stores.iter().any(|store| store == old)) {
+            continue;
+        }
+        let content = fs::read_to_string(&hook_path)?;
+        let updated = store_option.replace_all(&content, |caps: &regex::Captures| format!("--store {}{}", new, &caps[1]));
+        fs::write(&hook_path, updated.as_bytes())?;
+        renamed.push(hook_name.to_string());
+    }
+    Ok(renamed)
+}
+
+// Returns the store names hard-coded in a git-trunk managed hook, or None if the
+// hook does not exist or was not written by git-trunk. Dispatcher hooks (which
+// defer to `git trunk hook-run`) reference no stores; older per-store scripts do.
+pub fn managed_hook_stores(hook_path: &Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(hook_path).ok()?;
+    if !content.contains(MANAGED_HOOK_MARKER) {
+        return None;
+    }
+    let mut stores: Vec<String> = Vec::new();
+    let tokens: Vec<&str> = content.split_whitespace().collect();
+    for pair in tokens.windows(2) {
+        if pair[0] == "--store" && !stores.contains(&pair[1].to_string()) {
+            stores.push(pair[1].to_string());
+        }
+    }
+    Some(stores)
+}
+
+// True when the hook is the git-trunk dispatcher that defers to `git trunk hook-run`.
+pub fn is_dispatcher_hook(hook_path: &Path) -> bool {
+    fs::read_to_string(hook_path)
+        .map(|content| content.contains(MANAGED_HOOK_MARKER) && content.contains("git trunk hook-run"))
+        .unwrap_or(false)
+}
+
+pub fn run(args: &HooksArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
     // Step 1: Get repository root
     debug!("➡️ Step 1: Getting repository root");
-    let repo_root_output = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--show-toplevel"),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to get git repository root: {}", e);
-        exit(1);
-    });
-    let repo_root_str = String::from_utf8_lossy(&repo_root_output.stdout).trim().to_string();
-    if repo_root_str.is_empty() {
-        error!("❌ Git repository root is empty. Ensure you are in a valid Git repository.");
-        exit(1);
-    }
-    let repo_root = Path::new(&repo_root_str);
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
     info!("✓ Step 1: Repository root found at {}", repo_root.display());
 
     // Step 2: Check if we are in a Git repository
@@ -44,137 +156,231 @@ pub fn run(args: &HooksArgs, _remote_name: &str, store_name: &str, verbose: bool
         verbose,
     );
     if git_check_output.map(|output| !output.status.success()).unwrap_or(true) {
-        error!("❌ hooks can only be invoked inside a git repo");
-        exit(1);
+        return Err(TrunkError::Git("hooks can only be invoked inside a git repo".to_string()));
     }
     info!("✓ Step 2: Confirmed inside a Git repository");
 
     // Step 3: Define hooks directory
     debug!("⚙️ Step 3: Setting up hooks directory");
-    let hooks_dir = repo_root.join(".git").join("hooks");
-    fs::create_dir_all(&hooks_dir).unwrap_or_else(|e| {
-        error!("❌ Failed to create hooks directory: {}", e);
-        exit(1);
-    });
+    let hooks_dir = hooks_dir(repo_root, verbose);
+    let (force, all, sync) = match args.action {
+        HooksAction::Install { force, all, sync } => (force, all, sync),
+        HooksAction::Uninstall { all } => return uninstall(repo_root, &hooks_dir, store_name, all, verbose),
+        HooksAction::Status { no_table } => return show_status(repo_root, &hooks_dir, no_table, verbose),
+    };
+    if hooks_dir.is_dir() || !dry_run(&format!("create directory {}", hooks_dir.display())) {
+        fs::create_dir_all(&hooks_dir).map_err(|e| TrunkError::Io(format!("Failed to create hooks directory: {}", e)))?;
+    }
     info!("✓ Step 3: Hooks directory ready at {:?}", hooks_dir.display());
 
-    let trunk_ref_name = format!("refs/trunk/{}", store_name);
-
-    // Step 4: Prompt for post-commit hook
-    let post_commit_path = hooks_dir.join("post-commit");
-    let install_post_commit = if post_commit_path.exists() && !args.force {
-        debug!("📍 Step 4: post-commit hook already exists");
-        print!("🐘 Overwrite existing post-commit hook? [y/N]: ");
-        io::stdout().flush().expect("Failed to flush stdout");
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("Failed to read user input");
-        input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes"
+    // Step 4: Install or register each hook, for the store or, with --all,
+    // under [trunk] for whatever is checked out in .trunk when the hook fires
+    let (stores_label, section) = if all {
+        ("every store in .trunk".to_string(), "trunk".to_string())
     } else {
-        debug!("🚫 Step 4: No post-commit hook found or --force specified for store '{}'", store_name);
-        print!("🐘 Install post-commit hook to auto-commit .trunk/{} after main repo commits? [y/N]: ", store_name);
-        io::stdout().flush().expect("Failed to flush stdout");
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("Failed to read user input");
-        input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes" || args.force
+        (format!("store '{}'", store_name), format!("store.{}", store_name))
     };
+    let checkout_label = if all { "every store in .trunk".to_string() } else { format!(".trunk/{}", store_name) };
+    let mut hook_descriptions = vec![
+        ("post-commit", format!("auto-commit {} after main repo commits", checkout_label)),
+        ("pre-push", format!("push {} with main branch pushes", if all { "every store in .trunk".to_string() } else { format!("refs/trunk/{}", store_name) })),
+    ];
+    if sync {
+        hook_descriptions.push(("post-merge", format!("pull {} after git pull and merge", checkout_label)));
+        hook_descriptions.push(("post-checkout", format!("pull {} (or check it out) after switching branches", checkout_label)));
+    }
+    let mut dry_run_events: Vec<String> = Vec::new(); // registrations --dry-run did not write
+    for (index, (hook_name, description)) in hook_descriptions.iter().enumerate() {
+        let step = format!("Step {}", index + 4);
+        let hook_path = hooks_dir.join(hook_name);
+        let is_foreign = hook_path.exists() && managed_hook_stores(&hook_path).is_none();
+        if !force && !confirm(&format!("Install {} hook to {}?", hook_name, description))? {
+            info!("= {}: Skipped {} hook installation for {}", step, hook_name, stores_label);
+            continue;
+        }
 
-    if install_post_commit {
-        debug!("✨ Step 4: Creating post-commit hook for store '{}'", store_name);
-        let post_commit_content = format!(r#"#!/bin/sh
-# Post-commit hook to auto-commit .trunk/{} changes
-# This hook is managed by git-trunk.
-echo "Git Trunk: Running post-commit hook for store '{}'..."
-git trunk commit --force --store {}
-if [ $? -eq 0 ]; then
-    echo "Git Trunk: Store '{}' committed successfully."
-else
-    echo "Git Trunk: Warning - Failed to commit store '{}'." >&2
-fi
-"#, store_name, store_name, store_name, store_name, store_name);
-        let mut post_commit_file = File::create(&post_commit_path).unwrap_or_else(|e| {
-            error!("❌ Failed to create post-commit hook: {}", e);
-            exit(1);
-        });
-        writeln!(post_commit_file, "{}", post_commit_content).expect("Failed to write post-commit hook");
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&post_commit_path, fs::Permissions::from_mode(0o755)).unwrap_or_else(|e| {
-                error!("❌ Failed to set executable permissions on post-commit hook: {}", e);
-                // Non-critical for Windows, but log it.
-            });
+        if is_foreign {
+            // Chain the existing hook (husky, lefthook, a user script) instead of overwriting it
+            let chained_path = chained_hook_path(&hooks_dir, hook_name);
+            debug!("📍 {}: {} hook already exists and is not managed by git-trunk", step, hook_name);
+            if chained_path.exists() {
+                return Err(TrunkError::Conflict(format!(
+                    "Both {} and {} hold hooks not written by git-trunk. Merge them into {} by hand, then run `git trunk hooks install` again.",
+                    hook_path.display(), chained_path.display(), chained_path.display()
+                )));
+            }
+            if !dry_run(&format!("move {} to {}", hook_path.display(), chained_path.display())) {
+                fs::rename(&hook_path, &chained_path).map_err(|e| TrunkError::Io(format!("Failed to move the existing {} hook aside: {}", hook_name, e)))?;
+            }
+            info!("✓ {}: Existing {} hook kept as {}.{}, it runs before git-trunk", step, hook_name, hook_name, CHAINED_HOOK_SUFFIX);
+        }
+
+        let hook_content = dispatcher_hook_content(hook_name);
+        let up_to_date = !is_foreign && fs::read_to_string(&hook_path).is_ok_and(|content| content == hook_content);
+        if !up_to_date && !dry_run(&format!("write the {} dispatcher hook to {}", hook_name, hook_path.display())) {
+            debug!("✨ {}: Writing {} dispatcher hook", step, hook_name);
+            let mut hook_file = File::create(&hook_path).map_err(|e| TrunkError::Io(format!("Failed to create {} hook: {}", hook_name, e)))?;
+            write!(hook_file, "{}", hook_content).map_err(|e| TrunkError::Io(format!("Failed to write {}: {}", hook_path.display(), e)))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap_or_else(|e| {
+                    error!("❌ Failed to set executable permissions on {} hook: {}", hook_name, e);
+                    // Non-critical for Windows, but log it.
+                });
+            }
+            if let Err(e) = apply_shared_permissions(&hook_path, shared_repository(repo_root, verbose)) {
+                error!("⚠️ Warning: {}", e);
+            }
         }
-        info!("✓ Step 4: Post-commit hook for store '{}' installed", store_name);
-    } else {
-        info!("= Step 4: Skipped post-commit hook installation for store '{}'", store_name);
-    }
-
-    // Step 5: Prompt for pre-push hook
-    let pre_push_path = hooks_dir.join("pre-push");
-    let install_pre_push = if pre_push_path.exists() && !args.force {
-        debug!("📍 Step 5: pre-push hook already exists");
-        print!("🐘 Overwrite existing pre-push hook? [y/N]: ");
-        io::stdout().flush().expect("Failed to flush stdout");
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("Failed to read user input");
-        input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes"
-    } else {
-        debug!("🚫 Step 5: No pre-push hook found or --force specified for store '{}'", store_name);
-        print!("🐘 Install pre-push hook to push {} with main branch pushes? [y/N]: ", trunk_ref_name);
-        io::stdout().flush().expect("Failed to flush stdout");
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("Failed to read user input");
-        input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes" || args.force
-    };
 
-    if install_pre_push {
-        debug!("✨ Step 5: Creating pre-push hook for store '{}' (ref: {})", store_name, trunk_ref_name);
-        let pre_push_content = format!(r#"#!/bin/sh
-# Pre-push hook to ensure {} is pushed when main branch is pushed.
-# This hook is managed by git-trunk.
-remote_name="$1"
-# remote_url="$2" # Not used in this script
-
-# Read stdin to get refs being pushed
-while read local_ref local_sha remote_ref remote_sha
-do
-    # Check if the main working branch (e.g., main, master) is being pushed
-    # Adjust "refs/heads/main" if your main branch has a different name
-    if [ "$local_ref" = "refs/heads/main" ] || [ "$local_ref" = "refs/heads/master" ]; then
-        echo "Git Trunk: Main branch is being pushed to '$remote_name'."
-        echo "Git Trunk: Ensuring {} for store '{}' is also pushed."
-        # Attempt to push the trunk ref for the specific store
-        # Use the remote name provided to the pre-push hook by Git
-        git push "$remote_name" {}:{}
-        if [ $? -eq 0 ]; then
-            echo "Git Trunk: {} pushed successfully to '$remote_name'."
-        else
-            echo "Git Trunk: Warning - Failed to push {} to '$remote_name'." >&2
-            echo "Git Trunk: You might need to push it manually: git trunk push --store {} --remote $remote_name" >&2
-        fi
-        # We don't want to block the main push if trunk push fails, so we don't exit 1 here.
-        # The user will see the warning.
-    fi
-done
-
-exit 0 # Always exit 0 to not block the push, warnings are printed to stderr
-"#, trunk_ref_name, trunk_ref_name, store_name, trunk_ref_name, trunk_ref_name, trunk_ref_name, trunk_ref_name, store_name);
-        let mut pre_push_file = File::create(&pre_push_path).unwrap_or_else(|e| {
-            error!("❌ Failed to create pre-push hook: {}", e);
-            exit(1);
-        });
-        writeln!(pre_push_file, "{}", pre_push_content).expect("Failed to write pre-push hook");
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&pre_push_path, fs::Permissions::from_mode(0o755)).unwrap_or_else(|e| {
-                error!("❌ Failed to set executable permissions on pre-push hook: {}", e);
-            });
+        debug!("📝 {}: Registering {} for {} in config", step, hook_name, stores_label);
+        let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+        let mut events = trunk_config.get_array(&section, "hooks");
+        if !events.contains(&hook_name.to_string()) {
+            events.extend(dry_run_events.iter().filter(|event| !events.contains(event)).cloned().collect::<Vec<_>>());
+            events.push(hook_name.to_string());
+            let value = ConfigValue::Array(events);
+            if dry_run(&format!("set [{}] hooks = {} in {}", section, value, trunk_config.path.display())) {
+                dry_run_events.push(hook_name.to_string());
+                continue;
+            }
+            config::set_value(&trunk_config, &section, "hooks", Some(&value)).map_err(|e| TrunkError::Failed(format!("Failed to register {} hook for {}: {}", hook_name, stores_label, e)))?;
         }
-        info!("✓ Step 5: Pre-push hook for store '{}' (ref: {}) installed", store_name, trunk_ref_name);
+        info!("✓ {}: {} hook for {} installed", step, hook_name, stores_label);
+    }
+
+    info!("✅ Trunk hooks configuration for {} completed", stores_label);
+    Ok(())
+}
+
+// Unregisters the store (or with `all`, every store) and removes each
+// git-trunk hook that no registered store needs any more, putting back the
+// hook it chained. Hooks not written by git-trunk are never touched.
+fn uninstall(repo_root: &Path, hooks_dir: &Path, store_name: &str, all: bool, verbose: bool) -> Result<(), TrunkError> {
+    // Step 3: Unregister the hook events
+    debug!("➡️ Step 3: Unregistering hook events");
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let stores: Vec<String> = if all { trunk_config.store_names() } else { vec![store_name.to_string()] };
+    let mut sections: Vec<String> = stores.iter().map(|store| format!("store.{}", store)).collect();
+    if all {
+        sections.push("trunk".to_string());
+    }
+    let mut unregistered: Vec<String> = Vec::new();
+    for section in &sections {
+        if trunk_config.get_array(section, "hooks").is_empty() {
+            continue;
+        }
+        if !dry_run(&format!("remove [{}] hooks from {}", section, trunk_config.path.display())) {
+            // Reload so the line numbers match the file after earlier removals
+            let current = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+            config::set_value(&current, section, "hooks", None).map_err(|e| TrunkError::Failed(format!("Failed to unregister [{}] hooks: {}", section, e)))?;
+        }
+        unregistered.push(section.strip_prefix("store.").map(|store| format!("store '{}'", store)).unwrap_or_else(|| "every store in .trunk".to_string()));
+    }
+    if unregistered.is_empty() {
+        info!("= Step 3: No hooks registered for {}", if all { "any store".to_string() } else { format!("store '{}'", store_name) });
     } else {
-        info!("= Step 5: Skipped pre-push hook installation for store '{}'", store_name);
+        info!("✓ Step 3: Unregistered hooks for {}", unregistered.join(", "));
     }
 
-    info!("✅ Trunk hooks configuration for store '{}' completed", store_name);
-}
\ No newline at end of file
+    // Step 4 and 5: Remove git-trunk hooks that no store uses any more
+    let remaining = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    for (index, hook_name) in MANAGED_HOOK_NAMES.iter().enumerate() {
+        let step = format!("Step {}", index + 4);
+        let hook_path = hooks_dir.join(hook_name);
+        let Some(hook_stores) = managed_hook_stores(&hook_path) else {
+            if hook_path.exists() {
+                info!("= {}: {} hook is not managed by git-trunk, left in place", step, hook_name);
+            } else {
+                debug!("= {}: No {} hook installed", step, hook_name);
+            }
+            continue;
+        };
+        // Under dry-run the config still lists the stores being unregistered
+        let users: Vec<String> = remaining.stores_with_hook(hook_name)
+            .into_iter()
+            .chain(hook_stores) // stores hard-coded in a legacy per-store hook
+            .filter(|store| !all && !stores.contains(store))
+            .collect();
+        if !users.is_empty() {
+            info!("= {}: {} hook kept, still used by store(s) {}", step, hook_name, users.join(", "));
+            continue;
+        }
+        if !all && remaining.get_array("trunk", "hooks").iter().any(|event| event == hook_name) {
+            info!("= {}: {} hook kept, [trunk] hooks runs it for every store in .trunk (`git trunk hooks uninstall --all` removes it)", step, hook_name);
+            continue;
+        }
+        if !dry_run(&format!("remove file {}", hook_path.display())) {
+            fs::remove_file(&hook_path).map_err(|e| TrunkError::Io(format!("Failed to remove {} hook: {}", hook_name, e)))?;
+        }
+        let chained_path = chained_hook_path(hooks_dir, hook_name);
+        if chained_path.exists() {
+            if !dry_run(&format!("move {} to {}", chained_path.display(), hook_path.display())) {
+                fs::rename(&chained_path, &hook_path).map_err(|e| TrunkError::Io(format!("Failed to restore the chained {} hook: {}", hook_name, e)))?;
+            }
+            info!("✓ {}: Removed the git-trunk {} hook and restored the one it chained", step, hook_name);
+        } else {
+            info!("✓ {}: Removed {} hook", step, hook_name);
+        }
+    }
+
+    info!("✅ Trunk hooks uninstalled for {}", if all { "every store".to_string() } else { format!("store '{}'", store_name) });
+    Ok(())
+}
+
+fn show_status(repo_root: &Path, hooks_dir: &Path, no_table: bool, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+
+    print_line(&format!("\n🪝 Git Trunk Hooks ({})", hooks_dir.display()));
+    let mut hooks_table = Table::new(&["Hook", "State"]);
+    for hook_name in MANAGED_HOOK_NAMES {
+        let hook_path = hooks_dir.join(hook_name);
+        let state = if !hook_path.exists() && SYNC_HOOK_NAMES.contains(&hook_name) {
+            Cell::colored("- Not installed (optional, `git trunk hooks install --sync`)", Color::Yellow)
+        } else if !hook_path.exists() {
+            Cell::colored("✗ Not installed", Color::Red)
+        } else if is_dispatcher_hook(&hook_path) && chained_hook_path(hooks_dir, hook_name).exists() {
+            Cell::colored(format!("✓ Installed (git-trunk dispatcher, runs {}.{} first)", hook_name, CHAINED_HOOK_SUFFIX), Color::Green)
+        } else if is_dispatcher_hook(&hook_path) {
+            Cell::colored("✓ Installed (git-trunk dispatcher)", Color::Green)
+        } else if let Some(stores) = managed_hook_stores(&hook_path) {
+            Cell::colored(format!("! Legacy git-trunk hook for {} (re-run `git trunk hooks install` to upgrade)", stores.join(", ")), Color::Yellow)
+        } else {
+            Cell::colored("! Not managed by git-trunk", Color::Yellow)
+        };
+        hooks_table.add_row(vec![hook_name.into(), state]);
+    }
+    hooks_table.print(no_table);
+
+    let mut stores_table = Table::new(&["Store", "Events", "Trigger Branches", "Remote"]);
+    let all_events = trunk_config.get_array("trunk", "hooks");
+    if !all_events.is_empty() {
+        stores_table.add_row(vec![
+            "(every store in .trunk)".into(),
+            all_events.join(", ").into(),
+            "(per store)".into(),
+            "(per store)".into(),
+        ]);
+    }
+    for store in trunk_config.store_names() {
+        let events = trunk_config.get_array(&format!("store.{}", store), "hooks");
+        if events.is_empty() {
+            continue;
+        }
+        let pushes = events.iter().any(|event| event == "pre-push");
+        stores_table.add_row(vec![
+            store.as_str().into(),
+            events.join(", ").into(),
+            if pushes { trunk_config.trigger_branches(&store).join(", ").into() } else { "-".into() },
+            if pushes { trunk_config.configured_remote_for_store(&store).unwrap_or_else(|| "(remote being pushed to)".to_string()).into() } else { "-".into() },
+        ]);
+    }
+    println!();
+    if stores_table.is_empty() {
+        print_line(&format!("No stores are registered for hooks in {}", trunk_config.path.display()));
+    } else {
+        stores_table.print(no_table);
+    }
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use clap::Parser;
+use log::{debug, info};
+use crate::backup;
+use crate::config;
+use crate::ops;
+use crate::quota;
+use crate::utils::{ensure_trunk_in_gitignore, run_git_command, short_hash};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Create a store from a git bundle, a tar or zip archive, or a directory",
+    after_help = "A bundle keeps its history: its refs/trunk/<store> (as written by `git trunk export`), else its HEAD or only branch, becomes the store. Archives and directories become the store's first commit; an archive holding a single directory is imported from inside it. To keep the history of a plain git repository, import a bundle of it: `git -C <repo> bundle create ../docs.bundle HEAD`.\n\nExamples:\n  git trunk import handbook.bundle\n  git trunk import docs-export.tar.gz --as docs\n  git trunk import ~/notes --as notes --no-checkout"
+)]
+pub struct ImportArgs {
+    #[arg(value_name = "PATH", help = "Bundle, archive (.tar, .tar.gz, .tgz, .zip) or directory to import")]
+    path: PathBuf,
+    #[arg(long = "as", value_name = "STORE", help = "Name of the new store [default: the store in the bundle, else the file or directory name]")]
+    store: Option<String>,
+    #[arg(long, help = "Only create refs/trunk/<store>, without .trunk/<store>")]
+    no_checkout: bool,
+}
+
+impl ImportArgs {
+    pub fn checks_out(&self) -> bool {
+        !self.no_checkout
+    }
+}
+
+// What the path holds, told apart by content rather than by name where possible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    Bundle,
+    Tar,
+    Zip,
+    Directory,
+}
+
+fn detect(path: &Path) -> Result<Source, TrunkError> {
+    if path.is_dir() {
+        return Ok(Source::Directory);
+    }
+    // A tar header block is 512 bytes, with "ustar" at offset 257
+    let mut head = Vec::with_capacity(512);
+    fs::File::open(path).and_then(|file| file.take(512).read_to_end(&mut head)).map_err(|e| TrunkError::NotFound(format!("{}: {}", path.display(), e)))?;
+    let name = path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if head.starts_with(b"# v2 git bundle") || head.starts_with(b"# v3 git bundle") {
+        Ok(Source::Bundle)
+    } else if head.starts_with(b"PK\x03\x04") {
+        Ok(Source::Zip)
+    } else if head.get(257..262) == Some(b"ustar") || head.starts_with(&[0x1f, 0x8b]) || [".tar", ".tar.gz", ".tgz", ".tar.bz2", ".tar.xz"].iter().any(|extension| name.ends_with(extension)) {
+        Ok(Source::Tar)
+    } else {
+        Err(TrunkError::Usage(format!("{} is not a git bundle, a tar or zip archive, or a directory", path.display())))
+    }
+}
+
+// The file or directory name without its archive extensions, e.g. `docs` for docs.tar.gz.
+fn base_name(path: &Path) -> String {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    [".tar.gz", ".tar.bz2", ".tar.xz", ".tgz", ".tar", ".zip", ".bundle"]
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+// Unpacks an archive into `dest` with the system's tools: tar for tarballs,
+// unzip (or bsdtar, as on macOS and Windows) for zip files.
+fn extract(archive: &Path, source: Source, dest: &Path) -> Result<(), TrunkError> {
+    let mut attempts: Vec<Command> = Vec::new();
+    if source == Source::Zip {
+        let mut unzip = Command::new("unzip");
+        unzip.arg("-q").arg(archive).arg("-d").arg(dest);
+        attempts.push(unzip);
+    }
+    let mut tar = Command::new("tar");
+    tar.arg("-xf").arg(archive).arg("-C").arg(dest);
+    attempts.push(tar);
+    for mut command in attempts {
+        match command.output() {
+            Ok(output) if output.status.success() => return Ok(()),
+            Ok(output) => debug!("⚠️ {:?} failed: {}", command.get_program(), String::from_utf8_lossy(&output.stderr).trim()),
+            Err(e) => debug!("⚠️ Cannot run {:?}: {}", command.get_program(), e),
+        }
+    }
+    Err(TrunkError::Failed(format!(
+        "Failed to unpack {}; check that it is a valid archive and that {} is installed (run with --verbose for the reason)",
+        archive.display(),
+        if source == Source::Zip { "unzip" } else { "tar" }
+    )))
+}
+
+// The directory an extracted archive's files are in: the single directory it
+// holds, as in archives `git trunk export` writes, or the top itself.
+fn archive_root(dir: &Path) -> (PathBuf, Option<String>) {
+    let entries: Vec<PathBuf> = fs::read_dir(dir).map(|entries| entries.filter_map(Result::ok).map(|entry| entry.path()).collect()).unwrap_or_default();
+    match entries.as_slice() {
+        [only] if only.is_dir() => (only.clone(), only.file_name().map(|name| name.to_string_lossy().to_string())),
+        _ => (dir.to_path_buf(), None),
+    }
+}
+
+// The bundle ref to import and the store it names: a single refs/trunk/<store>,
+// else HEAD, else the only ref.
+fn bundle_head(heads: &[(String, String)], bundle: &Path) -> Result<(String, String, Option<String>), TrunkError> {
+    let stores: Vec<&(String, String)> = heads.iter().filter(|(name, _)| name.strip_prefix("refs/trunk/").is_some_and(config::is_store_name)).collect();
+    let chosen = match (stores.as_slice(), heads) {
+        ([only], _) => *only,
+        ([], [only]) => only,
+        ([], _) => heads.iter().find(|(name, _)| name == "HEAD").ok_or_else(|| {
+            TrunkError::Usage(format!("{} holds several refs and no HEAD ({}); make a bundle of one of them", bundle.display(), heads.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ")))
+        })?,
+        (several, _) => {
+            return Err(TrunkError::Usage(format!(
+                "{} holds several stores ({}); import one with `git fetch {} refs/trunk/<store>:refs/trunk/<store>`",
+                bundle.display(),
+                several.iter().map(|(name, _)| name.trim_start_matches("refs/trunk/")).collect::<Vec<_>>().join(", "),
+                bundle.display()
+            )));
+        }
+    };
+    let store = chosen.0.strip_prefix("refs/trunk/").map(str::to_string);
+    Ok((chosen.0.clone(), chosen.1.clone(), store))
+}
+
+pub fn run(args: &ImportArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+    let path = fs::canonicalize(&args.path).map_err(|e| TrunkError::NotFound(format!("{}: {}", args.path.display(), e)))?;
+
+    // Step 2: Work out what the path holds and which store it becomes
+    debug!("➡️ Step 2: Reading {}", path.display());
+    let source = detect(&path)?;
+    let scratch = std::env::temp_dir().join(format!("git-trunk-import-{}", std::process::id()));
+    let result = (|| {
+        let (bundle_ref, content_dir, named) = match source {
+            Source::Bundle => {
+                let heads = backup::heads(&repo, &path, verbose)?;
+                let (name, hash, store) = bundle_head(&heads, &path)?;
+                (Some((name, hash)), None, store)
+            }
+            Source::Tar | Source::Zip => {
+                fs::create_dir_all(&scratch).map_err(|e| TrunkError::Io(format!("Failed to create {}: {}", scratch.display(), e)))?;
+                extract(&path, source, &scratch)?;
+                let (root, named) = archive_root(&scratch);
+                (None, Some(root), named)
+            }
+            Source::Directory => (None, Some(path.clone()), None),
+        };
+        let store = args.store.clone().or(named).unwrap_or_else(|| base_name(&path));
+        config::check_store_name(&store).map_err(|reason| TrunkError::Usage(format!("Invalid store name '{}': {}; pick one with --as", store, reason)))?;
+        let trunk_ref_name = format!("refs/trunk/{}", store);
+        if repo.ref_exists(&trunk_ref_name) || config::trunk_base_dir(repo_root, verbose).join(&store).exists() {
+            return Err(TrunkError::Conflict(format!("Store '{}' already exists here ({} or .trunk/{}); import it under another name with --as", store, trunk_ref_name, store)));
+        }
+        info!("✓ Step 2: {} becomes store '{}'", path.display(), store);
+
+        // Step 3: Create refs/trunk/<store>
+        debug!("➡️ Step 3: Creating {}", trunk_ref_name);
+        let tip = match (bundle_ref, content_dir) {
+            (Some((name, hash)), _) => {
+                let output = run_git_command(repo.git().arg("fetch").arg(&path).arg(&name), verbose)
+                    .map_err(|e| TrunkError::Git(format!("Failed to fetch from {}: {}", path.display(), e)))?;
+                if !output.status.success() {
+                    return Err(TrunkError::Git(format!("Failed to fetch {} from {} (run with --verbose for git's message)", name, path.display())));
+                }
+                quota::enforce(&repo, &store, quota::reachable_size(repo_root, &hash, verbose).unwrap_or(0), "Step 3", verbose)?;
+                repo.update_ref(&trunk_ref_name, &hash).map_err(|e| TrunkError::Git(format!("Failed to create {}: {}", trunk_ref_name, e)))?;
+                hash
+            }
+            (None, Some(dir)) => {
+                let message = format!("Initial commit for store '{}'\n\nImported from {}", store, path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default());
+                let commit = repo
+                    .commit_directory(&dir, &message)
+                    .map_err(|e| TrunkError::Git(format!("Failed to commit the files of {}: {}", path.display(), e)))?
+                    .ok_or_else(|| TrunkError::NotFound(format!("{} holds no files to import", path.display())))?;
+                quota::enforce(&repo, &store, quota::reachable_size(repo_root, &commit, verbose).unwrap_or(0), "Step 3", verbose)?;
+                repo.update_ref(&trunk_ref_name, &commit).map_err(|e| TrunkError::Git(format!("Failed to create {}: {}", trunk_ref_name, e)))?;
+                commit
+            }
+            (None, None) => unreachable!("every source yields a bundle ref or a directory"),
+        };
+        info!("✓ Step 3: Created {} at {}", trunk_ref_name, short_hash(repo_root, &tip, verbose));
+        Ok((store, trunk_ref_name))
+    })();
+    let _ = fs::remove_dir_all(&scratch);
+    let (store, trunk_ref_name) = result?;
+
+    // Steps 4-15: Check it out into .trunk/<store>
+    if args.no_checkout {
+        info!("= Step 4: --no-checkout, not creating .trunk/{}", store);
+    } else {
+        debug!("➡️ Step 4: Preparing .trunk for store '{}'", store);
+        ensure_trunk_in_gitignore(repo_root, "Step 4").map_err(|e| TrunkError::Io(format!("Failed to update .gitignore: {}", e)))?;
+        ops::ensure_trunk_dir(repo_root, "Step 4", verbose)?;
+        ops::materialize_store(&repo, &store, verbose)?;
+    }
+
+    info!("✅ Imported {} as store '{}' ({}). Run `git trunk push --store {}` to publish it.", path.display(), store, trunk_ref_name, store);
+    Ok(())
+}
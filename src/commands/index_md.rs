@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use clap::Parser;
+use log::{debug, info};
+use crate::config::{self, TrunkConfig};
+use crate::commands::lint_prose::PROSE_EXTENSIONS;
+use crate::commands::new::TEMPLATES_DIR;
+use crate::frontmatter;
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Regenerate an index of a store's documents grouped by directory", after_help = "Examples:\n  git trunk index-md\n  git trunk index-md --store wiki")]
+pub struct IndexMdArgs {
+    #[arg(long, help = "Index file inside the store [default: config `index` or INDEX.md]")]
+    file: Option<String>,
+}
+
+pub const DEFAULT_INDEX_FILE: &str = "INDEX.md";
+const GENERATED_NOTICE: &str = "<!-- Generated by `git trunk index-md`; manual edits will be overwritten. -->";
+
+// The index file configured for a store with `[store.<name>] index`, if any.
+pub fn configured_index(trunk_config: &TrunkConfig, store_name: &str) -> Option<String> {
+    trunk_config.get_string(&format!("store.{}", store_name), "index").filter(|file| !file.is_empty())
+}
+
+// Last commit date (YYYY-MM-DD) of every path in the store's history.
+fn last_modified_dates(store_dir: &Path, verbose: bool) -> HashMap<String, String> {
+    let mut dates = HashMap::new();
+    let Ok(output) = run_git_command(Command::new("git").arg("log").arg("--format=%x01%cs").arg("--name-only").arg("-z").current_dir(store_dir), verbose) else {
+        return dates;
+    };
+    let mut current = String::new();
+    for field in String::from_utf8_lossy(&output.stdout).split('\0') {
+        let field = field.trim_matches('\n');
+        if field.is_empty() {
+            continue;
+        }
+        if let Some(date) = field.strip_prefix('\u{1}') {
+            current = date.trim().to_string();
+        } else if !current.is_empty() {
+            dates.entry(field.to_string()).or_insert_with(|| current.clone());
+        }
+    }
+    dates
+}
+
+fn link_target(path: &str) -> String {
+    path.replace('%', "%25").replace(' ', "%20").replace('(', "%28").replace(')', "%29")
+}
+
+// Rewrites `index_file` in the store checkout; returns whether it changed.
+pub fn regenerate(store_dir: &Path, store_name: &str, index_file: &str, verbose: bool) -> io::Result<bool> {
+    let listing = run_git_command(
+        Command::new("git").arg("ls-files").arg("-z").arg("--cached").arg("--others").arg("--exclude-standard").current_dir(store_dir),
+        verbose,
+    )?;
+    if !listing.status.success() {
+        return Err(io::Error::other(format!("git ls-files failed in {}", store_dir.display())));
+    }
+    let mut paths: Vec<String> = listing.stdout
+        .split(|b| *b == 0)
+        .map(|path| String::from_utf8_lossy(path).to_string())
+        .filter(|path| !path.is_empty() && path != index_file && !path.starts_with(&format!("{}/", TEMPLATES_DIR)))
+        .filter(|path| store_dir.join(path).is_file())
+        .filter(|path| Path::new(path).extension().and_then(|ext| ext.to_str()).is_some_and(|ext| PROSE_EXTENSIONS.contains(&ext.to_lowercase().as_str())))
+        .collect();
+    paths.sort_by(|a, b| {
+        let dir = |path: &str| path.rsplit_once('/').map(|(dir, _)| dir.to_string()).unwrap_or_default();
+        (dir(a), a.to_lowercase()).cmp(&(dir(b), b.to_lowercase()))
+    });
+    paths.dedup();
+    // Other generated indexes are not documents.
+    let texts: HashMap<String, String> = paths.iter().map(|path| (path.clone(), fs::read_to_string(store_dir.join(path)).unwrap_or_default())).collect();
+    paths.retain(|path| !texts[path].starts_with(GENERATED_NOTICE));
+
+    let dates = last_modified_dates(store_dir, verbose);
+    let dirty: Vec<String> = run_git_command(Command::new("git").arg("status").arg("--porcelain").arg("-z").current_dir(store_dir), verbose)
+        .map(|output| output.stdout.split(|b| *b == 0).filter(|entry| entry.len() > 3).map(|entry| String::from_utf8_lossy(&entry[3..]).to_string()).collect())
+        .unwrap_or_default();
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    let mut out = format!("{}\n# {}\n\n{} document(s).\n", GENERATED_NOTICE, store_name, paths.len());
+    let mut current_dir: Option<String> = None;
+    for path in &paths {
+        let (dir, name) = path.rsplit_once('/').unwrap_or(("", path.as_str()));
+        if current_dir.as_deref() != Some(dir) {
+            out.push_str(&format!("\n## {}\n\n", if dir.is_empty() { "/" } else { dir }));
+            current_dir = Some(dir.to_string());
+        }
+        let text = &texts[path];
+        let title = frontmatter::title(text).unwrap_or_else(|| name.to_string());
+        let date = if dirty.contains(path) { today.clone() } else { dates.get(path).cloned().unwrap_or_else(|| today.clone()) };
+        out.push_str(&format!("- [{}]({}) — {}\n", title.replace(['[', ']'], ""), link_target(path), date));
+    }
+
+    let index_path = store_dir.join(index_file);
+    if fs::read_to_string(&index_path).ok().as_deref() == Some(out.as_str()) {
+        return Ok(false);
+    }
+    if let Some(parent) = index_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&index_path, out)?;
+    Ok(true)
+}
+
+pub fn run(args: &IndexMdArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Check the store checkout
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    debug!("➡️ Step 2: Checking {}", store_dir_relative_path);
+    if !trunk_store_dir.join(".git").exists() {
+        return Err(TrunkError::NotFound(format!("{} is not checked out. Run `git trunk checkout --store {}` first.", store_dir_relative_path, store_name)));
+    }
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let index_file = args.file.clone()
+        .or_else(|| configured_index(&trunk_config, store_name))
+        .unwrap_or_else(|| DEFAULT_INDEX_FILE.to_string());
+    info!("✓ Step 2: Indexing {} into {}", store_dir_relative_path, index_file);
+
+    // Step 3: Regenerate the index
+    debug!("➡️ Step 3: Regenerating {}/{}", store_dir_relative_path, index_file);
+    match regenerate(&trunk_store_dir, store_name, &index_file, verbose) {
+        Ok(true) => info!("✓ Step 3: Wrote {}/{}", store_dir_relative_path, index_file),
+        Ok(false) => info!("= Step 3: {}/{} is already up to date", store_dir_relative_path, index_file),
+        Err(e) => {
+            return Err(TrunkError::Failed(format!("Failed to regenerate {}/{}: {}", store_dir_relative_path, index_file, e)));
+        }
+    }
+
+    info!("✅ Index for store '{}' regenerated. Run `git trunk commit --store {}` to commit it.", store_name, store_name);
+    Ok(())
+}
@@ -1,101 +1,132 @@
 use std::fs;
-use std::path::{Path, PathBuf};
-use std::process::{Command, exit};
+use std::path::Path;
+use std::process::Command;
 use clap::Parser;
 use log::{debug, error, info};
-use crate::utils::run_git_command;
+use crate::table::{print_line, print_rule, Cell, Color, Table};
+use crate::config;
+use crate::json::Json;
+use crate::quota;
+use crate::template::Template;
+use crate::stores::StoreInfo;
+use crate::utils::{run_git_command, short_hash};
 use chrono::{DateTime, Local};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
 
 #[derive(Parser, Debug)]
-#[command(about = "Displays information about the git-trunk setup and stores")]
+#[command(about = "Displays information about the git-trunk setup and stores", after_help = "Examples:\n  git trunk info\n  git trunk info --all --short\n  git trunk info --short --format '{store}\\t{local_hash}\\t{remote_hash}'\n  git trunk info --json")]
 pub struct InfoArgs {
     #[arg(long, help = "Discover and display information for all stores found on the remote")]
     all: bool,
+    #[arg(long, help = "Show one summary row per store instead of the detailed view")]
+    short: bool,
+    #[arg(long, help = "With --short, print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+    #[arg(long, conflicts_with = "short", help = "Output every store's details as a JSON document instead of text")]
+    json: bool,
+    #[arg(long, value_name = "TEMPLATE", requires = "short", conflicts_with = "no_table", help = "With --short, print one line per store from a template; placeholders: {store}, {remote}, {path}, {checkout}, {uncommitted}, {local_hash}, {local_date}, {remote_hash}")]
+    format: Option<String>,
 }
 
-struct StoreInfo {
-    name: String,
-    local_path: PathBuf,
-    local_path_exists: bool,
-    is_git_repo: bool,
-    local_store_last_commit_date: Option<String>,
-    local_store_last_commit_hash: Option<String>,
-    local_store_uncommitted_changes: Option<String>, // "Clean" or "X uncommitted changes"
-    main_repo_ref: String,
-    main_repo_ref_exists: bool,
-    main_repo_ref_commit_date: Option<String>,
-    main_repo_ref_commit_hash: Option<String>,
-    remote_repo_ref_exists: Option<bool>, // None if remote check fails or not applicable
-    remote_repo_ref_commit_hash: Option<String>,
-}
+const FORMAT_FIELDS: &[&str] = &["store", "remote", "path", "checkout", "uncommitted", "local_hash", "local_date", "remote_hash"];
 
-fn get_commit_info(repo_path: &Path, ref_name: &str, verbose: bool) -> (Option<String>, Option<String>) {
-    match run_git_command(
-        Command::new("git")
-            .arg("log")
-            .arg("-1")
-            .arg("--pretty=format:%h%n%at") // hash newline unixtimestamp
-            .arg(ref_name)
-            .current_dir(repo_path),
-        verbose,
-    ) {
-        Ok(output) if output.status.success() => {
-            let out_str = String::from_utf8_lossy(&output.stdout);
-            let parts: Vec<&str> = out_str.trim().split('\n').collect();
-            if parts.len() == 2 {
-                let hash = parts[0].to_string();
-                let timestamp_str = parts[1];
-                if let Ok(timestamp_secs) = timestamp_str.parse::<i64>() {
-                    // Use DateTime::from_timestamp to create a DateTime<Utc> directly
-                    match DateTime::from_timestamp(timestamp_secs, 0) {
-                        Some(utc_dt) => {
-                            // Convert to local time
-                            let local_dt: DateTime<Local> = utc_dt.with_timezone(&Local);
-                            return (Some(local_dt.format("%Y-%m-%d %H:%M:%S").to_string()), Some(hash));
-                        }
-                        None => {
-                            debug!("🕰️ Failed to create DateTime<Utc> from timestamp: {}", timestamp_secs);
-                            return (Some("Invalid date".to_string()), Some(hash));
-                        }
-                    }
-                }
-                debug!("🕰️ Failed to parse timestamp string: {}", timestamp_str);
-                (None, Some(hash)) // Return hash even if date parsing fails
-            } else {
-                debug!("🕰️ Unexpected format from git log output: {}", out_str);
-                (None, None)
-            }
+impl StoreInfo {
+    // "Clean" or "X uncommitted change(s)"
+    fn uncommitted_summary(&self) -> Option<String> {
+        if !self.is_git_repo {
+            return None;
         }
-        Ok(output) => {
-            debug!("🔍 Git log command for ref '{}' in '{}' failed or returned no info. Exit_code: {:?}, stdout: {}, stderr: {}", ref_name, repo_path.display(), output.status.code(), String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
-            (None,None)
-        }
-        Err(e) => {
-            debug!("🔍 Failed to execute git log for ref '{}' in '{}': {}", ref_name, repo_path.display(), e);
-            (None, None)
-        },
+        Some(match self.local_store_uncommitted_count {
+            Some(0) => "Clean".to_string(),
+            Some(count) => format!("{} uncommitted change(s)", count),
+            None => "Status check failed".to_string(),
+        })
+    }
+
+    // The values of the --format placeholders; unknown values are left empty.
+    fn format_values<'a>(&self, remote_name: &str) -> Vec<(&'a str, String)> {
+        vec![
+            ("store", self.name.clone()),
+            ("remote", remote_name.to_string()),
+            ("path", format!(".trunk/{}", self.name)),
+            ("checkout", if self.is_git_repo { "yes" } else { "no" }.to_string()),
+            ("uncommitted", self.local_store_uncommitted_count.map(|count| count.to_string()).unwrap_or_default()),
+            ("local_hash", self.main_repo_ref_commit_hash.clone().unwrap_or_default()),
+            ("local_date", self.main_repo_ref_commit_date.map(|date| date.to_rfc3339()).unwrap_or_default()),
+            ("remote_hash", self.remote_repo_ref_commit_hash.clone().unwrap_or_default()),
+        ]
+    }
+
+    fn to_json(&self, repo_root: &Path) -> Json {
+        let optional = |value: Option<Json>| value.unwrap_or(Json::Null);
+        let commit = |hash: &Option<String>, date: &Option<DateTime<Local>>| match hash {
+            Some(hash) => Json::Object(vec![
+                ("hash".to_string(), Json::from(hash.as_str())),
+                ("date".to_string(), optional(date.map(|date| Json::from(date.to_rfc3339())))),
+            ]),
+            None => Json::Null,
+        };
+        let local_path = self.local_path.strip_prefix(repo_root).unwrap_or(&self.local_path);
+        Json::Object(vec![
+            ("name".to_string(), Json::from(self.name.as_str())),
+            ("local".to_string(), Json::Object(vec![
+                ("path".to_string(), Json::from(local_path.display().to_string())),
+                ("exists".to_string(), Json::Bool(self.local_path_exists)),
+                ("is_git_repo".to_string(), Json::Bool(self.is_git_repo)),
+                ("last_commit".to_string(), commit(&self.local_store_last_commit_hash, &self.local_store_last_commit_date)),
+                ("uncommitted_changes".to_string(), optional(self.local_store_uncommitted_count.map(|count| Json::Number(count as f64)))),
+            ])),
+            ("ref".to_string(), Json::Object(vec![
+                ("name".to_string(), Json::from(self.main_repo_ref.as_str())),
+                ("exists".to_string(), Json::Bool(self.main_repo_ref_exists)),
+                ("last_commit".to_string(), commit(&self.main_repo_ref_commit_hash, &self.main_repo_ref_commit_date)),
+                ("size_bytes".to_string(), optional(self.main_repo_ref_size.map(|size| Json::Number(size as f64)))),
+                ("quota_bytes".to_string(), optional(self.main_repo_ref_quota.map(|quota| Json::Number(quota as f64)))),
+            ])),
+            ("remote".to_string(), Json::Object(vec![
+                ("exists".to_string(), optional(self.remote_repo_ref_exists.map(Json::Bool))), // null when the remote could not be reached
+                ("hash".to_string(), optional(self.remote_repo_ref_commit_hash.as_deref().map(Json::from))),
+            ])),
+        ])
     }
 }
 
+fn print_json(remote_name: &str, stores: &[StoreInfo], repo_root: &Path) {
+    println!("{}", Json::Object(vec![
+        ("remote".to_string(), Json::from(remote_name)),
+        ("stores".to_string(), Json::Array(stores.iter().map(|store| store.to_json(repo_root)).collect())),
+    ]));
+}
+
+// "<date> (<short hash>)" for the detailed view.
+fn describe_commit(repo_path: &Path, hash: &Option<String>, date: &Option<DateTime<Local>>, verbose: bool) -> String {
+    format!("{} ({})",
+        date.map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_else(|| "N/A".to_string()),
+        hash.as_deref().map(|hash| short_hash(repo_path, hash, verbose)).unwrap_or_else(|| "N/A".to_string()))
+}
 
-pub fn run(args: &InfoArgs, remote_name: &str, global_store_name: &str, verbose: bool) {
+pub fn run(args: &InfoArgs, remote_name: &str, global_store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let template = args.format.as_deref().map(|format| Template::parse(format, FORMAT_FIELDS)).transpose()?;
+    // Templated lines and JSON are for scripts, so they skip the headers
+    let headers = !args.json && template.is_none();
     info!("🐘 Git Trunk Information");
 
     // Get repository root
     debug!("➡️ Getting repository root");
-    let repo_root_output = run_git_command(Command::new("git").arg("rev-parse").arg("--show-toplevel"), verbose)
-        .unwrap_or_else(|e| { error!("❌ Failed to get git repository root: {}", e); exit(1); });
-    let repo_root_str = String::from_utf8_lossy(&repo_root_output.stdout).trim().to_string();
-    if repo_root_str.is_empty() { error!("❌ Git repository root is empty."); exit(1); }
-    let repo_root = PathBuf::from(repo_root_str);
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root().to_path_buf();
     debug!("✓ Repository root found at {}", repo_root.display());
 
-    let trunk_base_dir = repo_root.join(".trunk");
+    let trunk_base_dir = config::trunk_base_dir(&repo_root, verbose);
+    let trunk_config = config::load(&repo_root, verbose).ok();
     let mut stores_to_check: Vec<String> = Vec::new();
 
     if args.all {
-        println!("\n🌳 Git Trunk Stores Overview (Remote: '{}', Mode: All Remote Stores)", remote_name);
-        println!("{:-<100}", "");
+        if headers {
+            print_line(&format!("\n🌳 Git Trunk Stores Overview (Remote: '{}', Mode: All Remote Stores)", remote_name));
+            print_rule();
+        }
         debug!("➡️ --all specified, discovering stores from remote '{}'", remote_name);
         match run_git_command(
             Command::new("git")
@@ -108,7 +139,6 @@ pub fn run(args: &InfoArgs, remote_name: &str, global_store_name: &str, verbose:
                 let output_str = String::from_utf8_lossy(&output.stdout);
                 if output_str.trim().is_empty() {
                     info!("ℹ️ No remote refs found under 'refs/trunk/' on remote '{}'.", remote_name);
-                    return;
                 }
                 for line in output_str.lines() {
                     let parts: Vec<&str> = line.split_whitespace().collect();
@@ -116,33 +146,31 @@ pub fn run(args: &InfoArgs, remote_name: &str, global_store_name: &str, verbose:
                         let ref_name_full = parts[1]; // e.g., refs/trunk/main
                         if let Some(store_name_from_ref) = ref_name_full.strip_prefix("refs/trunk/") {
                             // Ensure it's a direct child, not refs/trunk/foo/bar
-                            if !store_name_from_ref.is_empty() && !store_name_from_ref.contains('/') {
-                                 if !stores_to_check.contains(&store_name_from_ref.to_string()){
-                                    stores_to_check.push(store_name_from_ref.to_string());
-                                 }
+                            if config::is_store_name(store_name_from_ref)
+                                && !stores_to_check.contains(&store_name_from_ref.to_string()) {
+                                stores_to_check.push(store_name_from_ref.to_string());
                             }
                         }
                     }
                 }
-                 if stores_to_check.is_empty() {
+                if !output_str.trim().is_empty() && stores_to_check.is_empty() {
                     info!("ℹ️ No valid store names parsed from 'refs/trunk/*' on remote '{}'.", remote_name);
-                    return;
                 }
             }
             Ok(output) => { // ls-remote succeeded but no refs, or other non-zero exit
                 info!("ℹ️ No remote refs found under 'refs/trunk/' on remote '{}' (or command failed, exit code: {:?}).", remote_name, output.status.code());
                 debug!("ls-remote stdout: {}", String::from_utf8_lossy(&output.stdout));
                 debug!("ls-remote stderr: {}", String::from_utf8_lossy(&output.stderr));
-                return;
             }
             Err(e) => {
                 error!("❌ Failed to execute 'git ls-remote' for remote '{}': {}", remote_name, e);
-                return;
             }
         }
     } else { // Not --all, use local discovery or specified global_store_name
-        println!("\n🌳 Git Trunk Stores Overview (Remote: '{}')", remote_name);
-        println!("{:-<100}", "");
+        if headers {
+            print_line(&format!("\n🌳 Git Trunk Stores Overview (Remote: '{}')", remote_name));
+            print_rule();
+        }
 
         if global_store_name != "main" { // User explicitly specified a store via global --store
             debug!("➡️ Using explicitly specified store: {}", global_store_name);
@@ -167,20 +195,13 @@ pub fn run(args: &InfoArgs, remote_name: &str, global_store_name: &str, verbose:
                 }
             }
             // Discover stores from refs/trunk/ in main repo
-            match run_git_command(Command::new("git").arg("for-each-ref").arg("--format=%(refname:short)").arg("refs/trunk/").current_dir(&repo_root), verbose) {
-                Ok(output) if output.status.success() => {
-                    String::from_utf8_lossy(&output.stdout).lines().for_each(|line| {
-                        if let Some(name) = line.strip_prefix("trunk/") {
-                             // Ensure it's a direct child, not trunk/foo/bar
-                            if !name.is_empty() && !name.contains('/') {
-                                if !stores_to_check.contains(&name.to_string()){
-                                    stores_to_check.push(name.to_string());
-                                }
-                            }
-                        }
-                    });
+            if let Ok(names) = repo.ref_names("refs/trunk/") {
+                for name in names {
+                    // Ensure it's a direct child, not trunk/foo/bar
+                    if config::is_store_name(&name) && !stores_to_check.contains(&name) {
+                        stores_to_check.push(name);
+                    }
                 }
-                _ => { /* Ignore error, refs might not exist */ }
             }
             // Ensure "main" is checked if it's the target, even if not found locally yet (might be on remote)
             if !stores_to_check.contains(&"main".to_string()) {
@@ -193,109 +214,90 @@ pub fn run(args: &InfoArgs, remote_name: &str, global_store_name: &str, verbose:
     stores_to_check.dedup();
 
     if stores_to_check.is_empty() {
-        if args.all {
-             info!("ℹ️ No git-trunk stores found on remote '{}' under refs/trunk/.", remote_name);
-        } else {
+        // With --all, the discovery above already logged why the remote listed no stores
+        if !args.all {
              info!("ℹ️ No git-trunk stores found or specified locally for store '{}'.", global_store_name);
         }
-        return;
+        if args.json {
+            print_json(remote_name, &[], &repo_root);
+        }
+        return Ok(());
     }
     
     // The header print was moved up into the if/else args.all block.
 
+    let mut short_table = Table::new(&["Store", "Local Dir", "Local Status", "Local Ref", "Remote Ref"]);
+    let mut json_stores: Vec<StoreInfo> = Vec::new();
+
     for store_name in stores_to_check {
         debug!("➡️ Processing store: {}", store_name);
-        let mut store_info = StoreInfo {
-            name: store_name.clone(),
-            local_path: trunk_base_dir.join(&store_name),
-            local_path_exists: false,
-            is_git_repo: false,
-            local_store_last_commit_date: None,
-            local_store_last_commit_hash: None,
-            local_store_uncommitted_changes: None,
-            main_repo_ref: format!("refs/trunk/{}", store_name),
-            main_repo_ref_exists: false,
-            main_repo_ref_commit_date: None,
-            main_repo_ref_commit_hash: None,
-            remote_repo_ref_exists: None,
-            remote_repo_ref_commit_hash: None,
-        };
-
-        store_info.local_path_exists = store_info.local_path.exists() && store_info.local_path.is_dir();
-
-        if store_info.local_path_exists {
-            store_info.is_git_repo = store_info.local_path.join(".git").exists();
-            if store_info.is_git_repo {
-                let (date, hash) = get_commit_info(&store_info.local_path, "HEAD", verbose);
-                store_info.local_store_last_commit_date = date;
-                store_info.local_store_last_commit_hash = hash;
+        let store_info = StoreInfo::gather(&repo, trunk_config.as_ref(), &store_name, remote_name, true, !args.short, verbose);
 
-                match run_git_command(Command::new("git").arg("status").arg("--porcelain").current_dir(&store_info.local_path), verbose) {
-                    Ok(output) if output.status.success() => {
-                        if output.stdout.is_empty() {
-                            store_info.local_store_uncommitted_changes = Some("Clean".to_string());
-                        } else {
-                            let count = String::from_utf8_lossy(&output.stdout).lines().count();
-                            store_info.local_store_uncommitted_changes = Some(format!("{} uncommitted change(s)", count));
-                        }
-                    }
-                    _ => store_info.local_store_uncommitted_changes = Some("Status check failed".to_string()),
-                }
-            }
+        if args.json {
+            json_stores.push(store_info);
+            continue;
         }
-
-        store_info.main_repo_ref_exists = run_git_command(Command::new("git").arg("rev-parse").arg("--verify").arg(&store_info.main_repo_ref).current_dir(&repo_root), verbose)
-            .map_or(false, |out| out.status.success());
-        
-        if store_info.main_repo_ref_exists {
-            let (date, hash) = get_commit_info(&repo_root, &store_info.main_repo_ref, verbose);
-            store_info.main_repo_ref_commit_date = date;
-            store_info.main_repo_ref_commit_hash = hash;
+        if let Some(template) = &template {
+            println!("{}", template.render(&store_info.format_values(remote_name)));
+            continue;
         }
-
-        match run_git_command(Command::new("git").arg("ls-remote").arg(remote_name).arg(&store_info.main_repo_ref).current_dir(&repo_root), verbose) {
-            Ok(output) => {
-                if output.status.success() && !output.stdout.is_empty() {
-                    store_info.remote_repo_ref_exists = Some(true);
-                    let remote_out = String::from_utf8_lossy(&output.stdout);
-                    store_info.remote_repo_ref_commit_hash = remote_out.split_whitespace().next().map(|s| s[0..7].to_string()); // Take first 7 chars of hash
-                } else {
-                    store_info.remote_repo_ref_exists = Some(false);
-                }
-            }
-            Err(e) => {
-                debug!("⚠️ Failed to check remote ref for store {}: {}", store_name, e);
-                store_info.remote_repo_ref_exists = None; // Indicate check failed
-            }
+        if args.short {
+            let local_dir = if store_info.local_path_exists { format!(".trunk/{}", store_info.name) } else { "missing".to_string() };
+            short_table.add_row(vec![
+                store_info.name.as_str().into(),
+                Cell::flag(store_info.local_path_exists && store_info.is_git_repo, &local_dir),
+                store_info.uncommitted_summary().unwrap_or_else(|| "N/A".to_string()).into(),
+                match &store_info.main_repo_ref_commit_hash {
+                    Some(hash) if store_info.main_repo_ref_exists => Cell::flag(true, &short_hash(&repo_root, hash, verbose)),
+                    _ => Cell::flag(false, "missing"),
+                },
+                match store_info.remote_repo_ref_exists {
+                    Some(true) => Cell::flag(true, &store_info.remote_repo_ref_commit_hash.as_deref().map(|hash| short_hash(&repo_root, hash, verbose)).unwrap_or_else(|| "N/A".to_string())),
+                    Some(false) => Cell::flag(false, "missing"),
+                    None => Cell::colored("? check failed", Color::Yellow),
+                },
+            ]);
+            continue;
         }
-        
+
         // Presentation
-        println!("\nStore: {}", store_info.name);
-        println!("  Local Directory (.trunk/{})", store_info.name);
-        println!("    Exists: {}", if store_info.local_path_exists { "✓ Yes" } else { "❌ No" });
+        print_line(&format!("\nStore: {}", store_info.name));
+        print_line(&format!("  Local Directory (.trunk/{})", store_info.name));
+        print_line(&format!("    Exists: {}", if store_info.local_path_exists { "✓ Yes" } else { "❌ No" }));
         if store_info.local_path_exists {
-            println!("    Is Git Repo: {}", if store_info.is_git_repo { "✓ Yes" } else { "❌ No" });
+            print_line(&format!("    Is Git Repo: {}", if store_info.is_git_repo { "✓ Yes" } else { "❌ No" }));
             if store_info.is_git_repo {
-                println!("    Last Commit: {} ({})",
-                    store_info.local_store_last_commit_date.as_deref().unwrap_or("N/A"),
-                    store_info.local_store_last_commit_hash.as_deref().unwrap_or("N/A"));
-                println!("    Status: {}", store_info.local_store_uncommitted_changes.as_deref().unwrap_or("N/A"));
+                print_line(&format!("    Last Commit: {}", describe_commit(&store_info.local_path, &store_info.local_store_last_commit_hash, &store_info.local_store_last_commit_date, verbose)));
+                print_line(&format!("    Status: {}", store_info.uncommitted_summary().as_deref().unwrap_or("N/A")));
             }
         }
-        println!("  Main Repository Ref (refs/trunk/{})", store_info.name);
-        println!("    Exists Locally: {}", if store_info.main_repo_ref_exists { "✓ Yes" } else { "❌ No" });
+        print_line(&format!("  Main Repository Ref (refs/trunk/{})", store_info.name));
+        print_line(&format!("    Exists Locally: {}", if store_info.main_repo_ref_exists { "✓ Yes" } else { "❌ No" }));
         if store_info.main_repo_ref_exists {
-             println!("    Last Commit: {} ({})",
-                store_info.main_repo_ref_commit_date.as_deref().unwrap_or("N/A"),
-                store_info.main_repo_ref_commit_hash.as_deref().unwrap_or("N/A"));
+             print_line(&format!("    Last Commit: {}", describe_commit(&repo_root, &store_info.main_repo_ref_commit_hash, &store_info.main_repo_ref_commit_date, verbose)));
+            if let Some(size) = store_info.main_repo_ref_size {
+                match store_info.main_repo_ref_quota {
+                    Some(max_bytes) => print_line(&format!("    Size: {} of {} quota ({}%)",
+                        quota::format_size(size), quota::format_size(max_bytes), size * 100 / max_bytes.max(1))),
+                    None => print_line(&format!("    Size: {}", quota::format_size(size))),
+                }
+            }
         }
-        println!("  Remote '{}' Ref (refs/trunk/{})", remote_name, store_info.name);
+        print_line(&format!("  Remote '{}' Ref (refs/trunk/{})", remote_name, store_info.name));
         match store_info.remote_repo_ref_exists {
-            Some(true) => println!("    Exists on Remote: ✓ Yes (Hash: {})", store_info.remote_repo_ref_commit_hash.as_deref().unwrap_or("N/A")),
-            Some(false) => println!("    Exists on Remote: ❌ No"),
-            None => println!("    Exists on Remote: ❓ Check failed"),
+            Some(true) => print_line(&format!("    Exists on Remote: ✓ Yes (Hash: {})", store_info.remote_repo_ref_commit_hash.as_deref().map(|hash| short_hash(&repo_root, hash, verbose)).unwrap_or_else(|| "N/A".to_string()))),
+            Some(false) => print_line("    Exists on Remote: ❌ No"),
+            None => print_line("    Exists on Remote: ❓ Check failed"),
         }
-        println!("{:-<100}", "");
+        print_rule();
 
     }
-}
\ No newline at end of file
+
+    if args.json {
+        print_json(remote_name, &json_stores, &repo_root);
+    } else if args.short && template.is_none() {
+        println!();
+        short_table.print(args.no_table);
+    }
+    Ok(())
+}
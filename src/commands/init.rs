@@ -1,131 +1,109 @@
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::Path;
-use std::process::{Command, exit};
+use std::process::Command;
 use clap::Parser;
 use log::{debug, error, info};
-use crate::utils::{run_git_command, ensure_trunk_in_gitignore};
+use crate::commands::about::{self, ABOUT_FILE};
+use crate::locks;
+use crate::ops;
+use crate::utils::{dry_run, is_dry_run, describe_command, run_git_command, ensure_trunk_in_gitignore, shared_repository, apply_shared_permissions, init_object_format_arg};
+use crate::error::TrunkError;
 
 #[derive(Parser, Debug)]
-#[command(about = "Initialize a .trunk/<store> directory")]
+#[command(about = "Initialize a .trunk/<store> directory", after_help = "Examples:\n  git trunk init\n  git trunk init --store blog\n  git trunk init --store incidents --purpose \"Postmortems of production incidents\" --contact \"#sre\"")]
 pub struct InitArgs {
     #[arg(long, help = "Force initialization, overwriting existing .trunk/<store> directory")]
-    force: bool,
+    pub force: bool,
+    #[arg(long, help = "What the store is for, written to ABOUT.md")]
+    pub purpose: Option<String>,
+    #[arg(long, help = "Where to ask about the store (a channel, list or address), written to ABOUT.md")]
+    pub contact: Option<String>,
 }
 
-pub fn run(args: &InitArgs, _remote_name: &str, store_name: &str, verbose: bool) {
-    // Step 1: Check if we are in a Git repository
-    debug!("➡️ Step 1: Checking if inside a Git repository");
-    let git_check_output = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--is-inside-work-tree"),
-        verbose,
-    );
-    if git_check_output
-        .map(|output| !output.status.success())
-        .unwrap_or(true)
-    {
-        error!("❌ init can only be invoked inside a git repo");
-        exit(1);
-    }
-    info!("✓ Step 1: Confirmed inside a Git repository");
-
-    // Step 2: Get repository root
-    debug!("➡️ Step 2: Getting repository root");
-    let repo_root_output = run_git_command(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--show-toplevel"),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to get git repository root: {}", e);
-        exit(1);
-    });
-    let repo_root_str = String::from_utf8_lossy(&repo_root_output.stdout).trim().to_string();
-    if repo_root_str.is_empty() {
-        error!("❌ Git repository root is empty. Ensure you are in a valid Git repository.");
-        exit(1);
-    }
-    let repo_root = Path::new(&repo_root_str);
-    info!("✓ Step 2: Repository root found at {}", repo_root.display());
+pub fn run(args: &InitArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1 & 2: Check that we are in a Git repository and get its root
+    let repo = ops::ensure_repo_root("Step 1 & 2", verbose)?;
+    let repo_root = repo.root();
+    ops::check_foreign_refs(&repo, "Step 2b", verbose)?;
 
     // Step 3: Ensure .trunk is in .gitignore (parent directory)
     debug!("➡️ Step 3: Ensuring .trunk is in .gitignore");
     if let Err(e) = ensure_trunk_in_gitignore(repo_root, "Step 3") {
-        error!("❌ Failed to update .gitignore for Step 3: {}", e);
-        exit(1);
+        return Err(TrunkError::Git(format!("Failed to update .gitignore for Step 3: {}", e)));
     }
     // Detailed info/debug for Step 3 (added/already exists) is handled by ensure_trunk_in_gitignore
     
     // Step 4: Create .trunk parent directory if it doesn't exist
-    let parent_trunk_dir = repo_root.join(".trunk");
-    if !parent_trunk_dir.exists() {
-        debug!("✨ Step 4a: Creating parent .trunk directory");
-        fs::create_dir(&parent_trunk_dir).unwrap_or_else(|e| {
-            error!("❌ Failed to create .trunk parent directory: {}", e);
-            exit(1);
-        });
-        info!("✓ Step 4a: .trunk parent directory created at {:?}", parent_trunk_dir);
-    }
-
+    let shared = shared_repository(repo_root, verbose);
+    let parent_trunk_dir = ops::ensure_trunk_dir(repo_root, "Step 4a", verbose)?;
 
     // Step 5: Create .trunk/<store_name> directory
     let store_dir_name = format!(".trunk/{}", store_name);
     debug!("➡️ Step 5: Checking for {} directory", store_dir_name);
-    let trunk_store_dir = Path::new(&repo_root).join(&store_dir_name);
+    let trunk_store_dir = parent_trunk_dir.join(store_name);
     if trunk_store_dir.exists() {
-        if args.force {
+        if args.force && dry_run(&format!("remove directory {}", trunk_store_dir.display())) {
+            debug!("= Step 5: {} exists and would be replaced", store_dir_name);
+        } else if args.force {
             debug!("🗑️ Step 5: {} exists, --force specified, removing existing directory", store_dir_name);
-            fs::remove_dir_all(&trunk_store_dir).unwrap_or_else(|e| {
-                error!("❌ Failed to remove existing {} directory: {}", store_dir_name, e);
-                exit(1);
-            });
+            fs::remove_dir_all(&trunk_store_dir).map_err(|e| TrunkError::Io(format!("Failed to remove existing {} directory: {}", store_dir_name, e)))?;
             info!("✓ Step 5: Existing {} directory removed", store_dir_name);
         } else {
             info!("= Step 5: Trunk store '{}' is already initialized in this repository at {}", store_name, store_dir_name);
-            return;
+            return Ok(());
         }
     }
+    // The Step 7 git init command, built early so --dry-run can show it
+    let mut init_command = Command::new("git");
+    init_command.arg("init").current_dir(&trunk_store_dir);
+    if let Some(shared_value) = shared.init_value() {
+        init_command.arg(format!("--shared={}", shared_value));
+    }
+    if let Some(format_arg) = init_object_format_arg(repo_root, verbose) {
+        init_command.arg(format_arg);
+    }
+    if is_dry_run() {
+        // Nothing below can run without the directory, so describe it instead
+        let commit_message = format!("Initial commit for store '{}'", store_name);
+        dry_run(&format!("create directory {}", trunk_store_dir.display()));
+        dry_run(&format!("create file {}", trunk_store_dir.join("readme.md").display()));
+        dry_run(&format!("create file {}", trunk_store_dir.join(ABOUT_FILE).display()));
+        dry_run(&format!("run {}", describe_command(&init_command)));
+        dry_run(&format!("run {}", describe_command(Command::new("git").arg("add").arg("-A").current_dir(&trunk_store_dir))));
+        dry_run(&format!("run {}", describe_command(Command::new("git").arg("commit").arg("-m").arg(&commit_message).current_dir(&trunk_store_dir))));
+        return Ok(());
+    }
+
     debug!("✨ Step 5: Creating {} directory", store_dir_name);
-    fs::create_dir(&trunk_store_dir).unwrap_or_else(|e| {
-        error!("❌ Failed to create {} directory: {}", store_dir_name, e);
-        exit(1);
-    });
+    fs::create_dir(&trunk_store_dir).map_err(|e| TrunkError::Io(format!("Failed to create {} directory: {}", store_dir_name, e)))?;
+    if let Err(e) = apply_shared_permissions(&trunk_store_dir, shared) {
+        error!("⚠️ Warning: {}", e);
+    }
     info!("✓ Step 5: {} directory created", store_dir_name);
 
-    // Step 6: Create .trunk/<store_name>/readme.md
-    debug!("✨ Step 6: Creating {}/readme.md", store_dir_name);
+    // Step 6: Create .trunk/<store_name>/readme.md and ABOUT.md
+    debug!("✨ Step 6: Creating {}/readme.md and {}", store_dir_name, ABOUT_FILE);
     let readme_path = trunk_store_dir.join("readme.md");
-    let mut readme_file = File::create(&readme_path).unwrap_or_else(|e| {
-        error!("❌ Failed to create readme.md in {}: {}", store_dir_name, e);
-        exit(1);
-    });
+    let mut readme_file = File::create(&readme_path).map_err(|e| TrunkError::Io(format!("Failed to create readme.md in {}: {}", store_dir_name, e)))?;
     writeln!(
         readme_file,
         "# Trunk Documents for Store: {}\n\nThis directory stores repository-wide documents for the '{}' store, managed by git-trunk.",
         store_name, store_name
     )
     .expect("Failed to write to readme.md");
-    info!("✓ Step 6: Created {}/readme.md", store_dir_name);
+    // ABOUT.md tells whoever finds the store what it is and who maintains it
+    let owners: Vec<String> = locks::identity(&repo, verbose).ok().map(|me| format!("{} <{}>", me.name, me.email)).into_iter().collect();
+    let about_text = about::template(store_name, args.purpose.as_deref().unwrap_or_default(), &owners, args.contact.as_deref().unwrap_or_default());
+    fs::write(trunk_store_dir.join(ABOUT_FILE), about_text).map_err(|e| TrunkError::Io(format!("Failed to create {} in {}: {}", ABOUT_FILE, store_dir_name, e)))?;
+    info!("✓ Step 6: Created {}/readme.md and {}", store_dir_name, ABOUT_FILE);
 
     // Step 7: Initialize Git in .trunk/<store_name>
     debug!("⚙️ Step 7: Initializing Git repository in {}", store_dir_name);
-    let init_status = run_git_command(
-        Command::new("git")
-            .arg("init")
-            .current_dir(&trunk_store_dir),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to run git init in {}: {}", store_dir_name, e);
-        exit(1);
-    })
+    let init_status = run_git_command(&mut init_command, verbose)
+    .map_err(|e| TrunkError::Git(format!("Failed to run git init in {}: {}", store_dir_name, e)))?
     .status;
     if !init_status.success() {
-        error!("❌ git init failed in {}", store_dir_name);
-        exit(1);
+        return Err(TrunkError::Git(format!("git init failed in {}", store_dir_name)));
     }
     info!("✓ Step 7: Git repository initialized in {}", store_dir_name);
 
@@ -138,14 +116,10 @@ pub fn run(args: &InitArgs, _remote_name: &str, store_name: &str, verbose: bool)
             .current_dir(&trunk_store_dir),
         verbose,
     )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to run git add in {}: {}", store_dir_name, e);
-        exit(1);
-    })
+    .map_err(|e| TrunkError::Git(format!("Failed to run git add in {}: {}", store_dir_name, e)))?
     .status;
     if !stage_status.success() {
-        error!("❌ git add failed in {}", store_dir_name);
-        exit(1);
+        return Err(TrunkError::Git(format!("git add failed in {}", store_dir_name)));
     }
     info!("✓ Step 8: Files staged in {}", store_dir_name);
 
@@ -160,16 +134,14 @@ pub fn run(args: &InitArgs, _remote_name: &str, store_name: &str, verbose: bool)
             .current_dir(&trunk_store_dir),
         verbose,
     )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to run git commit in {}: {}", store_dir_name, e);
-        exit(1);
-    })
+    .map_err(|e| TrunkError::Git(format!("Failed to run git commit in {}: {}", store_dir_name, e)))?
     .status;
     if !commit_status.success() {
-        error!("❌ git commit failed in {}", store_dir_name);
-        exit(1);
+        return Err(TrunkError::Git(format!("git commit failed in {}", store_dir_name)));
     }
     info!("✓ Step 9: Initial commit created for store '{}'", store_name);
+    ops::record_checkout(repo_root, store_name, verbose);
 
     info!("✅ Trunk store '{}' initialized successfully at {}", store_name, store_dir_name);
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config::{self, TrunkConfig};
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Spell-check and prose-lint the documents of a git-trunk store", after_help = "Examples:\n  git trunk lint-prose\n  git trunk lint-prose --changed --fix\n  git trunk lint-prose --fix --fix-repeated")]
+pub struct LintProseArgs {
+    #[arg(long, help = "Only lint files with uncommitted changes in .trunk/<store>")]
+    changed: bool,
+    #[arg(long, help = "Correct known misspellings in place")]
+    fix: bool,
+    #[arg(long, requires = "fix", help = "With --fix, also remove repeated words, which are sometimes intended (\"had had\")")]
+    fix_repeated: bool,
+}
+
+// Extensions treated as prose.
+pub const PROSE_EXTENSIONS: [&str; 6] = ["md", "markdown", "txt", "rst", "adoc", "mdx"];
+
+// Common misspellings and their corrections (lowercase).
+const MISSPELLINGS: &[(&str, &str)] = &[
+    ("accomodate", "accommodate"), ("acheive", "achieve"), ("accross", "across"), ("adress", "address"),
+    ("agressive", "aggressive"), ("alot", "a lot"), ("apparantly", "apparently"), ("arguement", "argument"),
+    ("basicly", "basically"), ("begining", "beginning"), ("beleive", "believe"), ("calender", "calendar"),
+    ("catagory", "category"), ("comming", "coming"), ("commited", "committed"), ("commiting", "committing"),
+    ("completly", "completely"), ("concensus", "consensus"), ("definately", "definitely"), ("dependant", "dependent"),
+    ("desicion", "decision"), ("diffrent", "different"), ("enviroment", "environment"), ("existance", "existence"),
+    ("explaination", "explanation"), ("familar", "familiar"), ("finaly", "finally"), ("foward", "forward"),
+    ("goverment", "government"), ("guage", "gauge"), ("happend", "happened"), ("immediatly", "immediately"),
+    ("independant", "independent"), ("infomation", "information"), ("intial", "initial"), ("lenght", "length"),
+    ("libary", "library"), ("maintainance", "maintenance"), ("neccessary", "necessary"), ("necesary", "necessary"),
+    ("noticable", "noticeable"), ("occured", "occurred"), ("occurence", "occurrence"), ("occuring", "occurring"),
+    ("paramter", "parameter"), ("persistant", "persistent"), ("posible", "possible"), ("prefered", "preferred"),
+    ("priviledge", "privilege"), ("publically", "publicly"), ("realy", "really"), ("recieve", "receive"),
+    ("recieved", "received"), ("recomend", "recommend"), ("refered", "referred"), ("relevent", "relevant"),
+    ("repositary", "repository"), ("seperate", "separate"), ("seperately", "separately"), ("sucess", "success"),
+    ("succesful", "successful"), ("supress", "suppress"), ("teh", "the"), ("tommorow", "tomorrow"),
+    ("truely", "truly"), ("untill", "until"), ("usefull", "useful"), ("wich", "which"), ("writting", "writing"),
+];
+
+// Words that weaken technical prose (the write-good/Vale "weasel" rule).
+const WEASEL_WORDS: [&str; 8] = ["very", "really", "basically", "obviously", "clearly", "simply", "actually", "extremely"];
+
+pub struct ProseIssue {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub rule: &'static str,
+    pub message: String,
+}
+
+// Words accepted for a store: every word listed in the files of
+// `[store.<name>] dictionaries` (paths relative to the store checkout).
+// When no dictionary is configured, only the built-in rules apply.
+pub struct Dictionary {
+    words: HashSet<String>,
+    files: Vec<String>,
+}
+
+pub fn load_dictionary(trunk_config: &TrunkConfig, store_name: &str, store_dir: &Path) -> Dictionary {
+    let files = trunk_config.get_array(&format!("store.{}", store_name), "dictionaries");
+    let mut words = HashSet::new();
+    for dictionary in &files {
+        let path = store_dir.join(dictionary);
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                for word in content.lines().map(str::trim).filter(|w| !w.is_empty() && !w.starts_with('#')) {
+                    words.insert(word.to_lowercase());
+                }
+            }
+            Err(e) => error!("⚠️ Warning: Could not read dictionary {}: {}", path.display(), e),
+        }
+    }
+    Dictionary { words, files }
+}
+
+// A word of a line: char offsets [start, end) and its text.
+struct Token {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+// Splits a line into words, ignoring inline code spans and URLs.
+fn tokenize(chars: &[char]) -> Vec<Token> {
+    let mut masked: Vec<char> = chars.to_vec();
+    let mut i = 0;
+    while i < masked.len() {
+        if masked[i] == '`' {
+            let end = masked[i + 1..].iter().position(|c| *c == '`').map(|p| i + 1 + p).unwrap_or(masked.len() - 1);
+            masked[i..=end].iter_mut().for_each(|c| *c = ' ');
+            i = end + 1;
+            continue;
+        }
+        let rest: String = masked[i..].iter().take(8).collect();
+        if rest.starts_with("http://") || rest.starts_with("https://") {
+            let end = masked[i..].iter().position(|c| c.is_whitespace() || *c == ')' || *c == '>').map(|p| i + p).unwrap_or(masked.len());
+            masked[i..end].iter_mut().for_each(|c| *c = ' ');
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+    for (index, c) in masked.iter().chain(std::iter::once(&' ')).enumerate() {
+        let in_word = c.is_alphabetic() || (*c == '\'' && start.is_some() && masked.get(index + 1).is_some_and(|n| n.is_alphabetic()));
+        match (in_word, start) {
+            (true, None) => start = Some(index),
+            (false, Some(s)) => {
+                tokens.push(Token { start: s, end: index, text: masked[s..index].iter().collect() });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+// The correction of a known misspelling, unless the store dictionary
+// accepts the word.
+fn correction(word: &str, dictionary: &HashSet<String>) -> Option<&'static str> {
+    let lower = word.to_lowercase();
+    if dictionary.contains(&lower) {
+        return None;
+    }
+    MISSPELLINGS.iter().find(|(wrong, _)| *wrong == lower).map(|(_, right)| *right)
+}
+
+// Whether token `index` repeats the word before it, with only whitespace between.
+fn is_repeated(chars: &[char], tokens: &[Token], index: usize) -> bool {
+    index > 0
+        && tokens[index - 1].text.to_lowercase() == tokens[index].text.to_lowercase()
+        && chars[tokens[index - 1].end..tokens[index].start].iter().all(|c| c.is_whitespace())
+}
+
+// Applies the capitalization of `original` to `replacement`.
+fn match_case(original: &str, replacement: &str) -> String {
+    if original.len() > 1 && original.chars().all(|c| !c.is_lowercase()) {
+        replacement.to_uppercase()
+    } else if original.chars().next().is_some_and(char::is_uppercase) {
+        let mut chars = replacement.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+    } else {
+        replacement.to_string()
+    }
+}
+
+fn is_checkable_word(word: &str) -> bool {
+    word.chars().count() > 1 && word.chars().any(char::is_lowercase)
+}
+
+// Lints one document. Lines inside fenced code blocks are skipped.
+fn lint_text(path: &str, text: &str, dictionary: &HashSet<String>) -> Vec<ProseIssue> {
+    let mut issues = Vec::new();
+    let mut in_fence = false;
+    for (line_index, line) in text.lines().enumerate() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let chars: Vec<char> = line.chars().collect();
+        let tokens = tokenize(&chars);
+        let mut issue = |column: usize, rule: &'static str, message: String| {
+            issues.push(ProseIssue { path: path.to_string(), line: line_index + 1, column: column + 1, rule, message });
+        };
+        for (index, token) in tokens.iter().enumerate() {
+            let lower = token.text.to_lowercase();
+            if let Some(right) = correction(&token.text, dictionary) {
+                issue(token.start, "spelling", format!("'{}' is a misspelling of '{}'", token.text, match_case(&token.text, right)));
+            } else if !dictionary.is_empty() && is_checkable_word(&token.text) && !dictionary.contains(&lower) && !dictionary.contains(lower.trim_end_matches("'s")) {
+                issue(token.start, "unknown-word", format!("'{}' is not in the store dictionary", token.text));
+            }
+            if is_repeated(&chars, &tokens, index) {
+                issue(token.start, "repeated-word", format!("'{}' is repeated", token.text));
+            }
+            if WEASEL_WORDS.contains(&lower.as_str()) {
+                issue(token.start, "weasel", format!("'{}' weakens the sentence; consider removing it", token.text));
+            }
+        }
+    }
+    issues
+}
+
+// Corrects known misspellings the dictionary does not accept, and removes
+// repeated words when `fix_repeated` is set.
+fn fix_text(text: &str, dictionary: &HashSet<String>, fix_repeated: bool) -> String {
+    let mut out = String::new();
+    let mut in_fence = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if in_fence || trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            out.push_str(line);
+            continue;
+        }
+        let chars: Vec<char> = line.chars().collect();
+        let tokens = tokenize(&chars);
+        let mut cursor = 0;
+        for (index, token) in tokens.iter().enumerate() {
+            if fix_repeated && is_repeated(&chars, &tokens, index) {
+                out.extend(&chars[cursor..tokens[index - 1].end]);
+                cursor = token.end;
+                continue;
+            }
+            if let Some(right) = correction(&token.text, dictionary) {
+                out.extend(&chars[cursor..token.start]);
+                out.push_str(&match_case(&token.text, right));
+                cursor = token.end;
+            }
+        }
+        out.extend(&chars[cursor..]);
+    }
+    out
+}
+
+fn is_prose_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PROSE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+fn collect_prose_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        if path.is_dir() {
+            collect_prose_files(&path, files);
+        } else if is_prose_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+// Prose files with uncommitted changes in a store checkout, relative to it.
+// Deleted files are left out; a renamed file counts under its new name.
+pub fn changed_prose_files(store_dir: &Path, verbose: bool) -> Vec<String> {
+    let output = match run_git_command(
+        Command::new("git").arg("status").arg("--porcelain").arg("-z").arg("--untracked-files=all").current_dir(store_dir),
+        verbose,
+    ) {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+    // "XY <path>\0", followed by "<original path>\0" for renames and copies
+    let mut files = Vec::new();
+    let mut entries = output.stdout.split(|b| *b == 0).filter(|entry| !entry.is_empty());
+    while let Some(entry) = entries.next() {
+        if entry.len() <= 3 {
+            continue;
+        }
+        let (code, path) = entry.split_at(3);
+        if code.contains(&b'R') || code.contains(&b'C') {
+            entries.next();
+        }
+        let path = String::from_utf8_lossy(path).to_string();
+        if !code.contains(&b'D') && is_prose_file(Path::new(&path)) {
+            files.push(path);
+        }
+    }
+    files
+}
+
+// Lints the given files (relative to the store checkout), fixing them first
+// when `fix` is set (repeated words too with `fix_repeated`). Dictionary
+// files themselves are skipped. Returns the issues that remain.
+pub fn lint_files(store_dir: &Path, files: &[String], dictionary: &Dictionary, fix: bool, fix_repeated: bool) -> Vec<ProseIssue> {
+    let mut issues = Vec::new();
+    for file in files.iter().filter(|file| !dictionary.files.contains(file)) {
+        let path = store_dir.join(file);
+        let Ok(mut text) = fs::read_to_string(&path) else {
+            debug!("⚠️ Skipping unreadable file {}", path.display());
+            continue;
+        };
+        if fix {
+            let fixed = fix_text(&text, &dictionary.words, fix_repeated);
+            if fixed != text {
+                match fs::write(&path, &fixed) {
+                    Ok(()) => {
+                        info!("🔧 Fixed {}", file);
+                        text = fixed;
+                    }
+                    Err(e) => error!("❌ Failed to write {}: {}", path.display(), e),
+                }
+            }
+        }
+        issues.extend(lint_text(file, &text, &dictionary.words));
+    }
+    issues
+}
+
+pub fn print_issues(issues: &[ProseIssue]) {
+    for issue in issues {
+        println!("{}:{}:{}: {}: {}", issue.path, issue.line, issue.column, issue.rule, issue.message);
+    }
+}
+
+pub fn run(args: &LintProseArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Locate the store checkout and its dictionaries
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    debug!("➡️ Step 2: Checking for {} directory", store_dir_relative_path);
+    if !trunk_store_dir.exists() {
+        return Err(TrunkError::NotFound(format!("{} directory not found for store '{}'. Run `git trunk checkout --store {}` first.", store_dir_relative_path, store_name, store_name)));
+    }
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let dictionary = load_dictionary(&trunk_config, store_name, &trunk_store_dir);
+    info!("✓ Step 2: {} found, {} dictionary word(s) loaded", store_dir_relative_path, dictionary.words.len());
+
+    // Step 3: Collect the files to lint
+    debug!("➡️ Step 3: Collecting prose files");
+    let files: Vec<String> = if args.changed {
+        changed_prose_files(&trunk_store_dir, verbose)
+    } else {
+        let mut paths = Vec::new();
+        collect_prose_files(&trunk_store_dir, &mut paths);
+        paths.sort();
+        paths.iter()
+            .filter_map(|path| path.strip_prefix(&trunk_store_dir).ok())
+            .map(|path| path.to_string_lossy().to_string())
+            .collect()
+    };
+    if files.is_empty() {
+        info!("= Step 3: No prose files to lint in {}", store_dir_relative_path);
+        return Ok(());
+    }
+    info!("✓ Step 3: Linting {} file(s)", files.len());
+
+    // Step 4: Lint (and fix)
+    let issues = lint_files(&trunk_store_dir, &files, &dictionary, args.fix, args.fix_repeated);
+    if issues.is_empty() {
+        info!("✅ No prose issues found in store '{}'", store_name);
+        return Ok(());
+    }
+    print_issues(&issues);
+    Err(TrunkError::Failed(format!("Found {} prose issue(s) in store '{}'", issues.len(), store_name)))
+}
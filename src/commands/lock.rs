@@ -0,0 +1,59 @@
+use chrono::Local;
+use clap::Parser;
+use log::{debug, info};
+use crate::locks::{self, Lock};
+use crate::ops;
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(about = "Lock store files so teammates know not to edit them", after_help = "Examples:\n  git trunk lock diagrams/architecture.drawio\n  git trunk lock --store design mockups/home.fig mockups/cart.fig\n  git trunk lock design:mockups/home.fig slides:deck.key")]
+pub struct LockArgs {
+    #[arg(required = true, help = "Paths inside .trunk/<store> to lock, or `store:path`")]
+    paths: Vec<String>,
+}
+
+impl LockArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        self.paths.iter_mut().collect()
+    }
+}
+
+pub fn run(args: &LockArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+
+    // Step 2: Work out who is locking what
+    let me = locks::identity(&repo, verbose)?;
+    let paths = ops::store_paths(&repo, &args.paths, store_name, verbose).iter().map(|path| locks::normalize_path(store_name, path)).collect::<Result<Vec<_>, _>>()?;
+    debug!("✓ Step 2: Locking {} path(s) as {}", paths.len(), me.email);
+
+    // Step 3: Add the locks on the remote, all or none
+    debug!("🔒 Step 3: Updating {} on remote '{}'", locks::lock_ref(store_name), remote_name);
+    let mut added = Vec::new();
+    let message = format!("Lock {} in store '{}'", paths.join(", "), store_name);
+    let changed = locks::update(&repo, remote_name, store_name, &message, verbose, |current| {
+        added.clear();
+        let held: Vec<String> = current
+            .iter()
+            .filter(|lock| paths.contains(&lock.path) && lock.email != me.email)
+            .map(|lock| format!("{} is locked by {} since {}", lock.path, lock.owner(), lock.since.format("%Y-%m-%d %H:%M")))
+            .collect();
+        if !held.is_empty() {
+            return Err(TrunkError::Conflict(format!("{}. Ask them to run `git trunk unlock`, or break the lock with `git trunk unlock --force`.", held.join("; "))));
+        }
+        for path in &paths {
+            if !current.iter().any(|lock| &lock.path == path) {
+                current.push(Lock { path: path.clone(), email: me.email.clone(), name: me.name.clone(), since: Local::now() });
+                added.push(path.clone());
+            }
+        }
+        Ok(!added.is_empty())
+    })?;
+    if !changed {
+        info!("= Step 3: You already hold the lock on {}", paths.join(", "));
+        return Ok(());
+    }
+    info!("✓ Step 3: Locked {}", added.join(", "));
+    info!("✅ Locked {} path(s) in store '{}' on remote '{}'", added.len(), store_name, remote_name);
+    Ok(())
+}
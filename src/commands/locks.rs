@@ -0,0 +1,46 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::locks;
+use crate::ops;
+use crate::table::{Cell, Color, Table};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(about = "List the locks of a store", after_help = "Examples:\n  git trunk locks\n  git trunk locks --store design --local")]
+pub struct LocksArgs {
+    #[arg(long, help = "Show the locks as last fetched instead of asking the remote")]
+    local: bool,
+    #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+pub fn run(args: &LocksArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+
+    // Step 2: Fetch the current locks
+    if args.local {
+        debug!("= Step 2: --local, using the locks as last fetched");
+    } else {
+        debug!("📥 Step 2: Fetching {} from remote '{}'", locks::lock_ref(store_name), remote_name);
+        match locks::refresh(&repo, remote_name, store_name, verbose) {
+            Ok(()) => info!("✓ Step 2: Fetched the locks of store '{}' from remote '{}'", store_name, remote_name),
+            Err(e) => error!("⚠️ Warning: {}; showing the locks as last fetched", e),
+        }
+    }
+
+    // Step 3: List them
+    let current = locks::read(&repo, store_name, verbose);
+    if current.is_empty() {
+        info!("= No locks in store '{}'", store_name);
+        return Ok(());
+    }
+    let me = locks::identity(&repo, verbose).ok();
+    let mut table = Table::new(&["Path", "Owner", "Since"]);
+    for lock in &current {
+        let owner = if me.as_ref().is_some_and(|me| me.email == lock.email) { Cell::colored(format!("{} (you)", lock.owner()), Color::Green) } else { Cell::from(lock.owner()) };
+        table.add_row(vec![lock.path.as_str().into(), owner, lock.since.format("%Y-%m-%d %H:%M").to_string().into()]);
+    }
+    table.print(args.no_table);
+    Ok(())
+}
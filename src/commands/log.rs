@@ -0,0 +1,82 @@
+use std::process::Stdio;
+use clap::Parser;
+use log::debug;
+use crate::table::use_color;
+use crate::template::Template;
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Show the commit history of refs/trunk/<store>", after_help = "Examples:\n  git trunk log\n  git trunk log --oneline -n 10 --since \"2 weeks ago\"\n  git trunk log --format '{short_hash} {date} {subject}'")]
+pub struct LogArgs {
+    #[arg(long, help = "Show each commit on a single line")]
+    oneline: bool,
+    #[arg(short = 'n', long = "max-count", value_name = "N", help = "Show at most N commits")]
+    max_count: Option<usize>,
+    #[arg(long, value_name = "DATE", help = "Show commits newer than DATE (anything `git log --since` accepts, e.g. \"2 weeks ago\")")]
+    since: Option<String>,
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "oneline", help = "Print one line per commit from a template; placeholders: {store}, {hash}, {short_hash}, {author}, {email}, {date}, {subject}")]
+    format: Option<String>,
+}
+
+const FORMAT_FIELDS: &[&str] = &["store", "hash", "short_hash", "author", "email", "date", "subject"];
+
+// git log placeholders for FORMAT_FIELDS after {store}, separated by the
+// unit separator; each commit ends with a NUL (-z)
+const GIT_FORMAT: &str = "%H%x1f%h%x1f%an%x1f%ae%x1f%aI%x1f%s";
+
+pub fn run(args: &LogArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let template = args.format.as_deref().map(|format| Template::parse(format, FORMAT_FIELDS)).transpose()?;
+
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Check the store ref
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    debug!("➡️ Step 2: Checking {}", trunk_ref_name);
+    if !repo.ref_exists(&trunk_ref_name) {
+        return Err(TrunkError::NotFound(format!("{} for store '{}' does not exist in the local repository. Commit changes first using `git trunk commit --store {}`.", trunk_ref_name, store_name, store_name)));
+    }
+    debug!("✓ Step 2: {} exists", trunk_ref_name);
+
+    // Step 3: Show the history
+    let mut command = repo.git();
+    command.arg("log");
+    if template.is_some() {
+        command.arg("-z").arg(format!("--format={}", GIT_FORMAT));
+    } else {
+        command.arg(if use_color() { "--color=always" } else { "--color=never" });
+    }
+    if args.oneline {
+        command.arg("--oneline");
+    }
+    if let Some(count) = args.max_count {
+        command.arg(format!("--max-count={}", count));
+    }
+    if let Some(since) = &args.since {
+        command.arg(format!("--since={}", since));
+    }
+    command.arg(&trunk_ref_name).arg("--");
+    debug!("➡️ Step 3: Running {:?}", command);
+    if let Some(template) = template {
+        let output = run_git_command(&mut command, verbose).map_err(|e| TrunkError::Git(format!("git log failed for {}: {}", trunk_ref_name, e)))?;
+        if !output.status.success() {
+            return Err(TrunkError::Git(format!("git log failed for {}: {}", trunk_ref_name, String::from_utf8_lossy(&output.stderr).trim())));
+        }
+        for record in String::from_utf8_lossy(&output.stdout).split('\0').filter(|record| !record.is_empty()) {
+            let mut values = vec![("store", store_name.to_string())];
+            values.extend(FORMAT_FIELDS[1..].iter().copied().zip(record.split('\x1f').map(str::to_string)));
+            println!("{}", template.render(&values));
+        }
+        return Ok(());
+    }
+    let status = command.stdin(Stdio::null()).status();
+    if !status.is_ok_and(|status| status.success()) {
+        return Err(TrunkError::Git(format!("git log failed for {}", trunk_ref_name)));
+    }
+    Ok(())
+}
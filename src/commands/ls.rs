@@ -0,0 +1,97 @@
+use clap::Parser;
+use log::debug;
+use crate::locks::normalize_path;
+use crate::ops;
+use crate::quota::format_size;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "List the files in refs/trunk/<store> without checking it out", after_help = "Examples:\n  git trunk ls\n  git trunk ls policies --long\n  git trunk ls handbook:policies\n  git trunk ls --ref 1a2b3c4 policies")]
+pub struct LsArgs {
+    #[arg(help = "Directory or file to list: relative to .trunk/<store>, or `store:path` [default: the whole store]")]
+    path: Option<String>,
+    #[arg(long = "ref", value_name = "COMMIT", help = "List this commit of the store, e.g. one shown by `git trunk log`, instead of refs/trunk/<store>")]
+    rev: Option<String>,
+    #[arg(long, short = 'l', help = "Show the size of each file")]
+    long: bool,
+}
+
+impl LsArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        self.path.iter_mut().collect()
+    }
+}
+
+// One file of the tree: its size is None for submodules.
+struct Entry {
+    size: Option<u64>,
+    path: String,
+}
+
+fn list_tree(repo: &GitRepo, commit: &str, path: Option<&str>, verbose: bool) -> Result<Vec<Entry>, TrunkError> {
+    let mut command = repo.git();
+    command.arg("ls-tree").arg("-r").arg("-l").arg("-z").arg(commit);
+    if let Some(path) = path {
+        command.arg("--").arg(path);
+    }
+    let output = run_git_command(&mut command, verbose).map_err(|e| TrunkError::Git(format!("git ls-tree failed for {}: {}", commit, e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git ls-tree failed for {}: {}", commit, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    // "<mode> <type> <object> <size>\t<path>"
+    Ok(output
+        .stdout
+        .split(|b| *b == 0)
+        .filter_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (header, path) = entry.split_once('\t')?;
+            Some(Entry { size: header.split_whitespace().nth(3)?.parse().ok(), path: path.to_string() })
+        })
+        .collect())
+}
+
+pub fn run(args: &LsArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    debug!("✓ Step 1: Repository root found at {}", repo.root().display());
+    let path = match args.path.as_ref() {
+        Some(path) => ops::store_paths(&repo, std::slice::from_ref(path), store_name, verbose).pop().unwrap_or_else(|| path.clone()),
+        None => String::new(),
+    };
+    // An empty path or `.` lists the whole store
+    let path = match path.trim_end_matches('/') {
+        "" | "." => None,
+        path => Some(normalize_path(store_name, path)?),
+    };
+
+    // Step 2: Resolve the commit to list
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let rev = args.rev.as_deref().unwrap_or(&trunk_ref_name);
+    debug!("➡️ Step 2: Resolving {}", rev);
+    let Some(commit) = repo.resolve_commit(rev) else {
+        return Err(TrunkError::NotFound(match &args.rev {
+            Some(rev) => format!("'{}' is not a commit in this repository", rev),
+            None => format!("{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.", trunk_ref_name, store_name, store_name),
+        }));
+    };
+    debug!("✓ Step 2: Listing {} ({})", rev, short_hash(repo.root(), &commit, verbose));
+
+    // Step 3: List the files
+    let entries = list_tree(&repo, &commit, path.as_deref(), verbose)?;
+    if let (true, Some(path)) = (entries.is_empty(), &path) {
+        return Err(TrunkError::NotFound(format!("'{}' is not in {} of store '{}'", path, rev, store_name)));
+    }
+    // File names are printed as they are, even under --plain, so scripts can read them
+    for entry in &entries {
+        if args.long {
+            let size = entry.size.map(format_size).unwrap_or_else(|| "-".to_string());
+            println!("{:>9}  {}", size, entry.path);
+        } else {
+            println!("{}", entry.path);
+        }
+    }
+    Ok(())
+}
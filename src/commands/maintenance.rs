@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::Path;
+use clap::{Parser, Subcommand};
+use log::{debug, info};
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Register refs/trunk/* with git's background maintenance", after_help = "Examples:\n  git trunk maintenance register\n  git trunk maintenance register --remote upstream\n  git trunk maintenance unregister")]
+pub struct MaintenanceArgs {
+    #[command(subcommand)]
+    action: MaintenanceAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum MaintenanceAction {
+    /// Lets `git maintenance` prefetch refs/trunk/* from the remote and pack refs in the background
+    Register,
+    /// Stops prefetching refs/trunk/* from the remote
+    Unregister,
+}
+
+// Fetch refspec that makes prefetch (and plain `git fetch`) mirror the
+// remote's stores into refs/trunk-remotes/<remote>/, never touching the local
+// refs/trunk/* which may hold unpushed commits. Prefetch rewrites the
+// destination to refs/prefetch/trunk-remotes/<remote>/.
+pub fn prefetch_refspec(remote_name: &str) -> String {
+    format!("+refs/trunk/*:refs/trunk-remotes/{}/*", remote_name)
+}
+
+// Remotes whose fetch refspecs include the trunk prefetch refspec.
+pub fn prefetch_remotes(repo: &GitRepo, verbose: bool) -> Vec<String> {
+    let Ok(output) = run_git_command(repo.git().arg("config").arg("--get-regexp").arg(r"^remote\..*\.fetch$"), verbose) else {
+        return Vec::new();
+    };
+    let mut remotes: Vec<String> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((key, value)) = line.split_once(' ') else { continue };
+        let Some(remote_name) = key.strip_prefix("remote.").and_then(|key| key.strip_suffix(".fetch")) else { continue };
+        if value == prefetch_refspec(remote_name) && !remotes.iter().any(|r| r == remote_name) {
+            remotes.push(remote_name.to_string());
+        }
+    }
+    remotes
+}
+
+// True when the repository is listed in git's `maintenance.repo`, so the
+// scheduled `git maintenance run` visits it.
+pub fn is_registered(repo: &GitRepo, verbose: bool) -> bool {
+    let Ok(output) = run_git_command(repo.git().arg("config").arg("--get-all").arg("maintenance.repo"), verbose) else {
+        return false;
+    };
+    let root = fs::canonicalize(repo.root()).unwrap_or_else(|_| repo.root().to_path_buf());
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| fs::canonicalize(Path::new(line.trim())).is_ok_and(|path| path == root))
+}
+
+// Runs `git maintenance register`, which lists the repository in the global
+// config and enables the incremental strategy (hourly prefetch) unless a
+// strategy is already set.
+pub fn register_repository(repo: &GitRepo, verbose: bool) -> Result<(), TrunkError> {
+    let output = run_git_command(repo.git().arg("maintenance").arg("register"), verbose).map_err(|e| TrunkError::Git(format!("Failed to run git maintenance register: {}", e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git maintenance register failed: {}", String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    Ok(())
+}
+
+fn config_value(repo: &GitRepo, key: &str, verbose: bool) -> Option<String> {
+    let output = run_git_command(repo.git().arg("config").arg("--get").arg(key), verbose).ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+fn set_config(repo: &GitRepo, args: &[&str], verbose: bool) -> Result<(), TrunkError> {
+    let output = run_git_command(repo.git().arg("config").args(args), verbose).map_err(|e| TrunkError::Git(format!("Failed to run git config: {}", e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git config {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    Ok(())
+}
+
+pub fn run(args: &MaintenanceArgs, remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    info!("✓ Step 1: Repository root found at {}", repo.root().display());
+
+    // Step 2: Prefetch only visits named remotes
+    debug!("➡️ Step 2: Checking remote '{}'", remote_name);
+    if config_value(&repo, &format!("remote.{}.url", remote_name), verbose).is_none() {
+        return Err(TrunkError::NotFound(format!("Remote '{}' is not configured; background prefetch only fetches from named remotes (see `git remote add`)", remote_name)));
+    }
+    info!("✓ Step 2: Remote '{}' is configured", remote_name);
+
+    let refspec = prefetch_refspec(remote_name);
+    let registered = prefetch_remotes(&repo, verbose).iter().any(|r| r == remote_name);
+    match args.action {
+        MaintenanceAction::Register => {
+            // Step 3: Add the trunk refspec to the remote
+            if registered {
+                info!("= Step 3: remote.{}.fetch already includes {}", remote_name, refspec);
+            } else {
+                debug!("📝 Step 3: Adding {} to remote.{}.fetch", refspec, remote_name);
+                set_config(&repo, &["--add", &format!("remote.{}.fetch", remote_name), &refspec], verbose)?;
+                info!("✓ Step 3: Added {} to remote.{}.fetch", refspec, remote_name);
+            }
+
+            // Step 4: Register the repository with git maintenance
+            if is_registered(&repo, verbose) {
+                info!("= Step 4: Repository already registered with git maintenance");
+            } else {
+                debug!("📝 Step 4: Running git maintenance register");
+                register_repository(&repo, verbose)?;
+                info!("✓ Step 4: Repository registered with git maintenance");
+            }
+
+            // Step 5: Keep refs packed; the incremental strategy of older git
+            // versions does not schedule pack-refs
+            if config_value(&repo, "maintenance.pack-refs.enabled", verbose).is_some() {
+                info!("= Step 5: maintenance.pack-refs already configured");
+            } else {
+                set_config(&repo, &["maintenance.pack-refs.enabled", "true"], verbose)?;
+                set_config(&repo, &["maintenance.pack-refs.schedule", "weekly"], verbose)?;
+                info!("✓ Step 5: Scheduled weekly pack-refs");
+            }
+            info!("✅ Background maintenance now prefetches refs/trunk/* from '{}'", remote_name);
+        }
+        MaintenanceAction::Unregister => {
+            // Step 3: Remove the trunk refspec from the remote
+            if !registered {
+                info!("= Step 3: remote.{}.fetch does not include {}, nothing to do", remote_name, refspec);
+                return Ok(());
+            }
+            debug!("📝 Step 3: Removing {} from remote.{}.fetch", refspec, remote_name);
+            set_config(&repo, &["--fixed-value", "--unset-all", &format!("remote.{}.fetch", remote_name), &refspec], verbose)?;
+            info!("✓ Step 3: Removed {} from remote.{}.fetch", refspec, remote_name);
+
+            // Step 4: Drop the mirrored refs
+            debug!("➡️ Step 4: Deleting refs/trunk-remotes/{}/*", remote_name);
+            let mirrored = repo.ref_names(&format!("refs/trunk-remotes/{}/", remote_name)).unwrap_or_default();
+            for name in &mirrored {
+                repo.delete_ref(&format!("refs/trunk-remotes/{}/{}", remote_name, name)).map_err(|e| TrunkError::Git(format!("Failed to delete refs/trunk-remotes/{}/{}: {}", remote_name, name, e)))?;
+            }
+            info!("✓ Step 4: Deleted {} mirrored ref(s)", mirrored.len());
+            info!("✅ Background maintenance no longer prefetches refs/trunk/* from '{}'; run `git maintenance unregister` to stop it entirely", remote_name);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use clap::Parser;
+use log::{debug, error, info};
+use crate::ops;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+// Written last into every exported directory: it names what the directory
+// holds, so a repeated run can skip the export and --clean knows the
+// directory is git-trunk's to remove.
+const MARKER_FILE: &str = ".git-trunk-materialized";
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Export the files of refs/trunk/<store> into an untracked directory, e.g. for a build",
+    after_help = "Runs again with the same commit do nothing, so build scripts can call it every time.\n\nExamples:\n  git trunk --store docs materialize-temp --dest build/docs\n  git trunk --store docs materialize-temp --dest build/docs --rev v1.2-docs\n  git trunk materialize-temp --clean --dest build/docs"
+)]
+pub struct MaterializeTempArgs {
+    #[arg(long, value_name = "DIR", help = "Directory to export into, relative to the current directory; created if missing")]
+    dest: PathBuf,
+    #[arg(long, value_name = "COMMIT", help = "Export this commit of the store instead of refs/trunk/<store>")]
+    rev: Option<String>,
+    #[arg(long, conflicts_with = "rev", help = "Remove a directory an earlier run exported, instead of exporting")]
+    clean: bool,
+}
+
+// The store and commit a marker names, or None when `dir` has no marker.
+fn read_marker(dir: &Path) -> Option<(String, String)> {
+    let text = fs::read_to_string(dir.join(MARKER_FILE)).ok()?;
+    let field = |key: &str| text.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix(' ').map(str::to_string));
+    Some((field("store")?, field("commit")?))
+}
+
+// `path` made absolute against `base`, with `.` and `..` resolved by name.
+fn absolute(base: &Path, path: &Path) -> PathBuf {
+    let mut absolute = PathBuf::new();
+    for component in base.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                absolute.pop();
+            }
+            component => absolute.push(component),
+        }
+    }
+    absolute
+}
+
+fn is_empty_dir(dir: &Path) -> bool {
+    fs::read_dir(dir).map(|mut entries| entries.next().is_none()).unwrap_or(false)
+}
+
+// Refuses a destination that would clobber files git-trunk did not write.
+fn check_dest(repo: &GitRepo, dest: &Path, relative: Option<&str>, verbose: bool) -> Result<(), TrunkError> {
+    if let Some(relative) = relative {
+        let tracked = run_git_command(repo.git().arg("ls-files").arg("--").arg(relative), verbose)
+            .map_err(|e| TrunkError::Git(format!("git ls-files failed: {}", e)))?;
+        if relative.is_empty() || relative == ".git" || relative.starts_with(".git/") || !tracked.stdout.is_empty() {
+            return Err(TrunkError::Conflict(format!("{} holds files of the repository; choose a directory of its own for --dest", dest.display())));
+        }
+    }
+    if dest.exists() && read_marker(dest).is_none() && !is_empty_dir(dest) {
+        return Err(TrunkError::Conflict(format!(
+            "{} already exists and was not exported by `git trunk materialize-temp`; remove it or choose another --dest",
+            dest.display()
+        )));
+    }
+    Ok(())
+}
+
+// Adds `/<relative>/` to .git/info/exclude unless git already ignores the
+// directory, so the export never shows up in `git status`.
+fn exclude(repo: &GitRepo, relative: &str, verbose: bool) -> Result<bool, String> {
+    let ignored = run_git_command(repo.git().arg("check-ignore").arg("-q").arg("--no-index").arg(format!("{}/", relative)), verbose).map_err(|e| e.to_string())?;
+    if ignored.status.success() {
+        return Ok(false);
+    }
+    let info_dir = repo.common_dir().map_err(|e| e.to_string())?.join("info");
+    fs::create_dir_all(&info_dir).map_err(|e| e.to_string())?;
+    let exclude_path = info_dir.join("exclude");
+    let mut content = fs::read_to_string(&exclude_path).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&format!("/{}/\n", relative));
+    fs::write(&exclude_path, content).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+pub fn run(args: &MaterializeTempArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let current_dir = std::env::current_dir().map_err(|e| TrunkError::Io(format!("Failed to read the current directory: {}", e)))?;
+    let dest = absolute(&current_dir, &args.dest);
+    // The destination relative to the repository root, when it is inside it
+    let relative = dest.strip_prefix(repo.root()).ok().map(|path| path.to_string_lossy().replace('\\', "/"));
+    let display = args.dest.display().to_string();
+
+    if args.clean {
+        // Step 2: Remove the exported directory
+        debug!("➡️ Step 2: Removing {}", display);
+        if !dest.exists() {
+            info!("✅ {} does not exist; nothing to clean", display);
+            return Ok(());
+        }
+        if read_marker(&dest).is_none() {
+            return Err(TrunkError::Conflict(format!("{} was not exported by `git trunk materialize-temp`; leaving it alone", display)));
+        }
+        fs::remove_dir_all(&dest).map_err(|e| TrunkError::Io(format!("Failed to remove {}: {}", display, e)))?;
+        info!("✅ Removed {}", display);
+        return Ok(());
+    }
+
+    // Step 2: Resolve the commit to export
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let rev = args.rev.as_deref().unwrap_or(&trunk_ref_name);
+    debug!("➡️ Step 2: Resolving {}", rev);
+    let Some(commit) = repo.resolve_commit(rev) else {
+        return Err(TrunkError::NotFound(match &args.rev {
+            Some(rev) => format!("'{}' is not a commit in this repository", rev),
+            None => format!("{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.", trunk_ref_name, store_name, store_name),
+        }));
+    };
+    let short = short_hash(repo.root(), &commit, verbose);
+    info!("✓ Step 2: Exporting {} ({})", rev, short);
+
+    // Step 3: Check the destination; an export of the same commit is kept
+    debug!("➡️ Step 3: Checking {}", display);
+    check_dest(&repo, &dest, relative.as_deref(), verbose)?;
+    if read_marker(&dest) == Some((store_name.to_string(), commit.clone())) {
+        info!("✅ {} already holds store '{}' at {}", display, store_name, short);
+        return Ok(());
+    }
+    info!("✓ Step 3: {} is free to use", display);
+
+    // Step 4: Export into a directory next to the destination, then swap it in,
+    // so the destination is never half-written
+    debug!("➡️ Step 4: Exporting store '{}' into {}", store_name, display);
+    let parent = dest.parent().ok_or_else(|| TrunkError::Usage(format!("{} cannot be exported into", display)))?;
+    fs::create_dir_all(parent).map_err(|e| TrunkError::Io(format!("Failed to create {}: {}", parent.display(), e)))?;
+    let file_name = dest.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let staging = parent.join(format!(".{}.git-trunk-{}", file_name, std::process::id()));
+    let export = || -> Result<(), TrunkError> {
+        repo.export_tree(&commit, &staging).map_err(|e| TrunkError::Git(format!("Failed to export {}: {}", rev, e)))?;
+        let marker = format!(
+            "# Exported by `git trunk materialize-temp`; `git trunk materialize-temp --clean --dest {}` removes this directory\nstore {}\ncommit {}\n",
+            display, store_name, commit
+        );
+        fs::write(staging.join(MARKER_FILE), marker).map_err(|e| TrunkError::Io(format!("Failed to write {}: {}", MARKER_FILE, e)))?;
+        if dest.exists() {
+            fs::remove_dir_all(&dest).map_err(|e| TrunkError::Io(format!("Failed to remove the previous export in {}: {}", display, e)))?;
+        }
+        fs::rename(&staging, &dest).map_err(|e| TrunkError::Io(format!("Failed to move the export into {}: {}", display, e)))
+    };
+    if let Err(e) = export() {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    info!("✓ Step 4: Exported store '{}' into {}", store_name, display);
+
+    // Step 5: Keep the export out of `git status`
+    if let Some(relative) = &relative {
+        match exclude(&repo, relative, verbose) {
+            Ok(true) => info!("✓ Step 5: Added /{}/ to .git/info/exclude", relative),
+            Ok(false) => info!("= Step 5: {} is already ignored", display),
+            Err(e) => error!("⚠️ Warning: Could not add /{}/ to .git/info/exclude, so git status may list it: {}", relative, e),
+        }
+    }
+    info!("✅ Exported store '{}' at {} into {}", store_name, short, display);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config;
+use crate::ops;
+use crate::quota;
+use crate::utils::{ensure_trunk_in_gitignore, run_git_command, short_hash};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Convert a branch holding documents, such as an orphan docs or gh-pages branch, into a store",
+    after_help = "Every commit of the branch is carried over with its files, author, date and message, on top of the empty first commit every store starts with; the commits therefore get new hashes. A branch that only exists on the remote is taken from <remote>/<branch>. The branch is kept unless --delete-branch is given.\n\nExamples:\n  git trunk migrate-branch gh-pages --store site\n  git trunk migrate-branch docs --store docs --delete-branch"
+)]
+pub struct MigrateBranchArgs {
+    #[arg(value_name = "BRANCH", help = "Branch to convert, local or on the remote")]
+    branch: String,
+    #[arg(long, help = "Delete the local branch once the store is created")]
+    delete_branch: bool,
+    #[arg(long, help = "Only create refs/trunk/<store>, without .trunk/<store>")]
+    no_checkout: bool,
+}
+
+impl MigrateBranchArgs {
+    pub fn checks_out(&self) -> bool {
+        !self.no_checkout
+    }
+}
+
+pub fn run(args: &MigrateBranchArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let branch = args.branch.trim_start_matches("refs/heads/");
+    let local_ref = format!("refs/heads/{}", branch);
+    let remote_ref = format!("refs/remotes/{}/{}", remote_name, branch);
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    // Step 2: Find the branch and check that the store is new
+    debug!("➡️ Step 2: Looking up branch '{}'", branch);
+    let (source_ref, tip) = match (repo.resolve_commit(&local_ref), repo.resolve_commit(&remote_ref)) {
+        (Some(tip), _) => (local_ref.clone(), tip),
+        (None, Some(tip)) => (remote_ref.clone(), tip),
+        (None, None) => {
+            return Err(TrunkError::NotFound(format!(
+                "Branch '{}' exists neither locally nor as {}; run `git fetch {} {}` first if it is only on the remote",
+                branch, remote_ref, remote_name, branch
+            )));
+        }
+    };
+    if repo.ref_exists(&trunk_ref_name) || config::trunk_base_dir(repo_root, verbose).join(store_name).exists() {
+        return Err(TrunkError::Conflict(format!("Store '{}' already exists here ({} or .trunk/{}); migrate into another store with --store", store_name, trunk_ref_name, store_name)));
+    }
+    let head = run_git_command(repo.git().arg("symbolic-ref").arg("-q").arg("HEAD"), verbose)
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    if args.delete_branch && head == local_ref {
+        return Err(TrunkError::Conflict(format!("Branch '{}' is checked out, so --delete-branch cannot delete it; switch to another branch first", branch)));
+    }
+    info!("✓ Step 2: Converting {} at {} into store '{}'", source_ref, short_hash(repo_root, &tip, verbose), store_name);
+
+    // Step 3: Write the store's first commit and carry the branch over onto it
+    debug!("➡️ Step 3: Rewriting the history of {}", source_ref);
+    let git_line = |command: &mut std::process::Command, what: &str| -> Result<String, TrunkError> {
+        match run_git_command(command, verbose) {
+            Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            _ => Err(TrunkError::Git(format!("Failed to write {} (run with --verbose for git's message)", what))),
+        }
+    };
+    let empty_tree = git_line(repo.git().arg("hash-object").arg("-w").arg("-t").arg("tree").arg("--stdin"), "the empty tree")?;
+    let message = format!("Initial commit for store '{}'\n\nMigrated from branch {}", store_name, branch);
+    let base = git_line(repo.git().arg("commit-tree").arg(&empty_tree).arg("-m").arg(&message), "the initial commit")?;
+    let (new_tip, count) = repo
+        .graft_history(&tip, &base)
+        .map_err(|e| TrunkError::Git(format!("Failed to rewrite the history of {}: {}", source_ref, e)))?;
+    info!("✓ Step 3: Carried over {} commit(s) from {}", count, source_ref);
+
+    // Step 4: Point refs/trunk/<store> at it
+    debug!("➡️ Step 4: Creating {}", trunk_ref_name);
+    quota::enforce(&repo, store_name, quota::reachable_size(repo_root, &new_tip, verbose).unwrap_or(0), "Step 4", verbose)?;
+    repo.update_ref(&trunk_ref_name, &new_tip).map_err(|e| TrunkError::Git(format!("Failed to create {}: {}", trunk_ref_name, e)))?;
+    info!("✓ Step 4: Created {} at {}", trunk_ref_name, short_hash(repo_root, &new_tip, verbose));
+
+    // Steps 5-15: Check it out into .trunk/<store>
+    if args.no_checkout {
+        info!("= Step 5: --no-checkout, not creating .trunk/{}", store_name);
+    } else {
+        debug!("➡️ Step 5: Preparing .trunk for store '{}'", store_name);
+        ensure_trunk_in_gitignore(repo_root, "Step 5").map_err(|e| TrunkError::Io(format!("Failed to update .gitignore: {}", e)))?;
+        ops::ensure_trunk_dir(repo_root, "Step 5", verbose)?;
+        ops::materialize_store(&repo, store_name, verbose)?;
+    }
+
+    // Step 16: Delete the branch, or say it is still there
+    if !args.delete_branch {
+        info!("= Step 16: Kept branch '{}'; delete it with `git branch -D {}` once the store is published", branch, branch);
+    } else if source_ref != local_ref {
+        info!("= Step 16: No local branch '{}' to delete; delete it on the remote with `git push {} --delete {}`", branch, remote_name, branch);
+    } else {
+        debug!("🗑️ Step 16: Deleting branch '{}'", branch);
+        match run_git_command(repo.git().arg("branch").arg("-D").arg(branch), verbose) {
+            Ok(output) if output.status.success() => info!("✓ Step 16: Deleted branch '{}' (was {}); `git branch {} {}` brings it back", branch, short_hash(repo_root, &tip, verbose), branch, tip),
+            _ => error!("⚠️ Warning: Store '{}' was created, but branch '{}' could not be deleted (checked out in another worktree?); delete it with `git branch -D {}`", store_name, branch, branch),
+        }
+    }
+
+    info!("✅ Migrated branch '{}' into store '{}'. Run `git trunk push --store {}` to publish it.", branch, store_name, store_name);
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use clap::{Parser, Subcommand};
+use log::{debug, error, info};
+use crate::config;
+use crate::ops;
+use crate::table::{Cell, Color, Table};
+use crate::utils::{confirm, run_git_change, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Compare refs/trunk/* on the remote with the mirrors in `[trunk] mirrors`, or bring the mirrors up to date",
+    after_help = "The remote (--remote, `[trunk] remote` or origin) is the canonical copy.\n\nExamples:\n  git trunk config set trunk.mirrors backup,github\n  git trunk mirrors status\n  git trunk mirrors reconcile\n  git trunk mirrors reconcile --force"
+)]
+pub struct MirrorsArgs {
+    #[command(subcommand)]
+    action: MirrorsAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum MirrorsAction {
+    /// Lists each store's commit on the remote and on every mirror, flagging drift
+    Status {
+        #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+        no_table: bool,
+    },
+    /// Pushes the remote's stores to the mirrors that do not have them
+    Reconcile {
+        #[arg(long, help = "Also overwrite stores a mirror has commits of its own for")]
+        force: bool,
+    },
+}
+
+impl MirrorsArgs {
+    pub fn writes_git_dir(&self) -> bool {
+        matches!(self.action, MirrorsAction::Reconcile { .. })
+    }
+}
+
+// How a mirror's refs/trunk/<store> compares with the remote's.
+#[derive(Debug, Clone, PartialEq)]
+enum Drift {
+    InSync,
+    // The mirror does not have the store
+    Missing,
+    // Only the mirror has the store; reconcile leaves it alone
+    Extra,
+    // The mirror is behind by this many commits, and a push fast-forwards it
+    Behind(usize),
+    // The mirror has commits the remote does not (ahead, behind)
+    Diverged(usize, usize),
+    // The commits are not all here, so their history cannot be compared
+    Differs,
+}
+
+impl Drift {
+    fn cell(&self) -> Cell {
+        match self {
+            Drift::InSync => Cell::flag(true, "in sync"),
+            Drift::Missing => Cell::flag(false, "missing"),
+            Drift::Extra => Cell::colored("only on the mirror", Color::Yellow),
+            Drift::Behind(behind) => Cell::flag(false, &format!("{} behind", behind)),
+            Drift::Diverged(ahead, behind) => Cell::flag(false, &format!("{} ahead, {} behind", ahead, behind)),
+            Drift::Differs => Cell::flag(false, "differs"),
+        }
+    }
+}
+
+// One store on one mirror.
+struct MirrorStore {
+    mirror: String,
+    store: String,
+    canonical: Option<String>,
+    hash: Option<String>,
+    drift: Drift,
+}
+
+fn compare(repo: &GitRepo, canonical: Option<&str>, hash: Option<&str>) -> Drift {
+    match (canonical, hash) {
+        (Some(canonical), Some(hash)) if canonical == hash => Drift::InSync,
+        (Some(_), None) => Drift::Missing,
+        (None, _) => Drift::Extra,
+        (Some(canonical), Some(hash)) => match repo.ahead_behind(hash, canonical) {
+            Some((0, behind)) => Drift::Behind(behind),
+            Some((ahead, behind)) => Drift::Diverged(ahead, behind),
+            None => Drift::Differs,
+        },
+    }
+}
+
+// Every store on every mirror next to the remote's, plus the mirrors that
+// could not be listed.
+fn survey(repo: &GitRepo, remote_name: &str, mirrors: &[String], verbose: bool) -> Result<(Vec<MirrorStore>, Vec<String>), TrunkError> {
+    let canonical = ops::remote_store_tips(repo, remote_name, verbose)?;
+    let mut stores = Vec::new();
+    let mut unreachable = Vec::new();
+    for mirror in mirrors {
+        let tips = match ops::remote_store_tips(repo, mirror, verbose) {
+            Ok(tips) => tips,
+            Err(e) => {
+                error!("⚠️ Warning: {}", e);
+                unreachable.push(mirror.clone());
+                continue;
+            }
+        };
+        let mut names: Vec<&String> = canonical.iter().chain(&tips).map(|(store, _)| store).collect();
+        names.sort();
+        names.dedup();
+        for store in names {
+            let find = |tips: &[(String, String)]| tips.iter().find(|(name, _)| name == store).map(|(_, hash)| hash.clone());
+            let (canonical_hash, hash) = (find(&canonical), find(&tips));
+            stores.push(MirrorStore {
+                mirror: mirror.clone(),
+                store: store.clone(),
+                drift: compare(repo, canonical_hash.as_deref(), hash.as_deref()),
+                canonical: canonical_hash,
+                hash,
+            });
+        }
+    }
+    Ok((stores, unreachable))
+}
+
+pub fn run(args: &MirrorsArgs, remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let trunk_config = config::load(repo.root(), verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let mirrors = trunk_config.mirrors(remote_name);
+    if mirrors.is_empty() {
+        return Err(TrunkError::Config(format!(
+            "No mirrors of remote '{}' configured. List them with `git trunk config set trunk.mirrors <remote>,<remote>`.",
+            remote_name
+        )));
+    }
+
+    // Step 2: List refs/trunk/* on the remote and every mirror
+    debug!("➡️ Step 2: Listing the stores on '{}' and {} mirror(s)", remote_name, mirrors.len());
+    let (stores, unreachable) = survey(&repo, remote_name, &mirrors, verbose)?;
+    let short = |hash: &Option<String>| hash.as_deref().map(|hash| short_hash(repo.root(), hash, verbose)).unwrap_or_else(|| "-".to_string());
+    info!("✓ Step 2: Compared {} store(s) across {} mirror(s)", stores.iter().map(|entry| &entry.store).collect::<std::collections::BTreeSet<_>>().len(), mirrors.len());
+
+    match &args.action {
+        MirrorsAction::Status { no_table } => {
+            let mut table = Table::new(&["Store", "Mirror", remote_name, "Mirror commit", "State"]);
+            for entry in &stores {
+                table.add_row(vec![entry.store.as_str().into(), entry.mirror.as_str().into(), short(&entry.canonical).into(), short(&entry.hash).into(), entry.drift.cell()]);
+            }
+            for mirror in &unreachable {
+                table.add_row(vec!["-".into(), mirror.as_str().into(), "-".into(), "-".into(), Cell::flag(false, "unreachable")]);
+            }
+            table.print(*no_table);
+            let drifted = stores.iter().filter(|entry| !matches!(entry.drift, Drift::InSync | Drift::Extra)).count();
+            if drifted > 0 || !unreachable.is_empty() {
+                return Err(TrunkError::Conflict(format!(
+                    "{} store(s) out of date on the mirrors{}; `git trunk mirrors reconcile` pushes the remote's state to them",
+                    drifted,
+                    if unreachable.is_empty() { String::new() } else { format!(", {} unreachable", unreachable.join(", ")) }
+                )));
+            }
+            info!("✅ All {} mirror(s) match remote '{}'", mirrors.len(), remote_name);
+            Ok(())
+        }
+        MirrorsAction::Reconcile { force } => {
+            let (to_push, held): (Vec<&MirrorStore>, Vec<&MirrorStore>) = stores
+                .iter()
+                .filter(|entry| !matches!(entry.drift, Drift::InSync | Drift::Extra))
+                .partition(|entry| *force || !matches!(entry.drift, Drift::Diverged(..)));
+            for entry in &held {
+                error!("⚠️ Warning: '{}' has commits of its own for store '{}' ({}); leaving it, --force overwrites it", entry.mirror, entry.store, short(&entry.hash));
+            }
+            if to_push.is_empty() {
+                info!("✅ Nothing to push to the mirrors");
+                return if held.is_empty() && unreachable.is_empty() {
+                    Ok(())
+                } else {
+                    Err(TrunkError::Conflict(format!("{} store(s) still differ on the mirrors", held.len() + unreachable.len())))
+                };
+            }
+            if !confirm(&format!("This will push {} store(s) from '{}' to the mirrors. Continue?", to_push.len(), remote_name))? {
+                info!("🚫 Reconcile aborted by user");
+                return Ok(());
+            }
+
+            // Step 3: Fetch the remote's stores, which the pushes send on
+            let mut fetched: Vec<&str> = to_push.iter().map(|entry| entry.store.as_str()).collect();
+            fetched.sort();
+            fetched.dedup();
+            debug!("➡️ Step 3: Fetching {} store(s) from '{}'", fetched.len(), remote_name);
+            for store in &fetched {
+                ops::fetch_store_ref(&repo, remote_name, store, &format!("refs/trunk-remotes/{}/{}", remote_name, store), verbose)?;
+            }
+            info!("✓ Step 3: Fetched {} store(s) from '{}'", fetched.len(), remote_name);
+
+            // Step 4: One push per mirror; without --force git refuses to move
+            // a store the mirror changed meanwhile
+            debug!("➡️ Step 4: Pushing to the mirrors");
+            let mut failed = held.len() + unreachable.len();
+            for mirror in &mirrors {
+                let entries: Vec<&&MirrorStore> = to_push.iter().filter(|entry| &entry.mirror == mirror).collect();
+                if entries.is_empty() {
+                    continue;
+                }
+                let mut push = repo.git();
+                push.arg("push").arg(mirror);
+                for entry in &entries {
+                    if *force {
+                        // Only over the commit listed in Step 2
+                        push.arg(format!("--force-with-lease=refs/trunk/{}:{}", entry.store, entry.hash.as_deref().unwrap_or_default()));
+                    }
+                }
+                for entry in &entries {
+                    push.arg(format!("{}:refs/trunk/{}", entry.canonical.as_deref().unwrap_or_default(), entry.store));
+                }
+                let output = run_git_change(&mut push, verbose).map_err(|e| TrunkError::Git(format!("Failed to push to mirror '{}': {}", mirror, e)))?;
+                if output.status.success() {
+                    info!("✓ Step 4: Updated {} store(s) on '{}'", entries.len(), mirror);
+                } else {
+                    failed += entries.len();
+                    error!("⚠️ Warning: Failed to update '{}': {}", mirror, String::from_utf8_lossy(&output.stderr).trim());
+                }
+            }
+            if failed > 0 {
+                return Err(TrunkError::Conflict(format!("{} store(s) still differ on the mirrors; see the warnings above", failed)));
+            }
+            info!("✅ All {} mirror(s) match remote '{}'", mirrors.len(), remote_name);
+            Ok(())
+        }
+    }
+}
@@ -5,4 +5,65 @@ pub mod push;
 pub mod hooks;
 pub mod stegano;
 pub mod delete;
-pub mod info;
\ No newline at end of file
+pub mod info;
+pub mod doctor;
+pub mod config;
+pub mod hook_run;
+pub mod advertise;
+pub mod worktree;
+pub mod lint_prose;
+pub mod query;
+pub mod board;
+pub mod agenda;
+pub mod new;
+pub mod index_md;
+pub mod diff;
+pub mod changelog;
+pub mod release_notes;
+pub mod pull;
+pub mod status;
+pub mod batch;
+pub mod discard_local;
+pub mod log;
+pub mod examples;
+pub mod maintenance;
+pub mod expose;
+pub mod unexpose;
+pub mod ci;
+pub mod conflicts;
+pub mod cache;
+pub mod protect_clean;
+pub mod fetch;
+pub mod report;
+pub mod lock;
+pub mod unlock;
+pub mod locks;
+pub mod history;
+pub mod restore_backup;
+pub mod undelete;
+pub mod trash;
+pub mod ls;
+pub mod cat;
+pub mod explain;
+pub mod put;
+pub mod materialize_temp;
+pub mod rm;
+pub mod mirrors;
+pub mod blame;
+pub mod about;
+pub mod restore;
+pub mod owners;
+pub mod revert;
+pub mod reset;
+pub mod digest;
+pub mod grep;
+pub mod rename;
+pub mod copy;
+pub mod export;
+pub mod import;
+pub mod adopt;
+pub mod migrate_branch;
+pub mod export_branch;
+pub mod gc;
+pub mod verify;
+pub mod mount;
@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use clap::Parser;
+use log::{debug, info};
+use crate::utils::short_hash;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Mount refs/trunk/<store> as a read-only directory, without checking it out",
+    after_help = "Stays in the foreground until the directory is unmounted with `umount` (or `fusermount -u` on Linux). Needs a git-trunk built with the `fuse` feature, and macFUSE on macOS.\n\nExamples:\n  git trunk --store docs mount /tmp/docs\n  git trunk --store docs mount /tmp/docs-v1 --rev v1.2-docs"
+)]
+pub struct MountArgs {
+    #[arg(value_name = "MOUNTPOINT", help = "Empty directory to mount the store on")]
+    mountpoint: PathBuf,
+    #[arg(long, value_name = "COMMIT", help = "Mount this commit of the store instead of refs/trunk/<store>")]
+    rev: Option<String>,
+}
+
+#[cfg(feature = "fuse")]
+fn mount(repo: &GitRepo, store_name: &str, commit: &str, mountpoint: &Path, verbose: bool) -> Result<(), TrunkError> {
+    use crate::fuse::Tree;
+
+    // Step 4: Read the directory tree of the commit
+    debug!("➡️ Step 4: Reading the files of {}", commit);
+    let tree = Tree::load(repo, commit, verbose).map_err(|e| TrunkError::Git(format!("git ls-tree failed for {}: {}", commit, e)))?;
+    debug!("✓ Step 4: Files read");
+
+    // Step 5: Mount and serve the files until unmounted
+    debug!("➡️ Step 5: Mounting {}", mountpoint.display());
+    let session = crate::fuse::mount(tree, repo, mountpoint, verbose).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied => TrunkError::Unsupported(format!("Failed to mount {}: {}", mountpoint.display(), e)),
+        _ => TrunkError::Io(format!("Failed to mount {}: {}", mountpoint.display(), e)),
+    })?;
+    info!("✅ Mounted store '{}' on {}; unmount it with `umount {}`", store_name, mountpoint.display(), mountpoint.display());
+    session.run().map_err(|e| TrunkError::Io(format!("Failed to serve {}: {}", mountpoint.display(), e)))?;
+    info!("✅ Unmounted {}", mountpoint.display());
+    Ok(())
+}
+
+#[cfg(not(feature = "fuse"))]
+fn mount(_repo: &GitRepo, store_name: &str, _commit: &str, _mountpoint: &Path, _verbose: bool) -> Result<(), TrunkError> {
+    Err(TrunkError::Unsupported(format!(
+        "This git-trunk was built without FUSE support: `git trunk mount` needs a build with `cargo install git-trunk --features fuse`. Browse store '{}' with `git trunk ls` and `git trunk cat <path>`, or export it with `git trunk materialize-temp --dest <dir>`.",
+        store_name
+    )))
+}
+
+pub fn run(args: &MountArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    debug!("✓ Step 1: Repository root found at {}", repo.root().display());
+
+    // Step 2: Resolve the commit to mount
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let rev = args.rev.as_deref().unwrap_or(&trunk_ref_name);
+    debug!("➡️ Step 2: Resolving {}", rev);
+    let Some(commit) = repo.resolve_commit(rev) else {
+        return Err(TrunkError::NotFound(match &args.rev {
+            Some(rev) => format!("'{}' is not a commit in this repository", rev),
+            None => format!("{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.", trunk_ref_name, store_name, store_name),
+        }));
+    };
+    info!("✓ Step 2: Mounting {} ({})", rev, short_hash(repo.root(), &commit, verbose));
+
+    // Step 3: Check the mount point, which would hide any files in it
+    debug!("➡️ Step 3: Checking {}", args.mountpoint.display());
+    let mut entries = fs::read_dir(&args.mountpoint).map_err(|e| TrunkError::NotFound(format!("{} is not a directory ({}); create an empty one to mount on", args.mountpoint.display(), e)))?;
+    if entries.next().is_some() {
+        return Err(TrunkError::Conflict(format!("{} is not empty; mount on an empty directory", args.mountpoint.display())));
+    }
+    let mountpoint = fs::canonicalize(&args.mountpoint).map_err(|e| TrunkError::Io(format!("Failed to resolve {}: {}", args.mountpoint.display(), e)))?;
+    debug!("✓ Step 3: {} is empty", mountpoint.display());
+
+    mount(&repo, store_name, &commit, &mountpoint, verbose)
+}
@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use clap::Parser;
+use log::{debug, error, info};
+use regex::Regex;
+use crate::config;
+use crate::commands::commit::{self, CommitArgs};
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Create a document in a store from one of its _templates, edit it and commit it", after_help = "Examples:\n  git trunk new adr \"Use Postgres\"\n  git trunk new meeting weekly --var team=core --no-edit")]
+pub struct NewArgs {
+    #[arg(help = "Template name: a file in .trunk/<store>/_templates/ (e.g. `meeting` for _templates/meeting.md)")]
+    template: String,
+    #[arg(help = "Name of the new document (e.g. 2024-06-14-planning); the template's extension is added")]
+    name: String,
+    #[arg(long, help = "Directory inside the store for the new document [default: the template name]")]
+    dir: Option<String>,
+    #[arg(long = "var", value_name = "KEY=VALUE", help = "Extra template variable; may be repeated")]
+    vars: Vec<String>,
+    #[arg(long, help = "Do not open the editor; commit the instantiated template as is")]
+    no_edit: bool,
+}
+
+impl NewArgs {
+    pub fn opens_editor(&self) -> bool {
+        !self.no_edit
+    }
+}
+
+pub const TEMPLATES_DIR: &str = "_templates";
+
+fn git_output(args: &[&str], dir: &Path, verbose: bool) -> Option<String> {
+    let output = run_git_command(Command::new("git").args(args).current_dir(dir), verbose).ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+// Replaces `{{ key }}` placeholders; unknown ones are returned so they can be reported.
+fn instantiate(template: &str, vars: &[(String, String)]) -> (String, Vec<String>) {
+    let placeholder = Regex::new(r"\{\{\s*([A-Za-z0-9_.-]+)\s*\}\}").expect("valid placeholder regex");
+    let mut unknown: Vec<String> = Vec::new();
+    let text = placeholder.replace_all(template, |caps: &regex::Captures| {
+        match vars.iter().find(|(key, _)| key == &caps[1]) {
+            Some((_, value)) => value.clone(),
+            None => {
+                if !unknown.contains(&caps[1].to_string()) {
+                    unknown.push(caps[1].to_string());
+                }
+                caps[0].to_string()
+            }
+        }
+    });
+    (text.to_string(), unknown)
+}
+
+pub fn run(args: &NewArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Find the template in the store checkout
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    let templates_dir = trunk_store_dir.join(TEMPLATES_DIR);
+    debug!("➡️ Step 2: Looking for template '{}' in {}/{}", args.template, store_dir_relative_path, TEMPLATES_DIR);
+    if !trunk_store_dir.join(".git").exists() {
+        return Err(TrunkError::NotFound(format!("{} is not checked out. Run `git trunk checkout --store {}` first.", store_dir_relative_path, store_name)));
+    }
+    let mut templates: Vec<String> = fs::read_dir(&templates_dir)
+        .map(|entries| entries.filter_map(|entry| entry.ok()).filter(|entry| entry.path().is_file()).map(|entry| entry.file_name().to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+    templates.sort();
+    let template_file = templates.iter()
+        .find(|file| **file == args.template || Path::new(file).file_stem().is_some_and(|stem| stem.to_string_lossy() == args.template))
+        .ok_or_else(|| {
+            if templates.is_empty() {
+                TrunkError::NotFound(format!("Store '{}' has no templates. Add them to {}/{}/ (e.g. {}/meeting.md).", store_name, store_dir_relative_path, TEMPLATES_DIR, TEMPLATES_DIR))
+            } else {
+                TrunkError::NotFound(format!("Template '{}' not found. Available templates: {}", args.template, templates.join(", ")))
+            }
+        })?;
+    let template = fs::read_to_string(templates_dir.join(template_file)).map_err(|e| TrunkError::Io(format!("Failed to read template {}: {}", template_file, e)))?;
+    info!("✓ Step 2: Using template {}/{}", TEMPLATES_DIR, template_file);
+
+    // Step 3: Work out the new document's path
+    let extension = Path::new(template_file).extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    let file_name = if args.name.ends_with(&extension) { args.name.clone() } else { format!("{}{}", args.name, extension) };
+    let dir = args.dir.clone().unwrap_or_else(|| args.template.clone());
+    let document = if dir.is_empty() || dir == "." { file_name } else { format!("{}/{}", dir.trim_end_matches('/'), file_name) };
+    let document_path = trunk_store_dir.join(&document);
+    if document_path.exists() {
+        return Err(TrunkError::Conflict(format!("{}/{} already exists", store_dir_relative_path, document)));
+    }
+    let status_output = run_git_command(Command::new("git").arg("status").arg("--porcelain").current_dir(&trunk_store_dir), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to run git status in {}: {}", store_dir_relative_path, e)))?;
+    if !status_output.stdout.is_empty() {
+        return Err(TrunkError::Conflict(format!("{} has uncommitted changes. Commit them with `git trunk commit --store {}` first.", store_dir_relative_path, store_name)));
+    }
+
+    // Step 4: Fill in the variables
+    debug!("➡️ Step 4: Instantiating template");
+    let now = chrono::Local::now();
+    let mut vars: Vec<(String, String)> = vec![
+        ("name".to_string(), args.name.clone()),
+        ("title".to_string(), args.name.replace(['-', '_'], " ")),
+        ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+        ("time".to_string(), now.format("%H:%M").to_string()),
+        ("datetime".to_string(), now.to_rfc3339()),
+        ("author".to_string(), git_output(&["config", "user.name"], repo_root, verbose).unwrap_or_default()),
+        ("email".to_string(), git_output(&["config", "user.email"], repo_root, verbose).unwrap_or_default()),
+        ("repo".to_string(), repo_root.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()),
+        ("branch".to_string(), git_output(&["branch", "--show-current"], repo_root, verbose).unwrap_or_default()),
+        ("store".to_string(), store_name.to_string()),
+    ];
+    for var in &args.vars {
+        let Some((key, value)) = var.split_once('=') else {
+            return Err(TrunkError::Usage(format!("Invalid --var '{}': expected KEY=VALUE", var)));
+        };
+        vars.retain(|(existing, _)| existing != key.trim());
+        vars.push((key.trim().to_string(), value.to_string()));
+    }
+    let (content, unknown) = instantiate(&template, &vars);
+    if !unknown.is_empty() {
+        error!("⚠️ Warning: No value for {} (pass --var KEY=VALUE); left as is", unknown.iter().map(|key| format!("{{{{{}}}}}", key)).collect::<Vec<_>>().join(", "));
+    }
+    if let Some(parent) = document_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| TrunkError::Io(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+    fs::write(&document_path, &content).map_err(|e| TrunkError::Io(format!("Failed to write {}/{}: {}", store_dir_relative_path, document, e)))?;
+    info!("✓ Step 4: Created {}/{}", store_dir_relative_path, document);
+
+    // Step 5: Let the user edit it
+    if args.no_edit {
+        info!("= Step 5: Skipped editor (--no-edit)");
+    } else {
+        let editor = git_output(&["var", "GIT_EDITOR"], repo_root, verbose).unwrap_or_else(|| "vi".to_string());
+        debug!("➡️ Step 5: Opening {} in {}", document, editor);
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$@\"", editor))
+            .arg(&editor)
+            .arg(&document_path)
+            .status();
+        if !status.map(|status| status.success()).unwrap_or(false) {
+            let _ = fs::remove_file(&document_path);
+            return Err(TrunkError::Failed(format!("Editor '{}' failed; discarded {}/{}", editor, store_dir_relative_path, document)));
+        }
+        let edited = fs::read_to_string(&document_path).unwrap_or_default();
+        if edited.trim().is_empty() {
+            let _ = fs::remove_file(&document_path);
+            return Err(TrunkError::Failed(format!("{}/{} was emptied; nothing committed", store_dir_relative_path, document)));
+        }
+        info!("✓ Step 5: Saved {}", document);
+    }
+
+    // Step 6: Commit the store
+    let message = format!("Add {} from template {}", document, args.template);
+    commit::run(&CommitArgs { force: true, message: Some(message), ..Default::default() }, remote_name, store_name, verbose)
+}
@@ -0,0 +1,80 @@
+use clap::Parser;
+use log::{debug, info};
+use crate::locks;
+use crate::ops;
+use crate::owners::{self, OWNERS_FILE};
+use crate::table::{Cell, Color, Table};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Show who owns store paths, from the store's TRUNKOWNERS file",
+    after_help = "TRUNKOWNERS holds a line per rule: a path pattern, then its owners (e-mail addresses, or user.name values as `name` or `@name`). Patterns work like .gitignore, and the last matching rule wins:\n\n  *                  docs-team@example.com\n  policies/          hr@example.com @ana\n  policies/travel.md finance@example.com\n\n`git trunk commit` warns about changes to paths you do not own.\n\nExamples:\n  git trunk owners\n  git trunk owners policies/travel.md\n  git trunk owners handbook:policies --ref 1a2b3c4"
+)]
+pub struct OwnersArgs {
+    #[arg(help = "Paths to look up: relative to .trunk/<store>, or `store:path` [default: list the rules]")]
+    paths: Vec<String>,
+    #[arg(long = "ref", value_name = "COMMIT", help = "Read TRUNKOWNERS as of this commit of the store instead of refs/trunk/<store>")]
+    rev: Option<String>,
+    #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+impl OwnersArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        self.paths.iter_mut().collect()
+    }
+}
+
+pub fn run(args: &OwnersArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let paths = ops::store_paths(&repo, &args.paths, store_name, verbose)
+        .iter()
+        .map(|path| locks::normalize_path(store_name, path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Step 2: Read TRUNKOWNERS
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let rev = args.rev.as_deref().unwrap_or(&trunk_ref_name);
+    debug!("➡️ Step 2: Reading {} from {}", OWNERS_FILE, rev);
+    let Some(commit) = repo.resolve_commit(rev) else {
+        return Err(TrunkError::NotFound(match &args.rev {
+            Some(rev) => format!("'{}' is not a commit in this repository", rev),
+            None => format!("{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.", trunk_ref_name, store_name, store_name),
+        }));
+    };
+    let rules = owners::read_at(&repo, &commit, verbose).ok_or_else(|| TrunkError::NotFound(format!(
+        "Store '{}' has no {} in {}. Add one with a line per path pattern and its owners, e.g. `policies/ hr@example.com`, and commit it.",
+        store_name, OWNERS_FILE, rev
+    )))?;
+    info!("✓ Step 2: {} rule(s) in {}", rules.len(), OWNERS_FILE);
+
+    let me = locks::identity(&repo, verbose).ok();
+    let owner_cell = |rule: &owners::Rule| -> Cell {
+        if rule.owners.is_empty() {
+            "(unowned)".into()
+        } else if me.as_ref().is_some_and(|me| rule.is_owner(me)) {
+            Cell::colored(format!("{} (you)", rule.owners.join(", ")), Color::Green)
+        } else {
+            rule.owners.join(", ").into()
+        }
+    };
+    if paths.is_empty() {
+        let mut table = Table::new(&["Line", "Pattern", "Owners"]);
+        for rule in &rules {
+            table.add_row(vec![rule.line.to_string().into(), rule.pattern.as_str().into(), owner_cell(rule)]);
+        }
+        table.print(args.no_table);
+        return Ok(());
+    }
+    let mut table = Table::new(&["Path", "Owners", "Rule"]);
+    for path in &paths {
+        match owners::rule_for(&rules, path) {
+            Some(rule) => table.add_row(vec![path.as_str().into(), owner_cell(rule), format!("line {}: {}", rule.line, rule.pattern).into()]),
+            None => table.add_row(vec![path.as_str().into(), "(unowned)".into(), "-".into()]),
+        }
+    }
+    table.print(args.no_table);
+    Ok(())
+}
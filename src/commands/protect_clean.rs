@@ -0,0 +1,125 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config;
+use crate::ops;
+use crate::utils::{dry_run, run_git_command};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Guard the uncommitted work in .trunk against `git clean`", after_help = "Examples:\n  git trunk protect-clean\n  git clean-safe -ffdx\n  git trunk protect-clean --check\n  git trunk protect-clean --undo")]
+pub struct ProtectCleanArgs {
+    #[arg(long, conflicts_with = "undo", help = "Only check: fail when a checked-out store has uncommitted changes")]
+    check: bool,
+    #[arg(long, help = "Remove the `git clean-safe` alias again")]
+    undo: bool,
+}
+
+// Alias installed in the main repository. Git never lets an alias shadow a
+// built-in command and has no pre-clean hook, so the guard is a separate
+// command that runs the check before handing its arguments to `git clean`.
+pub const GUARD_ALIAS: &str = "clean-safe";
+const GUARD_COMMAND: &str = "!git trunk protect-clean --check && git clean";
+
+// True when `git trunk protect-clean` installed the guard alias.
+pub fn is_protected(repo: &GitRepo, verbose: bool) -> bool {
+    config_value(repo, &format!("alias.{}", GUARD_ALIAS), verbose).is_some_and(|value| value == GUARD_COMMAND)
+}
+
+// Checked-out stores with uncommitted changes and how many paths changed.
+// `git clean -fdx` skips them because they are nested repositories, but
+// `git clean -ffdx` deletes them along with that work.
+pub fn uncommitted_stores(repo: &GitRepo, verbose: bool) -> Vec<(String, usize)> {
+    let trunk_base_dir = config::trunk_base_dir(repo.root(), verbose);
+    ops::checked_out_stores(repo.root(), verbose)
+        .into_iter()
+        .filter_map(|store| {
+            let store_repo = GitRepo::open(&trunk_base_dir.join(&store), verbose).ok()?;
+            let changes = store_repo.status_porcelain().ok()?.len();
+            (changes > 0).then_some((store, changes))
+        })
+        .collect()
+}
+
+fn config_value(repo: &GitRepo, key: &str, verbose: bool) -> Option<String> {
+    let output = run_git_command(repo.git().arg("config").arg("--local").arg("--get").arg(key), verbose).ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+fn set_config(repo: &GitRepo, args: &[&str], verbose: bool) -> Result<(), TrunkError> {
+    let output = run_git_command(repo.git().arg("config").arg("--local").args(args), verbose).map_err(|e| TrunkError::Git(format!("Failed to run git config: {}", e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git config {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    Ok(())
+}
+
+pub fn run(args: &ProtectCleanArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+
+    if args.check {
+        // Step 2: Refuse while a store holds work that only lives on disk
+        debug!("➡️ Step 2: Checking the stores in .trunk for uncommitted changes");
+        let uncommitted = uncommitted_stores(&repo, verbose);
+        if uncommitted.is_empty() {
+            info!("✓ Step 2: No store has uncommitted changes");
+            return Ok(());
+        }
+        let listing: Vec<String> = uncommitted.iter().map(|(store, changes)| format!("'{}' ({} change(s))", store, changes)).collect();
+        return Err(TrunkError::Conflict(format!(
+            "Uncommitted changes in store(s) {} would be lost by `git clean -ff`. Commit them with `git trunk commit --all` first, or run `git clean` directly to skip this check.",
+            listing.join(", ")
+        )));
+    }
+
+    let alias_key = format!("alias.{}", GUARD_ALIAS);
+    if args.undo {
+        // Step 2: Remove the guard alias
+        if !is_protected(&repo, verbose) {
+            info!("= Step 2: git {} is not installed, nothing to do", GUARD_ALIAS);
+            return Ok(());
+        }
+        if !dry_run(&format!("unset {}", alias_key)) {
+            set_config(&repo, &["--unset", &alias_key], verbose)?;
+        }
+        info!("✓ Step 2: Removed git {}", GUARD_ALIAS);
+        info!("✅ git clean is no longer guarded; clean.requireForce is left as it is");
+        return Ok(());
+    }
+
+    // Step 2: Never let `git clean` run without -f in this repository, even
+    // when a global config turned the safety off
+    debug!("➡️ Step 2: Checking clean.requireForce");
+    let output = run_git_command(repo.git().arg("config").arg("--bool").arg("--get").arg("clean.requireForce"), verbose).map_err(|e| TrunkError::Git(format!("Failed to run git config: {}", e)))?;
+    if String::from_utf8_lossy(&output.stdout).trim() == "false" {
+        if !dry_run("set clean.requireForce = true") {
+            set_config(&repo, &["clean.requireForce", "true"], verbose)?;
+        }
+        info!("✓ Step 2: Set clean.requireForce = true for this repository");
+    } else {
+        info!("= Step 2: clean.requireForce is already on");
+    }
+
+    // Step 3: Install the guard alias
+    match config_value(&repo, &alias_key, verbose) {
+        Some(value) if value == GUARD_COMMAND => info!("= Step 3: git {} is already installed", GUARD_ALIAS),
+        Some(value) => {
+            return Err(TrunkError::Conflict(format!("{} is already set to '{}'; remove it with `git config --unset {}` first", alias_key, value, alias_key)));
+        }
+        None => {
+            if !dry_run(&format!("set {} = {}", alias_key, GUARD_COMMAND)) {
+                set_config(&repo, &[&alias_key, GUARD_COMMAND], verbose)?;
+            }
+            info!("✓ Step 3: Installed git {}", GUARD_ALIAS);
+        }
+    }
+
+    // Step 4: Point out work that is at risk right now
+    for (store, changes) in uncommitted_stores(&repo, verbose) {
+        error!("⚠️ Warning: store '{}' has {} uncommitted change(s) that `git clean -ffdx` would delete; commit them with `git trunk --store {} commit`", store, changes, store);
+    }
+    info!("✅ Use `git {} -ffdx` instead of `git clean -ffdx`: it refuses while a store in .trunk has uncommitted changes", GUARD_ALIAS);
+    Ok(())
+}
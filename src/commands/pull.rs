@@ -0,0 +1,176 @@
+use std::io;
+use std::process::Command;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::locks;
+use crate::ops;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Fetch refs/trunk/<store> from the remote and merge it into .trunk/<store>, keeping uncommitted changes", after_help = "Examples:\n  git trunk pull\n  git trunk pull --ff-only --store blog\n  git trunk pull --rebase\n  git trunk pull --continue")]
+pub struct PullArgs {
+    #[arg(long, help = "Only fast-forward; fail instead of creating a merge commit")]
+    ff_only: bool,
+    #[arg(long, conflicts_with = "ff_only", help = "Replay local store commits on top of the remote ref instead of merging, for a linear history")]
+    rebase: bool,
+    #[arg(long = "continue", conflicts_with_all = ["ff_only", "rebase", "abort"], help = "Continue a `pull --rebase` that stopped with conflicts, once they are resolved and added")]
+    continue_rebase: bool,
+    #[arg(long, conflicts_with_all = ["ff_only", "rebase"], help = "Abort a `pull --rebase` that stopped with conflicts, putting .trunk/<store> back as it was")]
+    abort: bool,
+}
+
+fn git_ok(command: &mut Command, verbose: bool) -> io::Result<std::process::Output> {
+    let output = run_git_command(command, verbose)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(io::Error::other(if stderr.is_empty() { "git command failed".to_string() } else { stderr }));
+    }
+    Ok(output)
+}
+
+// Whether a rebase stopped in the store checkout, waiting for --continue or --abort.
+fn rebase_in_progress(store_repo: &GitRepo, verbose: bool) -> bool {
+    ["rebase-merge", "rebase-apply"].iter().any(|dir| {
+        run_git_command(store_repo.git().arg("rev-parse").arg("--git-path").arg(dir), verbose)
+            .is_ok_and(|output| store_repo.root().join(String::from_utf8_lossy(&output.stdout).trim()).is_dir())
+    })
+}
+
+fn rebase_conflict(store_name: &str, e: io::Error) -> TrunkError {
+    TrunkError::Conflict(format!(
+        "Rebase stopped with conflicts in .trunk/{}: {}\n   `git trunk conflicts --store {}` lists them. Resolve them there and `git add` them, then run `git trunk pull --continue --store {}`, or `git trunk pull --abort --store {}` to undo the pull.",
+        store_name, e, store_name, store_name, store_name
+    ))
+}
+
+// Git reports a stash that no longer applies after the merge or rebase on stderr.
+fn warn_on_autostash_conflict(store_repo: &GitRepo, output: &std::process::Output, store_dir_relative_path: &str) {
+    let stash_conflict = store_repo.conflicted_paths().is_ok_and(|paths| !paths.is_empty())
+        || String::from_utf8_lossy(&output.stderr).contains("Applying autostash resulted in conflicts");
+    if stash_conflict {
+        error!("⚠️ Warning: Your uncommitted changes conflicted with the pulled ones; resolve the conflict markers in {} (the changes are also kept in `git stash list`)", store_dir_relative_path);
+    }
+}
+
+// `pull --continue` and `pull --abort`: finish or undo a rebase that stopped
+// with conflicts. refs/trunk/<store> only moves once the rebase is done.
+fn finish_rebase(args: &PullArgs, repo: &GitRepo, store_repo: &GitRepo, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    if !rebase_in_progress(store_repo, verbose) {
+        return Err(TrunkError::Failed(format!("No `git trunk pull --rebase` is in progress in {}", store_dir_relative_path)));
+    }
+    if args.abort {
+        debug!("➡️ Step 3: Aborting the rebase in {}", store_dir_relative_path);
+        git_ok(store_repo.git().arg("rebase").arg("--abort"), verbose).map_err(|e| TrunkError::Git(format!("Failed to abort the rebase in {}: {}", store_dir_relative_path, e)))?;
+        info!("✓ Step 3: Aborted the rebase");
+        info!("✅ Pull aborted; {} and refs/trunk/{} are as they were before it", store_dir_relative_path, store_name);
+        return Ok(());
+    }
+
+    debug!("➡️ Step 3: Continuing the rebase in {}", store_dir_relative_path);
+    // Keep the replayed commit messages instead of opening an editor
+    let output = git_ok(store_repo.git().env("GIT_EDITOR", "true").arg("rebase").arg("--continue"), verbose).map_err(|e| rebase_conflict(store_name, e))?;
+    warn_on_autostash_conflict(store_repo, &output, &store_dir_relative_path);
+    info!("✓ Step 3: Rebased {}", store_dir_relative_path);
+
+    debug!("🔄 Step 4: Updating refs/trunk/{} from {}", store_name, store_dir_relative_path);
+    ops::update_store_ref(repo, store_repo, store_name, verbose)?;
+    info!("✓ Step 4: Updated refs/trunk/{}", store_name);
+    info!("✅ Trunk store '{}' rebased onto the pulled commits", store_name);
+    Ok(())
+}
+
+pub fn run(args: &PullArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let fetched_ref_name = format!("refs/trunk-fetch/{}", store_name);
+    let temp_store_ref = "refs/temp/trunk_pull";
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+
+    // Step 2: Check the store checkout
+    let store_repo = ops::ensure_store_checkout(repo_root, store_name, "Step 2", verbose)?;
+    if args.continue_rebase || args.abort {
+        return finish_rebase(args, &repo, &store_repo, store_name, verbose);
+    }
+    if rebase_in_progress(&store_repo, verbose) {
+        return Err(TrunkError::Conflict(format!("A rebase is in progress in {}; finish it with `git trunk pull --continue --store {}` or undo it with `git trunk pull --abort --store {}`", store_dir_relative_path, store_name, store_name)));
+    }
+
+    // Step 3: Fetch the remote store ref under a temporary name
+    debug!("📥 Step 3: Fetching {} from remote '{}'", trunk_ref_name, remote_name);
+    ops::fetch_store_ref(&repo, remote_name, store_name, &fetched_ref_name, verbose)?;
+    let remote_hash = repo
+        .resolve_commit(&fetched_ref_name)
+        .ok_or_else(|| TrunkError::Git(format!("Failed to read fetched {}", trunk_ref_name)))?;
+    info!("✓ Step 3: Fetched {} from remote '{}' at {}", trunk_ref_name, remote_name, short_hash(repo_root, &remote_hash, verbose));
+    if let Err(e) = locks::refresh(&repo, remote_name, store_name, verbose) {
+        debug!("⚠️ Step 3: Could not fetch the locks of store '{}': {}", store_name, e);
+    }
+
+    let cleanup = || {
+        let _ = repo.delete_ref(&fetched_ref_name);
+        let _ = store_repo.delete_ref(temp_store_ref);
+    };
+
+    // Step 4: Bring the fetched commits into the store checkout
+    debug!("📥 Step 4: Fetching {} into {}", trunk_ref_name, store_dir_relative_path);
+    if let Err(e) = store_repo.fetch_local(repo_root, &format!("+{}:{}", fetched_ref_name, temp_store_ref)) {
+        cleanup();
+        return Err(TrunkError::Git(format!("Failed to fetch {} into {}: {}", trunk_ref_name, store_dir_relative_path, e)));
+    }
+    let up_to_date = store_repo.is_ancestor(temp_store_ref, "HEAD");
+    if up_to_date {
+        cleanup();
+        info!("= Step 4: {} already contains {} from remote '{}'", store_dir_relative_path, trunk_ref_name, remote_name);
+        info!("✅ Trunk store '{}' is up to date", store_name);
+        return Ok(());
+    }
+    info!("✓ Step 4: Fetched remote commits into {}", store_dir_relative_path);
+
+    // Step 5: Merge or rebase, stashing and restoring uncommitted changes around it
+    if args.rebase {
+        debug!("🔀 Step 5: Rebasing {} onto the remote changes", store_dir_relative_path);
+        let rebase_result = git_ok(store_repo.git().arg("rebase").arg("--autostash").arg(temp_store_ref), verbose);
+        cleanup();
+        let output = rebase_result.map_err(|e| rebase_conflict(store_name, e))?;
+        warn_on_autostash_conflict(&store_repo, &output, &store_dir_relative_path);
+        info!("✓ Step 5: Rebased {} onto the remote changes", store_dir_relative_path);
+    } else {
+        debug!("🔀 Step 5: Merging remote changes into {}", store_dir_relative_path);
+        let mut merge = store_repo.git();
+        merge.arg("merge").arg("--autostash").arg("--no-edit");
+        if args.ff_only {
+            merge.arg("--ff-only");
+        }
+        merge.arg("-m").arg(format!("Merge {} from {}", trunk_ref_name, remote_name)).arg(temp_store_ref);
+        let merge_result = git_ok(&mut merge, verbose);
+        cleanup();
+        let output = match merge_result {
+            Ok(output) => output,
+            Err(_) if args.ff_only => {
+                return Err(TrunkError::Conflict(format!("Cannot fast-forward {}: local and remote changes diverged. Re-run without --ff-only to merge them, or with --rebase to replay local commits on top.", store_dir_relative_path)));
+            }
+            Err(e) => {
+                return Err(TrunkError::Conflict(format!(
+                    "Merge stopped with conflicts in {}: {}\n   `git trunk conflicts --store {}` lists them. Resolve them there (git add / git commit), then run `git trunk commit --store {}`.",
+                    store_dir_relative_path, e, store_name, store_name
+                )));
+            }
+        };
+        warn_on_autostash_conflict(&store_repo, &output, &store_dir_relative_path);
+        info!("✓ Step 5: Merged remote changes into {}", store_dir_relative_path);
+    }
+
+    // Step 6: Point refs/trunk/<store> at the merged history
+    debug!("🔄 Step 6: Updating {} from {}", trunk_ref_name, store_dir_relative_path);
+    ops::update_store_ref(&repo, &store_repo, store_name, verbose)?;
+    info!("✓ Step 6: Updated {}", trunk_ref_name);
+
+    info!("✅ Trunk store '{}' pulled from remote '{}'", store_name, remote_name);
+    Ok(())
+}
@@ -1,59 +1,287 @@
+use std::process::Command;
 use clap::Parser;
-use std::process::{Command, exit};
 use log::{debug, error, info};
-use crate::utils::run_git_command; // Ensure this line is present
+use crate::config;
+use crate::table::{Cell, Table};
+use crate::commands::expose;
+use crate::ops;
+use crate::utils::{dry_run, is_dry_run, run_git_change, run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
 
-#[derive(Parser, Debug)]
-#[command(about = "Push refs/trunk/<store> to the specified remote")]
+#[derive(Parser, Debug, Default)]
+#[command(about = "Push refs/trunk/<store> to the specified remote", after_help = "Examples:\n  git trunk push\n  git trunk push --store blog --remote upstream\n  git trunk push --all\n  git trunk push --squash-since-last-push -m \"Q3 roadmap\"\n  git trunk fetch && git trunk push --force-with-lease")]
 pub struct PushArgs {
     // Remote is now a global option, remove from here
     // store is now a global option, remove from here if it was ever considered locally
+    #[arg(long, help = "Squash the commits made since the remote's tip into one publish commit, keeping them locally under refs/trunk-wip/<store>/")]
+    squash_since_last_push: bool,
+    #[arg(short = 'm', long, requires = "squash_since_last_push", help = "Message of the publish commit [default: the squashed commit subjects]")]
+    message: Option<String>,
+    #[arg(long, help = "Push every local refs/trunk/* store, each to its configured remote, and summarize the results")]
+    all: bool,
+    #[arg(long, requires = "all", help = "With --all, print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+    #[arg(long, conflicts_with = "force", help = "Replace the remote's ref even if it is not an ancestor, but only if it is still where `git trunk fetch` or the last push saw it")]
+    force_with_lease: bool,
+    #[arg(long, help = "Replace the remote's ref even if that drops commits only the remote has")]
+    force: bool,
 }
 
-pub fn run(_args: &PushArgs, remote_name: &str, store_name: &str, verbose: bool) {
+fn git_stdout(command: &mut Command, verbose: bool) -> Result<String, String> {
+    let output = run_git_command(command, verbose).map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() { "git command failed".to_string() } else { stderr });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Replaces the commits on refs/trunk/<store> since the remote's tip with one
+// commit holding the same tree. The old tip stays reachable from
+// refs/trunk-wip/<store>/<publish commit>, and the store checkout is moved
+// onto the publish commit so later commits build on what was pushed.
+fn squash_since_last_push(repo: &GitRepo, args: &PushArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let repo_root = repo.root();
     let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let fetched_ref_name = format!("refs/trunk-fetch/{}", store_name);
 
-    // Step 1: Verify that refs/trunk/<store_name> exists locally
-    debug!("➡️ Step 1: Checking if {} exists locally for store '{}'", trunk_ref_name, store_name);
-    let show_ref = run_git_command(
-        Command::new("git")
-            .args(["show-ref", "--quiet", &trunk_ref_name]),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to check {}: {}", trunk_ref_name, e);
-        exit(1);
+    // Step 1a: Find the remote's tip
+    debug!("📥 Step 1a: Fetching {} from remote '{}'", trunk_ref_name, remote_name);
+    let remote_tip = ops::remote_store_tip(repo, remote_name, store_name, verbose)?;
+    let local_tip = repo.resolve_commit(&trunk_ref_name).ok_or_else(|| TrunkError::Git(format!("Failed to resolve {}", trunk_ref_name)))?;
+    if let Some(remote_tip) = &remote_tip {
+        if repo.resolve_commit(remote_tip).is_none() && !is_dry_run() {
+            ops::fetch_store_ref(repo, remote_name, store_name, &fetched_ref_name, verbose)?;
+            let _ = repo.delete_ref(&fetched_ref_name);
+        }
+    }
+    let since = remote_tip.as_deref().map(|tip| short_hash(repo_root, tip, verbose)).unwrap_or_else(|| "the first commit".to_string());
+    info!("✓ Step 1a: Remote '{}' is at {}", remote_name, since);
+
+    // Step 1b: Count the commits to squash
+    let range = match &remote_tip {
+        Some(remote_tip) => {
+            if is_dry_run() && repo.resolve_commit(remote_tip).is_none() {
+                dry_run(&format!("fetch {} from '{}' and squash the commits made since {} into one publish commit", trunk_ref_name, remote_name, since));
+                return Ok(());
+            }
+            if !repo.is_ancestor(remote_tip, &local_tip) {
+                return Err(TrunkError::Conflict(format!("{} on remote '{}' has commits that are not in the local store. Run `git trunk pull --store {}` first, then push again.", trunk_ref_name, remote_name, store_name)));
+            }
+            format!("{}..{}", remote_tip, local_tip)
+        }
+        None => local_tip.clone(),
+    };
+    let subjects: Vec<String> = git_stdout(repo.git().arg("log").arg("--reverse").arg("--format=%s").arg(&range), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to list the commits to squash: {}", e)))?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    if subjects.len() < 2 {
+        info!("= Step 1b: {} commit(s) since {}, nothing to squash", subjects.len(), since);
+        return Ok(());
+    }
+    info!("✓ Step 1b: {} commits to squash since {}", subjects.len(), since);
+
+    // Step 1c: Create the publish commit on top of the remote's tip
+    let message = args.message.clone().unwrap_or_else(|| {
+        let list: Vec<String> = subjects.iter().map(|subject| format!("* {}", subject)).collect();
+        format!("Publish {} commits to store '{}'\n\n{}", subjects.len(), store_name, list.join("\n"))
     });
+    if dry_run(&format!("squash {} commits since {} into one publish commit and point {} at it, keeping them on refs/trunk-wip/{}/", subjects.len(), since, trunk_ref_name, store_name)) {
+        return Ok(());
+    }
+    debug!("📝 Step 1c: Creating the publish commit for store '{}'", store_name);
+    let mut commit_tree = repo.git();
+    commit_tree.arg("commit-tree").arg(format!("{}^{{tree}}", local_tip)).arg("-m").arg(&message);
+    if let Some(remote_tip) = &remote_tip {
+        commit_tree.arg("-p").arg(remote_tip);
+    }
+    let publish_commit = git_stdout(&mut commit_tree, verbose).map_err(|e| TrunkError::Git(format!("Failed to create the publish commit: {}", e)))?;
+    let publish_short = short_hash(repo_root, &publish_commit, verbose);
+    let wip_ref_name = format!("refs/trunk-wip/{}/{}", store_name, publish_short);
+    repo.update_ref(&wip_ref_name, &local_tip).map_err(|e| TrunkError::Git(format!("Failed to create {}: {}", wip_ref_name, e)))?;
+    repo.update_ref(&trunk_ref_name, &publish_commit).map_err(|e| TrunkError::Git(format!("Failed to update {}: {}", trunk_ref_name, e)))?;
+    info!("✓ Step 1c: Squashed {} commits into {}; the originals are kept on {}", subjects.len(), publish_short, wip_ref_name);
+    expose::sync_exposed_branch(repo, store_name, verbose);
+
+    // Step 1d: Move the store checkout onto the publish commit. Its files
+    // already match, so only the branch moves.
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let Ok(store_repo) = GitRepo::open(&config::trunk_base_dir(repo_root, verbose).join(store_name), verbose) else {
+        debug!("= Step 1d: {} is not checked out", store_dir_relative_path);
+        return Ok(());
+    };
+    if store_repo.resolve_commit("HEAD").as_deref() != Some(local_tip.as_str()) {
+        error!("⚠️ Warning: {} has commits that were not in {}; move them onto the publish commit with `git rebase --onto {} {}` there", store_dir_relative_path, trunk_ref_name, publish_short, short_hash(repo_root, &local_tip, verbose));
+        return Ok(());
+    }
+    let temp_store_ref = "refs/temp/trunk_squash";
+    let moved = store_repo.fetch_local(repo_root, &format!("+{}:{}", trunk_ref_name, temp_store_ref))
+        .map_err(|e| e.to_string())
+        .and_then(|_| git_stdout(store_repo.git().arg("reset").arg("--soft").arg(temp_store_ref), verbose));
+    let _ = store_repo.delete_ref(temp_store_ref);
+    match moved {
+        Ok(_) => info!("✓ Step 1d: {} now builds on the publish commit", store_dir_relative_path),
+        Err(e) => error!("⚠️ Warning: Could not move {} onto the publish commit ({}); run `git trunk checkout --store {} --force`", store_dir_relative_path, e, store_name),
+    }
+    Ok(())
+}
+
+pub fn run(args: &PushArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    if !args.all {
+        return push_store(&repo, args, remote_name, store_name, verbose);
+    }
 
-    if !show_ref.status.success() {
-        error!("❌ {} for store '{}' does not exist in the local repository. Commit changes first using `git trunk commit --store {}`.", trunk_ref_name, store_name, store_name);
-        exit(1);
+    // Like `status --all`, every store goes to its own configured remote
+    let trunk_config = config::load(repo.root(), verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let stores: Vec<String> = repo.ref_names("refs/trunk/")
+        .map_err(|e| TrunkError::Git(format!("Failed to list refs/trunk/*: {}", e)))?
+        .into_iter()
+        .filter(|name| config::is_store_name(name))
+        .collect();
+    if stores.is_empty() {
+        info!("= No local refs/trunk/* stores to push");
+        return Ok(());
+    }
+    let mut table = Table::new(&["Store", "Remote", "Result"]);
+    let mut failed = 0;
+    for store in &stores {
+        let store_remote = trunk_config.remote_for_store(store);
+        info!("➡️ Pushing store '{}' to '{}'", store, store_remote);
+        let result = push_store(&repo, args, &store_remote, store, verbose);
+        if let Err(e) = &result {
+            error!("❌ Store '{}': {}", store, e);
+            failed += 1;
+        }
+        table.add_row(vec![
+            store.as_str().into(),
+            store_remote.into(),
+            match result {
+                Ok(()) => Cell::flag(true, "pushed"),
+                Err(e) => Cell::flag(false, &e.to_string()),
+            },
+        ]);
+    }
+    println!();
+    table.print(args.no_table);
+    if failed > 0 {
+        return Err(TrunkError::Failed(format!("{} of {} store(s) failed to push", failed, stores.len())));
+    }
+    info!("✅ Pushed all {} store(s)", stores.len());
+    Ok(())
+}
+
+fn push_store(repo: &GitRepo, args: &PushArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+
+    // Step 1: Verify that refs/trunk/<store_name> exists locally
+    debug!("➡️ Step 1: Checking if {} exists locally for store '{}'", trunk_ref_name, store_name);
+    if !repo.ref_exists(&trunk_ref_name) {
+        return Err(TrunkError::NotFound(format!("{} for store '{}' does not exist in the local repository. Commit changes first using `git trunk commit --store {}`.", trunk_ref_name, store_name, store_name)));
     }
     info!("✓ Step 1: {} found locally for store '{}'", trunk_ref_name, store_name);
 
-    // Step 2: Push refs/trunk/<store_name> to the remote
-    debug!("📤 Step 2: Pushing {} for store '{}' to remote '{}'", trunk_ref_name, store_name, remote_name);
-    let refspec = format!("{}:{}", trunk_ref_name, trunk_ref_name);
-    let push_status = run_git_command(
-        Command::new("git")
-            .args([
-                "push",
-                remote_name,
-                &refspec,
-            ]),
-        verbose,
-    )
-    .unwrap_or_else(|e| {
-        error!("❌ Failed to execute git push for store '{}' to remote '{}': {}", store_name, remote_name, e);
-        exit(1);
-    })
-    .status;
+    if args.squash_since_last_push {
+        squash_since_last_push(repo, args, remote_name, store_name, verbose)?;
+    }
+
+    // Step 2: Compare with the remote's tip. Servers differ in whether they
+    // accept non-fast-forward pushes, so refuse them here unless forced.
+    let repo_root = repo.root();
+    let local_tip = repo.resolve_commit(&trunk_ref_name).ok_or_else(|| TrunkError::Git(format!("Failed to resolve {}", trunk_ref_name)))?;
+    let last_known_ref_name = format!("refs/trunk-remotes/{}/{}", remote_name, store_name);
+    let last_known = repo.resolve_commit(&last_known_ref_name);
+    // Review servers such as Gerrit take the push on a review ref and only
+    // move refs/trunk/<store> once the change is merged
+    let review_ref = config::load(repo_root, verbose).ok().and_then(|c| c.review_ref(remote_name, store_name));
+    let pending_review_ref_name = format!("refs/trunk-review/{}/{}", remote_name, store_name);
+    if review_ref.is_some() && (args.force || args.force_with_lease) {
+        return Err(TrunkError::Usage(format!("Remote '{}' takes pushes for review ([remote.{}] review_ref), so --force and --force-with-lease do not apply", remote_name, remote_name)));
+    }
+    debug!("➡️ Step 2: Comparing {} with remote '{}'", trunk_ref_name, remote_name);
+    let remote_tip = ops::remote_store_tip(repo, remote_name, store_name, verbose)?;
+    let describe = |hash: &Option<String>| hash.as_deref().map(|hash| short_hash(repo_root, hash, verbose)).unwrap_or_else(|| "nothing".to_string());
+    let mut push = repo.git();
+    push.arg("push");
+    if args.force {
+        error!("⚠️ Warning: --force: replacing {} on remote '{}' (at {}) with {}", trunk_ref_name, remote_name, describe(&remote_tip), describe(&Some(local_tip.clone())));
+        push.arg(remote_name).arg(format!("+{}:{}", trunk_ref_name, trunk_ref_name));
+    } else if args.force_with_lease {
+        // The lease is the last value seen on the remote; without one there is
+        // nothing to protect against, except a ref that appeared since
+        if last_known.is_none() && remote_tip.is_some() {
+            return Err(TrunkError::Conflict(format!("No last-known value of {} on remote '{}' to lease against. Run `git trunk fetch --store {}` to record it (and check what the remote has), then push again.", trunk_ref_name, remote_name, store_name)));
+        }
+        if last_known != remote_tip {
+            return Err(TrunkError::Conflict(format!("{} on remote '{}' moved since it was last fetched (expected {}, found {}). Run `git trunk fetch --store {}` and review the remote's commits before forcing again.", trunk_ref_name, remote_name, describe(&last_known), describe(&remote_tip), store_name)));
+        }
+        info!("✓ Step 2: Remote '{}' is still at {}, the last-known value", remote_name, describe(&last_known));
+        push.arg(format!("--force-with-lease={}:{}", trunk_ref_name, last_known.as_deref().unwrap_or(""))).arg(remote_name).arg(format!("+{}:{}", trunk_ref_name, trunk_ref_name));
+    } else {
+        match &remote_tip {
+            Some(remote_tip) if *remote_tip == local_tip => {
+                info!("= Step 2: Remote '{}' already has {} at {}", remote_name, trunk_ref_name, describe(&Some(local_tip.clone())));
+                if !is_dry_run() {
+                    let _ = repo.update_ref(&last_known_ref_name, &local_tip);
+                    let _ = repo.delete_ref(&pending_review_ref_name);
+                }
+                info!("✅ Trunk store '{}' is up to date on remote '{}'", store_name, remote_name);
+                return Ok(());
+            }
+            // A remote commit missing locally cannot be an ancestor of the local tip
+            Some(remote_tip) if !repo.is_ancestor(remote_tip, &local_tip) => {
+                return Err(TrunkError::Conflict(format!(
+                    "{} on remote '{}' has commits that are not in the local store (remote at {}, local at {}). Run `git trunk pull --store {}` to merge them, then push again; to replace them instead, use `git trunk fetch --store {}` and `push --force-with-lease`, or `push --force`.",
+                    trunk_ref_name, remote_name, describe(&Some(remote_tip.clone())), describe(&Some(local_tip.clone())), store_name, store_name
+                )));
+            }
+            _ => info!("✓ Step 2: Pushing {} is a fast-forward of remote '{}' (at {})", trunk_ref_name, remote_name, describe(&remote_tip)),
+        }
+        if review_ref.is_some() && repo.resolve_commit(&pending_review_ref_name).as_deref() == Some(local_tip.as_str()) {
+            info!("= Step 2: {} at {} is already pending review on remote '{}'", trunk_ref_name, describe(&Some(local_tip.clone())), remote_name);
+            info!("✅ Nothing new to push for store '{}'", store_name);
+            return Ok(());
+        }
+        push.arg(remote_name).arg(format!("{}:{}", trunk_ref_name, review_ref.as_deref().unwrap_or(&trunk_ref_name)));
+    }
+
+    // Step 3: Push refs/trunk/<store_name> to the remote (through git, so
+    // transports and credential helpers behave as configured)
+    debug!("📤 Step 3: Pushing {} for store '{}' to remote '{}'", trunk_ref_name, store_name, remote_name);
+    let push_status = run_git_change(&mut push, verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to execute git push for store '{}' to remote '{}': {}", store_name, remote_name, e)))?
+        .status;
 
     if !push_status.success() {
-        error!("❌ Failed to push {} for store '{}' to remote '{}'", trunk_ref_name, store_name, remote_name);
-        exit(1);
+        // The remote can still move between the check and the push
+        if ops::remote_store_tip(repo, remote_name, store_name, verbose).is_ok_and(|tip| tip != remote_tip) {
+            return Err(TrunkError::Conflict(format!("{} on remote '{}' changed while pushing. Run `git trunk pull --store {}`, then push again.", trunk_ref_name, remote_name, store_name)));
+        }
+        return Err(TrunkError::Git(format!("Failed to push {} for store '{}' to remote '{}'", trunk_ref_name, store_name, remote_name)));
+    }
+    if let Some(review_ref) = &review_ref {
+        if !is_dry_run() {
+            // Lets `status` tell a change waiting for review from unpushed work
+            if let Err(e) = repo.update_ref(&pending_review_ref_name, &local_tip) {
+                debug!("⚠️ Failed to update {}: {}", pending_review_ref_name, e);
+            }
+        }
+        info!("✓ Step 3: Pushed {} for store '{}' to {} on remote '{}'", trunk_ref_name, store_name, review_ref, remote_name);
+        info!("✅ Trunk store '{}' pushed for review; {} on remote '{}' changes once the review is merged", store_name, trunk_ref_name, remote_name);
+        return Ok(());
+    }
+    if !is_dry_run() {
+        ops::forget_remote_listing(repo, remote_name);
+        // The new last-known value for the next --force-with-lease
+        if let Err(e) = repo.update_ref(&last_known_ref_name, &local_tip) {
+            debug!("⚠️ Failed to update {}: {}", last_known_ref_name, e);
+        }
     }
 
-    info!("✓ Step 2: Successfully pushed {} for store '{}' to remote '{}'", trunk_ref_name, store_name, remote_name);
+    info!("✓ Step 3: Successfully pushed {} for store '{}' to remote '{}'", trunk_ref_name, store_name, remote_name);
     info!("✅ Trunk store '{}' pushed successfully", store_name);
-}
\ No newline at end of file
+    Ok(())
+}
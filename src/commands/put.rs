@@ -0,0 +1,119 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::commands::{checkout, push};
+use crate::config;
+use crate::locks::{self, normalize_path};
+use crate::ops;
+use crate::quota;
+use crate::utils::short_hash;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Commit a file into refs/trunk/<store> straight from the main worktree, without a checkout",
+    after_help = "Examples:\n  git trunk put build/api.md reference/api.md -m \"Regenerate the API reference\"\n  git trunk put notes.md handbook:meetings/\n  ./collect-logs | git trunk put - logs/nightly.txt"
+)]
+pub struct PutArgs {
+    #[arg(help = "File to commit, relative to the current directory; `-` reads standard input")]
+    source: String,
+    #[arg(help = "Where to put it: relative to .trunk/<store>, or `store:path`; a trailing `/` keeps the source's file name [default: the source's file name]")]
+    dest: Option<String>,
+    #[arg(short = 'm', long, help = "Commit message [default: \"Put <dest>\"]")]
+    message: Option<String>,
+}
+
+impl PutArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        self.dest.iter_mut().collect()
+    }
+}
+
+// Where the file goes in the store: the destination, with the source's file
+// name appended when it is missing or names a directory.
+fn dest_path(args: &PutArgs, dest: Option<String>, store_name: &str) -> Result<String, TrunkError> {
+    let file_name = || {
+        Path::new(&args.source)
+            .file_name()
+            .filter(|_| args.source != "-")
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| TrunkError::Usage("Give a destination path when reading standard input".to_string()))
+    };
+    let path = match dest {
+        None => file_name()?,
+        Some(dest) if dest.is_empty() || dest == "." || dest.ends_with('/') => format!("{}/{}", dest.trim_end_matches('/'), file_name()?),
+        Some(dest) => dest,
+    };
+    normalize_path(store_name, path.trim_start_matches("./").trim_start_matches('/'))
+}
+
+pub fn run(args: &PutArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let dest = args.dest.as_ref().map(|dest| ops::store_paths(&repo, std::slice::from_ref(dest), store_name, verbose).pop().unwrap_or_else(|| dest.clone()));
+    let dest = dest_path(args, dest, store_name)?;
+
+    // Step 2: Read the source file
+    debug!("➡️ Step 2: Reading {}", args.source);
+    let mut content = Vec::new();
+    if args.source == "-" {
+        std::io::stdin().read_to_end(&mut content).map_err(|e| TrunkError::Io(format!("Failed to read standard input: {}", e)))?;
+    } else {
+        content = fs::read(&args.source).map_err(|e| TrunkError::Io(format!("Failed to read {}: {}", args.source, e)))?;
+    }
+    info!("✓ Step 2: Read {} byte(s) from {}", content.len(), if args.source == "-" { "standard input" } else { &args.source });
+    let message = args.message.clone().unwrap_or_else(|| format!("Put {}", dest));
+    put_content(&repo, remote_name, store_name, &dest, content, &message, verbose)
+}
+
+// Steps 3-5 of `put`: commits `content` as `dest` (a normalized store path)
+// on top of refs/trunk/<store>, then follows with the checkout and pushes
+// when auto_push is set. Also used by commands that write into a store.
+pub fn put_content(repo: &GitRepo, remote_name: &str, store_name: &str, dest: &str, content: Vec<u8>, message: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+
+    // Step 3: Check refs/trunk/<store> and the checkout, which must not hold
+    // commits or changes the new commit would leave behind
+    debug!("➡️ Step 3: Checking {}", trunk_ref_name);
+    let Some(parent) = repo.resolve_commit(&trunk_ref_name) else {
+        return Err(TrunkError::NotFound(format!(
+            "{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` or `git trunk init --store {}` first.",
+            trunk_ref_name, store_name, store_name, store_name
+        )));
+    };
+    let store_repo = ops::checkout_to_follow(repo, store_name, &parent, &[dest.to_string()], verbose)?;
+    let me = locks::identity(repo, verbose).ok();
+    for lock in locks::read(repo, store_name, verbose).into_iter().filter(|lock| lock.path == dest) {
+        if me.as_ref().is_none_or(|me| me.email != lock.email) {
+            error!("⚠️ Warning: {} is locked by {} since {}; check with them before changing it", lock.path, lock.owner(), lock.since.format("%Y-%m-%d %H:%M"));
+        }
+    }
+    info!("✓ Step 3: {} is at {}", trunk_ref_name, short_hash(repo.root(), &parent, verbose));
+
+    quota::enforce(repo, store_name, content.len() as u64, "Step 3", verbose)?;
+
+    // Step 4: Commit the file on top of refs/trunk/<store>
+    debug!("➡️ Step 4: Committing {} to {}", dest, trunk_ref_name);
+    let Some(commit) = ops::commit_to_store_ref(repo, store_name, &parent, &[(dest.to_string(), Some(content))], message, verbose)? else {
+        info!("✅ {} already holds this version of {}; nothing to commit", trunk_ref_name, dest);
+        return Ok(());
+    };
+    info!("✓ Step 4: Committed {} to {} at {}", dest, trunk_ref_name, short_hash(repo.root(), &commit, verbose));
+
+    // Steps 9-12: Bring an existing checkout along, as `git trunk checkout` would
+    if let Some(store_repo) = &store_repo {
+        checkout::update_checkout(repo, store_repo, store_name, verbose)?;
+    }
+    info!("✅ Put {} into store '{}'", dest, store_name);
+
+    // Step 5: Push the new commit when auto_push is configured, like commit
+    let trunk_config = config::load(repo.root(), verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    if trunk_config.auto_push(store_name) {
+        debug!("📤 Step 5: auto_push is set, pushing store '{}' to '{}'", store_name, remote_name);
+        push::run(&push::PushArgs::default(), remote_name, store_name, verbose)?;
+    }
+    Ok(())
+}
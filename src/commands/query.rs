@@ -0,0 +1,342 @@
+use std::path::Path;
+use clap::Parser;
+use log::{debug, info};
+use regex::Regex;
+use crate::frontmatter::{self, Frontmatter, Value};
+use crate::json::Json;
+use crate::table::{Cell, Table};
+use crate::utils::{list_tree_files, read_blobs};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Query the frontmatter of a store's Markdown documents", after_help = "Examples:\n  git trunk query --where 'status == \"draft\"' --select path,owner\n  git trunk query --rev refs/trunk/main~5 --json")]
+pub struct QueryArgs {
+    #[arg(long = "where", value_name = "EXPR", help = "Filter, e.g. 'status == \"draft\" and priority >= 2' (operators: == != < <= > >= contains =~, and/or/not, parentheses)")]
+    filter: Option<String>,
+    #[arg(long, value_name = "FIELDS", value_delimiter = ',', help = "Comma-separated fields to output (`path` is the document path) [default: path and every field found]")]
+    select: Vec<String>,
+    #[arg(long, default_value = "", hide_default_value = true, help = "Store revision to query [default: refs/trunk/<store>]")]
+    rev: String,
+    #[arg(long, help = "Output a JSON array of objects instead of a table")]
+    json: bool,
+    #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+// Extensions whose documents may carry YAML frontmatter.
+pub const FRONTMATTER_EXTENSIONS: [&str; 3] = ["md", "markdown", "mdx"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(Value),
+    Op(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Field(String),
+    Compare(String, String, Value),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 1;
+                }
+                value.push(chars[i]);
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(format!("unterminated string starting with {}{}", c, value));
+            }
+            i += 1;
+            tokens.push(Token::Literal(Value::String(value)));
+        } else if "=!<>~&|".contains(c) {
+            let two: String = chars[i..].iter().take(2).collect();
+            let op = match two.as_str() {
+                "==" | "!=" | "<=" | ">=" | "=~" | "&&" | "||" => two,
+                _ if c == '<' || c == '>' || c == '!' => c.to_string(),
+                _ => return Err(format!("unknown operator at '{}'", chars[i..].iter().collect::<String>())),
+            };
+            i += op.chars().count();
+            tokens.push(match op.as_str() {
+                "&&" => Token::And,
+                "||" => Token::Or,
+                "!" => Token::Not,
+                _ => Token::Op(op),
+            });
+        } else {
+            let start = i;
+            while i < chars.len() && !chars[i].is_whitespace() && !"()=!<>~&|\"'".contains(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(match word.as_str() {
+                "and" | "AND" => Token::And,
+                "or" | "OR" => Token::Or,
+                "not" | "NOT" => Token::Not,
+                "contains" => Token::Op("contains".to_string()),
+                "true" => Token::Literal(Value::Bool(true)),
+                "false" => Token::Literal(Value::Bool(false)),
+                "null" => Token::Literal(Value::Null),
+                _ => match word.parse::<f64>() {
+                    Ok(number) => Token::Literal(Value::Number(number)),
+                    Err(_) => Token::Ident(word),
+                },
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+struct ExprParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("missing ')'".to_string()),
+                }
+            }
+            Some(Token::Ident(field)) => {
+                let Some(Token::Op(op)) = self.peek().cloned() else {
+                    return Ok(Expr::Field(field));
+                };
+                self.pos += 1;
+                match self.next() {
+                    Some(Token::Literal(value)) => Ok(Expr::Compare(field, op, value)),
+                    Some(Token::Ident(word)) => Ok(Expr::Compare(field, op, Value::String(word))),
+                    _ => Err(format!("expected a value after '{} {}'", field, op)),
+                }
+            }
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn parse_filter(input: &str) -> Result<Expr, String> {
+    let mut parser = ExprParser { tokens: tokenize(input)?, pos: 0 };
+    let expr = parser.or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!("unexpected {:?}", parser.tokens[parser.pos]));
+    }
+    if let Some(pattern) = find_invalid_regex(&expr) {
+        return Err(format!("invalid regular expression '{}'", pattern));
+    }
+    Ok(expr)
+}
+
+fn find_invalid_regex(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Compare(_, op, Value::String(pattern)) if op == "=~" => Regex::new(pattern).is_err().then(|| pattern.clone()),
+        Expr::Not(inner) => find_invalid_regex(inner),
+        Expr::And(a, b) | Expr::Or(a, b) => find_invalid_regex(a).or_else(|| find_invalid_regex(b)),
+        _ => None,
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => Some(*number),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn scalar_matches(actual: &Value, op: &str, expected: &Value) -> bool {
+    if let (Some(a), Some(b)) = (as_number(actual), as_number(expected)) {
+        return match op {
+            "==" => a == b,
+            "!=" => a != b,
+            "<" => a < b,
+            "<=" => a <= b,
+            ">" => a > b,
+            ">=" => a >= b,
+            _ => false,
+        };
+    }
+    let (a, b) = (actual.to_string(), expected.to_string());
+    match op {
+        "==" => actual == expected || (!matches!(expected, Value::Null) && a == b),
+        "!=" => !(actual == expected || (!matches!(expected, Value::Null) && a == b)),
+        "<" => a < b,
+        "<=" => a <= b,
+        ">" => a > b,
+        ">=" => a >= b,
+        "contains" => a.contains(&b),
+        "=~" => Regex::new(&b).is_ok_and(|re| re.is_match(&a)),
+        _ => false,
+    }
+}
+
+pub fn field_value(path: &str, frontmatter: &Frontmatter, field: &str) -> Value {
+    if field == "path" {
+        return Value::String(path.to_string());
+    }
+    frontmatter.get(field).cloned().unwrap_or(Value::Null)
+}
+
+fn evaluate(expr: &Expr, path: &str, frontmatter: &Frontmatter) -> bool {
+    match expr {
+        Expr::Field(field) => match field_value(path, frontmatter, field) {
+            Value::Null | Value::Bool(false) => false,
+            Value::String(text) => !text.is_empty(),
+            Value::List(items) => !items.is_empty(),
+            _ => true,
+        },
+        Expr::Compare(field, op, expected) => match field_value(path, frontmatter, field) {
+            // Lists match when any element does; `!=` when none equals
+            Value::List(items) if op == "contains" => items.iter().any(|item| *item == expected.to_string()),
+            Value::List(items) if op == "!=" => items.iter().all(|item| scalar_matches(&Value::String(item.clone()), op, expected)),
+            Value::List(items) => items.iter().any(|item| scalar_matches(&Value::String(item.clone()), op, expected)),
+            actual => scalar_matches(&actual, op, expected),
+        },
+        Expr::Not(inner) => !evaluate(inner, path, frontmatter),
+        Expr::And(a, b) => evaluate(a, path, frontmatter) && evaluate(b, path, frontmatter),
+        Expr::Or(a, b) => evaluate(a, path, frontmatter) || evaluate(b, path, frontmatter),
+    }
+}
+
+// Reads the frontmatter of every Markdown document at `rev` that has one,
+// sorted by path.
+pub fn load_documents(repo_root: &Path, rev: &str, verbose: bool) -> std::io::Result<Vec<(String, Frontmatter)>> {
+    let mut paths: Vec<String> = list_tree_files(repo_root, rev, verbose)?
+        .into_iter()
+        .filter(|path| Path::new(path).extension().and_then(|ext| ext.to_str()).is_some_and(|ext| FRONTMATTER_EXTENSIONS.contains(&ext.to_lowercase().as_str())))
+        .collect();
+    paths.sort();
+    let objects: Vec<String> = paths.iter().map(|path| format!("{}:{}", rev, path)).collect();
+    let blobs = read_blobs(repo_root, &objects, verbose)?;
+    Ok(paths.into_iter()
+        .zip(blobs)
+        .filter_map(|(path, blob)| {
+            let text = String::from_utf8_lossy(&blob?).to_string();
+            frontmatter::parse(&text).map(|frontmatter| (path, frontmatter))
+        })
+        .collect())
+}
+
+pub fn run(args: &QueryArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Parse the filter
+    let filter = args.filter.as_deref()
+        .map(|input| parse_filter(input).map_err(|e| TrunkError::Usage(format!("Invalid --where expression: {}", e))))
+        .transpose()?;
+
+    // Step 3: Read the documents at the store revision
+    let rev = if args.rev.is_empty() { format!("refs/trunk/{}", store_name) } else { args.rev.clone() };
+    debug!("➡️ Step 3: Reading frontmatter of documents at {}", rev);
+    let documents = load_documents(repo_root, &rev, verbose).map_err(|e| TrunkError::Git(format!("Failed to read store '{}' at {}: {}. Commit the store with `git trunk commit --store {}` first.", store_name, rev, e, store_name)))?;
+    let matches: Vec<&(String, Frontmatter)> = documents.iter()
+        .filter(|(path, frontmatter)| filter.as_ref().is_none_or(|expr| evaluate(expr, path, frontmatter)))
+        .collect();
+    debug!("✓ Step 3: {} of {} document(s) match", matches.len(), documents.len());
+
+    // Step 4: Output
+    let columns: Vec<String> = if args.select.is_empty() {
+        let mut columns = vec!["path".to_string()];
+        for (_, frontmatter) in &matches {
+            for (key, _) in &frontmatter.fields {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+        columns
+    } else {
+        args.select.iter().map(|field| field.trim().to_string()).filter(|field| !field.is_empty()).collect()
+    };
+
+    if args.json {
+        let rows: Vec<Json> = matches.iter()
+            .map(|(path, frontmatter)| Json::Object(columns.iter().map(|column| (column.clone(), field_value(path, frontmatter, column).to_json())).collect()))
+            .collect();
+        println!("{}", Json::Array(rows));
+        return Ok(());
+    }
+    if matches.is_empty() {
+        info!("= No documents in store '{}' match", store_name);
+        return Ok(());
+    }
+    let headers: Vec<&str> = columns.iter().map(String::as_str).collect();
+    let mut table = Table::new(&headers);
+    for (path, frontmatter) in &matches {
+        table.add_row(columns.iter().map(|column| Cell::from(field_value(path, frontmatter, column).to_string())).collect());
+    }
+    table.print(args.no_table);
+    Ok(())
+}
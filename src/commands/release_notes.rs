@@ -0,0 +1,176 @@
+use std::fs;
+use std::process::Command;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::config;
+use crate::commands::commit::{self, CommitArgs};
+use crate::utils::{is_dry_run, run_git_command};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Assemble news fragments from a store into a changelog section, commit it and remove the fragments", after_help = "Examples:\n  git trunk release-notes --version 1.4.0 --dry-run\n  git trunk release-notes --version 1.4.0", disable_version_flag = true)]
+pub struct ReleaseNotesArgs {
+    #[arg(long, help = "Version the section is written for (e.g. 1.4.0)")]
+    version: String,
+    #[arg(long, default_value = "changes", help = "Directory inside the store holding the fragments")]
+    fragments: String,
+    #[arg(long, default_value = "CHANGELOG.md", help = "Changelog file inside the store that receives the section")]
+    output: String,
+    #[arg(long, help = "Release date [default: today]")]
+    date: Option<String>,
+}
+
+// Fragment types (`<name>.<type>.md`) and their headings, in output order.
+// Fragments with any other type are listed under "Other".
+const FRAGMENT_TYPES: [(&str, &str); 6] = [
+    ("feature", "Features"),
+    ("bugfix", "Bug Fixes"),
+    ("removal", "Removals and Deprecations"),
+    ("doc", "Documentation"),
+    ("security", "Security"),
+    ("misc", "Other"),
+];
+
+#[derive(Debug)]
+struct Fragment {
+    file_name: String,
+    kind: String,
+    id: String,
+    text: String,
+}
+
+// `123.bugfix.md` → id "123", type "bugfix"; `tidy-up.md` → type "misc".
+fn parse_fragment_name(file_name: &str) -> (String, String) {
+    let stem = file_name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(file_name);
+    match stem.rsplit_once('.') {
+        Some((id, kind)) => (id.to_string(), kind.to_lowercase()),
+        None => (stem.to_string(), "misc".to_string()),
+    }
+}
+
+fn heading_for(kind: &str) -> &'static str {
+    FRAGMENT_TYPES.iter().find(|(name, _)| *name == kind).map(|(_, heading)| *heading).unwrap_or("Other")
+}
+
+fn render_section(version: &str, date: &str, fragments: &[Fragment]) -> String {
+    let mut section = format!("## {} ({})\n", version, date);
+    for (_, heading) in FRAGMENT_TYPES {
+        let items: Vec<&Fragment> = fragments.iter().filter(|fragment| heading_for(&fragment.kind) == heading).collect();
+        if items.is_empty() {
+            continue;
+        }
+        section.push_str(&format!("\n### {}\n\n", heading));
+        for fragment in items {
+            let mut lines = fragment.text.lines();
+            let first = lines.next().unwrap_or_default();
+            let reference = if !fragment.id.is_empty() && fragment.id.chars().all(|c| c.is_ascii_digit()) { format!(" (#{})", fragment.id) } else { String::new() };
+            section.push_str(&format!("- {}{}\n", first, reference));
+            for line in lines {
+                if line.trim().is_empty() {
+                    section.push('\n');
+                } else {
+                    section.push_str(&format!("  {}\n", line));
+                }
+            }
+        }
+    }
+    section
+}
+
+// Inserts the section above the newest entry: after a leading `# ` title and
+// its introduction, before the first `## ` heading.
+fn insert_section(changelog: &str, section: &str) -> String {
+    if changelog.trim().is_empty() {
+        return format!("# Changelog\n\n{}", section);
+    }
+    let mut offset = 0;
+    for line in changelog.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            return format!("{}{}\n{}", &changelog[..offset], section, &changelog[offset..]);
+        }
+        offset += line.len();
+    }
+    let separator = if changelog.ends_with("\n\n") { "" } else if changelog.ends_with('\n') { "\n" } else { "\n\n" };
+    format!("{}{}{}", changelog, separator, section)
+}
+
+pub fn run(args: &ReleaseNotesArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Collect the fragments
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    let fragments_dir_name = args.fragments.trim_end_matches('/');
+    let fragments_dir = trunk_store_dir.join(fragments_dir_name);
+    debug!("➡️ Step 2: Reading fragments from {}/{}", store_dir_relative_path, fragments_dir_name);
+    if !trunk_store_dir.join(".git").exists() {
+        return Err(TrunkError::NotFound(format!("{} is not checked out. Run `git trunk checkout --store {}` first.", store_dir_relative_path, store_name)));
+    }
+    let mut file_names: Vec<String> = fs::read_dir(&fragments_dir)
+        .map(|entries| entries.filter_map(|entry| entry.ok()).filter(|entry| entry.path().is_file()).map(|entry| entry.file_name().to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+    file_names.retain(|name| !name.starts_with('.') && !name.to_lowercase().starts_with("readme"));
+    file_names.sort();
+    let mut fragments: Vec<Fragment> = Vec::new();
+    for file_name in file_names {
+        let text = fs::read_to_string(fragments_dir.join(&file_name)).map_err(|e| TrunkError::Io(format!("Failed to read fragment {}: {}", file_name, e)))?;
+        if text.trim().is_empty() {
+            error!("⚠️ Warning: Skipping empty fragment {}", file_name);
+            continue;
+        }
+        let (id, kind) = parse_fragment_name(&file_name);
+        if heading_for(&kind) == "Other" && kind != "misc" {
+            debug!("Fragment {} has unknown type '{}'; listing it under Other", file_name, kind);
+        }
+        fragments.push(Fragment { file_name, kind, id, text: text.trim().to_string() });
+    }
+    if fragments.is_empty() {
+        return Err(TrunkError::Failed(format!("No fragments in {}/{}. Add files such as {}/123.feature.md first.", store_dir_relative_path, fragments_dir_name, fragments_dir_name)));
+    }
+    info!("✓ Step 2: Found {} fragment(s)", fragments.len());
+
+    // Step 3: Assemble the section
+    let date = args.date.clone().unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    let section = render_section(&args.version, &date, &fragments);
+    // The global --dry-run only prints the section
+    if is_dry_run() {
+        print!("{}", section);
+        info!("= Step 3: Dry run; nothing written");
+        return Ok(());
+    }
+    let status_output = run_git_command(Command::new("git").arg("status").arg("--porcelain").arg("-z").current_dir(&trunk_store_dir), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to run git status in {}: {}", store_dir_relative_path, e)))?;
+    let fragments_prefix = format!("{}/", fragments_dir_name);
+    let unrelated: Vec<String> = status_output.stdout
+        .split(|b| *b == 0)
+        .filter(|entry| entry.len() > 3)
+        .map(|entry| String::from_utf8_lossy(&entry[3..]).to_string())
+        .filter(|path| !path.starts_with(&fragments_prefix) && *path != fragments_dir_name)
+        .collect();
+    if !unrelated.is_empty() {
+        return Err(TrunkError::Conflict(format!("{} has uncommitted changes outside {}/ ({}). Commit them with `git trunk commit --store {}` first.", store_dir_relative_path, fragments_dir_name, unrelated.join(", "), store_name)));
+    }
+    let changelog_path = trunk_store_dir.join(&args.output);
+    let changelog = fs::read_to_string(&changelog_path).unwrap_or_default();
+    if changelog.lines().any(|line| line.starts_with(&format!("## {} ", args.version)) || line == format!("## {}", args.version)) {
+        return Err(TrunkError::Conflict(format!("{} already has a section for {}", args.output, args.version)));
+    }
+    fs::write(&changelog_path, insert_section(&changelog, &section)).map_err(|e| TrunkError::Io(format!("Failed to write {}/{}: {}", store_dir_relative_path, args.output, e)))?;
+    info!("✓ Step 3: Added the {} section to {}/{}", args.version, store_dir_relative_path, args.output);
+
+    // Step 4: Remove the fragments
+    debug!("➡️ Step 4: Removing {} fragment(s)", fragments.len());
+    for fragment in &fragments {
+        fs::remove_file(fragments_dir.join(&fragment.file_name)).map_err(|e| TrunkError::Io(format!("Failed to remove fragment {}: {}", fragment.file_name, e)))?;
+    }
+    info!("✓ Step 4: Removed {} fragment(s)", fragments.len());
+
+    // Step 5: Commit the store
+    let message = format!("Release notes for {}", args.version);
+    commit::run(&CommitArgs { force: true, message: Some(message), ..Default::default() }, remote_name, store_name, verbose)
+}
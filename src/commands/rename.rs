@@ -0,0 +1,171 @@
+use std::fs;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::commands::hooks;
+use crate::config::{self, ConfigValue};
+use crate::locks;
+use crate::ops;
+use crate::utils::{confirm, run_git_command, short_hash};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Rename a store: its ref locally and on the remote, its checkout, config and hooks",
+    after_help = "Teammates' clones keep the old name until they run `git trunk fetch` and `git trunk checkout --store <new>`; their .trunk/<old> can then be deleted with `git trunk delete --store <old> --local-only`.\n\nExamples:\n  git trunk rename notes handbook\n  git trunk rename scratch drafts --local-only"
+)]
+pub struct RenameArgs {
+    #[arg(value_name = "OLD", help = "Current name of the store")]
+    old: String,
+    #[arg(value_name = "NEW", help = "New name of the store")]
+    new: String,
+    #[arg(long, help = "Only rename the store in this repository, leaving the remote's refs alone")]
+    local_only: bool,
+}
+
+pub fn run(args: &RenameArgs, remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let (old, new) = (args.old.as_str(), args.new.as_str());
+    let old_ref = format!("refs/trunk/{}", old);
+    let new_ref = format!("refs/trunk/{}", new);
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+    let trunk_base_dir = config::trunk_base_dir(repo_root, verbose);
+    let (old_dir, new_dir) = (trunk_base_dir.join(old), trunk_base_dir.join(new));
+
+    // Step 2: Check both names, the remote and the locks before changing anything
+    debug!("➡️ Step 2: Checking that store '{}' can become '{}'", old, new);
+    config::check_store_name(new).map_err(|reason| TrunkError::Usage(format!("Invalid store name '{}': {}", new, reason)))?;
+    if old == new {
+        return Err(TrunkError::Usage(format!("Store '{}' already has that name", old)));
+    }
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let old_section = format!("store.{}", old);
+    let new_section = format!("store.{}", new);
+    let Some(tip) = repo.resolve_commit(&old_ref) else {
+        return Err(TrunkError::NotFound(format!(
+            "{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.",
+            old_ref, old, old
+        )));
+    };
+    if repo.ref_exists(&new_ref) || new_dir.exists() || trunk_config.section_line(&new_section).is_some() {
+        return Err(TrunkError::Conflict(format!("Store '{}' already exists here ({}, .trunk/{} or [{}]); pick another name", new, new_ref, new, new_section)));
+    }
+    let remote_tip = if args.local_only {
+        None
+    } else {
+        let unreachable = |e: TrunkError| TrunkError::Git(format!("{}. Rename with --local-only to leave the remote alone.", e));
+        if ops::remote_store_tip(&repo, remote_name, new, verbose).map_err(unreachable)?.is_some() {
+            return Err(TrunkError::Conflict(format!("Remote '{}' already has {}; pick another name", remote_name, new_ref)));
+        }
+        let remote_tip = ops::remote_store_tip(&repo, remote_name, old, verbose).map_err(unreachable)?;
+        if let Some(remote_tip) = &remote_tip {
+            if repo.resolve_commit(remote_tip).is_none() || !repo.is_ancestor(remote_tip, &tip) {
+                return Err(TrunkError::Conflict(format!(
+                    "Remote '{}' has commits of store '{}' that are not here. Run `git trunk pull --store {}` first.",
+                    remote_name, old, old
+                )));
+            }
+        }
+        locks::refresh(&repo, remote_name, old, verbose)?;
+        remote_tip
+    };
+    let held = locks::read(&repo, old, verbose);
+    if !held.is_empty() {
+        let paths: Vec<&str> = held.iter().map(|lock| lock.path.as_str()).collect();
+        return Err(TrunkError::Conflict(format!(
+            "Store '{}' has locked paths ({}); unlock them before renaming it, then lock them again under the new name",
+            old,
+            paths.join(", ")
+        )));
+    }
+    info!("✓ Step 2: Store '{}' can be renamed to '{}'", old, new);
+
+    // Step 3: Confirm
+    let remote_part = match (&remote_tip, args.local_only) {
+        (_, true) => format!(" (the remote '{}' is left alone)", remote_name),
+        (Some(_), false) => format!(", and {} on remote '{}' to {}", old_ref, remote_name, new_ref),
+        (None, false) => format!(", and push {} to remote '{}'", new_ref, remote_name),
+    };
+    if !confirm(&format!("This will rename {} to {}, .trunk/{} to .trunk/{} and [{}] to [{}]{}. Continue?", old_ref, new_ref, old, new, old_section, new_section, remote_part))? {
+        info!("🚫 Rename of store '{}' aborted by user", old);
+        return Ok(());
+    }
+
+    // Step 4: Rename on the remote first, in one atomic push, so a refused
+    // push leaves everything as it was
+    if !args.local_only {
+        debug!("📤 Step 4: Pushing {} and removing {} on remote '{}'", new_ref, old_ref, remote_name);
+        let mut command = repo.git();
+        command.arg("push").arg("--atomic").arg(remote_name).arg(format!("{}:{}", tip, new_ref));
+        if let Some(remote_tip) = &remote_tip {
+            command.arg(format!("--force-with-lease={}:{}", old_ref, remote_tip)).arg(format!(":{}", old_ref));
+        }
+        let output = run_git_command(&mut command, verbose).map_err(|e| TrunkError::Git(format!("Failed to push to remote '{}': {}", remote_name, e)))?;
+        if !output.status.success() {
+            return Err(TrunkError::Git(format!(
+                "Remote '{}' refused the rename: {}. Nothing was changed.",
+                remote_name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        ops::forget_remote_listing(&repo, remote_name);
+        info!("✓ Step 4: Remote '{}' has {} at {}", remote_name, new_ref, short_hash(repo_root, &tip, verbose));
+    } else {
+        info!("= Step 4: --local-only, leaving remote '{}' alone", remote_name);
+    }
+
+    // Step 5: Move the local ref
+    debug!("➡️ Step 5: Moving {} to {}", old_ref, new_ref);
+    repo.update_ref(&new_ref, &tip)
+        .and_then(|_| repo.delete_ref(&old_ref))
+        .map_err(|e| TrunkError::Git(format!("Failed to move {} to {}: {}", old_ref, new_ref, e)))?;
+    // The remote renamed in Step 4 now has the store under the new name; the
+    // old name's copies of other remotes would list it as a store of its own,
+    // and `fetch` brings them back under whatever name those remotes use
+    for mirrored in repo.ref_names("refs/trunk-remotes/").unwrap_or_default() {
+        let Some(mirror_remote) = mirrored.strip_suffix(old).and_then(|rest| rest.strip_suffix('/')).filter(|rest| !rest.is_empty()) else { continue };
+        let old_mirror_ref = format!("refs/trunk-remotes/{}", mirrored);
+        let moved = if mirror_remote == remote_name && !args.local_only {
+            repo.update_ref(&format!("refs/trunk-remotes/{}/{}", remote_name, new), &tip).and_then(|_| repo.delete_ref(&old_mirror_ref))
+        } else {
+            repo.delete_ref(&old_mirror_ref)
+        };
+        if let Err(e) = moved {
+            error!("⚠️ Warning: Failed to remove {}: {}. Delete it with `git update-ref -d {}`.", old_mirror_ref, e, old_mirror_ref);
+        }
+    }
+    info!("✓ Step 5: Moved {} to {}", old_ref, new_ref);
+
+    // Step 6: Move the checkout
+    if old_dir.exists() {
+        debug!("➡️ Step 6: Moving .trunk/{} to .trunk/{}", old, new);
+        fs::rename(&old_dir, &new_dir).map_err(|e| TrunkError::Io(format!("Failed to move {} to {}: {}", old_dir.display(), new_dir.display(), e)))?;
+        ops::forget_checkout(repo_root, old, verbose);
+        ops::record_checkout(repo_root, new, verbose);
+        info!("✓ Step 6: Moved .trunk/{} to .trunk/{}", old, new);
+    } else {
+        info!("= Step 6: Store '{}' is not checked out", old);
+    }
+
+    // Step 7: Rename the config section, the default store and legacy hooks
+    debug!("➡️ Step 7: Updating the config and hooks");
+    config::rename_section(&trunk_config, &old_section, &new_section).map_err(|e| TrunkError::Io(format!("Failed to update config: {}", e)))?;
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    if trunk_config.section_line(&new_section).is_some() {
+        info!("✓ Step 7: [{}] is now [{}]", old_section, new_section);
+    }
+    if matches!(trunk_config.get_local("trunk", "store").map(|entry| &entry.value), Some(ConfigValue::String(store)) if store == old) {
+        config::set_value(&trunk_config, "trunk", "store", Some(&ConfigValue::String(new.to_string())))
+            .map_err(|e| TrunkError::Io(format!("Failed to update config: {}", e)))?;
+        info!("✓ Step 7: [trunk] store is now '{}'", new);
+    }
+    match hooks::rename_store_in_hooks(repo_root, old, new, verbose) {
+        Ok(renamed) if !renamed.is_empty() => info!("✓ Step 7: Updated the {} hook(s)", renamed.join(", ")),
+        Ok(_) => {}
+        Err(e) => error!("⚠️ Warning: Failed to update hooks naming store '{}': {}. Run `git trunk hooks install --store {} --force`.", old, e, new),
+    }
+
+    info!("✅ Renamed store '{}' to '{}'", old, new);
+    Ok(())
+}
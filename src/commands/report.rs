@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Duration, Local};
+use clap::Parser;
+use log::{debug, info};
+use crate::commands::diff::html_escape;
+use crate::commands::lint_prose::PROSE_EXTENSIONS;
+use crate::config;
+use crate::ops;
+use crate::quota;
+use crate::stores::StoreInfo;
+use crate::utils::{list_tree_files, run_git_command};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Write a static HTML dashboard of every store", after_help = "Examples:\n  git trunk report --output report.html\n  git trunk report --output public/index.html --stale-days 90\n  git trunk report --output - --local")]
+pub struct ReportArgs {
+    #[arg(long, value_name = "FILE", help = "Write the HTML report to FILE (`-` for stdout)")]
+    output: String,
+    #[arg(long, help = "Skip the remote checks and work offline")]
+    local: bool,
+    #[arg(long, value_name = "DAYS", default_value_t = 180, help = "List documents unchanged for this many days as stale")]
+    stale_days: i64,
+    #[arg(long, value_name = "MONTHS", default_value_t = 6, help = "Months of store size history to chart")]
+    trend_months: i64,
+}
+
+// Authors shown per store, most recent first.
+const MAX_AUTHORS: usize = 3;
+
+// One commit of a store's history.
+pub struct HistoryCommit {
+    pub hash: String,
+    pub time: DateTime<Local>,
+    pub author: String,
+    pub paths: Vec<String>,
+}
+
+struct StaleDocument {
+    path: String,
+    changed: DateTime<Local>,
+    author: String,
+}
+
+struct StoreReport {
+    info: StoreInfo,
+    authors: Vec<String>,
+    trend: Vec<(DateTime<Local>, u64)>,
+    documents: usize,
+    stale: Vec<StaleDocument>,
+}
+
+// Every commit reachable from `rev`, newest first, with the paths it changed.
+pub fn history(repo: &GitRepo, rev: &str, verbose: bool) -> Vec<HistoryCommit> {
+    let mut commits: Vec<HistoryCommit> = Vec::new();
+    let Ok(output) = run_git_command(repo.git().arg("log").arg("--format=%x01%H%x1f%ct%x1f%an").arg("--name-only").arg("-z").arg(rev).arg("--"), verbose) else {
+        return commits;
+    };
+    for field in String::from_utf8_lossy(&output.stdout).split('\0') {
+        let field = field.trim_matches('\n');
+        if field.is_empty() {
+            continue;
+        }
+        if let Some(header) = field.strip_prefix('\u{1}') {
+            let mut parts = header.splitn(3, '\u{1f}');
+            let (Some(hash), Some(time), Some(author)) = (parts.next(), parts.next(), parts.next()) else { continue };
+            let Some(time) = time.parse().ok().and_then(|secs| DateTime::from_timestamp(secs, 0)) else { continue };
+            commits.push(HistoryCommit { hash: hash.to_string(), time: time.with_timezone(&Local), author: author.to_string(), paths: Vec::new() });
+        } else if let Some(commit) = commits.last_mut() {
+            commit.paths.push(field.to_string());
+        }
+    }
+    commits
+}
+
+pub fn is_document(path: &str) -> bool {
+    Path::new(path).extension().and_then(|ext| ext.to_str()).is_some_and(|ext| PROSE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+// History, authors, size trend and stale documents of one store's ref.
+fn store_report(repo: &GitRepo, info: StoreInfo, args: &ReportArgs, now: DateTime<Local>, verbose: bool) -> StoreReport {
+    let mut report = StoreReport { info, authors: Vec::new(), trend: Vec::new(), documents: 0, stale: Vec::new() };
+    if !report.info.main_repo_ref_exists {
+        return report;
+    }
+    let rev = report.info.main_repo_ref.clone();
+    let commits = history(repo, &rev, verbose);
+
+    for commit in &commits {
+        if report.authors.len() == MAX_AUTHORS {
+            break;
+        }
+        if !report.authors.contains(&commit.author) {
+            report.authors.push(commit.author.clone());
+        }
+    }
+
+    // The store's size at the end of each of the last months, oldest first;
+    // `git log` lists the newest commit first
+    let mut sizes: HashMap<&str, u64> = HashMap::new();
+    for months_ago in (0..args.trend_months.max(1)).rev() {
+        let at = now - Duration::days(30 * months_ago);
+        let Some(commit) = commits.iter().find(|commit| commit.time <= at) else { continue };
+        let size = match sizes.get(commit.hash.as_str()) {
+            Some(size) => *size,
+            None => match quota::reachable_size(repo.root(), &commit.hash, verbose) {
+                Ok(size) => *sizes.entry(&commit.hash).or_insert(size),
+                Err(e) => {
+                    debug!("⚠️ Failed to measure {} at {}: {}", rev, commit.hash, e);
+                    continue;
+                }
+            },
+        };
+        report.trend.push((at, size));
+    }
+
+    // Documents nobody has touched for --stale-days, oldest first
+    let mut last_changes: HashMap<&str, &HistoryCommit> = HashMap::new();
+    for commit in &commits {
+        for path in &commit.paths {
+            last_changes.entry(path.as_str()).or_insert(commit);
+        }
+    }
+    let documents: Vec<String> = list_tree_files(repo.root(), &rev, verbose).unwrap_or_default().into_iter().filter(|path| is_document(path)).collect();
+    report.documents = documents.len();
+    let stale_before = now - Duration::days(args.stale_days);
+    for path in documents {
+        if let Some(commit) = last_changes.get(path.as_str()).filter(|commit| commit.time <= stale_before) {
+            report.stale.push(StaleDocument { changed: commit.time, author: commit.author.clone(), path });
+        }
+    }
+    report.stale.sort_by(|a, b| a.changed.cmp(&b.changed).then_with(|| a.path.cmp(&b.path)));
+    report
+}
+
+// Cell text and CSS class of the checkout column.
+fn checkout_cell(info: &StoreInfo) -> (String, &'static str) {
+    if !info.is_git_repo {
+        return ("not checked out".to_string(), "muted");
+    }
+    let mut facts = Vec::new();
+    let mut class = "ok";
+    match info.local_store_uncommitted_count {
+        Some(0) => facts.push("clean".to_string()),
+        Some(count) => {
+            facts.push(format!("{} uncommitted", count));
+            class = "warn";
+        }
+        None => facts.push("status unknown".to_string()),
+    }
+    if let Some((ahead, behind)) = info.checkout_ahead_behind.filter(|(ahead, behind)| ahead + behind > 0) {
+        facts.push(format!("{} ahead, {} behind the ref", ahead, behind));
+        class = "warn";
+    }
+    (facts.join(", "), class)
+}
+
+// Cell text and CSS class of the remote column.
+fn remote_cell(info: &StoreInfo, check_remote: bool) -> (String, &'static str) {
+    let (text, class) = match info.remote_repo_ref_exists {
+        None if !check_remote => ("not checked".to_string(), "muted"),
+        None => ("unreachable".to_string(), "bad"),
+        Some(false) if info.main_repo_ref_exists => ("not pushed".to_string(), "warn"),
+        Some(false) => ("missing".to_string(), "muted"),
+        Some(true) if info.remote_repo_ref_commit_hash == info.main_repo_ref_commit_hash => ("in sync".to_string(), "ok"),
+        Some(true) => match info.remote_ahead_behind {
+            Some((ahead, 0)) => (format!("{} to push", ahead), "warn"),
+            Some((0, behind)) => (format!("{} to pull", behind), "warn"),
+            Some((ahead, behind)) => (format!("diverged ({} local, {} remote)", ahead, behind), "bad"),
+            None => ("differs".to_string(), "warn"),
+        },
+    };
+    (format!("{}: {}", info.remote_name, text), class)
+}
+
+// Cell text and CSS class of the size column, with the quota if one is set.
+fn size_cell(info: &StoreInfo) -> (String, &'static str) {
+    match (info.main_repo_ref_size, info.main_repo_ref_quota) {
+        (Some(size), Some(max)) => {
+            let percent = size as f64 * 100.0 / max.max(1) as f64;
+            let class = if size > max { "bad" } else if percent >= 80.0 { "warn" } else { "ok" };
+            (format!("{} ({:.0}% of {})", quota::format_size(size), percent, quota::format_size(max)), class)
+        }
+        (Some(size), None) => (quota::format_size(size), ""),
+        (None, _) => ("-".to_string(), "muted"),
+    }
+}
+
+// An inline SVG line of the size trend, hover showing each point.
+fn sparkline(trend: &[(DateTime<Local>, u64)]) -> String {
+    const WIDTH: f64 = 120.0;
+    const HEIGHT: f64 = 24.0;
+    if trend.len() < 2 {
+        return "-".to_string();
+    }
+    let min = trend.iter().map(|(_, size)| *size).min().unwrap_or(0);
+    let max = trend.iter().map(|(_, size)| *size).max().unwrap_or(0);
+    let span = (max - min).max(1) as f64;
+    let step = WIDTH / (trend.len() - 1) as f64;
+    let points: Vec<String> = trend
+        .iter()
+        .enumerate()
+        .map(|(index, (_, size))| format!("{:.1},{:.1}", index as f64 * step, HEIGHT - 2.0 - (*size - min) as f64 / span * (HEIGHT - 4.0)))
+        .collect();
+    let tooltip: Vec<String> = trend.iter().map(|(at, size)| format!("{}: {}", at.format("%Y-%m-%d"), quota::format_size(*size))).collect();
+    format!(
+        "<svg width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\"><title>{}</title><polyline points=\"{}\"/></svg>",
+        WIDTH, HEIGHT, WIDTH, HEIGHT, html_escape(&tooltip.join("\n")), points.join(" ")
+    )
+}
+
+fn render_html(title: &str, reports: &[StoreReport], args: &ReportArgs, now: DateTime<Local>) -> String {
+    let mut body = String::new();
+    if reports.is_empty() {
+        body.push_str("<p>No stores.</p>\n");
+    } else {
+        body.push_str("<table>\n<tr><th>Store</th><th>Checkout</th><th>Remote</th><th>Last commit</th><th>Last authors</th><th>Size</th><th>Size trend</th><th>Stale documents</th></tr>\n");
+        for report in reports {
+            let info = &report.info;
+            let (checkout, checkout_class) = checkout_cell(info);
+            let (remote, remote_class) = remote_cell(info, !args.local);
+            let (size, size_class) = size_cell(info);
+            let last_commit = match (&info.main_repo_ref_commit_date, &info.main_repo_ref_commit_hash) {
+                (Some(date), Some(hash)) => format!("{} <code>{}</code>", date.format("%Y-%m-%d"), html_escape(&hash[..hash.len().min(7)])),
+                _ => "no commits".to_string(),
+            };
+            let stale = if report.stale.is_empty() {
+                format!("<td class=\"ok\">0 of {}</td>", report.documents)
+            } else {
+                format!("<td class=\"warn\"><a href=\"#stale-{}\">{} of {}</a></td>", html_escape(&info.name), report.stale.len(), report.documents)
+            };
+            body.push_str(&format!(
+                "<tr><td><strong>{}</strong></td><td class=\"{}\">{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td>{}</tr>\n",
+                html_escape(&info.name),
+                checkout_class, html_escape(&checkout),
+                remote_class, html_escape(&remote),
+                last_commit,
+                html_escape(&report.authors.join(", ")),
+                size_class, html_escape(&size),
+                sparkline(&report.trend),
+                stale,
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+    for report in reports.iter().filter(|report| !report.stale.is_empty()) {
+        body.push_str(&format!("<h2 id=\"stale-{}\">Stale documents in '{}'</h2>\n<table>\n<tr><th>Document</th><th>Last changed</th><th>By</th></tr>\n", html_escape(&report.info.name), html_escape(&report.info.name)));
+        for document in &report.stale {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{} ({} days ago)</td><td>{}</td></tr>\n",
+                html_escape(&document.path),
+                document.changed.format("%Y-%m-%d"),
+                (now - document.changed).num_days(),
+                html_escape(&document.author),
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+    format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; margin-bottom: 2em; }}
+td, th {{ border: 1px solid #ddd; padding: 4px 8px; text-align: left; vertical-align: middle; }}
+th {{ background: #f6f8fa; }}
+td.ok {{ color: #1a7f37; }}
+td.warn {{ background: #fffbe6; }}
+td.bad {{ background: #ffebe9; color: #a40e26; }}
+td.muted {{ color: #888; }}
+polyline {{ fill: none; stroke: #0969da; stroke-width: 1.5; }}
+p.generated {{ color: #888; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="generated">Generated {generated}; documents unchanged for {stale_days} days are stale.</p>
+{body}</body>
+</html>
+"#, title = html_escape(title), generated = now.format("%Y-%m-%d %H:%M %Z"), stale_days = args.stale_days, body = body)
+}
+
+pub fn run(args: &ReportArgs, remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    // Step 2: Gather every store, each against its configured remote
+    let stores = ops::local_stores(&repo, verbose);
+    debug!("➡️ Step 2: Gathering {} store(s)", stores.len());
+    let trunk_config = config::load(repo_root, verbose).ok();
+    let now = Local::now();
+    let mut reports = Vec::new();
+    for store in &stores {
+        debug!("➡️ Step 2: Gathering store '{}'", store);
+        let store_remote = trunk_config.as_ref().map(|c| c.remote_for_store(store)).unwrap_or_else(|| remote_name.to_string());
+        let store_info = StoreInfo::gather(&repo, trunk_config.as_ref(), store, &store_remote, !args.local, true, verbose);
+        reports.push(store_report(&repo, store_info, args, now, verbose));
+    }
+    info!("✓ Step 2: Gathered {} store(s)", reports.len());
+
+    // Step 3: Render and write the report
+    let repo_name = repo_root.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| repo_root.display().to_string());
+    let html = render_html(&format!("git-trunk report: {}", repo_name), &reports, args, now);
+    if args.output == "-" {
+        print!("{}", html);
+        return Ok(());
+    }
+    fs::write(&args.output, html).map_err(|e| TrunkError::Io(format!("Failed to write {}: {}", args.output, e)))?;
+    let stale: usize = reports.iter().map(|report| report.stale.len()).sum();
+    info!("✅ Wrote report of {} store(s) with {} stale document(s) to {}", reports.len(), stale, args.output);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use chrono::Utc;
+use clap::Parser;
+use log::{debug, info};
+use crate::ops;
+use crate::utils::{confirm, run_git_command, short_hash};
+use crate::error::TrunkError;
+
+// Where `reset` keeps the commit it moved refs/trunk/<store> away from:
+// refs/trunk-backup/<store>-<UTC time>, local only.
+pub const BACKUP_PREFIX: &str = "refs/trunk-backup/";
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Move refs/trunk/<store> back to an earlier commit, keeping the commits it drops in a backup ref",
+    after_help = "Always asks first. The checkout follows the ref, and the remote keeps the dropped commits until `git trunk push --force-with-lease`.\n\nExamples:\n  git trunk reset --to refs/trunk/handbook~2 --store handbook\n  git trunk reset --to 1a2b3c4\n  git trunk reset --to refs/trunk-backup/handbook-20260102T030405Z --store handbook"
+)]
+pub struct ResetArgs {
+    #[arg(long, value_name = "COMMIT", help = "Commit to move refs/trunk/<store> to: one of its history, or a backup ref to undo a reset")]
+    to: String,
+}
+
+pub fn run(args: &ResetArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+
+    // Step 2: Resolve the target, which has to share the store's history
+    debug!("➡️ Step 2: Resolving {}", args.to);
+    let Some(tip) = repo.resolve_commit(&trunk_ref_name) else {
+        return Err(TrunkError::NotFound(format!(
+            "{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.",
+            trunk_ref_name, store_name, store_name
+        )));
+    };
+    let target = repo.resolve_commit(&args.to).ok_or_else(|| TrunkError::NotFound(format!("'{}' is not a commit in this repository", args.to)))?;
+    let short = short_hash(repo.root(), &target, verbose);
+    if target == tip {
+        info!("✅ {} is already at {}", trunk_ref_name, short);
+        return Ok(());
+    }
+    if !repo.is_ancestor(&target, &tip) && !repo.is_ancestor(&tip, &target) {
+        return Err(TrunkError::Usage(format!("{} is not in the history of {}; reset only moves the store along its own history", short, trunk_ref_name)));
+    }
+    // The checkout follows the ref, so it must not hold work of its own
+    let store_repo = ops::checkout_to_follow(&repo, store_name, &tip, &[], verbose)?;
+    if let Some(store_repo) = &store_repo {
+        let status = store_repo.status_porcelain().map_err(|e| TrunkError::Git(format!("Failed to read the status of {}: {}", store_dir_relative_path, e)))?;
+        if !status.is_empty() {
+            return Err(TrunkError::Conflict(format!("{} has uncommitted changes. Commit or discard them first.", store_dir_relative_path)));
+        }
+    }
+    let dropped = run_git_command(repo.git().arg("log").arg("--format=%h %s").arg(&tip).arg("--not").arg(&target).arg("--"), verbose)
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect::<Vec<_>>())
+        .unwrap_or_default();
+    info!("✓ Step 2: Moving {} to {}", trunk_ref_name, short);
+    for commit in &dropped {
+        info!("   dropping {}", commit);
+    }
+
+    // Step 3: Ask, always: the dropped commits leave the store's history
+    let backup_ref = format!("{}{}-{}", BACKUP_PREFIX, store_name, Utc::now().format("%Y%m%dT%H%M%SZ"));
+    let prompt = if dropped.is_empty() {
+        format!("This will move {} forward to {}. Continue?", trunk_ref_name, short)
+    } else {
+        format!("This will move {} back to {}, dropping {} commit(s) (kept in {}). Continue?", trunk_ref_name, short, dropped.len(), backup_ref)
+    };
+    if !confirm(&prompt)? {
+        info!("🚫 Reset of store '{}' aborted by user", store_name);
+        return Ok(());
+    }
+
+    // Step 4: Keep the current commit in a backup ref, then move the store ref
+    debug!("➡️ Step 4: Saving {} as {}", trunk_ref_name, backup_ref);
+    repo.update_ref(&backup_ref, &tip).map_err(|e| TrunkError::Git(format!("Failed to create {}: {}", backup_ref, e)))?;
+    ops::move_store_ref(&repo, store_name, &tip, &target, &format!("reset to {}", short), verbose)?;
+    info!("✓ Step 4: {} is at {}; the previous commit is kept in {}", trunk_ref_name, short, backup_ref);
+
+    // Step 5: Move the checkout along
+    if let Some(store_repo) = &store_repo {
+        let temp_store_ref = "refs/temp/trunk_reset";
+        debug!("🔄 Step 5: Resetting {} to {}", store_dir_relative_path, short);
+        store_repo
+            .fetch_local(repo.root(), &format!("+{}:{}", trunk_ref_name, temp_store_ref))
+            .map_err(|e| TrunkError::Git(format!("Failed to fetch {} into {}: {}", trunk_ref_name, store_dir_relative_path, e)))?;
+        let output = run_git_command(store_repo.git().arg("reset").arg("--hard").arg(temp_store_ref), verbose);
+        let _ = store_repo.delete_ref(temp_store_ref);
+        let output = output.map_err(|e| TrunkError::Git(format!("git reset failed in {}: {}", store_dir_relative_path, e)))?;
+        if !output.status.success() {
+            return Err(TrunkError::Git(format!(
+                "git reset failed in {}: {}. Run `git trunk checkout --force --store {}` to recreate it.",
+                store_dir_relative_path,
+                String::from_utf8_lossy(&output.stderr).trim(),
+                store_name
+            )));
+        }
+        info!("✓ Step 5: {} is at {}", store_dir_relative_path, short);
+    }
+    info!(
+        "✅ Reset store '{}' to {}. `git trunk push --force-with-lease --store {}` replaces it on the remote; `git trunk reset --to {} --store {}` undoes the reset.",
+        store_name, short, store_name, backup_ref, store_name
+    );
+    Ok(())
+}
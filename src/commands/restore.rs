@@ -0,0 +1,122 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::locks;
+use crate::ops;
+use crate::utils::{list_tree_files, run_git_command, short_hash};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Bring files, or the whole store, in .trunk/<store> back to an earlier commit, ready to review and commit",
+    after_help = "The commit can be any commit of the store's history: a hash, `HEAD~2` of the checkout, or `refs/trunk/<store>~2`. Nothing is committed; review the change with `git -C .trunk/<store> diff --cached`, then run `git trunk commit`.\n\nExamples:\n  git trunk restore policies/travel.md --to 1a2b3c4\n  git trunk restore handbook:policies --to HEAD~3\n  git trunk restore --all --to refs/trunk/handbook~1"
+)]
+pub struct RestoreArgs {
+    #[arg(required_unless_present = "all", help = "Files or directories to restore: relative to .trunk/<store>, or `store:path`")]
+    paths: Vec<String>,
+    #[arg(long, conflicts_with = "paths", help = "Restore every file of the store")]
+    all: bool,
+    #[arg(long, value_name = "COMMIT", help = "Commit to take the content from")]
+    to: String,
+    #[arg(long, help = "Overwrite uncommitted changes to the restored paths")]
+    force: bool,
+}
+
+impl RestoreArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        self.paths.iter_mut().collect()
+    }
+}
+
+pub fn run(args: &RestoreArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let paths = ops::store_paths(&repo, &args.paths, store_name, verbose)
+        .iter()
+        .map(|path| locks::normalize_path(store_name, path.trim_end_matches('/')))
+        .collect::<Result<Vec<_>, _>>()?;
+    // The store root stands for every file, like --all
+    let paths: Vec<String> = if args.all || paths.iter().any(|path| path.is_empty() || path == ".") { Vec::new() } else { paths };
+
+    // Step 2: Open .trunk/<store>
+    let store_repo = ops::ensure_store_checkout(repo.root(), store_name, "Step 2", verbose)?;
+
+    // Step 3: Find the commit in the checkout, or else in refs/trunk/<store>,
+    // whose history the checkout may not have fetched yet
+    debug!("➡️ Step 3: Resolving {}", args.to);
+    let temp_store_ref = "refs/temp/trunk_restore";
+    let commit = match store_repo.resolve_commit(&args.to) {
+        Some(commit) => commit,
+        None => {
+            let commit = repo
+                .resolve_commit(&args.to)
+                .filter(|commit| repo.resolve_commit(&trunk_ref_name).is_some_and(|tip| repo.is_ancestor(commit, &tip)))
+                .ok_or_else(|| TrunkError::NotFound(format!("'{}' is not a commit of store '{}'", args.to, store_name)))?;
+            store_repo
+                .fetch_local(repo.root(), &format!("+{}:{}", trunk_ref_name, temp_store_ref))
+                .map_err(|e| TrunkError::Git(format!("Failed to fetch {} into {}: {}", trunk_ref_name, store_dir_relative_path, e)))?;
+            commit
+        }
+    };
+    let short = short_hash(store_repo.root(), &commit, verbose);
+    info!("✓ Step 3: Restoring from {} ({})", args.to, short);
+    let cleanup = || {
+        let _ = store_repo.delete_ref(temp_store_ref);
+    };
+
+    // Step 4: Check the paths, and that no uncommitted change would be lost
+    debug!("➡️ Step 4: Checking {}", if paths.is_empty() { "every file" } else { "the paths" });
+    let in_commit = list_tree_files(store_repo.root(), &commit, verbose).unwrap_or_default();
+    let in_head = store_repo.resolve_commit("HEAD").and_then(|head| list_tree_files(store_repo.root(), &head, verbose).ok()).unwrap_or_default();
+    if let Some(missing) = paths.iter().find(|path| !in_commit.iter().chain(&in_head).any(|file| ops::is_under(file, path))) {
+        cleanup();
+        return Err(TrunkError::NotFound(format!("'{}' is neither in {} nor in {}", missing, short, store_dir_relative_path)));
+    }
+    let status = store_repo.status_porcelain().map_err(|e| TrunkError::Git(format!("Failed to read the status of {}: {}", store_dir_relative_path, e)))?;
+    let changed: Vec<&str> = status
+        .iter()
+        .filter(|line| !line.starts_with("??"))
+        .filter_map(|line| line.get(3..))
+        .flat_map(|path| path.split(" -> "))
+        .filter(|path| paths.is_empty() || paths.iter().any(|target| ops::is_under(path, target) || ops::is_under(target, path)))
+        .collect();
+    if !changed.is_empty() {
+        if !args.force {
+            cleanup();
+            return Err(TrunkError::Conflict(format!(
+                "{} has uncommitted changes the restore would overwrite: {}. Commit or discard them first, or pass --force.",
+                store_dir_relative_path,
+                changed.join(", ")
+            )));
+        }
+        error!("⚠️ Warning: Overwriting uncommitted changes in {}: {}", store_dir_relative_path, changed.join(", "));
+    }
+    info!("✓ Step 4: Nothing uncommitted is in the way");
+
+    // Step 5: Restore the files; ones the commit does not have are removed
+    debug!("➡️ Step 5: Restoring {} from {}", if paths.is_empty() { "every file" } else { "the paths" }, short);
+    let mut restore = store_repo.git();
+    restore.arg("restore").arg(format!("--source={}", commit)).arg("--staged").arg("--worktree").arg("--");
+    if paths.is_empty() {
+        restore.arg(".");
+    } else {
+        restore.args(&paths);
+    }
+    let output = run_git_command(&mut restore, verbose);
+    cleanup();
+    let output = output.map_err(|e| TrunkError::Git(format!("git restore failed in {}: {}", store_dir_relative_path, e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("git restore failed in {}: {}", store_dir_relative_path, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    info!("✓ Step 5: Restored the files in {}", store_dir_relative_path);
+    info!(
+        "✅ Restored {} of store '{}' to {}. Review with `git -C {} diff --cached`, then run `git trunk commit --store {}`.",
+        if paths.is_empty() { "every file".to_string() } else { paths.join(", ") },
+        store_name,
+        short,
+        store_dir_relative_path,
+        store_name
+    );
+    Ok(())
+}
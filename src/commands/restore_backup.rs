@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::PathBuf;
+use clap::Parser;
+use log::{debug, info};
+use crate::backup;
+use crate::config;
+use crate::ops;
+use crate::quota::format_size;
+use crate::table::Table;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(about = "Bring back refs/trunk/<store> from a backup `git trunk delete` wrote", after_help = "Examples:\n  git trunk restore-backup --store scratch\n  git trunk restore-backup --list\n  git trunk restore-backup .git/trunk-backups/scratch-20250101-120000.bundle")]
+pub struct RestoreBackupArgs {
+    #[arg(help = "Bundle to restore; its store is the one in the bundle [default: the newest backup of the store]")]
+    bundle: Option<PathBuf>,
+    #[arg(long, conflicts_with = "bundle", help = "List the backups of every store instead of restoring one")]
+    list: bool,
+    #[arg(long, requires = "list", help = "With --list, print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+}
+
+impl RestoreBackupArgs {
+    pub fn writes_git_dir(&self) -> bool {
+        !self.list
+    }
+}
+
+pub fn run(args: &RestoreBackupArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let backups = backup::list(&repo)?;
+
+    if args.list {
+        if backups.is_empty() {
+            info!("= No backups in {}", backup::backup_dir(&repo)?.display());
+            return Ok(());
+        }
+        let mut table = Table::new(&["Store", "Created", "Size", "Bundle"]);
+        for backup in &backups {
+            table.add_row(vec![
+                backup.store.as_str().into(),
+                backup.created.format("%Y-%m-%d %H:%M:%S").to_string().into(),
+                format_size(backup.size).into(),
+                backup.path.display().to_string().into(),
+            ]);
+        }
+        table.print(args.no_table);
+        return Ok(());
+    }
+
+    // Step 2: Pick the bundle and read its refs
+    let bundle = match &args.bundle {
+        Some(bundle) => fs::canonicalize(bundle).map_err(|e| TrunkError::NotFound(format!("{}: {}", bundle.display(), e)))?,
+        None => backups
+            .iter()
+            .find(|backup| backup.store == store_name)
+            .map(|backup| backup.path.clone())
+            .ok_or_else(|| TrunkError::NotFound(format!("No backups of store '{}'. `git trunk restore-backup --list` shows the backups there are.", store_name)))?,
+    };
+    debug!("➡️ Step 2: Reading {}", bundle.display());
+    let heads = backup::heads(&repo, &bundle, verbose)?;
+    // refs/trunk/<store>, or the remote's ref when only that was backed up
+    let store_head = |prefix: &str, skip_remote: bool| {
+        heads.iter().find_map(|(name, hash)| {
+            let rest = name.strip_prefix(prefix)?;
+            let store = if skip_remote { rest.split_once('/')?.1 } else { rest };
+            config::is_store_name(store).then(|| (store.to_string(), name.clone(), hash.clone()))
+        })
+    };
+    let store_head = store_head("refs/trunk/", false).or_else(|| store_head("refs/trunk-remotes/", true));
+    let Some((store, source_ref, hash)) = store_head else {
+        return Err(TrunkError::NotFound(format!("{} holds no store ref", bundle.display())));
+    };
+    info!("✓ Step 2: {} holds store '{}'", bundle.display(), store);
+
+    // Step 3: Recreate refs/trunk/<store>, never over an existing one
+    let trunk_ref_name = format!("refs/trunk/{}", store);
+    match repo.resolve_commit(&trunk_ref_name) {
+        Some(current) if current == hash => {
+            info!("= Step 3: {} is already at the backed-up commit", trunk_ref_name);
+            return Ok(());
+        }
+        Some(current) => {
+            return Err(TrunkError::Conflict(format!(
+                "{} exists at {}; restoring would replace it. Delete the store first with `git trunk delete --store {} --local-only`, or fetch the backup under another name with `git fetch {} {}:refs/trunk/<name>`.",
+                trunk_ref_name,
+                short_hash(repo.root(), &current, verbose),
+                store,
+                bundle.display(),
+                source_ref
+            )));
+        }
+        None => {}
+    }
+    debug!("📥 Step 3: Fetching {} from {} into {}", source_ref, bundle.display(), trunk_ref_name);
+    let output = run_git_command(repo.git().arg("fetch").arg(&bundle).arg(format!("{}:{}", source_ref, trunk_ref_name)), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to fetch from {}: {}", bundle.display(), e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("Failed to fetch from {}: {}", bundle.display(), String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    info!("✓ Step 3: Restored {} at {}", trunk_ref_name, short_hash(repo.root(), &hash, verbose));
+
+    info!("✅ Store '{}' restored. `git trunk checkout --store {}` recreates .trunk/{}, and `git trunk push --store {}` puts it back on the remote.", store, store, store, store);
+    Ok(())
+}
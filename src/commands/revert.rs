@@ -0,0 +1,92 @@
+use clap::Parser;
+use log::{debug, info};
+use crate::commands::{checkout, push};
+use crate::config;
+use crate::git::Revert;
+use crate::ops;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Undo a commit of refs/trunk/<store> with a new commit, without a checkout",
+    after_help = "Files the commit changed that were changed again since stop the revert; revert those in the checkout with `git -C .trunk/<store> revert <commit>` and `git trunk commit`.\n\nExamples:\n  git trunk revert 1a2b3c4\n  git trunk --store handbook revert refs/trunk/handbook~2 -m \"Bring back the old travel policy\""
+)]
+pub struct RevertArgs {
+    #[arg(value_name = "COMMIT", help = "Commit of the store to undo")]
+    commit: String,
+    #[arg(short = 'm', long, help = "Commit message [default: \"Revert \\\"<subject>\\\"\"]")]
+    message: Option<String>,
+}
+
+pub fn run(args: &RevertArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+
+    // Step 2: Check that the commit is in the store's history, and that the
+    // checkout holds no commits or changes the revert would leave behind
+    debug!("➡️ Step 2: Resolving {} in {}", args.commit, trunk_ref_name);
+    let Some(tip) = repo.resolve_commit(&trunk_ref_name) else {
+        return Err(TrunkError::NotFound(format!(
+            "{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.",
+            trunk_ref_name, store_name, store_name
+        )));
+    };
+    let commit = repo
+        .resolve_commit(&args.commit)
+        .filter(|commit| repo.is_ancestor(commit, &tip))
+        .ok_or_else(|| TrunkError::NotFound(format!("'{}' is not a commit of {}", args.commit, trunk_ref_name)))?;
+    let short = short_hash(repo.root(), &commit, verbose);
+    let parents = run_git_command(repo.git().arg("rev-list").arg("--parents").arg("-n1").arg(&commit), verbose)
+        .map_err(|e| TrunkError::Git(format!("git rev-list failed for {}: {}", short, e)))?;
+    match String::from_utf8_lossy(&parents.stdout).split_whitespace().count() {
+        2 => {}
+        1 => return Err(TrunkError::Usage(format!("{} is the first commit of store '{}' and cannot be reverted; `git trunk delete --store {}` removes the store", short, store_name, store_name))),
+        _ => return Err(TrunkError::Usage(format!("{} is a merge; revert it in the checkout with `git -C .trunk/{} revert -m 1 {}` and `git trunk commit`", short, store_name, short))),
+    }
+    let changed = run_git_command(repo.git().arg("diff-tree").arg("--no-commit-id").arg("--name-only").arg("-r").arg("-z").arg(&commit), verbose)
+        .map_err(|e| TrunkError::Git(format!("git diff-tree failed for {}: {}", short, e)))?;
+    let paths: Vec<String> = changed.stdout.split(|b| *b == 0).filter(|path| !path.is_empty()).map(|path| String::from_utf8_lossy(path).to_string()).collect();
+    let store_repo = ops::checkout_to_follow(&repo, store_name, &tip, &paths, verbose)?;
+    info!("✓ Step 2: Reverting {} ({} file(s))", short, paths.len());
+
+    // Step 3: Commit the inverse changes on top of refs/trunk/<store>
+    debug!("➡️ Step 3: Undoing {} on top of {}", short, trunk_ref_name);
+    let subject = run_git_command(repo.git().arg("log").arg("-1").arg("--format=%s").arg(&commit), verbose)
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    let message = args.message.clone().unwrap_or_else(|| format!("Revert \"{}\"\n\nThis reverts commit {}.", subject, commit));
+    let new_commit = match repo.commit_revert(&commit, &tip, &message).map_err(|e| TrunkError::Git(format!("Failed to revert {}: {}", short, e)))? {
+        Revert::Committed(new_commit) => new_commit,
+        Revert::Unchanged => {
+            info!("✅ The changes of {} are already undone in store '{}'", short, store_name);
+            return Ok(());
+        }
+        Revert::Conflicts(conflicts) => {
+            return Err(TrunkError::Conflict(format!(
+                "{} changed since {}, so the revert cannot be made without a checkout. Revert it in the checkout with `git -C .trunk/{} revert {}`, resolve the conflicts and run `git trunk commit`.",
+                conflicts.join(", "),
+                short,
+                store_name,
+                short
+            )));
+        }
+    };
+    ops::move_store_ref(&repo, store_name, &tip, &new_commit, &message, verbose)?;
+    info!("✓ Step 3: Committed the revert to {} at {}", trunk_ref_name, short_hash(repo.root(), &new_commit, verbose));
+
+    // Steps 9-12: Bring an existing checkout along, as `git trunk checkout` would
+    if let Some(store_repo) = &store_repo {
+        checkout::update_checkout(&repo, store_repo, store_name, verbose)?;
+    }
+    info!("✅ Reverted {} in store '{}'", short, store_name);
+
+    // Step 4: Push the new commit when auto_push is configured, like commit
+    let trunk_config = config::load(repo.root(), verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    if trunk_config.auto_push(store_name) {
+        debug!("📤 Step 4: auto_push is set, pushing store '{}' to '{}'", store_name, remote_name);
+        push::run(&push::PushArgs::default(), remote_name, store_name, verbose)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::commands::{checkout, push};
+use crate::config;
+use crate::locks;
+use crate::ops;
+use crate::utils::{list_tree_files, short_hash};
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Remove files or directories from refs/trunk/<store> in a single commit",
+    after_help = "Examples:\n  git trunk rm drafts -m \"Drop the old drafts\"\n  git trunk rm handbook:policies/travel.md\n  git trunk rm --store design mockups/home.fig mockups/cart.fig"
+)]
+pub struct RmArgs {
+    #[arg(required = true, help = "Files or directories to remove: relative to .trunk/<store>, or `store:path`")]
+    paths: Vec<String>,
+    #[arg(short = 'm', long, help = "Commit message [default: \"Remove <paths>\"]")]
+    message: Option<String>,
+}
+
+impl RmArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        self.paths.iter_mut().collect()
+    }
+}
+
+pub fn run(args: &RmArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let paths = ops::store_paths(&repo, &args.paths, store_name, verbose)
+        .iter()
+        .map(|path| locks::normalize_path(store_name, path.trim_end_matches('/')))
+        .collect::<Result<Vec<_>, _>>()?;
+    if paths.iter().any(|path| path.is_empty() || path == ".") {
+        return Err(TrunkError::Usage(format!("`git trunk rm` removes paths inside the store; `git trunk delete --store {}` removes the whole store", store_name)));
+    }
+
+    // Step 2: Check that every path is in refs/trunk/<store>, and that the
+    // checkout holds no commits or changes the removal would leave behind
+    debug!("➡️ Step 2: Checking {}", trunk_ref_name);
+    let Some(parent) = repo.resolve_commit(&trunk_ref_name) else {
+        return Err(TrunkError::NotFound(format!(
+            "{} for store '{}' does not exist in the local repository. Run `git trunk fetch --store {}` to get it from the remote.",
+            trunk_ref_name, store_name, store_name
+        )));
+    };
+    let files = list_tree_files(repo.root(), &parent, verbose).map_err(|e| TrunkError::Git(format!("Failed to list {}: {}", trunk_ref_name, e)))?;
+    if let Some(missing) = paths.iter().find(|path| !files.iter().any(|file| ops::is_under(file, path))) {
+        return Err(TrunkError::NotFound(format!("'{}' is not in {} of store '{}'", missing, trunk_ref_name, store_name)));
+    }
+    let store_repo = ops::checkout_to_follow(&repo, store_name, &parent, &paths, verbose)?;
+    let me = locks::identity(&repo, verbose).ok();
+    for lock in locks::read(&repo, store_name, verbose).into_iter().filter(|lock| paths.iter().any(|path| ops::is_under(&lock.path, path))) {
+        if me.as_ref().is_none_or(|me| me.email != lock.email) {
+            error!("⚠️ Warning: {} is locked by {} since {}; check with them before removing it", lock.path, lock.owner(), lock.since.format("%Y-%m-%d %H:%M"));
+        }
+    }
+    let removed = files.iter().filter(|file| paths.iter().any(|path| ops::is_under(file, path))).count();
+    info!("✓ Step 2: {} file(s) to remove from {}", removed, trunk_ref_name);
+
+    // Step 3: Commit the removal on top of refs/trunk/<store>
+    debug!("➡️ Step 3: Removing {} from {}", paths.join(", "), trunk_ref_name);
+    let message = args.message.clone().unwrap_or_else(|| format!("Remove {}", paths.join(", ")));
+    let changes: Vec<(String, Option<Vec<u8>>)> = paths.iter().map(|path| (path.clone(), None)).collect();
+    let commit = ops::commit_to_store_ref(&repo, store_name, &parent, &changes, &message, verbose)?
+        .ok_or_else(|| TrunkError::Failed(format!("Removing {} left {} unchanged", paths.join(", "), trunk_ref_name)))?;
+    info!("✓ Step 3: Committed the removal to {} at {}", trunk_ref_name, short_hash(repo.root(), &commit, verbose));
+
+    // Steps 9-12: Remove the files from an existing checkout too, as `git trunk checkout` would
+    if let Some(store_repo) = &store_repo {
+        checkout::update_checkout(&repo, store_repo, store_name, verbose)?;
+    }
+    info!("✅ Removed {} file(s) from store '{}'", removed, store_name);
+
+    // Step 4: Push the new commit when auto_push is configured, like commit
+    let trunk_config = config::load(repo.root(), verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    if trunk_config.auto_push(store_name) {
+        debug!("📤 Step 4: auto_push is set, pushing store '{}' to '{}'", store_name, remote_name);
+        push::run(&push::PushArgs::default(), remote_name, store_name, verbose)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use clap::Parser;
+use log::{debug, error, info};
+use crate::commands::protect_clean;
+use crate::config;
+use crate::ops;
+use crate::table::is_plain;
+use crate::template::Template;
+use crate::utils::confirm;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Show per store whether .trunk/<store>, refs/trunk/<store> and the remote ref have diverged", after_help = "Examples:\n  git trunk status\n  git trunk status --all --porcelain\n  git trunk status --all --format '{store}\\t{remote_hash}\\t{ahead}'")]
+pub struct StatusArgs {
+    #[arg(long, help = "Report every local store instead of only the current one")]
+    all: bool,
+    #[arg(long, help = "Skip the remote check (no network access)")]
+    local: bool,
+    #[arg(long, help = "Print stable `key=value` fields for scripts")]
+    porcelain: bool,
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "porcelain", help = "Print one line per store from a template; placeholders: {store}, {remote}, {checkout}, {uncommitted}, {local_hash}, {remote_hash}, {ahead}, {behind}, {remote_state}")]
+    format: Option<String>,
+}
+
+const FORMAT_FIELDS: &[&str] = &["store", "remote", "checkout", "uncommitted", "local_hash", "remote_hash", "ahead", "behind", "remote_state"];
+
+#[derive(Debug, Default)]
+struct StoreStatus {
+    checkout: bool,
+    // The checkout disappeared without git-trunk removing it, see ops::lost_checkouts
+    lost: bool,
+    uncommitted: Option<usize>,
+    local_ref: Option<String>,
+    ahead_behind: Option<(usize, usize)>,
+    remote: Option<RemoteState>,
+    remote_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RemoteState {
+    Same,
+    Differs,
+    Missing,
+    Unknown,
+    // Pushed to a review ref (`[remote.<name>] review_ref`), not merged yet
+    PendingReview,
+}
+
+impl RemoteState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RemoteState::Same => "same",
+            RemoteState::Differs => "differs",
+            RemoteState::Missing => "missing",
+            RemoteState::Unknown => "unknown",
+            RemoteState::PendingReview => "pending-review",
+        }
+    }
+}
+
+fn local_stores(repo: &GitRepo, trunk_base_dir: &Path) -> Vec<String> {
+    let mut stores: Vec<String> = repo
+        .ref_names("refs/trunk/")
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| config::is_store_name(name))
+        .collect();
+    if let Ok(entries) = fs::read_dir(trunk_base_dir) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            if entry.path().join(".git").exists() {
+                stores.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    stores.sort();
+    stores.dedup();
+    stores
+}
+
+fn store_status(repo: &GitRepo, trunk_base_dir: &Path, store_name: &str, remote_name: &str, check_remote: bool, verbose: bool) -> StoreStatus {
+    let mut status = StoreStatus::default();
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let store_dir = trunk_base_dir.join(store_name);
+    status.local_ref = repo.resolve_commit(&trunk_ref_name);
+
+    if let Ok(store_repo) = GitRepo::open(&store_dir, verbose) {
+        status.checkout = true;
+        status.uncommitted = store_repo.status_porcelain().ok().map(|lines| lines.len());
+        // The checkout usually has every commit of the ref; the main
+        // repository has them when the checkout was just committed.
+        if let (Some(local_ref), Some(store_head)) = (&status.local_ref, store_repo.resolve_commit("main")) {
+            status.ahead_behind = store_repo.ahead_behind(&store_head, local_ref)
+                .or_else(|| repo.ahead_behind(&store_head, local_ref));
+        }
+    }
+
+    if check_remote {
+        let remote_tip = ops::cached_remote_store_tip(repo, remote_name, store_name, verbose)
+            .map(Ok)
+            .unwrap_or_else(|| ops::remote_store_tip(repo, remote_name, store_name, verbose));
+        // The local ref as last pushed for review, see `[remote.<name>] review_ref`
+        let pending_review = repo.resolve_commit(&format!("refs/trunk-review/{}/{}", remote_name, store_name));
+        status.remote = Some(match remote_tip {
+            Ok(None) if status.local_ref.is_some() && pending_review == status.local_ref => RemoteState::PendingReview,
+            Ok(None) => RemoteState::Missing,
+            Ok(Some(hash)) => {
+                let state = match &status.local_ref {
+                    Some(local_ref) if *local_ref == hash => RemoteState::Same,
+                    Some(local_ref) if pending_review.as_ref() == Some(local_ref) => RemoteState::PendingReview,
+                    _ => RemoteState::Differs,
+                };
+                status.remote_hash = Some(hash);
+                state
+            }
+            Err(_) => RemoteState::Unknown,
+        });
+    }
+    status
+}
+
+fn porcelain_line(store_name: &str, status: &StoreStatus) -> String {
+    let mut fields = vec![store_name.to_string()];
+    fields.push(format!("checkout={}", if status.checkout { "yes" } else { "no" }));
+    fields.push(format!("uncommitted={}", status.uncommitted.map(|count| count.to_string()).unwrap_or_else(|| "-".to_string())));
+    fields.push(format!("ref={}", status.local_ref.as_deref().map(|hash| &hash[..hash.len().min(7)]).unwrap_or("-")));
+    let (ahead, behind) = status.ahead_behind.map(|(a, b)| (a.to_string(), b.to_string())).unwrap_or(("-".to_string(), "-".to_string()));
+    fields.push(format!("ahead={}", ahead));
+    fields.push(format!("behind={}", behind));
+    fields.push(format!("remote={}", status.remote.map(|remote| remote.as_str()).unwrap_or("-")));
+    fields.join(" ")
+}
+
+// The values of the --format placeholders; unknown values are left empty.
+fn format_values<'a>(store_name: &str, status: &StoreStatus, remote_name: &str) -> Vec<(&'a str, String)> {
+    let (ahead, behind) = status.ahead_behind.map(|(a, b)| (a.to_string(), b.to_string())).unwrap_or_default();
+    vec![
+        ("store", store_name.to_string()),
+        ("remote", remote_name.to_string()),
+        ("checkout", if status.checkout { "yes" } else { "no" }.to_string()),
+        ("uncommitted", status.uncommitted.map(|count| count.to_string()).unwrap_or_default()),
+        ("local_hash", status.local_ref.clone().unwrap_or_default()),
+        ("remote_hash", status.remote_hash.clone().unwrap_or_default()),
+        ("ahead", ahead),
+        ("behind", behind),
+        ("remote_state", status.remote.map(|remote| remote.as_str().to_string()).unwrap_or_default()),
+    ]
+}
+
+// The facts of the human-readable line, e.g. "clean", "committed", "in sync with origin".
+fn summary_facts(store_name: &str, status: &StoreStatus, remote_name: &str) -> Vec<String> {
+    let mut parts: Vec<String> = Vec::new();
+    match (status.checkout, status.uncommitted) {
+        (false, _) if status.lost => parts.push("checkout removed outside git-trunk".to_string()),
+        (false, _) => parts.push("not checked out".to_string()),
+        (true, Some(0)) => parts.push("clean".to_string()),
+        (true, Some(count)) => parts.push(format!("{} uncommitted change(s)", count)),
+        (true, None) => parts.push("status unknown".to_string()),
+    }
+    match (&status.local_ref, status.ahead_behind) {
+        (None, _) => parts.push("never committed".to_string()),
+        (Some(_), Some((0, 0))) => parts.push("committed".to_string()),
+        (Some(_), Some((ahead, 0))) => parts.push(format!("{} commit(s) not in refs/trunk/{}", ahead, store_name)),
+        (Some(_), Some((0, behind))) => parts.push(format!("{} commit(s) behind refs/trunk/{}", behind, store_name)),
+        (Some(_), Some((ahead, behind))) => parts.push(format!("diverged from refs/trunk/{} ({} ahead, {} behind)", store_name, ahead, behind)),
+        (Some(_), None) if status.checkout => parts.push(format!("unrelated to refs/trunk/{}", store_name)),
+        (Some(_), None) => {}
+    }
+    match status.remote {
+        Some(RemoteState::Same) => parts.push(format!("in sync with {}", remote_name)),
+        Some(RemoteState::Differs) => parts.push(format!("differs from {}", remote_name)),
+        Some(RemoteState::Missing) => parts.push(format!("not on {}", remote_name)),
+        Some(RemoteState::Unknown) => parts.push(format!("{} unreachable", remote_name)),
+        Some(RemoteState::PendingReview) => parts.push(format!("pending review on {}", remote_name)),
+        None => {}
+    }
+    parts
+}
+
+pub fn run(args: &StatusArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let template = args.format.as_deref().map(|format| Template::parse(format, FORMAT_FIELDS)).transpose()?;
+
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    debug!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Work out which stores to report
+    let trunk_base_dir = config::trunk_base_dir(repo_root, verbose);
+    let stores = if args.all { local_stores(&repo, &trunk_base_dir) } else { vec![store_name.to_string()] };
+    if stores.is_empty() {
+        info!("= No local stores found");
+        return Ok(());
+    }
+
+    // Step 3: One line per store
+    let trunk_config = config::load(repo_root, verbose).ok();
+    let lost: Vec<String> = ops::lost_checkouts(&repo, verbose).into_iter().filter(|store| stores.contains(store)).collect();
+    let mut at_risk = Vec::new();
+    for store in &stores {
+        debug!("➡️ Step 3: Checking store '{}'", store);
+        let store_remote = if args.all {
+            trunk_config.as_ref().map(|c| c.remote_for_store(store)).unwrap_or_else(|| remote_name.to_string())
+        } else {
+            remote_name.to_string()
+        };
+        let mut status = store_status(&repo, &trunk_base_dir, store, &store_remote, !args.local, verbose);
+        status.lost = lost.contains(store);
+        if let Some(count) = status.uncommitted.filter(|count| *count > 0) {
+            at_risk.push((store, count));
+        }
+        if let Some(template) = &template {
+            println!("{}", template.render(&format_values(store, &status, &store_remote)));
+        } else if args.porcelain {
+            println!("{}", porcelain_line(store, &status));
+        } else if is_plain() {
+            // One fact per line for screen readers
+            for fact in summary_facts(store, &status, &store_remote) {
+                println!("{}: {}", store, fact);
+            }
+        } else {
+            println!("{}: {}", store, summary_facts(store, &status, &store_remote).join(", "));
+        }
+    }
+
+    // Step 4: With `git trunk protect-clean` on, say what `git clean -ffdx` would destroy
+    if !at_risk.is_empty() && protect_clean::is_protected(&repo, verbose) {
+        for (store, count) in at_risk {
+            error!("⚠️ Warning: store '{}' has {} uncommitted change(s) that `git clean -ffdx` would delete; commit them with `git trunk --store {} commit`", store, count, store);
+        }
+    }
+
+    // Step 5: Offer to restore checkouts removed behind git-trunk's back; only
+    // in a terminal, so scripts reading the output are never prompted
+    let interactive = template.is_none() && !args.porcelain && io::stdin().is_terminal();
+    for store in &lost {
+        error!("⚠️ Warning: .trunk/{} disappeared (e.g. `git stash --all` or `git clean -x`), but refs/trunk/{} still has its last commit", store, store);
+        if interactive && confirm(&format!("Restore .trunk/{} from refs/trunk/{}?", store, store))? {
+            ops::restore_checkout(&repo, store, "Step 5", verbose)?;
+            info!("✓ Step 5: Restored .trunk/{} from refs/trunk/{}", store, store);
+        } else {
+            info!("ℹ️ Restore it with `git trunk doctor` or `git trunk checkout --store {}`", store);
+        }
+    }
+    Ok(())
+}
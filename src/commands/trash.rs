@@ -0,0 +1,141 @@
+use chrono::{Local, Utc};
+use clap::{Parser, Subcommand};
+use log::{debug, error, info};
+use crate::ops;
+use crate::table::{print_line, Table};
+use crate::trash::{self, TrashEntry};
+use crate::utils::{confirm, run_git_change, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "List the stores `git trunk delete` moved to refs/trunk-trash, or remove them for good", after_help = "Examples:\n  git trunk trash list\n  git trunk trash purge --expire 30d\n  git trunk trash purge --expire now --local")]
+pub struct TrashArgs {
+    #[command(subcommand)]
+    action: TrashAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum TrashAction {
+    /// Lists the deleted stores here and on the remote, newest first
+    List {
+        #[arg(long, help = "Only list the local trash, without asking the remote")]
+        local: bool,
+        #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+        no_table: bool,
+    },
+    /// Deletes the trash entries older than --expire, here and on the remote
+    Purge {
+        #[arg(long, value_name = "AGE", help = "Remove entries deleted longer ago than AGE, e.g. 30d, 2w, 12h or now")]
+        expire: String,
+        #[arg(long, help = "Only purge the local trash, leaving the remote's alone")]
+        local: bool,
+    },
+}
+
+impl TrashArgs {
+    pub fn writes_git_dir(&self) -> bool {
+        matches!(self.action, TrashAction::Purge { .. })
+    }
+}
+
+fn count_entries(count: usize) -> String {
+    format!("{} entr{}", count, if count == 1 { "y" } else { "ies" })
+}
+
+// The remote's entries, or none with a warning when it cannot be reached.
+fn remote_entries(repo: &GitRepo, remote_name: &str, local: bool, verbose: bool) -> Vec<TrashEntry> {
+    if local {
+        return Vec::new();
+    }
+    trash::remote(repo, remote_name, verbose).unwrap_or_else(|e| {
+        error!("⚠️ Warning: {}; only using the local trash", e);
+        Vec::new()
+    })
+}
+
+pub fn run(args: &TrashArgs, remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+
+    match &args.action {
+        TrashAction::List { local, no_table } => {
+            let local_entries = trash::local(&repo, verbose)?;
+            let remote_entries = remote_entries(&repo, remote_name, *local, verbose);
+            let mut entries: Vec<&TrashEntry> = local_entries.iter().collect();
+            entries.extend(remote_entries.iter().filter(|remote| !local_entries.iter().any(|local| local.name == remote.name)));
+            entries.sort_by(|a, b| b.deleted.cmp(&a.deleted).then_with(|| a.store.cmp(&b.store)));
+            if entries.is_empty() {
+                print_line("The trash is empty");
+                return Ok(());
+            }
+            let mut table = Table::new(&["Store", "Deleted", "Commit", "Where", "Entry"]);
+            for entry in entries {
+                let here = local_entries.iter().any(|local| local.name == entry.name);
+                let there = remote_entries.iter().any(|remote| remote.name == entry.name);
+                let location = match (here, there) {
+                    (true, true) => format!("local, {}", remote_name),
+                    (true, false) => "local".to_string(),
+                    _ => remote_name.to_string(),
+                };
+                table.add_row(vec![
+                    entry.store.as_str().into(),
+                    entry.deleted.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string().into(),
+                    short_hash(repo.root(), &entry.hash, verbose).into(),
+                    location.into(),
+                    entry.name.as_str().into(),
+                ]);
+            }
+            table.print(*no_table);
+            Ok(())
+        }
+        TrashAction::Purge { expire, local } => {
+            let age = trash::parse_duration(expire).ok_or_else(|| TrunkError::Usage(format!("Invalid --expire '{}' (expected e.g. 30d, 2w, 12h or now)", expire)))?;
+            let cutoff = Utc::now() - age;
+
+            // Step 2: Find the expired entries
+            debug!("➡️ Step 2: Finding trash entries deleted before {}", cutoff.with_timezone(&Local).format("%Y-%m-%d %H:%M"));
+            let expired = |entries: Vec<TrashEntry>| -> Vec<TrashEntry> { entries.into_iter().filter(|entry| entry.deleted <= cutoff).collect() };
+            let local_expired = expired(trash::local(&repo, verbose)?);
+            let remote_expired = expired(remote_entries(&repo, remote_name, *local, verbose));
+            if local_expired.is_empty() && remote_expired.is_empty() {
+                info!("✅ Nothing in the trash is older than {}", expire);
+                return Ok(());
+            }
+            let mut names: Vec<&str> = local_expired.iter().chain(&remote_expired).map(|entry| entry.name.as_str()).collect();
+            names.sort();
+            names.dedup();
+            info!("✓ Step 2: {} expired: {}", count_entries(names.len()), names.join(", "));
+            if !confirm(&format!("This will permanently delete {} of the trash. Continue?", count_entries(names.len())))? {
+                info!("🚫 Purge aborted by user");
+                return Ok(());
+            }
+
+            // Step 3: Delete the local entries
+            debug!("➡️ Step 3: Deleting {} of the local trash", count_entries(local_expired.len()));
+            for entry in &local_expired {
+                repo.delete_ref(&entry.ref_name()).map_err(|e| TrunkError::Git(format!("Failed to delete {}: {}", entry.ref_name(), e)))?;
+            }
+            info!("✓ Step 3: Deleted {} of the local trash", count_entries(local_expired.len()));
+
+            // Step 4: Delete the remote's entries in one push
+            if remote_expired.is_empty() {
+                info!("= Step 4: Nothing to purge on remote '{}'", remote_name);
+            } else {
+                debug!("➡️ Step 4: Deleting {} of the trash of remote '{}'", count_entries(remote_expired.len()), remote_name);
+                let mut push = repo.git();
+                push.arg("push").arg(remote_name);
+                for entry in &remote_expired {
+                    push.arg(format!(":{}", entry.ref_name()));
+                }
+                let output = run_git_change(&mut push, verbose).map_err(|e| TrunkError::Git(format!("Failed to push to remote '{}': {}", remote_name, e)))?;
+                if !output.status.success() {
+                    return Err(TrunkError::Git(format!("Failed to purge the trash of remote '{}': {}", remote_name, String::from_utf8_lossy(&output.stderr).trim())));
+                }
+                info!("✓ Step 4: Deleted {} of the trash of remote '{}'", count_entries(remote_expired.len()), remote_name);
+            }
+            info!("✅ Purged {} of the trash", count_entries(names.len()));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::ops;
+use crate::trash::{self, TrashEntry};
+use crate::utils::{is_dry_run, run_git_change, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Bring back a store `git trunk delete` moved to refs/trunk-trash", after_help = "Examples:\n  git trunk undelete scratch\n  git trunk undelete scratch-20250101T120000Z\n  git trunk undelete scratch --local-only")]
+pub struct UndeleteArgs {
+    #[arg(help = "Store to bring back, or an entry shown by `git trunk trash list` [default: the --store store]")]
+    store: Option<String>,
+    #[arg(long, help = "Only restore the local ref, leaving the remote and its trash alone")]
+    local_only: bool,
+}
+
+pub fn run(args: &UndeleteArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+
+    // Step 2: Find the newest trash entry of the store, here or on the remote
+    let wanted = args.store.as_deref().unwrap_or(store_name);
+    debug!("➡️ Step 2: Looking for '{}' in the trash", wanted);
+    let local_entries = trash::local(&repo, verbose)?;
+    let remote_entries = if args.local_only {
+        Vec::new()
+    } else {
+        trash::remote(&repo, remote_name, verbose).unwrap_or_else(|e| {
+            error!("⚠️ Warning: {}; only looking in the local trash", e);
+            Vec::new()
+        })
+    };
+    let Some(entry) = local_entries
+        .iter()
+        .chain(&remote_entries)
+        .filter(|entry| entry.name == wanted || entry.store == wanted)
+        .max_by_key(|entry| (entry.name == wanted, entry.deleted))
+        .cloned()
+    else {
+        return Err(TrunkError::NotFound(format!(
+            "No deleted store '{}' in the trash of this repository or remote '{}'. `git trunk trash list` shows what is there.",
+            wanted, remote_name
+        )));
+    };
+    let in_local_trash = local_entries.iter().any(|local| local.name == entry.name);
+    let in_remote_trash = remote_entries.iter().any(|remote| remote.name == entry.name);
+    info!("✓ Step 2: Found {} ({}), deleted {}", entry.ref_name(), short_hash(repo.root(), &entry.hash, verbose), entry.deleted.format("%Y-%m-%d %H:%M:%S UTC"));
+
+    restore_local_ref(&repo, remote_name, &entry, in_local_trash, verbose)?;
+    if in_remote_trash {
+        restore_remote_ref(&repo, remote_name, &entry, verbose)?;
+    } else {
+        info!("= Step 4: {} is not in the trash of remote '{}'", entry.name, remote_name);
+    }
+
+    info!(
+        "✅ Store '{}' undeleted at {}. `git trunk checkout --store {}` recreates .trunk/{}.",
+        entry.store,
+        short_hash(repo.root(), &entry.hash, verbose),
+        entry.store,
+        entry.store
+    );
+    Ok(())
+}
+
+// Points refs/trunk/<store> at the entry again, never over another commit,
+// and empties the local trash entry.
+fn restore_local_ref(repo: &GitRepo, remote_name: &str, entry: &TrashEntry, in_local_trash: bool, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", entry.store);
+    let trash_ref_name = entry.ref_name();
+
+    // Step 3: Recreate refs/trunk/<store>
+    debug!("➡️ Step 3: Restoring {} from {}", trunk_ref_name, trash_ref_name);
+    match repo.resolve_commit(&trunk_ref_name) {
+        Some(current) if current == entry.hash => {
+            info!("= Step 3: {} is already at {}", trunk_ref_name, short_hash(repo.root(), &current, verbose));
+        }
+        Some(current) => {
+            return Err(TrunkError::Conflict(format!(
+                "{} exists at {}; undeleting would replace it. Delete the store first, or fetch the deleted one under another name with `git update-ref refs/trunk/<name> {}`.",
+                trunk_ref_name,
+                short_hash(repo.root(), &current, verbose),
+                entry.hash
+            )));
+        }
+        None => {
+            if !in_local_trash {
+                // Only the remote kept it; bring its commits here first
+                let output = run_git_change(repo.git().arg("fetch").arg(remote_name).arg(format!("+{}:{}", trash_ref_name, trash_ref_name)), verbose)
+                    .map_err(|e| TrunkError::Git(format!("Failed to fetch {} from remote '{}': {}", trash_ref_name, remote_name, e)))?;
+                if !output.status.success() {
+                    return Err(TrunkError::Git(format!("Failed to fetch {} from remote '{}': {}", trash_ref_name, remote_name, String::from_utf8_lossy(&output.stderr).trim())));
+                }
+            }
+            repo.update_ref(&trunk_ref_name, &entry.hash).map_err(|e| TrunkError::Git(format!("Failed to update {}: {}", trunk_ref_name, e)))?;
+            info!("✓ Step 3: {} restored at {}", trunk_ref_name, short_hash(repo.root(), &entry.hash, verbose));
+        }
+    }
+    repo.delete_ref(&trash_ref_name).map_err(|e| TrunkError::Git(format!("Failed to delete {}: {}", trash_ref_name, e)))?;
+    Ok(())
+}
+
+// Moves the remote's trash entry back to refs/trunk/<store> in one atomic
+// push, unless the remote has a new store of that name.
+fn restore_remote_ref(repo: &GitRepo, remote_name: &str, entry: &TrashEntry, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", entry.store);
+    let trash_ref_name = entry.ref_name();
+
+    // Step 4: Move the remote's trash entry back
+    debug!("➡️ Step 4: Restoring {} on remote '{}'", trunk_ref_name, remote_name);
+    let mut push = repo.git();
+    push.arg("push").arg("--atomic").arg(remote_name);
+    match ops::remote_store_tip(repo, remote_name, &entry.store, verbose)? {
+        Some(remote_hash) if remote_hash != entry.hash => {
+            error!(
+                "⚠️ Warning: Remote '{}' has a new {} at {}; leaving it and {} there alone",
+                remote_name,
+                trunk_ref_name,
+                short_hash(repo.root(), &remote_hash, verbose),
+                trash_ref_name
+            );
+            return Ok(());
+        }
+        Some(_) => {}
+        None => {
+            push.arg(format!("{}:{}", entry.hash, trunk_ref_name));
+        }
+    }
+    let output = run_git_change(push.arg(format!(":{}", trash_ref_name)), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to push to remote '{}': {}", remote_name, e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Git(format!("Failed to restore {} on remote '{}': {}", trunk_ref_name, remote_name, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    if !is_dry_run() {
+        ops::forget_remote_listing(repo, remote_name);
+    }
+    // Like push, remember what the remote now has
+    if let Err(e) = repo.update_ref(&format!("refs/trunk-remotes/{}/{}", remote_name, entry.store), &entry.hash) {
+        debug!("⚠️ Step 4: Failed to record the remote's tip: {}", e);
+    }
+    info!("✓ Step 4: {} restored on remote '{}'", trunk_ref_name, remote_name);
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use clap::Parser;
+use log::{debug, info};
+use crate::config;
+use crate::commands::expose::checked_out_in;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Stop mirroring refs/trunk/<store> into a local branch and delete the branch", after_help = "Examples:\n  git trunk unexpose\n  git trunk unexpose --store blog")]
+pub struct UnexposeArgs {}
+
+pub fn run(_args: &UnexposeArgs, _remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: Look up the exposed branch
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let section = format!("store.{}", store_name);
+    let Some(branch) = trunk_config.get_string(&section, "expose_branch") else {
+        info!("= Step 2: Store '{}' is not exposed as a branch, nothing to do", store_name);
+        return Ok(());
+    };
+    info!("✓ Step 2: Store '{}' is exposed as {}", store_name, branch);
+
+    // Step 3: Delete the branch
+    debug!("🧹 Step 3: Deleting branch {}", branch);
+    if let Some(worktree) = checked_out_in(&repo, &branch, verbose) {
+        return Err(TrunkError::Conflict(format!("Branch {} is checked out in {}; switch to another branch first", branch, worktree)));
+    }
+    repo.delete_ref(&format!("refs/heads/{}", branch)).map_err(|e| TrunkError::Git(format!("Failed to delete branch {}: {}", branch, e)))?;
+    info!("✓ Step 3: Deleted branch {}", branch);
+
+    // Step 4: Forget the branch
+    config::set_value(&trunk_config, &section, "expose_branch", None).map_err(|e| TrunkError::Io(format!("Failed to update config: {}", e)))?;
+    info!("✓ Step 4: Removed expose_branch from [{}]", section);
+
+    info!("✅ Store '{}' is no longer exposed as a branch", store_name);
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use clap::Parser;
+use log::{debug, error, info};
+use crate::locks;
+use crate::ops;
+use crate::error::TrunkError;
+
+#[derive(Parser, Debug)]
+#[command(about = "Release locks taken with `git trunk lock`", after_help = "Examples:\n  git trunk unlock diagrams/architecture.drawio\n  git trunk unlock --force mockups/home.fig")]
+pub struct UnlockArgs {
+    #[arg(required = true, help = "Locked paths inside .trunk/<store>, or `store:path`")]
+    paths: Vec<String>,
+    #[arg(long, help = "Also release locks held by someone else")]
+    force: bool,
+}
+
+impl UnlockArgs {
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        self.paths.iter_mut().collect()
+    }
+}
+
+pub fn run(args: &UnlockArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+
+    // Step 2: Work out who is unlocking what
+    let me = locks::identity(&repo, verbose)?;
+    let paths = ops::store_paths(&repo, &args.paths, store_name, verbose).iter().map(|path| locks::normalize_path(store_name, path)).collect::<Result<Vec<_>, _>>()?;
+    debug!("✓ Step 2: Unlocking {} path(s) as {}", paths.len(), me.email);
+
+    // Step 3: Remove the locks on the remote, all or none
+    debug!("🔓 Step 3: Updating {} on remote '{}'", locks::lock_ref(store_name), remote_name);
+    let mut broken = Vec::new();
+    let message = format!("Unlock {} in store '{}'", paths.join(", "), store_name);
+    locks::update(&repo, remote_name, store_name, &message, verbose, |current| {
+        broken.clear();
+        for path in &paths {
+            let Some(lock) = current.iter().find(|lock| &lock.path == path) else {
+                return Err(TrunkError::NotFound(format!("{} is not locked in store '{}'", path, store_name)));
+            };
+            if lock.email != me.email {
+                if !args.force {
+                    return Err(TrunkError::Conflict(format!("{} is locked by {} since {}. Ask them to unlock it, or break the lock with --force.", path, lock.owner(), lock.since.format("%Y-%m-%d %H:%M"))));
+                }
+                broken.push(format!("{} (held by {})", path, lock.owner()));
+            }
+        }
+        current.retain(|lock| !paths.contains(&lock.path));
+        Ok(true)
+    })?;
+    for lock in &broken {
+        error!("⚠️ Warning: Broke the lock on {}", lock);
+    }
+    info!("✓ Step 3: Unlocked {}", paths.join(", "));
+    info!("✅ Unlocked {} path(s) in store '{}' on remote '{}'", paths.len(), store_name, remote_name);
+    Ok(())
+}
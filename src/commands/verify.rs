@@ -0,0 +1,188 @@
+use clap::Parser;
+use log::{debug, info};
+use crate::config;
+use crate::ops;
+use crate::table::{Cell, Color, Table};
+use crate::json::Json;
+use crate::utils::{run_git_command, short_hash};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Check a store's integrity: its ref, objects, checkout and remote",
+    after_help = "Checks that refs/trunk/<store> resolves, that every object it reaches is present and sound (git fsck from the ref), that .trunk/<store> is not corrupted and is on its main branch, and that the remote's ref is one the local history knows. Exits with 1 when a check fails; warnings alone exit with 0.\n\nExamples:\n  git trunk verify\n  git trunk verify --store 'docs-*' --json\n  git trunk verify --local"
+)]
+pub struct VerifyArgs {
+    #[arg(long, help = "Output the result as a JSON document instead of a table")]
+    json: bool,
+    #[arg(long, help = "Print one \"Field: value\" line per fact instead of a table")]
+    no_table: bool,
+    #[arg(long, help = "Skip the remote check and work offline")]
+    local: bool,
+}
+
+// Outcome of one check, from best to worst; a store's health is its worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Health {
+    Ok,
+    Skipped,
+    Warn,
+    Fail,
+}
+
+impl Health {
+    fn name(&self) -> &'static str {
+        match self {
+            Health::Ok => "ok",
+            Health::Skipped => "skipped",
+            Health::Warn => "warn",
+            Health::Fail => "fail",
+        }
+    }
+
+    fn cell(&self) -> Cell {
+        match self {
+            Health::Ok => Cell::colored(self.name(), Color::Green),
+            Health::Skipped => self.name().into(),
+            Health::Warn => Cell::colored(self.name(), Color::Yellow),
+            Health::Fail => Cell::colored(self.name(), Color::Red),
+        }
+    }
+}
+
+struct Check {
+    name: &'static str,
+    health: Health,
+    detail: String,
+}
+
+fn check(name: &'static str, health: Health, detail: impl Into<String>) -> Check {
+    Check { name, health, detail: detail.into() }
+}
+
+// Runs `git fsck` from `heads` (every ref, the index and reflogs when empty)
+// and returns None when it finds nothing, else its first complaint.
+fn fsck(repo: &GitRepo, heads: &[&str], verbose: bool) -> Option<String> {
+    match run_git_command(repo.git().arg("fsck").arg("--no-dangling").arg("--no-progress").args(heads), verbose) {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => {
+            let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+            let first = text.lines().map(|line| line.split_whitespace().collect::<Vec<_>>().join(" ")).find(|line| !line.is_empty());
+            Some(first.unwrap_or_else(|| "git fsck failed".to_string()))
+        }
+        Err(e) => Some(format!("cannot run git fsck: {}", e)),
+    }
+}
+
+// How the remote's tip relates to the local one; fine as long as the local
+// history knows it.
+fn remote_check(repo: &GitRepo, remote_name: &str, store_name: &str, local_tip: &str, verbose: bool) -> Check {
+    let describe = |hash: &str| short_hash(repo.root(), hash, verbose);
+    match ops::remote_store_tip(repo, remote_name, store_name, verbose) {
+        Err(e) => check("remote", Health::Warn, e.to_string()),
+        Ok(None) => check("remote", Health::Skipped, format!("not on remote '{}'", remote_name)),
+        Ok(Some(remote_tip)) if remote_tip == local_tip => check("remote", Health::Ok, format!("'{}' is at {}, like the local ref", remote_name, describe(&remote_tip))),
+        Ok(Some(remote_tip)) if repo.resolve_commit(&remote_tip).is_none() => check(
+            "remote",
+            Health::Warn,
+            format!("'{}' is at {}, which the local history does not have; run `git trunk fetch --store {}`", remote_name, &remote_tip[..remote_tip.len().min(7)], store_name),
+        ),
+        Ok(Some(remote_tip)) if repo.is_ancestor(&remote_tip, local_tip) => check("remote", Health::Ok, format!("'{}' is at {}, an ancestor of the local ref", remote_name, describe(&remote_tip))),
+        Ok(Some(remote_tip)) if repo.is_ancestor(local_tip, &remote_tip) => check("remote", Health::Warn, format!("'{}' is ahead at {}; run `git trunk pull --store {}`", remote_name, describe(&remote_tip), store_name)),
+        Ok(Some(remote_tip)) => check("remote", Health::Warn, format!("'{}' at {} has diverged from the local ref; run `git trunk pull --store {}`", remote_name, describe(&remote_tip), store_name)),
+    }
+}
+
+pub fn run(args: &VerifyArgs, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+
+    // Step 1: Get repository root
+    let repo = ops::ensure_repo_root("Step 1", verbose)?;
+    let repo_root = repo.root();
+
+    // Step 2: Run the checks
+    debug!("➡️ Step 2: Verifying store '{}'", store_name);
+    let mut checks: Vec<Check> = Vec::new();
+    let local_tip = repo.resolve_commit(&trunk_ref_name);
+    match &local_tip {
+        Some(tip) => {
+            checks.push(check("ref", Health::Ok, format!("{} is at {}", trunk_ref_name, short_hash(repo_root, tip, verbose))));
+            // Connectivity from the ref only, so unrelated breakage in the main
+            // repository's other refs is not blamed on the store
+            checks.push(match fsck(&repo, &[tip], verbose) {
+                None => check("objects", Health::Ok, "every object reachable from the ref is present and sound"),
+                Some(problem) => check("objects", Health::Fail, problem),
+            });
+        }
+        None if repo.ref_exists(&trunk_ref_name) => checks.push(check("ref", Health::Fail, format!("{} does not point to a commit", trunk_ref_name))),
+        None => checks.push(check("ref", Health::Fail, format!("{} does not exist; run `git trunk fetch --store {}` or `git trunk commit --store {}`", trunk_ref_name, store_name, store_name))),
+    }
+
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    match GitRepo::open(&trunk_store_dir, verbose) {
+        Ok(store_repo) if trunk_store_dir.join(".git").exists() => {
+            checks.push(match fsck(&store_repo, &[], verbose) {
+                None => check("checkout", Health::Ok, format!("{} is a sound repository", store_dir_relative_path)),
+                Some(problem) => check("checkout", Health::Fail, format!("{}: {}", store_dir_relative_path, problem)),
+            });
+            let head = run_git_command(store_repo.git().arg("symbolic-ref").arg("-q").arg("HEAD"), verbose)
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .unwrap_or_default();
+            checks.push(match head.strip_prefix("refs/heads/") {
+                Some("main") => check("head", Health::Ok, "on branch main"),
+                Some(branch) => check("head", Health::Warn, format!("on branch {}, not main; run `git -C {} checkout main`", branch, store_dir_relative_path)),
+                None => check("head", Health::Warn, format!("HEAD is detached; run `git -C {} checkout main`", store_dir_relative_path)),
+            });
+        }
+        _ if trunk_store_dir.exists() => checks.push(check("checkout", Health::Fail, format!("{} exists but is not a git repository", store_dir_relative_path))),
+        _ => checks.push(check("checkout", Health::Skipped, format!("{} is not checked out", store_dir_relative_path))),
+    }
+
+    match (&local_tip, args.local) {
+        (_, true) => checks.push(check("remote", Health::Skipped, "--local")),
+        (None, false) => checks.push(check("remote", Health::Skipped, "no local ref to compare")),
+        (Some(tip), false) => checks.push(remote_check(&repo, remote_name, store_name, tip, verbose)),
+    }
+    let health = match checks.iter().map(|check| check.health).max() {
+        Some(Health::Skipped) | None => Health::Ok,
+        Some(health) => health,
+    };
+    info!("✓ Step 2: Ran {} check(s) on store '{}'", checks.len(), store_name);
+
+    // Step 3: Report
+    if args.json {
+        println!("{}", Json::Object(vec![
+            ("store".to_string(), Json::from(store_name)),
+            ("health".to_string(), Json::from(health.name())),
+            ("checks".to_string(), Json::Array(checks.iter().map(|check| Json::Object(vec![
+                ("check".to_string(), Json::from(check.name)),
+                ("status".to_string(), Json::from(check.health.name())),
+                ("detail".to_string(), Json::from(check.detail.as_str())),
+            ])).collect())),
+        ]));
+    } else {
+        let mut table = Table::new(&["Check", "Status", "Detail"]);
+        for check in &checks {
+            table.add_row(vec![check.name.into(), check.health.cell(), check.detail.as_str().into()]);
+        }
+        table.print(args.no_table);
+    }
+
+    match health {
+        Health::Fail => Err(TrunkError::Failed(format!(
+            "Store '{}' failed verification: {}",
+            store_name,
+            checks.iter().filter(|check| check.health == Health::Fail).map(|check| check.name).collect::<Vec<_>>().join(", ")
+        ))),
+        Health::Warn => {
+            info!("✅ Store '{}' is intact, with warnings", store_name);
+            Ok(())
+        }
+        _ => {
+            info!("✅ Store '{}' is healthy", store_name);
+            Ok(())
+        }
+    }
+}
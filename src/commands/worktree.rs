@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::PathBuf;
+use clap::{Parser, Subcommand};
+use log::{debug, error, info};
+use crate::config::{self, ConfigValue};
+use crate::utils::list_worktrees;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+#[derive(Parser, Debug)]
+#[command(about = "Share one set of store checkouts across all worktrees of the repository", after_help = "Examples:\n  git trunk worktree share\n  git trunk worktree unshare")]
+pub struct WorktreeArgs {
+    #[command(subcommand)]
+    action: WorktreeAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum WorktreeAction {
+    /// Records a single canonical .trunk directory used by every worktree
+    Share {
+        #[arg(long, help = "Directory to use for store checkouts [default: .trunk in the main worktree]")]
+        path: Option<String>,
+    },
+    /// Goes back to one .trunk directory per worktree
+    Unshare,
+}
+
+pub fn run(args: &WorktreeArgs, _remote_name: &str, _store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    // Step 1: Get repository root
+    debug!("➡️ Step 1: Getting repository root");
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    let repo_root = repo.root();
+    info!("✓ Step 1: Repository root found at {}", repo_root.display());
+
+    // Step 2: List worktrees
+    debug!("➡️ Step 2: Listing worktrees");
+    let worktrees = list_worktrees(repo_root, verbose).map_err(|e| TrunkError::Git(format!("Failed to list worktrees: {}", e)))?;
+    let Some(main_worktree) = worktrees.first() else {
+        return Err(TrunkError::Git("git worktree list returned no worktrees".to_string()));
+    };
+    info!("✓ Step 2: Found {} worktree(s), main worktree at {}", worktrees.len(), main_worktree.display());
+
+    let trunk_config = config::load(repo_root, verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+
+    match &args.action {
+        WorktreeAction::Share { path } => {
+            // Step 3: Record the canonical checkout directory
+            let shared_dir = match path {
+                Some(path) => {
+                    let path = PathBuf::from(path);
+                    if path.is_absolute() { path } else { repo_root.join(path) }
+                }
+                None => main_worktree.join(".trunk"),
+            };
+            let shared_dir = fs::canonicalize(&shared_dir).unwrap_or(shared_dir);
+            debug!("📝 Step 3: Recording {} as the shared store directory", shared_dir.display());
+            config::set_value(&trunk_config, "trunk", "shared_trunk_dir", Some(&ConfigValue::String(shared_dir.display().to_string()))).map_err(|e| TrunkError::Git(format!("Failed to update config: {}", e)))?;
+            info!("✓ Step 3: All worktrees now use {} for store checkouts", shared_dir.display());
+
+            // Step 4: Point out per-worktree copies that are now duplicates
+            debug!("➡️ Step 4: Looking for per-worktree store checkouts");
+            let duplicates: Vec<PathBuf> = worktrees.iter()
+                .map(|worktree| worktree.join(".trunk"))
+                .filter(|dir| dir.is_dir() && fs::canonicalize(dir).map(|dir| dir != shared_dir).unwrap_or(true))
+                .collect();
+            if duplicates.is_empty() {
+                info!("= Step 4: No per-worktree store checkouts found");
+            } else {
+                for duplicate in &duplicates {
+                    error!("⚠️ Warning: {} holds its own store checkouts; run `git trunk doctor` to remove the duplicates", duplicate.display());
+                }
+            }
+            info!("✅ Store checkouts are shared across worktrees");
+        }
+        WorktreeAction::Unshare => {
+            // Step 3: Remove the canonical checkout directory from the config
+            match trunk_config.get_string("trunk", "shared_trunk_dir") {
+                None => info!("= Step 3: Store checkouts are not shared, nothing to do"),
+                Some(shared_dir) => {
+                    debug!("📝 Step 3: Removing shared_trunk_dir from config");
+                    config::set_value(&trunk_config, "trunk", "shared_trunk_dir", None).map_err(|e| TrunkError::Git(format!("Failed to update config: {}", e)))?;
+                    info!("✓ Step 3: Worktrees use their own .trunk again; existing checkouts stay in {}", shared_dir);
+                    info!("✅ Run `git trunk checkout` in each linked worktree to get its own copy of a store");
+                }
+            }
+        }
+    }
+    Ok(())
+}
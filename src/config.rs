@@ -0,0 +1,886 @@
+use std::env;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use log::debug;
+use crate::utils::{run_git_command, git_common_dir, shared_repository, apply_shared_permissions, PromptMode};
+use crate::git::GitRepo;
+
+// Repository-level configuration lives in the common git dir so it is shared by
+// every worktree and never shows up as an untracked file in the main repository.
+pub const CONFIG_FILE_NAME: &str = "trunk.toml";
+
+// `.trunk/config.toml` of the worktree is read instead when the common git dir
+// has no trunk.toml, for repositories that keep their settings next to the
+// store checkouts.
+pub const TRUNK_DIR_CONFIG_FILE_NAME: &str = "config.toml";
+
+// User-level configuration, shared by every repository: `config.toml` in the
+// git-trunk home, `$GIT_TRUNK_HOME` when set, otherwise `$XDG_CONFIG_HOME/git-trunk`,
+// `%APPDATA%\git-trunk` on Windows or `~/.config/git-trunk`. It is loaded
+// beneath the repository's file, whose keys win.
+pub const HOME_ENV: &str = "GIT_TRUNK_HOME";
+pub const USER_CONFIG_FILE_NAME: &str = "config.toml";
+const HOME_DIR_NAME: &str = "git-trunk";
+
+pub const DEFAULT_REMOTE: &str = "origin";
+pub const DEFAULT_STORE: &str = "main";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueKind {
+    String,
+    Bool,
+    Integer,
+    Array,
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ValueKind::String => "a string",
+            ValueKind::Bool => "a boolean",
+            ValueKind::Integer => "an integer",
+            ValueKind::Array => "an array of strings",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Known keys of the `[trunk]` section.
+pub const TRUNK_KEYS: &[(&str, ValueKind)] = &[
+    ("remote", ValueKind::String),
+    ("store", ValueKind::String),
+    ("hook_min_interval", ValueKind::Integer), // seconds between hook-run invocations per event
+    ("offline", ValueKind::Bool), // skip network operations in hooks
+    ("shared_trunk_dir", ValueKind::String), // one .trunk checkout shared by all worktrees
+    ("auto_push", ValueKind::Bool), // push the store after every `git trunk commit`
+    ("stage", ValueKind::String), // default of `[store.<name>] stage`
+    ("prompt", ValueKind::String), // "ask" (default), "yes" or "no" for confirmation prompts
+    ("foreign_refs", ValueKind::Array), // refs under refs/trunk/ of other tooling, accepted on first run
+    ("hooks", ValueKind::Array), // hook events run for every store checked out in .trunk
+    ("remote_cache_ttl", ValueKind::Integer), // seconds `status` and `info` reuse a cached listing of the remote's stores
+    ("notify", ValueKind::String), // "off" (default), "bell", "desktop" or "both" when a long command finishes
+    ("notify_after", ValueKind::Integer), // seconds a command must run before it notifies
+    ("notify_commands", ValueKind::Array), // commands that notify, default DEFAULT_NOTIFY_COMMANDS
+    ("auto_restore", ValueKind::Bool), // hooks recreate checkouts `git stash --all` or `git clean -x` removed
+    ("mirrors", ValueKind::Array), // git remotes holding copies of the remote's stores, checked by `git trunk mirrors`
+    ("cache_dir", ValueKind::String), // absolute directory for caches instead of $XDG_CACHE_HOME/git-trunk, user config only
+];
+
+// `[trunk]` keys that describe one repository, so the user config cannot set them.
+pub const REPO_ONLY_KEYS: [&str; 4] = ["shared_trunk_dir", "foreign_refs", "hooks", "mirrors"];
+
+// `[trunk]` keys only the user config can set.
+pub const USER_ONLY_KEYS: [&str; 1] = ["cache_dir"];
+
+// Known keys of the `[user]` section: the identity locks, TRUNKOWNERS and
+// ABOUT.md record, instead of git's user.name and user.email.
+pub const USER_KEYS: &[(&str, ValueKind)] = &[
+    ("name", ValueKind::String),
+    ("email", ValueKind::String),
+];
+
+// Known keys of each `[store.<name>]` section.
+pub const STORE_KEYS: &[(&str, ValueKind)] = &[
+    ("remote", ValueKind::String),
+    ("hooks", ValueKind::Array), // hook events handled by `git trunk hook-run`
+    ("trigger_branches", ValueKind::Array), // main-repo branch globs whose push also pushes the store
+    ("dictionaries", ValueKind::Array), // word lists (relative to the store checkout) for lint-prose
+    ("lint_prose", ValueKind::Bool), // lint changed prose files on commit
+    ("board_field", ValueKind::String), // frontmatter field grouping `git trunk board` columns
+    ("board_columns", ValueKind::Array), // board columns, in display order
+    ("index", ValueKind::String), // index file regenerated on every commit
+    ("max_size", ValueKind::String), // store size quota, e.g. "500MB"
+    ("max_size_policy", ValueKind::String), // "block" (default) or "warn" when a commit exceeds max_size
+    ("expose_branch", ValueKind::String), // local branch kept pointing at refs/trunk/<store>
+    ("export_branch", ValueKind::String), // branch `git trunk export-branch` publishes the store as
+    ("auto_push", ValueKind::Bool), // overrides `[trunk] auto_push` for this store
+    ("stage", ValueKind::String), // what `git trunk commit` stages: "ask" (default), "all" or "tracked"
+];
+
+// Known keys of each `[remote.<name>]` section, named after a git remote.
+pub const REMOTE_KEYS: &[(&str, ValueKind)] = &[
+    ("review_ref", ValueKind::String), // ref `push` targets instead of refs/trunk/<store>, e.g. "refs/for/refs/trunk/{store}"
+];
+
+pub const PROMPT_VALUES: [&str; 3] = ["ask", "yes", "no"];
+
+pub const STAGE_VALUES: [&str; 3] = ["ask", "all", "tracked"];
+
+pub const NOTIFY_VALUES: [&str; 4] = ["off", "bell", "desktop", "both"];
+
+// The commands that may run for minutes on large stores.
+pub const DEFAULT_NOTIFY_COMMANDS: [&str; 4] = ["checkout", "push", "pull", "fetch"];
+
+pub const DEFAULT_NOTIFY_AFTER: u64 = 10;
+
+// How a finished command gets the user's attention.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotifyMode {
+    Off,
+    // The terminal bell
+    Bell,
+    // A desktop notification
+    Desktop,
+    Both,
+}
+
+impl NotifyMode {
+    pub fn parse(value: &str) -> Option<NotifyMode> {
+        match value {
+            "off" => Some(NotifyMode::Off),
+            "bell" => Some(NotifyMode::Bell),
+            "desktop" => Some(NotifyMode::Desktop),
+            "both" => Some(NotifyMode::Both),
+            _ => None,
+        }
+    }
+}
+
+// What `git trunk commit` stages in a store checkout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StagePolicy {
+    // Everything, after confirming unless --force
+    Ask,
+    // Everything, without asking
+    All,
+    // Changes to tracked files only, without asking; untracked files are never added
+    Tracked,
+}
+
+pub const DEFAULT_TRIGGER_BRANCHES: [&str; 2] = ["main", "master"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    String(String),
+    Bool(bool),
+    Integer(i64),
+    Array(Vec<String>),
+}
+
+impl ConfigValue {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            ConfigValue::String(_) => ValueKind::String,
+            ConfigValue::Bool(_) => ValueKind::Bool,
+            ConfigValue::Integer(_) => ValueKind::Integer,
+            ConfigValue::Array(_) => ValueKind::Array,
+        }
+    }
+}
+
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigValue::String(s) => write!(f, "\"{}\"", s),
+            ConfigValue::Bool(b) => write!(f, "{}", b),
+            ConfigValue::Integer(i) => write!(f, "{}", i),
+            ConfigValue::Array(items) => {
+                let quoted: Vec<String> = items.iter().map(|item| format!("\"{}\"", item)).collect();
+                write!(f, "[{}]", quoted.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigEntry {
+    pub section: String,
+    pub key: String,
+    pub value: ConfigValue,
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct TrunkConfig {
+    pub path: PathBuf,
+    pub exists: bool,
+    pub sections: Vec<(String, usize)>, // (section name, line number)
+    pub entries: Vec<ConfigEntry>,
+    // True for the user config in the git-trunk home
+    pub is_user: bool,
+    // The user config beneath a repository's, read by `get` for keys the
+    // repository's file does not set
+    pub user: Option<Box<TrunkConfig>>,
+}
+
+impl TrunkConfig {
+    // Stores are declared as `[store.<name>]` sections.
+    pub fn store_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (section, _) in &self.sections {
+            if let Some(name) = section.strip_prefix("store.") {
+                if !names.contains(&name.to_string()) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names
+    }
+
+    pub fn section_line(&self, section: &str) -> Option<usize> {
+        self.sections.iter().find(|(s, _)| s == section).map(|(_, line)| *line)
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&ConfigEntry> {
+        self.get_local(section, key).or_else(|| self.user.as_ref()?.get(section, key))
+    }
+
+    // The key as set in this file, ignoring the user config beneath it.
+    pub fn get_local(&self, section: &str, key: &str) -> Option<&ConfigEntry> {
+        self.entries.iter().find(|entry| entry.section == section && entry.key == key)
+    }
+
+    pub fn get_string(&self, section: &str, key: &str) -> Option<String> {
+        match self.get(section, key).map(|entry| &entry.value) {
+            Some(ConfigValue::String(s)) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn get_bool(&self, section: &str, key: &str) -> Option<bool> {
+        match self.get(section, key).map(|entry| &entry.value) {
+            Some(ConfigValue::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn get_integer(&self, section: &str, key: &str) -> Option<i64> {
+        match self.get(section, key).map(|entry| &entry.value) {
+            Some(ConfigValue::Integer(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn get_array(&self, section: &str, key: &str) -> Vec<String> {
+        match self.get(section, key).map(|entry| &entry.value) {
+            Some(ConfigValue::Array(items)) => items.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Stores whose `hooks` list contains the given hook event.
+    pub fn stores_with_hook(&self, event: &str) -> Vec<String> {
+        self.store_names()
+            .into_iter()
+            .filter(|store| self.get_array(&format!("store.{}", store), "hooks").iter().any(|e| e == event))
+            .collect()
+    }
+
+    // Default store: `[trunk] store`, falling back to "main".
+    pub fn default_store(&self) -> String {
+        self.get_string("trunk", "store").unwrap_or_else(|| DEFAULT_STORE.to_string())
+    }
+
+    // Remote explicitly configured for a store: `[store.<name>] remote`, then `[trunk] remote`.
+    pub fn configured_remote_for_store(&self, store_name: &str) -> Option<String> {
+        self.get_string(&format!("store.{}", store_name), "remote")
+            .or_else(|| self.get_string("trunk", "remote"))
+    }
+
+    // Remote for a store, falling back to "origin".
+    pub fn remote_for_store(&self, store_name: &str) -> String {
+        self.configured_remote_for_store(store_name).unwrap_or_else(|| DEFAULT_REMOTE.to_string())
+    }
+
+    // Where `push` sends the store on `remote_name` when that remote takes
+    // pushes for review: `[remote.<name>] review_ref` with `{store}` filled in.
+    pub fn review_ref(&self, remote_name: &str, store_name: &str) -> Option<String> {
+        self.get_string(&format!("remote.{}", remote_name), "review_ref").map(|review_ref| review_ref.replace("{store}", store_name))
+    }
+
+    // Whether `git trunk commit` pushes the store: `[store.<name>] auto_push`,
+    // then `[trunk] auto_push`, off by default.
+    pub fn auto_push(&self, store_name: &str) -> bool {
+        self.get_bool(&format!("store.{}", store_name), "auto_push")
+            .or_else(|| self.get_bool("trunk", "auto_push"))
+            .unwrap_or(false)
+    }
+
+    // The store's `stage` policy, falling back to `[trunk] stage` and then "ask".
+    pub fn stage_policy(&self, store_name: &str) -> StagePolicy {
+        match self.get_string(&format!("store.{}", store_name), "stage").or_else(|| self.get_string("trunk", "stage")).as_deref() {
+            Some("all") => StagePolicy::All,
+            Some("tracked") => StagePolicy::Tracked,
+            _ => StagePolicy::Ask,
+        }
+    }
+
+    // `[trunk] notify`, off by default.
+    pub fn notify_mode(&self) -> NotifyMode {
+        self.get_string("trunk", "notify").as_deref().and_then(NotifyMode::parse).unwrap_or(NotifyMode::Off)
+    }
+
+    // Whether `command` (e.g. "checkout") notifies when `[trunk] notify` is on.
+    pub fn notifies(&self, command: &str) -> bool {
+        if self.get("trunk", "notify_commands").is_some() {
+            self.get_array("trunk", "notify_commands").iter().any(|name| name == command)
+        } else {
+            DEFAULT_NOTIFY_COMMANDS.contains(&command)
+        }
+    }
+
+    // How long a command runs before it notifies: `[trunk] notify_after` seconds.
+    pub fn notify_after(&self) -> u64 {
+        self.get_integer("trunk", "notify_after").and_then(|seconds| u64::try_from(seconds).ok()).unwrap_or(DEFAULT_NOTIFY_AFTER)
+    }
+
+    // How confirmation prompts are answered: `[trunk] prompt`, "ask" by default.
+    pub fn prompt_mode(&self) -> PromptMode {
+        match self.get_string("trunk", "prompt").as_deref() {
+            Some("yes") => PromptMode::Yes,
+            Some("no") => PromptMode::No,
+            _ => PromptMode::Ask,
+        }
+    }
+
+    // `[trunk] mirrors`: git remotes that should hold the same stores as the
+    // remote, leaving out `remote_name` itself.
+    pub fn mirrors(&self, remote_name: &str) -> Vec<String> {
+        let mut mirrors = self.get_array("trunk", "mirrors");
+        mirrors.retain(|mirror| mirror != remote_name);
+        mirrors.dedup();
+        mirrors
+    }
+
+    // Main-repo branch globs (e.g. "release/*") whose push triggers pushing the store.
+    pub fn trigger_branches(&self, store_name: &str) -> Vec<String> {
+        let section = format!("store.{}", store_name);
+        if self.get(&section, "trigger_branches").is_some() {
+            self.get_array(&section, "trigger_branches")
+        } else {
+            DEFAULT_TRIGGER_BRANCHES.iter().map(|b| b.to_string()).collect()
+        }
+    }
+
+    // Directory holding the store checkouts: `[trunk] shared_trunk_dir` when
+    // worktrees share one checkout, otherwise `.trunk` in the given worktree.
+    pub fn trunk_base_dir(&self, repo_root: &Path) -> PathBuf {
+        self.get_string("trunk", "shared_trunk_dir")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| repo_root.join(".trunk"))
+    }
+
+    // `[alias] <name>`: the words a command alias stands for.
+    pub fn alias(&self, name: &str) -> Option<Vec<String>> {
+        self.get_string("alias", name).map(|value| value.split_whitespace().map(str::to_string).collect())
+    }
+}
+
+// The git-trunk home holding the user config, None when no variable names one.
+pub fn trunk_home() -> Option<PathBuf> {
+    let absolute = |name: &str| env::var_os(name).map(PathBuf::from).filter(|dir| dir.is_absolute());
+    absolute(HOME_ENV)
+        .or_else(|| absolute("XDG_CONFIG_HOME").map(|dir| dir.join(HOME_DIR_NAME)))
+        .or_else(|| if cfg!(windows) { absolute("APPDATA").map(|dir| dir.join(HOME_DIR_NAME)) } else { None })
+        .or_else(|| absolute("HOME").map(|home| home.join(".config").join(HOME_DIR_NAME)))
+}
+
+pub fn user_config_path() -> Option<PathBuf> {
+    trunk_home().map(|home| home.join(USER_CONFIG_FILE_NAME))
+}
+
+// Loads and validates the user config alone. No home or no file yields an
+// empty config.
+pub fn load_user() -> io::Result<TrunkConfig> {
+    let config = load_user_unvalidated()?;
+    check_problems(&config, "git trunk config validate --user")?;
+    Ok(config)
+}
+
+pub fn load_user_unvalidated() -> io::Result<TrunkConfig> {
+    let Some(path) = user_config_path() else {
+        debug!("🔍 No git-trunk home: none of {}, XDG_CONFIG_HOME or HOME is set", HOME_ENV);
+        return Ok(TrunkConfig { is_user: true, ..Default::default() });
+    };
+    let mut config = read_config_file(path)?;
+    config.is_user = true;
+    Ok(config)
+}
+
+// Resolves the store checkout directory for a worktree, falling back to its
+// own `.trunk` if the config cannot be read.
+pub fn trunk_base_dir(repo_root: &Path, verbose: bool) -> PathBuf {
+    match load(repo_root, verbose) {
+        Ok(config) => config.trunk_base_dir(repo_root),
+        Err(e) => {
+            debug!("⚠️ Could not read config, using {}: {}", repo_root.join(".trunk").display(), e);
+            repo_root.join(".trunk")
+        }
+    }
+}
+
+pub fn config_path(repo_root: &Path, verbose: bool) -> io::Result<PathBuf> {
+    let common_config = git_common_dir(repo_root, verbose)?.join(CONFIG_FILE_NAME);
+    let trunk_dir_config = trunk_dir_config_path(repo_root);
+    if !common_config.exists() && trunk_dir_config.is_file() {
+        debug!("🔍 No {}, using {}", common_config.display(), trunk_dir_config.display());
+        return Ok(trunk_dir_config);
+    }
+    Ok(common_config)
+}
+
+pub fn trunk_dir_config_path(repo_root: &Path) -> PathBuf {
+    repo_root.join(".trunk").join(TRUNK_DIR_CONFIG_FILE_NAME)
+}
+
+// Loads and validates the config. A missing file yields an empty config; an
+// invalid one is an error carrying the file and line of the first problem.
+// The user config beneath it is checked too.
+pub fn load(repo_root: &Path, verbose: bool) -> io::Result<TrunkConfig> {
+    let config = load_unvalidated(repo_root, verbose)?;
+    check_problems(&config, "git trunk config validate")?;
+    if let Some(user) = &config.user {
+        check_problems(user, "git trunk config validate --user")?;
+    }
+    Ok(config)
+}
+
+// The repository's config with the user config beneath it.
+pub fn load_unvalidated(repo_root: &Path, verbose: bool) -> io::Result<TrunkConfig> {
+    let mut config = read_config_file(config_path(repo_root, verbose)?)?;
+    config.user = Some(Box::new(load_user_unvalidated()?));
+    Ok(config)
+}
+
+fn read_config_file(path: PathBuf) -> io::Result<TrunkConfig> {
+    if !path.exists() {
+        debug!("🔍 No config file found at {}", path.display());
+        return Ok(TrunkConfig { path, ..Default::default() });
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| io::Error::new(e.kind(), format!("Failed to read {}: {}", path.display(), e)))?;
+    let mut config = parse(&content, &path)?;
+    config.exists = true;
+    Ok(config)
+}
+
+fn check_problems(config: &TrunkConfig, validate_command: &str) -> io::Result<()> {
+    let problems = validate(config);
+    if let Some(first) = problems.first() {
+        let more = if problems.len() > 1 { format!(" (and {} more, run `{}`)", problems.len() - 1, validate_command) } else { String::new() };
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{}{}", first, more)));
+    }
+    Ok(())
+}
+
+// Parses the small TOML subset git-trunk understands: `[section]` headers and
+// `key = value` lines where value is a string, boolean, integer or array of strings.
+pub fn parse(content: &str, path: &Path) -> io::Result<TrunkConfig> {
+    let mut config = TrunkConfig { path: path.to_path_buf(), ..Default::default() };
+    let mut current_section = String::new();
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, format!("{}:{}: {}", path.display(), line_no, msg));
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| invalid(format!("unterminated section header '{}'", line)))?
+                .trim();
+            if name.is_empty() {
+                return Err(invalid("empty section header".to_string()));
+            }
+            current_section = name.to_string();
+            config.sections.push((current_section.clone(), line_no));
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("expected 'key = value', found '{}'", line)))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(invalid("missing key before '='".to_string()));
+        }
+        if current_section.is_empty() {
+            return Err(invalid(format!("key '{}' must appear inside a [section]", key)));
+        }
+        let value = parse_value(value.trim()).map_err(invalid)?;
+        config.entries.push(ConfigEntry {
+            section: current_section.clone(),
+            key: key.to_string(),
+            value,
+            line: line_no,
+        });
+    }
+    Ok(config)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(raw: &str) -> Result<ConfigValue, String> {
+    if raw == "true" {
+        return Ok(ConfigValue::Bool(true));
+    }
+    if raw == "false" {
+        return Ok(ConfigValue::Bool(false));
+    }
+    if let Some(inner) = raw.strip_prefix('[') {
+        let inner = inner.strip_suffix(']').ok_or_else(|| format!("unterminated array '{}'", raw))?;
+        let mut items = Vec::new();
+        for item in inner.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            match parse_value(item)? {
+                ConfigValue::String(s) => items.push(s),
+                _ => return Err(format!("arrays may only contain strings, found '{}'", item)),
+            }
+        }
+        return Ok(ConfigValue::Array(items));
+    }
+    if let Some(inner) = raw.strip_prefix('"') {
+        let inner = inner.strip_suffix('"').ok_or_else(|| format!("unterminated string '{}'", raw))?;
+        return Ok(ConfigValue::String(inner.to_string()));
+    }
+    raw.parse::<i64>()
+        .map(ConfigValue::Integer)
+        .map_err(|_| format!("unrecognized value '{}' (strings must be quoted)", raw))
+}
+
+// Why `name` cannot name a store, if it cannot. A store name is both the one
+// component after refs/trunk/ and a directory under .trunk, so it follows
+// git's rules for a ref component (`git check-ref-format`) and may not hold
+// whitespace or path separators.
+pub fn check_store_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("it is empty".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| c.is_whitespace() || c.is_control() || "/\\~^:?*[".contains(*c)) {
+        return Err(format!("it contains {:?}", c));
+    }
+    if name.starts_with('.') || name.ends_with('.') || name.contains("..") {
+        return Err("it starts or ends with '.' or contains '..'".to_string());
+    }
+    if name.ends_with(".lock") || name.contains("@{") || name == "@" {
+        return Err("git does not allow it in a ref name".to_string());
+    }
+    Ok(())
+}
+
+pub fn is_store_name(name: &str) -> bool {
+    check_store_name(name).is_ok()
+}
+
+// Checks every section and key against the known schema. Returns one
+// "file:line: message" string per problem, in file order.
+pub fn validate(config: &TrunkConfig) -> Vec<String> {
+    let mut problems: Vec<(usize, String)> = Vec::new();
+    let location = |line: usize| format!("{}:{}", config.path.display(), line);
+
+    for (section, line) in &config.sections {
+        if section == "trunk" || section == "user" || section == "alias" {
+            continue;
+        }
+        if section.strip_prefix("remote.").is_some_and(|name| !name.is_empty()) {
+            continue;
+        }
+        match section.strip_prefix("store.") {
+            Some(name) if is_store_name(name) => {}
+            Some(name) => problems.push((*line, format!("{}: invalid store name '{}' in section header", location(*line), name))),
+            None => {
+                let hint = suggest(section.split('.').next().unwrap_or(section), ["trunk", "user", "alias", "store", "remote"].into_iter())
+                    .map(|s| if ["trunk", "user", "alias"].contains(&s) { format!(" (did you mean '[{}]'?)", s) } else { format!(" (did you mean '[{}.<name>]'?)", s) })
+                    .unwrap_or_default();
+                problems.push((*line, format!("{}: unknown section '[{}]'{}", location(*line), section, hint)));
+            }
+        }
+    }
+
+    for (index, entry) in config.entries.iter().enumerate() {
+        if section_keys(&entry.section).is_none() && entry.section != "alias" {
+            continue; // Unknown section already reported
+        }
+        if let Err(problem) = check_entry(&entry.section, &entry.key, &entry.value) {
+            problems.push((entry.line, format!("{}: {}", location(entry.line), problem)));
+        }
+        if let Err(problem) = check_scope(&entry.section, &entry.key, config.is_user) {
+            problems.push((entry.line, format!("{}: {}", location(entry.line), problem)));
+        }
+        if let Some(first) = config.entries[..index].iter().find(|e| e.section == entry.section && e.key == entry.key) {
+            problems.push((entry.line, format!("{}: duplicate key '{}' in [{}] (first set on line {})", location(entry.line), entry.key, entry.section, first.line)));
+        }
+    }
+
+    problems.sort_by_key(|(line, _)| *line);
+    problems.into_iter().map(|(_, message)| message).collect()
+}
+
+fn section_keys(section: &str) -> Option<&'static [(&'static str, ValueKind)]> {
+    if section == "trunk" {
+        Some(TRUNK_KEYS)
+    } else if section == "user" {
+        Some(USER_KEYS)
+    } else if section.starts_with("store.") {
+        Some(STORE_KEYS)
+    } else if section.starts_with("remote.") {
+        Some(REMOTE_KEYS)
+    } else {
+        None
+    }
+}
+
+// The kind of a known key, or an "unknown key" message with a "did you mean"
+// hint. `section` must be `trunk`, `user`, `alias`, `store.<name>` or `remote.<name>`.
+// Any word names an alias.
+pub fn key_kind(section: &str, key: &str) -> Result<ValueKind, String> {
+    if section == "alias" {
+        if key.is_empty() || key.starts_with('-') || key.contains(|c: char| c.is_whitespace()) {
+            return Err(format!("'{}' in [alias] is not a command name", key));
+        }
+        return Ok(ValueKind::String);
+    }
+    let known_keys = section_keys(section).ok_or_else(|| format!("unknown section '[{}]'", section))?;
+    match known_keys.iter().find(|(known, _)| *known == key) {
+        Some((_, kind)) => Ok(*kind),
+        None => {
+            let hint = suggest(key, known_keys.iter().map(|(key, _)| *key))
+                .map(|s| format!(" (did you mean '{}'?)", s))
+                .unwrap_or_default();
+            Err(format!("unknown key '{}' in [{}]{}", key, section, hint))
+        }
+    }
+}
+
+// Checks one key and value against the schema.
+pub fn check_entry(section: &str, key: &str, value: &ConfigValue) -> Result<(), String> {
+    let kind = key_kind(section, key)?;
+    if kind != value.kind() {
+        return Err(format!("'{}' in [{}] must be {}, found {}", key, section, kind, value.kind()));
+    }
+    if let (("trunk", "prompt"), ConfigValue::String(prompt)) = ((section, key), value) {
+        if !PROMPT_VALUES.contains(&prompt.as_str()) {
+            return Err(format!("'prompt' in [trunk] must be one of \"ask\", \"yes\" or \"no\", found \"{}\"", prompt));
+        }
+    }
+    if let (("trunk", "notify"), ConfigValue::String(notify)) = ((section, key), value) {
+        if !NOTIFY_VALUES.contains(&notify.as_str()) {
+            return Err(format!("'notify' in [trunk] must be one of \"off\", \"bell\", \"desktop\" or \"both\", found \"{}\"", notify));
+        }
+    }
+    if let ("stage", ConfigValue::String(stage)) = (key, value) {
+        if !STAGE_VALUES.contains(&stage.as_str()) {
+            return Err(format!("'stage' in [{}] must be one of \"ask\", \"all\" or \"tracked\", found \"{}\"", section, stage));
+        }
+    }
+    if let ("review_ref", ConfigValue::String(review_ref)) = (key, value) {
+        if !review_ref.starts_with("refs/") || review_ref.replace("{store}", "").contains(['{', '}']) {
+            return Err(format!("'review_ref' in [{}] must be a full ref name starting with \"refs/\" where only {{store}} is replaced, found \"{}\"", section, review_ref));
+        }
+    }
+    if let (("trunk", "cache_dir"), ConfigValue::String(cache_dir)) = ((section, key), value) {
+        if !Path::new(cache_dir).is_absolute() {
+            return Err(format!("'cache_dir' in [trunk] must be an absolute path, found \"{}\"", cache_dir));
+        }
+    }
+    if let ("alias", ConfigValue::String(words)) = (section, value) {
+        if words.trim().is_empty() {
+            return Err(format!("alias '{}' is empty", key));
+        }
+    }
+    if let (("trunk", "store"), ConfigValue::String(store)) = ((section, key), value) {
+        check_store_name(store).map_err(|reason| format!("'store' in [trunk] is not a valid store name, {}: \"{}\"", reason, store))?;
+    }
+    Ok(())
+}
+
+// Checks that a key belongs in the repository's config or in the user config.
+pub fn check_scope(section: &str, key: &str, is_user: bool) -> Result<(), String> {
+    if is_user && section.starts_with("store.") {
+        return Err(format!("[{}] belongs to one repository; stores cannot be configured in the user config", section));
+    }
+    if is_user && section == "trunk" && REPO_ONLY_KEYS.contains(&key) {
+        return Err(format!("'{}' in [trunk] describes one repository; set it in .git/trunk.toml instead", key));
+    }
+    if !is_user && section == "trunk" && USER_ONLY_KEYS.contains(&key) {
+        return Err(format!("'{}' in [trunk] applies to every repository; set it with `git trunk config set --user trunk.{}`", key, key));
+    }
+    Ok(())
+}
+
+// Parses a value given on the command line (`git trunk config set`) as the
+// kind its key expects: strings need no quotes and arrays are comma-separated.
+pub fn parse_cli_value(kind: ValueKind, raw: &str) -> Result<ConfigValue, String> {
+    if raw.contains('"') {
+        return Err(format!("values cannot contain '\"', found '{}'", raw));
+    }
+    match kind {
+        ValueKind::String => Ok(ConfigValue::String(raw.to_string())),
+        ValueKind::Bool => match raw {
+            "true" => Ok(ConfigValue::Bool(true)),
+            "false" => Ok(ConfigValue::Bool(false)),
+            _ => Err(format!("expected true or false, found '{}'", raw)),
+        },
+        ValueKind::Integer => raw.parse::<i64>().map(ConfigValue::Integer).map_err(|_| format!("expected an integer, found '{}'", raw)),
+        ValueKind::Array => Ok(ConfigValue::Array(raw.split(',').map(str::trim).filter(|item| !item.is_empty()).map(str::to_string).collect())),
+    }
+}
+
+// Returns the closest candidate within a small edit distance, used for
+// "did you mean" hints on mistyped keys and sections.
+fn suggest<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (input.len() / 3).max(1);
+    candidates
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let cost = if a_char == *b_char { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b_chars.len()]
+}
+
+pub fn add_store_section(config: &TrunkConfig, store_name: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&config.path)
+        .map_err(|e| io::Error::new(e.kind(), format!("Failed to open {} for writing: {}", config.path.display(), e)))?;
+    if config.exists {
+        writeln!(file)?;
+    }
+    writeln!(file, "[store.{}]", store_name)?;
+    share_config_file(config)
+}
+
+// Sets (or with None, removes) a key in place, keeping the rest of the file,
+// including comments, untouched. The config must be freshly loaded so that its
+// line numbers match the file on disk.
+pub fn set_value(config: &TrunkConfig, section: &str, key: &str, value: Option<&ConfigValue>) -> io::Result<()> {
+    if config.is_user && !config.exists {
+        if let Some(home) = config.path.parent() {
+            fs::create_dir_all(home).map_err(|e| io::Error::new(e.kind(), format!("Failed to create {}: {}", home.display(), e)))?;
+        }
+    }
+    let content = if config.exists { fs::read_to_string(&config.path)? } else { String::new() };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let new_line = value.map(|v| format!("{} = {}", key, v));
+
+    match (config.get_local(section, key).map(|entry| entry.line), new_line) {
+        (Some(line), Some(new_line)) => lines[line - 1] = new_line,
+        (Some(line), None) => {
+            lines.remove(line - 1);
+        }
+        (None, Some(new_line)) => match config.section_line(section) {
+            Some(header_line) => {
+                let last_line = config.entries.iter()
+                    .filter(|entry| entry.section == section)
+                    .map(|entry| entry.line)
+                    .max()
+                    .unwrap_or(header_line);
+                lines.insert(last_line, new_line);
+            }
+            None => {
+                if !lines.is_empty() {
+                    lines.push(String::new());
+                }
+                lines.push(format!("[{}]", section));
+                lines.push(new_line);
+            }
+        },
+        (None, None) => return Ok(()),
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    fs::write(&config.path, updated)
+        .map_err(|e| io::Error::new(e.kind(), format!("Failed to write {}: {}", config.path.display(), e)))?;
+    share_config_file(config)
+}
+
+// Renames every `[old]` header to `[new]`, keeping the keys and comments under
+// it. Like set_value, the config must be freshly loaded.
+pub fn rename_section(config: &TrunkConfig, old: &str, new: &str) -> io::Result<()> {
+    if !config.exists || config.section_line(old).is_none() {
+        return Ok(());
+    }
+    let content = fs::read_to_string(&config.path)?;
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    for (section, line) in &config.sections {
+        if section == old {
+            lines[line - 1] = format!("[{}]", new);
+        }
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    fs::write(&config.path, updated)
+        .map_err(|e| io::Error::new(e.kind(), format!("Failed to write {}: {}", config.path.display(), e)))?;
+    share_config_file(config)
+}
+
+// Gives a newly written config file the permissions core.sharedRepository asks for.
+fn share_config_file(config: &TrunkConfig) -> io::Result<()> {
+    if config.is_user {
+        return Ok(());
+    }
+    let git_dir = config.path.parent().unwrap_or(Path::new("."));
+    apply_shared_permissions(&config.path, shared_repository(git_dir, false))
+}
+
+// Resolves the effective remote, store and prompt mode for this invocation.
+// Explicit command-line values win; otherwise the config supplies defaults.
+// Outside a git repository the built-in defaults are used.
+pub fn resolve_defaults(cli_remote: Option<&str>, cli_store: Option<&str>, verbose: bool) -> io::Result<(String, String, PromptMode)> {
+    let config = match GitRepo::discover(verbose) {
+        Ok(repo) => load(repo.root(), verbose)?,
+        Err(_) => load_user()?,
+    };
+    let store_name = cli_store.map(str::to_string).unwrap_or_else(|| config.default_store());
+    let remote_name = match cli_remote {
+        Some(remote) => absolutize_path_remote(remote, verbose),
+        None => config.remote_for_store(&store_name),
+    };
+    Ok((remote_name, store_name, config.prompt_mode()))
+}
+
+// A remote given on the command line as a relative path (`--remote ../backup.git`)
+// means a path relative to where the user is, but git resolves it against the
+// repository root that every command runs in. Such paths are made absolute.
+// Remote names, URLs (`file://`, `https://`, `ssh://`) and scp-like
+// `host:path` addresses are returned unchanged.
+fn absolutize_path_remote(remote: &str, verbose: bool) -> String {
+    let is_url = remote.contains("://");
+    let is_scp_like = remote.find(':').is_some_and(|colon| colon > 1 && !remote[..colon].contains('/'));
+    let path = Path::new(remote);
+    if is_url || is_scp_like || path.is_absolute() || !path.exists() {
+        return remote.to_string();
+    }
+    let is_remote_name = run_git_command(Command::new("git").arg("config").arg("--get").arg(format!("remote.{}.url", remote)), verbose)
+        .is_ok_and(|output| output.status.success());
+    if is_remote_name {
+        return remote.to_string();
+    }
+    match fs::canonicalize(path) {
+        Ok(absolute) => {
+            debug!("Resolved remote path '{}' to {}", remote, absolute.display());
+            absolute.to_string_lossy().to_string()
+        }
+        Err(_) => remote.to_string(),
+    }
+}
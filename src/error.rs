@@ -0,0 +1,57 @@
+use std::io;
+use thiserror::Error;
+
+// Error returned by every command. main() renders it once and exits with the
+// code of its kind, so scripts can tell a missing store from a failed git call.
+#[derive(Debug, Error)]
+pub enum TrunkError {
+    // Generic failure (exit code 1)
+    #[error("{0}")]
+    Failed(String),
+    // Invalid arguments or input (exit code 2, like clap's own usage errors)
+    #[error("{0}")]
+    Usage(String),
+    // A store, ref, file or remote that does not exist (exit code 3)
+    #[error("{0}")]
+    NotFound(String),
+    // Uncommitted changes, divergence, conflicts or a quota stand in the way (exit code 4)
+    #[error("{0}")]
+    Conflict(String),
+    // A git command failed or could not be run (exit code 5)
+    #[error("{0}")]
+    Git(String),
+    // Reading or writing a file failed (exit code 6)
+    #[error("{0}")]
+    Io(String),
+    // .git/trunk.toml is invalid (exit code 7)
+    #[error("{0}")]
+    Config(String),
+    // The git directory is not writable (exit code 8)
+    #[error("{0}")]
+    ReadOnly(String),
+    // The environment lacks a work tree, an editor or FUSE support the command needs (exit code 9)
+    #[error("{0}")]
+    Unsupported(String),
+}
+
+impl TrunkError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            TrunkError::Failed(_) => 1,
+            TrunkError::Usage(_) => 2,
+            TrunkError::NotFound(_) => 3,
+            TrunkError::Conflict(_) => 4,
+            TrunkError::Git(_) => 5,
+            TrunkError::Io(_) => 6,
+            TrunkError::Config(_) => 7,
+            TrunkError::ReadOnly(_) => 8,
+            TrunkError::Unsupported(_) => 9,
+        }
+    }
+}
+
+impl From<io::Error> for TrunkError {
+    fn from(e: io::Error) -> Self {
+        TrunkError::Io(e.to_string())
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use crate::json::Json;
+
+// A frontmatter value. Only the YAML subset documents commonly use is
+// understood: scalars, inline `[a, b]` lists, block `- item` lists and
+// `|`/`>` block scalars. Nested mappings are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    List(Vec<String>),
+}
+
+impl Value {
+    pub fn to_json(&self) -> Json {
+        match self {
+            Value::Null => Json::Null,
+            Value::Bool(value) => Json::Bool(*value),
+            Value::Number(value) => Json::Number(*value),
+            Value::String(value) => Json::String(value.clone()),
+            Value::List(items) => Json::Array(items.iter().map(|item| Json::String(item.clone())).collect()),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => Ok(()),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Number(value) if value.fract() == 0.0 && value.abs() < 1e15 => write!(f, "{}", *value as i64),
+            Value::Number(value) => write!(f, "{}", value),
+            Value::String(value) => write!(f, "{}", value),
+            Value::List(items) => write!(f, "{}", items.join(", ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Frontmatter {
+    pub fields: Vec<(String, Value)>,
+}
+
+impl Frontmatter {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, value)| value)
+    }
+}
+
+// Lines of the frontmatter block: (index of the opening `---`, index of the
+// closing `---`/`...`), or None when the document has no frontmatter.
+fn block_bounds(lines: &[&str]) -> Option<(usize, usize)> {
+    if lines.first().map(|line| line.trim_end()) != Some("---") {
+        return None;
+    }
+    let end = lines.iter().skip(1).position(|line| matches!(line.trim_end(), "---" | "..."))? + 1;
+    Some((0, end))
+}
+
+pub fn parse(text: &str) -> Option<Frontmatter> {
+    let lines: Vec<&str> = text.lines().collect();
+    let (start, end) = block_bounds(&lines)?;
+    let body = &lines[start + 1..end];
+
+    let mut fields: Vec<(String, Value)> = Vec::new();
+    let mut i = 0;
+    while i < body.len() {
+        let line = body[i];
+        i += 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') || line.starts_with(' ') || line.starts_with('\t') {
+            continue;
+        }
+        let Some((key, raw)) = line.split_once(':') else { continue };
+        let key = unquote(key.trim());
+        let raw = strip_comment(raw.trim());
+
+        // Indented continuation lines belonging to this key
+        let mut continuation: Vec<&str> = Vec::new();
+        while i < body.len() && (body[i].starts_with(' ') || body[i].starts_with('\t') || body[i].starts_with("- ") || body[i].trim().is_empty()) {
+            continuation.push(body[i]);
+            i += 1;
+        }
+
+        let value = if raw == "|" || raw == ">" || raw.starts_with("|-") || raw.starts_with(">-") {
+            let parts: Vec<&str> = continuation.iter().map(|l| l.trim()).collect();
+            let joined = if raw.starts_with('|') { parts.join("\n") } else { parts.join(" ") };
+            Value::String(joined.trim().to_string())
+        } else if raw.is_empty() {
+            let items: Vec<String> = continuation.iter()
+                .filter_map(|l| l.trim_start().strip_prefix("- ").or_else(|| (l.trim() == "-").then_some("")))
+                .map(|item| unquote(strip_comment(item.trim())))
+                .collect();
+            if items.is_empty() { Value::Null } else { Value::List(items) }
+        } else {
+            parse_scalar_or_list(raw)
+        };
+        fields.retain(|(k, _)| *k != key);
+        fields.push((key, value));
+    }
+    Some(Frontmatter { fields })
+}
+
+fn parse_scalar_or_list(raw: &str) -> Value {
+    if let Some(inner) = raw.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+        return Value::List(split_inline_list(inner).into_iter().map(|item| unquote(item.trim())).filter(|item| !item.is_empty()).collect());
+    }
+    if raw.starts_with('"') || raw.starts_with('\'') {
+        return Value::String(unquote(raw));
+    }
+    match raw.to_lowercase().as_str() {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        "null" | "~" => return Value::Null,
+        _ => {}
+    }
+    match raw.parse::<f64>() {
+        Ok(number) if !raw.starts_with('+') && !raw.ends_with('.') => Value::Number(number),
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+// Splits `a, "b, c", d` on commas outside quotes.
+fn split_inline_list(inner: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for c in inner.chars() {
+        match (c, quote) {
+            ('"' | '\'', None) => { quote = Some(c); current.push(c); }
+            (c, Some(q)) if c == q => { quote = None; current.push(c); }
+            (',', None) => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+    items
+}
+
+fn strip_comment(raw: &str) -> &str {
+    if raw.starts_with('"') || raw.starts_with('\'') {
+        return raw;
+    }
+    match raw.find(" #") {
+        Some(pos) => raw[..pos].trim_end(),
+        None => raw,
+    }
+}
+
+fn unquote(raw: &str) -> String {
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        let inner = &raw[1..raw.len() - 1];
+        let mut out = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some(other) => out.push(other),
+                    None => out.push('\\'),
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    } else if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
+        raw[1..raw.len() - 1].replace("''", "'")
+    } else {
+        raw.to_string()
+    }
+}
+
+// Formats a string as a YAML scalar, quoting it when it would otherwise be
+// read back as something else.
+pub fn format_scalar(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value.trim() == value
+        && !value.starts_with(['"', '\'', '[', '{', '-', '#', '&', '*', '!', '|', '>', '@', '`', '%'])
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && matches!(parse_scalar_or_list(value), Value::String(_));
+    if plain {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+// Sets a top-level frontmatter key to a scalar, replacing its previous value
+// (including any continuation lines) or appending it to the block. A
+// document without frontmatter gets a new block.
+pub fn set_field(text: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{}: {}", key, format_scalar(value));
+    let lines: Vec<&str> = text.lines().collect();
+    let trailing_newline = text.ends_with('\n') || text.is_empty();
+    let Some((start, end)) = block_bounds(&lines) else {
+        return format!("---\n{}\n---\n{}", new_line, text);
+    };
+
+    let mut out: Vec<String> = lines[..=start].iter().map(|l| l.to_string()).collect();
+    let mut replaced = false;
+    let mut i = start + 1;
+    while i < end {
+        let line = lines[i];
+        let is_key_line = !line.starts_with([' ', '\t']) && line.split_once(':').is_some_and(|(k, _)| unquote(k.trim()) == key);
+        if is_key_line && !replaced {
+            out.push(new_line.clone());
+            replaced = true;
+            i += 1;
+            while i < end && (lines[i].starts_with([' ', '\t']) || lines[i].starts_with("- ")) {
+                i += 1;
+            }
+            continue;
+        }
+        out.push(line.to_string());
+        i += 1;
+    }
+    if !replaced {
+        out.push(new_line);
+    }
+    out.extend(lines[end..].iter().map(|l| l.to_string()));
+    let mut result = out.join("\n");
+    if trailing_newline {
+        result.push('\n');
+    }
+    result
+}
+
+// The document after its frontmatter block.
+pub fn body(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let body_start = block_bounds(&lines).map(|(_, end)| end + 1).unwrap_or(0);
+    lines[body_start.min(lines.len())..].join("\n")
+}
+
+// A document's title: its frontmatter `title`, else its first `# ` heading.
+pub fn title(text: &str) -> Option<String> {
+    if let Some(Value::String(title)) = parse(text).as_ref().and_then(|frontmatter| frontmatter.get("title")) {
+        if !title.trim().is_empty() {
+            return Some(title.trim().to_string());
+        }
+    }
+    body(text).lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|heading| heading.trim().to_string())
+        .filter(|heading| !heading.is_empty())
+}
@@ -0,0 +1,263 @@
+// A read-only FUSE file system serving the files of one commit, for
+// `git trunk mount`, on top of the `fuser` crate. The directory tree is read
+// once with `git ls-tree`; a file's blob is read with `git cat-file` when the
+// file is first read, and only the last blob read is kept in memory.
+// Built only with the `fuse` feature.
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use fuser::{
+    Config, Errno, FileAttr, FileHandle, FileType, Filesystem, Generation, INodeNo, LockOwner, MountOption, OpenFlags,
+    ReplyAttr, ReplyData, ReplyDirectory, ReplyEntry, ReplyStatfs, Request, Session,
+};
+use log::debug;
+use crate::git::GitRepo;
+use crate::utils::{read_blobs, run_git_command};
+
+// Attributes and names never change under a mounted commit
+const TTL: Duration = Duration::from_secs(3600);
+const BLOCK_SIZE: u32 = 4096;
+
+enum Kind {
+    // Entries by name, in tree order
+    Dir(Vec<(Vec<u8>, u64)>),
+    File { object: String, executable: bool },
+    Symlink { object: String },
+}
+
+struct Node {
+    parent: u64,
+    size: u64,
+    kind: Kind,
+}
+
+// The files of a commit: node `ino` is `nodes[ino - 1]`, the root being 1 as
+// FUSE expects.
+pub struct Tree {
+    nodes: Vec<Node>,
+    time: SystemTime,
+}
+
+impl Tree {
+    pub fn load(repo: &GitRepo, commit: &str, verbose: bool) -> io::Result<Tree> {
+        let time = run_git_command(repo.git().arg("log").arg("-1").arg("--format=%ct").arg(commit), verbose)?;
+        let time = String::from_utf8_lossy(&time.stdout).trim().parse().unwrap_or(0);
+        let output = run_git_command(repo.git().arg("ls-tree").arg("-r").arg("-t").arg("-l").arg("-z").arg(commit), verbose)?;
+        if !output.status.success() {
+            return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        let root = INodeNo::ROOT.0;
+        let mut tree = Tree {
+            nodes: vec![Node { parent: root, size: 0, kind: Kind::Dir(Vec::new()) }],
+            time: UNIX_EPOCH + Duration::from_secs(time),
+        };
+        let mut dirs: HashMap<Vec<u8>, u64> = HashMap::from([(Vec::new(), root)]);
+        // "<mode> <type> <object> <size>\t<path>", each directory before its entries
+        for entry in output.stdout.split(|b| *b == 0).filter(|entry| !entry.is_empty()) {
+            let Some(tab) = entry.iter().position(|b| *b == b'\t') else { continue };
+            let header = String::from_utf8_lossy(&entry[..tab]);
+            let path = &entry[tab + 1..];
+            let fields: Vec<&str> = header.split_whitespace().collect();
+            let [mode, _, object, size] = fields[..] else { continue };
+            let (dir, name) = match path.iter().rposition(|b| *b == b'/') {
+                Some(slash) => (&path[..slash], &path[slash + 1..]),
+                None => (&path[..0], path),
+            };
+            let Some(&parent) = dirs.get(dir) else { continue };
+            let size = size.parse().unwrap_or(0);
+            let kind = match mode {
+                // Submodules show up as empty directories
+                "040000" | "160000" => Kind::Dir(Vec::new()),
+                "120000" => Kind::Symlink { object: object.to_string() },
+                _ => Kind::File { object: object.to_string(), executable: mode == "100755" },
+            };
+            let ino = tree.nodes.len() as u64 + 1;
+            if matches!(kind, Kind::Dir(_)) {
+                dirs.insert(path.to_vec(), ino);
+            }
+            tree.nodes.push(Node { parent, size, kind });
+            if let Kind::Dir(entries) = &mut tree.nodes[parent as usize - 1].kind {
+                entries.push((name.to_vec(), ino));
+            }
+        }
+        debug!("Read {} entries of {}", tree.nodes.len() - 1, commit);
+        Ok(tree)
+    }
+
+    fn node(&self, ino: u64) -> Option<&Node> {
+        self.nodes.get((ino as usize).checked_sub(1)?)
+    }
+
+    fn lookup(&self, parent: u64, name: &[u8]) -> Option<u64> {
+        match &self.node(parent)?.kind {
+            Kind::Dir(entries) => entries.iter().find(|(entry, _)| entry == name).map(|(_, ino)| *ino),
+            _ => None,
+        }
+    }
+}
+
+// A mounted commit. Its files and directories belong to the owner of the
+// mount point and carry the commit time.
+pub struct TrunkFs {
+    tree: Tree,
+    repo_root: PathBuf,
+    uid: u32,
+    gid: u32,
+    verbose: bool,
+    // The object and content of the last blob read, so that reading a file
+    // in chunks runs `git cat-file` once
+    last_blob: Mutex<Option<(String, Arc<Vec<u8>>)>>,
+}
+
+impl TrunkFs {
+    fn attr(&self, ino: u64, node: &Node) -> FileAttr {
+        let (kind, perm, nlink) = match node.kind {
+            Kind::Dir(_) => (FileType::Directory, 0o555, 2),
+            Kind::File { executable: true, .. } => (FileType::RegularFile, 0o555, 1),
+            Kind::File { .. } => (FileType::RegularFile, 0o444, 1),
+            Kind::Symlink { .. } => (FileType::Symlink, 0o777, 1),
+        };
+        FileAttr {
+            ino: INodeNo(ino),
+            size: node.size,
+            blocks: node.size.div_ceil(512),
+            atime: self.tree.time,
+            mtime: self.tree.time,
+            ctime: self.tree.time,
+            crtime: self.tree.time,
+            kind,
+            perm,
+            nlink,
+            uid: self.uid,
+            gid: self.gid,
+            rdev: 0,
+            flags: 0,
+            blksize: BLOCK_SIZE,
+        }
+    }
+
+    fn blob(&self, object: &str) -> Result<Arc<Vec<u8>>, Errno> {
+        let mut last_blob = self.last_blob.lock().map_err(|_| Errno::EIO)?;
+        if let Some((last, content)) = last_blob.as_ref() {
+            if last == object {
+                return Ok(Arc::clone(content));
+            }
+        }
+        let content = match read_blobs(&self.repo_root, &[object.to_string()], self.verbose) {
+            Ok(mut blobs) => blobs.pop().flatten().ok_or(Errno::EIO)?,
+            Err(e) => {
+                debug!("Failed to read blob {}: {}", object, e);
+                return Err(Errno::EIO);
+            }
+        };
+        let content = Arc::new(content);
+        *last_blob = Some((object.to_string(), Arc::clone(&content)));
+        Ok(content)
+    }
+}
+
+impl Filesystem for TrunkFs {
+    fn lookup(&self, _req: &Request, parent: INodeNo, name: &OsStr, reply: ReplyEntry) {
+        match self.tree.lookup(parent.0, name.as_bytes()).and_then(|ino| Some((ino, self.tree.node(ino)?))) {
+            Some((ino, node)) => reply.entry(&TTL, &self.attr(ino, node), Generation(0)),
+            None => reply.error(Errno::ENOENT),
+        }
+    }
+
+    fn getattr(&self, _req: &Request, ino: INodeNo, _fh: Option<FileHandle>, reply: ReplyAttr) {
+        match self.tree.node(ino.0) {
+            Some(node) => reply.attr(&TTL, &self.attr(ino.0, node)),
+            None => reply.error(Errno::ENOENT),
+        }
+    }
+
+    fn readlink(&self, _req: &Request, ino: INodeNo, reply: ReplyData) {
+        match self.tree.node(ino.0).map(|node| &node.kind) {
+            Some(Kind::Symlink { object }) => match self.blob(object) {
+                Ok(target) => reply.data(&target),
+                Err(errno) => reply.error(errno),
+            },
+            Some(_) => reply.error(Errno::EINVAL),
+            None => reply.error(Errno::ENOENT),
+        }
+    }
+
+    fn read(
+        &self,
+        _req: &Request,
+        ino: INodeNo,
+        _fh: FileHandle,
+        offset: u64,
+        size: u32,
+        _flags: OpenFlags,
+        _lock_owner: Option<LockOwner>,
+        reply: ReplyData,
+    ) {
+        let object = match self.tree.node(ino.0).map(|node| &node.kind) {
+            Some(Kind::File { object, .. }) => object,
+            Some(Kind::Dir(_)) => return reply.error(Errno::EISDIR),
+            Some(Kind::Symlink { .. }) => return reply.error(Errno::EINVAL),
+            None => return reply.error(Errno::ENOENT),
+        };
+        match self.blob(object) {
+            Ok(content) => {
+                let start = usize::try_from(offset).unwrap_or(usize::MAX).min(content.len());
+                let end = start.saturating_add(size as usize).min(content.len());
+                reply.data(&content[start..end]);
+            }
+            Err(errno) => reply.error(errno),
+        }
+    }
+
+    fn readdir(&self, _req: &Request, ino: INodeNo, _fh: FileHandle, offset: u64, mut reply: ReplyDirectory) {
+        let Some(node) = self.tree.node(ino.0) else { return reply.error(Errno::ENOENT) };
+        let Kind::Dir(entries) = &node.kind else { return reply.error(Errno::ENOTDIR) };
+        let dots = [(b".".as_slice(), ino.0), (b"..".as_slice(), node.parent)];
+        let entries = dots.into_iter().chain(entries.iter().map(|(name, ino)| (name.as_slice(), *ino)));
+        for (i, (name, entry_ino)) in entries.enumerate().skip(offset as usize) {
+            let kind = match self.tree.node(entry_ino).map(|node| &node.kind) {
+                Some(Kind::File { .. }) => FileType::RegularFile,
+                Some(Kind::Symlink { .. }) => FileType::Symlink,
+                _ => FileType::Directory,
+            };
+            // An entry's offset is where the next readdir picks up
+            if reply.add(INodeNo(entry_ino), (i + 1) as u64, kind, OsStr::from_bytes(name)) {
+                break;
+            }
+        }
+        reply.ok();
+    }
+
+    fn statfs(&self, _req: &Request, _ino: INodeNo, reply: ReplyStatfs) {
+        let blocks = self.tree.nodes.iter().map(|node| node.size.div_ceil(BLOCK_SIZE as u64)).sum();
+        reply.statfs(blocks, 0, 0, self.tree.nodes.len() as u64, 0, BLOCK_SIZE, 255, BLOCK_SIZE);
+    }
+}
+
+// Mounts `tree` read-only on `mountpoint`. `Session::run` then answers
+// requests until the directory is unmounted.
+pub fn mount(tree: Tree, repo: &GitRepo, mountpoint: &Path, verbose: bool) -> io::Result<Session<TrunkFs>> {
+    let owner = fs::metadata(mountpoint)?;
+    let filesystem = TrunkFs {
+        tree,
+        repo_root: repo.root().to_path_buf(),
+        uid: owner.uid(),
+        gid: owner.gid(),
+        verbose,
+        last_blob: Mutex::new(None),
+    };
+    let mut config = Config::default();
+    config.mount_options = vec![
+        MountOption::RO,
+        MountOption::FSName("git-trunk".to_string()),
+        MountOption::Subtype("git-trunk".to_string()),
+    ];
+    Session::new(filesystem, mountpoint, &config)
+}
@@ -0,0 +1,548 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use git2::{ErrorCode, Oid, Repository, RepositoryOpenFlags, Status, StatusOptions};
+use log::debug;
+use crate::utils::{dry_run, run_git_command};
+
+// A git repository (the main one or a store checkout). Reads and writes of
+// refs, revision lookups, status and fetches between repositories on disk go
+// through libgit2. Repositories libgit2 cannot open (SHA-256 object format,
+// unsupported extensions) fall back to running `git`, as do operations
+// libgit2 does not cover: network transports and their credential helpers,
+// commits that should honor hooks and signing config, merges and checkouts.
+// Ref updates, deletions and fetches are only reported under --dry-run.
+pub struct GitRepo {
+    root: PathBuf,
+    repo: Option<Repository>,
+    verbose: bool,
+}
+
+// The outcome of `GitRepo::commit_revert`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Revert {
+    Committed(String),
+    // The changes are already undone
+    Unchanged,
+    // Files changed since that the revert would have to merge
+    Conflicts(Vec<String>),
+}
+
+// An index entry left unmerged by a merge, cherry-pick, revert, rebase or
+// stash pop: the blob of each stage that exists (the common ancestor, ours
+// and theirs). A missing side means that side deleted the path or never had it.
+#[derive(Debug, Clone)]
+pub struct UnmergedPath {
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+fn to_io(e: git2::Error) -> io::Error {
+    let kind = if e.code() == ErrorCode::NotFound { io::ErrorKind::NotFound } else { io::ErrorKind::Other };
+    io::Error::new(kind, e.message().to_string())
+}
+
+fn checked(output: std::process::Output, what: &str) -> io::Result<std::process::Output> {
+    if output.status.success() {
+        return Ok(output);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(io::Error::other(if stderr.is_empty() { format!("{} failed", what) } else { stderr }))
+}
+
+// Two-letter `git status --porcelain` code for a libgit2 status.
+fn porcelain_code(status: Status) -> String {
+    if status.is_conflicted() {
+        return "UU".to_string();
+    }
+    if status.is_wt_new() && !status.intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED | Status::INDEX_RENAMED | Status::INDEX_TYPECHANGE) {
+        return "??".to_string();
+    }
+    let index = if status.is_index_new() {
+        'A'
+    } else if status.is_index_modified() {
+        'M'
+    } else if status.is_index_deleted() {
+        'D'
+    } else if status.is_index_renamed() {
+        'R'
+    } else if status.is_index_typechange() {
+        'T'
+    } else {
+        ' '
+    };
+    let worktree = if status.is_wt_modified() {
+        'M'
+    } else if status.is_wt_deleted() {
+        'D'
+    } else if status.is_wt_renamed() {
+        'R'
+    } else if status.is_wt_typechange() {
+        'T'
+    } else {
+        ' '
+    };
+    format!("{}{}", index, worktree)
+}
+
+impl GitRepo {
+    // Opens the repository whose working tree is exactly `path`, without
+    // searching parent directories (a missing store checkout must not resolve
+    // to the main repository around it).
+    pub fn open(path: &Path, verbose: bool) -> io::Result<GitRepo> {
+        match Repository::open(path) {
+            Ok(repo) => Ok(GitRepo { root: path.to_path_buf(), repo: Some(repo), verbose }),
+            Err(e) if e.code() == ErrorCode::NotFound => Err(to_io(e)),
+            Err(e) => {
+                debug!("⚠️ libgit2 cannot open {} ({}), using git", path.display(), e.message());
+                if !path.join(".git").exists() {
+                    return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} is not a git repository", path.display())));
+                }
+                Ok(GitRepo { root: path.to_path_buf(), repo: None, verbose })
+            }
+        }
+    }
+
+    // The repository containing the current directory, rooted at the top of
+    // its working tree (like `git rev-parse --show-toplevel`), or at the git
+    // directory of a bare repository, which only commands working on refs
+    // can use (see `capabilities`). Like git, the search stops at the
+    // directories listed in GIT_CEILING_DIRECTORIES.
+    pub fn discover(verbose: bool) -> io::Result<GitRepo> {
+        let cwd = std::env::current_dir()?;
+        let ceilings = std::env::var_os("GIT_CEILING_DIRECTORIES").unwrap_or_default();
+        match Repository::open_ext(&cwd, RepositoryOpenFlags::empty(), std::env::split_paths(&ceilings)) {
+            Ok(repo) => {
+                let root = repo.workdir().unwrap_or(repo.path()).components().collect::<PathBuf>();
+                Ok(GitRepo { root, repo: Some(repo), verbose })
+            }
+            Err(e) if e.code() == ErrorCode::NotFound => Err(io::Error::new(io::ErrorKind::NotFound, "not a git repository (or any of the parent directories)")),
+            Err(e) => {
+                debug!("⚠️ libgit2 cannot open the repository ({}), using git", e.message());
+                let bare = run_git_command(Command::new("git").arg("rev-parse").arg("--is-bare-repository"), verbose)?;
+                let top = if String::from_utf8_lossy(&bare.stdout).trim() == "true" { "--absolute-git-dir" } else { "--show-toplevel" };
+                let output = checked(run_git_command(Command::new("git").arg("rev-parse").arg(top), verbose)?, "git rev-parse")?;
+                let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                if root.is_empty() {
+                    return Err(io::Error::other("Git repository root is empty"));
+                }
+                Ok(GitRepo { root: PathBuf::from(root), repo: None, verbose })
+            }
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // Whether the repository has no working tree, as on a hosting server.
+    pub fn is_bare(&self) -> bool {
+        match &self.repo {
+            Some(repo) => repo.is_bare(),
+            None => self
+                .run(self.git().arg("rev-parse").arg("--is-bare-repository"), "git rev-parse")
+                .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "true"),
+        }
+    }
+
+    // A `git` command running in this repository, for what libgit2 does not cover.
+    pub fn git(&self) -> Command {
+        let mut command = Command::new("git");
+        command.current_dir(&self.root);
+        command
+    }
+
+    fn run(&self, command: &mut Command, what: &str) -> io::Result<std::process::Output> {
+        checked(run_git_command(command, self.verbose)?, what)
+    }
+
+    // Like `run`, writing `input` to the command's stdin.
+    fn run_with_input(&self, command: &mut Command, input: &[u8], what: &str) -> io::Result<std::process::Output> {
+        let mut child = command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+        child.stdin.take().ok_or_else(|| io::Error::other(format!("{} stdin unavailable", what)))?.write_all(input)?;
+        checked(child.wait_with_output()?, what)
+    }
+
+    // The common git directory, shared by all worktrees, as an absolute path.
+    pub fn common_dir(&self) -> io::Result<PathBuf> {
+        if let Some(repo) = &self.repo {
+            let common_dir = repo.commondir();
+            return Ok(if common_dir.is_absolute() { common_dir.to_path_buf() } else { self.root.join(common_dir) });
+        }
+        let output = self.run(self.git().arg("rev-parse").arg("--git-common-dir"), "git rev-parse")?;
+        let git_dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        Ok(if git_dir.is_absolute() { git_dir } else { self.root.join(git_dir) })
+    }
+
+    // Full object name of the commit `rev` points to, or None if it does not
+    // resolve to a commit.
+    pub fn resolve_commit(&self, rev: &str) -> Option<String> {
+        if let Some(repo) = &self.repo {
+            return repo.revparse_single(rev).and_then(|object| object.peel_to_commit()).map(|commit| commit.id().to_string()).ok();
+        }
+        let output = self.run(self.git().arg("rev-parse").arg("--verify").arg("--quiet").arg(format!("{}^{{commit}}", rev)), "git rev-parse").ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|hash| !hash.is_empty())
+    }
+
+    pub fn ref_exists(&self, name: &str) -> bool {
+        if let Some(repo) = &self.repo {
+            return repo.find_reference(name).is_ok();
+        }
+        self.run(self.git().arg("show-ref").arg("--verify").arg("--quiet").arg(name), "git show-ref").is_ok()
+    }
+
+    // Points `name` at the commit `hash`, creating the ref if needed.
+    pub fn update_ref(&self, name: &str, hash: &str) -> io::Result<()> {
+        if dry_run(&format!("point {} at {} in {}", name, hash, self.root.display())) {
+            return Ok(());
+        }
+        if let Some(repo) = &self.repo {
+            let oid = Oid::from_str(hash).map_err(to_io)?;
+            return repo.reference(name, oid, true, "git-trunk: update").map(|_| ()).map_err(to_io);
+        }
+        self.run(self.git().arg("update-ref").arg(name).arg(hash), "git update-ref").map(|_| ())
+    }
+
+    // Deletes `name`; a ref that does not exist is not an error.
+    pub fn delete_ref(&self, name: &str) -> io::Result<()> {
+        if self.ref_exists(name) && dry_run(&format!("delete {} in {}", name, self.root.display())) {
+            return Ok(());
+        }
+        if let Some(repo) = &self.repo {
+            return match repo.find_reference(name) {
+                Ok(mut reference) => reference.delete().map_err(to_io),
+                Err(e) if e.code() == ErrorCode::NotFound => Ok(()),
+                Err(e) => Err(to_io(e)),
+            };
+        }
+        if !self.ref_exists(name) {
+            return Ok(());
+        }
+        self.run(self.git().arg("update-ref").arg("-d").arg(name), "git update-ref").map(|_| ())
+    }
+
+    // Names under `prefix` (e.g. "refs/trunk/"), with the prefix stripped.
+    pub fn ref_names(&self, prefix: &str) -> io::Result<Vec<String>> {
+        if let Some(repo) = &self.repo {
+            let mut names = Vec::new();
+            for reference in repo.references_glob(&format!("{}*", prefix)).map_err(to_io)? {
+                if let Some(name) = reference.map_err(to_io)?.name().and_then(|name| name.strip_prefix(prefix)) {
+                    names.push(name.to_string());
+                }
+            }
+            names.sort();
+            return Ok(names);
+        }
+        let output = self.run(self.git().arg("for-each-ref").arg("--format=%(refname)").arg(prefix), "git for-each-ref")?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.strip_prefix(prefix))
+            .map(str::to_string)
+            .collect())
+    }
+
+    // Changed, staged and untracked paths as `git status --porcelain` lines.
+    pub fn status_porcelain(&self) -> io::Result<Vec<String>> {
+        if let Some(repo) = &self.repo {
+            let mut options = StatusOptions::new();
+            options.include_untracked(true).include_ignored(false).renames_head_to_index(true);
+            let statuses = repo.statuses(Some(&mut options)).map_err(to_io)?;
+            return Ok(statuses
+                .iter()
+                .filter(|entry| entry.status() != Status::CURRENT && !entry.status().is_ignored())
+                .map(|entry| format!("{} {}", porcelain_code(entry.status()), entry.path().unwrap_or_default()))
+                .collect());
+        }
+        let output = self.run(self.git().arg("status").arg("--porcelain"), "git status")?;
+        Ok(String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect())
+    }
+
+    // Paths with unresolved merge conflicts.
+    pub fn conflicted_paths(&self) -> io::Result<Vec<String>> {
+        if let Some(repo) = &self.repo {
+            let statuses = repo.statuses(None).map_err(to_io)?;
+            return Ok(statuses
+                .iter()
+                .filter(|entry| entry.status().is_conflicted())
+                .filter_map(|entry| entry.path().map(str::to_string))
+                .collect());
+        }
+        let output = self.run(self.git().arg("diff").arg("--name-only").arg("--diff-filter=U"), "git diff")?;
+        Ok(String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect())
+    }
+
+    // Unmerged index entries, one per path, in index order.
+    pub fn unmerged_paths(&self) -> io::Result<Vec<UnmergedPath>> {
+        if let Some(repo) = &self.repo {
+            let index = repo.index().map_err(to_io)?;
+            let mut unmerged = Vec::new();
+            for conflict in index.conflicts().map_err(to_io)? {
+                let conflict = conflict.map_err(to_io)?;
+                let Some(path) = [&conflict.ancestor, &conflict.our, &conflict.their].into_iter().flatten().next().map(|entry| String::from_utf8_lossy(&entry.path).to_string()) else { continue };
+                let blob = |entry: &Option<git2::IndexEntry>| entry.as_ref().map(|entry| entry.id.to_string());
+                unmerged.push(UnmergedPath { path, base: blob(&conflict.ancestor), ours: blob(&conflict.our), theirs: blob(&conflict.their) });
+            }
+            return Ok(unmerged);
+        }
+        // "<mode> <blob> <stage>\t<path>" per existing stage, grouped by path
+        let output = self.run(self.git().arg("ls-files").arg("--unmerged").arg("-z"), "git ls-files")?;
+        let mut unmerged: Vec<UnmergedPath> = Vec::new();
+        for record in String::from_utf8_lossy(&output.stdout).split('\0').filter(|record| !record.is_empty()) {
+            let Some((info, path)) = record.split_once('\t') else { continue };
+            let fields: Vec<&str> = info.split_whitespace().collect();
+            let [_, blob, stage] = fields[..] else { continue };
+            if unmerged.last().is_none_or(|last| last.path != path) {
+                unmerged.push(UnmergedPath { path: path.to_string(), base: None, ours: None, theirs: None });
+            }
+            let entry = unmerged.last_mut().expect("pushed above");
+            match stage {
+                "1" => entry.base = Some(blob.to_string()),
+                "2" => entry.ours = Some(blob.to_string()),
+                "3" => entry.theirs = Some(blob.to_string()),
+                _ => {}
+            }
+        }
+        Ok(unmerged)
+    }
+
+    // Commits on `ours` not on `theirs`, and the other way around.
+    pub fn ahead_behind(&self, ours: &str, theirs: &str) -> Option<(usize, usize)> {
+        if let Some(repo) = &self.repo {
+            let ours = Oid::from_str(ours).ok()?;
+            let theirs = Oid::from_str(theirs).ok()?;
+            return repo.graph_ahead_behind(ours, theirs).ok();
+        }
+        let output = self.run(self.git().arg("rev-list").arg("--left-right").arg("--count").arg(format!("{}...{}", ours, theirs)), "git rev-list").ok()?;
+        let counts = String::from_utf8_lossy(&output.stdout).to_string();
+        let mut parts = counts.split_whitespace().filter_map(|count| count.parse().ok());
+        Some((parts.next()?, parts.next()?))
+    }
+
+    // Whether the commit `ancestor` is reachable from `descendant` (or is it).
+    pub fn is_ancestor(&self, ancestor: &str, descendant: &str) -> bool {
+        if let Some(repo) = &self.repo {
+            let oid = |rev: &str| self.resolve_commit(rev).and_then(|hash| Oid::from_str(&hash).ok());
+            let (Some(ancestor), Some(descendant)) = (oid(ancestor), oid(descendant)) else { return false };
+            return ancestor == descendant || repo.graph_descendant_of(descendant, ancestor).unwrap_or(false);
+        }
+        self.run(self.git().arg("merge-base").arg("--is-ancestor").arg(ancestor).arg(descendant), "git merge-base").is_ok()
+    }
+
+    // Writes a commit whose tree holds the single file `name` with `content`,
+    // on top of `parent` if given, and returns its hash. No ref is changed.
+    pub fn commit_single_file(&self, name: &str, content: &[u8], parent: Option<&str>, message: &str) -> io::Result<String> {
+        if let Some(repo) = &self.repo {
+            let blob = repo.blob(content).map_err(to_io)?;
+            let mut tree = repo.treebuilder(None).map_err(to_io)?;
+            tree.insert(name, blob, 0o100644).map_err(to_io)?;
+            let tree = repo.find_tree(tree.write().map_err(to_io)?).map_err(to_io)?;
+            let signature = repo.signature().map_err(to_io)?;
+            let parent = parent.map(|hash| Oid::from_str(hash).and_then(|oid| repo.find_commit(oid))).transpose().map_err(to_io)?;
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            return repo.commit(None, &signature, &signature, message, &tree, &parents).map(|oid| oid.to_string()).map_err(to_io);
+        }
+        let blob = self.run_with_input(self.git().arg("hash-object").arg("-w").arg("--stdin"), content, "git hash-object")?;
+        let tree_entry = format!("100644 blob {}\t{}\n", String::from_utf8_lossy(&blob.stdout).trim(), name);
+        let tree = self.run_with_input(self.git().arg("mktree"), tree_entry.as_bytes(), "git mktree")?;
+        let mut commit_tree = self.git();
+        commit_tree.arg("commit-tree").arg(String::from_utf8_lossy(&tree.stdout).trim()).arg("-m").arg(message);
+        if let Some(parent) = parent {
+            commit_tree.arg("-p").arg(parent);
+        }
+        let commit = self.run(&mut commit_tree, "git commit-tree")?;
+        Ok(String::from_utf8_lossy(&commit.stdout).trim().to_string())
+    }
+
+    // Writes a commit on top of `parent` whose tree is the parent's with each
+    // `(path, Some(content))` added or replaced and each `(path, None)` file or
+    // directory removed, and returns its hash, or None when the tree would not
+    // change. Runs `git` on a temporary index, so neither a checkout nor the
+    // main repository's index is touched, and changes no ref.
+    pub fn commit_tree_changes(&self, parent: &str, changes: &[(String, Option<Vec<u8>>)], message: &str) -> io::Result<Option<String>> {
+        let index = self.common_dir()?.join(format!("trunk-index-{}", std::process::id()));
+        let git = || {
+            let mut command = self.git();
+            command.env("GIT_INDEX_FILE", &index).env("GIT_LITERAL_PATHSPECS", "1");
+            command
+        };
+        let result = (|| {
+            self.run(git().arg("read-tree").arg(parent), "git read-tree")?;
+            for (path, content) in changes {
+                match content {
+                    Some(content) => {
+                        let blob = self.run_with_input(git().arg("hash-object").arg("-w").arg("--stdin"), content, "git hash-object")?;
+                        let blob = String::from_utf8_lossy(&blob.stdout).trim().to_string();
+                        self.run(git().arg("update-index").arg("--add").arg("--cacheinfo").arg(format!("100644,{},{}", blob, path)), "git update-index")?;
+                    }
+                    None => {
+                        // Every file under the path, whatever the main worktree holds there
+                        let files = self.run(git().arg("ls-files").arg("-z").arg("--full-name").arg("--").arg(path), "git ls-files")?;
+                        self.run_with_input(git().arg("update-index").arg("--force-remove").arg("-z").arg("--stdin"), &files.stdout, "git update-index")?;
+                    }
+                }
+            }
+            let tree = self.run(git().arg("write-tree"), "git write-tree")?;
+            let tree = String::from_utf8_lossy(&tree.stdout).trim().to_string();
+            let parent_tree = self.run(self.git().arg("rev-parse").arg(format!("{}^{{tree}}", parent)), "git rev-parse")?;
+            if String::from_utf8_lossy(&parent_tree.stdout).trim() == tree {
+                return Ok(None);
+            }
+            let commit = self.run(self.git().arg("commit-tree").arg(&tree).arg("-p").arg(parent).arg("-m").arg(message), "git commit-tree")?;
+            Ok(Some(String::from_utf8_lossy(&commit.stdout).trim().to_string()))
+        })();
+        let _ = std::fs::remove_file(&index);
+        result
+    }
+
+    // Writes a root commit holding every file under the directory `dir`
+    // (honoring .gitignore files in it) and returns its hash, or None when
+    // there is nothing to commit. Like `commit_tree_changes` it uses a
+    // temporary index, so it works without a checkout, also in bare
+    // repositories, and changes no ref.
+    pub fn commit_directory(&self, dir: &Path, message: &str) -> io::Result<Option<String>> {
+        let git_dir = self.common_dir()?;
+        let index = git_dir.join(format!("trunk-index-{}", std::process::id()));
+        let result = (|| {
+            let mut add = Command::new("git");
+            add.current_dir(dir).env("GIT_DIR", &git_dir).env("GIT_WORK_TREE", dir).env("GIT_INDEX_FILE", &index).arg("add").arg("-A").arg(".");
+            self.run(&mut add, "git add")?;
+            let files = self.run(self.git().env("GIT_INDEX_FILE", &index).arg("ls-files"), "git ls-files")?;
+            if files.stdout.is_empty() {
+                return Ok(None);
+            }
+            let tree = self.run(self.git().env("GIT_INDEX_FILE", &index).arg("write-tree"), "git write-tree")?;
+            let tree = String::from_utf8_lossy(&tree.stdout).trim().to_string();
+            let commit = self.run(self.git().arg("commit-tree").arg(&tree).arg("-m").arg(message), "git commit-tree")?;
+            Ok(Some(String::from_utf8_lossy(&commit.stdout).trim().to_string()))
+        })();
+        let _ = std::fs::remove_file(&index);
+        result
+    }
+
+    // Rewrites the whole history of `tip` so that its root commits get `base`
+    // as their parent, keeping every tree, author, committer, date and message,
+    // and returns the new tip with the number of commits rewritten. Merges
+    // keep their shape; signatures are dropped since they no longer match.
+    // Changes no ref.
+    pub fn graft_history(&self, tip: &str, base: &str) -> io::Result<(String, usize)> {
+        let commits = self.run(self.git().arg("rev-list").arg("--topo-order").arg("--reverse").arg("--parents").arg(tip), "git rev-list")?;
+        let mut rewritten: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        let mut new_tip = base.to_string();
+        for line in String::from_utf8_lossy(&commits.stdout).lines() {
+            let mut hashes = line.split(' ');
+            let Some(commit) = hashes.next() else { continue };
+            let parents: Vec<&str> = hashes.map(|parent| rewritten.get(parent).map(String::as_str).unwrap_or(parent)).collect();
+            let raw = self.run(self.git().arg("cat-file").arg("commit").arg(commit), "git cat-file")?.stdout;
+            let split = raw.windows(2).position(|pair| pair == b"\n\n").map(|at| at + 1).unwrap_or(raw.len());
+            let (headers, message) = raw.split_at(split);
+            let mut object = Vec::with_capacity(raw.len());
+            let mut in_signature = false;
+            for header in headers.split_inclusive(|&byte| byte == b'\n') {
+                // Signature headers continue on lines starting with a space
+                if in_signature && header.starts_with(b" ") {
+                    continue;
+                }
+                in_signature = header.starts_with(b"gpgsig");
+                if in_signature || header.starts_with(b"parent ") {
+                    continue;
+                }
+                object.extend_from_slice(header);
+                if header.starts_with(b"tree ") {
+                    for parent in if parents.is_empty() { vec![base] } else { parents.clone() } {
+                        object.extend_from_slice(format!("parent {}\n", parent).as_bytes());
+                    }
+                }
+            }
+            object.extend_from_slice(message);
+            let written = self.run_with_input(self.git().arg("hash-object").arg("-t").arg("commit").arg("-w").arg("--stdin"), &object, "git hash-object")?;
+            new_tip = String::from_utf8_lossy(&written.stdout).trim().to_string();
+            rewritten.insert(commit.to_string(), new_tip.clone());
+        }
+        let tip = self.run(self.git().arg("rev-parse").arg(tip), "git rev-parse")?;
+        let tip = String::from_utf8_lossy(&tip.stdout).trim().to_string();
+        Ok((rewritten.get(&tip).cloned().unwrap_or(new_tip), rewritten.len()))
+    }
+
+    // Writes a commit on top of `onto` that undoes `commit`, which must have one
+    // parent: a three-way merge of `onto` and that parent over `commit`, at
+    // the level of whole files, in a temporary index like
+    // `commit_tree_changes`. A file changed both by `commit` and since is
+    // reported rather than merged line by line.
+    pub fn commit_revert(&self, commit: &str, onto: &str, message: &str) -> io::Result<Revert> {
+        let index = self.common_dir()?.join(format!("trunk-index-{}", std::process::id()));
+        let git = || {
+            let mut command = self.git();
+            command.env("GIT_INDEX_FILE", &index);
+            command
+        };
+        let result = (|| {
+            self.run(git().arg("read-tree").arg("-m").arg("-i").arg("--aggressive").arg(commit).arg(onto).arg(format!("{}^", commit)), "git read-tree")?;
+            let unmerged = self.run(git().arg("ls-files").arg("--unmerged").arg("-z"), "git ls-files")?;
+            let mut conflicts: Vec<String> = unmerged
+                .stdout
+                .split(|b| *b == 0)
+                .filter_map(|entry| String::from_utf8_lossy(entry).split_once('\t').map(|(_, path)| path.to_string()))
+                .collect();
+            if !conflicts.is_empty() {
+                conflicts.dedup();
+                return Ok(Revert::Conflicts(conflicts));
+            }
+            let tree = self.run(git().arg("write-tree"), "git write-tree")?;
+            let tree = String::from_utf8_lossy(&tree.stdout).trim().to_string();
+            let onto_tree = self.run(self.git().arg("rev-parse").arg(format!("{}^{{tree}}", onto)), "git rev-parse")?;
+            if String::from_utf8_lossy(&onto_tree.stdout).trim() == tree {
+                return Ok(Revert::Unchanged);
+            }
+            let new_commit = self.run(self.git().arg("commit-tree").arg(&tree).arg("-p").arg(onto).arg("-m").arg(message), "git commit-tree")?;
+            Ok(Revert::Committed(String::from_utf8_lossy(&new_commit.stdout).trim().to_string()))
+        })();
+        let _ = std::fs::remove_file(&index);
+        result
+    }
+
+    // Writes every file of `commit` under the directory `dest`, with git's
+    // modes and symlinks, through `git checkout-index` on a temporary index
+    // outside the git directory, so it works in read-only repositories and
+    // leaves the worktree, its index and every ref alone.
+    pub fn export_tree(&self, commit: &str, dest: &Path) -> io::Result<()> {
+        let index = std::env::temp_dir().join(format!("git-trunk-index-{}", std::process::id()));
+        let git = || {
+            let mut command = self.git();
+            command.env("GIT_INDEX_FILE", &index);
+            command
+        };
+        let result = (|| {
+            self.run(git().arg("read-tree").arg(commit), "git read-tree")?;
+            let prefix = dest.to_str().ok_or_else(|| io::Error::other(format!("{} is not valid UTF-8", dest.display())))?;
+            self.run(git().arg("checkout-index").arg("--all").arg("--force").arg(format!("--prefix={}/", prefix.trim_end_matches('/'))), "git checkout-index")
+                .map(|_| ())
+        })();
+        let _ = std::fs::remove_file(&index);
+        result
+    }
+
+    // Fetches `refspec` (e.g. "+main:refs/trunk/docs") from another repository
+    // on this machine. Remotes by name or URL go through `git fetch` instead,
+    // so transports and credential helpers behave exactly as configured.
+    pub fn fetch_local(&self, source: &Path, refspec: &str) -> io::Result<()> {
+        if dry_run(&format!("fetch {} from {} into {}", refspec, source.display(), self.root.display())) {
+            return Ok(());
+        }
+        if let Some(repo) = &self.repo {
+            let source_str = source.to_str().ok_or_else(|| io::Error::other(format!("{} is not valid UTF-8", source.display())))?;
+            let mut remote = repo.remote_anonymous(source_str).map_err(to_io)?;
+            return match remote.fetch(&[refspec], None, None) {
+                Ok(()) => Ok(()),
+                // libgit2 cannot read every repository git can (e.g. SHA-256 sources)
+                Err(e) if e.class() == git2::ErrorClass::Repository || e.class() == git2::ErrorClass::Odb => {
+                    debug!("⚠️ libgit2 cannot fetch from {} ({}), using git", source.display(), e.message());
+                    self.run(self.git().arg("fetch").arg(source).arg(refspec), "git fetch").map(|_| ())
+                }
+                Err(e) => Err(to_io(e)),
+            };
+        }
+        self.run(self.git().arg("fetch").arg(source).arg(refspec), "git fetch").map(|_| ())
+    }
+}
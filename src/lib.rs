@@ -0,0 +1,60 @@
+//! git-trunk keeps repository-wide documents in stores: separate git
+//! repositories checked out in `.trunk/<store>` whose history lives under
+//! `refs/trunk/<store>` of the main repository.
+//!
+//! The `git-trunk` binary is built on this crate. Its supported library API is
+//! the [`stores`] module, which lists the stores of a repository with their
+//! checkout, ref and remote state, so tools can embed store discovery without
+//! running the CLI and parsing its output. With the `serde` feature,
+//! [`stores::StoreInfo`] implements `Serialize` and `Deserialize`.
+//!
+//! ```no_run
+//! for store in git_trunk::stores::stores(".")?.offline() {
+//!     println!("{}: {:?} uncommitted", store.name, store.local_store_uncommitted_count);
+//! }
+//! # Ok::<(), git_trunk::error::TrunkError>(())
+//! ```
+//!
+//! Every other module is an implementation detail of the binary and may
+//! change in any release.
+
+pub mod stores;
+pub mod error;
+
+#[doc(hidden)]
+pub mod commands;
+#[doc(hidden)]
+pub mod utils;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod table;
+#[doc(hidden)]
+pub mod json;
+#[doc(hidden)]
+pub mod template;
+#[doc(hidden)]
+pub mod cache;
+#[doc(hidden)]
+pub mod frontmatter;
+#[doc(hidden)]
+pub mod quota;
+#[doc(hidden)]
+pub mod git;
+#[doc(hidden)]
+pub mod ops;
+#[doc(hidden)]
+pub mod locks;
+#[doc(hidden)]
+pub mod owners;
+#[doc(hidden)]
+pub mod backup;
+#[doc(hidden)]
+pub mod trash;
+#[doc(hidden)]
+pub mod notify;
+#[doc(hidden)]
+pub mod capabilities;
+#[cfg(feature = "fuse")]
+#[doc(hidden)]
+pub mod fuse;
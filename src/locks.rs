@@ -0,0 +1,169 @@
+use chrono::{DateTime, Local};
+use log::debug;
+use crate::config;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+use crate::utils::{dry_run, read_blobs, run_git_command};
+
+// Advisory locks on store paths, for files that cannot be merged (diagrams,
+// spreadsheets). The locks of a store live in refs/trunk-locks/<store>: a
+// commit whose tree holds one file, `locks`, with a line per locked path.
+// Every change is a new commit pushed to the remote without force, so of two
+// teammates locking the same path at once only one push goes through.
+const LOCKS_FILE: &str = "locks";
+
+// Times a lock change is retried when someone else pushed in between.
+const UPDATE_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct Lock {
+    pub path: String,
+    pub email: String,
+    pub name: String,
+    pub since: DateTime<Local>,
+}
+
+impl Lock {
+    pub fn owner(&self) -> String {
+        format!("{} <{}>", self.name, self.email)
+    }
+}
+
+// Who is locking: `[user] email` of the git-trunk config, else the main
+// repository's user.email, and the name likewise for display.
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub email: String,
+    pub name: String,
+}
+
+pub fn lock_ref(store_name: &str) -> String {
+    format!("refs/trunk-locks/{}", store_name)
+}
+
+fn config_value(repo: &GitRepo, key: &str, verbose: bool) -> Option<String> {
+    let output = run_git_command(repo.git().arg("config").arg("--get").arg(key), verbose).ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+pub fn identity(repo: &GitRepo, verbose: bool) -> Result<Identity, TrunkError> {
+    let trunk_config = config::load(repo.root(), verbose).unwrap_or_default();
+    let email = trunk_config
+        .get_string("user", "email")
+        .or_else(|| config_value(repo, "user.email", verbose))
+        .ok_or_else(|| TrunkError::Config("Locks are recorded by user.email, which is not set. Set it with `git config user.email <address>` or `git trunk config set --user user.email <address>`.".to_string()))?;
+    let name = trunk_config.get_string("user", "name").or_else(|| config_value(repo, "user.name", verbose)).unwrap_or_else(|| email.clone());
+    Ok(Identity { email, name })
+}
+
+// A path as locks record it: relative to the store checkout, so
+// `.trunk/docs/a.png`, `./a.png` and `a.png` name the same lock.
+pub fn normalize_path(store_name: &str, path: &str) -> Result<String, TrunkError> {
+    let store_prefix = format!(".trunk/{}/", store_name);
+    let mut normalized = path.trim_end_matches('/');
+    while let Some(rest) = normalized.strip_prefix("./") {
+        normalized = rest;
+    }
+    normalized = normalized.strip_prefix(&store_prefix).unwrap_or(normalized);
+    if normalized.is_empty() || normalized.starts_with('/') || normalized.split('/').any(|part| part == "..") || normalized.contains(['\t', '\n']) {
+        return Err(TrunkError::Usage(format!("Invalid path '{}': give a path inside .trunk/{}", path, store_name)));
+    }
+    Ok(normalized.to_string())
+}
+
+fn parse(text: &str) -> Vec<Lock> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let (path, email, name, since) = (fields.next()?, fields.next()?, fields.next()?, fields.next()?);
+            let since = DateTime::from_timestamp(since.parse().ok()?, 0)?.with_timezone(&Local);
+            Some(Lock { path: path.to_string(), email: email.to_string(), name: name.to_string(), since })
+        })
+        .collect()
+}
+
+fn serialize(locks: &[Lock]) -> String {
+    locks.iter().map(|lock| format!("{}\t{}\t{}\t{}\n", lock.path, lock.email, lock.name.replace(['\t', '\n'], " "), lock.since.timestamp())).collect()
+}
+
+// The locks as last fetched or pushed, sorted by path.
+pub fn read(repo: &GitRepo, store_name: &str, verbose: bool) -> Vec<Lock> {
+    let object = format!("{}:{}", lock_ref(store_name), LOCKS_FILE);
+    match read_blobs(repo.root(), &[object], verbose) {
+        Ok(blobs) => blobs.into_iter().next().flatten().map(|blob| parse(&String::from_utf8_lossy(&blob))).unwrap_or_default(),
+        Err(e) => {
+            debug!("⚠️ Failed to read the locks of store '{}': {}", store_name, e);
+            Vec::new()
+        }
+    }
+}
+
+fn remote_locks_tip(repo: &GitRepo, remote_name: &str, store_name: &str, verbose: bool) -> Result<Option<String>, TrunkError> {
+    let locks_ref = lock_ref(store_name);
+    let output = run_git_command(repo.git().arg("ls-remote").arg(remote_name).arg(&locks_ref), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to reach remote '{}': {}", remote_name, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(TrunkError::Git(format!("Failed to reach remote '{}': {}", remote_name, if stderr.is_empty() { "git ls-remote failed" } else { &stderr })));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().find_map(|line| {
+        let (hash, name) = line.split_once(char::is_whitespace)?;
+        (name.trim() == locks_ref).then(|| hash.to_string())
+    }))
+}
+
+// Makes refs/trunk-locks/<store> match the remote's.
+pub fn refresh(repo: &GitRepo, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let locks_ref = lock_ref(store_name);
+    let local = repo.resolve_commit(&locks_ref);
+    match remote_locks_tip(repo, remote_name, store_name, verbose)? {
+        None => repo.delete_ref(&locks_ref).map_err(|e| TrunkError::Git(format!("Failed to delete {}: {}", locks_ref, e))),
+        Some(remote) if local.as_ref() == Some(&remote) => Ok(()),
+        Some(_) => {
+            let output = run_git_command(repo.git().arg("fetch").arg(remote_name).arg(format!("+{}:{}", locks_ref, locks_ref)), verbose)
+                .map_err(|e| TrunkError::Git(format!("Failed to fetch {} from remote '{}': {}", locks_ref, remote_name, e)))?;
+            if !output.status.success() {
+                return Err(TrunkError::Git(format!("Failed to fetch {} from remote '{}': {}", locks_ref, remote_name, String::from_utf8_lossy(&output.stderr).trim())));
+            }
+            Ok(())
+        }
+    }
+}
+
+// Applies `change` to the remote's current locks and pushes the result,
+// starting over when someone else pushed first. `change` returns whether it
+// changed anything; nothing is pushed otherwise.
+pub fn update<F>(repo: &GitRepo, remote_name: &str, store_name: &str, message: &str, verbose: bool, mut change: F) -> Result<bool, TrunkError>
+where
+    F: FnMut(&mut Vec<Lock>) -> Result<bool, TrunkError>,
+{
+    let locks_ref = lock_ref(store_name);
+    for _ in 0..UPDATE_ATTEMPTS {
+        refresh(repo, remote_name, store_name, verbose)?;
+        let parent = repo.resolve_commit(&locks_ref);
+        let mut locks = read(repo, store_name, verbose);
+        if !change(&mut locks)? {
+            return Ok(false);
+        }
+        locks.sort_by(|a, b| a.path.cmp(&b.path));
+        if dry_run(&format!("push the changed locks of store '{}' to {} on remote '{}'", store_name, locks_ref, remote_name)) {
+            return Ok(true);
+        }
+        let commit = repo
+            .commit_single_file(LOCKS_FILE, serialize(&locks).as_bytes(), parent.as_deref(), message)
+            .map_err(|e| TrunkError::Git(format!("Failed to record the locks of store '{}': {}", store_name, e)))?;
+        let output = run_git_command(repo.git().arg("push").arg(remote_name).arg(format!("{}:{}", commit, locks_ref)), verbose)
+            .map_err(|e| TrunkError::Git(format!("Failed to push {} to remote '{}': {}", locks_ref, remote_name, e)))?;
+        if output.status.success() {
+            repo.update_ref(&locks_ref, &commit).map_err(|e| TrunkError::Git(format!("Failed to update {}: {}", locks_ref, e)))?;
+            return Ok(true);
+        }
+        // A rejected push means the remote moved; anything else is an error
+        if remote_locks_tip(repo, remote_name, store_name, verbose)? == parent {
+            return Err(TrunkError::Git(format!("Failed to push {} to remote '{}': {}", locks_ref, remote_name, String::from_utf8_lossy(&output.stderr).trim())));
+        }
+        debug!("🔁 {} changed on remote '{}' meanwhile, trying again", locks_ref, remote_name);
+    }
+    Err(TrunkError::Conflict(format!("The locks of store '{}' on remote '{}' kept changing while updating them; try again", store_name, remote_name)))
+}
@@ -1,13 +1,18 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use log::LevelFilter;
 use env_logger::{Builder, Env};
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
 
-mod commands;
-mod utils; // Added utils module
+use git_trunk::{commands, config, notify, ops, table, utils};
+use git_trunk::capabilities::{Capabilities, Need};
+use git_trunk::config::NotifyMode;
+use git_trunk::error::TrunkError;
+use git_trunk::git::GitRepo;
 
 #[derive(Parser)]
 #[command(author, version, about = "Git Trunk CLI for managing repository-wide documents", long_about = None)]
+#[command(after_help = "Run `git trunk examples` for task-oriented recipes, or `git trunk <command> --help` for examples of each command.")]
 #[command(propagate_version = true)]
 struct Cli {
     #[command(subcommand)]
@@ -24,20 +29,49 @@ struct Cli {
     #[arg(
         long,
         short = 'r',
-        help = "Specify the remote repository",
-        default_value = "origin",
+        help = "Specify the remote repository [default: config or origin]",
         global = true
     )]
-    remote: String,
+    remote: Option<String>,
 
     #[arg(
         long,
         short = 's',
-        help = "Specify the trunk store name (e.g., main, blog, issues)",
-        default_value = "main",
+        help = "Specify the trunk store name (e.g., main, blog, issues) [default: config or main]; repeat it or use a glob (e.g., 'docs-*') to run the command once per store",
         global = true
     )]
-    store: String,
+    store: Vec<String>,
+
+    #[arg(
+        long,
+        short = 'y',
+        help = "Answer yes to every confirmation prompt (also GIT_TRUNK_NONINTERACTIVE=1)",
+        global = true
+    )]
+    yes: bool,
+
+    #[arg(
+        long,
+        help = "Print the git commands, refs, files and directories a command would change, without changing them",
+        global = true
+    )]
+    dry_run: bool,
+
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_parser = config::NOTIFY_VALUES,
+        help = "Ring the terminal bell and/or show a desktop notification when the command finishes [default: [trunk] notify for long runs]",
+        global = true
+    )]
+    notify: Option<String>,
+
+    #[arg(
+        long,
+        help = "Screen-reader-friendly output: words instead of emoji and symbols, no color, one fact per line (also GIT_TRUNK_PLAIN=1)",
+        global = true
+    )]
+    plain: bool,
 }
 
 #[derive(Subcommand)]
@@ -58,12 +92,320 @@ enum Commands {
     Delete(commands::delete::DeleteArgs),
     /// Displays information about the git-trunk setup and stores
     Info(commands::info::InfoArgs),
+    /// Runs the per-store actions registered for a git hook event (called by installed hooks)
+    HookRun(commands::hook_run::HookRunArgs),
+    /// Reads, changes and validates the git-trunk config file
+    Config(commands::config::ConfigArgs),
+    /// Cross-checks hooks, config and refs/trunk/* for inconsistencies and offers fixes
+    Doctor(commands::doctor::DoctorArgs),
+    /// Writes a pointer to the available trunk stores into the main repository
+    Advertise(commands::advertise::AdvertiseArgs),
+    /// Shares one set of store checkouts across all worktrees of the repository
+    Worktree(commands::worktree::WorktreeArgs),
+    /// Spell-checks and prose-lints the documents of a store
+    LintProse(commands::lint_prose::LintProseArgs),
+    /// Queries the frontmatter of a store's Markdown documents without a checkout
+    Query(commands::query::QueryArgs),
+    /// Shows a store's documents as a kanban board and moves items between columns
+    Board(commands::board::BoardArgs),
+    /// Lists due dates and open tasks found in store documents
+    Agenda(commands::agenda::AgendaArgs),
+    /// Creates a document from a store template, opens it in the editor and commits it
+    New(commands::new::NewArgs),
+    /// Regenerates an index of a store's documents grouped by directory
+    IndexMd(commands::index_md::IndexMdArgs),
+    /// Shows changes in a store checkout, optionally word by word or as side-by-side HTML
+    Diff(commands::diff::DiffArgs),
+    /// Summarizes the documents added, changed or removed between two store revisions
+    Changelog(commands::changelog::ChangelogArgs),
+    /// Assembles news fragments into a changelog section and commits it
+    ReleaseNotes(commands::release_notes::ReleaseNotesArgs),
+    /// Fetches refs/trunk/<store> from the remote and merges it into .trunk/<store>
+    Pull(commands::pull::PullArgs),
+    /// Shows per store whether the checkout, refs/trunk/<store> and the remote ref have diverged
+    Status(commands::status::StatusArgs),
+    /// Runs newline-delimited JSON commands from stdin, writing one JSON result per line
+    Batch(commands::batch::BatchArgs),
+    /// Discards local store commits and changes, resetting to refs/trunk/<store> on the remote
+    DiscardLocal(commands::discard_local::DiscardLocalArgs),
+    /// Shows the commit history of refs/trunk/<store>
+    Log(commands::log::LogArgs),
+    /// Prints task-oriented recipes from the built-in cookbook
+    Examples(commands::examples::ExamplesArgs),
+    /// Registers refs/trunk/* with git's background maintenance (prefetch and pack-refs)
+    Maintenance(commands::maintenance::MaintenanceArgs),
+    /// Mirrors refs/trunk/<store> into a local branch for tools that only understand branches
+    Expose(commands::expose::ExposeArgs),
+    /// Stops mirroring refs/trunk/<store> into a local branch and deletes it
+    Unexpose(commands::unexpose::UnexposeArgs),
+    /// Generates CI configuration, such as a scheduled job that refreshes every store
+    Ci(commands::ci::CiArgs),
+    /// Lists the unresolved merge conflicts in .trunk/<store>, optionally as JSON
+    Conflicts(commands::conflicts::ConflictsArgs),
+    /// Shows or clears the per-user cache of remote listings and hook run times
+    Cache(commands::cache::CacheArgs),
+    /// Guards uncommitted work in .trunk against `git clean -ffdx` with a `git clean-safe` alias
+    ProtectClean(commands::protect_clean::ProtectCleanArgs),
+    /// Updates refs/trunk/<store> from the remote without touching .trunk/<store>
+    Fetch(commands::fetch::FetchArgs),
+    /// Writes a static HTML dashboard of every store: status, authors, size trends and stale documents
+    Report(commands::report::ReportArgs),
+    /// Locks store files that cannot be merged, recording the lock in refs/trunk-locks/<store> on the remote
+    Lock(commands::lock::LockArgs),
+    /// Releases locks taken with `git trunk lock`
+    Unlock(commands::unlock::UnlockArgs),
+    /// Lists the locks of a store
+    Locks(commands::locks::LocksArgs),
+    /// Lists every revision of one store file and can render the versions or the diffs between them
+    History(commands::history::HistoryArgs),
+    /// Restores refs/trunk/<store> from the backup bundle `git trunk delete` wrote
+    RestoreBackup(commands::restore_backup::RestoreBackupArgs),
+    /// Brings back a store `git trunk delete` moved to refs/trunk-trash
+    Undelete(commands::undelete::UndeleteArgs),
+    /// Lists deleted stores or purges the ones older than a given age
+    Trash(commands::trash::TrashArgs),
+    /// Lists the files in refs/trunk/<store> without a checkout
+    Ls(commands::ls::LsArgs),
+    /// Prints a file of refs/trunk/<store> without a checkout
+    #[command(visible_alias = "show")]
+    Cat(commands::cat::CatArgs),
+    /// Prints the git operations a command would perform, with resolved refs and paths, without running them
+    Explain(commands::explain::ExplainArgs),
+    /// Commits a file into refs/trunk/<store> without a checkout
+    Put(commands::put::PutArgs),
+    /// Exports refs/trunk/<store> into an untracked directory for builds
+    MaterializeTemp(commands::materialize_temp::MaterializeTempArgs),
+    /// Removes files or directories from refs/trunk/<store> in a single commit
+    Rm(commands::rm::RmArgs),
+    /// Compares the stores on the remote with its mirrors, or pushes the mirrors up to date
+    Mirrors(commands::mirrors::MirrorsArgs),
+    /// Shows who last changed each line or Markdown section of a store file
+    Blame(commands::blame::BlameArgs),
+    /// Prints what a store is for and who maintains it, from its ABOUT.md
+    About(commands::about::AboutArgs),
+    /// Brings files, or the whole store, in .trunk/<store> back to an earlier commit
+    Restore(commands::restore::RestoreArgs),
+    /// Shows who owns store paths, from the store's TRUNKOWNERS file
+    Owners(commands::owners::OwnersArgs),
+    /// Undoes a commit of refs/trunk/<store> with a new commit
+    Revert(commands::revert::RevertArgs),
+    /// Moves refs/trunk/<store> back to an earlier commit, keeping a backup ref
+    Reset(commands::reset::ResetArgs),
+    /// Summarizes recent activity across stores in Markdown, for a mailing list or chat
+    Digest(commands::digest::DigestArgs),
+    /// Searches the files of refs/trunk/<store>, or its whole history, with git grep
+    Grep(commands::grep::GrepArgs),
+    /// Renames a store: refs/trunk/<store> locally and on the remote, .trunk/<store>, config and hooks
+    Rename(commands::rename::RenameArgs),
+    /// Creates a store from another store's current commit, sharing its history, and checks it out
+    Copy(commands::copy::CopyArgs),
+    /// Packages a store into a git bundle of its history, or a tar or zip archive of its files
+    Export(commands::export::ExportArgs),
+    /// Creates a store from a git bundle, a tar or zip archive, or a directory, and checks it out
+    Import(commands::import::ImportArgs),
+    /// Turns a directory of the main repository into a store, optionally with its history
+    Adopt(commands::adopt::AdoptArgs),
+    /// Converts a branch, such as an orphan docs or gh-pages branch, into a store with its whole history
+    MigrateBranch(commands::migrate_branch::MigrateBranchArgs),
+    /// Publishes a store's history as a regular branch, locally and on the remote, and keeps it in sync
+    ExportBranch(commands::export_branch::ExportBranchArgs),
+    /// Drops objects that deleted or rewritten stores left behind, in the main repository and every store checkout
+    Gc(commands::gc::GcArgs),
+    /// Checks a store's integrity: its ref, objects, checkout, branch and remote, optionally as JSON
+    Verify(commands::verify::VerifyArgs),
+    /// Mounts refs/trunk/<store> as a read-only directory through FUSE, without a checkout
+    Mount(commands::mount::MountArgs),
 }
 
-fn init_logger(verbose: bool) {
+impl Commands {
+    // Commands that create refs, objects, hooks or config in the git directory
+    fn writes_git_dir(&self) -> bool {
+        match self {
+            Commands::Init(_) | Commands::Commit(_) | Commands::Checkout(_) | Commands::Stegano(_) | Commands::Worktree(_) | Commands::New(_) | Commands::IndexMd(_) | Commands::Pull(_) | Commands::DiscardLocal(_) | Commands::Maintenance(_) | Commands::Expose(_) | Commands::Unexpose(_) | Commands::ReleaseNotes(_) | Commands::ProtectClean(_) | Commands::Fetch(_) | Commands::Lock(_) | Commands::Unlock(_) | Commands::Undelete(_) | Commands::Put(_) | Commands::Rm(_) | Commands::Restore(_) | Commands::Revert(_) | Commands::Reset(_) | Commands::Rename(_) | Commands::Copy(_) | Commands::Import(_) | Commands::Adopt(_) | Commands::MigrateBranch(_) | Commands::ExportBranch(_) | Commands::Gc(_) => true,
+            Commands::Hooks(args) => args.writes_hooks(),
+            Commands::Delete(args) => args.writes_git_dir(),
+            Commands::RestoreBackup(args) => args.writes_git_dir(),
+            Commands::Trash(args) => args.writes_git_dir(),
+            Commands::Mirrors(args) => args.writes_git_dir(),
+            Commands::Digest(args) => args.writes_git_dir(),
+            Commands::Doctor(args) => args.fix,
+            Commands::Board(args) => args.moves_item(),
+            Commands::Diff(args) => args.remote_ref,
+            Commands::Config(args) => args.writes_config(),
+            Commands::Push(_) | Commands::Info(_) | Commands::Advertise(_) | Commands::LintProse(_) | Commands::Query(_) | Commands::Agenda(_) | Commands::Changelog(_) | Commands::Status(_) | Commands::Batch(_) | Commands::Log(_) | Commands::Examples(_) | Commands::HookRun(_) | Commands::Ci(_) | Commands::Conflicts(_) | Commands::Cache(_) | Commands::Report(_) | Commands::Locks(_) | Commands::History(_) | Commands::Ls(_) | Commands::Cat(_) | Commands::Explain(_) | Commands::MaterializeTemp(_) | Commands::Blame(_) | Commands::About(_) | Commands::Owners(_) | Commands::Grep(_) | Commands::Export(_) | Commands::Verify(_) | Commands::Mount(_) => false,
+        }
+    }
+
+    // What a command needs besides a repository, so it is refused before its
+    // first step where that is missing (bare repositories on a server, no
+    // terminal, no editor)
+    fn needs(&self) -> Vec<Need> {
+        let mut needs = Vec::new();
+        let work_tree = match self {
+            Commands::Init(_) | Commands::Commit(_) | Commands::Checkout(_) | Commands::Stegano(_) | Commands::Advertise(_) | Commands::Worktree(_) | Commands::LintProse(_) | Commands::New(_) | Commands::IndexMd(_) | Commands::Diff(_) | Commands::ReleaseNotes(_) | Commands::Pull(_) | Commands::DiscardLocal(_) | Commands::Conflicts(_) | Commands::ProtectClean(_) | Commands::Restore(_) | Commands::Adopt(_) => true,
+            Commands::Board(args) => args.moves_item(),
+            Commands::Hooks(args) => args.writes_hooks(),
+            Commands::Copy(args) => args.checks_out(),
+            Commands::Import(args) => args.checks_out(),
+            Commands::MigrateBranch(args) => args.checks_out(),
+            _ => false,
+        };
+        if work_tree {
+            needs.push(Need::WorkTree);
+        }
+        if matches!(self, Commands::New(args) if args.opens_editor()) {
+            needs.push(Need::Editor);
+        }
+        // Commands asking before anything else, unless --yes, --dry-run,
+        // GIT_TRUNK_NONINTERACTIVE or [trunk] prompt answers for the user
+        if matches!(self, Commands::Delete(_) | Commands::Reset(_) | Commands::Rename(_)) && !utils::prompts_answered() {
+            needs.push(Need::Terminal);
+        }
+        needs
+    }
+
+    // What to suggest when `need` is missing, usually a command working on
+    // refs/trunk/<store> without a checkout
+    fn alternative(&self, need: Need) -> Option<&'static str> {
+        match (self, need) {
+            (Commands::Init(_), Need::WorkTree) => Some("create the store in a clone and publish it with `git trunk push`"),
+            (Commands::Commit(_), Need::WorkTree) => Some("commit files without a checkout with `git trunk put` and remove them with `git trunk rm`"),
+            (Commands::Checkout(_), Need::WorkTree) => Some("read store files without a checkout with `git trunk ls` and `git trunk show`"),
+            (Commands::Pull(_), Need::WorkTree) => Some("update refs/trunk/<store> from the remote with `git trunk fetch`"),
+            (Commands::DiscardLocal(_), Need::WorkTree) => Some("move refs/trunk/<store> back with `git trunk reset --to <commit>`"),
+            (Commands::Diff(_), Need::WorkTree) => Some("compare revisions of the store with `git trunk changelog` or `git trunk history`"),
+            (Commands::Restore(_), Need::WorkTree) => Some("undo a commit with `git trunk revert` or go back with `git trunk reset --to <commit>`"),
+            (Commands::Board(_), Need::WorkTree) => Some("change the document's frontmatter and commit it with `git trunk put`"),
+            (Commands::New(_), Need::WorkTree) => Some("write the document and commit it with `git trunk put`"),
+            (Commands::Copy(_), Need::WorkTree) => Some("pass --no-checkout to only create refs/trunk/<dst>"),
+            (Commands::Import(_), Need::WorkTree) => Some("pass --no-checkout to only create refs/trunk/<store>"),
+            (Commands::MigrateBranch(_), Need::WorkTree) => Some("pass --no-checkout to only create refs/trunk/<store>"),
+            (Commands::New(_), Need::Editor) => Some("pass --no-edit to commit the template as is, or set GIT_EDITOR"),
+            _ => None,
+        }
+    }
+
+    // Commands that honor --dry-run. Others would change things regardless, so
+    // the flag is refused for them rather than silently ignored.
+    fn supports_dry_run(&self) -> bool {
+        matches!(self, Commands::Init(_) | Commands::Commit(_) | Commands::Checkout(_) | Commands::Push(_) | Commands::Delete(_) | Commands::Stegano(_) | Commands::Hooks(_) | Commands::ReleaseNotes(_))
+    }
+
+    // Path arguments that may name their store as `store:path`
+    fn paths_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            Commands::History(args) => args.paths_mut(),
+            Commands::Lock(args) => args.paths_mut(),
+            Commands::Unlock(args) => args.paths_mut(),
+            Commands::Ls(args) => args.paths_mut(),
+            Commands::Cat(args) => args.paths_mut(),
+            Commands::Grep(args) => args.paths_mut(),
+            Commands::Put(args) => args.paths_mut(),
+            Commands::Rm(args) => args.paths_mut(),
+            Commands::Blame(args) => args.paths_mut(),
+            Commands::Restore(args) => args.paths_mut(),
+            Commands::Owners(args) => args.paths_mut(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Commands that work on one store, so several --store values run them once
+    // per store. The others cover every store, or none, in a single run.
+    fn takes_store(&self) -> bool {
+        !matches!(self, Commands::Config(_) | Commands::HookRun(_) | Commands::Doctor(_) | Commands::Advertise(_) | Commands::Worktree(_) | Commands::Batch(_) | Commands::Examples(_) | Commands::Maintenance(_) | Commands::Ci(_) | Commands::Cache(_) | Commands::ProtectClean(_) | Commands::Report(_) | Commands::Trash(_) | Commands::Explain(_) | Commands::Mirrors(_) | Commands::Digest(_) | Commands::Rename(_) | Commands::Copy(_) | Commands::Import(_) | Commands::Gc(_))
+    }
+
+    fn run(&self, remote_name: &str, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+        match self {
+            Commands::Init(args) => commands::init::run(args, remote_name, store_name, verbose),
+            Commands::Commit(args) => commands::commit::run(args, remote_name, store_name, verbose),
+            Commands::Checkout(args) => commands::checkout::run(args, remote_name, store_name, verbose),
+            Commands::Push(args) => commands::push::run(args, remote_name, store_name, verbose),
+            Commands::Hooks(args) => commands::hooks::run(args, remote_name, store_name, verbose),
+            Commands::Stegano(args) => commands::stegano::run(args, remote_name, store_name, verbose),
+            Commands::Delete(args) => commands::delete::run(args, remote_name, store_name, verbose),
+            Commands::Info(args) => commands::info::run(args, remote_name, store_name, verbose),
+            Commands::HookRun(args) => commands::hook_run::run(args, remote_name, store_name, verbose),
+            Commands::Config(args) => commands::config::run(args, remote_name, store_name, verbose),
+            Commands::Doctor(args) => commands::doctor::run(args, remote_name, store_name, verbose),
+            Commands::Advertise(args) => commands::advertise::run(args, remote_name, store_name, verbose),
+            Commands::Worktree(args) => commands::worktree::run(args, remote_name, store_name, verbose),
+            Commands::LintProse(args) => commands::lint_prose::run(args, remote_name, store_name, verbose),
+            Commands::Query(args) => commands::query::run(args, remote_name, store_name, verbose),
+            Commands::Board(args) => commands::board::run(args, remote_name, store_name, verbose),
+            Commands::Agenda(args) => commands::agenda::run(args, remote_name, store_name, verbose),
+            Commands::New(args) => commands::new::run(args, remote_name, store_name, verbose),
+            Commands::IndexMd(args) => commands::index_md::run(args, remote_name, store_name, verbose),
+            Commands::Diff(args) => commands::diff::run(args, remote_name, store_name, verbose),
+            Commands::Changelog(args) => commands::changelog::run(args, remote_name, store_name, verbose),
+            Commands::ReleaseNotes(args) => commands::release_notes::run(args, remote_name, store_name, verbose),
+            Commands::Pull(args) => commands::pull::run(args, remote_name, store_name, verbose),
+            Commands::Status(args) => commands::status::run(args, remote_name, store_name, verbose),
+            Commands::Batch(args) => commands::batch::run(args, remote_name, store_name, verbose),
+            Commands::DiscardLocal(args) => commands::discard_local::run(args, remote_name, store_name, verbose),
+            Commands::Log(args) => commands::log::run(args, remote_name, store_name, verbose),
+            Commands::Examples(args) => commands::examples::run(args, remote_name, store_name, verbose),
+            Commands::Maintenance(args) => commands::maintenance::run(args, remote_name, store_name, verbose),
+            Commands::Expose(args) => commands::expose::run(args, remote_name, store_name, verbose),
+            Commands::Unexpose(args) => commands::unexpose::run(args, remote_name, store_name, verbose),
+            Commands::Ci(args) => commands::ci::run(args, remote_name, store_name, verbose),
+            Commands::Conflicts(args) => commands::conflicts::run(args, remote_name, store_name, verbose),
+            Commands::Cache(args) => commands::cache::run(args, remote_name, store_name, verbose),
+            Commands::ProtectClean(args) => commands::protect_clean::run(args, remote_name, store_name, verbose),
+            Commands::Fetch(args) => commands::fetch::run(args, remote_name, store_name, verbose),
+            Commands::Report(args) => commands::report::run(args, remote_name, store_name, verbose),
+            Commands::Lock(args) => commands::lock::run(args, remote_name, store_name, verbose),
+            Commands::Unlock(args) => commands::unlock::run(args, remote_name, store_name, verbose),
+            Commands::Locks(args) => commands::locks::run(args, remote_name, store_name, verbose),
+            Commands::History(args) => commands::history::run(args, remote_name, store_name, verbose),
+            Commands::RestoreBackup(args) => commands::restore_backup::run(args, remote_name, store_name, verbose),
+            Commands::Undelete(args) => commands::undelete::run(args, remote_name, store_name, verbose),
+            Commands::Trash(args) => commands::trash::run(args, remote_name, store_name, verbose),
+            Commands::Ls(args) => commands::ls::run(args, remote_name, store_name, verbose),
+            Commands::Cat(args) => commands::cat::run(args, remote_name, store_name, verbose),
+            Commands::Put(args) => commands::put::run(args, remote_name, store_name, verbose),
+            Commands::MaterializeTemp(args) => commands::materialize_temp::run(args, remote_name, store_name, verbose),
+            Commands::Rm(args) => commands::rm::run(args, remote_name, store_name, verbose),
+            Commands::Mirrors(args) => commands::mirrors::run(args, remote_name, store_name, verbose),
+            Commands::Blame(args) => commands::blame::run(args, remote_name, store_name, verbose),
+            Commands::About(args) => commands::about::run(args, remote_name, store_name, verbose),
+            Commands::Restore(args) => commands::restore::run(args, remote_name, store_name, verbose),
+            Commands::Owners(args) => commands::owners::run(args, remote_name, store_name, verbose),
+            Commands::Revert(args) => commands::revert::run(args, remote_name, store_name, verbose),
+            Commands::Reset(args) => commands::reset::run(args, remote_name, store_name, verbose),
+            Commands::Digest(args) => commands::digest::run(args, remote_name, store_name, verbose),
+            Commands::Grep(args) => commands::grep::run(args, remote_name, store_name, verbose),
+            Commands::Rename(args) => commands::rename::run(args, remote_name, store_name, verbose),
+            Commands::Copy(args) => commands::copy::run(args, remote_name, store_name, verbose),
+            Commands::Export(args) => commands::export::run(args, remote_name, store_name, verbose),
+            Commands::Import(args) => commands::import::run(args, remote_name, store_name, verbose),
+            Commands::Adopt(args) => commands::adopt::run(args, remote_name, store_name, verbose),
+            Commands::MigrateBranch(args) => commands::migrate_branch::run(args, remote_name, store_name, verbose),
+            Commands::ExportBranch(args) => commands::export_branch::run(args, remote_name, store_name, verbose),
+            Commands::Gc(args) => commands::gc::run(args, remote_name, store_name, verbose),
+            Commands::Verify(args) => commands::verify::run(args, remote_name, store_name, verbose),
+            Commands::Mount(args) => commands::mount::run(args, remote_name, store_name, verbose),
+            // main runs the explained command in its place
+            Commands::Explain(_) => Err(TrunkError::Usage("explain runs another command, e.g. `git trunk explain checkout`".to_string())),
+        }
+    }
+}
+
+// `quiet` keeps only warnings and errors, so `explain` prints just its plan
+fn init_logger(verbose: bool, quiet: bool) {
     let env = Env::default().filter_or("RUST_LOG", if verbose { "debug" } else { "info" });
+    let plain = table::is_plain();
     Builder::from_env(env)
-        .format(|buf, record| {
+        .format(move |buf, record| {
+            if plain {
+                // No symbols or color; an error line always names itself
+                let text = table::plain_text(&record.args().to_string());
+                let labeled = text.starts_with("ERROR") || text.starts_with("WARNING");
+                return match record.level() {
+                    log::Level::Error if !labeled => writeln!(buf, "ERROR: {}", text),
+                    _ => writeln!(buf, "{}", text),
+                };
+            }
             let level_style = match record.level() {
                 log::Level::Error => "\x1B[31m❌\x1B[0m", // Red ❌ for errors
                 log::Level::Info => "🐘",                // 🐘 for info
@@ -72,25 +414,220 @@ fn init_logger(verbose: bool) {
             };
             writeln!(buf, "{} {}", level_style, record.args())
         })
-        .filter(None, if verbose { LevelFilter::Debug } else { LevelFilter::Info })
+        .filter(None, if verbose { LevelFilter::Debug } else if quiet { LevelFilter::Warn } else { LevelFilter::Info })
         .init();
 }
 
+// The single place errors are rendered: one ❌ line, then the kind's exit code
+fn exit_with(e: TrunkError) -> ! {
+    log::error!("❌ {}", e);
+    std::process::exit(e.exit_code());
+}
+
+// Tells the user the command finished: always with --notify, otherwise when
+// `[trunk] notify` is on for the command and it ran for `notify_after`
+// seconds in a terminal, so scripts and hooks stay quiet.
+fn notify_finished(cli: &Cli, command_name: &str, stores: &[&str], elapsed: Duration, result: &Result<(), TrunkError>) {
+    let mode = match cli.notify.as_deref().and_then(NotifyMode::parse) {
+        Some(mode) => mode,
+        None => {
+            let Ok(repo) = GitRepo::discover(cli.verbose) else { return };
+            let Ok(trunk_config) = config::load(repo.root(), cli.verbose) else { return };
+            if !trunk_config.notifies(command_name) || elapsed.as_secs() < trunk_config.notify_after() || !std::io::stderr().is_terminal() {
+                return;
+            }
+            trunk_config.notify_mode()
+        }
+    };
+    if mode == NotifyMode::Off {
+        return;
+    }
+    let (title, message) = match result {
+        Ok(()) => (format!("git trunk {} finished", command_name), format!("Store {} done in {}s", stores.join(", "), elapsed.as_secs())),
+        Err(e) => (format!("git trunk {} failed", command_name), e.to_string()),
+    };
+    notify::send(mode, &title, &message, result.is_ok());
+}
+
+// A command name found in `[alias]` (repository config, then user config) is
+// replaced by the words it stands for, as git does with its aliases. Commands
+// of git-trunk itself always win.
+fn expand_alias(mut argv: Vec<std::ffi::OsString>) -> Vec<std::ffi::OsString> {
+    let mut index = 1;
+    while let Some(arg) = argv.get(index).and_then(|arg| arg.to_str()) {
+        match arg {
+            "-r" | "--remote" | "-s" | "--store" | "--notify" => index += 2,
+            _ if arg.starts_with('-') => index += 1,
+            _ => break,
+        }
+    }
+    let Some(name) = argv.get(index).and_then(|arg| arg.to_str()).map(str::to_string) else { return argv };
+    if name == "help" || Cli::command().find_subcommand(&name).is_some() {
+        return argv;
+    }
+    let trunk_config = match GitRepo::discover(false) {
+        Ok(repo) => config::load_unvalidated(repo.root(), false),
+        Err(_) => config::load_user_unvalidated(),
+    };
+    if let Some(words) = trunk_config.ok().and_then(|trunk_config| trunk_config.alias(&name)) {
+        argv.splice(index..=index, words.into_iter().map(std::ffi::OsString::from));
+    }
+    argv
+}
+
 fn main() {
-    let cli = Cli::parse();
-    init_logger(cli.verbose);
-
-    let remote_name = &cli.remote;
-    let store_name = &cli.store;
-
-    match cli.command {
-        Commands::Init(args) => commands::init::run(&args, remote_name, store_name, cli.verbose),
-        Commands::Commit(args) => commands::commit::run(&args, remote_name, store_name, cli.verbose),
-        Commands::Checkout(args) => commands::checkout::run(&args, remote_name, store_name, cli.verbose),
-        Commands::Push(args) => commands::push::run(&args, remote_name, store_name, cli.verbose),
-        Commands::Hooks(args) => commands::hooks::run(&args, remote_name, store_name, cli.verbose),
-        Commands::Stegano(args) => commands::stegano::run(&args, remote_name, store_name, cli.verbose),
-        Commands::Delete(args) => commands::delete::run(&args, remote_name, store_name, cli.verbose),
-        Commands::Info(args) => commands::info::run(&args, remote_name, store_name, cli.verbose),
+    let argv = expand_alias(std::env::args_os().collect());
+    let matches = Cli::command().get_matches_from(argv.clone());
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let mut command_name = matches.subcommand_name().unwrap_or_default().to_string();
+    // `explain <command>` parses the command line again without the word
+    // `explain`, keeping the global options, and runs it as a dry run
+    let mut explaining = None;
+    if matches!(cli.command, Commands::Explain(_)) {
+        let mut argv = argv;
+        if let Some(position) = argv.iter().skip(1).position(|arg| arg == "explain") {
+            argv.remove(position + 1);
+        }
+        let words: Vec<String> = argv.iter().skip(1).map(|arg| arg.to_string_lossy().to_string()).collect();
+        explaining = Some(format!("git trunk {}", words.join(" ")));
+        let matches = Cli::command().try_get_matches_from(argv).unwrap_or_else(|e| e.exit());
+        cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        command_name = matches.subcommand_name().unwrap_or_default().to_string();
+        cli.dry_run = true;
+    }
+    if cli.plain {
+        // Set before logging starts; commands spawned by batch and hooks read it too
+        std::env::set_var(table::PLAIN_ENV, "1");
+    }
+    init_logger(cli.verbose, explaining.is_some());
+    if cli.yes {
+        // Prompts read the variable, so commands spawned by batch and hooks
+        // are non-interactive too
+        std::env::set_var(utils::NONINTERACTIVE_ENV, "1");
+    }
+
+    let tolerates_bad_config = matches!(cli.command, Commands::Config(_) | Commands::HookRun(_));
+    let mut store_args: Vec<Option<String>> = match ops::expand_store_patterns(&cli.store, cli.verbose) {
+        Ok(stores) if !stores.is_empty() => stores.into_iter().map(Some).collect(),
+        Ok(_) => vec![None],
+        Err(e) => exit_with(e),
+    };
+    if !cli.command.takes_store() {
+        store_args.truncate(1);
+    }
+    // Paths that name their store run the command for that store; the others
+    // still go to the --store stores
+    let verbose = cli.verbose;
+    let mut paths = cli.command.paths_mut();
+    if !paths.is_empty() {
+        if let Ok(discovered) = GitRepo::discover(verbose) {
+            let checkout_root = discovered.root().to_path_buf();
+            let repo = ops::main_repo_of_checkout(discovered, verbose);
+            let stores = ops::local_stores(&repo, verbose);
+            let mut named: Vec<Option<String>> = Vec::new();
+            let mut unnamed = false;
+            for path in paths.iter_mut() {
+                match ops::qualify_store_path(&repo, path, &stores, verbose) {
+                    Some(qualified) => {
+                        named.push(qualified.split_once(':').map(|(store, _)| store.to_string()));
+                        **path = qualified;
+                    }
+                    None => unnamed = true,
+                }
+            }
+            if !named.is_empty() {
+                if unnamed {
+                    named.append(&mut store_args);
+                }
+                store_args = named;
+                // Run from the main repository rather than the store checkout
+                // the paths were given in, now that they name their store
+                if repo.root() != checkout_root {
+                    if let Err(e) = std::env::set_current_dir(repo.root()) {
+                        exit_with(TrunkError::Io(format!("Failed to change to {}: {}", repo.root().display(), e)));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut targets: Vec<(String, String)> = Vec::new();
+    for store_arg in &store_args {
+        let (remote_name, store_name, prompt_mode) = match config::resolve_defaults(cli.remote.as_deref(), store_arg.as_deref(), cli.verbose) {
+            Ok(resolved) => resolved,
+            Err(e) if tolerates_bad_config => {
+                // Let `config validate` report every problem instead of only the first,
+                // and never let a broken config block the git operation running a hook
+                log::debug!("⚠️ Ignoring invalid config for the config command: {}", e);
+                (config::DEFAULT_REMOTE.to_string(), config::DEFAULT_STORE.to_string(), utils::PromptMode::Ask)
+            }
+            Err(e) => exit_with(TrunkError::Config(format!("Invalid config: {}", e))),
+        };
+        utils::set_prompt_mode(prompt_mode);
+        if let Err(reason) = config::check_store_name(&store_name) {
+            // The name becomes a path under .trunk and a ref under refs/trunk
+            if !tolerates_bad_config {
+                exit_with(TrunkError::Usage(format!("Invalid store name '{}': {}", store_name, reason)));
+            }
+        }
+        if !targets.iter().any(|(_, known)| known == &store_name) {
+            targets.push((remote_name, store_name));
+        }
+    }
+
+    if cli.dry_run {
+        if explaining.is_some() && (matches!(cli.command, Commands::Explain(_)) || !cli.command.supports_dry_run()) {
+            exit_with(TrunkError::Usage(format!("`git trunk {}` cannot be explained: explain supports init, commit, checkout, push, delete, stegano, hooks and release-notes", command_name)));
+        }
+        if !cli.command.supports_dry_run() {
+            exit_with(TrunkError::Usage("--dry-run is supported by init, commit, checkout, push, delete, stegano, hooks and release-notes".to_string()));
+        }
+        utils::set_dry_run(true);
+        log::info!("🔍 Dry run: nothing will be changed");
+    }
+
+    if let Err(e) = utils::check_git_dir_writable(cli.verbose) {
+        if cli.command.writes_git_dir() && !cli.dry_run {
+            exit_with(TrunkError::ReadOnly(format!("Read-only repository: {}. This command needs write access; run it from a writable clone or ask for write permission.", e)));
+        }
+        // Read-only commands still work; keep git from attempting optional
+        // index refreshes it could not write anyway
+        log::debug!("⚠️ {}, continuing read-only", e);
+        std::env::set_var("GIT_OPTIONAL_LOCKS", "0");
+    }
+
+    let needs = cli.command.needs();
+    if !needs.is_empty() {
+        let capabilities = Capabilities::detect(&needs, cli.verbose);
+        if let Err(e) = capabilities.check(&command_name, &needs, |need| cli.command.alternative(need)) {
+            exit_with(e);
+        }
+    }
+
+    let started = Instant::now();
+    let result = if let [(remote_name, store_name)] = targets.as_slice() {
+        cli.command.run(remote_name, store_name, cli.verbose)
+    } else {
+        let mut failed = 0;
+        for (remote_name, store_name) in &targets {
+            log::info!("➡️ Store '{}'", store_name);
+            if let Err(e) = cli.command.run(remote_name, store_name, cli.verbose) {
+                log::error!("❌ Store '{}': {}", store_name, e);
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            Err(TrunkError::Failed(format!("{} of {} store(s) failed", failed, targets.len())))
+        } else {
+            Ok(())
+        }
+    };
+    let stores: Vec<&str> = targets.iter().map(|(_, store_name)| store_name.as_str()).collect();
+    match &explaining {
+        Some(command_line) => commands::explain::print_plan(command_line, &result),
+        None => notify_finished(&cli, &command_name, &stores, started.elapsed(), &result),
+    }
+    if let Err(e) = result {
+        exit_with(e);
     }
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use log::debug;
+use crate::config::NotifyMode;
+
+// Tells the user a command finished, for `[trunk] notify` and `--notify`:
+// the terminal bell on stderr and/or a desktop notification through the
+// platform's own tool (notify-send on Linux and BSD, osascript on macOS).
+// Nothing here fails the command; a missing tool is only logged.
+pub fn send(mode: NotifyMode, title: &str, message: &str, success: bool) {
+    if matches!(mode, NotifyMode::Bell | NotifyMode::Both) && io::stderr().is_terminal() {
+        let _ = io::stderr().write_all(b"\x07");
+        let _ = io::stderr().flush();
+    }
+    if matches!(mode, NotifyMode::Desktop | NotifyMode::Both) {
+        if let Err(e) = desktop_notification(title, message, success) {
+            debug!("⚠️ No desktop notification: {}", e);
+        }
+    }
+}
+
+fn desktop_notification(title: &str, message: &str, success: bool) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!("display notification {} with title {}", applescript_string(message), applescript_string(title)));
+        command
+    } else if cfg!(windows) {
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "desktop notifications are not supported on Windows; use \"bell\""));
+    } else {
+        let mut command = Command::new("notify-send");
+        command.arg(format!("--urgency={}", if success { "normal" } else { "critical" })).arg("--app-name=git-trunk").arg(title).arg(message);
+        command
+    };
+    // Do not wait on the notification daemon
+    command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).spawn().map(|_| ())
+}
+
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,683 @@
+// Store operations shared by the commands. Each one is a step (or a run of
+// steps) a command used to spell out itself; callers pass the step label so
+// the log still reads like one command's progress.
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use log::{debug, error, info};
+use crate::cache::{self, Cache};
+use crate::config;
+use crate::commands::expose;
+use crate::utils::{confirm, dry_run, is_dry_run, describe_command, ensure_trunk_in_gitignore, git_common_dir, glob_match, run_git_change, run_git_command, shared_repository, apply_shared_permissions, init_object_format_arg};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+// Finds the main repository the command runs in.
+pub fn ensure_repo_root(step: &str, verbose: bool) -> Result<GitRepo, TrunkError> {
+    debug!("➡️ {}: Getting repository root", step);
+    let repo = GitRepo::discover(verbose).map_err(|e| TrunkError::Git(format!("Failed to get git repository root: {}", e)))?;
+    info!("✓ {}: Repository root found at {}", step, repo.root().display());
+    Ok(repo)
+}
+
+// Creates the .trunk directory (with the repository's shared permissions) if
+// it is missing and returns its path.
+pub fn ensure_trunk_dir(repo_root: &Path, step: &str, verbose: bool) -> Result<PathBuf, TrunkError> {
+    let parent_trunk_dir = config::trunk_base_dir(repo_root, verbose);
+    if !parent_trunk_dir.exists() && !dry_run(&format!("create directory {}", parent_trunk_dir.display())) {
+        debug!("✨ {}: Creating parent .trunk directory", step);
+        fs::create_dir(&parent_trunk_dir).map_err(|e| TrunkError::Io(format!("Failed to create .trunk parent directory: {}", e)))?;
+        if let Err(e) = apply_shared_permissions(&parent_trunk_dir, shared_repository(repo_root, verbose)) {
+            error!("⚠️ Warning: {}", e);
+        }
+        info!("✓ {}: .trunk parent directory created at {:?}", step, parent_trunk_dir);
+    }
+    Ok(parent_trunk_dir)
+}
+
+// Opens the .trunk/<store> checkout, which commands that work on files need.
+pub fn ensure_store_checkout(repo_root: &Path, store_name: &str, step: &str, verbose: bool) -> Result<GitRepo, TrunkError> {
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    debug!("➡️ {}: Checking {}", step, store_dir_relative_path);
+    let Ok(store_repo) = GitRepo::open(&config::trunk_base_dir(repo_root, verbose).join(store_name), verbose) else {
+        return Err(TrunkError::NotFound(format!("{} is not checked out. Run `git trunk checkout --store {}` to create it.", store_dir_relative_path, store_name)));
+    };
+    info!("✓ {}: {} found", step, store_dir_relative_path);
+    Ok(store_repo)
+}
+
+// Stores checked out under .trunk, sorted by name.
+pub fn checked_out_stores(repo_root: &Path, verbose: bool) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(config::trunk_base_dir(repo_root, verbose)) else { return Vec::new() };
+    let mut stores: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().join(".git").exists())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| config::is_store_name(name))
+        .collect();
+    stores.sort();
+    stores
+}
+
+// Store checkouts made at some point, one absolute path per line in
+// <git dir>/trunk-checkouts (paths, as each worktree may have its own .trunk),
+// so a checkout removed behind git-trunk's back (`git stash --all`,
+// `git clean -x`) can be told apart from one that never existed. Removing a
+// checkout through git-trunk forgets it.
+const CHECKOUTS_FILE: &str = "trunk-checkouts";
+
+fn checkouts_file(repo_root: &Path, verbose: bool) -> io::Result<PathBuf> {
+    Ok(git_common_dir(repo_root, verbose)?.join(CHECKOUTS_FILE))
+}
+
+fn recorded_checkouts(repo_root: &Path, verbose: bool) -> Vec<PathBuf> {
+    let Ok(path) = checkouts_file(repo_root, verbose) else { return Vec::new() };
+    fs::read_to_string(path).unwrap_or_default().lines().filter(|line| !line.trim().is_empty()).map(PathBuf::from).collect()
+}
+
+fn write_recorded_checkouts(repo_root: &Path, checkouts: &[PathBuf], verbose: bool) {
+    let result = checkouts_file(repo_root, verbose).and_then(|path| {
+        let content: String = checkouts.iter().map(|checkout| format!("{}\n", checkout.display())).collect();
+        fs::write(&path, content)?;
+        apply_shared_permissions(&path, shared_repository(repo_root, verbose))
+    });
+    if let Err(e) = result {
+        // Only costs the detection of a lost checkout, e.g. in a read-only repository
+        debug!("⚠️ Failed to update {}: {}", CHECKOUTS_FILE, e);
+    }
+}
+
+// Remembers that .trunk/<store> is checked out. Bookkeeping, skipped under --dry-run.
+pub fn record_checkout(repo_root: &Path, store_name: &str, verbose: bool) {
+    let checkout = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    let mut checkouts = recorded_checkouts(repo_root, verbose);
+    if is_dry_run() || checkouts.contains(&checkout) {
+        return;
+    }
+    checkouts.push(checkout);
+    checkouts.sort();
+    write_recorded_checkouts(repo_root, &checkouts, verbose);
+}
+
+// Forgets .trunk/<store> after git-trunk removed it on purpose.
+pub fn forget_checkout(repo_root: &Path, store_name: &str, verbose: bool) {
+    let checkout = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    let mut checkouts = recorded_checkouts(repo_root, verbose);
+    if is_dry_run() || !checkouts.contains(&checkout) {
+        return;
+    }
+    checkouts.retain(|recorded| *recorded != checkout);
+    write_recorded_checkouts(repo_root, &checkouts, verbose);
+}
+
+// Stores whose checkout in this .trunk disappeared without git-trunk removing
+// it while refs/trunk/<store> survives, so it can be restored from the ref.
+// Checkouts made before the record existed are added to it here; records of
+// stores without a ref are dropped, as there is nothing to restore them from.
+pub fn lost_checkouts(repo: &GitRepo, verbose: bool) -> Vec<String> {
+    let trunk_base_dir = config::trunk_base_dir(repo.root(), verbose);
+    let recorded = recorded_checkouts(repo.root(), verbose);
+    let mut lost = Vec::new();
+    let mut kept: Vec<PathBuf> = Vec::new();
+    for checkout in &recorded {
+        let store = checkout.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        if checkout.parent() != Some(trunk_base_dir.as_path()) || checkout.join(".git").exists() {
+            kept.push(checkout.clone());
+        } else if config::is_store_name(store) && repo.ref_exists(&format!("refs/trunk/{}", store)) {
+            lost.push(store.to_string());
+            kept.push(checkout.clone());
+        }
+    }
+    for store in checked_out_stores(repo.root(), verbose) {
+        let checkout = trunk_base_dir.join(store);
+        if !kept.contains(&checkout) {
+            kept.push(checkout);
+        }
+    }
+    kept.sort();
+    if kept != recorded && !is_dry_run() {
+        write_recorded_checkouts(repo.root(), &kept, verbose);
+    }
+    lost.sort();
+    lost
+}
+
+// Recreates a lost .trunk/<store> from refs/trunk/<store> alone, without
+// asking the remote, and ignores .trunk again in case `git stash --all` took
+// the .gitignore entry with it.
+pub fn restore_checkout(repo: &GitRepo, store_name: &str, step: &str, verbose: bool) -> Result<(), TrunkError> {
+    ensure_trunk_in_gitignore(repo.root(), step).map_err(|e| TrunkError::Io(format!("Failed to update .gitignore: {}", e)))?;
+    ensure_trunk_dir(repo.root(), step, verbose)?;
+    let trunk_store_dir = config::trunk_base_dir(repo.root(), verbose).join(store_name);
+    // An empty directory left behind would stop the restore
+    if trunk_store_dir.is_dir() && fs::read_dir(&trunk_store_dir).is_ok_and(|mut entries| entries.next().is_none()) {
+        let _ = fs::remove_dir(&trunk_store_dir);
+    }
+    if trunk_store_dir.exists() {
+        return Err(TrunkError::Conflict(format!(".trunk/{} exists but is not a store checkout; move it away, then run `git trunk checkout --store {}`", store_name, store_name)));
+    }
+    materialize_store(repo, store_name, verbose)?;
+    expose::sync_exposed_branch(repo, store_name, verbose);
+    Ok(())
+}
+
+// Subject of the first commit `git trunk init` makes in every store.
+const INITIAL_COMMIT_SUBJECT: &str = "Initial commit for store '";
+
+// Refs under refs/trunk/ that git-trunk did not create, with the reason: names
+// that are not a store name, objects that are not commits, and histories that
+// do not start with the commit `git trunk init` makes.
+fn foreign_trunk_refs(repo: &GitRepo, verbose: bool) -> Vec<(String, String)> {
+    let output = match run_git_command(repo.git().arg("for-each-ref").arg("--format=%(refname) %(objecttype)").arg("refs/trunk/"), verbose) {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+    let mut foreign = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((ref_name, object_type)) = line.split_once(' ') else { continue };
+        let name = ref_name.strip_prefix("refs/trunk/").unwrap_or(ref_name);
+        let reason = if !config::is_store_name(name) {
+            "not a store name".to_string()
+        } else if object_type != "commit" {
+            format!("points to a {}", object_type)
+        } else {
+            let roots = run_git_command(repo.git().arg("log").arg("--max-parents=0").arg("--format=%s").arg(ref_name), verbose)
+                .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+                .unwrap_or_default();
+            if roots.lines().any(|subject| subject.starts_with(INITIAL_COMMIT_SUBJECT)) {
+                continue;
+            }
+            "history not started by `git trunk init`".to_string()
+        };
+        foreign.push((ref_name.to_string(), reason));
+    }
+    foreign
+}
+
+// First-run check, done until the first store checkout exists: other tooling
+// may already use refs/trunk/, so its refs are reported loudly and the user is
+// asked before git-trunk shares the namespace with them. Accepted refs are
+// recorded in `[trunk] foreign_refs` and not reported again.
+pub fn check_foreign_refs(repo: &GitRepo, step: &str, verbose: bool) -> Result<(), TrunkError> {
+    debug!("➡️ {}: Checking refs/trunk/ for refs git-trunk did not create", step);
+    if !checked_out_stores(repo.root(), verbose).is_empty() {
+        debug!("= {}: Store checkouts exist, not a first run", step);
+        return Ok(());
+    }
+    let trunk_config = config::load(repo.root(), verbose).ok();
+    let acknowledged = trunk_config.as_ref().map(|c| c.get_array("trunk", "foreign_refs")).unwrap_or_default();
+    let foreign: Vec<(String, String)> = foreign_trunk_refs(repo, verbose)
+        .into_iter()
+        .filter(|(ref_name, _)| !acknowledged.contains(ref_name))
+        .collect();
+    if foreign.is_empty() {
+        debug!("✓ {}: refs/trunk/ holds no foreign refs", step);
+        return Ok(());
+    }
+
+    error!("⚠️ Warning: refs/trunk/ already holds {} ref(s) that git-trunk did not create:", foreign.len());
+    for (ref_name, reason) in &foreign {
+        error!("⚠️   {} ({})", ref_name, reason);
+    }
+    error!("⚠️ Another tool probably uses this namespace. git-trunk stores would live next to these refs, and `push --all` or `status --all` may pick them up.");
+    let ref_names: Vec<String> = foreign.into_iter().map(|(ref_name, _)| ref_name).collect();
+    if !confirm("Use refs/trunk/ alongside these refs?")? {
+        info!("🚫 {}: Stopped before writing to refs/trunk/", step);
+        return Err(TrunkError::Conflict(format!(
+            "refs/trunk/ holds refs git-trunk did not create ({}). Move them out of refs/trunk/, or confirm to share the namespace.",
+            ref_names.join(", ")
+        )));
+    }
+    if dry_run(&format!("record {} in [trunk] foreign_refs", ref_names.join(", "))) {
+        return Ok(());
+    }
+    match trunk_config {
+        Some(trunk_config) => {
+            let mut recorded = acknowledged;
+            recorded.extend(ref_names);
+            if let Err(e) = config::set_value(&trunk_config, "trunk", "foreign_refs", Some(&config::ConfigValue::Array(recorded))) {
+                error!("⚠️ Warning: Could not record the foreign refs in the config: {}", e);
+            } else {
+                info!("✓ {}: Recorded the foreign refs in [trunk] foreign_refs", step);
+            }
+        }
+        None => error!("⚠️ Warning: The config could not be loaded, so the foreign refs will be reported again"),
+    }
+    Ok(())
+}
+
+// Stores known locally: refs/trunk/*, checkouts under .trunk and the
+// [store.<name>] sections of the config, sorted by name.
+pub fn local_stores(repo: &GitRepo, verbose: bool) -> Vec<String> {
+    let mut stores = checked_out_stores(repo.root(), verbose);
+    stores.extend(repo.ref_names("refs/trunk/").unwrap_or_default().into_iter().filter(|name| config::is_store_name(name)));
+    if let Ok(trunk_config) = config::load(repo.root(), verbose) {
+        stores.extend(trunk_config.store_names().into_iter().filter(|name| config::is_store_name(name)));
+    }
+    stores.sort();
+    stores.dedup();
+    stores
+}
+
+// Path arguments of path-centric commands (`history`, `lock`, `unlock`) can
+// name their store: `blog:drafts/post.md`, or a path into .trunk/<store>
+// relative to the current directory. Other paths are relative to the
+// --store store as before.
+pub fn split_store_path<'a>(arg: &'a str, stores: &[String]) -> Option<(&'a str, &'a str)> {
+    let (store, path) = arg.split_once(':')?;
+    stores.iter().any(|known| known == store).then_some((store, path))
+}
+
+fn lexically_normalized(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// The main repository when `repo` is a store checkout in its .trunk, which
+// git finds first from a directory inside the checkout.
+pub fn main_repo_of_checkout(repo: GitRepo, verbose: bool) -> GitRepo {
+    let Some(trunk_dir) = repo.root().parent().filter(|dir| dir.file_name().is_some_and(|name| name == ".trunk")) else {
+        return repo;
+    };
+    match trunk_dir.parent().and_then(|main_root| GitRepo::open(main_root, verbose).ok()) {
+        Some(main_repo) if config::trunk_base_dir(main_repo.root(), verbose) == trunk_dir => main_repo,
+        _ => repo,
+    }
+}
+
+// `arg` in the `store:path` form when it names a store, None otherwise.
+pub fn qualify_store_path(repo: &GitRepo, arg: &str, stores: &[String], verbose: bool) -> Option<String> {
+    if split_store_path(arg, stores).is_some() {
+        return Some(arg.to_string());
+    }
+    let absolute = lexically_normalized(&std::env::current_dir().ok()?.join(arg));
+    let trunk_base_dir = fs::canonicalize(config::trunk_base_dir(repo.root(), verbose)).ok()?;
+    let mut components = absolute.strip_prefix(&trunk_base_dir).ok()?.components();
+    let store = components.next()?.as_os_str().to_str()?.to_string();
+    let path = components.as_path().to_str()?;
+    (config::is_store_name(&store) && !path.is_empty()).then(|| format!("{}:{}", store, path))
+}
+
+// The paths a run for `store_name` works on: those qualified with it, without
+// the prefix, and the unqualified ones.
+pub fn store_paths(repo: &GitRepo, paths: &[String], store_name: &str, verbose: bool) -> Vec<String> {
+    let stores = local_stores(repo, verbose);
+    paths
+        .iter()
+        .filter_map(|arg| match split_store_path(arg, &stores) {
+            Some((store, path)) => (store == store_name).then(|| path.to_string()),
+            None => Some(arg.clone()),
+        })
+        .collect()
+}
+
+// Expands the --store values: plain names are kept, patterns with `*` or `?`
+// become the local stores they match. Order is kept and duplicates dropped.
+pub fn expand_store_patterns(patterns: &[String], verbose: bool) -> Result<Vec<String>, TrunkError> {
+    let mut local: Option<Vec<String>> = None;
+    let mut stores: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let matches = if pattern.contains(['*', '?']) {
+            let local = local.get_or_insert_with(|| GitRepo::discover(verbose).map(|repo| local_stores(&repo, verbose)).unwrap_or_default());
+            let matches: Vec<String> = local.iter().filter(|name| glob_match(pattern, name)).cloned().collect();
+            if matches.is_empty() {
+                return Err(TrunkError::NotFound(format!("No local store matches '{}'", pattern)));
+            }
+            debug!("✓ --store '{}' matches {}", pattern, matches.join(", "));
+            matches
+        } else {
+            vec![pattern.clone()]
+        };
+        for name in matches {
+            if !stores.contains(&name) {
+                stores.push(name);
+            }
+        }
+    }
+    Ok(stores)
+}
+
+// Hash of refs/trunk/<store> on the remote, None when the remote has no such
+// ref. Fails when the remote cannot be reached.
+pub fn remote_store_tip(repo: &GitRepo, remote_name: &str, store_name: &str, verbose: bool) -> Result<Option<String>, TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let output = run_git_command(repo.git().arg("ls-remote").arg(remote_name).arg(&trunk_ref_name), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to reach remote '{}': {}", remote_name, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(TrunkError::Git(format!("Failed to reach remote '{}': {}", remote_name, if stderr.is_empty() { "git ls-remote failed" } else { &stderr })));
+    }
+    // ls-remote patterns match the end of a ref name, so refs/for/refs/trunk/<store> matches too
+    Ok(String::from_utf8_lossy(&output.stdout).lines().find_map(|line| {
+        let (hash, name) = line.split_once(char::is_whitespace)?;
+        (name.trim() == trunk_ref_name).then(|| hash.to_string())
+    }))
+}
+
+// Stores the remote has under refs/trunk/*, sorted by name.
+pub fn remote_store_names(repo: &GitRepo, remote_name: &str, verbose: bool) -> Result<Vec<String>, TrunkError> {
+    Ok(remote_store_tips(repo, remote_name, verbose)?.into_iter().map(|(store, _)| store).collect())
+}
+
+// Each store on the remote with the hash of its refs/trunk/<store>, sorted by
+// store name, from a single `git ls-remote`.
+pub fn remote_store_tips(repo: &GitRepo, remote_name: &str, verbose: bool) -> Result<Vec<(String, String)>, TrunkError> {
+    let output = run_git_command(repo.git().arg("ls-remote").arg(remote_name).arg("refs/trunk/*"), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to reach remote '{}': {}", remote_name, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(TrunkError::Git(format!("Failed to reach remote '{}': {}", remote_name, if stderr.is_empty() { "git ls-remote failed" } else { &stderr })));
+    }
+    let mut tips: Vec<(String, String)> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (hash, name) = line.split_once(char::is_whitespace)?;
+            Some((name.trim().strip_prefix("refs/trunk/")?.to_string(), hash.to_string()))
+        })
+        .filter(|(name, _)| config::is_store_name(name))
+        .collect();
+    tips.sort();
+    Ok(tips)
+}
+
+fn remote_listing_key(remote_name: &str) -> String {
+    format!("ls-remote-{}", cache::sanitize_key(remote_name))
+}
+
+// Hash of refs/trunk/<store> on the remote, read from a listing of the
+// remote's refs/trunk/* that is cached for `[trunk] remote_cache_ttl`
+// seconds, so `status --all` and concurrent invocations ask the remote once.
+// The outer None means the cache is off or the remote could not be listed,
+// and the caller should ask the remote itself.
+pub fn cached_remote_store_tip(repo: &GitRepo, remote_name: &str, store_name: &str, verbose: bool) -> Option<Option<String>> {
+    let ttl = config::load(repo.root(), verbose).ok()?.get_integer("trunk", "remote_cache_ttl").unwrap_or(0);
+    if ttl <= 0 {
+        return None;
+    }
+    let cache = Cache::open(repo).map_err(|e| debug!("⚠️ No cache directory: {}", e)).ok()?;
+    let key = remote_listing_key(remote_name);
+    let listing = {
+        let _lock = cache.lock().map_err(|e| debug!("⚠️ Not using the cache: {}", e)).ok()?;
+        match cache.read(&key) {
+            Some((listing, age)) if age < Duration::from_secs(ttl as u64) => {
+                debug!("💾 Using the listing of '{}' cached {}s ago", remote_name, age.as_secs());
+                listing
+            }
+            _ => {
+                let output = run_git_command(repo.git().arg("ls-remote").arg(remote_name).arg("refs/trunk/*"), verbose).ok()?;
+                if !output.status.success() {
+                    return None;
+                }
+                let listing = String::from_utf8_lossy(&output.stdout).to_string();
+                if let Err(e) = cache.write(&key, &listing) {
+                    debug!("⚠️ Failed to cache the listing of '{}': {}", remote_name, e);
+                }
+                listing
+            }
+        }
+    };
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    Some(listing.lines().find_map(|line| {
+        let (hash, name) = line.split_once(char::is_whitespace)?;
+        (name.trim() == trunk_ref_name).then(|| hash.to_string())
+    }))
+}
+
+// Drops the cached listing of the remote once its refs/trunk/* changed.
+pub fn forget_remote_listing(repo: &GitRepo, remote_name: &str) {
+    if let Ok(cache) = Cache::open(repo) {
+        if let Err(e) = cache.remove(&remote_listing_key(remote_name)) {
+            debug!("⚠️ Failed to drop the cached listing of '{}': {}", remote_name, e);
+        }
+    }
+}
+
+// Fetches refs/trunk/<store> from the remote into `dest_ref` of the main
+// repository, replacing whatever it pointed at.
+pub fn fetch_store_ref(repo: &GitRepo, remote_name: &str, store_name: &str, dest_ref: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let output = run_git_change(repo.git().arg("fetch").arg(remote_name).arg(format!("+{}:{}", trunk_ref_name, dest_ref)), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to fetch {} from remote '{}': {}", trunk_ref_name, remote_name, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(TrunkError::Git(format!("Failed to fetch {} from remote '{}': {}", trunk_ref_name, remote_name, if stderr.is_empty() { "git fetch failed" } else { &stderr })));
+    }
+    Ok(())
+}
+
+// Points refs/trunk/<store> at the main branch of the store checkout, copying
+// its objects into the main repository. Returns the previous and the new hash
+// of the ref, and mirrors the exposed branch if the store has one.
+pub fn update_store_ref(repo: &GitRepo, store_repo: &GitRepo, store_name: &str, verbose: bool) -> Result<(Option<String>, String), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let previous = repo.resolve_commit(&trunk_ref_name);
+    let commit_hash = store_repo
+        .resolve_commit("main")
+        .ok_or_else(|| TrunkError::Git(format!("Failed to get commit hash from {}. It might be empty or not have commits on 'main'.", store_dir_relative_path)))?;
+    repo.fetch_local(store_repo.root(), &format!("+main:{}", trunk_ref_name))
+        .map_err(|e| TrunkError::Git(format!("Failed to update {} from {}: {}", trunk_ref_name, store_dir_relative_path, e)))?;
+    expose::sync_exposed_branch(repo, store_name, verbose);
+    Ok((previous, commit_hash))
+}
+
+// Whether `path` is `prefix` or lies under the directory `prefix`.
+pub fn is_under(path: &str, prefix: &str) -> bool {
+    path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+// The checkout a commit made straight to refs/trunk/<store> (`put`, `rm`) has
+// to follow, if the store is checked out. Refuses a checkout with commits the
+// ref at `tip` does not have, or with uncommitted changes to `paths` (files,
+// or directories and everything under them), which the commit would drop.
+pub fn checkout_to_follow(repo: &GitRepo, store_name: &str, tip: &str, paths: &[String], verbose: bool) -> Result<Option<GitRepo>, TrunkError> {
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let Some(store_repo) = GitRepo::open(&config::trunk_base_dir(repo.root(), verbose).join(store_name), verbose).ok().filter(|store_repo| store_repo.resolve_commit("HEAD").is_some()) else {
+        return Ok(None);
+    };
+    // The main repository has every commit of refs/trunk/<store>, and none
+    // the checkout made since its last `git trunk commit`
+    if !repo.is_ancestor(&store_repo.resolve_commit("HEAD").unwrap_or_default(), tip) {
+        return Err(TrunkError::Conflict(format!(
+            "{} has commits that are not in refs/trunk/{}. Run `git trunk commit --store {}` first.",
+            store_dir_relative_path, store_name, store_name
+        )));
+    }
+    let status = store_repo.status_porcelain().map_err(|e| TrunkError::Git(format!("Failed to read the status of {}: {}", store_dir_relative_path, e)))?;
+    let changed = status.iter().filter_map(|line| line.get(3..)).flat_map(|path| path.split(" -> ")).map(|path| path.trim_end_matches('/'));
+    for path in changed {
+        if let Some(target) = paths.iter().find(|target| is_under(path, target) || is_under(target, path)) {
+            return Err(TrunkError::Conflict(format!(
+                "{}/{} has uncommitted changes the commit would overwrite. Commit or discard them first.",
+                store_dir_relative_path, target
+            )));
+        }
+    }
+    Ok(Some(store_repo))
+}
+
+// Commits `changes` (see `GitRepo::commit_tree_changes`) on top of `parent`,
+// the current refs/trunk/<store>, and moves the ref only if it is still at
+// `parent`, so a concurrent commit is never lost. Returns the new commit, or
+// None when the store already matched.
+pub fn commit_to_store_ref(repo: &GitRepo, store_name: &str, parent: &str, changes: &[(String, Option<Vec<u8>>)], message: &str, verbose: bool) -> Result<Option<String>, TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let commit = repo
+        .commit_tree_changes(parent, changes, message)
+        .map_err(|e| TrunkError::Git(format!("Failed to commit to {}: {}", trunk_ref_name, e)))?;
+    let Some(commit) = commit else { return Ok(None) };
+    move_store_ref(repo, store_name, parent, &commit, message, verbose)?;
+    Ok(Some(commit))
+}
+
+// Moves refs/trunk/<store> from `old` to `new`, recording the first line of
+// `message` in the reflog, and mirrors the exposed branch. Fails with a
+// conflict when the ref is no longer at `old`.
+pub fn move_store_ref(repo: &GitRepo, store_name: &str, old: &str, new: &str, message: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let reflog = format!("git trunk: {}", message.lines().next().unwrap_or_default());
+    let output = run_git_change(repo.git().arg("update-ref").arg("-m").arg(reflog).arg(&trunk_ref_name).arg(new).arg(old), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to update {}: {}", trunk_ref_name, e)))?;
+    if !output.status.success() {
+        return Err(TrunkError::Conflict(format!("{} changed while the commit was being written; run the command again", trunk_ref_name)));
+    }
+    expose::sync_exposed_branch(repo, store_name, verbose);
+    Ok(())
+}
+
+fn git_checked(command: &mut Command, what: &str, verbose: bool) -> io::Result<()> {
+    run_git_command(command, verbose).and_then(|out| if !out.status.success() { Err(io::Error::other(format!("{} failed", what))) } else { Ok(()) })
+}
+
+// Creates .trunk/<store> as a fresh repository on the main branch holding
+// refs/trunk/<store>. The directory must not exist yet (Steps 9-15 of checkout).
+pub fn materialize_store(repo: &GitRepo, store_name: &str, verbose: bool) -> Result<(), TrunkError> {
+    let repo_root = repo.root();
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    let shared = shared_repository(repo_root, verbose);
+
+    // The Step 10 git init command, built early so --dry-run can show it
+    let mut init_command = Command::new("git");
+    init_command.arg("init").current_dir(&trunk_store_dir);
+    if let Some(shared_value) = shared.init_value() {
+        init_command.arg(format!("--shared={}", shared_value));
+    }
+    if let Some(format_arg) = init_object_format_arg(repo_root, verbose) {
+        init_command.arg(format_arg);
+    }
+    if is_dry_run() {
+        // Nothing below can run without the directory, so describe it instead
+        dry_run(&format!("create directory {}", trunk_store_dir.display()));
+        dry_run(&format!("run {}", describe_command(&init_command)));
+        dry_run(&format!("fetch {} from {} into {}", trunk_ref_name, repo_root.display(), trunk_store_dir.display()));
+        dry_run(&format!("reset refs/heads/main in {} to {} and check it out", trunk_store_dir.display(), trunk_ref_name));
+        return Ok(());
+    }
+
+    // Step 9: Create .trunk/<store_name> directory
+    debug!("✨ Step 9: Creating {} directory for store '{}'", store_dir_relative_path, store_name);
+    fs::create_dir_all(&trunk_store_dir).map_err(|e| TrunkError::Io(format!("Failed to create {} directory: {}", store_dir_relative_path, e)))?;
+    if let Err(e) = apply_shared_permissions(&trunk_store_dir, shared) {
+        error!("⚠️ Warning: {}", e);
+    }
+    info!("✓ Step 9: {} directory created for store '{}'", store_dir_relative_path, store_name);
+
+    // Step 10: Initialize Git repository in .trunk/<store_name>
+    debug!("⚙️ Step 10: Initializing Git repository in {}", store_dir_relative_path);
+    git_checked(&mut init_command, "git init", verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to run git init in {}: {}", store_dir_relative_path, e)))?;
+    info!("✓ Step 10: Git repository initialized in {}", store_dir_relative_path);
+    let store_repo = GitRepo::open(&trunk_store_dir, verbose).map_err(|e| TrunkError::Git(format!("Failed to open {}: {}", store_dir_relative_path, e)))?;
+
+    // Step 11: Fetch history from main repo's refs/trunk/<store_name> into a temporary ref in .trunk/<store_name>
+    let temp_store_ref = "refs/temp/trunk_store_data";
+    debug!("📥 Step 11: Fetching {} from main repo into {} temporary ref '{}'", trunk_ref_name, store_dir_relative_path, temp_store_ref);
+    store_repo.fetch_local(repo_root, &format!("{}:{}", trunk_ref_name, temp_store_ref))
+        .map_err(|e| TrunkError::Git(format!("Failed to fetch {} into {}: {}", trunk_ref_name, store_dir_relative_path, e)))?;
+    info!("✓ Step 11: Successfully fetched {} into temporary ref in {}", trunk_ref_name, store_dir_relative_path);
+
+    // Step 12: Get the fetched commit hash from the temporary ref
+    debug!("🔑 Step 12: Getting fetched commit hash from {} in {}", temp_store_ref, store_dir_relative_path);
+    let commit_hash = store_repo
+        .resolve_commit(temp_store_ref)
+        .ok_or_else(|| TrunkError::NotFound(format!("{} not found after fetch in {}", temp_store_ref, store_dir_relative_path)))?;
+    info!("✓ Step 12: Fetched commit hash for store '{}': {}", store_name, commit_hash);
+
+    // Step 13: Reset main branch in .trunk/<store_name> to the fetched commit
+    debug!("🔄 Step 13: Resetting {} main branch to fetched commit {}", store_dir_relative_path, commit_hash);
+    git_checked(store_repo.git().arg("reset").arg("--hard").arg(&commit_hash), "git reset", verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to reset {} to fetched commit: {}", store_dir_relative_path, e)))?;
+    info!("✓ Step 13: Main branch in {} reset to commit {}", store_dir_relative_path, commit_hash);
+
+    // Step 14: Update main branch ref in .trunk/<store_name> (git reset --hard might not update HEAD if not on a branch yet)
+    debug!("🔄 Step 14: Updating refs/heads/main in {}", store_dir_relative_path);
+    store_repo.update_ref("refs/heads/main", &commit_hash)
+        .map_err(|e| TrunkError::Git(format!("Failed to update refs/heads/main in {}: {}", store_dir_relative_path, e)))?;
+    info!("✓ Step 14: refs/heads/main updated in {}", store_dir_relative_path);
+
+    // Step 14b: Ensure .trunk/<store_name> is on the main branch
+    debug!("⤵️ Step 14b: Ensuring {} is on the main branch", store_dir_relative_path);
+    git_checked(store_repo.git().arg("checkout").arg("main"), "git checkout main", verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to checkout main in {}: {}", store_dir_relative_path, e)))?;
+
+    // Step 15: Clean up temporary ref in .trunk/<store_name>
+    debug!("🧹 Step 15: Cleaning up temporary ref {} in {}", temp_store_ref, store_dir_relative_path);
+    if let Err(e) = store_repo.delete_ref(temp_store_ref) {
+        error!("⚠️ Warning: Failed to delete temporary ref {} in {}: {}", temp_store_ref, store_dir_relative_path, e);
+    }
+    info!("✓ Step 15: Temporary ref cleaned up in {}", store_dir_relative_path);
+    record_checkout(repo_root, store_name, verbose);
+    Ok(())
+}
+
+// Removes .trunk/<store>. Returns whether it is gone (or under --dry-run
+// would be); a failure is logged so the caller can go on cleaning up refs.
+pub fn remove_store_dir(repo_root: &Path, store_name: &str, step: &str, verbose: bool) -> bool {
+    let store_dir_relative_path = format!(".trunk/{}", store_name);
+    let trunk_store_dir = config::trunk_base_dir(repo_root, verbose).join(store_name);
+    debug!("➡️ {}: Checking for {} directory for store '{}'", step, store_dir_relative_path, store_name);
+    if !trunk_store_dir.exists() {
+        info!("= {}: No {} directory to remove for store '{}'", step, store_dir_relative_path, store_name);
+        return true;
+    }
+    if dry_run(&format!("remove directory {}", trunk_store_dir.display())) {
+        return true;
+    }
+    debug!("🗑️ {}: Removing {} directory for store '{}'", step, store_dir_relative_path, store_name);
+    match fs::remove_dir_all(&trunk_store_dir) {
+        Ok(()) => {
+            forget_checkout(repo_root, store_name, verbose);
+            info!("✓ {}: {} directory removed for store '{}'", step, store_dir_relative_path, store_name);
+            true
+        }
+        Err(e) => {
+            error!("❌ Failed to remove {} directory: {}", store_dir_relative_path, e);
+            false
+        }
+    }
+}
+
+// Removes the .trunk directory once its last store is gone. Returns whether it
+// was (or under --dry-run would be) removed.
+pub fn remove_empty_trunk_dir(repo_root: &Path, removed_store_dir: &Path, step: &str, verbose: bool) -> bool {
+    let parent_trunk_dir = config::trunk_base_dir(repo_root, verbose);
+    if !parent_trunk_dir.exists() {
+        return false;
+    }
+    let entries = match fs::read_dir(&parent_trunk_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("⚠️ Warning: Could not read .trunk directory contents at {}: {}", parent_trunk_dir.display(), e);
+            return false;
+        }
+    };
+    // Under --dry-run the store directory is still there
+    let mut entries = entries.filter_map(Result::ok).filter(|entry| !is_dry_run() || entry.path() != removed_store_dir);
+    if entries.next().is_some() {
+        debug!("ℹ️ {}: .trunk directory is not empty, retaining it.", step);
+        return false;
+    }
+    debug!("🗑️ {}: .trunk directory is empty, removing it.", step);
+    if dry_run(&format!("remove directory {}", parent_trunk_dir.display())) {
+        return true;
+    }
+    if let Err(e) = fs::remove_dir(&parent_trunk_dir) {
+        error!("⚠️ Warning: Failed to remove empty .trunk directory at {}: {}", parent_trunk_dir.display(), e);
+        return false;
+    }
+    info!("✓ {}: Empty .trunk directory removed.", step);
+    true
+}
@@ -0,0 +1,80 @@
+use log::debug;
+use crate::git::GitRepo;
+use crate::locks::Identity;
+use crate::utils::{glob_match, read_blobs};
+
+// Who owns which paths of a store, CODEOWNERS style: the TRUNKOWNERS file at
+// the root of the store holds a line per rule, a path pattern followed by its
+// owners (e-mail addresses, or user.name values as `name` or `@name`). The
+// last rule matching a path wins, and a rule without owners leaves the path
+// unowned. Rules are read from refs/trunk/<store>, so they take effect once
+// committed.
+pub const OWNERS_FILE: &str = "TRUNKOWNERS";
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub pattern: String,
+    pub owners: Vec<String>,
+    // 1-based line in TRUNKOWNERS
+    pub line: usize,
+}
+
+impl Rule {
+    // Patterns work like .gitignore: one with a slash other than at the end
+    // is relative to the store root, others match at any depth, and one
+    // matching a directory matches everything under it. A path ending in `/`
+    // is a directory, as `git status` lists untracked ones.
+    pub fn matches(&self, path: &str) -> bool {
+        let dir_only = self.pattern.ends_with('/');
+        let pattern = self.pattern.trim_end_matches('/');
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+        let is_dir = path.ends_with('/');
+        let path = path.trim_end_matches('/');
+        // The path and each directory above it: a/b/c.md, a/b, a
+        let candidates = std::iter::successors(Some(path), |candidate| candidate.rsplit_once('/').map(|(parent, _)| parent));
+        candidates.filter(|candidate| !dir_only || is_dir || *candidate != path).any(|candidate| {
+            glob_match(pattern, candidate)
+                || (!anchored && candidate.match_indices('/').any(|(index, _)| glob_match(pattern, &candidate[index + 1..])))
+        })
+    }
+
+    pub fn is_owner(&self, me: &Identity) -> bool {
+        self.owners.iter().any(|owner| {
+            let owner = owner.trim_start_matches('@').trim_start_matches('<').trim_end_matches('>');
+            owner.eq_ignore_ascii_case(&me.email) || owner == me.name
+        })
+    }
+}
+
+pub fn parse(text: &str) -> Vec<Rule> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.split_once('#').map_or(line, |(rule, _)| rule);
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?;
+            Some(Rule { pattern: pattern.to_string(), owners: fields.map(str::to_string).collect(), line: index + 1 })
+        })
+        .collect()
+}
+
+// The rules in TRUNKOWNERS of `commit`, or None when the store has no such file.
+pub fn read_at(repo: &GitRepo, commit: &str, verbose: bool) -> Option<Vec<Rule>> {
+    let content = read_blobs(repo.root(), &[format!("{}:{}", commit, OWNERS_FILE)], verbose).ok()?.pop()??;
+    let rules = parse(&String::from_utf8_lossy(&content));
+    debug!("Read {} rule(s) from {} of {}", rules.len(), OWNERS_FILE, commit);
+    Some(rules)
+}
+
+// The rules of refs/trunk/<store>; none when the store or the file is missing.
+pub fn read(repo: &GitRepo, store_name: &str, verbose: bool) -> Vec<Rule> {
+    repo.resolve_commit(&format!("refs/trunk/{}", store_name))
+        .and_then(|commit| read_at(repo, &commit, verbose))
+        .unwrap_or_default()
+}
+
+// The rule that decides who owns `path`: the last one matching it.
+pub fn rule_for<'a>(rules: &'a [Rule], path: &str) -> Option<&'a Rule> {
+    rules.iter().rev().find(|rule| rule.matches(path))
+}
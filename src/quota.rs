@@ -0,0 +1,161 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use log::{debug, error, info};
+use crate::config::{self, TrunkConfig};
+use crate::utils::run_git_command;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+
+// What happens when a commit would take a store over its quota.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuotaPolicy {
+    Block,
+    Warn,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Quota {
+    pub max_bytes: u64,
+    pub policy: QuotaPolicy,
+}
+
+// Parses sizes such as "500MB", "1.5 GiB", "800k" or "1048576" (bytes).
+// Units are binary: 1 KB = 1024 bytes.
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, UNITS[unit]) }
+}
+
+// The quota of a store from `[store.<name>] max_size` and `max_size_policy`.
+pub fn store_quota(trunk_config: &TrunkConfig, store_name: &str) -> Result<Option<Quota>, String> {
+    let section = format!("store.{}", store_name);
+    let Some(max_size) = trunk_config.get_string(&section, "max_size") else { return Ok(None) };
+    let max_bytes = parse_size(&max_size).ok_or_else(|| format!("invalid max_size '{}' for store '{}' (expected e.g. \"500MB\")", max_size, store_name))?;
+    let policy = match trunk_config.get_string(&section, "max_size_policy").as_deref() {
+        None | Some("block") => QuotaPolicy::Block,
+        Some("warn") => QuotaPolicy::Warn,
+        Some(other) => return Err(format!("invalid max_size_policy '{}' for store '{}' (expected \"block\" or \"warn\")", other, store_name)),
+    };
+    Ok(Some(Quota { max_bytes, policy }))
+}
+
+// On-disk size of every object reachable from `rev`, as git stores them
+// (compressed, and delta-compressed once packed).
+pub fn reachable_size(repo: &Path, rev: &str, verbose: bool) -> io::Result<u64> {
+    let output = run_git_command(Command::new("git").arg("rev-list").arg("--objects").arg("--disk-usage").arg(rev).current_dir(repo), verbose)?;
+    if !output.status.success() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} not found", rev)));
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().map_err(|_| io::Error::other("unexpected git rev-list --disk-usage output"))
+}
+
+// Estimated disk space a checkout of `rev` takes: every file of its tree,
+// uncompressed, plus the objects the checkout's repository fetches.
+pub fn checkout_size(repo: &Path, rev: &str, verbose: bool) -> io::Result<u64> {
+    let output = run_git_command(Command::new("git").arg("ls-tree").arg("-r").arg("-l").arg("-z").arg(rev).current_dir(repo), verbose)?;
+    if !output.status.success() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} not found", rev)));
+    }
+    // "<mode> <type> <object> <size>\t<path>"; submodules have "-" for a size
+    let files: u64 = output.stdout
+        .split(|b| *b == 0)
+        .filter_map(|entry| {
+            let header = entry.split(|b| *b == b'\t').next()?;
+            String::from_utf8_lossy(header).split_whitespace().nth(3)?.parse::<u64>().ok()
+        })
+        .sum();
+    Ok(files + reachable_size(repo, rev, verbose)?)
+}
+
+// Space unprivileged users may still fill on the filesystem holding `dir`,
+// or None where it cannot be measured.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // the statvfs field types differ between platforms
+pub fn available_space(dir: &Path) -> io::Result<Option<u64>> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    let path = CString::new(dir.as_os_str().as_bytes()).map_err(io::Error::other)?;
+    // SAFETY: `path` is NUL-terminated and `stat` is a plain struct statvfs fills in
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(Some(stat.f_bavail as u64 * stat.f_frsize as u64))
+}
+
+#[cfg(not(unix))]
+pub fn available_space(_dir: &Path) -> io::Result<Option<u64>> {
+    Ok(None)
+}
+
+// Uncompressed size of every changed file in a store checkout, an upper
+// bound of what committing them adds since git compresses them.
+pub fn pending_size(store_dir: &Path, verbose: bool) -> io::Result<u64> {
+    let status = run_git_command(Command::new("git").arg("status").arg("--porcelain").arg("-z").arg("--untracked-files=all").current_dir(store_dir), verbose)?;
+    let pending: u64 = status.stdout
+        .split(|b| *b == 0)
+        .filter(|entry| entry.len() > 3)
+        .map(|entry| String::from_utf8_lossy(&entry[3..]).to_string())
+        .filter_map(|path| fs::metadata(store_dir.join(path)).ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum();
+    Ok(pending)
+}
+
+// Checks that adding `added_bytes` to refs/trunk/<store> keeps the store
+// within its quota: over it, fails under the "block" policy and warns under
+// "warn". Every command that writes into a store runs this before moving
+// the ref.
+pub fn enforce(repo: &GitRepo, store_name: &str, added_bytes: u64, step: &str, verbose: bool) -> Result<(), TrunkError> {
+    let trunk_config = config::load(repo.root(), verbose).map_err(|e| TrunkError::Config(format!("Failed to load config: {}", e)))?;
+    let Some(store_quota) = store_quota(&trunk_config, store_name).map_err(TrunkError::Config)? else { return Ok(()) };
+    debug!("➡️ {}: Checking the size quota of store '{}'", step, store_name);
+    let trunk_ref_name = format!("refs/trunk/{}", store_name);
+    let current = if repo.ref_exists(&trunk_ref_name) {
+        match reachable_size(repo.root(), &trunk_ref_name, verbose) {
+            Ok(size) => size,
+            Err(e) => {
+                error!("⚠️ Warning: Could not compute the size of store '{}': {}", store_name, e);
+                return Ok(());
+            }
+        }
+    } else {
+        0
+    };
+    let projected = current + added_bytes;
+    if projected <= store_quota.max_bytes {
+        info!("✓ {}: Store '{}' stays within its quota ({} of {})", step, store_name, format_size(projected), format_size(store_quota.max_bytes));
+        return Ok(());
+    }
+    let message = format!("Store '{}' would grow to about {}, over its quota of {}", store_name, format_size(projected), format_size(store_quota.max_bytes));
+    if store_quota.policy == QuotaPolicy::Block {
+        return Err(TrunkError::Conflict(format!("{}. Leave out large files or raise [store.{}] max_size.", message, store_name)));
+    }
+    error!("⚠️ Warning: {}", message);
+    Ok(())
+}
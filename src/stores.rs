@@ -0,0 +1,257 @@
+//! Store discovery for tools that embed git-trunk.
+//!
+//! [`stores`] lists the stores of a repository, the same ones `git trunk
+//! status --all` reports: checkouts in `.trunk`, `refs/trunk/*` refs and
+//! stores declared in `.git/trunk.toml`. Iterating yields one [`StoreInfo`]
+//! per store, gathered when it is reached.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use chrono::{DateTime, Local};
+use log::debug;
+use crate::config::{self, TrunkConfig};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+use crate::ops;
+use crate::quota;
+use crate::utils::run_git_command;
+
+/// Everything git-trunk knows about one store without changing anything.
+///
+/// Fields that could not be determined (no checkout, no ref, an unreachable
+/// remote) are `None`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoreInfo {
+    /// Store name, the `<store>` of `.trunk/<store>` and `refs/trunk/<store>`.
+    pub name: String,
+    /// Path of the checkout, whether or not it exists.
+    pub local_path: PathBuf,
+    pub local_path_exists: bool,
+    /// Whether the checkout is a git repository.
+    pub is_git_repo: bool,
+    /// Date and hash of the checkout's `HEAD`.
+    pub local_store_last_commit_date: Option<DateTime<Local>>,
+    pub local_store_last_commit_hash: Option<String>,
+    /// Number of changed and untracked paths in the checkout.
+    pub local_store_uncommitted_count: Option<usize>,
+    /// Commits on the checkout's `main` not yet in `refs/trunk/<store>`, and
+    /// the other way around.
+    pub checkout_ahead_behind: Option<(usize, usize)>,
+    /// `refs/trunk/<store>` in the main repository.
+    pub main_repo_ref: String,
+    pub main_repo_ref_exists: bool,
+    pub main_repo_ref_commit_date: Option<DateTime<Local>>,
+    pub main_repo_ref_commit_hash: Option<String>,
+    /// Disk size of every object reachable from the ref, in bytes.
+    pub main_repo_ref_size: Option<u64>,
+    /// The store's `max_size` quota, in bytes.
+    pub main_repo_ref_quota: Option<u64>,
+    /// Remote the store was checked against: its configured remote unless
+    /// [`Stores::remote`] chose one.
+    pub remote_name: String,
+    /// Whether the remote has `refs/trunk/<store>`; `None` when the remote
+    /// could not be reached or was not asked.
+    pub remote_repo_ref_exists: Option<bool>,
+    pub remote_repo_ref_commit_hash: Option<String>,
+    /// Commits on `refs/trunk/<store>` not on the remote's ref, and the other
+    /// way around. Only known when the remote's commit has been fetched (e.g.
+    /// by `git trunk fetch`).
+    pub remote_ahead_behind: Option<(usize, usize)>,
+}
+
+/// Hash and date of the commit `rev` names in the repository at `repo_path`.
+pub(crate) fn get_commit_info(repo_path: &Path, rev: &str, verbose: bool) -> (Option<DateTime<Local>>, Option<String>) {
+    match run_git_command(
+        Command::new("git")
+            .arg("log")
+            .arg("-1")
+            .arg("--pretty=format:%H%n%at") // hash newline unixtimestamp
+            .arg(rev)
+            .current_dir(repo_path),
+        verbose,
+    ) {
+        Ok(output) if output.status.success() => {
+            let out_str = String::from_utf8_lossy(&output.stdout);
+            let parts: Vec<&str> = out_str.trim().split('\n').collect();
+            if parts.len() == 2 {
+                let hash = parts[0].to_string();
+                let timestamp_str = parts[1];
+                if let Ok(timestamp_secs) = timestamp_str.parse::<i64>() {
+                    // Use DateTime::from_timestamp to create a DateTime<Utc> directly
+                    match DateTime::from_timestamp(timestamp_secs, 0) {
+                        // Convert to local time
+                        Some(utc_dt) => return (Some(utc_dt.with_timezone(&Local)), Some(hash)),
+                        None => {
+                            debug!("🕰️ Failed to create DateTime<Utc> from timestamp: {}", timestamp_secs);
+                            return (None, Some(hash));
+                        }
+                    }
+                }
+                debug!("🕰️ Failed to parse timestamp string: {}", timestamp_str);
+                (None, Some(hash)) // Return hash even if date parsing fails
+            } else {
+                debug!("🕰️ Unexpected format from git log output: {}", out_str);
+                (None, None)
+            }
+        }
+        Ok(output) => {
+            debug!("🔍 Git log command for ref '{}' in '{}' failed or returned no info. Exit_code: {:?}, stdout: {}, stderr: {}", rev, repo_path.display(), output.status.code(), String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+            (None,None)
+        }
+        Err(e) => {
+            debug!("🔍 Failed to execute git log for ref '{}' in '{}': {}", rev, repo_path.display(), e);
+            (None, None)
+        },
+    }
+}
+
+impl StoreInfo {
+    // Gathers the facts about `store_name`. The remote is only asked when
+    // `check_remote` is set, and sizes are only measured with `sizes`.
+    pub(crate) fn gather(repo: &GitRepo, trunk_config: Option<&TrunkConfig>, store_name: &str, remote_name: &str, check_remote: bool, sizes: bool, verbose: bool) -> StoreInfo {
+        let repo_root = repo.root();
+        let mut store_info = StoreInfo {
+            name: store_name.to_string(),
+            local_path: config::trunk_base_dir(repo_root, verbose).join(store_name),
+            local_path_exists: false,
+            is_git_repo: false,
+            local_store_last_commit_date: None,
+            local_store_last_commit_hash: None,
+            local_store_uncommitted_count: None,
+            checkout_ahead_behind: None,
+            main_repo_ref: format!("refs/trunk/{}", store_name),
+            main_repo_ref_exists: false,
+            main_repo_ref_commit_date: None,
+            main_repo_ref_commit_hash: None,
+            main_repo_ref_size: None,
+            main_repo_ref_quota: None,
+            remote_name: remote_name.to_string(),
+            remote_repo_ref_exists: None,
+            remote_repo_ref_commit_hash: None,
+            remote_ahead_behind: None,
+        };
+
+        store_info.local_path_exists = store_info.local_path.is_dir();
+        let store_repo = if store_info.local_path_exists { GitRepo::open(&store_info.local_path, verbose).ok() } else { None };
+        store_info.is_git_repo = store_repo.is_some();
+        if let Some(store_repo) = &store_repo {
+            let (date, hash) = get_commit_info(&store_info.local_path, "HEAD", verbose);
+            store_info.local_store_last_commit_date = date;
+            store_info.local_store_last_commit_hash = hash;
+            store_info.local_store_uncommitted_count = store_repo.status_porcelain().ok().map(|lines| lines.len());
+        }
+
+        store_info.main_repo_ref_exists = repo.ref_exists(&store_info.main_repo_ref);
+        if store_info.main_repo_ref_exists {
+            let (date, hash) = get_commit_info(repo_root, &store_info.main_repo_ref, verbose);
+            store_info.main_repo_ref_commit_date = date;
+            store_info.main_repo_ref_commit_hash = hash;
+            if sizes {
+                store_info.main_repo_ref_size = quota::reachable_size(repo_root, &store_info.main_repo_ref, verbose).ok();
+                if let Some(Ok(Some(store_quota))) = trunk_config.map(|c| quota::store_quota(c, store_name)) {
+                    store_info.main_repo_ref_quota = Some(store_quota.max_bytes);
+                }
+            }
+        }
+        // Like `status`: the checkout usually has every commit of the ref
+        if let (Some(store_repo), Some(local_ref)) = (&store_repo, &store_info.main_repo_ref_commit_hash) {
+            if let Some(store_head) = store_repo.resolve_commit("main") {
+                store_info.checkout_ahead_behind = store_repo.ahead_behind(&store_head, local_ref).or_else(|| repo.ahead_behind(&store_head, local_ref));
+            }
+        }
+
+        if check_remote {
+            let remote_tip = ops::cached_remote_store_tip(repo, remote_name, store_name, verbose)
+                .map(Ok)
+                .unwrap_or_else(|| ops::remote_store_tip(repo, remote_name, store_name, verbose));
+            match remote_tip {
+                Ok(tip) => {
+                    store_info.remote_repo_ref_exists = Some(tip.is_some());
+                    store_info.remote_repo_ref_commit_hash = tip;
+                }
+                // An unreachable remote (e.g. a local path that does not exist) is not "missing"
+                Err(e) => debug!("⚠️ Failed to check remote ref for store {}: {}", store_name, e),
+            }
+        }
+        if let (Some(local_ref), Some(remote_hash)) = (&store_info.main_repo_ref_commit_hash, &store_info.remote_repo_ref_commit_hash) {
+            store_info.remote_ahead_behind = repo.ahead_behind(local_ref, remote_hash);
+        }
+        store_info
+    }
+}
+
+/// Iterator over the stores of a repository, returned by [`stores`].
+///
+/// By default every store is checked against its configured remote and its
+/// size is measured; [`Stores::offline`] and [`Stores::without_sizes`] skip
+/// the network access and the object walk.
+pub struct Stores {
+    repo: GitRepo,
+    trunk_config: Option<TrunkConfig>,
+    names: std::vec::IntoIter<String>,
+    remote: Option<String>,
+    check_remote: bool,
+    sizes: bool,
+}
+
+/// Lists the stores of the repository whose working tree is `repo_path`,
+/// sorted by name.
+///
+/// Fails when `repo_path` is not the top of a git working tree.
+pub fn stores(repo_path: impl AsRef<Path>) -> Result<Stores, TrunkError> {
+    let repo_path = repo_path.as_ref();
+    let repo_root = fs::canonicalize(repo_path).map_err(|e| TrunkError::NotFound(format!("{}: {}", repo_path.display(), e)))?;
+    let repo = GitRepo::open(&repo_root, false).map_err(|e| TrunkError::Git(format!("Failed to open {}: {}", repo_root.display(), e)))?;
+    let names = ops::local_stores(&repo, false);
+    Ok(Stores {
+        trunk_config: config::load(&repo_root, false).ok(),
+        repo,
+        names: names.into_iter(),
+        remote: None,
+        check_remote: true,
+        sizes: true,
+    })
+}
+
+impl Stores {
+    /// Checks every store against `remote_name` instead of its configured remote.
+    pub fn remote(mut self, remote_name: &str) -> Stores {
+        self.remote = Some(remote_name.to_string());
+        self
+    }
+
+    /// Does not contact any remote; the remote fields stay `None`.
+    pub fn offline(mut self) -> Stores {
+        self.check_remote = false;
+        self
+    }
+
+    /// Does not measure store sizes; `main_repo_ref_size` and
+    /// `main_repo_ref_quota` stay `None`.
+    pub fn without_sizes(mut self) -> Stores {
+        self.sizes = false;
+        self
+    }
+}
+
+impl Iterator for Stores {
+    type Item = StoreInfo;
+
+    fn next(&mut self) -> Option<StoreInfo> {
+        let name = self.names.next()?;
+        let remote_name = match (&self.remote, &self.trunk_config) {
+            (Some(remote), _) => remote.clone(),
+            (None, Some(trunk_config)) => trunk_config.remote_for_store(&name),
+            (None, None) => config::DEFAULT_REMOTE.to_string(),
+        };
+        Some(StoreInfo::gather(&self.repo, self.trunk_config.as_ref(), &name, &remote_name, self.check_remote, self.sizes, false))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.names.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Stores {}
@@ -0,0 +1,266 @@
+use std::env;
+use std::io::IsTerminal;
+
+// Width used when the terminal width cannot be determined (matches the
+// 100-column separators used throughout the human-readable output).
+const DEFAULT_WIDTH: usize = 100;
+const MIN_COLUMN_WIDTH: usize = 4;
+const COLUMN_GAP: &str = "  ";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Green,
+    Red,
+    Yellow,
+}
+
+impl Color {
+    fn code(&self) -> &'static str {
+        match self {
+            Color::Green => "\x1B[32m",
+            Color::Red => "\x1B[31m",
+            Color::Yellow => "\x1B[33m",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cell {
+    text: String,
+    color: Option<Color>,
+}
+
+impl Cell {
+    pub fn colored(text: impl Into<String>, color: Color) -> Self {
+        Cell { text: text.into(), color: Some(color) }
+    }
+
+    // A ✓/✗ cell colored green or red.
+    pub fn flag(ok: bool, text: &str) -> Self {
+        if ok {
+            Cell::colored(format!("✓ {}", text), Color::Green)
+        } else {
+            Cell::colored(format!("✗ {}", text), Color::Red)
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell { text, color: None }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell { text: text.to_string(), color: None }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Table { headers: headers.iter().map(|h| h.to_string()).collect(), rows: Vec::new() }
+    }
+
+    pub fn add_row(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn print(&self, no_table: bool) {
+        let plain = is_plain();
+        let out = self.render(no_table || plain, terminal_width(), use_color());
+        print!("{}", if plain { plain_text(&out) } else { out });
+    }
+
+    // Renders aligned columns fitted to `width`, or with `no_table` one
+    // "Header: value" line per cell and a blank line between rows.
+    pub fn render(&self, no_table: bool, width: usize, color: bool) -> String {
+        let mut out = String::new();
+        if no_table {
+            for row in &self.rows {
+                for (header, cell) in self.headers.iter().zip(row) {
+                    out.push_str(&format!("{}: {}\n", header, cell.text));
+                }
+                out.push('\n');
+            }
+            return out;
+        }
+
+        let widths = self.column_widths(width);
+        let header_cells: Vec<Cell> = self.headers.iter().map(|h| Cell::from(h.as_str())).collect();
+        out.push_str(&render_line(&header_cells, &widths, color, true));
+        let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        out.push_str(rule.join(COLUMN_GAP).trim_end());
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&render_line(row, &widths, color, false));
+        }
+        out
+    }
+
+    // Natural column widths, with the widest columns shrunk until the table
+    // fits in `max_width`.
+    fn column_widths(&self, max_width: usize) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| display_width(h)).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate().take(widths.len()) {
+                widths[i] = widths[i].max(display_width(&cell.text));
+            }
+        }
+        let gaps = COLUMN_GAP.len() * widths.len().saturating_sub(1);
+        while widths.iter().sum::<usize>() + gaps > max_width {
+            let (widest, width) = widths.iter().enumerate().max_by_key(|(_, w)| **w).map(|(i, w)| (i, *w)).unwrap_or((0, 0));
+            if width <= MIN_COLUMN_WIDTH {
+                break;
+            }
+            widths[widest] -= 1;
+        }
+        widths
+    }
+}
+
+fn render_line(cells: &[Cell], widths: &[usize], color: bool, bold: bool) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for (i, width) in widths.iter().enumerate() {
+        let (text, cell_color) = cells.get(i).map(|c| (c.text.as_str(), c.color)).unwrap_or(("", None));
+        let fitted = truncate(&text.replace(['\n', '\r', '\t'], " "), *width);
+        let padding = " ".repeat(width.saturating_sub(display_width(&fitted)));
+        let styled = match (color, bold, cell_color) {
+            (true, true, _) => format!("\x1B[1m{}\x1B[0m", fitted),
+            (true, false, Some(c)) => format!("{}{}\x1B[0m", c.code(), fitted),
+            _ => fitted,
+        };
+        parts.push(format!("{}{}", styled, padding));
+    }
+    format!("{}\n", parts.join(COLUMN_GAP).trim_end())
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = char_width(c);
+        if used + w + 1 > width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+// Approximate terminal column width: zero-width joiners and variation
+// selectors take no space, emoji take two columns, everything else one.
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x200D | 0xFE0E | 0xFE0F => 0,
+        0x1F000..=0x1FAFF | 0x274C | 0x2705 => 2,
+        _ => 1,
+    }
+}
+
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+pub fn terminal_width() -> usize {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|cols| cols.trim().parse::<usize>().ok())
+        .filter(|cols| *cols > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+// Color only when writing to a terminal, NO_COLOR is unset and --plain is off.
+pub fn use_color() -> bool {
+    env::var_os("NO_COLOR").is_none() && !is_plain() && std::io::stdout().is_terminal()
+}
+
+// Environment variable behind `--plain`. The flag sets it, so commands spawned
+// by batch and hooks print plain output too.
+pub const PLAIN_ENV: &str = "GIT_TRUNK_PLAIN";
+
+// Screen-reader-friendly output: words instead of symbols, no color, and
+// tables printed as one "Field: value" line per fact.
+pub fn is_plain() -> bool {
+    env::var_os(PLAIN_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+// Symbols with a meaning, and the words --plain prints instead. Longer
+// phrases come first so they win over their symbols.
+const PLAIN_WORDS: &[(&str, &str)] = &[
+    ("❌ ❌ ", "ERROR: "),
+    ("⚠️ Warning: ", "WARNING: "),
+    ("✓ Yes", "YES"),
+    ("❌ No", "NO"),
+    ("❓ Check failed", "UNKNOWN (check failed)"),
+    ("✗ missing", "MISSING"),
+    ("✗ Not installed", "MISSING"),
+    ("❌ ", "ERROR: "),
+    ("✗ ", "ERROR: "),
+    ("✅", "OK"),
+    ("✓", "OK"),
+    ("✗", "ERROR"),
+    ("❌", "ERROR"),
+    ("⚠️", "WARNING"),
+    ("🚫", "STOPPED"),
+    ("❓", "UNKNOWN"),
+    ("≠", "DIFFERS"),
+];
+
+// Emoji, arrows, dingbats and box-drawing characters, which screen readers
+// announce by name or skip.
+fn is_decoration(c: char) -> bool {
+    matches!(c as u32, 0x200D | 0xFE00..=0xFE0F | 0x2190..=0x21FF | 0x2300..=0x23FF | 0x2500..=0x25FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x1F000..=0x1FAFF)
+}
+
+// Rewrites a line for --plain: meaningful symbols become words and other
+// decoration is dropped together with the space after it.
+pub fn plain_text(text: &str) -> String {
+    let mut text = text.to_string();
+    for (symbol, word) in PLAIN_WORDS {
+        text = text.replace(symbol, word);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_decoration(c) {
+            if chars.peek() == Some(&' ') {
+                chars.next();
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+// The 100-column separator between report sections; --plain leaves it out.
+pub fn print_rule() {
+    if !is_plain() {
+        println!("{:-<width$}", "", width = DEFAULT_WIDTH);
+    }
+}
+
+// println! for human-readable reports, rewritten under --plain.
+pub fn print_line(text: &str) {
+    if is_plain() {
+        println!("{}", plain_text(text));
+    } else {
+        println!("{}", text);
+    }
+}
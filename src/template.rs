@@ -0,0 +1,75 @@
+use crate::error::TrunkError;
+
+// Placeholder templates for `--format`, in the spirit of `git for-each-ref
+// --format`: `{field}` is replaced by the field's value, `{{` and `}}` are
+// literal braces, and `\t`, `\n` and `\\` are expanded so one-liners can be
+// written without the shell's help.
+#[derive(Debug, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Text(String),
+    Field(String),
+}
+
+impl Template {
+    // Parses `format`, refusing placeholders that are not in `fields`.
+    pub fn parse(format: &str, fields: &[&str]) -> Result<Template, TrunkError> {
+        let usage = |message: String| TrunkError::Usage(format!("Invalid --format '{}': {}", format, message));
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(usage("unclosed '{'".to_string())),
+                        }
+                    }
+                    if !fields.contains(&name.as_str()) {
+                        return Err(usage(format!("unknown field '{{{}}}', available: {}", name, fields.iter().map(|field| format!("{{{}}}", field)).collect::<Vec<_>>().join(", "))));
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Field(name));
+                }
+                '}' => return Err(usage("unmatched '}', write '}}' for a literal brace".to_string())),
+                '\\' => match chars.peek() {
+                    Some('t') => { chars.next(); text.push('\t'); }
+                    Some('n') => { chars.next(); text.push('\n'); }
+                    Some('\\') => { chars.next(); text.push('\\'); }
+                    _ => text.push('\\'),
+                },
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Template { parts })
+    }
+
+    // Fills in the placeholders; a field without a value renders empty.
+    pub fn render(&self, values: &[(&str, String)]) -> String {
+        self.parts.iter().map(|part| match part {
+            Part::Text(text) => text.as_str(),
+            Part::Field(name) => values.iter().find(|(field, _)| field == name).map(|(_, value)| value.as_str()).unwrap_or(""),
+        }).collect()
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use crate::config;
+use crate::error::TrunkError;
+use crate::git::GitRepo;
+use crate::utils::run_git_command;
+
+// Deleted stores. `delete` moves refs/trunk/<store> to
+// refs/trunk-trash/<store>-<UTC time>, locally and on the remote, where
+// `undelete` finds it until `trash purge` expires it. The time is UTC so
+// teammates in other time zones agree on which entry is newest.
+pub const TRASH_PREFIX: &str = "refs/trunk-trash/";
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    // `<store>-<time>`, the ref name without TRASH_PREFIX
+    pub name: String,
+    pub store: String,
+    pub deleted: DateTime<Utc>,
+    pub hash: String,
+}
+
+impl TrashEntry {
+    pub fn ref_name(&self) -> String {
+        format!("{}{}", TRASH_PREFIX, self.name)
+    }
+}
+
+pub fn trash_ref_name(store_name: &str, deleted: DateTime<Utc>) -> String {
+    format!("{}{}-{}", TRASH_PREFIX, store_name, deleted.format(TIME_FORMAT))
+}
+
+fn parse_entry(name: &str, hash: &str) -> Option<TrashEntry> {
+    let (store, time) = name.rsplit_once('-')?;
+    let deleted = NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?.and_utc();
+    config::is_store_name(store).then(|| TrashEntry { name: name.to_string(), store: store.to_string(), deleted, hash: hash.to_string() })
+}
+
+// Entries from "<hash> <ref name>" lines, newest first.
+fn parse_listing(listing: &str) -> Vec<TrashEntry> {
+    let mut entries: Vec<TrashEntry> = listing
+        .lines()
+        .filter_map(|line| {
+            let (hash, name) = line.split_once(char::is_whitespace)?;
+            parse_entry(name.trim().strip_prefix(TRASH_PREFIX)?, hash)
+        })
+        .collect();
+    entries.sort_by(|a, b| b.deleted.cmp(&a.deleted).then_with(|| a.store.cmp(&b.store)));
+    entries
+}
+
+pub fn local(repo: &GitRepo, verbose: bool) -> Result<Vec<TrashEntry>, TrunkError> {
+    let output = run_git_command(repo.git().arg("for-each-ref").arg("--format=%(objectname) %(refname)").arg(TRASH_PREFIX), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to list {}*: {}", TRASH_PREFIX, e)))?;
+    Ok(parse_listing(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Fails when the remote cannot be reached.
+pub fn remote(repo: &GitRepo, remote_name: &str, verbose: bool) -> Result<Vec<TrashEntry>, TrunkError> {
+    let output = run_git_command(repo.git().arg("ls-remote").arg(remote_name).arg(format!("{}*", TRASH_PREFIX)), verbose)
+        .map_err(|e| TrunkError::Git(format!("Failed to reach remote '{}': {}", remote_name, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(TrunkError::Git(format!("Failed to reach remote '{}': {}", remote_name, if stderr.is_empty() { "git ls-remote failed" } else { &stderr })));
+    }
+    Ok(parse_listing(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Parses ages such as "30d", "2w", "12h", "90m", "45s" or "now"; a bare
+// number is days.
+pub fn parse_duration(text: &str) -> Option<chrono::Duration> {
+    let text = text.trim();
+    if text == "now" {
+        return Some(chrono::Duration::zero());
+    }
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: i64 = number.parse().ok()?;
+    match unit.trim() {
+        "s" => chrono::Duration::try_seconds(number),
+        "m" => chrono::Duration::try_minutes(number),
+        "h" => chrono::Duration::try_hours(number),
+        "" | "d" => chrono::Duration::try_days(number),
+        "w" => chrono::Duration::try_weeks(number),
+        _ => None,
+    }
+}
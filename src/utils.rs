@@ -1,17 +1,25 @@
-use std::fs::{File, OpenOptions};
-use std::io::{self, Read, Write};
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
 use log::{debug, info};
+use crate::error::TrunkError;
+use crate::git::GitRepo;
 
 pub fn run_git_command(command: &mut Command, verbose: bool) -> io::Result<std::process::Output> {
-    // Check if git is available
-    let git_check = Command::new("git")
-        .arg("--version")
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status();
-    if git_check.is_err() || !git_check.unwrap().success() {
+    // Check once per process that git is available
+    static GIT_AVAILABLE: OnceLock<bool> = OnceLock::new();
+    let git_available = *GIT_AVAILABLE.get_or_init(|| {
+        Command::new("git")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    });
+    if !git_available {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
             "Git executable not found or failed to execute. Please ensure Git is installed and in your PATH.",
@@ -34,6 +42,229 @@ pub fn run_git_command(command: &mut Command, verbose: bool) -> io::Result<std::
     Ok(output)
 }
 
+// Set once by main from the global --dry-run flag.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+// The operation plan: every change a dry run skipped, in order, for `explain`.
+static PLAN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// Under --dry-run, reports the change `what` describes, adds it to the plan and
+// returns true so the caller skips it; otherwise returns false and the caller
+// goes ahead.
+pub fn dry_run(what: &str) -> bool {
+    if is_dry_run() {
+        info!("🔍 Would {}", what);
+        PLAN.lock().unwrap_or_else(|e| e.into_inner()).push(what.to_string());
+    }
+    is_dry_run()
+}
+
+// The plan recorded so far, emptying it.
+pub fn take_plan() -> Vec<String> {
+    std::mem::take(&mut *PLAN.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+// A command as it would be typed, and the directory it runs in, for dry-run output.
+pub fn describe_command(command: &Command) -> String {
+    let mut text = command.get_program().to_string_lossy().to_string();
+    for arg in command.get_args() {
+        let arg = arg.to_string_lossy();
+        if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"') {
+            text.push_str(&format!(" '{}'", arg.replace('\'', "'\\''")));
+        } else {
+            text.push_str(&format!(" {}", arg));
+        }
+    }
+    match command.get_current_dir() {
+        Some(dir) => format!("`{}` in {}", text, dir.display()),
+        None => format!("`{}`", text),
+    }
+}
+
+// Runs a git command that changes refs, objects or files. Under --dry-run the
+// command is only reported and an empty, successful result is returned.
+pub fn run_git_change(command: &mut Command, verbose: bool) -> io::Result<Output> {
+    if dry_run(&format!("run {}", describe_command(command))) {
+        return Ok(Output { status: ExitStatus::default(), stdout: Vec::new(), stderr: Vec::new() });
+    }
+    run_git_command(command, verbose)
+}
+
+// Returns the common git directory (shared by all worktrees) as an absolute path.
+pub fn git_common_dir(repo_root: &Path, verbose: bool) -> io::Result<PathBuf> {
+    GitRepo::open(repo_root, verbose)?.common_dir()
+}
+
+// Object format of a repository ("sha1" or "sha256"). Store repositories must
+// match the main repository, or fetching objects between them fails. Git
+// versions that predate SHA-256 support only know "sha1".
+pub fn object_format(repo_path: &Path, verbose: bool) -> String {
+    run_git_command(
+        Command::new("git")
+            .arg("rev-parse")
+            .arg("--show-object-format")
+            .current_dir(repo_path),
+        verbose,
+    )
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    .filter(|format| !format.is_empty())
+    .unwrap_or_else(|| "sha1".to_string())
+}
+
+// Argument for `git init` that creates a repository with the same object
+// format as repo_path, or None for the default SHA-1 (older gits reject the flag).
+pub fn init_object_format_arg(repo_path: &Path, verbose: bool) -> Option<String> {
+    let format = object_format(repo_path, verbose);
+    (format != "sha1").then(|| format!("--object-format={}", format))
+}
+
+// Abbreviates a full object name the way git displays it (like `%h`), for
+// SHA-1 and SHA-256 alike. The object need not exist locally, so hashes
+// reported by a remote can be shortened too.
+pub fn short_hash(repo_path: &Path, hash: &str, verbose: bool) -> String {
+    run_git_command(
+        Command::new("git")
+            .arg("rev-parse")
+            .arg("--short")
+            .arg(hash)
+            .current_dir(repo_path),
+        verbose,
+    )
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    .filter(|short| !short.is_empty())
+    .unwrap_or_else(|| hash.to_string())
+}
+
+// Probes write access to the current repository's git directory by creating
+// and removing a scratch file, so read-only checkouts (CI caches, sandboxes,
+// SELinux-confined mounts) are detected before any work starts. Outside a
+// repository there is nothing to probe and Ok is returned.
+pub fn check_git_dir_writable(verbose: bool) -> io::Result<()> {
+    let Ok(git_dir) = GitRepo::discover(verbose).and_then(|repo| repo.common_dir()) else {
+        return Ok(());
+    };
+    check_dir_writable(&git_dir)
+}
+
+// Creates and removes a scratch file in `dir`, which must exist.
+pub fn check_dir_writable(dir: &Path) -> io::Result<()> {
+    let probe_path = dir.join(format!("trunk-write-probe-{}", std::process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe_path)
+        .and_then(|_| fs::remove_file(&probe_path))
+        .map_err(|e| io::Error::new(e.kind(), format!("{} is not writable ({})", dir.display(), e)))
+}
+
+// Environment variable that auto-confirms every prompt. `--yes` sets it, so
+// commands spawned by git-trunk itself (batch requests, hooks) inherit it.
+pub const NONINTERACTIVE_ENV: &str = "GIT_TRUNK_NONINTERACTIVE";
+
+// How prompts are answered when neither `--yes` nor GIT_TRUNK_NONINTERACTIVE
+// is set, from `[trunk] prompt`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromptMode {
+    Ask,
+    Yes,
+    No,
+}
+
+static PROMPT_MODE: OnceLock<PromptMode> = OnceLock::new();
+
+pub fn set_prompt_mode(mode: PromptMode) {
+    let _ = PROMPT_MODE.set(mode);
+}
+
+// Whether `confirm` answers on its own, without reading stdin.
+pub fn prompts_answered() -> bool {
+    is_dry_run()
+        || std::env::var_os(NONINTERACTIVE_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+        || PROMPT_MODE.get().is_some_and(|mode| *mode != PromptMode::Ask)
+}
+
+// Asks a yes/no question on stdin. Answers yes without asking when `--yes` or
+// GIT_TRUNK_NONINTERACTIVE is set, then follows `[trunk] prompt`, and fails
+// instead of blocking when stdin is not a terminal, so CI jobs never hang on a
+// prompt.
+pub fn confirm(prompt: &str) -> Result<bool, TrunkError> {
+    if is_dry_run() {
+        info!("🔍 Would ask: {} [y/N] (assuming yes)", prompt);
+        return Ok(true);
+    }
+    if std::env::var_os(NONINTERACTIVE_ENV).is_some_and(|value| !value.is_empty() && value != "0") {
+        info!("✓ {} [y/N]: y (non-interactive)", prompt);
+        return Ok(true);
+    }
+    match PROMPT_MODE.get().copied().unwrap_or(PromptMode::Ask) {
+        PromptMode::Yes => {
+            info!("✓ {} [y/N]: y ([trunk] prompt = \"yes\")", prompt);
+            return Ok(true);
+        }
+        PromptMode::No => {
+            info!("🚫 {} [y/N]: n ([trunk] prompt = \"no\")", prompt);
+            return Ok(false);
+        }
+        PromptMode::Ask => {}
+    }
+    if !io::stdin().is_terminal() {
+        return Err(TrunkError::Usage(format!(
+            "Cannot ask \"{}\": stdin is not a terminal. Pass --yes or set {}=1 to confirm non-interactively.",
+            prompt, NONINTERACTIVE_ENV
+        )));
+    }
+    if crate::table::is_plain() {
+        print!("QUESTION: {} [y/N]: ", prompt);
+    } else {
+        print!("🐘︖ {} [y/N]: ", prompt);
+    }
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
+
+// Paths of all worktrees of the repository, the main worktree first.
+pub fn list_worktrees(repo_root: &Path, verbose: bool) -> io::Result<Vec<PathBuf>> {
+    let output = run_git_command(
+        Command::new("git")
+            .arg("worktree")
+            .arg("list")
+            .arg("--porcelain")
+            .current_dir(repo_root),
+        verbose,
+    )?;
+    if !output.status.success() {
+        return Err(io::Error::other("git worktree list failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("worktree "))
+        .map(PathBuf::from)
+        .collect())
+}
+
+// Whether a .gitignore line ignores the root .trunk directory: `.trunk`,
+// `/.trunk`, `.trunk/` or `/.trunk/`. Like git, a trailing CR and trailing
+// spaces are dropped, while leading whitespace and tabs are part of the pattern.
+pub fn is_trunk_gitignore_line(line: &str) -> bool {
+    let pattern = line.strip_suffix('\r').unwrap_or(line).trim_end_matches(' ');
+    matches!(pattern, ".trunk" | "/.trunk" | ".trunk/" | "/.trunk/")
+}
+
 pub fn ensure_trunk_in_gitignore(
     repo_root: &Path,
     step_log_prefix: &str,
@@ -51,7 +282,12 @@ pub fn ensure_trunk_in_gitignore(
             .map_err(|e| {
                 io::Error::new(e.kind(), format!("Failed to read .gitignore content: {}", e))
             })?;
-        if !gitignore_content.lines().any(|line| line.trim() == ".trunk") {
+        // The last line about .trunk wins, so a later `!.trunk` undoes the entry
+        let last_rule = gitignore_content
+            .lines()
+            .rev()
+            .find(|line| is_trunk_gitignore_line(line) || line.strip_prefix('!').is_some_and(is_trunk_gitignore_line));
+        if !last_rule.is_some_and(is_trunk_gitignore_line) {
             gitignore_needs_update = true;
         }
     } else {
@@ -60,6 +296,9 @@ pub fn ensure_trunk_in_gitignore(
 
     if gitignore_needs_update {
         debug!("✨ {}: Adding .trunk to .gitignore", step_log_prefix);
+        if dry_run(&format!("add .trunk to {}", gitignore_path.display())) {
+            return Ok(());
+        }
         let mut gitignore_file = OpenOptions::new()
             .create(true)
             .append(true)
@@ -68,10 +307,12 @@ pub fn ensure_trunk_in_gitignore(
                 io::Error::new(e.kind(), format!("Failed to open .gitignore for writing: {}", e))
             })?;
 
+        // Keep the file's line endings
+        let newline = if gitignore_content.contains("\r\n") { "\r\n" } else { "\n" };
         if !gitignore_content.is_empty() && !gitignore_content.ends_with('\n') {
-            writeln!(gitignore_file)?;
+            write!(gitignore_file, "{}", newline)?;
         }
-        writeln!(gitignore_file, ".trunk")?;
+        write!(gitignore_file, ".trunk{}", newline)?;
         info!("✓ {}: Added .trunk to .gitignore", step_log_prefix);
     } else {
         debug!("= {}: .trunk already in .gitignore", step_log_prefix);
@@ -91,20 +332,19 @@ pub fn remove_trunk_from_gitignore(
         File::open(&gitignore_path)?
             .read_to_string(&mut current_content)?;
 
-        let original_lines_count = current_content.lines().count();
+        // Lines keep their own endings, so CRLF files and a missing final
+        // newline survive the rewrite
+        let original_lines_count = current_content.split_inclusive('\n').count();
         let new_lines: Vec<&str> = current_content
-            .lines()
-            .filter(|line| line.trim() != ".trunk")
+            .split_inclusive('\n')
+            .filter(|line| !is_trunk_gitignore_line(line.strip_suffix('\n').unwrap_or(line)))
             .collect();
 
         if new_lines.len() < original_lines_count {
-            let mut updated_content = new_lines.join("\n");
-            if !new_lines.is_empty() { // If there's any content left
-                updated_content.push('\n');
+            if dry_run(&format!("remove .trunk from {}", gitignore_path.display())) {
+                return Ok(());
             }
-            // If new_lines was empty, updated_content is empty.
-            // If new_lines was ["foo"], updated_content is "foo\n".
-            // If new_lines was ["foo", "bar"], updated_content is "foo\nbar\n".
+            let updated_content = new_lines.concat();
 
             OpenOptions::new()
                 .write(true)
@@ -121,4 +361,221 @@ pub fn remove_trunk_from_gitignore(
         info!("= {}: No .gitignore file to modify.", step_log_prefix);
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+// Lists any .trunk paths tracked in the main repository's index. These end up
+// committed to code branches when the .gitignore entry is removed or `git add -f` is used.
+pub fn tracked_trunk_paths(repo_root: &Path, verbose: bool) -> io::Result<Vec<String>> {
+    let output = run_git_command(
+        Command::new("git")
+            .arg("ls-files")
+            .arg("--cached")
+            .arg("--")
+            .arg(".trunk")
+            .current_dir(repo_root),
+        verbose,
+    )?;
+    if !output.status.success() {
+        return Err(io::Error::other("git ls-files failed in the main repository"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+pub fn untrack_trunk_paths(
+    repo_root: &Path,
+    step_log_prefix: &str,
+    verbose: bool,
+) -> io::Result<()> {
+    let output = run_git_change(
+        Command::new("git")
+            .arg("rm")
+            .arg("--cached")
+            .arg("-r")
+            .arg("-f")
+            .arg("-q")
+            .arg("--")
+            .arg(".trunk")
+            .current_dir(repo_root),
+        verbose,
+    )?;
+    if !output.status.success() {
+        return Err(io::Error::other("git rm --cached -r -f .trunk failed in the main repository"));
+    }
+    info!("✓ {}: Removed .trunk from the main repository index (commit this change to clean the branch)", step_log_prefix);
+    ensure_trunk_in_gitignore(repo_root, step_log_prefix)
+}
+
+// Matches `text` against a glob `pattern`: `*` matches within one path segment,
+// `**` matches across segments and `?` matches a single non-'/' character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        match pattern.first() {
+            None => text.is_empty(),
+            Some('*') if pattern.get(1) == Some(&'*') => {
+                (0..=text.len()).any(|i| matches(&pattern[2..], &text[i..]))
+            }
+            Some('*') => {
+                let segment_len = text.iter().position(|c| *c == '/').unwrap_or(text.len());
+                (0..=segment_len).any(|i| matches(&pattern[1..], &text[i..]))
+            }
+            Some('?') => !text.is_empty() && text[0] != '/' && matches(&pattern[1..], &text[1..]),
+            Some(c) => text.first() == Some(c) && matches(&pattern[1..], &text[1..]),
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches(&pattern, &text)
+}
+
+// The repository's core.sharedRepository setting, which decides the
+// permissions of files and directories git-trunk creates for it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SharedRepository {
+    Umask,
+    Group,
+    All,
+    Mode(u32),
+}
+
+impl SharedRepository {
+    pub fn parse(value: &str) -> SharedRepository {
+        match value.trim().to_lowercase().as_str() {
+            "" | "false" | "umask" | "0" | "no" | "off" => SharedRepository::Umask,
+            "true" | "group" | "1" | "yes" | "on" => SharedRepository::Group,
+            "all" | "world" | "everybody" | "2" => SharedRepository::All,
+            other => match u32::from_str_radix(other, 8) {
+                Ok(mode) => SharedRepository::Mode(mode & 0o777),
+                Err(_) => SharedRepository::Umask,
+            },
+        }
+    }
+
+    // Value for `git init --shared=<value>`, or None when permissions follow the umask.
+    pub fn init_value(&self) -> Option<String> {
+        match self {
+            SharedRepository::Umask => None,
+            SharedRepository::Group => Some("group".to_string()),
+            SharedRepository::All => Some("all".to_string()),
+            SharedRepository::Mode(mode) => Some(format!("0{:o}", mode)),
+        }
+    }
+}
+
+pub fn shared_repository(repo_path: &Path, verbose: bool) -> SharedRepository {
+    run_git_command(
+        Command::new("git")
+            .arg("config")
+            .arg("--get")
+            .arg("core.sharedRepository")
+            .current_dir(repo_path),
+        verbose,
+    )
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| SharedRepository::parse(&String::from_utf8_lossy(&output.stdout)))
+    .unwrap_or(SharedRepository::Umask)
+}
+
+// Adjusts permissions of a file or directory the way git does for shared
+// repositories: group (and for "all", world) bits mirror the owner's, an
+// explicit octal mode is used as-is, and directories get setgid plus search bits.
+#[cfg(unix)]
+pub fn apply_shared_permissions(path: &Path, shared: SharedRepository) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    if shared == SharedRepository::Umask {
+        return Ok(());
+    }
+    let metadata = fs::metadata(path)?;
+    let current = metadata.permissions().mode() & 0o777;
+    let mut mode = match shared {
+        SharedRepository::Umask => current,
+        SharedRepository::Group => current | ((current & 0o700) >> 3),
+        SharedRepository::All => current | ((current & 0o700) >> 3) | ((current & 0o500) >> 6),
+        SharedRepository::Mode(explicit) => {
+            let mut m = explicit & 0o666;
+            if current & 0o100 != 0 {
+                m |= (m & 0o444) >> 2; // keep executables executable
+            }
+            m
+        }
+    };
+    if metadata.is_dir() {
+        mode |= ((mode & 0o444) >> 2) | 0o2000;
+    }
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .map_err(|e| io::Error::new(e.kind(), format!("Failed to set shared permissions on {}: {}", path.display(), e)))
+}
+
+#[cfg(not(unix))]
+pub fn apply_shared_permissions(_path: &Path, _shared: SharedRepository) -> io::Result<()> {
+    Ok(())
+}
+
+// Paths of all files in a tree-ish such as `refs/trunk/<store>`, read from
+// the object database without a checkout.
+pub fn list_tree_files(repo_root: &Path, treeish: &str, verbose: bool) -> io::Result<Vec<String>> {
+    let output = run_git_command(
+        Command::new("git")
+            .arg("ls-tree")
+            .arg("-r")
+            .arg("-z")
+            .arg("--name-only")
+            .arg(treeish)
+            .current_dir(repo_root),
+        verbose,
+    )?;
+    if !output.status.success() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} is not a valid tree", treeish)));
+    }
+    Ok(output.stdout
+        .split(|b| *b == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).to_string())
+        .collect())
+}
+
+// Contents of many objects (e.g. "refs/trunk/main:docs/a.md") read through a
+// single `git cat-file --batch`. Missing objects yield None.
+pub fn read_blobs(repo_root: &Path, objects: &[String], verbose: bool) -> io::Result<Vec<Option<Vec<u8>>>> {
+    let mut child = Command::new("git")
+        .arg("cat-file")
+        .arg("--batch")
+        .current_dir(repo_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(if verbose { Stdio::inherit() } else { Stdio::null() })
+        .spawn()?;
+    let mut stdin = child.stdin.take().ok_or_else(|| io::Error::other("git cat-file stdin unavailable"))?;
+    let input: String = objects.iter().map(|object| format!("{}\n", object)).collect();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let mut output = Vec::new();
+    child.stdout.take().ok_or_else(|| io::Error::other("git cat-file stdout unavailable"))?.read_to_end(&mut output)?;
+    writer.join().map_err(|_| io::Error::other("git cat-file writer panicked"))??;
+    child.wait()?;
+
+    let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "Truncated git cat-file output");
+    let mut blobs = Vec::with_capacity(objects.len());
+    let mut pos = 0;
+    for _ in objects {
+        let header_end = output[pos..].iter().position(|b| *b == b'\n').map(|p| pos + p).ok_or_else(truncated)?;
+        let header = String::from_utf8_lossy(&output[pos..header_end]).to_string();
+        pos = header_end + 1;
+        if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+            blobs.push(None);
+            continue;
+        }
+        let size: usize = header.rsplit(' ').next().and_then(|size| size.parse().ok()).ok_or_else(truncated)?;
+        if pos + size > output.len() {
+            return Err(truncated());
+        }
+        blobs.push(Some(output[pos..pos + size].to_vec()));
+        pos += size + 1;
+    }
+    if verbose {
+        debug!("Read {} object(s) with git cat-file --batch", blobs.len());
+    }
+    Ok(blobs)
+}
@@ -0,0 +1,90 @@
+#!/bin/bash
+# generate_fixtures.sh <fixtures dir>
+#
+# Builds the repositories the benchmarks run against, with git fast-import so
+# thousands of commits take seconds:
+#   large/        a repository with one store, refs/trunk/large, holding
+#                 $FILES files and $COMMITS commits; remote: remote-large.git
+#   many/         a repository with $STORES small stores, refs/trunk/store-NN;
+#                 remote: remote-many.git
+#   many-clone/   a clone of many/ without any refs/trunk/*, for checkout --all
+
+set -euo pipefail
+
+FIXTURES_DIR="$1"
+: "${FILES:=10000}"
+: "${COMMITS:=5000}"
+: "${STORES:=50}"
+
+COMMITTER="Bench User <bench@example.com> 1700000000 +0000"
+
+new_repo() {
+    local name="$1"
+    git init -q -b main "$name"
+    git -C "$name" config user.email "bench@example.com"
+    git -C "$name" config user.name "Bench User"
+    git -C "$name" commit -q --allow-empty -m "Initial commit"
+    git init -q --bare "remote-$name.git"
+    git -C "$name" remote add origin "../remote-$name.git"
+    git -C "$name" push -q origin main
+}
+
+# One store: a first commit adding every file, then one commit per change,
+# each appending a line to one of the files. First commits use the subject
+# `git trunk init` gives them, so the stores are not taken for foreign refs.
+large_store_stream() {
+    awk -v files="$FILES" -v commits="$COMMITS" -v committer="$COMMITTER" 'BEGIN {
+        print "commit refs/trunk/large"
+        print "committer " committer
+        print "data <<EOT"; print "Initial commit for store \x27large\x27"; print "EOT"
+        for (i = 0; i < files; i++) {
+            printf "M 100644 inline docs/section-%02d/doc-%05d.md\n", i % 100, i
+            print "data <<EOT"
+            printf "---\ntitle: Document %d\nstatus: draft\n---\n\n# Document %d\n\nBody of document %d.\n", i, i, i
+            print "EOT"
+        }
+        for (c = 1; c < commits; c++) {
+            i = (c * 7919) % files
+            print "commit refs/trunk/large"
+            print "committer " committer
+            print "data <<EOT"; print "Edit document " i " (" c ")"; print "EOT"
+            printf "M 100644 inline docs/section-%02d/doc-%05d.md\n", i % 100, i
+            print "data <<EOT"
+            printf "---\ntitle: Document %d\nstatus: review\n---\n\n# Document %d\n\nRevision %d of document %d.\n", i, i, c, i
+            print "EOT"
+        }
+    }'
+}
+
+many_stores_stream() {
+    awk -v stores="$STORES" -v committer="$COMMITTER" 'BEGIN {
+        for (s = 1; s <= stores; s++) {
+            printf "commit refs/trunk/store-%02d\n", s
+            print "committer " committer
+            print "data <<EOT"; printf "Initial commit for store \x27store-%02d\x27\n", s; print "EOT"
+            for (f = 0; f < 5; f++) {
+                printf "M 100644 inline notes-%d.md\n", f
+                print "data <<EOT"; printf "# Store %d, note %d\n", s, f; print "EOT"
+            }
+        }
+    }'
+}
+
+rm -rf "$FIXTURES_DIR"
+mkdir -p "$FIXTURES_DIR"
+cd "$FIXTURES_DIR"
+
+echo "INFO: Generating large/ ($FILES files, $COMMITS commits)"
+new_repo large
+large_store_stream | git -C large fast-import --quiet
+git -C large push -q origin refs/trunk/large:refs/trunk/large
+
+echo "INFO: Generating many/ ($STORES stores)"
+new_repo many
+many_stores_stream | git -C many fast-import --quiet
+git -C many push -q origin 'refs/trunk/*:refs/trunk/*'
+git clone -q remote-many.git many-clone
+git -C many-clone config user.email "bench@example.com"
+git -C many-clone config user.name "Bench User"
+
+echo "SUCCESS: Fixtures generated in $FIXTURES_DIR"
@@ -0,0 +1,108 @@
+#!/bin/bash
+# run_bench.sh <fixtures dir> <git-trunk command> [baseline file]
+#
+# Times git-trunk against the fixtures of generate_fixtures.sh and prints the
+# median of $RUNS runs per benchmark, in milliseconds. With a baseline file (the
+# output of an earlier run, saved with SAVE=<file>), fails when a benchmark got
+# more than $TOLERANCE percent slower.
+
+set -euo pipefail
+
+FIXTURES_DIR="$(cd "$1" && pwd)"
+GIT_TRUNK_CMD="$2"
+BASELINE="${3:-}"
+: "${RUNS:=5}"
+: "${TOLERANCE:=25}"
+: "${SAVE:=}"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+RESULTS=$(mktemp)
+trap 'rm -f "$RESULTS"' EXIT
+
+now_ns() {
+    date +%s%N
+}
+
+# Runs a command in a directory and prints its wall time in milliseconds
+time_ms() {
+    local dir="$1" start end
+    shift
+    start=$(now_ns)
+    if ! (cd "$dir" && "$@") > /dev/null 2>&1; then
+        echo "ERROR: '$*' failed in $dir" >&2
+        (cd "$dir" && "$@") >&2 || true
+        exit 1
+    fi
+    end=$(now_ns)
+    echo $(( (end - start) / 1000000 ))
+}
+
+median() {
+    sort -n | sed -n "$(( (RUNS + 1) / 2 ))p"
+}
+
+record() {
+    local name="$1" samples="$2"
+    # Unquoted, so the samples split into one number per line
+    echo "$name $(echo $samples | tr ' ' '\n' | median)" >> "$RESULTS"
+}
+
+echo "INFO: Benchmarking '$GIT_TRUNK_CMD' on $FIXTURES_DIR ($RUNS runs each)"
+LARGE="$FIXTURES_DIR/large"
+MANY="$FIXTURES_DIR/many"
+MANY_CLONE="$FIXTURES_DIR/many-clone"
+
+# Large store: check out, change one document, commit, push
+checkout_large="" commit_large="" push_large=""
+for run in $(seq 1 "$RUNS"); do
+    checkout_large+=" $(time_ms "$LARGE" $GIT_TRUNK_CMD checkout --store large --force)"
+    echo "bench run $run" >> "$LARGE/.trunk/large/docs/section-00/doc-00000.md"
+    commit_large+=" $(time_ms "$LARGE" $GIT_TRUNK_CMD commit --store large --force -m "Bench run $run")"
+    push_large+=" $(time_ms "$LARGE" $GIT_TRUNK_CMD push --store large)"
+done
+record checkout_large "$checkout_large"
+record commit_large "$commit_large"
+record push_large "$push_large"
+
+# Many stores: inspect, materialize and push all of them
+info_all_many="" checkout_all_many="" push_all_many=""
+for run in $(seq 1 "$RUNS"); do
+    info_all_many+=" $(time_ms "$MANY" $GIT_TRUNK_CMD info --all --short --no-table)"
+    checkout_all_many+=" $(time_ms "$MANY_CLONE" $GIT_TRUNK_CMD checkout --all --force --no-table)"
+    push_all_many+=" $(time_ms "$MANY" $GIT_TRUNK_CMD push --all --no-table)"
+done
+record info_all_many "$info_all_many"
+record checkout_all_many "$checkout_all_many"
+record push_all_many "$push_all_many"
+
+if [ -n "$SAVE" ]; then
+    cp "$RESULTS" "$SAVE"
+    echo "INFO: Results saved to $SAVE"
+fi
+
+regressions=0
+printf '\n%-20s %10s %10s %8s\n' "Benchmark" "Median ms" "Baseline" "Change"
+while read -r name ms; do
+    base=""
+    if [ -n "$BASELINE" ]; then
+        base=$(awk -v name="$name" '$1 == name { print $2 }' "$BASELINE")
+    fi
+    if [ -z "$base" ] || [ "$base" = "0" ]; then
+        printf '%-20s %10s %10s %8s\n' "$name" "$ms" "-" "-"
+        continue
+    fi
+    change=$(( (ms - base) * 100 / base ))
+    flag=""
+    if [ "$change" -gt "$TOLERANCE" ]; then
+        flag="  REGRESSION"
+        regressions=$((regressions + 1))
+    fi
+    printf '%-20s %10s %10s %7s%%%s\n' "$name" "$ms" "$base" "$change" "$flag"
+done < "$RESULTS"
+
+if [ "$regressions" -gt 0 ]; then
+    echo "ERROR: $regressions benchmark(s) more than $TOLERANCE% slower than $BASELINE"
+    exit 1
+fi
+echo "SUCCESS: Benchmarks completed."
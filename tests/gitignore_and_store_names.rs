@@ -0,0 +1,100 @@
+// Property tests for the .gitignore entry of .trunk and for store names.
+// The shell suite test_gitignore_and_store_names.sh covers the same ground
+// through the CLI with hand-picked cases.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use git_trunk::config::check_store_name;
+use git_trunk::utils::{ensure_trunk_in_gitignore, is_trunk_gitignore_line, remove_trunk_from_gitignore};
+use proptest::prelude::*;
+
+// A fresh directory under cargo's scratch space for integration tests
+fn scratch_dir() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join(format!("gitignore-{}-{}", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed)));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Any line, or one of the spellings that do or almost ignore .trunk
+fn gitignore_line() -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => "[^\r\n]{0,12}",
+        1 => prop::sample::select(vec![".trunk", "/.trunk", ".trunk/", "/.trunk/", ".trunk  ", "!.trunk", " .trunk", ".trunk/x", "*.md"])
+            .prop_map(str::to_string),
+    ]
+}
+
+// .gitignore content: LF or CRLF endings, with or without a final newline
+fn gitignore_content() -> impl Strategy<Value = String> {
+    (prop::collection::vec(gitignore_line(), 0..8), any::<bool>(), any::<bool>()).prop_map(|(lines, crlf, final_newline)| {
+        let newline = if crlf { "\r\n" } else { "\n" };
+        let mut content = lines.join(newline);
+        if final_newline && !lines.is_empty() {
+            content.push_str(newline);
+        }
+        content
+    })
+}
+
+fn other_lines(content: &str) -> Vec<&str> {
+    content.lines().filter(|line| !is_trunk_gitignore_line(line)).collect()
+}
+
+// Store names built around the pieces git is picky about, so rejected and
+// accepted names both come up often
+fn store_name() -> impl Strategy<Value = String> {
+    let piece = prop::sample::select(vec!["", ".", "..", ".lock", "@", "@{", "{", "}", "-", "/", "~", "\\", "é"]);
+    prop_oneof![
+        ("[a-zA-Z0-9_-]{0,6}", piece.clone(), "[a-zA-Z0-9_-]{0,6}", piece).prop_map(|(a, b, c, d)| format!("{}{}{}{}", a, b, c, d)),
+        "\\PC{1,12}",
+    ]
+}
+
+proptest! {
+    #[test]
+    fn adding_trunk_is_idempotent_and_keeps_other_lines(content in gitignore_content()) {
+        let dir = scratch_dir();
+        let path = dir.join(".gitignore");
+        fs::write(&path, &content).unwrap();
+
+        ensure_trunk_in_gitignore(&dir, "Test").unwrap();
+        let added = fs::read_to_string(&path).unwrap();
+        ensure_trunk_in_gitignore(&dir, "Test").unwrap();
+        let added_again = fs::read_to_string(&path).unwrap();
+
+        prop_assert_eq!(&added_again, &added);
+        prop_assert!(added.starts_with(&content));
+        prop_assert_eq!(other_lines(&added), other_lines(&content));
+        let last_rule = added.lines().rev().find(|line| is_trunk_gitignore_line(line) || line.strip_prefix('!').is_some_and(is_trunk_gitignore_line));
+        prop_assert!(last_rule.is_some_and(is_trunk_gitignore_line));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn removing_trunk_is_idempotent_and_keeps_other_lines(content in gitignore_content()) {
+        let dir = scratch_dir();
+        let path = dir.join(".gitignore");
+        fs::write(&path, &content).unwrap();
+
+        remove_trunk_from_gitignore(&dir, "Test").unwrap();
+        let removed = fs::read_to_string(&path).unwrap();
+        remove_trunk_from_gitignore(&dir, "Test").unwrap();
+        let removed_again = fs::read_to_string(&path).unwrap();
+
+        prop_assert_eq!(&removed_again, &removed);
+        prop_assert!(removed.lines().all(|line| !is_trunk_gitignore_line(line)));
+        prop_assert_eq!(removed.lines().collect::<Vec<_>>(), other_lines(&content));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn accepted_store_names_make_valid_refnames(name in store_name()) {
+        prop_assume!(check_store_name(&name).is_ok());
+        let refname = format!("refs/trunk/{}", name);
+        prop_assert!(git2::Reference::is_valid_name(&refname), "{} is not a valid refname", refname);
+    }
+}
@@ -10,6 +10,12 @@ set -euo pipefail
 BASE_TEST_DIR_NAME="functional_tests_workspace"
 BASE_TEST_DIR="$(pwd)/${BASE_TEST_DIR_NAME}"
 TEST_SUITES_DIR="$(pwd)/test_suites"
+# Fixtures shared by the suites, exported to them
+source "$(pwd)/test_helpers.sh"
+# Keep git-trunk's cache (hook run times, remote listings) inside the workspace
+export XDG_CACHE_HOME="${BASE_TEST_DIR}/cache"
+# and its user config away from the real one
+export GIT_TRUNK_HOME="${BASE_TEST_DIR}/home"
 
 # List of test suite scripts to run
 TEST_SUITES=(
@@ -21,8 +27,64 @@ TEST_SUITES=(
     "test_delete.sh"
     "test_hooks.sh"
     "test_info.sh"
+    "test_doctor.sh"
+    "test_hook_run.sh"
+    "test_shared_permissions.sh"
+    "test_advertise.sh"
+    "test_worktree.sh"
+    "test_query.sh"
+    "test_board.sh"
+    "test_agenda.sh"
+    "test_index_md.sh"
+    "test_diff.sh"
+    "test_changelog.sh"
+    "test_release_notes.sh"
+    "test_batch.sh"
+    "test_discard_local.sh"
+    "test_examples.sh"
+    "test_remote_transports.sh"
+    "test_maintenance.sh"
+    "test_expose.sh"
+    "test_conflicts.sh"
+    "test_gitignore_and_store_names.sh"
+    "test_format_templates.sh"
+    "test_foreign_refs.sh"
+    "test_plain_output.sh"
+    "test_cache.sh"
+    "test_protect_clean.sh"
+    "test_fetch.sh"
+    "test_report.sh"
+    "test_pull.sh"
+    "test_locks.sh"
+    "test_history.sh"
+    "test_notify.sh"
+    "test_ls.sh"
+    "test_explain.sh"
+    "test_put.sh"
+    "test_rm.sh"
+    "test_mirrors.sh"
+    "test_blame.sh"
+    "test_revert_reset.sh"
+    "test_grep.sh"
+    "test_rename.sh"
+    "test_copy.sh"
+    "test_import.sh"
+    "test_adopt.sh"
+    "test_migrate_branch.sh"
+    "test_export_branch.sh"
+    "test_gc.sh"
+    "test_verify.sh"
+    "test_mount.sh"
+    "test_lint_prose.sh"
+    "test_ci.sh"
+    "test_config.sh"
     "test_full_flow.sh"
 )
+# test_mount_fuse.sh mounts stores: it needs a git-trunk built with
+# `--features fuse` and permission to mount, so it runs only with GIT_TRUNK_TEST_FUSE=1
+if [ "${GIT_TRUNK_TEST_FUSE:-0}" = "1" ]; then
+    TEST_SUITES+=("test_mount_fuse.sh")
+fi
 
 # --- Helper Functions ---
 cleanup_workspace() {
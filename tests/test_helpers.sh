@@ -0,0 +1,26 @@
+#!/bin/bash
+# test_helpers.sh
+# Fixtures shared by the test suites. run_tests.sh sources this file and
+# exports the functions, so every suite can call them.
+
+# setup_repo [--remote]
+# Creates the repository "$TEST_DIR/repo" on branch main, with the test
+# identity and an empty initial commit, and changes into it. With --remote,
+# also creates the bare repository "$TEST_DIR/remote.git" as its origin.
+setup_repo() {
+    mkdir -p "$TEST_DIR"
+    cd "$TEST_DIR"
+    if [ "${1:-}" = "--remote" ]; then
+        git init -q --bare remote.git
+    fi
+    git init -q -b main repo
+    cd repo
+    git config user.email "test@example.com"
+    git config user.name "Test User"
+    git commit -q --allow-empty -m "Initial commit"
+    if [ "${1:-}" = "--remote" ]; then
+        git remote add origin ../remote.git
+    fi
+}
+
+export -f setup_repo
@@ -0,0 +1,70 @@
+#!/bin/bash
+# test_adopt.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_adopt in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q -b main repo
+cd repo
+git config user.email "test@example.com"
+git config user.name "Test User"
+mkdir -p guide src
+echo "Install it" > guide/install.md
+echo "fn main() {}" > src/main.rs
+git add . && git commit -q -m "Add the guide and the code"
+echo "fn main() { run() }" > src/main.rs
+git commit -q -am "Change only the code"
+echo "Then run it" >> guide/install.md
+GIT_AUTHOR_NAME="Ada Writer" GIT_AUTHOR_EMAIL="ada@example.com" git commit -q -am "Explain running it"
+
+# Scenario 52.1: a directory becomes a store's first commit
+echo "INFO: Scenario 1: adopt"
+$GIT_TRUNK_CMD --store guide adopt guide 2> /dev/null
+if [ "$(git rev-list --count refs/trunk/guide)" != "1" ] || [ "$(git rev-parse refs/trunk/guide^{tree})" != "$(git rev-parse HEAD:guide)" ] \
+    || ! diff -r -x .git guide .trunk/guide > /dev/null || [ -n "$(git status --porcelain -- guide)" ]; then
+    echo "ERROR: adopt did not commit guide/ as the store's first commit:"; git log --oneline refs/trunk/guide
+    exit 1
+fi
+echo "VERIFY: adopt commits a directory of HEAD as a store's first commit and checks it out, leaving the directory in place."
+
+# Scenario 52.2: --preserve-history replays the commits that changed it, --remove stages its removal
+echo "INFO: Scenario 2: adopt --preserve-history --remove"
+(cd guide && $GIT_TRUNK_CMD --store guide_history adopt . --preserve-history --remove 2> /dev/null)
+if [ "$(git log --format='%an|%s' refs/trunk/guide_history | head -2 | paste -sd,)" != "Ada Writer|Explain running it,Test User|Add the guide and the code" ] \
+    || [ "$(git rev-list --count refs/trunk/guide_history)" != "3" ] || ! git status --porcelain -- guide | grep -q "^D  guide/install.md"; then
+    echo "ERROR: adopt --preserve-history did not replay the history of guide/ or stage its removal:"; git log --format='%an|%s' refs/trunk/guide_history
+    exit 1
+fi
+git commit -q -m "Move the guide into a store"
+echo "VERIFY: --preserve-history replays only the commits that changed the directory, with their authors, and --remove stages its removal."
+
+# Scenario 52.3: errors
+echo "INFO: Scenario 3: adopt errors"
+echo "// local" >> src/main.rs
+mkdir -p scratch && echo "Untracked" > scratch/note.md
+set +e
+$GIT_TRUNK_CMD --store guide adopt src 2> /dev/null
+taken=$?
+$GIT_TRUNK_CMD --store code adopt src --remove 2> /dev/null
+dirty=$?
+$GIT_TRUNK_CMD --store scratch adopt scratch 2> /dev/null
+untracked=$?
+$GIT_TRUNK_CMD --store nowhere adopt no-such-dir 2> /dev/null
+missing=$?
+set -e
+if [ "$taken" != "4" ] || [ "$dirty" != "4" ] || [ "$untracked" != "3" ] || [ "$missing" != "3" ] \
+    || git show-ref -q refs/trunk/code || git show-ref -q refs/trunk/scratch || ! grep -q "// local" src/main.rs; then
+    echo "ERROR: adopt into an existing store exited with $taken (expected 4), --remove with uncommitted changes with $dirty (expected 4), of an untracked directory with $untracked and a missing one with $missing (expected 3)"
+    exit 1
+fi
+echo "VERIFY: adopt refuses existing stores and uncommitted changes --remove would lose with 4, and directories not committed at HEAD with 3, creating nothing."
+
+echo "SUCCESS: test_adopt completed."
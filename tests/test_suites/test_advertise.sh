@@ -0,0 +1,68 @@
+#!/bin/bash
+# test_advertise.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_advertise in $TEST_DIR"
+setup_repo --remote
+
+# Scenario 13.1: nothing to advertise
+echo "INFO: Scenario 1: no stores"
+set +e
+$GIT_TRUNK_CMD advertise 2> none.err
+none=$?
+set -e
+if [ "$none" != "3" ] || ! grep -q "No trunk stores found locally or on remote 'origin'" none.err || [ -e docs/TRUNK.md ]; then
+    echo "ERROR: advertise without stores exited with $none (expected 3) or wrote a file:"; cat none.err
+    exit 1
+fi
+echo "VERIFY: advertise exits 3 and writes nothing when there is no store."
+
+# Scenario 13.2: pushed and local stores
+echo "INFO: Scenario 2: docs/TRUNK.md"
+for store in docs notes; do
+    $GIT_TRUNK_CMD --store "$store" init 2> /dev/null
+    $GIT_TRUNK_CMD --store "$store" commit --force -m "Start" 2> /dev/null
+done
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+$GIT_TRUNK_CMD advertise 2> /dev/null
+$GIT_TRUNK_CMD advertise 2> rerun.err
+if ! grep -qxF '| docs | `git trunk checkout --store docs` |' docs/TRUNK.md || ! grep -qxF '| notes | not pushed to `origin` yet |' docs/TRUNK.md \
+    || [ "$(head -1 docs/TRUNK.md)" != "<!-- git-trunk:advertise:begin -->" ] || [ "$(tail -1 docs/TRUNK.md)" != "<!-- git-trunk:advertise:end -->" ] \
+    || ! grep -q "docs/TRUNK.md is already up to date" rerun.err; then
+    echo "ERROR: docs/TRUNK.md did not list docs as pushed and notes as local, or the rerun rewrote it:"; cat docs/TRUNK.md rerun.err
+    exit 1
+fi
+echo "VERIFY: advertise writes docs/TRUNK.md between the markers with a checkout command for the pushed store and a note for the local one, and a rerun changes nothing."
+
+# Scenario 13.3: a section inside an existing file
+echo "INFO: Scenario 3: --file and --remote"
+printf '# Project\n\nIntro.\n' > README.md
+$GIT_TRUNK_CMD advertise --file README.md 2> /dev/null
+echo "Footer." >> README.md
+git init -q --bare ../upstream.git
+git remote add upstream ../upstream.git
+$GIT_TRUNK_CMD --store notes push --remote upstream 2> /dev/null
+$GIT_TRUNK_CMD --remote upstream advertise --file README.md 2> /dev/null
+if [ "$(head -4 README.md)" != "$(printf '# Project\n\nIntro.\n')" ] || [ "$(tail -1 README.md)" != "Footer." ] \
+    || [ "$(grep -c "git-trunk:advertise:begin" README.md)" != "1" ] \
+    || ! grep -qxF '| notes | `git trunk checkout --store notes --remote upstream` |' README.md || ! grep -qxF '| docs | not pushed to `upstream` yet |' README.md; then
+    echo "ERROR: advertise did not append to README.md and refresh the section in place:"; cat README.md
+    exit 1
+fi
+git remote remove upstream
+rm -rf ../upstream.git
+$GIT_TRUNK_CMD --remote upstream advertise --file README.md 2> gone.err
+if ! grep -q "Could not list refs/trunk/\* on remote 'upstream'" gone.err || ! grep -qxF '| notes | not pushed to `upstream` yet |' README.md; then
+    echo "ERROR: advertise did not fall back to the local stores for an unreachable remote:"; cat gone.err README.md
+    exit 1
+fi
+echo "VERIFY: advertise appends the section to an existing file, refreshes it in place keeping the text around it, names a non-default remote, and warns and lists local stores when the remote is unreachable."
+
+echo "SUCCESS: test_advertise completed."
@@ -0,0 +1,70 @@
+#!/bin/bash
+# test_agenda.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_agenda in $TEST_DIR"
+setup_repo
+soon=$(date -d "+3 days" +%F)
+overdue=$(date -d "-2 days" +%F)
+later=$(date -d "+40 days" +%F)
+$GIT_TRUNK_CMD --store notes init 2> /dev/null
+cat > .trunk/notes/todo.md <<EOF
+# Todo
+- [ ] Ship the release @due($soon)
+- [x] Write the changelog @due($soon)
+- [ ] Someday
+Review the budget @due($overdue)
+Plan the offsite @due($later)
+\`\`\`
+- [ ] Example task @due($soon)
+\`\`\`
+EOF
+$GIT_TRUNK_CMD --store notes commit --force 2> /dev/null
+$GIT_TRUNK_CMD --store team init 2> /dev/null
+echo "- [ ] Hire @due($soon)" > .trunk/team/hiring.md
+$GIT_TRUNK_CMD --store team commit --force 2> /dev/null
+
+# Scenario 17.1: due dates within --days, overdue first
+echo "INFO: Scenario 1: agenda"
+$GIT_TRUNK_CMD --store notes agenda > agenda.out 2> /dev/null
+if [ "$(grep -c "notes  todo.md" agenda.out)" != "2" ] || ! grep -q "^$overdue  2 day(s) overdue  notes  todo.md:5  Review the budget$" agenda.out \
+    || ! grep -q "^$soon  in 3 days *notes  todo.md:2  Ship the release$" agenda.out \
+    || grep -q "changelog\|Example task\|offsite\|Hire" agenda.out; then
+    echo "ERROR: agenda did not list the overdue and upcoming items only:"; cat agenda.out
+    exit 1
+fi
+echo "VERIFY: agenda lists overdue items and those due within 14 days, skipping checked tasks, code blocks and other stores."
+
+# Scenario 17.2: --undated, --all, --days and --ical
+echo "INFO: Scenario 2: agenda options"
+$GIT_TRUNK_CMD --store notes agenda --undated --no-table > undated.out 2> /dev/null
+$GIT_TRUNK_CMD agenda --all --days 60 > all.out 2> /dev/null
+$GIT_TRUNK_CMD --store notes agenda --ical > agenda.ics 2> /dev/null
+if ! grep -q "^Item: Someday$" undated.out || ! grep -q "Hire" all.out || ! grep -q "Plan the offsite" all.out \
+    || [ "$(grep -c "^BEGIN:VEVENT" agenda.ics)" != "3" ] || ! grep -q "^DTSTART;VALUE=DATE:${soon//-/}" agenda.ics \
+    || ! grep -q "^DESCRIPTION:notes/todo.md:2" agenda.ics; then
+    echo "ERROR: agenda options did not work:"; cat undated.out all.out agenda.ics
+    exit 1
+fi
+echo "VERIFY: --undated adds open tasks without a date, --all scans every store, --days widens the window and --ical exports every dated item."
+
+# Scenario 17.3: a store without a ref
+echo "INFO: Scenario 3: agenda of a missing store"
+set +e
+$GIT_TRUNK_CMD --store missing agenda 2> missing.err
+missing=$?
+set -e
+if [ "$missing" != "5" ] || ! grep -q "git trunk commit --store missing" missing.err; then
+    echo "ERROR: agenda of a missing store exited with $missing (expected 5):"; cat missing.err
+    exit 1
+fi
+echo "VERIFY: agenda of a store without a ref exits with 5, pointing to commit."
+
+echo "SUCCESS: test_agenda completed."
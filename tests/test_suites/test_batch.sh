@@ -0,0 +1,63 @@
+#!/bin/bash
+# test_batch.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_batch in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+echo "The plan." > .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Plan" 2> /dev/null
+
+# Scenario 22.1: one JSON result per request, in order
+echo "INFO: Scenario 1: batch"
+echo "More plan." >> .trunk/docs/plan.md
+printf '%s\n' \
+    '{"id": 1, "cmd": "ls", "store": "docs"}' \
+    '{"id": "two", "cmd": "commit", "store": "docs", "force": true, "message": "From batch"}' \
+    '{"id": 3, "cmd": "cat", "store": "docs", "args": ["plan.md"]}' \
+    | $GIT_TRUNK_CMD batch > results.jsonl 2> /dev/null
+if [ "$(wc -l < results.jsonl)" != "3" ] \
+    || [ "$(sed -n 1p results.jsonl)" != '{"id":1,"ok":true,"exit_code":0,"stdout":"ABOUT.md\nplan.md\nreadme.md\n","stderr":""}' ] \
+    || ! sed -n 2p results.jsonl | grep -q '^{"id":"two","ok":true,"exit_code":0,' \
+    || ! sed -n 3p results.jsonl | grep -q '"stdout":"The plan.\\nMore plan.\\n"' \
+    || [ "$(git log -1 --format=%s refs/trunk/docs)" != "From batch" ]; then
+    echo "ERROR: batch did not run the requests in order:"; cat results.jsonl
+    exit 1
+fi
+echo "VERIFY: batch runs each request with its store, flags and args and writes one JSON result per line with the id, exit code and output."
+
+# Scenario 22.2: failed and invalid requests
+echo "INFO: Scenario 2: batch errors"
+set +e
+printf '%s\n' \
+    'not json' \
+    '{"id": 1, "cmd": "cat", "store": "missing", "args": ["x.md"]}' \
+    '{"id": 2, "cmd": "batch"}' \
+    '{"id": 3, "cmd": "ls", "store": "docs"}' \
+    | $GIT_TRUNK_CMD batch > failed.jsonl 2> /dev/null
+failed=$?
+printf '%s\n' \
+    '{"id": 1, "cmd": "cat", "store": "missing", "args": ["x.md"]}' \
+    '{"id": 2, "cmd": "ls", "store": "docs"}' \
+    | $GIT_TRUNK_CMD batch --stop-on-error > stopped.jsonl 2> /dev/null
+stopped=$?
+set -e
+if [ "$failed" != "1" ] || [ "$(wc -l < failed.jsonl)" != "4" ] \
+    || ! sed -n 1p failed.jsonl | grep -q '^{"id":null,"ok":false,"error":"invalid JSON' \
+    || ! sed -n 2p failed.jsonl | grep -q '^{"id":1,"ok":false,"exit_code":3,' \
+    || [ "$(sed -n 3p failed.jsonl)" != '{"id":2,"ok":false,"error":"batch requests cannot be nested"}' ] \
+    || ! sed -n 4p failed.jsonl | grep -q '^{"id":3,"ok":true,' \
+    || [ "$stopped" != "1" ] || [ "$(wc -l < stopped.jsonl)" != "1" ]; then
+    echo "ERROR: batch exited with $failed and $stopped (expected 1) or did not report each failure:"; cat failed.jsonl stopped.jsonl
+    exit 1
+fi
+echo "VERIFY: Invalid, failing and nested requests get an error result and batch goes on, exiting with 1; --stop-on-error stops at the first failure."
+
+echo "SUCCESS: test_batch completed."
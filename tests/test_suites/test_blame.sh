@@ -0,0 +1,57 @@
+#!/bin/bash
+# test_blame.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_blame in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+printf '# Intro\nHello\n# Usage\nRun it\n' > .trunk/docs/guide.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Guide" 2> /dev/null
+first=$(git rev-parse refs/trunk/docs)
+sed -i 's/Run it/Run it twice/' .trunk/docs/guide.md
+git -C .trunk/docs -c user.name=Bob -c user.email=bob@example.com commit -q -am "Usage"
+$GIT_TRUNK_CMD --store docs commit --force 2> /dev/null
+
+# Scenario 46.1: line and section blame of refs/trunk/<store>
+echo "INFO: Scenario 1: blame"
+$GIT_TRUNK_CMD --store docs blame guide.md > blame.out 2> /dev/null
+$GIT_TRUNK_CMD --store docs blame docs:guide.md --sections --no-table > sections.out 2> /dev/null
+if [ "$(wc -l < blame.out)" != "4" ] || ! grep -q "(Bob .* 4) Run it twice$" blame.out || ! grep -q "1) # Intro$" blame.out || grep -q "(Bob .* 1)" blame.out \
+    || [ "$(grep -A5 "^Section: Usage$" sections.out | grep "^Author:")" != "Author: Bob" ] \
+    || [ "$(grep -A5 "^Section: Intro$" sections.out | grep "^Message:")" != "Message: Guide" ]; then
+    echo "ERROR: blame did not attribute the lines or sections:"; cat blame.out sections.out
+    exit 1
+fi
+echo "VERIFY: blame attributes each line of a store file, and --sections the last change to each Markdown section."
+
+# Scenario 46.2: --ref blames an older commit
+echo "INFO: Scenario 2: blame --ref"
+$GIT_TRUNK_CMD --store docs blame guide.md --ref "$first" > old.out 2> /dev/null
+if ! grep -q "4) Run it$" old.out || grep -q "Bob" old.out; then
+    echo "ERROR: blame --ref did not blame the older commit:"; cat old.out
+    exit 1
+fi
+echo "VERIFY: --ref blames the file as of another commit of the store."
+
+# Scenario 46.3: missing files and stores
+echo "INFO: Scenario 3: blame errors"
+set +e
+$GIT_TRUNK_CMD --store docs blame no-such-file.md 2> unknown.err
+unknown=$?
+$GIT_TRUNK_CMD --store missing blame guide.md 2> /dev/null
+missing=$?
+set -e
+if [ "$unknown" != "3" ] || ! grep -q "'no-such-file.md' is not in refs/trunk/docs" unknown.err || [ "$missing" != "3" ]; then
+    echo "ERROR: blame of a missing file exited with $unknown, of a missing store with $missing (expected 3):"; cat unknown.err
+    exit 1
+fi
+echo "VERIFY: blame of a file or store that does not exist exits with 3."
+
+echo "SUCCESS: test_blame completed."
@@ -0,0 +1,62 @@
+#!/bin/bash
+# test_board.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_board in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store tasks init 2> /dev/null
+printf -- '---\ntitle: Login\nstatus: draft\n---\nLog in.\n' > .trunk/tasks/login.md
+printf -- '---\ntitle: Logout\nstatus: done\n---\nLog out.\n' > .trunk/tasks/logout.md
+$GIT_TRUNK_CMD --store tasks commit --force -m "Two tasks" 2> /dev/null
+
+# Scenario 16.1: the board groups documents by status
+echo "INFO: Scenario 1: board"
+$GIT_TRUNK_CMD --store tasks board > board.out 2> /dev/null
+$GIT_TRUNK_CMD --store tasks board --no-table > lines.out 2> /dev/null
+if ! grep -q "^draft (1) *done (1) *(none) (1)$" board.out || ! grep -q "^Login (login.md) *Logout (logout.md) *ABOUT.md$" board.out \
+    || ! grep -A1 "^draft (1)$" lines.out | grep -q "Login (login.md)"; then
+    echo "ERROR: board did not group the documents by status:"; cat board.out lines.out
+    exit 1
+fi
+echo "VERIFY: board shows one column per status value, in the order found and documents without one last, side by side or as lines."
+
+# Scenario 16.2: board_columns fixes the columns, and move commits the change
+echo "INFO: Scenario 2: board_columns and board move"
+$GIT_TRUNK_CMD config set store.tasks.board_columns draft,review,done 2> /dev/null
+$GIT_TRUNK_CMD --store tasks board move login.md review 2> /dev/null
+$GIT_TRUNK_CMD --store tasks board > board.out 2> /dev/null
+if ! grep -q "^draft (0) *review (1) *done (1) *(none) (1)$" board.out \
+    || [ "$(git log -1 --format=%s refs/trunk/tasks)" != "Move login.md from draft to review" ] \
+    || ! git show refs/trunk/tasks:login.md | grep -q "^status: review$"; then
+    echo "ERROR: board move did not commit the new column:"; cat board.out; git log -1 --format=%s refs/trunk/tasks
+    exit 1
+fi
+echo "VERIFY: board_columns fixes the columns and their order, and board move edits the frontmatter and commits it to refs/trunk/<store>."
+
+# Scenario 16.3: errors
+echo "INFO: Scenario 3: board move errors"
+tip=$(git rev-parse refs/trunk/tasks)
+set +e
+$GIT_TRUNK_CMD --store tasks board move login.md nowhere 2> /dev/null
+unknown=$?
+$GIT_TRUNK_CMD --store tasks board move missing.md done 2> /dev/null
+missing=$?
+echo "Half done." >> .trunk/tasks/logout.md
+$GIT_TRUNK_CMD --store tasks board move login.md done 2> /dev/null
+dirty=$?
+set -e
+if [ "$unknown" != "2" ] || [ "$missing" != "3" ] || [ "$dirty" != "4" ] || [ "$(git rev-parse refs/trunk/tasks)" != "$tip" ] \
+    || ! grep -q "^status: review$" .trunk/tasks/login.md; then
+    echo "ERROR: board move to an unknown column exited with $unknown (expected 2), of a missing document with $missing (expected 3), in a dirty checkout with $dirty (expected 4)"
+    exit 1
+fi
+echo "VERIFY: board move refuses unknown columns with 2, missing documents with 3 and a checkout with uncommitted changes with 4, changing nothing."
+
+echo "SUCCESS: test_board completed."
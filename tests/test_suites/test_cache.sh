@@ -0,0 +1,143 @@
+#!/bin/bash
+# test_cache.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+export XDG_CACHE_HOME="$TEST_DIR/cache"
+export GIT_TRUNK_HOME="$TEST_DIR/home"
+
+# The installed hooks call `git trunk`, so put the binary under test on PATH
+mkdir -p "$TEST_DIR/bin"
+ln -sf "$(command -v "$GIT_TRUNK_CMD")" "$TEST_DIR/bin/git-trunk"
+export PATH="$TEST_DIR/bin:$PATH"
+
+expect_state() {
+    local description="$1" expected="$2" actual
+    actual=$($GIT_TRUNK_CMD --store docs status --format '{remote_state}')
+    if [ "$actual" != "$expected" ]; then
+        echo "ERROR: $description: status reported '$actual', expected '$expected'"
+        exit 1
+    fi
+}
+
+echo "INFO: Starting test_cache in $TEST_DIR"
+setup_repo --remote
+
+$GIT_TRUNK_CMD --store docs init
+$GIT_TRUNK_CMD --store docs commit --force
+$GIT_TRUNK_CMD --store docs push
+$GIT_TRUNK_CMD --store docs hooks install
+$GIT_TRUNK_CMD config set trunk.remote_cache_ttl 3600
+
+# Scenario 33.1: cache files live under XDG_CACHE_HOME
+echo "INFO: Scenario 1: cache location"
+expect_state "a fresh listing" "same"
+git commit -q --allow-empty -m "Fire the post-commit hook"
+CACHE_DIR=$(ls -d "$XDG_CACHE_HOME"/git-trunk/repo-*)
+for entry in ls-remote-origin hook-post-commit.stamp; do
+    if [ ! -f "$CACHE_DIR/$entry" ]; then
+        echo "ERROR: $entry was not cached in $CACHE_DIR:"; ls -la "$CACHE_DIR"
+        exit 1
+    fi
+done
+if ls .git | grep -q "stamp"; then
+    echo "ERROR: cache files were written to the git directory:"; ls .git
+    exit 1
+fi
+echo "VERIFY: the cache lives in \$XDG_CACHE_HOME/git-trunk/<repo-id>, not in .git."
+
+# Scenario 33.2: the listing is reused until cleared or pushed over
+echo "INFO: Scenario 2: reuse and invalidation"
+elsewhere=$(git commit-tree -p refs/trunk/docs -m "Pushed from elsewhere" "refs/trunk/docs^{tree}")
+git push -q --no-verify origin "$elsewhere:refs/trunk/docs"
+expect_state "a cached listing" "same"
+$GIT_TRUNK_CMD cache clear
+expect_state "after cache clear" "differs"
+git push -q --no-verify --force origin refs/trunk/docs:refs/trunk/docs
+echo "# Docs" > .trunk/docs/readme.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Add docs"
+$GIT_TRUNK_CMD --store docs push
+expect_state "after push" "same"
+echo "VERIFY: status reuses the cached listing; cache clear and push drop it."
+
+# Scenario 33.3: cache info
+echo "INFO: Scenario 3: cache info"
+if ! $GIT_TRUNK_CMD cache info --no-table | grep -q "^Entry: ls-remote-origin"; then
+    echo "ERROR: cache info did not list the cached listing:"; $GIT_TRUNK_CMD cache info --no-table
+    exit 1
+fi
+$GIT_TRUNK_CMD cache clear
+if ! $GIT_TRUNK_CMD cache info | grep -q "The cache is empty"; then
+    echo "ERROR: cache info did not report an empty cache after clear"
+    exit 1
+fi
+echo "VERIFY: cache info lists the entries and reports an empty cache."
+
+# Scenario 33.4: concurrent invocations share the cache safely
+echo "INFO: Scenario 4: concurrent invocations"
+pids=()
+for i in 1 2 3 4 5 6 7 8; do
+    $GIT_TRUNK_CMD --store docs status --format '{remote_state}' > "state-$i.txt" 2> "state-$i.log" &
+    pids+=($!)
+done
+for pid in "${pids[@]}"; do
+    wait "$pid"
+done
+if [ "$(cat state-*.txt | sort -u)" != "same" ]; then
+    echo "ERROR: concurrent status runs disagreed:"; cat state-*.txt state-*.log
+    exit 1
+fi
+if ls -A "$CACHE_DIR" | grep -q "^\."; then
+    echo "ERROR: lock or temporary files were left behind:"; ls -la "$CACHE_DIR"
+    exit 1
+fi
+echo "VERIFY: concurrent runs agree and leave no lock or temporary files."
+
+# Scenario 33.5: clear --all
+echo "INFO: Scenario 5: cache clear --all"
+cd ..
+git init -q -b main other
+(cd other && git remote add origin ../remote.git && printf '[trunk]\nremote_cache_ttl = 3600\n' > .git/trunk.toml && $GIT_TRUNK_CMD --store docs status > /dev/null)
+if [ "$(ls "$XDG_CACHE_HOME/git-trunk" | wc -l)" != "2" ]; then
+    echo "ERROR: expected a cache for each of the two repositories:"; ls "$XDG_CACHE_HOME/git-trunk"
+    exit 1
+fi
+(cd repo && $GIT_TRUNK_CMD cache clear --all)
+if [ -n "$(ls "$XDG_CACHE_HOME/git-trunk")" ]; then
+    echo "ERROR: cache clear --all left caches behind:"; ls -R "$XDG_CACHE_HOME/git-trunk"
+    exit 1
+fi
+echo "VERIFY: cache clear --all removes the caches of every repository."
+
+# Scenario 33.6: the user config in GIT_TRUNK_HOME
+echo "INFO: Scenario 6: user config"
+cd repo
+$GIT_TRUNK_CMD config set --user trunk.cache_dir "$TEST_DIR/user-cache"
+$GIT_TRUNK_CMD config set --user trunk.remote_cache_ttl 5
+$GIT_TRUNK_CMD config set --user alias.state "--store docs status --format {remote_state}"
+$GIT_TRUNK_CMD config set --user user.email "ana@example.com"
+state=$($GIT_TRUNK_CMD state)
+ttl=$($GIT_TRUNK_CMD config get trunk.remote_cache_ttl)
+$GIT_TRUNK_CMD --store docs lock readme.md
+set +e
+$GIT_TRUNK_CMD config set trunk.cache_dir /tmp 2> /dev/null
+repo_only=$?
+$GIT_TRUNK_CMD config set --user trunk.hooks post-commit 2> /dev/null
+user_only=$?
+set -e
+if [ ! -f "$GIT_TRUNK_HOME/config.toml" ] || [ "$state" != "same" ] || [ "$ttl" != "3600" ] \
+    || ! ls "$TEST_DIR"/user-cache/repo-*/ls-remote-origin > /dev/null 2>&1 || ! git show refs/trunk-locks/docs:locks | grep -q "ana@example.com" \
+    || [ "$repo_only" != "2" ] || [ "$user_only" != "2" ]; then
+    echo "ERROR: the user config was not applied beneath the repository's (state '$state', ttl '$ttl', exits $repo_only/$user_only):"
+    cat "$GIT_TRUNK_HOME/config.toml"; ls -R "$TEST_DIR/user-cache" || true
+    exit 1
+fi
+$GIT_TRUNK_CMD --store docs unlock readme.md
+echo "VERIFY: the user config supplies aliases, identity and cache_dir, the repository's keys win, and keys of the wrong scope exit with code 2."
+
+echo "SUCCESS: test_cache completed."
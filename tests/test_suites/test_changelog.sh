@@ -0,0 +1,64 @@
+#!/bin/bash
+# test_changelog.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_changelog in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+mkdir -p .trunk/docs/guides
+echo "Install it." > .trunk/docs/guides/install.md
+echo "Use it with care, as described in this rather long paragraph." > .trunk/docs/usage.md
+echo "Old news." > .trunk/docs/news.md
+$GIT_TRUNK_CMD --store docs commit --force -m "First docs" 2> /dev/null
+git tag docs-v1 refs/trunk/docs
+echo "Then configure it." >> .trunk/docs/guides/install.md
+echo "Upgrade it." > .trunk/docs/guides/upgrade.md
+rm .trunk/docs/news.md
+git -C .trunk/docs mv usage.md guide.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Second docs" 2> /dev/null
+
+# Scenario 20.1: changes between a tag and the store ref
+echo "INFO: Scenario 1: changelog"
+$GIT_TRUNK_CMD --store docs changelog --from docs-v1 > changelog.out 2> /dev/null
+if ! grep -q "1 added, 1 changed, 1 removed, 1 renamed" changelog.out || ! grep -q "^  + guides/upgrade.md$" changelog.out \
+    || ! grep -q "^  ~ guides/install.md$" changelog.out || ! grep -q "^  - news.md$" changelog.out \
+    || ! grep -q "^  » usage.md → guide.md$" changelog.out; then
+    echo "ERROR: changelog did not list the changes:"; cat changelog.out
+    exit 1
+fi
+echo "VERIFY: changelog lists added, changed, removed and renamed documents between a tag and refs/trunk/<store>."
+
+# Scenario 20.2: Markdown, grouped by directory, with the commit messages
+echo "INFO: Scenario 2: changelog --format md --group --messages"
+$GIT_TRUNK_CMD --store docs changelog --from '~1' --format md --group --messages > changelog.md 2> /dev/null
+if ! grep -q "^## Changes to store 'docs'" changelog.md || ! grep -q "^### guides/$" changelog.md \
+    || ! grep -q "^- added \`guides/upgrade.md\`$" changelog.md || ! grep -q "^### Commits$" changelog.md \
+    || ! grep -q "Second docs" changelog.md || grep -q "First docs" changelog.md; then
+    echo "ERROR: changelog --format md did not group the changes or list the commits:"; cat changelog.md
+    exit 1
+fi
+echo "VERIFY: --from ~N counts back from the store ref; --format md --group --messages writes Markdown sections per directory and the commits in between."
+
+# Scenario 20.3: unknown revisions and stores
+echo "INFO: Scenario 3: changelog errors"
+set +e
+$GIT_TRUNK_CMD --store docs changelog --from no-such-tag 2> unknown.err
+unknown=$?
+$GIT_TRUNK_CMD --store missing changelog --from '~1' 2> missing.err
+missing=$?
+set -e
+if [ "$unknown" != "2" ] || ! grep -q "Unknown store revision 'no-such-tag'" unknown.err \
+    || [ "$missing" != "2" ] || ! grep -q "refs/trunk/missing" missing.err; then
+    echo "ERROR: an unknown revision exited with $unknown, a missing store with $missing (expected 2):"; cat unknown.err missing.err
+    exit 1
+fi
+echo "VERIFY: An unknown revision, or ~N of a store without a ref, exits with 2."
+
+echo "SUCCESS: test_changelog completed."
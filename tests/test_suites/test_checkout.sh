@@ -0,0 +1,152 @@
+#!/bin/bash
+# test_checkout.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Commits `text` as <file> to store main in the current repository
+commit_file() {
+    local file="$1" text="$2"
+    echo "$text" > ".trunk/main/$file"
+    $GIT_TRUNK_CMD commit --force -m "Update $file" 2> /dev/null
+}
+
+# Brings alice's pushed refs/trunk/main into bob's refs/trunk/main, leaving .trunk/main alone
+fetch_ref() {
+    git fetch -q origin "+refs/trunk/main:refs/trunk/main"
+}
+
+echo "INFO: Starting test_checkout in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote.git
+for clone in alice bob; do
+    git init -q -b main "$clone"
+    (cd "$clone" && git config user.email "$clone@example.com" && git config user.name "$clone" && git commit -q --allow-empty -m "Initial commit" && git remote add origin ../remote.git)
+done
+cd alice
+$GIT_TRUNK_CMD init 2> /dev/null
+commit_file shared.md "v1"
+$GIT_TRUNK_CMD push 2> /dev/null
+
+# Scenario 4.2: checkout from the remote ref
+echo "INFO: Scenario 2: checkout from the remote"
+cd ../bob
+$GIT_TRUNK_CMD checkout 2> /dev/null
+if [ "$(cat .trunk/main/shared.md)" != "v1" ] || [ "$(git -C .trunk/main rev-parse main)" != "$(git rev-parse refs/trunk/main)" ] || ! grep -qx ".trunk" .gitignore; then
+    echo "ERROR: checkout did not create .trunk/main from the remote's refs/trunk/main"
+    exit 1
+fi
+echo "VERIFY: checkout fetches the ref and creates the checkout."
+
+# Scenario 4.6: an existing checkout is updated in place, keeping uncommitted work
+echo "INFO: Scenario 6: checkout over uncommitted changes"
+(cd ../alice && commit_file shared.md "v2" && commit_file alice.md "from alice" && $GIT_TRUNK_CMD push 2> /dev/null)
+fetch_ref
+echo "draft" > .trunk/main/draft.md
+echo "local note" > .trunk/main/notes.md
+$GIT_TRUNK_CMD checkout
+if [ "$(git -C .trunk/main rev-parse HEAD)" != "$(git rev-parse refs/trunk/main)" ] || [ "$(cat .trunk/main/shared.md)" != "v2" ] || [ "$(cat .trunk/main/draft.md)" != "draft" ] || [ "$(cat .trunk/main/notes.md)" != "local note" ]; then
+    echo "ERROR: checkout did not move .trunk/main to refs/trunk/main and keep the uncommitted files"
+    git -C .trunk/main status --short
+    exit 1
+fi
+if [ -n "$(git -C .trunk/main stash list)" ]; then
+    echo "ERROR: checkout left a stash behind after applying it cleanly"
+    exit 1
+fi
+rm .trunk/main/draft.md .trunk/main/notes.md
+echo "VERIFY: checkout fast-forwards an existing checkout and reapplies uncommitted changes."
+
+# Scenario 4.7: conflicting uncommitted changes are reported, not lost
+echo "INFO: Scenario 7: checkout over conflicting changes"
+(cd ../alice && commit_file shared.md "alice v3" && $GIT_TRUNK_CMD push 2> /dev/null)
+fetch_ref
+echo "bob v3" > .trunk/main/shared.md
+set +e
+$GIT_TRUNK_CMD checkout 2> checkout.log
+status=$?
+set -e
+if [ "$status" != "4" ] || ! grep -q "git stash list" checkout.log || [ "$(git -C .trunk/main rev-parse HEAD)" != "$(git rev-parse refs/trunk/main)" ]; then
+    echo "ERROR: a conflicting checkout did not exit with code 4 after moving to refs/trunk/main ($status):"; cat checkout.log
+    exit 1
+fi
+if ! grep -q "bob v3" .trunk/main/shared.md || [ "$(git -C .trunk/main stash list | wc -l)" != "1" ]; then
+    echo "ERROR: the conflicting uncommitted change was lost"
+    cat .trunk/main/shared.md; git -C .trunk/main stash list
+    exit 1
+fi
+git -C .trunk/main reset -q --hard
+git -C .trunk/main stash drop -q
+echo "VERIFY: conflicts exit with code 4, leave the conflict markers and keep the stash."
+
+# Scenario 4.8: commits only the checkout has are never dropped
+echo "INFO: Scenario 8: checkout over unrecorded store commits"
+(cd ../alice && commit_file alice.md "alice v4" && $GIT_TRUNK_CMD push 2> /dev/null)
+fetch_ref
+echo "bob's commit" > .trunk/main/bob.md
+git -C .trunk/main add bob.md
+git -C .trunk/main commit -q -m "Add bob.md"
+bob_head=$(git -C .trunk/main rev-parse HEAD)
+set +e
+$GIT_TRUNK_CMD checkout 2> checkout.log
+status=$?
+set -e
+if [ "$status" != "4" ] || [ "$(git -C .trunk/main rev-parse HEAD)" != "$bob_head" ] || ! grep -q "\-\-force" checkout.log; then
+    echo "ERROR: checkout over diverged store commits did not stop with exit code 4 ($status):"; cat checkout.log
+    exit 1
+fi
+echo "VERIFY: a diverged checkout is left alone with exit code 4."
+
+# Scenario 4.3: --force replaces the checkout
+echo "INFO: Scenario 3: checkout --force"
+echo "overwrite me" > .trunk/main/test_overwrite.txt
+$GIT_TRUNK_CMD checkout --force 2> /dev/null
+if [ -e .trunk/main/test_overwrite.txt ] || [ -e .trunk/main/bob.md ] || [ "$(git -C .trunk/main rev-parse HEAD)" != "$(git rev-parse refs/trunk/main)" ]; then
+    echo "ERROR: checkout --force did not recreate .trunk/main from refs/trunk/main"
+    exit 1
+fi
+echo "VERIFY: checkout --force deletes the checkout and clones it again."
+
+# Scenario 4.9: space and permissions are checked before a new checkout
+echo "INFO: Scenario 9: checkout pre-flight checks"
+$GIT_TRUNK_CMD checkout --force 2> checkout.log
+$GIT_TRUNK_CMD checkout --force --no-preflight 2> preflight.log
+if ! grep -q "needs about .* free" checkout.log || ! grep -q "\-\-no-preflight, not checking" preflight.log || [ ! -d .trunk/main/.git ]; then
+    echo "ERROR: checkout did not report its pre-flight check, or --no-preflight did not skip it:"; cat checkout.log preflight.log
+    exit 1
+fi
+if [ "$(id -u)" != "0" ]; then
+    rm -rf .trunk/main
+    chmod a-w .trunk
+    set +e
+    $GIT_TRUNK_CMD checkout 2> checkout.log
+    status=$?
+    set -e
+    chmod u+w .trunk
+    if [ "$status" != "6" ] || [ -e .trunk/main ] || ! grep -q "not writable" checkout.log; then
+        echo "ERROR: checkout into a read-only .trunk exited with $status, expected 6:"; cat checkout.log
+        exit 1
+    fi
+    $GIT_TRUNK_CMD checkout 2> /dev/null
+fi
+echo "VERIFY: checkout estimates the space it needs and refuses an unwritable .trunk up front; --no-preflight skips both."
+
+# Scenario 4.5: a store that exists nowhere
+echo "INFO: Scenario 5: checkout of a missing store"
+set +e
+$GIT_TRUNK_CMD --store non_existent_store checkout 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "3" ] || [ -e .trunk/non_existent_store ]; then
+    echo "ERROR: checking out a missing store exited with $status, expected 3"
+    exit 1
+fi
+echo "VERIFY: a missing store fails with exit code 3."
+
+echo "SUCCESS: test_checkout completed."
@@ -0,0 +1,68 @@
+#!/bin/bash
+# test_ci.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_ci in $TEST_DIR"
+setup_repo --remote
+git push -q origin main
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+echo "# Plan" > .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Plan" 2> /dev/null
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+
+# Runs the `run:` steps of a GitHub workflow in the current directory, with
+# `git trunk` standing for the binary under test. The install step is skipped.
+run_workflow() {
+    sed -n -e 's/^        run: //p' -e 's/^          //p' "$1" | grep -v -e '^|$' -e '^cargo install ' | while IFS= read -r command; do
+        eval "${command/#git trunk /\"\$GIT_TRUNK_CMD\" }" 2>> "$TEST_DIR/workflow.err"
+    done
+}
+
+# Scenario 60.1: the generated workflow runs
+echo "INFO: Scenario 1: running the GitHub workflow"
+$GIT_TRUNK_CMD ci refresh-job --output .github/workflows/trunk-refresh.yml 2> /dev/null
+if ! grep -qxF '    - cron: "0 3 * * *"' .github/workflows/trunk-refresh.yml || ! grep -qxF '          git trunk checkout --store docs --force' .github/workflows/trunk-refresh.yml; then
+    echo "ERROR: the workflow does not run daily or fetch store docs:"; cat .github/workflows/trunk-refresh.yml
+    exit 1
+fi
+git clone -q ../remote.git ../runner
+# A runner starts without a git identity; the workflow sets one globally
+(cd ../runner && GIT_CONFIG_GLOBAL="$TEST_DIR/runner.gitconfig" run_workflow "$TEST_DIR/repo/.github/workflows/trunk-refresh.yml")
+if ! git --git-dir=../remote.git cat-file -p refs/trunk/docs:INDEX.md | grep -qxF -- "- [Plan](plan.md) — $(date +%Y-%m-%d)" \
+    || [ "$(git --git-dir=../remote.git log --format='%an: %s' -1 refs/trunk/docs)" != "github-actions[bot]: Scheduled refresh of store 'docs'" ]; then
+    echo "ERROR: running the workflow in a fresh clone did not push a refreshed index:"; git --git-dir=../remote.git log --format='%an: %s' refs/trunk/docs; cat "$TEST_DIR/workflow.err"
+    exit 1
+fi
+before="$(git --git-dir=../remote.git rev-parse refs/trunk/docs)"
+(cd ../runner && GIT_CONFIG_GLOBAL="$TEST_DIR/runner.gitconfig" run_workflow "$TEST_DIR/repo/.github/workflows/trunk-refresh.yml")
+if [ "$(git --git-dir=../remote.git rev-parse refs/trunk/docs)" != "$before" ]; then
+    echo "ERROR: running the workflow again pushed a commit although nothing changed"
+    exit 1
+fi
+echo "VERIFY: The generated workflow, run in a fresh clone without a git identity, checks out store docs, passes doctor, and pushes a commit with the regenerated INDEX.md; a second run pushes nothing."
+
+# Scenario 60.2: stores, GitLab and options
+echo "INFO: Scenario 2: stores, --provider gitlab and --cron"
+$GIT_TRUNK_CMD config set store.notes.lint_prose true 2> /dev/null
+$GIT_TRUNK_CMD --remote upstream ci refresh-job --provider gitlab --cron "0 4 * * 1" > gitlab.yml 2> /dev/null
+set +e
+$GIT_TRUNK_CMD ci refresh-job --cron "daily" > /dev/null 2> cron.err
+cron=$?
+set -e
+if ! grep -qxF "    - 'git trunk checkout --store docs --remote upstream --force'" gitlab.yml || ! grep -qxF "    - 'git trunk checkout --store notes --remote upstream --force'" gitlab.yml \
+    || ! grep -qxF "    - 'git trunk commit --store notes --force -m \"Scheduled refresh of store ''notes''\"'" gitlab.yml \
+    || ! grep -qF 'git remote set-url upstream "https://oauth2:${TRUNK_PUSH_TOKEN}@' gitlab.yml || ! grep -qF 'cron expression "0 4 * * 1"' gitlab.yml \
+    || [ "$cron" != "2" ] || ! grep -q "'daily' is not a cron expression with five fields" cron.err; then
+    echo "ERROR: the GitLab job missed a store or the remote, or a bad --cron exited with $cron (expected 2):"; cat gitlab.yml cron.err
+    exit 1
+fi
+echo "VERIFY: The GitLab job covers stores from refs/trunk/* and the config, uses --remote and the token, quotes single quotes, and a cron expression without five fields exits 2."
+
+echo "SUCCESS: test_ci completed."
@@ -0,0 +1,125 @@
+#!/bin/bash
+# test_commit.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Fails unless refs/trunk/main holds exactly the given files
+expect_files() {
+    local description="$1" expected="$2" actual
+    actual=$(git ls-tree -r --name-only refs/trunk/main | tr '\n' ' ' | sed 's/ $//')
+    if [ "$actual" != "$expected" ]; then
+        echo "ERROR: $description: refs/trunk/main holds '$actual', expected '$expected'"
+        exit 1
+    fi
+}
+
+echo "INFO: Starting test_commit in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD init
+
+# Scenario 2.3: --force stages and commits everything
+echo "INFO: Scenario 1: commit --force"
+echo "notes" > .trunk/main/notes.md
+$GIT_TRUNK_CMD commit --force
+expect_files "commit --force" "ABOUT.md notes.md readme.md"
+echo "VERIFY: commit --force stages every change, new files included."
+
+# Scenario 2.6: stage = "tracked" never adds untracked files
+echo "INFO: Scenario 2: stage = \"tracked\""
+$GIT_TRUNK_CMD config set store.main.stage tracked
+echo "more notes" >> .trunk/main/notes.md
+echo "scratch" > .trunk/main/scratch.txt
+$GIT_TRUNK_CMD commit --force 2> commit.log
+expect_files "stage = tracked" "ABOUT.md notes.md readme.md"
+if ! git show refs/trunk/main:notes.md | grep -q "more notes" || ! grep -q "leaving 1 untracked path(s) out: scratch.txt" commit.log; then
+    echo "ERROR: stage = tracked did not commit the tracked change or report the untracked file"
+    cat commit.log
+    exit 1
+fi
+if [ ! -f .trunk/main/scratch.txt ]; then
+    echo "ERROR: the untracked file was removed"
+    exit 1
+fi
+echo "VERIFY: stage = \"tracked\" commits changes to tracked files and leaves untracked ones out."
+
+# Scenario 2.7: stage = "all" stages everything without asking
+echo "INFO: Scenario 3: stage = \"all\" at the [trunk] level"
+$GIT_TRUNK_CMD config unset store.main.stage
+$GIT_TRUNK_CMD config set trunk.stage all
+# A prompt would now be declined: GIT_TRUNK_NONINTERACTIVE takes precedence over [trunk] prompt
+$GIT_TRUNK_CMD config set trunk.prompt no
+env -u GIT_TRUNK_NONINTERACTIVE $GIT_TRUNK_CMD commit
+expect_files "stage = all" "ABOUT.md notes.md readme.md scratch.txt"
+echo "VERIFY: [trunk] stage = \"all\" commits every change without a prompt."
+
+# Scenario 2.8: the default asks first
+echo "INFO: Scenario 4: stage = \"ask\""
+$GIT_TRUNK_CMD config set trunk.stage ask
+echo "declined" > .trunk/main/declined.md
+env -u GIT_TRUNK_NONINTERACTIVE $GIT_TRUNK_CMD commit
+expect_files "a declined prompt" "ABOUT.md notes.md readme.md scratch.txt"
+echo "VERIFY: stage = \"ask\" commits nothing when the prompt is declined."
+
+# Invalid values are rejected
+set +e
+$GIT_TRUNK_CMD config set store.main.stage everything 2> err.log
+status=$?
+set -e
+if [ "$status" = "0" ] || ! grep -q '"ask", "all" or "tracked"' err.log; then
+    echo "ERROR: an invalid stage value was accepted"
+    cat err.log
+    exit 1
+fi
+echo "VERIFY: invalid stage values are rejected."
+
+# Size quotas apply to every command that writes into a store
+echo "INFO: Scenario 5: max_size quota"
+$GIT_TRUNK_CMD config unset trunk.stage
+$GIT_TRUNK_CMD config set store.main.max_size 2KB
+head -c 8192 /dev/urandom | base64 > .trunk/main/large.txt
+set +e
+$GIT_TRUNK_CMD commit --force 2> err.log
+status=$?
+set -e
+if [ "$status" != "4" ] || ! grep -q "over its quota of 2.0 KB" err.log; then
+    echo "ERROR: a commit over the quota was not refused with exit code 4 (got $status)"
+    cat err.log
+    exit 1
+fi
+expect_files "a refused commit" "ABOUT.md notes.md readme.md scratch.txt"
+$GIT_TRUNK_CMD config set store.copied.max_size 100
+set +e
+$GIT_TRUNK_CMD copy main copied 2> err.log
+status=$?
+set -e
+if [ "$status" != "4" ] || git rev-parse -q --verify refs/trunk/copied > /dev/null || [ -d .trunk/copied ]; then
+    echo "ERROR: a copy over the new store's quota was not refused with exit code 4 (got $status)"
+    cat err.log
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD put .trunk/main/large.txt main:put.txt 2> err.log
+status=$?
+set -e
+if [ "$status" != "4" ] || git rev-parse -q --verify refs/trunk/main:put.txt > /dev/null; then
+    echo "ERROR: a put over the quota was not refused with exit code 4 (got $status)"
+    cat err.log
+    exit 1
+fi
+$GIT_TRUNK_CMD config set store.main.max_size_policy warn
+$GIT_TRUNK_CMD commit --force 2> commit.log
+expect_files "a commit over a warn quota" "ABOUT.md declined.md large.txt notes.md readme.md scratch.txt"
+if ! grep -q "Warning: Store 'main' would grow to about" commit.log; then
+    echo "ERROR: a commit over a warn quota did not warn"
+    cat commit.log
+    exit 1
+fi
+echo "VERIFY: commit, copy and put refuse to go over a \"block\" quota, and commit only warns under \"warn\"."
+
+echo "SUCCESS: test_commit completed."
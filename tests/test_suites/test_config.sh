@@ -0,0 +1,66 @@
+#!/bin/bash
+# test_config.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_config in $TEST_DIR"
+setup_repo
+COMMON_CONFIG="$(cd "$(git rev-parse --git-common-dir)" && pwd)/trunk.toml"
+
+# Scenario 61.1: mistyped keys
+echo "INFO: Scenario 1: config validate"
+printf '[trunk]\nremote = "origin"\nstor = "docs"\n' > "$COMMON_CONFIG"
+set +e
+$GIT_TRUNK_CMD config validate 2> validate.err
+validate=$?
+$GIT_TRUNK_CMD --store docs init 2> init.err
+init=$?
+set -e
+if [ "$validate" != "7" ] || ! grep -qF "$COMMON_CONFIG:3: unknown key 'stor' in [trunk] (did you mean 'store'?)" validate.err \
+    || [ "$init" != "7" ] || ! grep -qF "$COMMON_CONFIG:3: unknown key 'stor'" init.err || [ -e .trunk ]; then
+    echo "ERROR: a mistyped key was not reported with its file, line and suggestion (exit codes $validate and $init, expected 7):"; cat validate.err init.err
+    exit 1
+fi
+rm "$COMMON_CONFIG"
+echo "VERIFY: config validate and every other command exit 7 on an unknown key, naming the file and line and suggesting the known key."
+
+# Scenario 61.2: .trunk/config.toml
+echo "INFO: Scenario 2: .trunk/config.toml"
+mkdir .trunk
+printf '[trunk]\nstore = "docs"\n' > .trunk/config.toml
+$GIT_TRUNK_CMD init 2> /dev/null
+$GIT_TRUNK_CMD config set store.docs.lint_prose true 2> /dev/null
+if [ ! -d .trunk/docs/.git ] || [ "$($GIT_TRUNK_CMD config get trunk.store 2> /dev/null)" != "docs" ] || [ -e "$COMMON_CONFIG" ] \
+    || ! grep -qx "lint_prose = true" .trunk/config.toml || ! git check-ignore -q .trunk/config.toml; then
+    echo "ERROR: .trunk/config.toml was not used as the config without .git/trunk.toml:"; cat .trunk/config.toml
+    exit 1
+fi
+printf '[trunk]\nstor = "docs"\n' > .trunk/config.toml
+set +e
+$GIT_TRUNK_CMD config validate 2> validate.err
+validate=$?
+set -e
+if [ "$validate" != "7" ] || ! grep -qF "$TEST_DIR/repo/.trunk/config.toml:2: unknown key 'stor'" validate.err; then
+    echo "ERROR: a mistyped key in .trunk/config.toml was not reported (exit code $validate, expected 7):"; cat validate.err
+    exit 1
+fi
+printf '[trunk]\nstore = "docs"\n' > .trunk/config.toml
+printf '[trunk]\nremote = "origin"\n' > "$COMMON_CONFIG"
+$GIT_TRUNK_CMD config validate 2> both.err
+set +e
+$GIT_TRUNK_CMD config get trunk.store > /dev/null 2>&1
+store=$?
+set -e
+if ! grep -qF ".trunk/config.toml is ignored while $COMMON_CONFIG exists" both.err || [ "$store" != "3" ]; then
+    echo "ERROR: with both files, .trunk/config.toml was read or config validate did not warn about it:"; cat both.err
+    exit 1
+fi
+echo "VERIFY: Without .git/trunk.toml, .trunk/config.toml is read, written by config set and validated; once .git/trunk.toml exists it wins and config validate warns about the other file."
+
+echo "SUCCESS: test_config completed."
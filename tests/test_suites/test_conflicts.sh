@@ -0,0 +1,115 @@
+#!/bin/bash
+# test_conflicts.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_conflicts in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+echo "Base plan." > .trunk/docs/plan.md
+echo "Keep me." > .trunk/docs/gone.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Base" 2> /dev/null
+git -C .trunk/docs branch -q other
+echo "Our plan." > .trunk/docs/plan.md
+git -C .trunk/docs rm -q gone.md
+git -C .trunk/docs commit -q -am "Ours"
+git -C .trunk/docs checkout -q other
+echo "Their plan." > .trunk/docs/plan.md
+echo "Changed." > .trunk/docs/gone.md
+git -C .trunk/docs commit -q -am "Theirs"
+git -C .trunk/docs checkout -q -
+set +e
+git -C .trunk/docs merge other > /dev/null 2>&1
+set -e
+ours=$(git -C .trunk/docs rev-parse HEAD)
+theirs=$(git -C .trunk/docs rev-parse other)
+base_plan=$(git -C .trunk/docs rev-parse other~1:plan.md)
+our_plan=$(git -C .trunk/docs rev-parse HEAD:plan.md)
+their_plan=$(git -C .trunk/docs rev-parse other:plan.md)
+base_gone=$(git -C .trunk/docs rev-parse other~1:gone.md)
+their_gone=$(git -C .trunk/docs rev-parse other:gone.md)
+
+# Scenario 28.1: JSON
+echo "INFO: Scenario 1: conflicts --json"
+set +e
+$GIT_TRUNK_CMD --store docs conflicts --json > conflicts.json 2> /dev/null
+status=$?
+set -e
+expected='{"store":"docs","path":".trunk/docs","operation":"merge","ours":"'$ours'","theirs":"'$theirs'","conflicts":[{"path":"gone.md","type":"deleted_by_us","base":"'$base_gone'","ours":null,"theirs":"'$their_gone'"},{"path":"plan.md","type":"both_modified","base":"'$base_plan'","ours":"'$our_plan'","theirs":"'$their_plan'"}]}'
+if [ "$status" != "4" ] || [ "$(cat conflicts.json)" != "$expected" ]; then
+    echo "ERROR: conflicts --json exited with $status (expected 4) or printed:"; cat conflicts.json
+    exit 1
+fi
+echo "VERIFY: conflicts --json names the merge, both commits and each conflicted path with its type and blobs, and exits with 4."
+
+# Scenario 28.2: table and --no-table output
+echo "INFO: Scenario 2: conflicts --no-table"
+set +e
+$GIT_TRUNK_CMD --store docs conflicts --no-table > lines.out 2> lines.err
+status=$?
+$GIT_TRUNK_CMD --store docs conflicts > table.out 2> /dev/null
+set -e
+expected="Path: gone.md
+Conflict: deleted_by_us
+Base: ${base_gone:0:7}
+Ours: -
+Theirs: ${their_gone:0:7}
+
+Path: plan.md
+Conflict: both_modified
+Base: ${base_plan:0:7}
+Ours: ${our_plan:0:7}
+Theirs: ${their_plan:0:7}"
+if [ "$status" != "4" ] || [ "$(cat lines.out)" != "$expected" ] \
+    || ! grep -q "merge in .trunk/docs: ours ${ours:0:7}, theirs ${theirs:0:7}$" lines.err \
+    || ! grep -q "2 conflicted path(s) in .trunk/docs" lines.err; then
+    echo "ERROR: conflicts --no-table exited with $status (expected 4) or printed:"; cat lines.out lines.err
+    exit 1
+fi
+if ! grep -q "^gone.md  deleted_by_us  ${base_gone:0:7}  -        ${their_gone:0:7}$" table.out \
+    || ! grep -q "^plan.md  both_modified  ${base_plan:0:7}  ${our_plan:0:7}  ${their_plan:0:7}$" table.out; then
+    echo "ERROR: the conflicts table does not list both paths:"; cat table.out
+    exit 1
+fi
+echo "VERIFY: conflicts prints a table of the paths, or one \"Field: value\" line per fact with --no-table, after naming the operation."
+
+# Scenario 28.3: a conflicted stash pop has no operation
+echo "INFO: Scenario 3: conflicts after a stash pop"
+git -C .trunk/docs merge --abort
+echo "Stashed plan." > .trunk/docs/plan.md
+git -C .trunk/docs stash -q
+echo "Committed plan." > .trunk/docs/plan.md
+git -C .trunk/docs commit -q -am "Another plan"
+set +e
+git -C .trunk/docs stash pop > /dev/null 2>&1
+$GIT_TRUNK_CMD --store docs conflicts --json > stash.json 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "4" ] || ! grep -q '"operation":null,"ours":"'"$(git -C .trunk/docs rev-parse HEAD)"'","theirs":null' stash.json \
+    || ! grep -q '"path":"plan.md","type":"both_modified"' stash.json; then
+    echo "ERROR: conflicts --json after a stash pop exited with $status (expected 4) or printed:"; cat stash.json
+    exit 1
+fi
+echo "VERIFY: A conflicted stash pop is reported with a null operation and theirs."
+
+# Scenario 28.4: no conflicts
+echo "INFO: Scenario 4: resolved"
+git -C .trunk/docs checkout -q HEAD -- plan.md
+git -C .trunk/docs stash drop -q
+set +e
+$GIT_TRUNK_CMD --store docs conflicts --json > clean.json 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "0" ] || [ "$(cat clean.json)" != '{"store":"docs","path":".trunk/docs","operation":null,"ours":"'"$(git -C .trunk/docs rev-parse HEAD)"'","theirs":null,"conflicts":[]}' ]; then
+    echo "ERROR: conflicts --json of a clean store exited with $status (expected 0) or printed:"; cat clean.json
+    exit 1
+fi
+echo "VERIFY: conflicts exits with 0 and an empty list once the store is clean."
+
+echo "SUCCESS: test_conflicts completed."
@@ -0,0 +1,54 @@
+#!/bin/bash
+# test_copy.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_copy in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+echo "Plan" > .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Plan" 2> /dev/null
+tip=$(git rev-parse refs/trunk/docs)
+
+# Scenario 50.1: the copy starts at the source's commit and is checked out
+echo "INFO: Scenario 1: copy"
+echo "Draft" > .trunk/docs/draft.md
+$GIT_TRUNK_CMD copy docs fork 2> copy.err
+$GIT_TRUNK_CMD copy docs fork_ref --no-checkout 2> /dev/null
+if [ "$(git rev-parse refs/trunk/fork)" != "$tip" ] || [ "$(git -C .trunk/fork rev-parse HEAD)" != "$tip" ] \
+    || [ "$(cat .trunk/fork/plan.md)" != "Plan" ] || [ -e .trunk/fork/draft.md ] || ! grep -q "uncommitted changes" copy.err \
+    || [ "$(git rev-parse refs/trunk/fork_ref)" != "$tip" ] || [ -e .trunk/fork_ref ] || [ "$(cat .trunk/docs/draft.md)" != "Draft" ]; then
+    echo "ERROR: copy did not start the stores at refs/trunk/docs:"; cat copy.err
+    exit 1
+fi
+echo "Forked" > .trunk/fork/plan.md
+$GIT_TRUNK_CMD --store fork commit --force -m "Fork" 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" != "$tip" ] || [ "$(git rev-parse refs/trunk/fork~1)" != "$tip" ]; then
+    echo "ERROR: a commit to the copy moved the source, or the copy does not share its history"
+    exit 1
+fi
+echo "VERIFY: copy creates refs/trunk/<dst> at the source's commit and .trunk/<dst> (not with --no-checkout), warns that uncommitted changes stay behind, and the stores then go separate ways."
+
+# Scenario 50.2: errors
+echo "INFO: Scenario 2: copy errors"
+set +e
+$GIT_TRUNK_CMD copy docs fork 2> /dev/null
+taken=$?
+$GIT_TRUNK_CMD copy missing anything 2> /dev/null
+missing=$?
+$GIT_TRUNK_CMD copy docs "bad name" 2> /dev/null
+invalid=$?
+set -e
+if [ "$taken" != "4" ] || [ "$missing" != "3" ] || [ "$invalid" != "2" ] || git show-ref -q refs/trunk/anything; then
+    echo "ERROR: copy onto an existing store exited with $taken (expected 4), of a missing store with $missing (expected 3), to an invalid name with $invalid (expected 2)"
+    exit 1
+fi
+echo "VERIFY: copy refuses existing names with 4, unknown stores with 3 and invalid names with 2."
+
+echo "SUCCESS: test_copy completed."
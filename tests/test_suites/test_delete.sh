@@ -0,0 +1,220 @@
+#!/bin/bash
+# test_delete.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Creates, commits and pushes <store>
+push_store() {
+    $GIT_TRUNK_CMD --store "$1" init 2> /dev/null
+    echo "$1" > ".trunk/$1/readme.md"
+    $GIT_TRUNK_CMD --store "$1" commit --force 2> /dev/null
+    $GIT_TRUNK_CMD --store "$1" push 2> /dev/null
+}
+
+on_remote() {
+    [ -n "$(git --git-dir=../remote.git for-each-ref "refs/trunk/$1")" ]
+}
+
+echo "INFO: Starting test_delete in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote.git
+git init -q -b main repo
+cd repo
+git commit -q --allow-empty -m "Initial commit"
+git remote add origin ../remote.git
+for store in both local_side remote_side; do
+    push_store "$store"
+done
+
+# Scenario 6.1: delete removes the checkout, the local ref and the remote ref
+echo "INFO: Scenario 1: delete"
+$GIT_TRUNK_CMD --store both delete 2> /dev/null
+if [ -e .trunk/both ] || git show-ref -q refs/trunk/both || on_remote both; then
+    echo "ERROR: delete left part of store 'both' behind"
+    exit 1
+fi
+echo "VERIFY: delete removes .trunk/<store>, the local ref and the remote ref."
+
+# Scenario 6.5: --local-only keeps the remote ref
+echo "INFO: Scenario 5: delete --local-only"
+$GIT_TRUNK_CMD --store local_side delete --local-only 2> delete.log
+if [ -e .trunk/local_side ] || git show-ref -q refs/trunk/local_side || ! on_remote local_side || ! grep -q "remote ref on remote 'origin' is kept" delete.log; then
+    echo "ERROR: delete --local-only did not keep only the remote ref:"; cat delete.log
+    exit 1
+fi
+echo "VERIFY: --local-only deletes the checkout and local ref, and says the remote ref is kept."
+
+# Scenario 6.6: --remote-only keeps the checkout and the local ref
+echo "INFO: Scenario 6: delete --remote-only"
+$GIT_TRUNK_CMD --store remote_side delete --remote-only 2> delete.log
+if [ ! -f .trunk/remote_side/readme.md ] || ! git show-ref -q refs/trunk/remote_side || on_remote remote_side || grep -q "delete the local directory" delete.log; then
+    echo "ERROR: delete --remote-only did not delete only the remote ref:"; cat delete.log
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD --store remote_side delete --local-only --remote-only 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "2" ] || [ ! -d .trunk/remote_side ]; then
+    echo "ERROR: --local-only with --remote-only exited with $status, expected 2"
+    exit 1
+fi
+echo "VERIFY: --remote-only deletes only the remote ref; both flags together are refused."
+
+# Scenario 6.7: delete backs the store up and restore-backup brings it back
+echo "INFO: Scenario 7: backups and restore-backup"
+if ! ls .git/trunk-backups/both-*.bundle > /dev/null 2>&1 || ! $GIT_TRUNK_CMD restore-backup --list --no-table 2> /dev/null | grep -q "Store: both"; then
+    echo "ERROR: delete did not write a backup of store 'both':"; ls .git/trunk-backups || true
+    exit 1
+fi
+$GIT_TRUNK_CMD --store both restore-backup 2> /dev/null
+$GIT_TRUNK_CMD --store both restore-backup 2> restore.log
+$GIT_TRUNK_CMD --store both checkout 2> /dev/null
+if [ "$(cat .trunk/both/readme.md)" != "both" ] || ! grep -q "already at the backed-up commit" restore.log; then
+    echo "ERROR: restore-backup did not bring store 'both' back once:"; cat restore.log
+    exit 1
+fi
+$GIT_TRUNK_CMD --store scratch init 2> /dev/null
+echo "scratch" > .trunk/scratch/readme.md
+$GIT_TRUNK_CMD --store scratch commit --force 2> /dev/null
+$GIT_TRUNK_CMD --store scratch delete --local-only --no-backup 2> /dev/null
+if ls .git/trunk-backups/scratch-*.bundle > /dev/null 2>&1; then
+    echo "ERROR: delete --no-backup wrote a backup"
+    exit 1
+fi
+bundle=$(ls .git/trunk-backups/both-*.bundle | head -n 1)
+echo "changed" > .trunk/both/readme.md
+$GIT_TRUNK_CMD --store both commit --force 2> /dev/null
+set +e
+$GIT_TRUNK_CMD restore-backup "$bundle" 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "4" ]; then
+    echo "ERROR: restore-backup over a different refs/trunk/both exited with $status, expected 4"
+    exit 1
+fi
+echo "VERIFY: delete writes a bundle that restore-backup re-imports, --no-backup skips it, and an existing ref is never replaced."
+
+# Scenario 6.8: delete moves refs to the trash; undelete and trash purge
+echo "INFO: Scenario 8: trash, undelete and trash purge"
+if [ -z "$(git for-each-ref 'refs/trunk-trash/local_side-*')" ] \
+    || ! git --git-dir=../remote.git for-each-ref --format='%(refname)' refs/trunk-trash/ | grep -q "^refs/trunk-trash/remote_side-" \
+    || ! $GIT_TRUNK_CMD trash list --no-table 2> /dev/null | grep -q "Where: origin"; then
+    echo "ERROR: delete did not move the refs to refs/trunk-trash:"; git for-each-ref refs/trunk-trash/
+    exit 1
+fi
+$GIT_TRUNK_CMD undelete remote_side 2> /dev/null
+$GIT_TRUNK_CMD undelete local_side 2> /dev/null
+if ! on_remote remote_side || ! git show-ref -q refs/trunk/local_side || [ -n "$(git --git-dir=../remote.git for-each-ref 'refs/trunk-trash/remote_side-*')" ] || [ -n "$(git for-each-ref 'refs/trunk-trash/local_side-*')" ]; then
+    echo "ERROR: undelete did not move the refs back out of the trash"
+    exit 1
+fi
+push_store gone
+$GIT_TRUNK_CMD --store gone delete 2> /dev/null
+git update-ref -d "$(git for-each-ref --format='%(refname)' 'refs/trunk-trash/gone-*')"
+$GIT_TRUNK_CMD undelete gone 2> /dev/null
+if ! git show-ref -q refs/trunk/gone || ! on_remote gone; then
+    echo "ERROR: undelete did not bring back a store only the remote's trash held"
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD undelete never_deleted 2> /dev/null
+status=$?
+set -e
+$GIT_TRUNK_CMD trash purge --expire 30d 2> /dev/null
+kept=$(git for-each-ref refs/trunk-trash/ | wc -l)
+$GIT_TRUNK_CMD trash purge --expire now 2> /dev/null
+if [ "$status" != "3" ] || [ "$kept" = "0" ] || [ -n "$(git for-each-ref refs/trunk-trash/)" ] || [ -n "$(git --git-dir=../remote.git for-each-ref refs/trunk-trash/)" ]; then
+    echo "ERROR: undelete of an unknown store exited with $status (expected 3), or trash purge removed the wrong entries"
+    exit 1
+fi
+echo "VERIFY: delete keeps the refs in refs/trunk-trash here and on the remote, undelete moves them back, and trash purge only removes expired entries."
+
+# Scenario 6.9: rename moves the ref here and on the remote, the checkout and the config
+echo "INFO: Scenario 9: rename"
+push_store old_name
+$GIT_TRUNK_CMD config set store.old_name.auto_push false 2> /dev/null
+$GIT_TRUNK_CMD config set trunk.store old_name 2> /dev/null
+tip=$(git rev-parse refs/trunk/old_name)
+git update-ref refs/trunk-remotes/mirror/old_name "$tip"
+$GIT_TRUNK_CMD rename old_name new_name 2> /dev/null
+if git show-ref -q refs/trunk/old_name || on_remote old_name || [ -e .trunk/old_name ] \
+    || [ -n "$(git for-each-ref 'refs/trunk-remotes/*/old_name')" ] || [ "$(git rev-parse refs/trunk-remotes/origin/new_name)" != "$tip" ] \
+    || [ "$(git rev-parse refs/trunk/new_name)" != "$tip" ] || [ "$(git --git-dir=../remote.git rev-parse refs/trunk/new_name)" != "$tip" ] \
+    || [ "$(cat .trunk/new_name/readme.md)" != "old_name" ] || [ "$($GIT_TRUNK_CMD config get store.new_name.auto_push)" != "false" ] \
+    || [ "$($GIT_TRUNK_CMD config get trunk.store)" != "new_name" ]; then
+    echo "ERROR: rename left part of store old_name behind:"; git for-each-ref refs/trunk/; cat .git/trunk.toml
+    exit 1
+fi
+$GIT_TRUNK_CMD config unset trunk.store 2> /dev/null
+set +e
+$GIT_TRUNK_CMD rename new_name remote_side 2> /dev/null
+taken=$?
+$GIT_TRUNK_CMD rename missing anything 2> /dev/null
+missing=$?
+set -e
+$GIT_TRUNK_CMD rename new_name local_name --local-only 2> /dev/null
+if [ "$taken" != "4" ] || [ "$missing" != "3" ] || ! git show-ref -q refs/trunk/local_name || ! on_remote new_name || on_remote local_name \
+    || [ -n "$(git for-each-ref 'refs/trunk-remotes/*/new_name' 'refs/trunk-remotes/*/local_name')" ]; then
+    echo "ERROR: rename onto an existing store exited with $taken (expected 4), of a missing store with $missing (expected 3), or --local-only touched the remote"
+    exit 1
+fi
+echo "VERIFY: rename moves refs/trunk/<store> here and on the remote, refs/trunk-remotes/<remote>/<store>, .trunk/<store>, [store.<name>] and [trunk] store; existing names exit with 4, unknown stores with 3, and --local-only leaves the remote alone."
+
+# Scenario 6.10: copy starts a new store at the source's commit and checks it out
+echo "INFO: Scenario 10: copy"
+echo "draft" > .trunk/local_name/draft.md
+$GIT_TRUNK_CMD copy local_name forked 2> copy.err
+$GIT_TRUNK_CMD copy local_name bare_fork --no-checkout 2> /dev/null
+set +e
+$GIT_TRUNK_CMD copy local_name forked 2> /dev/null
+taken=$?
+set -e
+if [ "$(git rev-parse refs/trunk/forked)" != "$(git rev-parse refs/trunk/local_name)" ] || [ "$(git -C .trunk/forked rev-parse HEAD)" != "$(git rev-parse refs/trunk/local_name)" ] \
+    || [ "$(cat .trunk/forked/readme.md)" != "old_name" ] || [ -e .trunk/forked/draft.md ] || ! grep -q "uncommitted changes" copy.err \
+    || ! git show-ref -q refs/trunk/bare_fork || [ -e .trunk/bare_fork ] || [ "$taken" != "4" ]; then
+    echo "ERROR: copy did not start the store at refs/trunk/local_name, or copying onto an existing store exited with $taken (expected 4)"
+    exit 1
+fi
+rm .trunk/local_name/draft.md
+echo "VERIFY: copy creates refs/trunk/<dst> at the source's commit and .trunk/<dst> (not with --no-checkout), warns that uncommitted changes stay behind, and refuses existing stores with 4."
+
+# Scenario 6.11: gc drops the objects of purged stores and rewritten store commits
+echo "INFO: Scenario 11: gc"
+$GIT_TRUNK_CMD --store bulky init 2> /dev/null
+echo "bulky $RANDOM $RANDOM" > .trunk/bulky/data.txt
+bulky_blob=$(git hash-object .trunk/bulky/data.txt)
+$GIT_TRUNK_CMD --store bulky commit --force 2> /dev/null
+$GIT_TRUNK_CMD --store bulky delete --local-only --no-backup 2> /dev/null
+$GIT_TRUNK_CMD trash purge --expire now --local 2> /dev/null
+(
+    cd .trunk/forked
+    echo "rewritten $RANDOM $RANDOM" > rewritten.md
+    git hash-object rewritten.md > ../../rewritten_blob
+    git add rewritten.md && git commit -q -m "Commit that is reset away"
+    git reset -q --hard HEAD~1
+    echo "stashed" >> readme.md
+    git stash -q
+)
+# Without --prune, git's reflog and prune expiry keep the recent reset commit
+$GIT_TRUNK_CMD gc 2> /dev/null
+if ! git -C .trunk/forked cat-file -e "$(cat rewritten_blob)" 2> /dev/null; then
+    echo "ERROR: gc without --prune dropped a commit reset away moments ago"
+    exit 1
+fi
+$GIT_TRUNK_CMD gc --prune now 2> gc.log
+if git cat-file -e "$bulky_blob" 2> /dev/null || git -C .trunk/forked cat-file -e "$(cat rewritten_blob)" 2> /dev/null \
+    || [ "$(git -C .trunk/forked stash list | wc -l)" != "1" ] || ! grep -q "Reclaimed" gc.log || ! git cat-file -e refs/trunk/forked; then
+    echo "ERROR: gc kept the objects of a purged store or a reset commit, or dropped the stash:"; cat gc.log
+    exit 1
+fi
+echo "VERIFY: gc prunes objects only purged stores and rewritten store commits held, in the main repository and store checkouts, and keeps stashes, live stores and, without --prune, recent reflog entries."
+
+echo "SUCCESS: test_delete completed."
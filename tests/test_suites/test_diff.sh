@@ -0,0 +1,87 @@
+#!/bin/bash
+# test_diff.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_diff in $TEST_DIR"
+setup_repo --remote
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+printf 'Line one.\nLine two.\n' > .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Plan" 2> /dev/null
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+
+# Scenario 19.1: working files against refs/trunk/<store>
+echo "INFO: Scenario 1: working files against the store ref"
+$GIT_TRUNK_CMD --store docs diff > clean.out 2> /dev/null
+printf 'Line one.\nLine 2.\n' > .trunk/docs/plan.md
+echo "Brand new." > .trunk/docs/new.md
+$GIT_TRUNK_CMD --store docs diff > changed.out 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store missing diff 2> missing.err
+missing=$?
+set -e
+if [ -s clean.out ] || ! grep -qx -- "-Line two." changed.out || ! grep -qx -- "+Line 2." changed.out || ! grep -qx -- "+Brand new." changed.out \
+    || [ "$missing" != "3" ] || ! grep -q ".trunk/missing is not checked out" missing.err \
+    || git -C .trunk/docs rev-parse -q --verify refs/temp/trunk_diff > /dev/null; then
+    echo "ERROR: diff against refs/trunk/docs was wrong, or diff of a missing checkout exited with $missing (expected 3):"; cat clean.out changed.out missing.err
+    exit 1
+fi
+echo "VERIFY: diff is empty for a clean checkout, shows changed and untracked files against refs/trunk/docs, removes its temporary ref and exits 3 without a checkout."
+
+# Scenario 19.2: revisions of the store
+echo "INFO: Scenario 2: revisions"
+$GIT_TRUNK_CMD --store docs commit --force -m "Edit" 2> /dev/null
+$GIT_TRUNK_CMD --store docs diff HEAD~1 HEAD > two.out 2> /dev/null
+echo "Line three." >> .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs diff HEAD~1 > one.out 2> /dev/null
+if ! grep -qx -- "+Line 2." two.out || grep -q "Line three" two.out \
+    || ! grep -qx -- "+Line 2." one.out || ! grep -qx -- "+Line three." one.out; then
+    echo "ERROR: diff between revisions, or of the working files against one, was wrong:"; cat two.out one.out
+    exit 1
+fi
+git -C .trunk/docs checkout -q -- plan.md
+echo "VERIFY: With two revisions diff compares them; with one it compares the working files to it."
+
+# Scenario 19.3: --remote-ref
+echo "INFO: Scenario 3: against the remote's store ref"
+$GIT_TRUNK_CMD --store docs diff > local.out 2> /dev/null
+$GIT_TRUNK_CMD --store docs diff --remote-ref > remote.out 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store docs diff --remote-ref HEAD 2> usage.err
+usage=$?
+$GIT_TRUNK_CMD --store notes init 2> /dev/null
+$GIT_TRUNK_CMD --store notes diff --remote-ref 2> unpushed.err
+unpushed=$?
+set -e
+if [ -s local.out ] || ! grep -qx -- "-Line two." remote.out || ! grep -qx -- "+Line 2." remote.out \
+    || git rev-parse -q --verify refs/trunk-fetch/docs > /dev/null || git -C .trunk/docs rev-parse -q --verify refs/temp/trunk_diff > /dev/null \
+    || [ "$usage" != "2" ] || [ "$unpushed" != "5" ] || ! grep -q "Failed to fetch refs/trunk/notes from remote 'origin'" unpushed.err; then
+    echo "ERROR: diff --remote-ref was wrong, left a ref behind, or exited with $usage and $unpushed (expected 2 and 5):"; cat local.out remote.out usage.err unpushed.err
+    exit 1
+fi
+echo "VERIFY: --remote-ref compares the working files to the remote's refs/trunk/docs, which is behind the local one, cleans up its refs, refuses revisions with 2 and exits 5 for a store the remote does not have."
+
+# Scenario 19.4: word diffs
+echo "INFO: Scenario 4: word diffs"
+printf '# Release\n\nWe **fix** the bug, then ship.\n' > .trunk/docs/prose.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Prose" 2> /dev/null
+printf '# Release\n\nWe **fixed** the bug, then ship.\n' > .trunk/docs/prose.md
+$GIT_TRUNK_CMD --store docs diff --word > word.out 2> /dev/null
+$GIT_TRUNK_CMD --store docs diff --html - > diff.html 2> /dev/null
+$GIT_TRUNK_CMD --store docs diff --html review.html 2> /dev/null
+if ! grep -qF "We **[-fix-]{+fixed+}** the bug, then ship." word.out \
+    || ! grep -qF "<h2>prose.md</h2>" diff.html || ! grep -qF "<del>fix</del>" diff.html || ! grep -qF "<ins>fixed</ins>" diff.html \
+    || ! grep -qF "<h1>Store 'docs': working files against refs/trunk/docs</h1>" diff.html \
+    || ! cmp -s diff.html review.html; then
+    echo "ERROR: the word diff did not mark just the changed word:"; cat word.out diff.html
+    exit 1
+fi
+echo "VERIFY: --word marks only the changed word inside the Markdown emphasis, and --html writes the same side-by-side page to stdout or a file."
+
+echo "SUCCESS: test_diff completed."
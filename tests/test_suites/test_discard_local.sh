@@ -0,0 +1,66 @@
+#!/bin/bash
+# test_discard_local.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_discard_local in $TEST_DIR"
+setup_repo --remote
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+echo "Shared." > .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Shared plan" 2> /dev/null
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+remote_tip=$(git rev-parse refs/trunk/docs)
+echo "Local only." >> .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Local plan" 2> /dev/null
+echo "Draft" > .trunk/docs/draft.md
+
+# Scenario 23.1: a declined prompt lists what would be lost and changes nothing
+echo "INFO: Scenario 1: discard-local declined"
+local_tip=$(git rev-parse refs/trunk/docs)
+$GIT_TRUNK_CMD config set trunk.prompt no 2> /dev/null
+env -u GIT_TRUNK_NONINTERACTIVE $GIT_TRUNK_CMD --store docs discard-local > declined.out 2> /dev/null
+$GIT_TRUNK_CMD config unset trunk.prompt 2> /dev/null
+if ! grep -q "1 local commit(s):" declined.out || ! grep -q "Local plan" declined.out || ! grep -q "?? draft.md" declined.out \
+    || [ "$(git rev-parse refs/trunk/docs)" != "$local_tip" ] || [ ! -f .trunk/docs/draft.md ]; then
+    echo "ERROR: a declined discard-local did not list the local work or changed something:"; cat declined.out
+    exit 1
+fi
+echo "VERIFY: discard-local lists local commits and uncommitted files and keeps them when the prompt is declined."
+
+# Scenario 23.2: --force resets the ref and the checkout to the remote
+echo "INFO: Scenario 2: discard-local --force"
+$GIT_TRUNK_CMD --store docs discard-local --force > /dev/null 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" != "$remote_tip" ] || [ "$(git -C .trunk/docs rev-parse HEAD)" != "$remote_tip" ] \
+    || [ "$(cat .trunk/docs/plan.md)" != "Shared." ] || [ -e .trunk/docs/draft.md ]; then
+    echo "ERROR: discard-local --force did not reset refs/trunk/docs and .trunk/docs to the remote"
+    exit 1
+fi
+$GIT_TRUNK_CMD --store docs discard-local 2> nothing.log
+if ! grep -q "already matches remote 'origin'" nothing.log; then
+    echo "ERROR: discard-local of a store in sync did not report it:"; cat nothing.log
+    exit 1
+fi
+echo "VERIFY: discard-local --force resets refs/trunk/<store> and .trunk/<store> to the remote, removing untracked files; a store in sync is left alone."
+
+# Scenario 23.3: a store the remote does not have
+echo "INFO: Scenario 3: discard-local without a remote store"
+$GIT_TRUNK_CMD --store notes init 2> /dev/null
+$GIT_TRUNK_CMD --store notes commit --force -m "Notes" 2> /dev/null
+notes_tip=$(git rev-parse refs/trunk/notes)
+set +e
+$GIT_TRUNK_CMD --store notes discard-local --force 2> missing.err
+missing=$?
+set -e
+if [ "$missing" != "5" ] || ! grep -q "There is no remote state to go back to" missing.err || [ "$(git rev-parse refs/trunk/notes)" != "$notes_tip" ]; then
+    echo "ERROR: discard-local of a store missing on the remote exited with $missing (expected 5) or moved the ref:"; cat missing.err
+    exit 1
+fi
+echo "VERIFY: discard-local of a store the remote does not have exits with 5 and keeps the local ref."
+
+echo "SUCCESS: test_discard_local completed."
@@ -0,0 +1,73 @@
+#!/bin/bash
+# test_doctor.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_doctor in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD init 2> /dev/null
+$GIT_TRUNK_CMD commit --force 2> /dev/null
+
+# Scenario 9.1: a consistent repository has nothing to report
+echo "INFO: Scenario 1: doctor on a consistent repository"
+$GIT_TRUNK_CMD doctor 2> doctor.log
+if ! grep -q "Doctor found no inconsistencies" doctor.log; then
+    echo "ERROR: doctor reported issues in a freshly initialized repository:"; cat doctor.log
+    exit 1
+fi
+echo "VERIFY: doctor exits with 0 and reports no inconsistencies right after init and commit."
+
+# Scenario 9.2: declined fixes leave everything as it was and exit with 1
+echo "INFO: Scenario 2: doctor with declined fixes"
+$GIT_TRUNK_CMD config set store.ghost.auto_push false 2> /dev/null
+git update-ref refs/trunk/orphan refs/trunk/main
+$GIT_TRUNK_CMD config set trunk.prompt no 2> /dev/null
+set +e
+env -u GIT_TRUNK_NONINTERACTIVE $GIT_TRUNK_CMD doctor 2> doctor.log
+status=$?
+set -e
+if [ "$status" != "1" ] || ! grep -q "config lists store 'ghost' (line 1) but it was never initialized" doctor.log \
+    || ! grep -q "refs/trunk/orphan exists but has no \[store.orphan\] config entry" doctor.log \
+    || ! grep -q "Doctor found 3 issue(s), 0 fixed" doctor.log || git show-ref -q refs/trunk/ghost || [ -e .trunk/ghost ]; then
+    echo "ERROR: doctor with declined fixes exited with $status (expected 1) or changed something:"; cat doctor.log
+    exit 1
+fi
+echo "VERIFY: doctor names each inconsistency, changes nothing when its fixes are declined and exits with 1."
+
+# Scenario 9.3: --fix applies every fix
+echo "INFO: Scenario 3: doctor --fix"
+$GIT_TRUNK_CMD doctor --fix 2> doctor.log
+if [ ! -d .trunk/ghost/.git ] \
+    || ! grep -q "^\[store.orphan\]" .git/trunk.toml || ! grep -q "^\[store.main\]" .git/trunk.toml \
+    || ! grep -q "Doctor found 3 issue(s), all fixed" doctor.log; then
+    echo "ERROR: doctor --fix did not fix every issue:"; cat doctor.log; cat .git/trunk.toml
+    exit 1
+fi
+$GIT_TRUNK_CMD doctor 2> doctor.log
+if ! grep -q "Doctor found no inconsistencies" doctor.log; then
+    echo "ERROR: doctor still reports issues after --fix:"; cat doctor.log
+    exit 1
+fi
+echo "VERIFY: doctor --fix initializes the configured store and adds the missing config entries, after which doctor is clean."
+
+# Scenario 9.4: outside a repository
+echo "INFO: Scenario 4: doctor outside a repository"
+mkdir -p "$TEST_DIR/not_a_repo"
+cd "$TEST_DIR/not_a_repo"
+set +e
+GIT_CEILING_DIRECTORIES="$TEST_DIR" $GIT_TRUNK_CMD doctor 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "5" ]; then
+    echo "ERROR: doctor outside a repository exited with $status (expected 5)"
+    exit 1
+fi
+echo "VERIFY: doctor outside a git repository exits with 5."
+
+echo "SUCCESS: test_doctor completed."
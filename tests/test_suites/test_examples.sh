@@ -0,0 +1,65 @@
+#!/bin/bash
+# test_examples.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_examples in $TEST_DIR"
+setup_repo --remote
+
+# Scenario 24.1: topics, aliases and unknown topics
+echo "INFO: Scenario 1: listing and finding recipes"
+$GIT_TRUNK_CMD examples > topics.out
+$GIT_TRUNK_CMD examples RECOVER > diverged.out
+set +e
+$GIT_TRUNK_CMD examples nope > nope.out 2> nope.err
+nope=$?
+set -e
+if [ "$(sed -n 's/^  \([a-z]*\) .*/\1/p' topics.out | tr '\n' ' ')" != "adopt share ci diverged review " ] \
+    || [ "$(head -1 diverged.out)" != "# Recover when the local store and the remote have diverged" ] \
+    || [ "$nope" != "3" ] || ! grep -q "No recipe named 'nope'" nope.err || ! cmp -s topics.out nope.out; then
+    echo "ERROR: examples listed the wrong topics, missed an alias, or exited with $nope for an unknown topic (expected 3):"; cat topics.out diverged.out nope.out nope.err
+    exit 1
+fi
+echo "VERIFY: examples lists the five topics, finds a recipe by alias in any case, and exits 3 listing the topics for an unknown one."
+
+# Scenario 24.2: every recipe line is a valid command line
+echo "INFO: Scenario 2: recipe command lines parse"
+export CI_COMMIT_SHA=0123456 # used by the ci recipe
+for topic in adopt share ci diverged review; do
+    $GIT_TRUNK_CMD examples "$topic" | grep "^git trunk " | while IFS= read -r command; do
+        # `--help` after the arguments makes clap check them without running anything
+        set +e
+        eval "${command/#git trunk /\"\$GIT_TRUNK_CMD\" } --help" > /dev/null 2> parse.err
+        parsed=$?
+        set -e
+        if [ "$parsed" != "0" ]; then
+            echo "ERROR: recipe '$topic' has a command git-trunk does not accept: $command"; cat parse.err
+            exit 1
+        fi
+    done
+done
+echo "VERIFY: Every git trunk line of every recipe names an existing command with options it accepts."
+
+# Scenario 24.3: the adopt recipe, run as printed
+echo "INFO: Scenario 3: running the adopt recipe"
+mkdir notes
+echo "Meeting notes." > notes/monday.md
+git add notes
+git commit -q -m "Add notes"
+$GIT_TRUNK_CMD examples adopt | grep -v -e "^#" -e "^$" | while IFS= read -r command; do
+    eval "${command/#git trunk /\"\$GIT_TRUNK_CMD\" }" > /dev/null 2>> recipe.err
+done
+if [ "$(git --git-dir=../remote.git cat-file -p refs/trunk/docs:notes/monday.md)" != "Meeting notes." ] || [ -n "$(git ls-files notes)" ] \
+    || [ "$(git log --format=%s -1)" != "Move notes to the docs trunk store" ] || ! grep -qF '| docs | `git trunk checkout --store docs` |' docs/TRUNK.md; then
+    echo "ERROR: the adopt recipe did not move notes into the pushed docs store:"; git log --oneline; cat recipe.err
+    exit 1
+fi
+echo "VERIFY: Run line by line, the adopt recipe takes notes/ off the branch, pushes it in refs/trunk/docs and advertises the store."
+
+echo "SUCCESS: test_examples completed."
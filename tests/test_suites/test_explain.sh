@@ -0,0 +1,58 @@
+#!/bin/bash
+# test_explain.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_explain in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote.git
+git init -q -b main repo
+cd repo
+git commit -q --allow-empty -m "Initial commit"
+git remote add origin ../remote.git
+git push -q origin main
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+$GIT_TRUNK_CMD --store docs commit --force -m "First version" 2> /dev/null
+before=$(git for-each-ref refs/trunk)
+
+# Scenario 42.1: explain prints the plan of a command and changes nothing
+echo "INFO: Scenario 1: explain checkout and push"
+$GIT_TRUNK_CMD explain --store docs checkout --force > checkout.out 2> checkout.err
+$GIT_TRUNK_CMD --store docs explain push > push.out 2> /dev/null
+if ! grep -q "^\`git trunk --store docs checkout --force\` would, in order:$" checkout.out \
+    || ! grep -q "^   1. remove directory .*/repo/.trunk/docs$" checkout.out \
+    || ! grep -q "run \`git init\` in .*/repo/.trunk/docs$" checkout.out \
+    || ! grep -q "fetch refs/trunk/docs from .*/repo into .*/repo/.trunk/docs$" checkout.out \
+    || ! grep -q "run \`git push origin refs/trunk/docs:refs/trunk/docs\` in .*/repo$" push.out \
+    || [ -s checkout.err ]; then
+    echo "ERROR: explain did not print the plan:"; cat checkout.out checkout.err push.out
+    exit 1
+fi
+if [ -n "$(git ls-remote origin 'refs/trunk/*')" ] || [ "$(git for-each-ref refs/trunk)" != "$before" ]; then
+    echo "ERROR: explain changed the repository or the remote"
+    exit 1
+fi
+echo "VERIFY: explain numbers the operations with resolved refs and paths and runs none of them."
+
+# Scenario 42.2: failures and commands without a plan
+echo "INFO: Scenario 2: explain errors"
+set +e
+$GIT_TRUNK_CMD --store missing explain push > missing.out 2> /dev/null
+missing=$?
+$GIT_TRUNK_CMD explain ls > /dev/null 2>&1
+unsupported=$?
+set -e
+if [ "$missing" != "3" ] || ! grep -q "would fail with the error below before changing anything" missing.out || [ "$unsupported" != "2" ]; then
+    echo "ERROR: explain exited with $missing and $unsupported:"; cat missing.out
+    exit 1
+fi
+echo "VERIFY: A failing command keeps its exit code, and commands without --dry-run support exit with code 2."
+
+echo "SUCCESS: test_explain completed."
@@ -0,0 +1,71 @@
+#!/bin/bash
+# test_export_branch.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_export_branch in $TEST_DIR"
+setup_repo --remote
+$GIT_TRUNK_CMD --store site init 2> /dev/null
+echo "Home" > .trunk/site/index.md
+$GIT_TRUNK_CMD --store site commit --force -m "Home" 2> /dev/null
+
+# Scenario 54.1: the first run names the branch, later runs fast-forward it here and on the remote
+echo "INFO: Scenario 1: export-branch"
+set +e
+$GIT_TRUNK_CMD --store site export-branch 2> /dev/null
+unnamed=$?
+set -e
+$GIT_TRUNK_CMD --store site export-branch --branch pages 2> /dev/null
+first=$(git ls-remote origin refs/heads/pages | cut -f1)
+first_tip=$(git rev-parse refs/trunk/site)
+echo "Home, updated" > .trunk/site/index.md
+$GIT_TRUNK_CMD --store site commit --force -m "Update" 2> /dev/null
+$GIT_TRUNK_CMD --store site export-branch 2> /dev/null
+tip=$(git rev-parse refs/trunk/site)
+if [ "$unnamed" != "2" ] || [ "$first" != "$first_tip" ] || [ "$($GIT_TRUNK_CMD config get store.site.export_branch 2> /dev/null)" != "pages" ] \
+    || [ "$(git rev-parse refs/heads/pages)" != "$tip" ] || [ "$(git ls-remote origin refs/heads/pages | cut -f1)" != "$tip" ]; then
+    echo "ERROR: export-branch did not create and then update the branch (exit code without a branch: $unnamed, expected 2):"; git ls-remote origin
+    exit 1
+fi
+echo "VERIFY: export-branch needs --branch the first time, records it in [store.<name>] export_branch and fast-forwards the local and remote branch on later runs."
+
+# Scenario 54.2: commits made on the branch are only replaced with --force
+echo "INFO: Scenario 2: export-branch over commits of the branch"
+platform_commit=$(git commit-tree -p "$tip" -m "Edited on the platform" "$tip^{tree}")
+git push -q --no-verify origin "$platform_commit:refs/heads/pages"
+set +e
+$GIT_TRUNK_CMD --store site export-branch 2> conflict.err
+diverged=$?
+set -e
+if [ "$diverged" != "4" ] || ! grep -q "has commits that are not in store" conflict.err || [ "$(git ls-remote origin refs/heads/pages | cut -f1)" != "$platform_commit" ]; then
+    echo "ERROR: export-branch over commits of the remote branch exited with $diverged (expected 4):"; cat conflict.err
+    exit 1
+fi
+$GIT_TRUNK_CMD --store site export-branch --force 2> /dev/null
+if [ "$(git ls-remote origin refs/heads/pages | cut -f1)" != "$tip" ]; then
+    echo "ERROR: export-branch --force did not replace the branch on the remote"
+    exit 1
+fi
+echo "VERIFY: export-branch exits with 4 when the branch has commits of its own and replaces them with --force."
+
+# Scenario 54.3: errors
+echo "INFO: Scenario 3: export-branch errors"
+set +e
+$GIT_TRUNK_CMD --store missing export-branch --branch pages 2> /dev/null
+missing=$?
+$GIT_TRUNK_CMD --store site export-branch --branch "bad..name" 2> /dev/null
+invalid=$?
+set -e
+if [ "$missing" != "3" ] || [ "$invalid" != "2" ] || [ "$($GIT_TRUNK_CMD config get store.site.export_branch 2> /dev/null)" != "pages" ]; then
+    echo "ERROR: export-branch of a missing store exited with $missing (expected 3), to an invalid branch with $invalid (expected 2)"
+    exit 1
+fi
+echo "VERIFY: export-branch of a store without a ref exits with 3 and to an invalid branch name with 2."
+
+echo "SUCCESS: test_export_branch completed."
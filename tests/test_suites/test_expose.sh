@@ -0,0 +1,114 @@
+#!/bin/bash
+# test_expose.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_expose in $TEST_DIR"
+setup_repo --remote
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+$GIT_TRUNK_CMD --store notes init 2> /dev/null
+$GIT_TRUNK_CMD --store notes commit --force -m "Notes" 2> /dev/null
+COMMON="$(git rev-parse --git-common-dir)"
+
+# Scenario 27.1: what expose refuses
+echo "INFO: Scenario 1: expose errors"
+git branch taken
+set +e
+$GIT_TRUNK_CMD --store docs expose 2> noref.err
+noref=$?
+$GIT_TRUNK_CMD --store notes expose --as-branch "bad..name" 2> badname.err
+badname=$?
+$GIT_TRUNK_CMD --store notes expose --as-branch taken 2> taken.err
+taken=$?
+set -e
+if [ "$noref" != "3" ] || ! grep -q "refs/trunk/docs does not exist" noref.err \
+    || [ "$badname" != "2" ] || ! grep -q "'bad..name' is not a valid branch name" badname.err \
+    || [ "$taken" != "4" ] || ! grep -q "Branch taken already exists" taken.err || grep -qs "expose_branch" "$COMMON/trunk.toml"; then
+    echo "ERROR: expose exited with $noref, $badname and $taken (expected 3, 2 and 4) or recorded a branch:"; cat noref.err badname.err taken.err
+    exit 1
+fi
+echo "VERIFY: expose refuses a store without a ref with 3, an invalid branch name with 2 and an existing branch with 4, recording nothing."
+
+# Scenario 27.2: the branch follows commit and push --squash-since-last-push
+echo "INFO: Scenario 2: the exposed branch follows the store"
+echo "First." > .trunk/docs/first.md
+$GIT_TRUNK_CMD --store docs commit --force -m "First" 2> /dev/null
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+pushed="$(git rev-parse refs/trunk/docs)"
+$GIT_TRUNK_CMD --store docs expose 2> /dev/null
+if [ "$(git rev-parse refs/heads/trunk/docs)" != "$(git rev-parse refs/trunk/docs)" ] || ! grep -q '^expose_branch = "trunk/docs"$' "$COMMON/trunk.toml"; then
+    echo "ERROR: expose did not create trunk/docs at refs/trunk/docs or record it:"; cat "$COMMON/trunk.toml"
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD --store notes expose --as-branch trunk/docs 2> twice.err
+twice=$?
+set -e
+if [ "$twice" != "4" ] || ! grep -q "Branch trunk/docs already mirrors store 'docs'" twice.err; then
+    echo "ERROR: exposing a second store as trunk/docs exited with $twice (expected 4):"; cat twice.err
+    exit 1
+fi
+echo "Second." > .trunk/docs/second.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Second" 2> /dev/null
+echo "Third." > .trunk/docs/third.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Third" 2> /dev/null
+if [ "$(git rev-parse refs/heads/trunk/docs)" != "$(git rev-parse refs/trunk/docs)" ] || [ "$(git log --format=%s -1 refs/heads/trunk/docs)" != "Third" ]; then
+    echo "ERROR: commit did not move trunk/docs:"; git log --oneline -3 refs/heads/trunk/docs
+    exit 1
+fi
+$GIT_TRUNK_CMD --store docs push --squash-since-last-push -m "Publish" 2> /dev/null
+if [ "$(git rev-parse refs/heads/trunk/docs)" != "$(git rev-parse refs/trunk/docs)" ] || [ "$(git log --format=%s -1 refs/heads/trunk/docs)" != "Publish" ] || [ "$(git rev-parse refs/heads/trunk/docs~1)" != "$pushed" ] \
+    || [ "$(git --git-dir=../remote.git rev-parse refs/trunk/docs)" != "$(git rev-parse refs/heads/trunk/docs)" ]; then
+    echo "ERROR: push --squash-since-last-push did not move trunk/docs onto the publish commit:"; git log --oneline -3 refs/heads/trunk/docs
+    exit 1
+fi
+git update-ref refs/heads/trunk/docs "$(git commit-tree -p refs/heads/trunk/docs -m "On the branch" "refs/heads/trunk/docs^{tree}")"
+echo "Fourth." > .trunk/docs/fourth.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Fourth" 2> /dev/null
+if [ "$(git rev-parse refs/heads/trunk/docs)" != "$(git rev-parse refs/trunk/docs)" ]; then
+    echo "ERROR: a commit made on trunk/docs was not overwritten by the next store commit"
+    exit 1
+fi
+echo "VERIFY: The exposed branch is created at refs/trunk/docs and follows commit and push --squash-since-last-push, overwriting commits made on it."
+
+# Scenario 27.3: a branch checked out in a worktree is left alone
+echo "INFO: Scenario 3: checked out in a worktree"
+git worktree add -q ../exposed trunk/docs 2> /dev/null
+exposed_at="$(git rev-parse refs/heads/trunk/docs)"
+echo "Fifth." > .trunk/docs/fifth.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Fifth" 2> commit.err
+set +e
+$GIT_TRUNK_CMD --store docs unexpose 2> unexpose.err
+unexposed=$?
+set -e
+if [ "$(git rev-parse refs/heads/trunk/docs)" != "$exposed_at" ] || ! grep -q "Branch trunk/docs is checked out in" commit.err \
+    || [ "$unexposed" != "4" ] || ! grep -q "switch to another branch first" unexpose.err; then
+    echo "ERROR: trunk/docs moved while checked out, or unexpose exited with $unexposed (expected 4):"; cat commit.err unexpose.err
+    exit 1
+fi
+git worktree remove ../exposed
+echo "VERIFY: A commit warns and leaves the branch alone while a worktree has it checked out, and unexpose refuses with 4."
+
+# Scenario 27.4: unexpose deletes the branch and stops following
+echo "INFO: Scenario 4: unexpose"
+$GIT_TRUNK_CMD --store docs unexpose 2> /dev/null
+echo "Sixth." > .trunk/docs/sixth.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Sixth" 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store docs unexpose 2> again.err
+again=$?
+set -e
+if git rev-parse -q --verify refs/heads/trunk/docs > /dev/null || grep -q "expose_branch" "$COMMON/trunk.toml" \
+    || [ "$again" != "0" ] || ! grep -q "is not exposed as a branch, nothing to do" again.err; then
+    echo "ERROR: unexpose left trunk/docs or its config entry behind, or a second unexpose exited with $again:"; cat "$COMMON/trunk.toml" again.err
+    exit 1
+fi
+echo "VERIFY: unexpose deletes the branch and expose_branch, later commits do not bring it back, and a second unexpose does nothing."
+
+echo "SUCCESS: test_expose completed."
@@ -0,0 +1,124 @@
+#!/bin/bash
+# test_fetch.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Commits `text` as <file> to store <store> in the current repository and pushes it
+publish() {
+    local store="$1" file="$2" text="$3"
+    echo "$text" > ".trunk/$store/$file"
+    $GIT_TRUNK_CMD --store "$store" commit --force -m "Update $file" 2> /dev/null
+    $GIT_TRUNK_CMD --store "$store" push 2> /dev/null
+}
+
+echo "INFO: Starting test_fetch in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote.git
+for clone in alice bob; do
+    git init -q -b main "$clone"
+    (cd "$clone" && git config user.email "$clone@example.com" && git config user.name "$clone" && git commit -q --allow-empty -m "Initial commit" && git remote add origin ../remote.git)
+done
+cd alice
+for store in notes docs; do
+    $GIT_TRUNK_CMD --store "$store" init 2> /dev/null
+    publish "$store" plan.md "v1"
+done
+
+# Scenario 35.1: a store that is not checked out is fast-forwarded
+echo "INFO: Scenario 1: fetch"
+cd ../bob
+$GIT_TRUNK_CMD --store notes fetch
+(cd ../alice && publish notes plan.md "v2")
+$GIT_TRUNK_CMD --store notes fetch 2> fetch.log
+if [ "$(git show refs/trunk/notes:plan.md)" != "v2" ] || [ -e .trunk ] || ! grep -q "refs/trunk/notes: fast-forwarded" fetch.log; then
+    echo "ERROR: fetch did not fast-forward refs/trunk/notes, or touched .trunk:"; cat fetch.log
+    exit 1
+fi
+echo "VERIFY: fetch updates refs/trunk/<store> and reports it without creating .trunk."
+
+# Scenario 35.2: a checked-out store keeps its ref until pull
+echo "INFO: Scenario 2: fetch with a checkout"
+$GIT_TRUNK_CMD --store notes checkout
+(cd ../alice && publish notes plan.md "v3")
+$GIT_TRUNK_CMD --store notes fetch 2> fetch.log
+if [ "$(git show refs/trunk/notes:plan.md)" != "v2" ] || ! grep -q "git trunk pull" fetch.log; then
+    echo "ERROR: fetch moved the ref of a checked-out store:"; cat fetch.log
+    exit 1
+fi
+if [ "$(git show refs/trunk-remotes/origin/notes:plan.md)" != "v3" ]; then
+    echo "ERROR: fetch did not record the remote tip in refs/trunk-remotes/origin/notes"
+    exit 1
+fi
+$GIT_TRUNK_CMD --store notes pull 2> /dev/null
+if [ "$(git show refs/trunk/notes:plan.md)" != "v3" ] || [ "$(cat .trunk/notes/plan.md)" != "v3" ]; then
+    echo "ERROR: pull did not bring in the fetched commits"
+    exit 1
+fi
+echo "VERIFY: fetch leaves checked-out stores to pull and keeps the remote tip in refs/trunk-remotes/."
+
+# Scenario 35.3: --all creates missing refs; divergence is reported
+echo "INFO: Scenario 3: fetch --all"
+$GIT_TRUNK_CMD fetch --all --no-table > fetch.txt
+if ! git rev-parse -q --verify refs/trunk/docs > /dev/null || ! grep -q "Change: new at" fetch.txt; then
+    echo "ERROR: fetch --all did not create refs/trunk/docs:"; cat fetch.txt
+    exit 1
+fi
+local_commit=$(git commit-tree -p refs/trunk/docs -m "Local only" "refs/trunk/docs^{tree}")
+git update-ref refs/trunk/docs "$local_commit"
+(cd ../alice && publish docs plan.md "v2")
+$GIT_TRUNK_CMD fetch --all --no-table > fetch.txt 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" != "$local_commit" ] || ! grep -q "diverged (1 local, 1 remote commit(s))" fetch.txt; then
+    echo "ERROR: fetch --all did not report the divergence, or moved the ref:"; cat fetch.txt
+    exit 1
+fi
+echo "VERIFY: fetch --all creates refs for new stores and never moves a diverged ref."
+
+# Scenario 35.4: --prune
+echo "INFO: Scenario 4: fetch --all --prune"
+git update-ref refs/trunk/docs refs/trunk-remotes/origin/docs
+git update-ref refs/trunk/scratch refs/trunk/docs
+git push -q origin :refs/trunk/docs
+$GIT_TRUNK_CMD fetch --all --prune --no-table > fetch.txt 2> /dev/null
+if git rev-parse -q --verify refs/trunk/docs > /dev/null || ! git rev-parse -q --verify refs/trunk/scratch > /dev/null; then
+    echo "ERROR: fetch --prune removed the wrong refs:"; git for-each-ref refs/trunk; cat fetch.txt
+    exit 1
+fi
+if ! grep -q "Change: kept, " fetch.txt; then
+    echo "ERROR: fetch --prune did not say why refs/trunk/scratch was kept:"; cat fetch.txt
+    exit 1
+fi
+echo "VERIFY: fetch --prune deletes stores gone from the remote and keeps ones with local-only commits."
+
+# Scenario 35.5: verify reports each check and an overall health
+echo "INFO: Scenario 5: verify"
+$GIT_TRUNK_CMD --store notes verify --json > verify.json 2> /dev/null
+(cd ../alice && publish notes plan.md "v4")
+git -C .trunk/notes checkout -q --detach
+$GIT_TRUNK_CMD --store notes verify --no-table > verify.txt 2> /dev/null
+git -C .trunk/notes checkout -q main
+blob=$(echo "soon missing" | git hash-object -w --stdin)
+tree=$(printf "100644 blob %s\tlost.md\n" "$blob" | git mktree)
+git update-ref refs/trunk/broken "$(git commit-tree -m "Initial commit for store 'broken'" "$tree")"
+rm ".git/objects/${blob:0:2}/${blob:2}"
+set +e
+$GIT_TRUNK_CMD --store broken verify --local --json > broken.json 2> /dev/null
+broken=$?
+set -e
+git update-ref -d refs/trunk/broken
+if ! grep -q '"health":"ok"' verify.json || [ "$(grep -o '"status":"ok"' verify.json | wc -l)" != "5" ] \
+    || ! grep -q "which the local history does not have" verify.txt || ! grep -q "HEAD is detached" verify.txt \
+    || [ "$broken" != "1" ] || ! grep -q '"health":"fail"' broken.json || ! grep -q '"check":"objects","status":"fail"' broken.json \
+    || ! grep -q '"check":"remote","status":"skipped"' broken.json; then
+    echo "ERROR: verify reported the wrong health, or a store with a missing object exited with $broken (expected 1):"; cat verify.json verify.txt broken.json
+    exit 1
+fi
+echo "VERIFY: verify passes a sound store, warns about an unfetched remote commit and a detached checkout, and fails a store with a missing object with exit code 1."
+
+echo "SUCCESS: test_fetch completed."
@@ -0,0 +1,93 @@
+#!/bin/bash
+# test_foreign_refs.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+setup_repo() {
+    local name="$1"
+    rm -rf "$name"
+    mkdir "$name"
+    cd "$name"
+    git init -q -b main
+    git config user.email "test@example.com"
+    git config user.name "Test User"
+    git commit -q --allow-empty -m "Initial commit"
+}
+
+echo "INFO: Starting test_foreign_refs in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+
+# Scenario 1: refs of another tool stop the first init unless confirmed
+echo "INFO: Scenario 1: foreign refs are reported and need confirmation"
+setup_repo foreign
+git update-ref refs/trunk/builds HEAD
+git update-ref refs/trunk/ci/cache HEAD
+set +e
+$GIT_TRUNK_CMD init < /dev/null 2> err.log
+status=$?
+set -e
+if [ "$status" = "0" ] || ! grep -q "refs/trunk/builds (history not started by" err.log || ! grep -q "refs/trunk/ci/cache (not a store name)" err.log; then
+    echo "ERROR: init did not stop at the foreign refs (exit code $status)"
+    cat err.log
+    exit 1
+fi
+if [ -e .trunk ]; then
+    echo "ERROR: init created .trunk before the foreign refs were confirmed"
+    exit 1
+fi
+printf '[trunk]\nprompt = "no"\n' > .git/trunk.toml
+set +e
+$GIT_TRUNK_CMD init 2> err.log
+status=$?
+set -e
+if [ "$status" != "4" ]; then
+    echo "ERROR: declining to share refs/trunk/ did not exit with code 4 (got $status)"
+    cat err.log
+    exit 1
+fi
+rm .git/trunk.toml
+$GIT_TRUNK_CMD --yes init
+if ! grep -q 'foreign_refs = \["refs/trunk/builds", "refs/trunk/ci/cache"\]' .git/trunk.toml; then
+    echo "ERROR: the accepted foreign refs were not recorded:"; cat .git/trunk.toml
+    exit 1
+fi
+echo "VERIFY: foreign refs are listed with a reason, declining exits with code 4, accepting records them."
+
+# Once accepted, the refs are not reported again, even before a checkout exists
+rm -rf .trunk
+$GIT_TRUNK_CMD init < /dev/null 2> err.log
+if grep -q "did not create" err.log; then
+    echo "ERROR: accepted foreign refs were reported again"
+    cat err.log
+    exit 1
+fi
+cd ..
+echo "VERIFY: accepted foreign refs are not reported again."
+
+# Scenario 2: stores made by git-trunk are not foreign
+echo "INFO: Scenario 2: git-trunk's own refs pass the check"
+setup_repo own
+git init -q --bare ../own-remote.git
+git remote add origin ../own-remote.git
+$GIT_TRUNK_CMD --store docs init
+$GIT_TRUNK_CMD --store docs commit --force
+$GIT_TRUNK_CMD --store docs push
+rm -rf .trunk
+git update-ref -d refs/trunk/docs
+$GIT_TRUNK_CMD --store docs checkout < /dev/null 2> checkout.log
+rm -rf .trunk
+$GIT_TRUNK_CMD --store blog init < /dev/null 2> init.log
+if grep -q "did not create" checkout.log init.log; then
+    echo "ERROR: a store created by git-trunk was reported as foreign"
+    cat checkout.log init.log
+    exit 1
+fi
+cd ..
+echo "VERIFY: stores created by git-trunk do not trigger the warning."
+
+echo "SUCCESS: test_foreign_refs completed."
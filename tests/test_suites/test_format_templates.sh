@@ -0,0 +1,75 @@
+#!/bin/bash
+# test_format_templates.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+expect_output() {
+    local description="$1" expected="$2" actual="$3"
+    if [ "$actual" != "$expected" ]; then
+        echo "ERROR: $description"
+        echo "Expected:"; printf '%s\n' "$expected" | cat -A
+        echo "Got:"; printf '%s\n' "$actual" | cat -A
+        exit 1
+    fi
+}
+
+echo "INFO: Starting test_format_templates in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote.git
+mkdir repo
+cd repo
+git init -q -b main
+git config user.email "test@example.com"
+git config user.name "Test User"
+git commit -q --allow-empty -m "Initial commit"
+git remote add origin ../remote.git
+
+$GIT_TRUNK_CMD --store docs init
+echo "# Docs" > .trunk/docs/readme.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Add docs"
+$GIT_TRUNK_CMD --store docs push
+$GIT_TRUNK_CMD --store notes init
+DOCS_HASH=$(git rev-parse refs/trunk/docs)
+
+# Scenario 1: status
+echo "INFO: Scenario 1: status --format"
+expect_output "status --all --format printed unexpected lines" \
+    "$(printf 'docs\t%s\t0|same\nnotes\t\t|missing' "$DOCS_HASH")" \
+    "$($GIT_TRUNK_CMD status --all --format '{store}\t{remote_hash}\t{ahead}|{remote_state}')"
+echo "VERIFY: status --format fills in the fields, with unknown values left empty."
+
+# Scenario 2: log
+echo "INFO: Scenario 2: log --format"
+expect_output "log --format printed unexpected lines" \
+    "docs $(git rev-parse --short refs/trunk/docs) {Add docs}" \
+    "$($GIT_TRUNK_CMD --store docs log -n 1 --format '{store} {short_hash} {{{subject}}}')"
+echo "VERIFY: log --format prints one line per commit, with {{ and }} as literal braces."
+
+# Scenario 3: info --short
+echo "INFO: Scenario 3: info --short --format"
+expect_output "info --short --format printed unexpected lines" \
+    "docs:$DOCS_HASH:$DOCS_HASH:yes" \
+    "$($GIT_TRUNK_CMD --store docs info --short --format '{store}:{local_hash}:{remote_hash}:{checkout}')"
+echo "VERIFY: info --short --format prints only the templated line."
+
+# Scenario 4: invalid templates are usage errors
+echo "INFO: Scenario 4: invalid templates"
+for template in "{nope}" "{store" "store}"; do
+    set +e
+    $GIT_TRUNK_CMD status --format "$template" 2> err.log
+    status=$?
+    set -e
+    if [ "$status" != "2" ] || ! grep -q "Invalid --format" err.log; then
+        echo "ERROR: template '$template' was not rejected with exit code 2 (got $status)"
+        cat err.log
+        exit 1
+    fi
+done
+echo "VERIFY: unknown fields and unbalanced braces exit with code 2."
+
+echo "SUCCESS: test_format_templates completed."
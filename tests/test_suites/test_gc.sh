@@ -0,0 +1,64 @@
+#!/bin/bash
+# test_gc.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_gc in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+$GIT_TRUNK_CMD --store docs commit --force 2> /dev/null
+$GIT_TRUNK_CMD --store bulky init 2> /dev/null
+echo "bulky $RANDOM $RANDOM" > .trunk/bulky/data.txt
+bulky_blob=$(git hash-object .trunk/bulky/data.txt)
+$GIT_TRUNK_CMD --store bulky commit --force 2> /dev/null
+$GIT_TRUNK_CMD --store bulky delete --local-only --no-backup 2> /dev/null
+(
+    cd .trunk/docs
+    echo "rewritten $RANDOM $RANDOM" > rewritten.md
+    git hash-object rewritten.md > ../../rewritten_blob
+    git add rewritten.md && git commit -q -m "Commit that is reset away"
+    git reset -q --hard HEAD~1
+    echo "stashed" >> readme.md
+    git stash -q
+)
+
+# Scenario 55.1: trashed stores and recent reflog entries keep their objects
+echo "INFO: Scenario 1: gc"
+$GIT_TRUNK_CMD gc 2> /dev/null
+if ! git cat-file -e "$bulky_blob" 2> /dev/null || ! git -C .trunk/docs cat-file -e "$(cat rewritten_blob)" 2> /dev/null; then
+    echo "ERROR: gc dropped the objects of a store in the trash or of a commit reset away moments ago"
+    exit 1
+fi
+echo "VERIFY: gc keeps the objects of stores in the trash and, without --prune, of recent reflog entries."
+
+# Scenario 55.2: --prune now drops what purged stores and rewritten commits left behind
+echo "INFO: Scenario 2: gc --prune now"
+$GIT_TRUNK_CMD trash purge --expire now --local 2> /dev/null
+$GIT_TRUNK_CMD gc --prune now 2> gc.log
+if git cat-file -e "$bulky_blob" 2> /dev/null || git -C .trunk/docs cat-file -e "$(cat rewritten_blob)" 2> /dev/null \
+    || [ "$(git -C .trunk/docs stash list | wc -l)" != "1" ] || ! grep -q "Reclaimed" gc.log || ! git cat-file -e refs/trunk/docs; then
+    echo "ERROR: gc --prune now kept the objects of a purged store or a reset commit, or dropped the stash:"; cat gc.log
+    exit 1
+fi
+echo "VERIFY: gc --prune now drops objects only purged stores and rewritten store commits held, in the main repository and store checkouts, and keeps stashes and live stores."
+
+# Scenario 55.3: outside a repository
+echo "INFO: Scenario 3: gc outside a repository"
+mkdir -p "$TEST_DIR/elsewhere"
+set +e
+(cd "$TEST_DIR/elsewhere" && GIT_CEILING_DIRECTORIES="$TEST_DIR" $GIT_TRUNK_CMD gc 2> /dev/null)
+outside=$?
+set -e
+if [ "$outside" != "5" ]; then
+    echo "ERROR: gc outside a repository exited with $outside (expected 5)"
+    exit 1
+fi
+echo "VERIFY: gc outside a git repository exits with 5."
+
+echo "SUCCESS: test_gc completed."
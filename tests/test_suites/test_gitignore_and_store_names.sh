@@ -0,0 +1,221 @@
+#!/bin/bash
+# test_gitignore_and_store_names.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+# Prompts (stegano, delete) are answered yes
+export GIT_TRUNK_NONINTERACTIVE=1
+
+setup_repo() {
+    local name="$1"
+    rm -rf "$name"
+    mkdir "$name"
+    cd "$name"
+    git init -q -b main
+    git config user.email "test@example.com"
+    git config user.name "Test User"
+    touch initial_file.txt
+    git add initial_file.txt
+    git commit -q -m "Initial commit"
+}
+
+# Number of lines in .gitignore that ignore the root .trunk directory
+trunk_lines() {
+    grep -c -E '^/?\.trunk/? *'$'\r''?$' .gitignore || true
+}
+
+expect_ignored() {
+    mkdir -p .trunk
+    touch .trunk/probe
+    if ! git check-ignore -q .trunk/probe; then
+        echo "ERROR: .trunk is not ignored by:"; cat -A .gitignore
+        exit 1
+    fi
+    rm .trunk/probe
+}
+
+echo "INFO: Starting test_gitignore_and_store_names in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+
+# Scenario 1: every spelling of the entry is recognized, so init adds no duplicate
+echo "INFO: Scenario 1: existing .trunk entries are recognized"
+for entry in ".trunk" "/.trunk" ".trunk/" "/.trunk/" ".trunk  " $'.trunk\r'; do
+    setup_repo recognized
+    printf 'target\n%s\n' "$entry" > .gitignore
+    $GIT_TRUNK_CMD init
+    if [ "$(trunk_lines)" != "1" ]; then
+        echo "ERROR: init duplicated the entry $(printf '%q' "$entry"):"; cat -A .gitignore
+        exit 1
+    fi
+    cd ..
+done
+echo "VERIFY: init keeps a single entry for every spelling of .trunk."
+
+# Scenario 2: lines that only look like the entry do not count
+echo "INFO: Scenario 2: comments, negations and leading whitespace are not the entry"
+for entry in "# .trunk" "!.trunk" " .trunk" $'\t.trunk' ".trunk2" "docs/.trunk"; do
+    setup_repo lookalike
+    printf '%s\n' "$entry" > .gitignore
+    $GIT_TRUNK_CMD init
+    expect_ignored
+    if ! grep -qxF -- "$entry" .gitignore; then
+        echo "ERROR: init dropped the line $(printf '%q' "$entry")"
+        exit 1
+    fi
+    cd ..
+done
+echo "VERIFY: init adds .trunk next to lines that do not ignore it."
+
+# A negation after the entry re-includes .trunk, so init must add it again
+setup_repo negated
+printf '.trunk\n!.trunk\n' > .gitignore
+$GIT_TRUNK_CMD init
+expect_ignored
+cd ..
+echo "VERIFY: init re-adds .trunk after a negating !.trunk line."
+
+# Scenario 3: CRLF files and files without a final newline keep their shape
+echo "INFO: Scenario 3: line endings are preserved"
+setup_repo line_endings
+printf 'target\r\nnode_modules\r\n' > .gitignore
+$GIT_TRUNK_CMD init
+if [ "$(cat .gitignore)" != $'target\r\nnode_modules\r\n.trunk\r' ]; then
+    echo "ERROR: init did not append .trunk with CRLF:"; cat -A .gitignore
+    exit 1
+fi
+$GIT_TRUNK_CMD stegano
+if [ "$(cat -A .gitignore)" != "$(printf 'target^M$\nnode_modules^M$')" ]; then
+    echo "ERROR: stegano changed the remaining CRLF lines:"; cat -A .gitignore
+    exit 1
+fi
+printf 'target\n/.trunk/\nlast-line' > .gitignore
+$GIT_TRUNK_CMD init
+$GIT_TRUNK_CMD stegano
+if [ "$(cat -A .gitignore)" != "$(printf 'target$\nlast-line')" ]; then
+    echo "ERROR: stegano did not keep the file without a final newline:"; cat -A .gitignore
+    exit 1
+fi
+cd ..
+echo "VERIFY: CRLF endings and a missing final newline survive init and stegano."
+
+# Scenario 4: generated .gitignore files. After init .trunk is ignored, with the
+# entry added only when missing or negated; after stegano every other line is
+# still there, byte for byte, in order.
+echo "INFO: Scenario 4: generated .gitignore contents"
+POOL=("target" "*.log" "# .trunk" "!.trunk" " .trunk" ".trunk" "/.trunk/" ".trunk/" ".trunk  " "build/" "" "docs/.trunk" ".trunk2")
+RANDOM=4513
+for case_number in $(seq 1 25); do
+    setup_repo "generated_$case_number"
+    ending=$'\n'
+    if [ $((RANDOM % 3)) -eq 0 ]; then ending=$'\r\n'; fi
+    : > .gitignore
+    : > expected
+    for _ in $(seq 1 $((RANDOM % 6))); do
+        line="${POOL[$((RANDOM % ${#POOL[@]}))]}"
+        printf '%s%s' "$line" "$ending" >> .gitignore
+        case "$line" in
+            ".trunk"|"/.trunk"|".trunk/"|"/.trunk/"|".trunk  ") ;;
+            *) printf '%s%s' "$line" "$ending" >> expected ;;
+        esac
+    done
+    had_entry=$(trunk_lines)
+    $GIT_TRUNK_CMD init
+    expect_ignored
+    if [ "$had_entry" = "0" ] && [ "$(trunk_lines)" != "1" ]; then
+        echo "ERROR: case $case_number: init did not add exactly one entry:"; cat -A .gitignore
+        exit 1
+    fi
+    if [ "$had_entry" != "0" ] && ! grep -q '^!' .gitignore && [ "$(trunk_lines)" != "$had_entry" ]; then
+        echo "ERROR: case $case_number: init added an entry although one existed:"; cat -A .gitignore
+        exit 1
+    fi
+    $GIT_TRUNK_CMD stegano
+    if ! cmp -s .gitignore expected; then
+        echo "ERROR: case $case_number: stegano left unexpected content. Got:"; cat -A .gitignore
+        echo "Expected:"; cat -A expected
+        exit 1
+    fi
+    cd ..
+done
+echo "VERIFY: 25 generated .gitignore files round-trip through init and stegano."
+
+# Scenario 5: store names must be one ref component and one directory name
+echo "INFO: Scenario 5: store names"
+setup_repo store_names
+for name in "../escape" "a/b" "a b" ".hidden" "a..b" "trailing." "x.lock" "@" "a@{1}" "a:b" "a[b" "a~1" ""; do
+    set +e
+    $GIT_TRUNK_CMD init --store "$name" 2> err.log
+    status=$?
+    set -e
+    if [ "$status" != "2" ] || ! grep -q "Invalid store name" err.log; then
+        echo "ERROR: store name $(printf '%q' "$name") was not rejected with exit code 2 (got $status)"
+        cat err.log
+        exit 1
+    fi
+done
+if [ -e ../escape ] || [ -e .trunk ] || [ -n "$(git for-each-ref refs/trunk/)" ]; then
+    echo "ERROR: a rejected store name left files or refs behind"
+    exit 1
+fi
+for name in "blog" "team-notes" "v1.2" "under_score" "UPPER"; do
+    $GIT_TRUNK_CMD init --store "$name"
+    $GIT_TRUNK_CMD commit --store "$name" --force
+    git rev-parse -q --verify "refs/trunk/$name" > /dev/null
+done
+printf '[trunk]\nstore = "a/b"\n' > "$(git rev-parse --git-common-dir)/trunk.toml"
+set +e
+$GIT_TRUNK_CMD config validate > validate.log 2>&1
+status=$?
+set -e
+if [ "$status" = "0" ] || ! grep -q "not a valid store name" validate.log; then
+    echo "ERROR: config validate accepted [trunk] store = \"a/b\""
+    cat validate.log
+    exit 1
+fi
+$GIT_TRUNK_CMD config unset trunk.store
+cd ..
+echo "VERIFY: invalid store names are rejected before anything is written; valid ones work end to end."
+
+# Scenario 6: several stores in one invocation, repeated or as a glob
+echo "INFO: Scenario 6: repeated --store values and globs"
+setup_repo multiple_stores
+$GIT_TRUNK_CMD -s docs-a -s docs-b -s blog init
+echo "a" > .trunk/docs-a/a.md
+echo "b" > .trunk/docs-b/b.md
+echo "blog" > .trunk/blog/post.md
+$GIT_TRUNK_CMD -s 'docs-*' commit --force -m "Docs"
+if ! git rev-parse -q --verify refs/trunk/docs-a > /dev/null || ! git rev-parse -q --verify refs/trunk/docs-b > /dev/null; then
+    echo "ERROR: the glob did not commit both docs stores"
+    exit 1
+fi
+if git rev-parse -q --verify refs/trunk/blog > /dev/null; then
+    echo "ERROR: the glob 'docs-*' committed the blog store"
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD -s 'missing-*' status 2> err.log
+status=$?
+set -e
+if [ "$status" != "3" ] || ! grep -q "No local store matches 'missing-\*'" err.log; then
+    echo "ERROR: a glob matching no store did not exit with code 3 (got $status)"
+    cat err.log
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD -s blog -s docs-a push 2> err.log
+status=$?
+set -e
+if [ "$status" != "1" ] || ! grep -q "2 of 2 store(s) failed" err.log; then
+    echo "ERROR: failures of several stores were not summed up (exit code $status)"
+    cat err.log
+    exit 1
+fi
+cd ..
+echo "VERIFY: repeated and glob --store values run once per store, and failures are summed up."
+
+echo "SUCCESS: test_gitignore_and_store_names completed."
@@ -0,0 +1,57 @@
+#!/bin/bash
+# test_grep.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_grep in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store handbook init 2> /dev/null
+$GIT_TRUNK_CMD --store handbook commit --force -m "Start" 2> /dev/null
+echo "Budget: 100 EUR" | $GIT_TRUNK_CMD --store handbook put - policies/budget.md 2> /dev/null
+echo "Budget: 120 EUR" | $GIT_TRUNK_CMD --store handbook put - policies/budget.md 2> /dev/null
+$GIT_TRUNK_CMD --store notes init 2> /dev/null
+echo "The budget is fine." > .trunk/notes/review.md
+$GIT_TRUNK_CMD --store notes commit --force -m "Review" 2> /dev/null
+echo "Budget: uncommitted" > .trunk/notes/draft.md
+
+# Scenario 48.1: grep searches the committed files of the ref
+echo "INFO: Scenario 1: grep"
+$GIT_TRUNK_CMD grep Budget handbook:policies > grep.out 2> /dev/null
+$GIT_TRUNK_CMD --store handbook grep --history "100 EUR" > history.out 2> /dev/null
+if [ "$(cat grep.out)" != "handbook:policies/budget.md:1:Budget: 120 EUR" ] \
+    || [ "$(cat history.out)" != "handbook@$(git rev-parse --short refs/trunk/handbook~1):policies/budget.md:1:Budget: 100 EUR" ]; then
+    echo "ERROR: grep did not find the committed lines:"; cat grep.out history.out
+    exit 1
+fi
+echo "VERIFY: grep prints store:path:line:text for refs/trunk/<store>, and --history finds replaced lines with the newest commit holding them."
+
+# Scenario 48.2: every store, file names only
+echo "INFO: Scenario 2: grep --all -l -i"
+$GIT_TRUNK_CMD grep --all -l -i BUDGET > files.out 2> /dev/null
+if [ "$(tr '\n' ' ' < files.out)" != "handbook:policies/budget.md notes:review.md " ]; then
+    echo "ERROR: grep --all -l -i did not list the matching files of every store:"; cat files.out
+    exit 1
+fi
+echo "VERIFY: --all searches every store, -l lists files and -i ignores case; uncommitted files are not searched."
+
+# Scenario 48.3: no match and a missing store
+echo "INFO: Scenario 3: grep errors"
+set +e
+$GIT_TRUNK_CMD --store handbook grep "Nothing like this" 2> /dev/null
+none=$?
+$GIT_TRUNK_CMD --store missing grep Budget 2> /dev/null
+missing=$?
+set -e
+if [ "$none" != "1" ] || [ "$missing" != "3" ]; then
+    echo "ERROR: grep without matches exited with $none (expected 1), in a missing store with $missing (expected 3)"
+    exit 1
+fi
+echo "VERIFY: grep exits with 1 without matches, like git grep, and with 3 for a store without a ref."
+
+echo "SUCCESS: test_grep completed."
@@ -0,0 +1,146 @@
+#!/bin/bash
+# test_history.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Commits `text` as <file> to store policies
+commit_file() {
+    local file="$1" text="$2"
+    mkdir -p "$(dirname ".trunk/policies/$file")"
+    echo "$text" > ".trunk/policies/$file"
+    $GIT_TRUNK_CMD --store policies commit --force -m "Update $file" 2> /dev/null
+}
+
+echo "INFO: Starting test_history in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q -b main repo
+cd repo
+git commit -q --allow-empty -m "Initial commit"
+$GIT_TRUNK_CMD --store policies init 2> /dev/null
+commit_file travel.md "Economy class only."
+commit_file other.md "Unrelated"
+commit_file travel.md "Economy class, business over six hours."
+git -C .trunk/policies mv travel.md travel-policy.md
+git -C .trunk/policies commit -q -m "Rename the travel policy"
+$GIT_TRUNK_CMD --store policies commit --force 2> /dev/null
+
+# Scenario 39.1: every revision of one file, across a rename
+echo "INFO: Scenario 1: history"
+$GIT_TRUNK_CMD --store policies history --no-table .trunk/policies/travel-policy.md > history.out 2> /dev/null
+if [ "$(grep -c "^Revision:" history.out)" != "3" ] || ! grep -q "renamed from travel.md" history.out || ! grep -q "Message: Update travel.md" history.out || grep -q "other.md" history.out; then
+    echo "ERROR: history did not list the three revisions of travel-policy.md:"; cat history.out
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD --store policies history missing.md 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "3" ]; then
+    echo "ERROR: history of a file that never existed exited with $status, expected 3"
+    exit 1
+fi
+echo "VERIFY: history follows renames and fails with exit code 3 for unknown files."
+
+# Scenario 39.2: --render writes each version
+echo "INFO: Scenario 2: history --render"
+$GIT_TRUNK_CMD --store policies history travel-policy.md --render --no-open --output versions 2> /dev/null
+if [ "$(ls versions | wc -l)" != "4" ] || [ "$(cat versions/01-*-travel.md)" != "Economy class only." ] || ! grep -q "travel-policy.md" versions/index.html || ! grep -q 'href="01-' versions/index.html; then
+    echo "ERROR: --render did not write the three versions and an index:"; ls versions
+    exit 1
+fi
+echo "VERIFY: --render writes one file per version, oldest first, and an index.html."
+
+# Scenario 39.3: --diffs writes a word diff per pair of versions
+echo "INFO: Scenario 3: history --render --diffs"
+$GIT_TRUNK_CMD --store policies history travel-policy.md --render --diffs --no-open --output diffs 2> /dev/null
+if [ "$(ls diffs | wc -l)" != "3" ] || ! grep -q "<ins>, business over six hours</ins>" diffs/01-*.html; then
+    echo "ERROR: --diffs did not write two word diffs and an index:"; ls diffs
+    exit 1
+fi
+echo "VERIFY: --diffs writes side-by-side word diffs between consecutive versions."
+
+# Scenario 39.4: the store comes from the path
+echo "INFO: Scenario 4: store:path and paths inside .trunk"
+$GIT_TRUNK_CMD --store handbook init 2> /dev/null
+echo "Welcome" > .trunk/handbook/welcome.md
+$GIT_TRUNK_CMD --store handbook commit --force -m "Add welcome" 2> /dev/null
+$GIT_TRUNK_CMD history --no-table handbook:welcome.md > prefixed.out 2> /dev/null
+(cd .trunk/policies && $GIT_TRUNK_CMD history --no-table ./travel-policy.md > ../../inside.out 2> /dev/null)
+if ! grep -q "Message: Add welcome" prefixed.out || [ "$(grep -c "^Revision:" inside.out)" != "3" ]; then
+    echo "ERROR: history did not pick the store from handbook:welcome.md or from the current directory"
+    cat prefixed.out inside.out
+    exit 1
+fi
+echo "VERIFY: store:path and paths relative to .trunk/<store> need no --store."
+
+# Scenario 39.5: blame by line and by section
+echo "INFO: Scenario 5: blame"
+GIT_AUTHOR_NAME="Ana Writer" commit_file handbook.md "$(printf '# Handbook\nIntro\n## Travel\nTrains\n## Leave\nThirty days')"
+GIT_AUTHOR_NAME="Bo Editor" commit_file handbook.md "$(printf '# Handbook\nIntro\n## Travel\nTrains\n## Leave\nForty days\n```\n# not a heading\n```')"
+$GIT_TRUNK_CMD --store policies blame handbook.md > blame.out 2> /dev/null
+$GIT_TRUNK_CMD blame policies:handbook.md --sections --no-table > sections.out 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store policies blame missing.md 2> /dev/null
+status=$?
+set -e
+if [ "$(wc -l < blame.out)" != "9" ] || ! grep -q "(Ana Writer .* 4) Trains$" blame.out || ! grep -q "(Bo Editor  .* 6) Forty days$" blame.out \
+    || [ "$(grep -c "^Section:" sections.out)" != "3" ] || [ "$(grep "^Author:" sections.out | tr '\n' '|')" != "Author: Ana Writer|Author: Ana Writer|Author: Bo Editor|" ] \
+    || ! grep -q "^Lines: 5-9$" sections.out || [ "$status" != "3" ]; then
+    echo "ERROR: blame did not attribute the lines or sections (missing file: $status):"; cat blame.out sections.out
+    exit 1
+fi
+echo "VERIFY: blame attributes each line, --sections each Markdown section ignoring code blocks, and a missing file exits with code 3."
+
+# Scenario 39.6: restore a file, then the whole store, from an earlier commit
+echo "INFO: Scenario 6: restore"
+tip=$(git rev-parse refs/trunk/policies)
+$GIT_TRUNK_CMD restore policies:handbook.md --to HEAD~1 2> /dev/null
+if ! grep -q "^Thirty days$" .trunk/policies/handbook.md || [ "$(git -C .trunk/policies diff --cached --name-only)" != "handbook.md" ] \
+    || [ "$(git rev-parse refs/trunk/policies)" != "$tip" ]; then
+    echo "ERROR: restore did not stage the earlier handbook.md:"; cat .trunk/policies/handbook.md
+    exit 1
+fi
+echo "Draft" >> .trunk/policies/handbook.md
+set +e
+$GIT_TRUNK_CMD --store policies restore --all --to "$(git rev-list --max-parents=0 refs/trunk/policies)" 2> /dev/null
+changed=$?
+$GIT_TRUNK_CMD --store policies restore missing.md --to HEAD 2> /dev/null
+missing=$?
+set -e
+$GIT_TRUNK_CMD --store policies restore --all --to "$(git rev-list --max-parents=0 refs/trunk/policies)" --force 2> /dev/null
+$GIT_TRUNK_CMD --store policies commit --force -m "Back to the start" 2> /dev/null
+if [ "$changed" != "4" ] || [ "$missing" != "3" ] || [ "$(git ls-tree --name-only refs/trunk/policies | tr '\n' ' ')" != "ABOUT.md readme.md " ]; then
+    echo "ERROR: restore --all did not bring back the first commit, or exited with $changed/$missing for uncommitted changes/a missing file:"
+    git ls-tree --name-only refs/trunk/policies
+    exit 1
+fi
+echo "VERIFY: restore stages earlier content for review without committing, --all removes files added since, uncommitted changes need --force (4) and unknown paths exit with 3."
+
+# Scenario 39.7: digest of recent activity, with a quiet store and links
+echo "INFO: Scenario 7: digest"
+$GIT_TRUNK_CMD --store archive init 2> /dev/null
+$GIT_TRUNK_CMD --store archive commit --force -m "Old notes" 2> /dev/null
+git update-ref refs/trunk/archive "$(GIT_COMMITTER_DATE="2020-01-01T00:00:00" git commit-tree "refs/trunk/archive^{tree}" -m "Old notes")"
+printf -- '---\ntitle: Remote work\n---\nSee https://example.com/remote.\n' > .trunk/policies/remote.md
+$GIT_TRUNK_CMD --store policies commit --force -m "Add remote work" 2> /dev/null
+$GIT_TRUNK_CMD digest --since 1w > digest.md 2> /dev/null
+if ! grep -q "^## Store \`policies\`$" digest.md || ! grep -q "^- \*\*Remote work\*\* \`remote.md\` by " digest.md \
+    || ! grep -q "^- <https://example.com/remote> in \`remote.md\`$" digest.md || ! grep -q "^No activity in \`archive\`.$" digest.md; then
+    echo "ERROR: digest did not summarize the week:"; cat digest.md
+    exit 1
+fi
+$GIT_TRUNK_CMD digest --stores 'pol*' --format text --commit-to policies > digest.txt 2> /dev/null
+if grep -q "archive" digest.txt || [ "$(git show "refs/trunk/policies:digests/$(date +%Y-%m-%d).txt")" != "$(cat digest.txt)" ]; then
+    echo "ERROR: digest --commit-to did not commit the text digest of the selected stores:"; cat digest.txt
+    exit 1
+fi
+echo "VERIFY: digest lists each store's changed documents by title with authors and new links, names quiet stores, and --commit-to keeps it in digests/."
+
+echo "SUCCESS: test_history completed."
@@ -0,0 +1,105 @@
+#!/bin/bash
+# test_hook_run.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_hook_run in $TEST_DIR"
+setup_repo --remote
+git push -q origin main
+for store in docs notes; do
+    $GIT_TRUNK_CMD --store "$store" init 2> /dev/null
+    $GIT_TRUNK_CMD --store "$store" commit --force -m "Start" 2> /dev/null
+done
+CONFIG="$(git rev-parse --git-common-dir)/trunk.toml"
+main=$(git rev-parse main)
+zero=0000000000000000000000000000000000000000
+
+# Scenario 11.1: exit codes
+echo "INFO: Scenario 1: exit codes"
+set +e
+$GIT_TRUNK_CMD hook-run post-rewrite 2> unknown.err
+unknown=$?
+$GIT_TRUNK_CMD hook-run post-commit 2> /dev/null
+unregistered=$?
+mkdir -p "$TEST_DIR/elsewhere"
+(cd "$TEST_DIR/elsewhere" && GIT_CEILING_DIRECTORIES="$TEST_DIR" $GIT_TRUNK_CMD hook-run post-commit 2> outside.err)
+outside=$?
+set -e
+if [ "$unknown" != "2" ] || ! grep -q "invalid value 'post-rewrite'" unknown.err || [ "$unregistered" != "0" ] \
+    || [ "$outside" != "0" ] || ! grep -q "could not find the repository root" "$TEST_DIR/elsewhere/outside.err"; then
+    echo "ERROR: hook-run exited with $unknown, $unregistered and $outside (expected 2, 0 and 0):"; cat unknown.err "$TEST_DIR/elsewhere/outside.err"
+    exit 1
+fi
+echo "VERIFY: hook-run refuses an unknown event with 2, and exits with 0 when no store is registered or outside a repository."
+
+# Scenario 11.2: post-commit commits every registered store
+echo "INFO: Scenario 2: post-commit"
+printf '[store.docs]\nhooks = ["post-commit", "pre-push"]\n\n[store.notes]\nhooks = ["pre-push"]\n' > "$CONFIG"
+docs_before=$(git rev-parse refs/trunk/docs)
+notes_before=$(git rev-parse refs/trunk/notes)
+echo "Hooked." > .trunk/docs/hooked.md
+echo "Not hooked." > .trunk/notes/unhooked.md
+$GIT_TRUNK_CMD hook-run post-commit 2> post-commit.err
+if [ "$(git rev-parse refs/trunk/docs)" = "$docs_before" ] || ! git cat-file -e refs/trunk/docs:hooked.md \
+    || [ "$(git rev-parse refs/trunk/notes)" != "$notes_before" ]; then
+    echo "ERROR: hook-run post-commit did not commit exactly the stores registered for it:"; cat post-commit.err
+    exit 1
+fi
+rm .trunk/notes/unhooked.md
+echo "VERIFY: hook-run post-commit commits the stores registered for post-commit and leaves the others alone."
+
+# Scenario 11.3: pre-push pushes the stores when the main branch is pushed
+echo "INFO: Scenario 3: pre-push"
+echo "refs/heads/topic $main refs/heads/topic $zero" | $GIT_TRUNK_CMD hook-run pre-push origin ../remote.git 2> topic.err
+if [ -n "$(git ls-remote ../remote.git 'refs/trunk/*')" ]; then
+    echo "ERROR: pushing a branch other than main pushed stores:"; git ls-remote ../remote.git; cat topic.err
+    exit 1
+fi
+echo "refs/heads/main $main refs/heads/main $zero" | $GIT_TRUNK_CMD hook-run pre-push origin ../remote.git 2> main.err
+if [ "$(git ls-remote ../remote.git refs/trunk/docs | cut -f1)" != "$(git rev-parse refs/trunk/docs)" ] \
+    || [ "$(git ls-remote ../remote.git refs/trunk/notes | cut -f1)" != "$(git rev-parse refs/trunk/notes)" ]; then
+    echo "ERROR: pushing main did not push both stores registered for pre-push:"; git ls-remote ../remote.git; cat main.err
+    exit 1
+fi
+echo "VERIFY: hook-run pre-push pushes the registered stores only when git pushes the main branch."
+
+# Scenario 11.4: a failing store does not stop the others or the hook
+echo "INFO: Scenario 4: a failing store"
+printf '[store.broken]\nhooks = ["post-commit"]\n\n[store.docs]\nhooks = ["post-commit"]\n' > "$CONFIG"
+docs_before=$(git rev-parse refs/trunk/docs)
+echo "Again." > .trunk/docs/again.md
+set +e
+$GIT_TRUNK_CMD hook-run post-commit 2> failing.err
+failing=$?
+set -e
+if [ "$failing" != "0" ] || ! grep -q "Failed to commit store 'broken'" failing.err || [ "$(git rev-parse refs/trunk/docs)" = "$docs_before" ]; then
+    echo "ERROR: hook-run exited with $failing (expected 0) or a failing store stopped the next one:"; cat failing.err
+    exit 1
+fi
+echo "VERIFY: A store whose action fails gets a warning, the next store still runs and hook-run exits with 0."
+
+# Scenario 11.5: a held lock skips the run
+echo "INFO: Scenario 5: trunk-hook.lock"
+docs_before=$(git rev-parse refs/trunk/docs)
+echo "Locked." > .trunk/docs/locked.md
+touch "$(git rev-parse --git-common-dir)/trunk-hook.lock"
+$GIT_TRUNK_CMD hook-run post-commit 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" != "$docs_before" ]; then
+    echo "ERROR: hook-run ran while another invocation held trunk-hook.lock"
+    exit 1
+fi
+rm "$(git rev-parse --git-common-dir)/trunk-hook.lock"
+$GIT_TRUNK_CMD hook-run post-commit 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" = "$docs_before" ] || [ -e "$(git rev-parse --git-common-dir)/trunk-hook.lock" ]; then
+    echo "ERROR: hook-run did not run once the lock was released, or left its lock behind"
+    exit 1
+fi
+echo "VERIFY: hook-run skips while trunk-hook.lock is held, and runs and removes its own lock otherwise."
+
+echo "SUCCESS: test_hook_run completed."
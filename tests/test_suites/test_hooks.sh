@@ -0,0 +1,180 @@
+#!/bin/bash
+# test_hooks.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# The installed hooks call `git trunk`, so put the binary under test on PATH
+mkdir -p "$TEST_DIR/bin"
+ln -sf "$(command -v "$GIT_TRUNK_CMD")" "$TEST_DIR/bin/git-trunk"
+export PATH="$TEST_DIR/bin:$PATH"
+
+echo "INFO: Starting test_hooks in $TEST_DIR"
+setup_repo
+CONFIG="$(git rev-parse --git-common-dir)/trunk.toml"
+
+# Scenario 7.1 and 7.3: install both hooks for two stores
+echo "INFO: Scenario 1: hooks install"
+for store in a b; do
+    $GIT_TRUNK_CMD --store "$store" init
+    $GIT_TRUNK_CMD --store "$store" hooks install
+done
+for hook in post-commit pre-push; do
+    if [ ! -x ".git/hooks/$hook" ] || ! grep -q "git trunk hook-run $hook" ".git/hooks/$hook"; then
+        echo "ERROR: $hook dispatcher hook was not installed"
+        exit 1
+    fi
+done
+if [ "$(grep -c 'hooks = \["post-commit", "pre-push"\]' "$CONFIG")" != "2" ]; then
+    echo "ERROR: hooks were not registered for both stores:"; cat "$CONFIG"
+    exit 1
+fi
+echo "VERIFY: hooks install writes both dispatchers and registers each store."
+
+# Scenario 7.9: status
+echo "INFO: Scenario 2: hooks status"
+$GIT_TRUNK_CMD hooks status --no-table > status.txt
+if [ "$(grep -c 'Installed (git-trunk dispatcher)' status.txt)" != "2" ] || ! grep -q "Store: b" status.txt; then
+    echo "ERROR: hooks status did not list the hooks and stores:"; cat status.txt
+    exit 1
+fi
+echo "VERIFY: hooks status lists the installed hooks and the registered stores."
+
+# Scenario 7.7: uninstall one store at a time
+echo "INFO: Scenario 3: hooks uninstall"
+$GIT_TRUNK_CMD --store a hooks uninstall
+if [ ! -e .git/hooks/post-commit ] || [ ! -e .git/hooks/pre-push ]; then
+    echo "ERROR: uninstalling store 'a' removed hooks store 'b' still uses"
+    exit 1
+fi
+$GIT_TRUNK_CMD --store b hooks uninstall
+if [ -e .git/hooks/post-commit ] || [ -e .git/hooks/pre-push ]; then
+    echo "ERROR: hooks were left behind after the last store was uninstalled"
+    exit 1
+fi
+if grep -q "hooks =" "$CONFIG"; then
+    echo "ERROR: hook registrations were left in the config:"; cat "$CONFIG"
+    exit 1
+fi
+echo "VERIFY: hooks are removed once no store is registered for them."
+
+# Scenario 7.10: one set of hooks for every store in .trunk
+echo "INFO: Scenario 4: hooks install --all"
+$GIT_TRUNK_CMD hooks install --all
+$GIT_TRUNK_CMD --store c init
+if [ "$(grep -c 'hooks = ' "$CONFIG")" != "1" ] || ! grep -q "every store in .trunk" <($GIT_TRUNK_CMD hooks status --no-table); then
+    echo "ERROR: hooks install --all did not register under [trunk]:"; cat "$CONFIG"
+    exit 1
+fi
+for store in a b c; do
+    echo "$store" > ".trunk/$store/all.txt"
+done
+git commit -q --allow-empty -m "Commit every store"
+for store in a b c; do
+    if ! git cat-file -e "refs/trunk/$store:all.txt" 2> /dev/null; then
+        echo "ERROR: the post-commit hook did not commit store '$store'"
+        exit 1
+    fi
+done
+$GIT_TRUNK_CMD --store a hooks uninstall
+if [ ! -e .git/hooks/post-commit ]; then
+    echo "ERROR: uninstalling one store removed the hooks registered for every store"
+    exit 1
+fi
+$GIT_TRUNK_CMD hooks uninstall --all
+if [ -e .git/hooks/post-commit ] || grep -q "hooks =" "$CONFIG"; then
+    echo "ERROR: uninstall --all left the hooks for every store behind"
+    exit 1
+fi
+echo "VERIFY: hooks install --all covers every checked-out store, including ones added later."
+
+# Scenario 7.5: existing hooks are chained, not overwritten
+echo "INFO: Scenario 5: existing hooks run before git-trunk"
+git init -q --bare ../remote.git
+git remote add origin ../remote.git
+printf '#!/bin/sh\necho "post-commit ran" >> "%s/user-hooks.log"\n' "$PWD" > .git/hooks/post-commit
+printf '#!/bin/sh\nwhile read local_ref rest; do echo "pre-push saw $local_ref" >> "%s/user-hooks.log"; done\n' "$PWD" > .git/hooks/pre-push
+chmod +x .git/hooks/post-commit .git/hooks/pre-push
+$GIT_TRUNK_CMD --store a hooks install --force
+for hook in post-commit pre-push; do
+    if [ ! -x ".git/hooks/$hook.pre-git-trunk" ] || ! grep -q "git trunk hook-run $hook" ".git/hooks/$hook"; then
+        echo "ERROR: the existing $hook hook was not chained"
+        ls -l .git/hooks
+        exit 1
+    fi
+done
+git commit -q --allow-empty -m "Chained commit"
+git push -q origin main
+if ! grep -q "post-commit ran" user-hooks.log || ! grep -q "pre-push saw refs/heads/main" user-hooks.log; then
+    echo "ERROR: the chained hooks did not run, or pre-push got no refs on stdin:"; cat user-hooks.log
+    exit 1
+fi
+printf '#!/bin/sh\nexit 3\n' > .git/hooks/pre-push.pre-git-trunk
+if git push -q origin HEAD:refs/heads/other 2> /dev/null; then
+    echo "ERROR: a failing chained pre-push hook did not stop the push"
+    exit 1
+fi
+echo "VERIFY: existing hooks are kept, run first with the same input, and can still stop a push."
+
+# Scenario 7.8: uninstall restores chained hooks and leaves others alone
+echo "INFO: Scenario 6: uninstall --all restores chained hooks"
+printf '#!/bin/sh\necho custom\n' > .git/hooks/pre-push.pre-git-trunk
+$GIT_TRUNK_CMD hooks uninstall --all
+if ! grep -q "post-commit ran" .git/hooks/post-commit || ! grep -q "echo custom" .git/hooks/pre-push || ls .git/hooks | grep -q "pre-git-trunk"; then
+    echo "ERROR: uninstall --all did not put the chained hooks back"
+    ls -l .git/hooks
+    exit 1
+fi
+$GIT_TRUNK_CMD hooks uninstall --all
+if ! grep -q "echo custom" .git/hooks/pre-push; then
+    echo "ERROR: uninstall removed a hook git-trunk did not write"
+    exit 1
+fi
+echo "VERIFY: uninstall --all restores chained hooks and never removes hooks it did not write."
+
+# Scenario 7.11: post-checkout and post-merge hooks keep stores in sync
+echo "INFO: Scenario 7: hooks install --sync"
+cd "$TEST_DIR"
+git init -q --bare sync-remote.git
+git clone -q sync-remote.git alice 2> /dev/null
+cd alice
+git config user.email "alice@example.com"
+git config user.name "Alice"
+git checkout -q -b main
+$GIT_TRUNK_CMD --store notes init
+echo "v1" > .trunk/notes/plan.md
+$GIT_TRUNK_CMD --store notes commit --force
+$GIT_TRUNK_CMD --store notes push
+git add .gitignore
+git commit -q -m "Ignore .trunk"
+git push -q origin main
+cd ..
+git clone -q sync-remote.git bob
+cd bob
+git config user.email "bob@example.com"
+git config user.name "Bob"
+$GIT_TRUNK_CMD --store notes hooks install --sync
+if [ ! -x .git/hooks/post-merge ] || [ ! -x .git/hooks/post-checkout ]; then
+    echo "ERROR: hooks install --sync did not install post-merge and post-checkout"
+    exit 1
+fi
+git checkout -q -b feature
+if ! grep -q "v1" .trunk/notes/plan.md 2> /dev/null; then
+    echo "ERROR: switching branches did not check out store 'notes'"
+    exit 1
+fi
+git checkout -q main
+(cd ../alice && echo "v2" > .trunk/notes/plan.md && $GIT_TRUNK_CMD --store notes commit --force && $GIT_TRUNK_CMD --store notes push && git commit -q --allow-empty -m "Plan v2" && git push -q origin main) 2> /dev/null
+git pull -q --no-rebase origin main
+if ! grep -q "v2" .trunk/notes/plan.md; then
+    echo "ERROR: git pull did not update store 'notes':"; cat .trunk/notes/plan.md
+    exit 1
+fi
+echo "VERIFY: switching branches checks out the store and git pull pulls it."
+
+echo "SUCCESS: test_hooks completed."
@@ -0,0 +1,67 @@
+#!/bin/bash
+# test_import.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_import in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q -b main source
+cd source
+git config user.email "test@example.com"
+git config user.name "Test User"
+git commit -q --allow-empty -m "Initial commit"
+$GIT_TRUNK_CMD --store handbook init 2> /dev/null
+echo "Trains first." > .trunk/handbook/travel.md
+$GIT_TRUNK_CMD --store handbook commit --force -m "Travel" 2> /dev/null
+$GIT_TRUNK_CMD --store handbook export -o ../handbook.bundle 2> /dev/null
+$GIT_TRUNK_CMD --store handbook export -o ../handbook.zip 2> /dev/null
+cd "$TEST_DIR"
+mkdir -p notes/meetings
+echo "Monday" > notes/meetings/monday.md
+echo "Not a store" > plain.txt
+git init -q -b main repo
+cd repo
+git config user.email "test@example.com"
+git config user.name "Test User"
+git commit -q --allow-empty -m "Initial commit"
+
+# Scenario 51.1: a bundle keeps its history, archives and directories become a first commit
+echo "INFO: Scenario 1: import"
+$GIT_TRUNK_CMD import ../handbook.bundle 2> /dev/null
+$GIT_TRUNK_CMD import ../handbook.zip --as zipped 2> /dev/null
+$GIT_TRUNK_CMD import ../notes --no-checkout 2> /dev/null
+if [ "$(git rev-parse refs/trunk/handbook)" != "$(git -C ../source rev-parse refs/trunk/handbook)" ] || [ "$(cat .trunk/handbook/travel.md)" != "Trains first." ] \
+    || [ "$(git log --format=%s refs/trunk/zipped)" != "Initial commit for store 'zipped'" ] || ! diff -r -x .git .trunk/handbook .trunk/zipped > /dev/null \
+    || [ "$(git show refs/trunk/notes:meetings/monday.md)" != "Monday" ] || [ -e .trunk/notes ] || ! grep -qx ".trunk" .gitignore; then
+    echo "ERROR: import did not create the stores:"; git for-each-ref refs/trunk/
+    exit 1
+fi
+echo "VERIFY: import keeps a bundle's history, commits a zip or a directory as a store's first commit, checks it out (not with --no-checkout) and ignores .trunk/."
+
+# Scenario 51.2: errors
+echo "INFO: Scenario 2: import errors"
+set +e
+$GIT_TRUNK_CMD import ../handbook.bundle 2> /dev/null
+taken=$?
+$GIT_TRUNK_CMD import ../no-such-file.zip 2> /dev/null
+missing=$?
+$GIT_TRUNK_CMD import ../plain.txt 2> /dev/null
+unknown=$?
+$GIT_TRUNK_CMD import ../notes --as "bad name" 2> /dev/null
+invalid=$?
+set -e
+if [ "$taken" != "4" ] || [ "$missing" != "3" ] || [ "$unknown" != "2" ] || [ "$invalid" != "2" ] \
+    || [ "$(git for-each-ref --format='%(refname)' refs/trunk/ | wc -l)" != "3" ]; then
+    echo "ERROR: import over an existing store exited with $taken (expected 4), of a missing file with $missing (expected 3), of an unknown format with $unknown and under an invalid name with $invalid (expected 2)"
+    exit 1
+fi
+echo "VERIFY: import refuses existing stores with 4, missing paths with 3 and unknown formats or invalid names with 2, creating nothing."
+
+echo "SUCCESS: test_import completed."
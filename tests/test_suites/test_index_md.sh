@@ -0,0 +1,89 @@
+#!/bin/bash
+# test_index_md.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_index_md in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+rm -f .trunk/docs/*.md
+mkdir -p ".trunk/docs/guides" ".trunk/docs/_templates"
+printf -- '---\ntitle: Setting up\n---\nInstall it.\n' > .trunk/docs/guides/setup.md
+printf '# Frequently [asked] questions\n\nAsk away.\n' > ".trunk/docs/guides/faq (old).md"
+echo "Plain notes." > .trunk/docs/notes.txt
+echo "# Template" > .trunk/docs/_templates/note.md
+echo "Not prose." > .trunk/docs/image.png
+$GIT_TRUNK_CMD --store docs commit --force -m "Documents" 2> /dev/null
+TODAY="$(date +%Y-%m-%d)"
+
+# Scenario 18.1: the generated index
+echo "INFO: Scenario 1: index-md"
+$GIT_TRUNK_CMD --store docs index-md 2> /dev/null
+cat > expected.md <<EXPECTED
+<!-- Generated by \`git trunk index-md\`; manual edits will be overwritten. -->
+# docs
+
+3 document(s).
+
+## /
+
+- [notes.txt](notes.txt) — $TODAY
+
+## guides
+
+- [Frequently asked questions](guides/faq%20%28old%29.md) — $TODAY
+- [Setting up](guides/setup.md) — $TODAY
+EXPECTED
+if ! diff expected.md .trunk/docs/INDEX.md; then
+    echo "ERROR: INDEX.md did not list the prose documents by directory with their titles:"; cat .trunk/docs/INDEX.md
+    exit 1
+fi
+echo "VERIFY: index-md lists the prose documents grouped by directory with their frontmatter or heading titles and escaped links, leaving out _templates and other files."
+
+# Scenario 18.2: reruns, --file and other indexes
+echo "INFO: Scenario 2: reruns and --file"
+$GIT_TRUNK_CMD --store docs index-md 2> rerun.err
+$GIT_TRUNK_CMD --store docs index-md --file guides/INDEX.md 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store missing index-md 2> missing.err
+missing=$?
+set -e
+if ! grep -q "INDEX.md is already up to date" rerun.err || ! grep -q "^3 document(s).$" .trunk/docs/guides/INDEX.md || grep -q "INDEX.md" .trunk/docs/guides/INDEX.md \
+    || [ "$missing" != "3" ] || ! grep -q ".trunk/missing is not checked out" missing.err; then
+    echo "ERROR: index-md rewrote an up-to-date index, listed another index, or exited with $missing (expected 3):"; cat rerun.err .trunk/docs/guides/INDEX.md missing.err
+    exit 1
+fi
+rm .trunk/docs/INDEX.md .trunk/docs/guides/INDEX.md
+echo "VERIFY: index-md leaves an up-to-date index alone, writes --file elsewhere without listing the other index, and exits 3 without a checkout."
+
+# Scenario 18.3: commit regenerates the configured index
+echo "INFO: Scenario 3: commit with [store.docs] index"
+$GIT_TRUNK_CMD config set store.docs.index INDEX.md 2> /dev/null
+echo "Untouched." > .trunk/docs/untouched.md
+$GIT_TRUNK_CMD --store docs commit --force --dry-run -m "Dry run" 2> /dev/null
+if [ -e .trunk/docs/INDEX.md ]; then
+    echo "ERROR: commit --dry-run wrote INDEX.md"
+    exit 1
+fi
+printf '# Release plan\n' > .trunk/docs/release.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Release plan" 2> /dev/null
+if ! git cat-file -p refs/trunk/docs:INDEX.md | grep -qx -- "- \[Release plan\](release.md) — $TODAY" || ! git cat-file -p refs/trunk/docs:INDEX.md | grep -q "^5 document(s).$" \
+    || [ -n "$(git -C .trunk/docs status --porcelain)" ]; then
+    echo "ERROR: commit did not regenerate and commit INDEX.md with the new documents:"; git cat-file -p refs/trunk/docs:INDEX.md
+    exit 1
+fi
+before="$(git rev-parse refs/trunk/docs)"
+$GIT_TRUNK_CMD --store docs commit --force -m "Nothing" 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" != "$before" ]; then
+    echo "ERROR: commit without changes made a commit for the index"
+    exit 1
+fi
+echo "VERIFY: With [store.docs] index set, commit regenerates INDEX.md into the same commit, not under --dry-run, and a commit without changes leaves it alone."
+
+echo "SUCCESS: test_index_md completed."
@@ -0,0 +1,123 @@
+#!/bin/bash
+# test_lint_prose.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_lint_prose in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+cat > .trunk/docs/guide.md <<'EOF_DOC'
+# Guide
+
+Run `teh build` first, see https://example.com/teh or <https://example.com/recieve>.
+
+```sh
+echo teh
+```
+
+We will Recieve the the files.
+He had had enough, so it is very done.
+EOF_DOC
+$GIT_TRUNK_CMD --store docs commit --force -m "Guide" 2> /dev/null
+
+# Scenario 59.1: issues outside code spans, URLs and fences
+echo "INFO: Scenario 1: lint-prose"
+set +e
+$GIT_TRUNK_CMD --store docs lint-prose > lint.out 2> /dev/null
+linted=$?
+set -e
+expected='guide.md:9:9: spelling: '"'"'Recieve'"'"' is a misspelling of '"'"'Receive'"'"'
+guide.md:9:21: repeated-word: '"'"'the'"'"' is repeated
+guide.md:10:8: repeated-word: '"'"'had'"'"' is repeated
+guide.md:10:29: weasel: '"'"'very'"'"' weakens the sentence; consider removing it'
+if [ "$linted" != "1" ] || [ "$(cat lint.out)" != "$expected" ]; then
+    echo "ERROR: lint-prose exited with $linted (expected 1) or reported the wrong issues:"; cat lint.out
+    exit 1
+fi
+echo "VERIFY: lint-prose reports misspellings, repeated words and weasel words with their positions, but not words in inline code, URLs or fenced code blocks."
+
+# Scenario 59.2: --fix corrects misspellings only, --fix-repeated also drops repeated words
+echo "INFO: Scenario 2: --fix"
+set +e
+$GIT_TRUNK_CMD --store docs lint-prose --fix > fix.out 2> /dev/null
+fixed=$?
+$GIT_TRUNK_CMD --store docs lint-prose --fix-repeated > /dev/null 2> usage.err
+usage=$?
+set -e
+if [ "$fixed" != "1" ] || ! grep -q "^We will Receive the the files.$" .trunk/docs/guide.md \
+    || ! grep -q "^He had had enough" .trunk/docs/guide.md || ! grep -q "Run \`teh build\` first, see https://example.com/teh" .trunk/docs/guide.md \
+    || grep -q "spelling" fix.out || [ "$(grep -c "repeated-word" fix.out)" != "2" ]; then
+    echo "ERROR: --fix exited with $fixed (expected 1) or changed more than the misspelling:"; cat .trunk/docs/guide.md fix.out
+    exit 1
+fi
+if [ "$usage" != "2" ]; then
+    echo "ERROR: --fix-repeated without --fix exited with $usage (expected 2):"; cat usage.err
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD --store docs lint-prose --fix --fix-repeated > fix.out 2> /dev/null
+set -e
+if ! grep -q "^We will Receive the files.$" .trunk/docs/guide.md || ! grep -q "^He had enough" .trunk/docs/guide.md || grep -q "repeated-word" fix.out; then
+    echo "ERROR: --fix --fix-repeated did not remove the repeated words:"; cat .trunk/docs/guide.md fix.out
+    exit 1
+fi
+git -C .trunk/docs checkout -q guide.md
+echo "VERIFY: --fix corrects the misspelling in its capitalization and keeps repeated words, which --fix --fix-repeated removes; --fix-repeated alone is a usage error."
+
+# Scenario 59.3: the store dictionary accepts words, even ones from the misspelling table
+echo "INFO: Scenario 3: dictionary"
+$GIT_TRUNK_CMD --store docs config set store.docs.dictionaries words.txt 2> /dev/null
+printf 'the\ncalender\npresses\npaper\nis\nit\n' > .trunk/docs/words.txt
+echo "The calender presses paper." > ".trunk/docs/paper mill.md"
+echo "It is teh paper." > .trunk/docs/café.md
+set +e
+$GIT_TRUNK_CMD --store docs lint-prose --changed --fix > dictionary.out 2> /dev/null
+dictionary=$?
+set -e
+if [ "$dictionary" != "0" ] || [ -s dictionary.out ] || ! grep -q "^The calender presses paper.$" ".trunk/docs/paper mill.md" \
+    || ! grep -q "^It is the paper.$" .trunk/docs/café.md; then
+    echo "ERROR: lint-prose --changed --fix exited with $dictionary (expected 0) or corrected a dictionary word:"; cat dictionary.out ".trunk/docs/paper mill.md" .trunk/docs/café.md
+    exit 1
+fi
+echo "Its calender is fine." > ".trunk/docs/paper mill.md"
+set +e
+$GIT_TRUNK_CMD --store docs lint-prose --changed > unknown.out 2> /dev/null
+unknown=$?
+set -e
+if [ "$unknown" != "1" ] || [ "$(cat unknown.out)" != "paper mill.md:1:1: unknown-word: 'Its' is not in the store dictionary
+paper mill.md:1:17: unknown-word: 'fine' is not in the store dictionary" ]; then
+    echo "ERROR: lint-prose --changed exited with $unknown (expected 1) or missed the unknown words:"; cat unknown.out
+    exit 1
+fi
+$GIT_TRUNK_CMD --store docs config unset store.docs.dictionaries 2> /dev/null
+rm ".trunk/docs/paper mill.md" .trunk/docs/café.md .trunk/docs/words.txt
+echo "VERIFY: Dictionary words are neither reported nor corrected, other words are reported as unknown, and --changed finds new files whose names git status quotes."
+
+# Scenario 59.4: lint_prose = true checks changed files on commit
+echo "INFO: Scenario 4: commit gate"
+$GIT_TRUNK_CMD config set store.docs.lint_prose true 2> /dev/null
+$GIT_TRUNK_CMD config set trunk.stage all 2> /dev/null
+$GIT_TRUNK_CMD config set trunk.prompt no 2> /dev/null
+before=$(git rev-parse refs/trunk/docs)
+echo "Nothing to recieve here." > .trunk/docs/inbox.md
+env -u GIT_TRUNK_NONINTERACTIVE $GIT_TRUNK_CMD --store docs commit -m "Inbox" > declined.out 2> declined.err
+$GIT_TRUNK_CMD config unset trunk.prompt 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" != "$before" ] || ! grep -q "inbox.md:1:12: spelling: 'recieve' is a misspelling of 'receive'" declined.out \
+    || grep -q "guide.md" declined.out || ! grep -q "aborted" declined.err; then
+    echo "ERROR: a declined commit with prose issues moved refs/trunk/docs or did not list the issues of the changed file:"; cat declined.out declined.err
+    exit 1
+fi
+$GIT_TRUNK_CMD --store docs commit --force -m "Inbox" > forced.out 2> forced.err
+if [ "$(git rev-parse refs/trunk/docs)" = "$before" ] || ! grep -q "1 prose issue(s) in store 'docs'" forced.err; then
+    echo "ERROR: commit --force did not commit with a warning:"; cat forced.out forced.err
+    exit 1
+fi
+echo "VERIFY: With lint_prose = true, commit lints only the changed files, stops when the prompt is declined and commits with a warning under --force."
+
+echo "SUCCESS: test_lint_prose completed."
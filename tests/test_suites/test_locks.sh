@@ -0,0 +1,135 @@
+#!/bin/bash
+# test_locks.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Runs git-trunk for store design, expecting exit code <expected>; stderr goes to err.log
+expect_exit() {
+    local expected="$1" status
+    shift
+    set +e
+    $GIT_TRUNK_CMD --store design "$@" 2> err.log
+    status=$?
+    set -e
+    if [ "$status" != "$expected" ]; then
+        echo "ERROR: git trunk $* exited with $status, expected $expected:"; cat err.log
+        exit 1
+    fi
+}
+
+echo "INFO: Starting test_locks in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote.git
+for clone in alice bob; do
+    git init -q -b main "$clone"
+    (cd "$clone" && git config user.email "$clone@example.com" && git config user.name "$clone" && git commit -q --allow-empty -m "Initial commit" && git remote add origin ../remote.git)
+done
+cd alice
+$GIT_TRUNK_CMD --store design init 2> /dev/null
+echo "v1" > .trunk/design/diagram.drawio
+$GIT_TRUNK_CMD --store design commit --force 2> /dev/null
+$GIT_TRUNK_CMD --store design push 2> /dev/null
+cd ../bob
+$GIT_TRUNK_CMD --store design checkout 2> /dev/null
+
+# Scenario 38.1: a lock is recorded on the remote
+echo "INFO: Scenario 1: lock"
+cd ../alice
+$GIT_TRUNK_CMD --store design lock .trunk/design/diagram.drawio
+if ! git --git-dir=../remote.git show refs/trunk-locks/design:locks | grep -q "^diagram.drawio	alice@example.com	"; then
+    echo "ERROR: the lock was not pushed to refs/trunk-locks/design"
+    exit 1
+fi
+expect_exit 0 lock diagram.drawio
+if ! grep -q "already hold the lock" err.log; then
+    echo "ERROR: locking a path twice was not reported as a no-op:"; cat err.log
+    exit 1
+fi
+echo "VERIFY: lock pushes refs/trunk-locks/<store> and is idempotent for the owner."
+
+# Scenario 38.2: others see the lock and are warned
+echo "INFO: Scenario 2: someone else's lock"
+cd ../bob
+expect_exit 4 lock diagram.drawio
+if ! grep -q "locked by alice <alice@example.com>" err.log; then
+    echo "ERROR: the conflicting lock did not name its owner:"; cat err.log
+    exit 1
+fi
+if ! $GIT_TRUNK_CMD --store design locks --no-table | grep -q "alice <alice@example.com>"; then
+    echo "ERROR: locks does not list alice's lock"
+    exit 1
+fi
+echo "v2" > .trunk/design/diagram.drawio
+$GIT_TRUNK_CMD --store design commit --force 2> commit.log
+if ! grep -q "diagram.drawio is locked by alice" commit.log; then
+    echo "ERROR: commit did not warn about the locked file:"; cat commit.log
+    exit 1
+fi
+expect_exit 4 unlock diagram.drawio
+echo "VERIFY: locks held by others block lock and unlock, and commit warns about them."
+
+# Scenario 38.3: unlock, and --force
+echo "INFO: Scenario 3: unlock"
+(cd ../alice && $GIT_TRUNK_CMD --store design unlock diagram.drawio 2> /dev/null)
+$GIT_TRUNK_CMD --store design lock diagram.drawio
+cd ../alice
+expect_exit 3 unlock missing.png
+expect_exit 0 unlock --force diagram.drawio
+if ! grep -q "Broke the lock on diagram.drawio (held by bob" err.log || git --git-dir=../remote.git show refs/trunk-locks/design:locks | grep -q diagram.drawio; then
+    echo "ERROR: unlock --force did not release bob's lock with a warning:"; cat err.log
+    exit 1
+fi
+echo "VERIFY: unlock releases locks; --force breaks someone else's with a warning."
+
+# Scenario 38.4: fetch brings the locks along
+echo "INFO: Scenario 4: fetch"
+$GIT_TRUNK_CMD --store design lock notes.png
+cd ../bob
+$GIT_TRUNK_CMD --store design fetch 2> /dev/null
+if ! git show refs/trunk-locks/design:locks | grep -q "^notes.png	"; then
+    echo "ERROR: fetch did not update refs/trunk-locks/design"
+    exit 1
+fi
+if ! $GIT_TRUNK_CMD --store design locks --local --no-table | grep -q "notes.png"; then
+    echo "ERROR: locks --local does not show the fetched lock"
+    exit 1
+fi
+echo "VERIFY: fetch updates the local copy of the locks."
+
+# Scenario 38.5: one lock command across stores
+echo "INFO: Scenario 5: lock store:path"
+$GIT_TRUNK_CMD --store slides init 2> /dev/null
+$GIT_TRUNK_CMD lock design:cover.png slides:deck.key
+(cd .trunk/slides && $GIT_TRUNK_CMD unlock deck.key)
+if ! git show refs/trunk-locks/design:locks | grep -q "^cover.png	bob@example.com" || git show refs/trunk-locks/slides:locks | grep -q "deck.key"; then
+    echo "ERROR: lock with store:path did not lock in each named store, or unlock from .trunk/slides did not find the lock"
+    exit 1
+fi
+echo "VERIFY: store:path and paths inside .trunk/<store> pick the store without --store."
+
+# Scenario 38.6: TRUNKOWNERS names the owners of paths
+echo "INFO: Scenario 6: owners"
+printf '# Design ownership\n*          alice@example.com\nspecs/     @bob  # bob writes the specs\nspecs/old/\n' > .trunk/design/TRUNKOWNERS
+$GIT_TRUNK_CMD --store design commit --force 2> /dev/null
+$GIT_TRUNK_CMD --store design owners --no-table diagram.drawio design:specs/api.md specs/old/v1.md > owners.out 2> /dev/null
+mkdir -p .trunk/design/specs
+echo "v3" > .trunk/design/diagram.drawio
+echo "API" > .trunk/design/specs/api.md
+expect_exit 3 owners --ref refs/trunk/design~1
+$GIT_TRUNK_CMD --store design commit --force 2> err.log
+if [ "$(grep "^Owners:" owners.out | tr '\n' '|')" != "Owners: alice@example.com|Owners: @bob (you)|Owners: (unowned)|" ] \
+    || ! grep -q "^Rule: line 3: specs/$" owners.out \
+    || ! grep -q "diagram.drawio is owned by alice@example.com (TRUNKOWNERS line 2)" err.log || grep -q "specs.* is owned by" err.log; then
+    echo "ERROR: owners did not apply the last matching rule, or commit did not warn about alice's file only:"; cat owners.out err.log
+    exit 1
+fi
+echo "VERIFY: owners resolves each path by the last matching TRUNKOWNERS rule, and commit warns about changes to paths others own."
+
+echo "SUCCESS: test_locks completed."
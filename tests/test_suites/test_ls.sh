@@ -0,0 +1,409 @@
+#!/bin/bash
+# test_ls.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_ls in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote.git
+git init -q -b main author
+cd author
+git commit -q --allow-empty -m "Initial commit"
+git remote add origin ../remote.git
+git push -q origin main
+$GIT_TRUNK_CMD --store handbook init 2> /dev/null
+mkdir -p .trunk/handbook/policies
+echo "Economy class only." > .trunk/handbook/policies/travel.md
+echo "Welcome" > .trunk/handbook/welcome.md
+$GIT_TRUNK_CMD --store handbook commit --force -m "First version" 2> /dev/null
+first=$(git rev-parse refs/trunk/handbook)
+echo "Up to 30 days." > .trunk/handbook/policies/leave.md
+$GIT_TRUNK_CMD --store handbook commit --force -m "Add leave" 2> /dev/null
+$GIT_TRUNK_CMD --store handbook push 2> /dev/null
+
+# A clone that never ran checkout
+cd ..
+git clone -q remote.git reader
+cd reader
+$GIT_TRUNK_CMD --store handbook fetch 2> /dev/null
+
+# Scenario 41.1: ls lists refs/trunk/<store> without a checkout
+echo "INFO: Scenario 1: ls"
+$GIT_TRUNK_CMD --store handbook ls > ls.out 2> /dev/null
+$GIT_TRUNK_CMD --store handbook ls --long policies > long.out 2> /dev/null
+if [ -e .trunk ] || [ "$(cat ls.out)" != "$(printf 'ABOUT.md\npolicies/leave.md\npolicies/travel.md\nreadme.md\nwelcome.md')" ] \
+    || [ "$(wc -l < long.out)" != "2" ] || ! grep -q "^ *20 B  policies/travel.md$" long.out; then
+    echo "ERROR: ls did not list the store files:"; cat ls.out long.out
+    exit 1
+fi
+echo "VERIFY: ls lists every file of the store, --long with sizes, without creating .trunk."
+
+# Scenario 41.2: --ref, store:path and missing paths
+echo "INFO: Scenario 2: ls --ref and store:path"
+$GIT_TRUNK_CMD ls --ref "$first" handbook:policies > old.out 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store handbook ls missing 2> /dev/null
+status=$?
+set -e
+if [ "$(cat old.out)" != "policies/travel.md" ] || [ "$status" != "3" ]; then
+    echo "ERROR: ls --ref or store:path listed the wrong files, or a missing path exited with $status:"; cat old.out
+    exit 1
+fi
+echo "VERIFY: --ref lists an older commit, store:path picks the store, and a missing path exits with code 3."
+
+# Scenario 41.3: cat prints one file, byte for byte
+echo "INFO: Scenario 3: cat"
+$GIT_TRUNK_CMD --store handbook cat policies/travel.md > travel.out 2> /dev/null
+$GIT_TRUNK_CMD show --ref "$first" handbook:welcome.md > welcome.out 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store handbook cat policies/missing.md 2> /dev/null
+missing=$?
+$GIT_TRUNK_CMD --store handbook cat policies 2> /dev/null
+directory=$?
+set -e
+if [ -e .trunk ] || [ "$(cat travel.out)" != "Economy class only." ] || [ "$(cat welcome.out)" != "Welcome" ] \
+    || [ "$missing" != "3" ] || [ "$directory" != "2" ]; then
+    echo "ERROR: cat printed the wrong content, or exited with $missing and $directory:"; cat travel.out welcome.out
+    exit 1
+fi
+echo "VERIFY: cat and its show alias print store files without a checkout; a missing file exits with code 3, a directory with 2."
+
+# Scenario 41.4: put commits a file without a checkout
+echo "INFO: Scenario 4: put"
+echo "Anywhere by train." > travel-draft.md
+$GIT_TRUNK_CMD --store handbook put travel-draft.md policies/travel.md -m "Prefer trains" 2> /dev/null
+echo "Mondays at ten." | $GIT_TRUNK_CMD put - handbook:meetings/weekly.md 2> /dev/null
+$GIT_TRUNK_CMD --store handbook put travel-draft.md policies/ 2> /dev/null
+$GIT_TRUNK_CMD --store handbook put travel-draft.md policies/ 2> /dev/null
+if [ -e .trunk ] || [ "$($GIT_TRUNK_CMD --store handbook cat policies/travel.md 2> /dev/null)" != "Anywhere by train." ] \
+    || [ "$($GIT_TRUNK_CMD --store handbook cat meetings/weekly.md 2> /dev/null)" != "Mondays at ten." ] \
+    || [ "$($GIT_TRUNK_CMD --store handbook cat policies/travel-draft.md 2> /dev/null)" != "Anywhere by train." ] \
+    || [ "$(git log --format=%s refs/trunk/handbook | head -n 4 | tr '\n' '|')" != "Put policies/travel-draft.md|Put meetings/weekly.md|Prefer trains|Add leave|" ] \
+    || [ -n "$(git status --porcelain -- . ':!*.out' ':!travel-draft.md')" ]; then
+    echo "ERROR: put did not commit the files as expected:"; git log --format=%s refs/trunk/handbook; git status --porcelain
+    exit 1
+fi
+echo "VERIFY: put commits a file, standard input or a file under a directory into the store without .trunk, and a second identical put commits nothing."
+
+# Scenario 41.5: put keeps an existing checkout in step
+echo "INFO: Scenario 5: put with a checkout"
+cd ../author
+echo "Draft" > .trunk/handbook/draft.md
+echo "Fifty days." > leave.md
+$GIT_TRUNK_CMD --store handbook put leave.md policies/leave.md 2> /dev/null
+if [ "$(cat .trunk/handbook/policies/leave.md)" != "Fifty days." ] || [ "$(cat .trunk/handbook/draft.md)" != "Draft" ] \
+    || [ "$(git -C .trunk/handbook rev-parse HEAD)" != "$(git rev-parse refs/trunk/handbook)" ]; then
+    echo "ERROR: put did not fast-forward .trunk/handbook or lost its changes"
+    exit 1
+fi
+git -C .trunk/handbook add draft.md
+git -C .trunk/handbook commit -q -m "Draft"
+set +e
+$GIT_TRUNK_CMD --store handbook put leave.md policies/leave-copy.md 2> /dev/null
+diverged=$?
+set -e
+if [ "$diverged" != "4" ] || [ -n "$($GIT_TRUNK_CMD --store handbook ls policies/leave-copy.md 2> /dev/null)" ]; then
+    echo "ERROR: put into a checkout with its own commits exited with $diverged"
+    exit 1
+fi
+echo "VERIFY: put fast-forwards .trunk/<store> and keeps its uncommitted changes, and refuses with code 4 while the checkout has commits of its own."
+
+# Scenario 41.6: materialize-temp exports the store for a build
+echo "INFO: Scenario 6: materialize-temp"
+cd ../reader
+mkdir -p tools
+(cd tools && $GIT_TRUNK_CMD --store handbook materialize-temp --dest ../build/handbook 2> /dev/null)
+touch build/handbook/stamp
+$GIT_TRUNK_CMD --store handbook materialize-temp --dest build/handbook 2> /dev/null
+if [ -e .trunk ] || [ "$(cat build/handbook/policies/travel.md)" != "Anywhere by train." ] || [ ! -e build/handbook/stamp ] \
+    || ! grep -q "^commit $(git rev-parse refs/trunk/handbook)$" build/handbook/.git-trunk-materialized \
+    || [ -n "$(git status --porcelain -- build)" ]; then
+    echo "ERROR: materialize-temp did not export the store once into an untracked build/handbook:"; ls -la build/handbook; git status --porcelain
+    exit 1
+fi
+$GIT_TRUNK_CMD --store handbook materialize-temp --dest build/handbook --rev "$first" 2> /dev/null
+mkdir -p build/mine && touch build/mine/notes
+set +e
+$GIT_TRUNK_CMD --store handbook materialize-temp --dest build/mine 2> /dev/null
+occupied=$?
+$GIT_TRUNK_CMD materialize-temp --clean --dest build/mine 2> /dev/null
+foreign=$?
+set -e
+if [ -e build/handbook/policies/leave.md ] || [ -e build/handbook/stamp ] || [ "$occupied" != "4" ] || [ "$foreign" != "4" ] || [ ! -e build/mine/notes ]; then
+    echo "ERROR: --rev did not replace the export, or a foreign directory was touched ($occupied, $foreign)"
+    exit 1
+fi
+$GIT_TRUNK_CMD materialize-temp --clean --dest build/handbook 2> /dev/null
+$GIT_TRUNK_CMD materialize-temp --clean --dest build/handbook 2> /dev/null
+if [ -e build/handbook ]; then
+    echo "ERROR: materialize-temp --clean did not remove build/handbook"
+    exit 1
+fi
+echo "VERIFY: materialize-temp exports into an untracked directory once per commit, replaces it for another --rev, --clean removes it, and directories it did not write exit with code 4."
+
+# Scenario 41.7: rm removes files and directories in one commit
+echo "INFO: Scenario 7: rm"
+commits=$(git rev-list --count refs/trunk/handbook)
+$GIT_TRUNK_CMD rm handbook:meetings handbook:policies/travel-draft.md -m "Tidy up" 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store handbook rm meetings 2> /dev/null
+missing=$?
+set -e
+if [ -e .trunk ] || [ "$(git rev-list --count refs/trunk/handbook)" != "$((commits + 1))" ] || [ "$missing" != "3" ] \
+    || [ "$($GIT_TRUNK_CMD --store handbook ls 2> /dev/null | tr '\n' ' ')" != "ABOUT.md policies/leave.md policies/travel.md readme.md welcome.md " ]; then
+    echo "ERROR: rm did not remove both paths in one commit, or a missing path exited with $missing:"; $GIT_TRUNK_CMD --store handbook ls
+    exit 1
+fi
+cd ../author
+git -C .trunk/handbook reset -q --hard HEAD~1
+echo "Keep" > .trunk/handbook/welcome.md
+set +e
+$GIT_TRUNK_CMD --store handbook rm welcome.md 2> /dev/null
+changed=$?
+set -e
+$GIT_TRUNK_CMD --store handbook rm policies/leave.md 2> /dev/null
+if [ "$changed" != "4" ] || [ -e .trunk/handbook/policies/leave.md ] || [ "$(cat .trunk/handbook/welcome.md)" != "Keep" ] \
+    || [ "$(git -C .trunk/handbook rev-parse HEAD)" != "$(git rev-parse refs/trunk/handbook)" ]; then
+    echo "ERROR: rm did not go through .trunk/handbook, or removed a file with uncommitted changes ($changed)"
+    exit 1
+fi
+echo "VERIFY: rm commits the removal of files and directories once, without a checkout or through one it fast-forwards, and refuses paths with uncommitted changes (4) or not in the store (3)."
+
+# Scenario 41.8: about reads ABOUT.md from the ref, fetching a store seen only on the remote
+echo "INFO: Scenario 8: about"
+$GIT_TRUNK_CMD --store incidents init --purpose "Postmortems of production incidents" --contact "#sre" 2> /dev/null
+$GIT_TRUNK_CMD --store incidents commit --force -m "Start incidents" 2> /dev/null
+$GIT_TRUNK_CMD --store incidents push 2> /dev/null
+cd ..
+git clone -q remote.git finder
+cd finder
+$GIT_TRUNK_CMD about incidents > about.out 2> /dev/null
+$GIT_TRUNK_CMD --store handbook fetch 2> /dev/null
+$GIT_TRUNK_CMD --store handbook rm ABOUT.md 2> /dev/null
+set +e
+$GIT_TRUNK_CMD about handbook > /dev/null 2>&1
+undescribed=$?
+$GIT_TRUNK_CMD about nowhere > /dev/null 2>&1
+unknown=$?
+set -e
+if [ -e .trunk ] || ! grep -q "^Purpose: Postmortems of production incidents$" about.out \
+    || ! grep -q "^Owners: $(git config user.name) <$(git config user.email)>$" about.out || ! grep -q "^Contact: #sre$" about.out \
+    || ! grep -q "^Conventions: (not set)$" about.out || ! grep -q "^# About the 'incidents' store$" about.out \
+    || [ "$undescribed" != "3" ] || [ "$unknown" != "3" ]; then
+    echo "ERROR: about did not describe the remote store, or exited with $undescribed/$unknown for stores without ABOUT.md:"; cat about.out
+    exit 1
+fi
+echo "VERIFY: init writes ABOUT.md, and about prints it for a store only on the remote; stores without one, or not anywhere, exit with 3."
+
+# Scenario 41.9: revert undoes a commit of the ref, unless its files changed since
+echo "INFO: Scenario 9: revert"
+$GIT_TRUNK_CMD --store handbook revert refs/trunk/handbook 2> /dev/null
+echo "First" | $GIT_TRUNK_CMD --store handbook put - notes.md 2> /dev/null
+echo "Second" | $GIT_TRUNK_CMD --store handbook put - notes.md 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store handbook revert refs/trunk/handbook~1 2> /dev/null
+changed=$?
+set -e
+if [ -e .trunk ] || ! git cat-file -e refs/trunk/handbook~2:ABOUT.md || [ "$(git log -1 --format=%s refs/trunk/handbook~2)" != 'Revert "Remove ABOUT.md"' ] \
+    || [ "$changed" != "4" ] || [ "$(git show refs/trunk/handbook:notes.md)" != "Second" ]; then
+    echo "ERROR: revert did not bring ABOUT.md back, or did not stop (exit $changed) on a file changed since:"; git log --oneline refs/trunk/handbook | head -5
+    exit 1
+fi
+echo "VERIFY: revert commits the inverse of a commit to the ref without a checkout, and exits with 4 when its files changed since."
+
+# Scenario 41.10: reset moves the ref and the checkout back, keeping a backup ref
+echo "INFO: Scenario 10: reset"
+cd ../author
+tip=$(git rev-parse refs/trunk/handbook)
+set +e
+$GIT_TRUNK_CMD --store handbook reset --to refs/trunk/handbook~1 2> /dev/null
+uncommitted=$?
+set -e
+git -C .trunk/handbook checkout -q -- welcome.md
+$GIT_TRUNK_CMD --store handbook reset --to refs/trunk/handbook~1 2> /dev/null
+backup=$(git for-each-ref --format='%(refname)' 'refs/trunk-backup/handbook-*')
+if [ "$(git rev-parse refs/trunk/handbook)" != "$(git rev-parse "$tip~1")" ] || [ "$(git -C .trunk/handbook rev-parse HEAD)" != "$(git rev-parse "$tip~1")" ] \
+    || [ -z "$backup" ] || [ "$(git rev-parse "$backup")" != "$tip" ] || [ "$uncommitted" != "4" ]; then
+    echo "ERROR: reset did not move refs/trunk/handbook and .trunk/handbook back, did not keep $tip in a backup ref ($backup), or went over uncommitted changes ($uncommitted)"
+    exit 1
+fi
+$GIT_TRUNK_CMD --store handbook reset --to "$backup" 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store handbook reset --to main 2> /dev/null
+unrelated=$?
+set -e
+if [ "$(git rev-parse refs/trunk/handbook)" != "$tip" ] || [ "$(git -C .trunk/handbook rev-parse HEAD)" != "$tip" ] || [ "$unrelated" != "2" ]; then
+    echo "ERROR: reset --to the backup ref did not undo the reset, or a commit outside the store exited with $unrelated"
+    exit 1
+fi
+echo "VERIFY: reset moves the ref and the checkout, keeps the old commit in refs/trunk-backup/, can be undone from it, and refuses uncommitted changes (4) and commits outside the store (2)."
+
+# Scenario 41.11: grep searches the ref, other commits and every store
+echo "INFO: Scenario 11: grep"
+echo "Budget: 100 EUR" | $GIT_TRUNK_CMD --store handbook put - policies/budget.md 2> /dev/null
+echo "Budget: 120 EUR" | $GIT_TRUNK_CMD --store handbook put - policies/budget.md 2> /dev/null
+$GIT_TRUNK_CMD grep Budget handbook:policies > grep.out 2> /dev/null
+$GIT_TRUNK_CMD --store handbook grep --history "100 EUR" > history.out 2> /dev/null
+$GIT_TRUNK_CMD grep --all -l -i BUDGET > files.out 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store handbook grep "Nothing like this" 2> /dev/null
+none=$?
+set -e
+if [ "$(cat grep.out)" != "handbook:policies/budget.md:1:Budget: 120 EUR" ] \
+    || [ "$(cat history.out)" != "handbook@$(git rev-parse --short refs/trunk/handbook~1):policies/budget.md:1:Budget: 100 EUR" ] \
+    || [ "$(cat files.out)" != "handbook:policies/budget.md" ] || [ "$none" != "1" ]; then
+    echo "ERROR: grep found the wrong lines, or exited with $none without matches:"; cat grep.out history.out files.out
+    exit 1
+fi
+echo "VERIFY: grep prints store:path:line:text, --history finds replaced lines with the newest commit holding them, --all -l lists files, and no match exits with 1."
+
+# Scenario 41.12: a bare repository (a hosting server) runs the commands that
+# need no checkout and refuses the others before their first step
+echo "INFO: Scenario 12: bare repository"
+cd "$TEST_DIR"
+git clone -q --mirror remote.git server.git
+cd server.git
+$GIT_TRUNK_CMD --store handbook ls > ls.out 2> /dev/null
+echo "Trains first." | $GIT_TRUNK_CMD --store handbook put - policies/trains.md 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store handbook checkout 2> checkout.err
+checkout=$?
+$GIT_TRUNK_CMD --store handbook commit -m "Nothing" 2> commit.err
+commit=$?
+env -u GIT_TRUNK_NONINTERACTIVE $GIT_TRUNK_CMD --store handbook delete < /dev/null 2> /dev/null
+prompt=$?
+env -u GIT_TRUNK_NONINTERACTIVE $GIT_TRUNK_CMD --store handbook --dry-run delete < /dev/null 2> /dev/null
+dry_run=$?
+cd "$TEST_DIR/author"
+GIT_EDITOR=no-such-editor $GIT_TRUNK_CMD --store handbook new meeting weekly 2> new.err
+editor=$?
+set -e
+cd "$TEST_DIR/server.git"
+if ! grep -q "policies/travel.md" ls.out || [ "$($GIT_TRUNK_CMD --store handbook cat policies/trains.md 2> /dev/null)" != "Trains first." ] \
+    || [ "$checkout" != "9" ] || ! grep -q "git trunk show" checkout.err || [ "$commit" != "9" ] || ! grep -q "git trunk put" commit.err \
+    || [ "$prompt" != "2" ] || [ "$dry_run" != "0" ] || ! git show-ref -q refs/trunk/handbook || [ -e .trunk ] \
+    || [ "$editor" != "9" ] || ! grep -q "no-such-editor" "$TEST_DIR/author/new.err"; then
+    echo "ERROR: checkout exited with $checkout, commit with $commit, delete without a terminal with $prompt (--dry-run: $dry_run) and new without its editor with $editor:"; cat checkout.err commit.err "$TEST_DIR/author/new.err"
+    exit 1
+fi
+echo "VERIFY: In a bare repository ls, put and cat work, checkout and commit exit with 9 pointing to show and put, delete without a terminal exits with 2 before asking unless --dry-run answers for it, and new with a missing editor exits with 9."
+
+# Scenario 41.13: export packages the history as a bundle or the files as an archive
+echo "INFO: Scenario 13: export"
+mkdir -p "$TEST_DIR/exports"
+(cd "$TEST_DIR/server.git" && $GIT_TRUNK_CMD --store handbook export -o "$TEST_DIR/exports/server.bundle" 2> /dev/null)
+cd "$TEST_DIR/reader"
+$GIT_TRUNK_CMD --store handbook export 2> /dev/null
+$GIT_TRUNK_CMD --store handbook export --output ../exports/handbook.zip 2> /dev/null
+$GIT_TRUNK_CMD --store handbook export --format tar -o ../exports/files 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store handbook export 2> /dev/null
+exists=$?
+set -e
+if [ "$(git bundle list-heads handbook.bundle)" != "$(git rev-parse refs/trunk/handbook) refs/trunk/handbook" ] \
+    || [ "$(git bundle list-heads ../exports/server.bundle | cut -d' ' -f2)" != "refs/trunk/handbook" ] \
+    || [ "$(head -c 2 ../exports/handbook.zip)" != "PK" ] || ! tar -tf ../exports/files > files.out || ! grep -qx "handbook/policies/travel.md" files.out \
+    || [ "$exists" != "4" ]; then
+    echo "ERROR: export wrote the wrong files, or exporting over an existing file exited with $exists (expected 4):"
+    git bundle list-heads handbook.bundle; git bundle list-heads ../exports/server.bundle; cat files.out
+    exit 1
+fi
+echo "VERIFY: export writes a bundle of refs/trunk/<store> (also in a bare repository), a zip picked by the extension and a tar under <store>/, and refuses to overwrite with 4."
+
+# Scenario 41.14: import creates stores from what export wrote and from a directory
+echo "INFO: Scenario 14: import"
+cd "$TEST_DIR"
+mkdir -p notes/meetings
+echo "Monday" > notes/meetings/monday.md
+git init -q -b main importer
+cd importer
+git commit -q --allow-empty -m "Initial commit"
+$GIT_TRUNK_CMD import ../reader/handbook.bundle 2> /dev/null
+$GIT_TRUNK_CMD import ../exports/handbook.zip --as zipped 2> /dev/null
+$GIT_TRUNK_CMD import ../notes 2> /dev/null
+set +e
+$GIT_TRUNK_CMD import ../exports/files 2> /dev/null
+taken=$?
+set -e
+(cd ../server.git && $GIT_TRUNK_CMD import ../exports/files --as from_tar --no-checkout 2> /dev/null)
+if [ "$(git rev-parse refs/trunk/handbook)" != "$(git -C ../reader rev-parse refs/trunk/handbook)" ] || [ "$(cat .trunk/handbook/policies/travel.md)" != "$(git -C ../reader show refs/trunk/handbook:policies/travel.md)" ] \
+    || ! diff -r -x .git .trunk/handbook .trunk/zipped > /dev/null || [ "$(git log --format=%s refs/trunk/zipped)" != "Initial commit for store 'zipped'" ] \
+    || [ "$(cat .trunk/notes/meetings/monday.md)" != "Monday" ] || [ "$taken" != "4" ] \
+    || [ "$(git -C ../server.git show refs/trunk/from_tar:welcome.md)" != "Welcome" ]; then
+    echo "ERROR: import did not recreate the stores, or importing over an existing store exited with $taken (expected 4)"
+    exit 1
+fi
+echo "VERIFY: import keeps a bundle's history, commits the files of a zip, a tar and a directory as a store's first commit, checks them out, and refuses existing stores with 4."
+
+# Scenario 41.15: adopt turns a directory of the main repository into a store
+echo "INFO: Scenario 15: adopt"
+cd "$TEST_DIR"
+git init -q -b main adopter
+cd adopter
+mkdir -p guide src
+echo "Install it" > guide/install.md
+echo "fn main() {}" > src/main.rs
+git add . && git commit -q -m "Add the guide and the code"
+echo "fn main() { run() }" > src/main.rs
+git commit -q -am "Change only the code"
+echo "Then run it" >> guide/install.md
+GIT_AUTHOR_NAME="Ada Writer" GIT_AUTHOR_EMAIL="ada@example.com" git commit -q -am "Explain running it"
+$GIT_TRUNK_CMD --store guide adopt guide 2> /dev/null
+(cd guide && $GIT_TRUNK_CMD --store guide_history adopt . --preserve-history --remove 2> /dev/null)
+set +e
+$GIT_TRUNK_CMD --store guide adopt src 2> /dev/null
+taken=$?
+set -e
+git status --porcelain -- guide > status.out
+me=$(git config user.name)
+if [ "$(git rev-list --count refs/trunk/guide)" != "1" ] || [ "$(git rev-parse refs/trunk/guide^{tree})" != "$(git rev-parse HEAD:guide)" ] \
+    || [ "$(git log --format='%an|%s' refs/trunk/guide_history | paste -sd,)" != "Ada Writer|Explain running it,$me|Add the guide and the code,$me|Initial commit for store 'guide_history'" ] \
+    || [ "$(cat .trunk/guide_history/install.md)" != "$(printf 'Install it\nThen run it')" ] \
+    || ! grep -q "^D  guide/install.md" status.out || [ "$taken" != "4" ]; then
+    echo "ERROR: adopt did not create the stores, or adopting into an existing store exited with $taken (expected 4):"
+    git log --format='%an|%s' refs/trunk/guide_history; cat status.out
+    exit 1
+fi
+echo "VERIFY: adopt commits a directory as a store's first commit, --preserve-history replays only the commits that changed it with their authors, --remove stages its removal, and an existing store exits with 4."
+
+# Scenario 41.16: migrate-branch converts an orphan branch with its history
+echo "INFO: Scenario 16: migrate-branch"
+git commit -q -m "Move the guide into a store"
+git checkout -q --orphan pages
+git rm -r -q --cached . && git clean -fdxq -e .trunk
+echo "Home" > index.md
+git add index.md && git commit -q -m "Start the site"
+git checkout -q -b pages-news
+echo "News" > news.md
+git add news.md && git commit -q -m "Add news"
+git checkout -q pages
+echo "Home, updated" > index.md
+git commit -q -am "Update the home page"
+git merge -q --no-ff -m "Merge the news" pages-news
+git checkout -q main
+$GIT_TRUNK_CMD --store site migrate-branch pages 2> /dev/null
+$GIT_TRUNK_CMD --store site_moved migrate-branch pages --delete-branch --no-checkout 2> /dev/null
+set +e
+$GIT_TRUNK_CMD --store site migrate-branch pages-news 2> /dev/null
+taken=$?
+$GIT_TRUNK_CMD --store other migrate-branch no-such-branch 2> /dev/null
+missing=$?
+set -e
+if [ "$(git rev-list --count refs/trunk/site)" != "5" ] || [ "$(git log --format=%s refs/trunk/site | tail -n 2 | paste -sd,)" != "Start the site,Initial commit for store 'site'" ] \
+    || [ "$(git rev-list --merges --count refs/trunk/site)" != "1" ] || [ "$(cat .trunk/site/index.md) $(cat .trunk/site/news.md)" != "Home, updated News" ] \
+    || [ "$(git rev-parse refs/trunk/site_moved^{tree})" != "$(git rev-parse refs/trunk/site^{tree})" ] || [ -d .trunk/site_moved ] \
+    || git rev-parse -q --verify refs/heads/pages > /dev/null || [ "$taken" != "4" ] || [ "$missing" != "3" ]; then
+    echo "ERROR: migrate-branch did not carry the branch over, or exited with $taken for an existing store (expected 4) and $missing for a missing branch (expected 3):"
+    git log --graph --format=%s refs/trunk/site
+    exit 1
+fi
+echo "VERIFY: migrate-branch carries every commit, merges included, onto the store's initial commit, checks it out, deletes the branch only with --delete-branch, and exits with 4 for an existing store and 3 for a missing branch."
+
+echo "SUCCESS: test_ls completed."
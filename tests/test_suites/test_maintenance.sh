@@ -0,0 +1,75 @@
+#!/bin/bash
+# test_maintenance.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_maintenance in $TEST_DIR"
+setup_repo --remote
+# `git maintenance register` lists the repository in the global config
+MAINTENANCE_CONFIG="$TEST_DIR/maintenance.gitconfig"
+git config -f "$MAINTENANCE_CONFIG" include.path "${GIT_CONFIG_GLOBAL:-$HOME/.gitconfig}"
+export GIT_CONFIG_GLOBAL="$MAINTENANCE_CONFIG"
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+$GIT_TRUNK_CMD --store docs commit --force -m "Start" 2> /dev/null
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+REFSPEC="+refs/trunk/*:refs/trunk-remotes/origin/*"
+
+# Scenario 26.1: only named remotes
+echo "INFO: Scenario 1: unknown remote"
+set +e
+$GIT_TRUNK_CMD --remote nowhere maintenance register 2> nowhere.err
+nowhere=$?
+set -e
+if [ "$nowhere" != "3" ] || ! grep -q "Remote 'nowhere' is not configured" nowhere.err || git config --get-all maintenance.repo > /dev/null; then
+    echo "ERROR: maintenance register for an unknown remote exited with $nowhere (expected 3) or registered the repository:"; cat nowhere.err
+    exit 1
+fi
+echo "VERIFY: maintenance register exits 3 for a remote that is not configured, registering nothing."
+
+# Scenario 26.2: register
+echo "INFO: Scenario 2: maintenance register"
+$GIT_TRUNK_CMD maintenance register 2> /dev/null
+$GIT_TRUNK_CMD maintenance register 2> again.err
+if [ "$(git config --get-all remote.origin.fetch | grep -cxF "$REFSPEC")" != "1" ] \
+    || [ "$(cd "$(git config -f "$MAINTENANCE_CONFIG" --get maintenance.repo)" && pwd -P)" != "$(pwd -P)" ] \
+    || [ "$(git config maintenance.pack-refs.enabled)" != "true" ] || [ "$(git config maintenance.pack-refs.schedule)" != "weekly" ] \
+    || ! grep -q "already includes" again.err || ! grep -q "already registered with git maintenance" again.err; then
+    echo "ERROR: maintenance register did not add the refspec once, register the repository and schedule pack-refs:"; git config --get-all remote.origin.fetch; cat "$MAINTENANCE_CONFIG" again.err
+    exit 1
+fi
+echo "VERIFY: maintenance register adds the trunk refspec once, lists the repository in git's maintenance.repo and schedules weekly pack-refs."
+
+# Scenario 26.3: fetch and prefetch mirror the remote's stores
+echo "INFO: Scenario 3: fetch and prefetch"
+pushed="$(git rev-parse refs/trunk/docs)"
+echo "Unpushed." > .trunk/docs/unpushed.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Unpushed" 2> /dev/null
+local_tip="$(git rev-parse refs/trunk/docs)"
+git fetch -q origin
+git maintenance run --task=prefetch 2> /dev/null
+if [ "$(git rev-parse refs/trunk-remotes/origin/docs)" != "$pushed" ] || [ "$(git rev-parse refs/prefetch/trunk-remotes/origin/docs)" != "$pushed" ] \
+    || [ "$(git rev-parse refs/trunk/docs)" != "$local_tip" ]; then
+    echo "ERROR: git fetch or prefetch did not mirror the remote's store, or moved the local refs/trunk/docs:"; git for-each-ref refs/trunk refs/trunk-remotes refs/prefetch
+    exit 1
+fi
+echo "VERIFY: git fetch mirrors the remote's store into refs/trunk-remotes/origin/, prefetch into refs/prefetch/trunk-remotes/origin/, and the unpushed local commit stays."
+
+# Scenario 26.4: unregister
+echo "INFO: Scenario 4: maintenance unregister"
+$GIT_TRUNK_CMD maintenance unregister 2> /dev/null
+$GIT_TRUNK_CMD maintenance unregister 2> again.err
+if git config --get-all remote.origin.fetch | grep -qxF "$REFSPEC" || [ -n "$(git for-each-ref refs/trunk-remotes/)" ] \
+    || [ "$(git config --get-all remote.origin.fetch)" != "+refs/heads/*:refs/remotes/origin/*" ] \
+    || ! grep -q "nothing to do" again.err || ! git config -f "$MAINTENANCE_CONFIG" --get maintenance.repo > /dev/null; then
+    echo "ERROR: maintenance unregister left the refspec or mirrored refs, or touched the other settings:"; git config --get-all remote.origin.fetch; git for-each-ref refs/trunk-remotes/; cat again.err
+    exit 1
+fi
+echo "VERIFY: maintenance unregister removes only the trunk refspec and the mirrored refs, keeps the repository registered, and does nothing the second time."
+
+echo "SUCCESS: test_maintenance completed."
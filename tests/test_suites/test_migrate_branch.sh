@@ -0,0 +1,64 @@
+#!/bin/bash
+# test_migrate_branch.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_migrate_branch in $TEST_DIR"
+setup_repo
+git checkout -q --orphan pages
+echo "Home" > index.md
+git add index.md && git commit -q -m "Start the site"
+git checkout -q -b pages-news
+echo "News" > news.md
+git add news.md && git commit -q -m "Add news"
+git checkout -q pages
+echo "Home, updated" > index.md
+git commit -q -am "Update the home page"
+git merge -q --no-ff -m "Merge the news" pages-news
+git checkout -q main
+
+# Scenario 53.1: every commit of the branch, merges included, lands on the store's initial commit
+echo "INFO: Scenario 1: migrate-branch"
+$GIT_TRUNK_CMD --store site migrate-branch pages 2> /dev/null
+if [ "$(git rev-list --count refs/trunk/site)" != "5" ] || [ "$(git rev-list --merges --count refs/trunk/site)" != "1" ] \
+    || [ "$(git log --format=%s refs/trunk/site | tail -n 2 | paste -sd,)" != "Start the site,Initial commit for store 'site'" ] \
+    || [ "$(cat .trunk/site/index.md) $(cat .trunk/site/news.md)" != "Home, updated News" ] || ! git rev-parse -q --verify refs/heads/pages > /dev/null; then
+    echo "ERROR: migrate-branch did not carry the branch over:"; git log --graph --format=%s refs/trunk/site
+    exit 1
+fi
+echo "VERIFY: migrate-branch carries every commit onto the store's initial commit, checks the store out and keeps the branch."
+
+# Scenario 53.2: --delete-branch --no-checkout
+echo "INFO: Scenario 2: migrate-branch --delete-branch --no-checkout"
+$GIT_TRUNK_CMD --store site_moved migrate-branch pages --delete-branch --no-checkout 2> /dev/null
+if [ "$(git rev-parse refs/trunk/site_moved^{tree})" != "$(git rev-parse refs/trunk/site^{tree})" ] || [ -d .trunk/site_moved ] \
+    || git rev-parse -q --verify refs/heads/pages > /dev/null; then
+    echo "ERROR: migrate-branch --delete-branch --no-checkout kept the branch or checked the store out"
+    exit 1
+fi
+echo "VERIFY: --delete-branch deletes the branch once it is converted, and --no-checkout skips .trunk/<store>."
+
+# Scenario 53.3: errors
+echo "INFO: Scenario 3: migrate-branch errors"
+set +e
+$GIT_TRUNK_CMD --store site migrate-branch pages-news 2> /dev/null
+taken=$?
+$GIT_TRUNK_CMD --store other migrate-branch no-such-branch 2> /dev/null
+missing=$?
+$GIT_TRUNK_CMD --store current migrate-branch main --delete-branch 2> /dev/null
+checked_out=$?
+set -e
+if [ "$taken" != "4" ] || [ "$missing" != "3" ] || [ "$checked_out" != "4" ] \
+    || git show-ref -q refs/trunk/other || git show-ref -q refs/trunk/current || ! git rev-parse -q --verify refs/heads/main > /dev/null; then
+    echo "ERROR: migrate-branch into an existing store exited with $taken (expected 4), of a missing branch with $missing (expected 3), deleting the checked-out branch with $checked_out (expected 4)"
+    exit 1
+fi
+echo "VERIFY: migrate-branch refuses existing stores and deleting the checked-out branch with 4 and missing branches with 3, creating nothing."
+
+echo "SUCCESS: test_migrate_branch completed."
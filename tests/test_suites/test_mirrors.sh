@@ -0,0 +1,83 @@
+#!/bin/bash
+# test_mirrors.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_mirrors in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare origin.git
+git init -q --bare backup.git
+git init -q --bare github.git
+git init -q -b main repo
+cd repo
+git commit -q --allow-empty -m "Initial commit"
+git remote add origin ../origin.git
+git remote add backup ../backup.git
+git remote add github ../github.git
+git push -q origin main
+git push -q backup main
+for store in docs notes; do
+    $GIT_TRUNK_CMD --store $store init 2> /dev/null
+    echo "$store" > .trunk/$store/first.md
+    $GIT_TRUNK_CMD --store $store commit --force -m "First $store" 2> /dev/null
+    $GIT_TRUNK_CMD --store $store push 2> /dev/null
+done
+# backup has both stores, github only an older docs
+git push -q backup refs/trunk/docs:refs/trunk/docs refs/trunk/notes:refs/trunk/notes
+git push -q github refs/trunk/docs:refs/trunk/docs
+echo "More" > .trunk/docs/second.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Second docs" 2> /dev/null
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+git push -q backup refs/trunk/docs:refs/trunk/docs
+
+# Scenario 45.1: mirrors status flags drift
+echo "INFO: Scenario 1: mirrors status"
+set +e
+$GIT_TRUNK_CMD mirrors status 2> /dev/null
+unconfigured=$?
+set -e
+$GIT_TRUNK_CMD config set trunk.mirrors backup,github 2> /dev/null
+set +e
+$GIT_TRUNK_CMD mirrors status --no-table > status.out 2> /dev/null
+drift=$?
+set -e
+if [ "$unconfigured" != "7" ] || [ "$drift" != "4" ] || [ "$(grep -c "State: ✓ in sync" status.out)" != "2" ] \
+    || ! grep -q "State: ✗ 1 behind" status.out || ! grep -q "State: ✗ missing" status.out; then
+    echo "ERROR: mirrors status did not flag the drift (exit $unconfigured, $drift):"; cat status.out
+    exit 1
+fi
+echo "VERIFY: mirrors status needs [trunk] mirrors, lists each store per mirror and exits with code 4 on drift."
+
+# Scenario 45.2: mirrors reconcile pushes the remote's state
+echo "INFO: Scenario 2: mirrors reconcile"
+# Someone pushes a commit of their own straight to the backup mirror
+git clone -q ../backup.git ../elsewhere
+(
+    cd ../elsewhere
+    $GIT_TRUNK_CMD --store notes checkout 2> /dev/null
+    echo "Mirror only" > .trunk/notes/mirror.md
+    $GIT_TRUNK_CMD --store notes commit --force -m "Mirror only" 2> /dev/null
+    $GIT_TRUNK_CMD --store notes push 2> /dev/null
+)
+set +e
+$GIT_TRUNK_CMD mirrors reconcile 2> /dev/null
+held=$?
+set -e
+if [ "$held" != "4" ] || [ "$(git ls-remote ../github.git refs/trunk/docs | cut -f1)" != "$(git rev-parse refs/trunk/docs)" ] \
+    || [ "$(git ls-remote ../github.git refs/trunk/notes | cut -f1)" != "$(git rev-parse refs/trunk/notes)" ] \
+    || [ "$(git ls-remote ../backup.git refs/trunk/notes | cut -f1)" = "$(git rev-parse refs/trunk/notes)" ]; then
+    echo "ERROR: mirrors reconcile did not update github or overwrote backup's own commit (exit $held)"
+    exit 1
+fi
+$GIT_TRUNK_CMD mirrors reconcile --force 2> /dev/null
+$GIT_TRUNK_CMD mirrors status > /dev/null 2>&1
+echo "VERIFY: mirrors reconcile fast-forwards and creates stores on the mirrors, leaves a mirror's own commits unless --force, and status then exits with 0."
+
+echo "SUCCESS: test_mirrors completed."
@@ -0,0 +1,57 @@
+#!/bin/bash
+# test_mount.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_mount in $TEST_DIR"
+setup_repo
+mkdir ../mnt
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+mkdir .trunk/docs/guides
+echo "The plan." > .trunk/docs/guides/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Plan" 2> /dev/null
+
+# Scenario 57.1: what is checked before mounting
+echo "INFO: Scenario 1: mount errors"
+echo "Not empty." > ../mnt/file.txt
+set +e
+$GIT_TRUNK_CMD --store missing mount ../mnt 2> missing.err
+missing=$?
+$GIT_TRUNK_CMD --store docs mount ../mnt --rev no-such-commit 2> rev.err
+rev=$?
+$GIT_TRUNK_CMD --store docs mount ../nowhere 2> nowhere.err
+nowhere=$?
+$GIT_TRUNK_CMD --store docs mount ../mnt 2> nonempty.err
+nonempty=$?
+set -e
+rm ../mnt/file.txt
+if [ "$missing" != "3" ] || ! grep -q "refs/trunk/missing for store 'missing' does not exist" missing.err \
+    || [ "$rev" != "3" ] || ! grep -q "'no-such-commit' is not a commit" rev.err \
+    || [ "$nowhere" != "3" ] || ! grep -q "is not a directory" nowhere.err \
+    || [ "$nonempty" != "4" ] || ! grep -q "is not empty" nonempty.err; then
+    echo "ERROR: mount exited with $missing, $rev, $nowhere and $nonempty (expected 3, 3, 3 and 4):"; cat missing.err rev.err nowhere.err nonempty.err
+    exit 1
+fi
+echo "VERIFY: mount refuses a missing store or commit and a missing mount point with 3, and a mount point with files in it with 4."
+
+# Scenario 57.2: exit 9 without FUSE support (test_mount_fuse.sh mounts)
+if [ "${GIT_TRUNK_TEST_FUSE:-0}" != "1" ]; then
+    echo "INFO: Scenario 2: mount without the fuse feature"
+    set +e
+    $GIT_TRUNK_CMD --store docs mount ../mnt 2> mount.err
+    mounted=$?
+    set -e
+    if [ "$mounted" != "9" ] || ! grep -q "built without FUSE support" mount.err || ! grep -q "git trunk materialize-temp" mount.err; then
+        echo "ERROR: mount without FUSE support exited with $mounted (expected 9):"; cat mount.err
+        exit 1
+    fi
+    echo "VERIFY: mount exits with 9 and points to ls, cat and materialize-temp when git-trunk was built without the fuse feature."
+fi
+
+echo "SUCCESS: test_mount completed."
@@ -0,0 +1,112 @@
+#!/bin/bash
+# test_mount_fuse.sh
+# Needs a git-trunk built with `--features fuse` and permission to mount;
+# run_tests.sh runs it only with GIT_TRUNK_TEST_FUSE=1.
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_mount_fuse in $TEST_DIR"
+setup_repo
+mkdir ../mnt
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+mkdir .trunk/docs/guides
+echo "The first plan." > .trunk/docs/guides/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "First plan" 2> /dev/null
+echo "The plan." > .trunk/docs/guides/plan.md
+printf '#!/bin/sh\necho built\n' > .trunk/docs/build.sh
+chmod +x .trunk/docs/build.sh
+ln -s guides/plan.md .trunk/docs/plan-link.md
+# Larger than one FUSE read, so it is read back in chunks
+seq 1 100000 > .trunk/docs/numbers.txt
+$GIT_TRUNK_CMD --store docs commit --force -m "Plan" 2> /dev/null
+
+# Starts `mount "$@" ../mnt` in the background and waits until it has
+# mounted; fails the suite if it exits first
+start_mount() {
+    $GIT_TRUNK_CMD --store docs mount ../mnt "$@" 2> mount.err &
+    mount_pid=$!
+    for _ in $(seq 50); do
+        if grep -q "Mounted store" mount.err; then
+            return 0
+        fi
+        if ! kill -0 "$mount_pid" 2> /dev/null; then
+            break
+        fi
+        sleep 0.1
+    done
+    echo "ERROR: mount did not mount the store:"; cat mount.err
+    kill "$mount_pid" 2> /dev/null || true
+    exit 1
+}
+
+# Unmounts ../mnt and checks that the mount command then exits with 0
+stop_mount() {
+    umount ../mnt 2> /dev/null || fusermount3 -u ../mnt 2> /dev/null || fusermount -u ../mnt
+    set +e
+    wait "$mount_pid"
+    stopped=$?
+    set -e
+    if [ "$stopped" != "0" ] || ! grep -q "Unmounted" mount.err; then
+        echo "ERROR: mount exited with $stopped after umount (expected 0):"; cat mount.err
+        exit 1
+    fi
+}
+
+# Scenario 58.1: read the store's files back
+echo "INFO: Scenario 1: read files back"
+start_mount
+listing=$(ls ../mnt | tr '\n' ' ')
+content=$(cat ../mnt/guides/plan.md)
+link=$(readlink ../mnt/plan-link.md)
+built=$(sh -c '../mnt/build.sh')
+if [ "$listing" != "ABOUT.md build.sh guides numbers.txt plan-link.md readme.md " ] || [ "$content" != "The plan." ] \
+    || [ "$link" != "guides/plan.md" ] || [ "$(cat ../mnt/plan-link.md)" != "The plan." ] || [ "$built" != "built" ] \
+    || ! cmp -s ../mnt/numbers.txt .trunk/docs/numbers.txt; then
+    echo "ERROR: the mounted store did not match the committed files:"
+    echo "listing: $listing"; echo "content: $content"; echo "link: $link"; echo "built: $built"
+    stop_mount
+    exit 1
+fi
+echo "VERIFY: The mount lists the store's files and reads them back, including a symlink, an executable and a file read in chunks."
+
+# Scenario 58.2: the mount is read-only
+echo "INFO: Scenario 2: read-only"
+set +e
+touch ../mnt/new.md 2> touch.err
+touched=$?
+echo "Changed." 2> write.err > ../mnt/guides/plan.md
+written=$?
+rm ../mnt/guides/plan.md 2> rm.err
+removed=$?
+set -e
+if [ "$touched" = "0" ] || [ "$written" = "0" ] || [ "$removed" = "0" ] || ! grep -q "Read-only file system" touch.err; then
+    echo "ERROR: the mount accepted a write:"; cat touch.err write.err rm.err
+    stop_mount
+    exit 1
+fi
+stop_mount
+if [ -e ../mnt/guides ]; then
+    echo "ERROR: ../mnt still shows the store after umount"
+    exit 1
+fi
+echo "VERIFY: Creating, writing and removing files fails with a read-only file system, and umount ends the command with 0."
+
+# Scenario 58.3: --rev mounts an older commit
+echo "INFO: Scenario 3: --rev"
+start_mount --rev "refs/trunk/docs~1"
+content=$(cat ../mnt/guides/plan.md)
+old_files=$(ls ../mnt | tr '\n' ' ')
+stop_mount
+if [ "$content" != "The first plan." ] || [ "$old_files" != "ABOUT.md guides readme.md " ]; then
+    echo "ERROR: --rev refs/trunk/docs~1 showed '$content' and '$old_files'"
+    exit 1
+fi
+echo "VERIFY: --rev mounts the given commit of the store."
+
+echo "SUCCESS: test_mount_fuse completed."
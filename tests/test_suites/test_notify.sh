@@ -0,0 +1,80 @@
+#!/bin/bash
+# test_notify.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# A notify-send that records its arguments, one per line
+mkdir -p "$TEST_DIR/bin"
+cat > "$TEST_DIR/bin/notify-send" <<SCRIPT
+#!/bin/sh
+printf '%s\n' "\$@" >> "$TEST_DIR/notifications"
+SCRIPT
+chmod +x "$TEST_DIR/bin/notify-send"
+export PATH="$TEST_DIR/bin:$PATH"
+
+# Waits for the notification spawned in the background to be recorded
+notifications() {
+    for _ in 1 2 3 4 5 6 7 8 9 10; do
+        [ -s "$TEST_DIR/notifications" ] && break
+        sleep 0.2
+    done
+    cat "$TEST_DIR/notifications" 2> /dev/null || true
+    rm -f "$TEST_DIR/notifications"
+}
+
+echo "INFO: Starting test_notify in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q -b main repo
+cd repo
+git commit -q --allow-empty -m "Initial commit"
+$GIT_TRUNK_CMD init 2> /dev/null
+echo "notes" > .trunk/main/notes.md
+$GIT_TRUNK_CMD commit --force 2> /dev/null
+
+# Scenario 40.1: --notify desktop on success
+echo "INFO: Scenario 1: --notify desktop"
+$GIT_TRUNK_CMD --notify desktop checkout 2> /dev/null
+sent=$(notifications)
+if ! echo "$sent" | grep -q "^git trunk checkout finished$" || ! echo "$sent" | grep -q "^--urgency=normal$"; then
+    echo "ERROR: --notify desktop did not send a notification for the finished checkout:"; echo "$sent"
+    exit 1
+fi
+echo "VERIFY: --notify desktop notifies that the command finished."
+
+# Scenario 40.2: --notify desktop on failure
+echo "INFO: Scenario 2: --notify desktop on failure"
+set +e
+$GIT_TRUNK_CMD --notify desktop --store missing checkout 2> /dev/null
+status=$?
+set -e
+sent=$(notifications)
+if [ "$status" != "3" ] || ! echo "$sent" | grep -q "^git trunk checkout failed$" || ! echo "$sent" | grep -q "^--urgency=critical$"; then
+    echo "ERROR: a failed checkout with --notify desktop exited with $status or sent the wrong notification:"; echo "$sent"
+    exit 1
+fi
+echo "VERIFY: a failure is notified as critical and keeps its exit code."
+
+# Scenario 40.3: [trunk] notify stays quiet outside a terminal, and is validated
+echo "INFO: Scenario 3: [trunk] notify"
+$GIT_TRUNK_CMD config set trunk.notify desktop 2> /dev/null
+$GIT_TRUNK_CMD config set trunk.notify_after 0 2> /dev/null
+$GIT_TRUNK_CMD checkout 2> /dev/null
+sent=$(notifications)
+set +e
+$GIT_TRUNK_CMD config set trunk.notify loud 2> /dev/null
+status=$?
+set -e
+if [ -n "$sent" ] || [ "$status" = "0" ]; then
+    echo "ERROR: [trunk] notify notified without a terminal ($sent) or accepted \"loud\""
+    exit 1
+fi
+echo "VERIFY: [trunk] notify only notifies in a terminal, and only accepts off, bell, desktop or both."
+
+echo "SUCCESS: test_notify completed."
@@ -0,0 +1,69 @@
+#!/bin/bash
+# test_plain_output.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Fails when the output holds anything but ASCII (emoji, symbols, box drawing)
+# or terminal escape sequences
+expect_plain() {
+    local description="$1" file="$2"
+    if LC_ALL=C grep -q -P '[^\x09\x0A\x20-\x7E]' "$file"; then
+        echo "ERROR: $description printed symbols or escape sequences under --plain:"
+        LC_ALL=C grep -n -P '[^\x09\x0A\x20-\x7E]' "$file" | cat -A
+        exit 1
+    fi
+}
+
+echo "INFO: Starting test_plain_output in $TEST_DIR"
+setup_repo --remote
+
+# Scenario 1: logs, reports and tables carry no symbols
+echo "INFO: Scenario 1: --plain output is words only"
+$GIT_TRUNK_CMD --plain --store docs init > out.log 2>&1
+$GIT_TRUNK_CMD --plain --store docs commit --force >> out.log 2>&1
+$GIT_TRUNK_CMD --plain --store docs push >> out.log 2>&1
+$GIT_TRUNK_CMD --plain --store docs hooks install >> out.log 2>&1
+expect_plain "init, commit, push and hooks install" out.log
+for command in "status --all" "info --store docs" "info --short" "hooks status"; do
+    $GIT_TRUNK_CMD --plain $command > out.log 2>&1
+    expect_plain "$command" out.log
+done
+if ! grep -q "Exists on Remote: YES" <($GIT_TRUNK_CMD --plain info --store docs 2>/dev/null); then
+    echo "ERROR: info --plain did not spell out YES"
+    exit 1
+fi
+if ! grep -q "^Local Ref: OK " <($GIT_TRUNK_CMD --plain info --short 2>/dev/null); then
+    echo "ERROR: info --short --plain did not print one Field: value line per fact"
+    exit 1
+fi
+echo "VERIFY: --plain prints words instead of symbols, and tables as Field: value lines."
+
+# Scenario 2: one fact per line in status
+echo "INFO: Scenario 2: status prints one fact per line"
+expected=$'docs: clean\ndocs: committed\ndocs: in sync with origin'
+actual=$($GIT_TRUNK_CMD --plain --store docs status)
+if [ "$actual" != "$expected" ]; then
+    echo "ERROR: status --plain printed:"; echo "$actual"
+    exit 1
+fi
+echo "VERIFY: status --plain prints each fact on its own line."
+
+# Scenario 3: errors are labeled in words, also via the environment variable
+echo "INFO: Scenario 3: errors"
+set +e
+GIT_TRUNK_PLAIN=1 $GIT_TRUNK_CMD --store missing log 2> err.log
+set -e
+expect_plain "a failing log" err.log
+if ! grep -q "^ERROR: refs/trunk/missing" err.log; then
+    echo "ERROR: the failure was not labeled ERROR:"; cat err.log
+    exit 1
+fi
+echo "VERIFY: errors start with ERROR: and GIT_TRUNK_PLAIN=1 works like --plain."
+
+echo "SUCCESS: test_plain_output completed."
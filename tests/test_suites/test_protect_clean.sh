@@ -0,0 +1,110 @@
+#!/bin/bash
+# test_protect_clean.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# The guard alias calls `git trunk`, so put the binary under test on PATH
+mkdir -p "$TEST_DIR/bin"
+ln -sf "$(command -v "$GIT_TRUNK_CMD")" "$TEST_DIR/bin/git-trunk"
+export PATH="$TEST_DIR/bin:$PATH"
+
+echo "INFO: Starting test_protect_clean in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD init
+echo "committed" > .trunk/main/notes.md
+$GIT_TRUNK_CMD commit --force
+
+# Scenario 34.1: install the guard
+echo "INFO: Scenario 1: protect-clean"
+git config clean.requireForce false
+$GIT_TRUNK_CMD protect-clean
+if [ "$(git config --local clean.requireForce)" != "true" ] || [ -z "$(git config --local alias.clean-safe)" ]; then
+    echo "ERROR: protect-clean did not set clean.requireForce and the clean-safe alias"
+    git config --local --list
+    exit 1
+fi
+echo "VERIFY: protect-clean turns clean.requireForce back on and installs git clean-safe."
+
+# Scenario 34.2: the guard refuses while a store has uncommitted work
+echo "INFO: Scenario 2: git clean-safe with uncommitted work"
+echo "draft" > .trunk/main/wip.md
+$GIT_TRUNK_CMD status --local 2> status.log
+if ! grep -q "store 'main' has 1 uncommitted change(s) that \`git clean -ffdx\` would delete" status.log; then
+    echo "ERROR: status did not warn about the uncommitted change:"; cat status.log
+    exit 1
+fi
+set +e
+git clean-safe -ffdxq 2> clean.log
+status=$?
+set -e
+if [ "$status" = "0" ] || [ ! -f .trunk/main/wip.md ] || ! grep -q "git trunk commit --all" clean.log; then
+    echo "ERROR: git clean-safe did not refuse (exit $status):"; cat clean.log
+    exit 1
+fi
+echo "VERIFY: status warns and git clean-safe refuses while a store has uncommitted changes."
+
+# Scenario 34.3: committed data survives a forced clean and checkout restores it
+echo "INFO: Scenario 3: recovery"
+$GIT_TRUNK_CMD commit --force
+git clean-safe -ffdxq
+if [ -e .trunk ]; then
+    echo "ERROR: git clean-safe did not run git clean once the work was committed"
+    exit 1
+fi
+$GIT_TRUNK_CMD checkout
+if ! grep -q "draft" .trunk/main/wip.md; then
+    echo "ERROR: checkout did not restore the committed store"
+    exit 1
+fi
+echo "VERIFY: once committed, git clean-safe cleans and git trunk checkout restores .trunk/main."
+
+# Scenario 34.4: undo
+echo "INFO: Scenario 4: protect-clean --undo"
+$GIT_TRUNK_CMD protect-clean --undo
+if git config --local alias.clean-safe > /dev/null; then
+    echo "ERROR: protect-clean --undo left the alias behind"
+    exit 1
+fi
+echo "VERIFY: protect-clean --undo removes the alias."
+
+# Scenario 34.5: status and doctor notice a checkout removed behind git-trunk's back
+echo "INFO: Scenario 5: lost checkouts"
+git clean -ffdxq
+$GIT_TRUNK_CMD status --local > status.out 2> status.err
+if [ "$(cat status.out)" != "main: checkout removed outside git-trunk" ] || ! grep -q "\.trunk/main disappeared" status.err; then
+    echo "ERROR: status did not report the lost checkout:"; cat status.out status.err
+    exit 1
+fi
+$GIT_TRUNK_CMD doctor --fix 2> doctor.log
+if ! grep -q "draft" .trunk/main/wip.md || ! grep -qx ".trunk" .gitignore; then
+    echo "ERROR: doctor --fix did not restore .trunk/main:"; cat doctor.log
+    exit 1
+fi
+$GIT_TRUNK_CMD delete --local-only --no-backup 2> /dev/null
+if $GIT_TRUNK_CMD doctor 2>&1 | grep -q "disappeared"; then
+    echo "ERROR: doctor reported a checkout git trunk delete removed"
+    exit 1
+fi
+echo "VERIFY: status and doctor report a lost checkout, doctor --fix restores it from the ref, and delete is not reported."
+
+# Scenario 34.6: [trunk] auto_restore lets the hooks restore it
+echo "INFO: Scenario 6: auto_restore"
+$GIT_TRUNK_CMD undelete main --local-only 2> /dev/null
+$GIT_TRUNK_CMD checkout 2> /dev/null
+$GIT_TRUNK_CMD config set trunk.auto_restore true
+$GIT_TRUNK_CMD hooks install --force 2> /dev/null
+git clean -ffdxq
+git commit -q --allow-empty -m "Trigger the post-commit hook" 2> hook.log
+if ! grep -q "draft" .trunk/main/wip.md; then
+    echo "ERROR: the post-commit hook did not restore .trunk/main:"; cat hook.log
+    exit 1
+fi
+echo "VERIFY: with auto_restore, the next git-trunk hook recreates the checkout."
+
+echo "SUCCESS: test_protect_clean completed."
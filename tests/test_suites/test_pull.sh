@@ -0,0 +1,108 @@
+#!/bin/bash
+# test_pull.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Commits `text` as <file> to store notes in the current repository
+commit_file() {
+    local file="$1" text="$2"
+    echo "$text" > ".trunk/notes/$file"
+    $GIT_TRUNK_CMD --store notes commit --force -m "Update $file" 2> /dev/null
+}
+
+# Fails with <message> unless refs/trunk/notes has no merge commits
+expect_linear() {
+    if [ -n "$(git rev-list --merges refs/trunk/notes)" ]; then
+        echo "ERROR: $1: refs/trunk/notes has merge commits:"; git log --oneline --graph refs/trunk/notes
+        exit 1
+    fi
+}
+
+echo "INFO: Starting test_pull in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote.git
+for clone in alice bob; do
+    git init -q -b main "$clone"
+    (cd "$clone" && git config user.email "$clone@example.com" && git config user.name "$clone" && git commit -q --allow-empty -m "Initial commit" && git remote add origin ../remote.git)
+done
+cd alice
+$GIT_TRUNK_CMD --store notes init 2> /dev/null
+commit_file shared.md "v1"
+$GIT_TRUNK_CMD --store notes push 2> /dev/null
+cd ../bob
+$GIT_TRUNK_CMD --store notes checkout 2> /dev/null
+
+# Scenario 37.1: local commits are replayed on top of the remote ones
+echo "INFO: Scenario 1: pull --rebase"
+(cd ../alice && commit_file alice.md "from alice" && $GIT_TRUNK_CMD --store notes push 2> /dev/null)
+commit_file bob.md "from bob"
+echo "draft" > .trunk/notes/draft.md
+$GIT_TRUNK_CMD --store notes pull --rebase
+expect_linear "pull --rebase"
+if [ "$(git show refs/trunk/notes:alice.md)" != "from alice" ] || [ "$(git log -1 --format=%s refs/trunk/notes)" != "Update bob.md" ] || [ ! -f .trunk/notes/draft.md ]; then
+    echo "ERROR: pull --rebase did not put the local commit on top of the remote one, or lost uncommitted files"
+    git log --oneline refs/trunk/notes
+    exit 1
+fi
+rm .trunk/notes/draft.md
+$GIT_TRUNK_CMD --store notes push 2> /dev/null
+echo "VERIFY: pull --rebase keeps the history linear and the uncommitted files."
+
+# Scenario 37.2: conflicts stop the rebase until --continue
+echo "INFO: Scenario 2: pull --rebase with conflicts, then --continue"
+(cd ../alice && $GIT_TRUNK_CMD --store notes pull 2> /dev/null && commit_file shared.md "alice v2" && $GIT_TRUNK_CMD --store notes push 2> /dev/null)
+commit_file shared.md "bob v2"
+before=$(git rev-parse refs/trunk/notes)
+set +e
+$GIT_TRUNK_CMD --store notes pull --rebase 2> /dev/null
+status=$?
+$GIT_TRUNK_CMD --store notes pull 2> pull.log
+second_status=$?
+set -e
+if [ "$status" != "4" ] || [ "$second_status" != "4" ] || ! grep -q "pull --continue" pull.log || [ "$(git rev-parse refs/trunk/notes)" != "$before" ]; then
+    echo "ERROR: a conflicting rebase did not stop with exit code 4 and keep refs/trunk/notes ($status, $second_status):"; cat pull.log
+    exit 1
+fi
+echo "bob and alice v2" > .trunk/notes/shared.md
+git -C .trunk/notes add shared.md
+$GIT_TRUNK_CMD --store notes pull --continue
+expect_linear "pull --continue"
+if [ "$(git show refs/trunk/notes:shared.md)" != "bob and alice v2" ] || ! git merge-base --is-ancestor "$(git --git-dir=../remote.git rev-parse refs/trunk/notes)" refs/trunk/notes; then
+    echo "ERROR: pull --continue did not finish the rebase onto alice's commit"
+    git log --oneline refs/trunk/notes
+    exit 1
+fi
+$GIT_TRUNK_CMD --store notes push 2> /dev/null
+echo "VERIFY: a conflicting rebase stops, blocks further pulls and finishes with --continue."
+
+# Scenario 37.3: --abort puts everything back
+echo "INFO: Scenario 3: pull --abort"
+(cd ../alice && $GIT_TRUNK_CMD --store notes pull 2> /dev/null && commit_file shared.md "alice v3" && $GIT_TRUNK_CMD --store notes push 2> /dev/null)
+commit_file shared.md "bob v3"
+before=$(git rev-parse refs/trunk/notes)
+set +e
+$GIT_TRUNK_CMD --store notes pull --rebase 2> /dev/null
+set -e
+$GIT_TRUNK_CMD --store notes pull --abort
+if [ "$(cat .trunk/notes/shared.md)" != "bob v3" ] || [ "$(git -C .trunk/notes rev-parse main)" != "$before" ] || [ "$(git rev-parse refs/trunk/notes)" != "$before" ]; then
+    echo "ERROR: pull --abort did not restore the store"
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD --store notes pull --continue 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "1" ]; then
+    echo "ERROR: pull --continue without a rebase in progress exited with $status, expected 1"
+    exit 1
+fi
+echo "VERIFY: pull --abort restores the checkout and the ref; --continue needs a rebase in progress."
+
+echo "SUCCESS: test_pull completed."
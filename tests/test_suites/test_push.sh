@@ -0,0 +1,189 @@
+#!/bin/bash
+# test_push.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Runs a push that must fail with exit code 4 and mention `text`
+expect_conflict() {
+    local description="$1" text="$2" status
+    shift 2
+    set +e
+    "$@" 2> push.log
+    status=$?
+    set -e
+    if [ "$status" != "4" ] || ! grep -q -- "$text" push.log; then
+        echo "ERROR: $description: expected exit code 4 mentioning '$text', got $status:"; cat push.log
+        exit 1
+    fi
+}
+
+echo "INFO: Starting test_push in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git init -q --bare remote_repo.git
+git init -q --bare alt_remote.git
+git init -q -b main main_repo
+cd main_repo
+git config user.email "test@example.com"
+git config user.name "Test User"
+git commit -q --allow-empty -m "Initial commit"
+git remote add origin ../remote_repo.git
+git remote add alternate ../alt_remote.git
+$GIT_TRUNK_CMD init
+echo "# Notes" > .trunk/main/readme.md
+$GIT_TRUNK_CMD commit --force
+
+# Scenario 3.1: basic push
+echo "INFO: Scenario 1: push"
+$GIT_TRUNK_CMD push
+if [ "$(git ls-remote origin refs/trunk/main | cut -f1)" != "$(git rev-parse refs/trunk/main)" ]; then
+    echo "ERROR: refs/trunk/main was not pushed"
+    exit 1
+fi
+if [ "$(git rev-parse refs/trunk-remotes/origin/main)" != "$(git rev-parse refs/trunk/main)" ]; then
+    echo "ERROR: push did not record the pushed value in refs/trunk-remotes/origin/main"
+    exit 1
+fi
+echo "VERIFY: push updates the remote and records the last-known value."
+
+# Scenario 3.2 and 3.3: a specific store, a specific remote
+echo "INFO: Scenario 2: push --store and --remote"
+$GIT_TRUNK_CMD --store assets init
+$GIT_TRUNK_CMD --store assets commit --force
+$GIT_TRUNK_CMD --store assets --remote alternate push
+if [ -z "$(git ls-remote alternate refs/trunk/assets)" ] || [ -n "$(git ls-remote alternate refs/trunk/main)" ]; then
+    echo "ERROR: push --store assets --remote alternate pushed the wrong refs"
+    exit 1
+fi
+echo "VERIFY: push sends only the selected store to the selected remote."
+
+# Scenario 3.4: nothing to push
+echo "INFO: Scenario 3: push without a local ref"
+set +e
+$GIT_TRUNK_CMD --store missing push 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "3" ]; then
+    echo "ERROR: pushing a store without a local ref exited with $status, expected 3"
+    exit 1
+fi
+echo "VERIFY: pushing a missing store fails with exit code 3."
+
+# Scenario 3.5: a non-fast-forward push is refused
+echo "INFO: Scenario 4: divergence"
+remote_commit=$(git commit-tree -p refs/trunk/main -m "Pushed from elsewhere" "refs/trunk/main^{tree}")
+git push -q --no-verify origin "$remote_commit:refs/trunk/main"
+echo "local change" >> .trunk/main/readme.md
+$GIT_TRUNK_CMD commit --force
+expect_conflict "a diverged push" "has commits that are not in the local store" $GIT_TRUNK_CMD push
+if [ "$(git ls-remote origin refs/trunk/main | cut -f1)" != "$remote_commit" ]; then
+    echo "ERROR: a refused push changed the remote"
+    exit 1
+fi
+echo "VERIFY: push refuses to replace remote commits the local store lacks."
+
+# Scenario 3.6: --force-with-lease only replaces what was last seen
+echo "INFO: Scenario 5: --force-with-lease"
+expect_conflict "a stale lease" "moved since it was last fetched" $GIT_TRUNK_CMD push --force-with-lease
+$GIT_TRUNK_CMD fetch 2> /dev/null
+$GIT_TRUNK_CMD push --force-with-lease
+if [ "$(git ls-remote origin refs/trunk/main | cut -f1)" != "$(git rev-parse refs/trunk/main)" ]; then
+    echo "ERROR: push --force-with-lease did not replace the fetched remote ref"
+    exit 1
+fi
+echo "VERIFY: --force-with-lease replaces the remote ref only when it is where it was last seen."
+
+# Scenario 3.7: --force
+echo "INFO: Scenario 6: --force"
+remote_commit=$(git commit-tree -p refs/trunk/main -m "Pushed from elsewhere again" "refs/trunk/main^{tree}")
+git push -q --no-verify origin "$remote_commit:refs/trunk/main"
+$GIT_TRUNK_CMD push --force 2> push.log
+if [ "$(git ls-remote origin refs/trunk/main | cut -f1)" != "$(git rev-parse refs/trunk/main)" ] || ! grep -q "Warning: --force" push.log; then
+    echo "ERROR: push --force did not replace the remote ref with a warning:"; cat push.log
+    exit 1
+fi
+echo "VERIFY: --force replaces the remote ref and says so."
+
+# Scenario 3.8: pushing for review
+echo "INFO: Scenario 7: [remote.<name>] review_ref"
+git init -q --bare ../review_remote.git
+git remote add review ../review_remote.git
+set +e
+$GIT_TRUNK_CMD config set remote.review.review_ref 'for/{store}' 2> /dev/null
+status=$?
+set -e
+if [ "$status" = "0" ]; then
+    echo "ERROR: a review_ref that is not a full ref name was accepted"
+    exit 1
+fi
+$GIT_TRUNK_CMD config set remote.review.review_ref 'refs/for/refs/trunk/{store}'
+$GIT_TRUNK_CMD --remote review push
+if git --git-dir=../review_remote.git rev-parse -q --verify refs/trunk/main > /dev/null || [ "$(git --git-dir=../review_remote.git rev-parse refs/for/refs/trunk/main)" != "$(git rev-parse refs/trunk/main)" ]; then
+    echo "ERROR: push did not go to the review ref:"; git ls-remote review
+    exit 1
+fi
+if [ "$($GIT_TRUNK_CMD --remote review status --format '{remote_state}')" != "pending-review" ]; then
+    echo "ERROR: status did not report the pending review"
+    exit 1
+fi
+$GIT_TRUNK_CMD --remote review push 2> push.log
+if ! grep -q "already pending review" push.log; then
+    echo "ERROR: pushing the same commit for review again was not skipped:"; cat push.log
+    exit 1
+fi
+set +e
+$GIT_TRUNK_CMD --remote review push --force 2> /dev/null
+status=$?
+set -e
+if [ "$status" != "2" ]; then
+    echo "ERROR: push --force to a review remote exited with $status, expected 2"
+    exit 1
+fi
+# The review server merges the change
+git --git-dir=../review_remote.git update-ref refs/trunk/main "$(git rev-parse refs/trunk/main)"
+if [ "$($GIT_TRUNK_CMD --remote review status --format '{remote_state}')" != "same" ]; then
+    echo "ERROR: status did not report the merged change as in sync"
+    exit 1
+fi
+$GIT_TRUNK_CMD --remote review push
+if git rev-parse -q --verify refs/trunk-review/review/main > /dev/null; then
+    echo "ERROR: the pending review marker was kept after the merge"
+    exit 1
+fi
+echo "VERIFY: review_ref pushes go to the review ref, status reports pending-review until the change is merged."
+
+# Scenario 3.9: export-branch publishes the store as a branch and keeps it in sync
+echo "INFO: Scenario 8: export-branch"
+set +e
+$GIT_TRUNK_CMD export-branch 2> /dev/null
+unnamed=$?
+set -e
+$GIT_TRUNK_CMD export-branch --branch pages 2> /dev/null
+first=$(git ls-remote origin refs/heads/pages | cut -f1)
+first_tip=$(git rev-parse refs/trunk/main)
+echo "More notes" >> .trunk/main/readme.md
+$GIT_TRUNK_CMD commit --force 2> /dev/null
+$GIT_TRUNK_CMD export-branch 2> /dev/null
+if [ "$unnamed" != "2" ] || [ "$first" != "$first_tip" ] || [ "$($GIT_TRUNK_CMD config get store.main.export_branch 2> /dev/null)" != "pages" ] \
+    || [ "$(git rev-parse refs/heads/pages)" != "$(git rev-parse refs/trunk/main)" ] || [ "$(git ls-remote origin refs/heads/pages | cut -f1)" != "$(git rev-parse refs/trunk/main)" ]; then
+    echo "ERROR: export-branch did not create and then update the branch (exit code without a branch: $unnamed, expected 2):"; git ls-remote origin
+    exit 1
+fi
+# The hosting platform commits on the branch
+platform_commit=$(git commit-tree -p refs/trunk/main -m "Edited on the platform" "refs/trunk/main^{tree}")
+git push -q --no-verify origin "$platform_commit:refs/heads/pages"
+expect_conflict "export-branch over a branch with its own commits" "has commits that are not in store" $GIT_TRUNK_CMD export-branch
+$GIT_TRUNK_CMD export-branch --force 2> /dev/null
+if [ "$(git ls-remote origin refs/heads/pages | cut -f1)" != "$(git rev-parse refs/trunk/main)" ]; then
+    echo "ERROR: export-branch --force did not replace the branch on the remote"
+    exit 1
+fi
+echo "VERIFY: export-branch needs a branch the first time, records it, fast-forwards the local and remote branch on later runs, and replaces commits made on the branch only with --force."
+
+echo "SUCCESS: test_push completed."
@@ -0,0 +1,67 @@
+#!/bin/bash
+# test_put.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_put in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+$GIT_TRUNK_CMD --store docs commit --force -m "Start" 2> /dev/null
+echo "# API" > api.md
+
+# Scenario 43.1: a file and standard input are committed to the ref and the checkout follows
+echo "INFO: Scenario 1: put"
+$GIT_TRUNK_CMD --store docs put api.md reference/ -m "Regenerate the API reference" 2> /dev/null
+echo "Remember." | $GIT_TRUNK_CMD --store docs put - notes/memo.md 2> /dev/null
+if [ "$(git log -2 --format=%s refs/trunk/docs | tr '\n' '|')" != "Put notes/memo.md|Regenerate the API reference|" ] \
+    || [ "$(git show refs/trunk/docs:reference/api.md)" != "# API" ] || [ "$(cat .trunk/docs/notes/memo.md)" != "Remember." ] \
+    || [ -n "$(git status --porcelain --untracked-files=no)" ]; then
+    echo "ERROR: put did not commit the file and standard input:"; git log --format=%s refs/trunk/docs
+    exit 1
+fi
+tip=$(git rev-parse refs/trunk/docs)
+$GIT_TRUNK_CMD --store docs put api.md reference/ 2> same.log
+if [ "$(git rev-parse refs/trunk/docs)" != "$tip" ] || ! grep -q "nothing to commit" same.log; then
+    echo "ERROR: put of unchanged content made a commit:"; cat same.log
+    exit 1
+fi
+echo "VERIFY: put commits a file (a trailing / keeps its name) or standard input to refs/trunk/<store>, fast-forwards .trunk/<store>, leaves the main worktree alone and skips unchanged content."
+
+# Scenario 43.2: uncommitted changes elsewhere in the checkout are kept
+echo "INFO: Scenario 2: put with other uncommitted changes"
+echo "Draft" > .trunk/docs/draft.md
+echo "# API v2" > api.md
+$GIT_TRUNK_CMD --store docs put api.md reference/api.md 2> /dev/null
+if [ "$(cat .trunk/docs/reference/api.md)" != "# API v2" ] || [ "$(cat .trunk/docs/draft.md)" != "Draft" ]; then
+    echo "ERROR: put did not update the checkout or lost its uncommitted files"
+    exit 1
+fi
+echo "VERIFY: put fast-forwards a checkout with unrelated uncommitted changes and keeps them."
+
+# Scenario 43.3: errors
+echo "INFO: Scenario 3: put errors"
+tip=$(git rev-parse refs/trunk/docs)
+echo "Local edit" >> .trunk/docs/reference/api.md
+echo "# API v3" > api.md
+set +e
+$GIT_TRUNK_CMD --store docs put api.md reference/ 2> /dev/null
+dirty=$?
+$GIT_TRUNK_CMD --store missing put api.md 2> /dev/null
+missing=$?
+$GIT_TRUNK_CMD --store docs put no-such-file.md 2> /dev/null
+unreadable=$?
+set -e
+if [ "$dirty" != "4" ] || [ "$missing" != "3" ] || [ "$unreadable" != "6" ] \
+    || [ "$(git rev-parse refs/trunk/docs)" != "$tip" ] || ! grep -q "Local edit" .trunk/docs/reference/api.md; then
+    echo "ERROR: put over uncommitted changes exited with $dirty (expected 4), to a missing store with $missing (expected 3), of a missing file with $unreadable (expected 6)"
+    exit 1
+fi
+echo "VERIFY: put exits with 4 when the checkout has uncommitted changes to the path, with 3 for a store without a ref and with 6 for an unreadable file, committing nothing."
+
+echo "SUCCESS: test_put completed."
@@ -0,0 +1,60 @@
+#!/bin/bash
+# test_query.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_query in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+printf -- '---\ntitle: Plan\nstatus: draft\npriority: 2\n---\nThe plan.\n' > .trunk/docs/plan.md
+printf -- '---\ntitle: Spec\nstatus: done\npriority: 1\n---\nThe spec.\n' > .trunk/docs/spec.md
+echo "No frontmatter here." > .trunk/docs/notes.txt
+$GIT_TRUNK_CMD --store docs commit --force -m "Plan and spec" 2> /dev/null
+
+# Scenario 15.1: --where, --select and --json
+echo "INFO: Scenario 1: query"
+$GIT_TRUNK_CMD --store docs query --where 'status == "draft"' --select path,title --json > draft.json 2> /dev/null
+$GIT_TRUNK_CMD --store docs query --where 'priority >= 1 and not status == "done"' --no-table > open.out 2> /dev/null
+$GIT_TRUNK_CMD --store docs query --select path,status > all.out 2> /dev/null
+if [ "$(cat draft.json)" != '[{"path":"plan.md","title":"Plan"}]' ] \
+    || ! grep -q "^path: plan.md$" open.out || grep -q "spec.md" open.out \
+    || ! grep -q "^spec.md *done$" all.out || grep -q "notes.txt" all.out; then
+    echo "ERROR: query did not filter and select the frontmatter:"; cat draft.json open.out all.out
+    exit 1
+fi
+echo "VERIFY: query filters documents with --where, outputs the --select fields as JSON, lines or a table, and skips files without frontmatter."
+
+# Scenario 15.2: --rev queries an earlier state of the store
+echo "INFO: Scenario 2: query --rev"
+sed -i 's/status: draft/status: done/' .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Finish the plan" 2> /dev/null
+$GIT_TRUNK_CMD --store docs query --where 'status == "draft"' --select path --json > now.json 2> /dev/null
+$GIT_TRUNK_CMD --store docs query --rev refs/trunk/docs~1 --where 'status == "draft"' --select path --json > before.json 2> /dev/null
+if [ "$(cat now.json)" != '[]' ] || [ "$(cat before.json)" != '[{"path":"plan.md"}]' ]; then
+    echo "ERROR: query --rev did not read the earlier commit:"; cat now.json before.json
+    exit 1
+fi
+echo "VERIFY: query reads refs/trunk/<store> by default and any revision with --rev."
+
+# Scenario 15.3: errors
+echo "INFO: Scenario 3: query errors"
+set +e
+$GIT_TRUNK_CMD --store docs query --where 'status ==' 2> invalid.err
+invalid=$?
+$GIT_TRUNK_CMD --store missing query 2> missing.err
+missing=$?
+set -e
+if [ "$invalid" != "2" ] || ! grep -q "Invalid --where expression" invalid.err \
+    || [ "$missing" != "5" ] || ! grep -q "git trunk commit --store missing" missing.err; then
+    echo "ERROR: an invalid expression exited with $invalid (expected 2), a missing store with $missing (expected 5):"; cat invalid.err missing.err
+    exit 1
+fi
+echo "VERIFY: An invalid --where expression exits with 2 and a store without a ref with 5, pointing to commit."
+
+echo "SUCCESS: test_query completed."
@@ -0,0 +1,65 @@
+#!/bin/bash
+# test_release_notes.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_release_notes in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store news init 2> /dev/null
+mkdir -p .trunk/news/changes
+echo "Add dark mode." > .trunk/news/changes/12.feature.md
+echo "Fix the crash on start." > .trunk/news/changes/7.bugfix.md
+echo "Tidy up." > .trunk/news/changes/tidy.md
+$GIT_TRUNK_CMD --store news commit --force -m "Fragments" 2> /dev/null
+
+# Scenario 21.1: --dry-run prints the section and changes nothing
+echo "INFO: Scenario 1: release-notes --dry-run"
+tip=$(git rev-parse refs/trunk/news)
+$GIT_TRUNK_CMD --store news --dry-run release-notes --version 1.0.0 --date 2026-01-01 > preview.out 2> /dev/null
+if ! grep -q "^## 1.0.0 (2026-01-01)$" preview.out || ! grep -q "^- Add dark mode. (#12)$" preview.out \
+    || [ "$(git rev-parse refs/trunk/news)" != "$tip" ] || [ ! -f .trunk/news/changes/tidy.md ] || [ -e .trunk/news/CHANGELOG.md ]; then
+    echo "ERROR: release-notes --dry-run did not preview the section or changed something:"; cat preview.out
+    exit 1
+fi
+echo "VERIFY: --dry-run prints the section and leaves the fragments and the ref alone."
+
+# Scenario 21.2: the section is written, the fragments removed and both committed
+echo "INFO: Scenario 2: release-notes"
+$GIT_TRUNK_CMD --store news release-notes --version 1.0.0 --date 2026-01-01 2> /dev/null
+git show refs/trunk/news:CHANGELOG.md > changelog.md
+if [ "$(git log -1 --format=%s refs/trunk/news)" != "Release notes for 1.0.0" ] || [ -n "$(git ls-tree --name-only refs/trunk/news changes/)" ] \
+    || ! grep -q "^### Features$" changelog.md || ! grep -q "^- Fix the crash on start. (#7)$" changelog.md \
+    || [ "$(grep -A2 "^### Other$" changelog.md | tail -1)" != "- Tidy up." ]; then
+    echo "ERROR: release-notes did not commit the section and remove the fragments:"; cat changelog.md
+    exit 1
+fi
+echo "VERIFY: release-notes writes the fragments under their headings into CHANGELOG.md, removes them and commits both."
+
+# Scenario 21.3: errors
+echo "INFO: Scenario 3: release-notes errors"
+set +e
+$GIT_TRUNK_CMD --store news release-notes --version 1.1.0 2> /dev/null
+empty=$?
+echo "Faster sync." > .trunk/news/changes/20.feature.md
+echo "Draft" > .trunk/news/draft.md
+$GIT_TRUNK_CMD --store news release-notes --version 1.1.0 2> /dev/null
+dirty=$?
+rm .trunk/news/draft.md
+$GIT_TRUNK_CMD --store news release-notes --version 1.0.0 2> /dev/null
+again=$?
+$GIT_TRUNK_CMD --store missing release-notes --version 1.0.0 2> /dev/null
+missing=$?
+set -e
+if [ "$empty" != "1" ] || [ "$dirty" != "4" ] || [ "$again" != "4" ] || [ "$missing" != "3" ] || [ ! -f .trunk/news/changes/20.feature.md ]; then
+    echo "ERROR: no fragments exited with $empty (expected 1), other changes with $dirty (expected 4), an existing version with $again (expected 4), a missing store with $missing (expected 3)"
+    exit 1
+fi
+echo "VERIFY: release-notes without fragments exits with 1, with other uncommitted changes or an existing section with 4, and for a store that is not checked out with 3."
+
+echo "SUCCESS: test_release_notes completed."
@@ -0,0 +1,124 @@
+#!/bin/bash
+# test_remote_transports.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+# ssh-like and https-like remotes are served from local bare repositories by
+# rewriting their URLs with url.<base>.insteadOf, so no network is needed.
+FIXTURE_CONFIG="$TEST_DIR/gitconfig"
+
+expect_remote_ref() {
+    local remote="$1" expected="$2" actual
+    actual=$(git ls-remote "$remote" refs/trunk/main | awk '{print $1}')
+    if [ "$actual" != "$expected" ]; then
+        echo "ERROR: refs/trunk/main on '$remote' is '$actual', expected '$expected'"
+        exit 1
+    fi
+    echo "VERIFY: refs/trunk/main on '$remote' is ${expected:-absent}."
+}
+
+setup_repo() {
+    local name="$1" url="$2"
+    mkdir "$name"
+    cd "$name"
+    git init -q -b main
+    git config user.email "test@example.com"
+    git config user.name "Test User"
+    git remote add origin "$url"
+    touch initial_file.txt
+    git add .
+    git commit -q -m "Initial commit"
+}
+
+# Runs the push / checkout / pull / status / delete round trip against one remote URL.
+run_flow() {
+    local kind="$1" url="$2"
+    echo "INFO: Scenario $kind: remote $url"
+    git init -q --bare "${kind}_remote.git"
+
+    setup_repo "${kind}_source" "$url"
+    $GIT_TRUNK_CMD init </dev/null
+    echo "$kind" > .trunk/main/transport.md
+    $GIT_TRUNK_CMD commit --force -m "Add transport note"
+    $GIT_TRUNK_CMD push
+    local pushed
+    pushed=$(git rev-parse refs/trunk/main)
+    expect_remote_ref origin "$pushed"
+    if ! $GIT_TRUNK_CMD info --short --no-table | grep -q "Remote Ref: ✓ ${pushed:0:7}"; then
+        echo "ERROR: info does not report the remote ref for $kind"
+        exit 1
+    fi
+    echo "VERIFY: info reports the remote ref."
+    cd ..
+
+    setup_repo "${kind}_clone" "$url"
+    $GIT_TRUNK_CMD checkout </dev/null
+    if [ "$(cat .trunk/main/transport.md)" != "$kind" ]; then
+        echo "ERROR: checkout over $kind did not bring the store contents"
+        exit 1
+    fi
+    echo "VERIFY: checkout fetched the store."
+    echo "more" >> .trunk/main/transport.md
+    $GIT_TRUNK_CMD commit --force -m "Extend note"
+    $GIT_TRUNK_CMD push
+    cd ..
+
+    cd "${kind}_source"
+    $GIT_TRUNK_CMD pull
+    if ! grep -q "more" .trunk/main/transport.md; then
+        echo "ERROR: pull over $kind did not merge the remote change"
+        exit 1
+    fi
+    echo "VERIFY: pull merged the remote change."
+    if ! $GIT_TRUNK_CMD status --porcelain | grep -q "remote=same"; then
+        echo "ERROR: status does not report the store in sync over $kind"
+        exit 1
+    fi
+    echo "VERIFY: status reports the store in sync."
+    $GIT_TRUNK_CMD delete --yes
+    expect_remote_ref origin ""
+    cd ..
+}
+
+echo "INFO: Starting test_remote_transports in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+git config -f "$FIXTURE_CONFIG" url."$TEST_DIR/ssh_remote.git".insteadOf "ssh://git@git.example.com/team/ssh.git"
+git config -f "$FIXTURE_CONFIG" url."$TEST_DIR/https_remote.git".insteadOf "https://git.example.com/team/https.git"
+git config -f "$FIXTURE_CONFIG" include.path "${GIT_CONFIG_GLOBAL:-$HOME/.gitconfig}"
+export GIT_CONFIG_GLOBAL="$FIXTURE_CONFIG"
+
+run_flow ssh "ssh://git@git.example.com/team/ssh.git"
+run_flow https "https://git.example.com/team/https.git"
+run_flow fileurl "file://$TEST_DIR/fileurl_remote.git"
+run_flow abspath "$TEST_DIR/abspath_remote.git"
+run_flow relpath "../relpath_remote.git"
+
+# A relative --remote path is relative to the current directory, not the repository root
+echo "INFO: Scenario: relative --remote from a subdirectory"
+git init -q --bare adhoc_remote.git
+setup_repo adhoc_source "../relpath_remote.git"
+$GIT_TRUNK_CMD init </dev/null
+$GIT_TRUNK_CMD commit --force -m "Adhoc"
+mkdir -p docs/deep
+cd docs/deep
+$GIT_TRUNK_CMD push --remote ../../../adhoc_remote.git
+cd ../..
+expect_remote_ref ../adhoc_remote.git "$(git rev-parse refs/trunk/main)"
+cd ..
+
+# An unreachable path remote is reported as such, not as a missing ref
+echo "INFO: Scenario: unreachable path remote"
+cd adhoc_source
+if $GIT_TRUNK_CMD info --short --no-table --remote ../does_not_exist.git | grep -q "Remote Ref: ✗ missing"; then
+    echo "ERROR: info reports an unreachable remote as a missing ref"
+    exit 1
+fi
+echo "VERIFY: info does not mistake an unreachable remote for a missing ref."
+cd ..
+
+echo "INFO: test_remote_transports PASSED"
@@ -0,0 +1,69 @@
+#!/bin/bash
+# test_rename.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+# Succeeds if the remote has refs/trunk/<store>
+on_remote() {
+    [ -n "$(git --git-dir=../remote.git for-each-ref "refs/trunk/$1")" ]
+}
+
+echo "INFO: Starting test_rename in $TEST_DIR"
+setup_repo --remote
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+echo "Plan" > .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Plan" 2> /dev/null
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+$GIT_TRUNK_CMD config set store.docs.auto_push false 2> /dev/null
+$GIT_TRUNK_CMD config set trunk.store docs 2> /dev/null
+$GIT_TRUNK_CMD --store notes init 2> /dev/null
+$GIT_TRUNK_CMD --store notes commit --force -m "Notes" 2> /dev/null
+tip=$(git rev-parse refs/trunk/docs)
+
+# Scenario 49.1: the ref moves here and on the remote, with the checkout and the config
+echo "INFO: Scenario 1: rename"
+echo "Draft" > .trunk/docs/draft.md
+$GIT_TRUNK_CMD rename docs guide 2> /dev/null
+if git show-ref -q refs/trunk/docs || on_remote docs || [ -e .trunk/docs ] \
+    || [ "$(git rev-parse refs/trunk/guide)" != "$tip" ] || [ "$(git --git-dir=../remote.git rev-parse refs/trunk/guide)" != "$tip" ] \
+    || [ "$(git rev-parse refs/trunk-remotes/origin/guide)" != "$tip" ] || [ -n "$(git for-each-ref 'refs/trunk-remotes/*/docs')" ] \
+    || [ "$(cat .trunk/guide/draft.md)" != "Draft" ] || [ "$($GIT_TRUNK_CMD config get store.guide.auto_push)" != "false" ] \
+    || [ "$($GIT_TRUNK_CMD config get trunk.store)" != "guide" ]; then
+    echo "ERROR: rename left part of store docs behind:"; git for-each-ref 'refs/trunk*'; cat .git/trunk.toml
+    exit 1
+fi
+echo "VERIFY: rename moves refs/trunk/<store> here and on the remote, refs/trunk-remotes/<remote>/<store>, .trunk/<store> with its uncommitted files, [store.<name>] and [trunk] store."
+
+# Scenario 49.2: --local-only leaves the remote alone
+echo "INFO: Scenario 2: rename --local-only"
+$GIT_TRUNK_CMD rename guide handbook --local-only 2> /dev/null
+if ! git show-ref -q refs/trunk/handbook || ! on_remote guide || on_remote handbook \
+    || [ -n "$(git for-each-ref 'refs/trunk-remotes/*/guide' 'refs/trunk-remotes/*/handbook')" ]; then
+    echo "ERROR: rename --local-only touched the remote or kept the remote-tracking ref:"; git for-each-ref 'refs/trunk*'
+    exit 1
+fi
+echo "VERIFY: --local-only renames the local store only and drops its refs/trunk-remotes/ entries."
+
+# Scenario 49.3: errors
+echo "INFO: Scenario 3: rename errors"
+set +e
+$GIT_TRUNK_CMD rename handbook notes 2> /dev/null
+taken=$?
+$GIT_TRUNK_CMD rename missing anything 2> /dev/null
+missing=$?
+$GIT_TRUNK_CMD rename handbook "bad/../name" 2> /dev/null
+invalid=$?
+set -e
+if [ "$taken" != "4" ] || [ "$missing" != "3" ] || [ "$invalid" != "2" ] || ! git show-ref -q refs/trunk/handbook || [ ! -d .trunk/handbook ]; then
+    echo "ERROR: rename onto an existing store exited with $taken (expected 4), of a missing store with $missing (expected 3), to an invalid name with $invalid (expected 2)"
+    exit 1
+fi
+echo "VERIFY: rename refuses existing names with 4, unknown stores with 3 and invalid names with 2, changing nothing."
+
+echo "SUCCESS: test_rename completed."
@@ -0,0 +1,60 @@
+#!/bin/bash
+# test_report.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_report in $TEST_DIR"
+setup_repo --remote
+
+# A store with a document nobody touched for years and a fresh one
+$GIT_TRUNK_CMD --store docs init
+echo "old" > .trunk/docs/old.md
+GIT_AUTHOR_NAME="Old Author" GIT_AUTHOR_DATE="2020-01-01T12:00:00" GIT_COMMITTER_DATE="2020-01-01T12:00:00" $GIT_TRUNK_CMD --store docs commit --force -m "Old document"
+echo "new" > .trunk/docs/new.md
+$GIT_TRUNK_CMD --store docs commit --force -m "New document"
+$GIT_TRUNK_CMD --store docs push
+$GIT_TRUNK_CMD --store notes init
+echo "draft" > .trunk/notes/draft.md
+
+# Scenario 36.1: one dashboard of every store
+echo "INFO: Scenario 1: report --output"
+$GIT_TRUNK_CMD report --output report.html
+if ! grep -q "<strong>docs</strong>" report.html || ! grep -q "<strong>notes</strong>" report.html; then
+    echo "ERROR: the report does not list both stores:"; cat report.html
+    exit 1
+fi
+if ! grep -q "origin: in sync" report.html || ! grep -q "origin: missing" report.html || ! grep -q "1 uncommitted" report.html; then
+    echo "ERROR: the report does not show the store status:"; cat report.html
+    exit 1
+fi
+if ! grep -q ", Old Author</td>" report.html || ! grep -q "<polyline" report.html; then
+    echo "ERROR: the report does not show the last authors and the size trend:"; cat report.html
+    exit 1
+fi
+echo "VERIFY: report lists every store with its status, authors and size trend."
+
+# Scenario 36.2: stale documents
+echo "INFO: Scenario 2: stale documents"
+if ! grep -q 'id="stale-docs"' report.html || ! grep -q "<td>old.md</td><td>2020-01-01" report.html || grep -q "<td>new.md</td>" report.html; then
+    echo "ERROR: the report did not list only the old document as stale:"; cat report.html
+    exit 1
+fi
+echo "VERIFY: documents unchanged for --stale-days are listed as stale."
+
+# Scenario 36.3: offline, to stdout
+echo "INFO: Scenario 3: report --output - --local"
+rm -rf ../remote.git
+$GIT_TRUNK_CMD report --output - --local > stdout.html
+if ! grep -q "origin: not checked" stdout.html || ! grep -q "</html>" stdout.html; then
+    echo "ERROR: report --output - --local did not print an offline report:"; cat stdout.html
+    exit 1
+fi
+echo "VERIFY: --local skips the remote and --output - prints the report."
+
+echo "SUCCESS: test_report completed."
@@ -0,0 +1,87 @@
+#!/bin/bash
+# test_revert_reset.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_revert_reset in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+echo "Alpha" > .trunk/docs/alpha.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Add alpha" 2> /dev/null
+echo "Beta" > .trunk/docs/beta.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Add beta" 2> /dev/null
+
+# Scenario 47.1: revert commits the inverse on top of the ref and the checkout follows
+echo "INFO: Scenario 1: revert"
+$GIT_TRUNK_CMD --store docs revert refs/trunk/docs -m "Drop beta" 2> /dev/null
+if [ "$(git log -2 --format=%s refs/trunk/docs | tr '\n' '|')" != "Drop beta|Add beta|" ] \
+    || git cat-file -e refs/trunk/docs:beta.md 2> /dev/null || [ -e .trunk/docs/beta.md ]; then
+    echo "ERROR: revert did not commit the inverse of 'Add beta':"; git log --oneline refs/trunk/docs
+    exit 1
+fi
+echo "VERIFY: revert undoes a commit with a new commit on refs/trunk/<store> and fast-forwards .trunk/<store>."
+
+# Scenario 47.2: revert errors
+echo "INFO: Scenario 2: revert errors"
+tip=$(git rev-parse refs/trunk/docs)
+set +e
+$GIT_TRUNK_CMD --store docs revert "$(git rev-list --max-parents=0 refs/trunk/docs)" 2> /dev/null
+first=$?
+$GIT_TRUNK_CMD --store docs revert main 2> /dev/null
+outside=$?
+$GIT_TRUNK_CMD --store missing revert main 2> /dev/null
+missing=$?
+set -e
+if [ "$first" != "2" ] || [ "$outside" != "3" ] || [ "$missing" != "3" ] || [ "$(git rev-parse refs/trunk/docs)" != "$tip" ]; then
+    echo "ERROR: revert of the first commit exited with $first (expected 2), of a commit outside the store with $outside (expected 3), in a missing store with $missing (expected 3)"
+    exit 1
+fi
+echo "VERIFY: revert refuses the first commit of a store with 2, and commits outside the store or stores without a ref with 3."
+
+# Scenario 47.3: reset asks first, then moves the ref and the checkout and keeps a backup
+echo "INFO: Scenario 3: reset"
+$GIT_TRUNK_CMD config set trunk.prompt no 2> /dev/null
+env -u GIT_TRUNK_NONINTERACTIVE $GIT_TRUNK_CMD --store docs reset --to refs/trunk/docs~2 2> declined.log
+$GIT_TRUNK_CMD config unset trunk.prompt 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" != "$tip" ] || ! grep -q "dropping .* Drop beta" declined.log; then
+    echo "ERROR: a declined reset moved the ref or did not list the commits it drops:"; cat declined.log
+    exit 1
+fi
+$GIT_TRUNK_CMD --store docs reset --to refs/trunk/docs~2 > /dev/null 2> /dev/null
+backup=$(git for-each-ref --format='%(refname)' 'refs/trunk-backup/docs-*')
+if [ "$(git log -1 --format=%s refs/trunk/docs)" != "Add alpha" ] || [ "$(git -C .trunk/docs rev-parse HEAD)" != "$(git rev-parse refs/trunk/docs)" ] \
+    || [ -z "$backup" ] || [ "$(git rev-parse "$backup")" != "$tip" ]; then
+    echo "ERROR: reset did not move refs/trunk/docs and .trunk/docs, or keep $tip in a backup ref ($backup)"
+    exit 1
+fi
+$GIT_TRUNK_CMD --store docs reset --to "$backup" > /dev/null 2> /dev/null
+if [ "$(git rev-parse refs/trunk/docs)" != "$tip" ]; then
+    echo "ERROR: reset --to the backup ref did not undo the reset"
+    exit 1
+fi
+echo "VERIFY: reset lists the commits it drops and changes nothing when declined; confirmed, it moves the ref and the checkout and keeps the old commit in refs/trunk-backup/, from which it can be undone."
+
+# Scenario 47.4: reset errors
+echo "INFO: Scenario 4: reset errors"
+echo "Edited" >> .trunk/docs/alpha.md
+set +e
+$GIT_TRUNK_CMD --store docs reset --to refs/trunk/docs~1 > /dev/null 2> /dev/null
+dirty=$?
+$GIT_TRUNK_CMD --store docs reset --to main > /dev/null 2> /dev/null
+outside=$?
+$GIT_TRUNK_CMD --store missing reset --to main > /dev/null 2> /dev/null
+missing=$?
+set -e
+if [ "$dirty" != "4" ] || [ "$outside" != "2" ] || [ "$missing" != "3" ] || [ "$(git rev-parse refs/trunk/docs)" != "$tip" ] || ! grep -q "Edited" .trunk/docs/alpha.md; then
+    echo "ERROR: reset over uncommitted changes exited with $dirty (expected 4), to a commit outside the store with $outside (expected 2), of a missing store with $missing (expected 3)"
+    exit 1
+fi
+echo "VERIFY: reset refuses a checkout with uncommitted changes with 4, commits outside the store with 2 and stores without a ref with 3."
+
+echo "SUCCESS: test_revert_reset completed."
@@ -0,0 +1,54 @@
+#!/bin/bash
+# test_rm.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_rm in $TEST_DIR"
+setup_repo
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+mkdir -p .trunk/docs/drafts
+echo "One" > .trunk/docs/drafts/one.md
+echo "Two" > .trunk/docs/drafts/two.md
+echo "Plan" > .trunk/docs/plan.md
+echo "Old" > .trunk/docs/old.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Docs" 2> /dev/null
+
+# Scenario 44.1: files and directories go in one commit, and the checkout follows
+echo "INFO: Scenario 1: rm"
+$GIT_TRUNK_CMD --store docs rm drafts docs:old.md -m "Drop the old drafts" 2> /dev/null
+if [ "$(git log -1 --format=%s refs/trunk/docs)" != "Drop the old drafts" ] \
+    || [ "$(git ls-tree -r --name-only refs/trunk/docs | tr '\n' ' ')" != "ABOUT.md plan.md readme.md " ] \
+    || [ -e .trunk/docs/drafts ] || [ -e .trunk/docs/old.md ]; then
+    echo "ERROR: rm did not remove the paths in one commit:"; git ls-tree -r --name-only refs/trunk/docs
+    exit 1
+fi
+echo "VERIFY: rm removes files and directories from refs/trunk/<store> in one commit and from .trunk/<store>."
+
+# Scenario 44.2: errors
+echo "INFO: Scenario 2: rm errors"
+tip=$(git rev-parse refs/trunk/docs)
+set +e
+$GIT_TRUNK_CMD --store docs rm no-such-file.md 2> /dev/null
+unknown=$?
+$GIT_TRUNK_CMD --store docs rm . 2> /dev/null
+root=$?
+echo "Edited" >> .trunk/docs/plan.md
+$GIT_TRUNK_CMD --store docs rm plan.md 2> /dev/null
+dirty=$?
+$GIT_TRUNK_CMD --store missing rm plan.md 2> /dev/null
+missing=$?
+set -e
+if [ "$unknown" != "3" ] || [ "$root" != "2" ] || [ "$dirty" != "4" ] || [ "$missing" != "3" ] \
+    || [ "$(git rev-parse refs/trunk/docs)" != "$tip" ] || ! grep -q "Edited" .trunk/docs/plan.md; then
+    echo "ERROR: rm of an unknown path exited with $unknown (expected 3), of the root with $root (expected 2), of a changed file with $dirty (expected 4), in a missing store with $missing (expected 3)"
+    exit 1
+fi
+echo "VERIFY: rm exits with 3 for paths or stores that do not exist, with 2 for the store's root and with 4 for uncommitted changes under a path, committing nothing."
+
+echo "SUCCESS: test_rm completed."
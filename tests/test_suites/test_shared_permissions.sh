@@ -0,0 +1,108 @@
+#!/bin/bash
+# test_shared_permissions.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+# Octal permission bits of a path (GNU stat, falling back to BSD stat).
+perms() {
+    stat -c '%a' "$1" 2>/dev/null || stat -f '%Lp' "$1"
+}
+
+expect_perms() {
+    local path="$1" expected="$2" actual
+    actual=$(perms "$path")
+    if [ "$actual" != "$expected" ]; then
+        echo "ERROR: $path has permissions $actual, expected $expected"
+        exit 1
+    fi
+    echo "VERIFY: $path has permissions $expected."
+}
+
+setup_repo() {
+    local name="$1"
+    mkdir "$name"
+    cd "$name"
+    git init -q -b main
+    git config user.email "test@example.com"
+    git config user.name "Test User"
+    touch initial_file.txt
+    git add .
+    git commit -q -m "Initial commit"
+}
+
+echo "INFO: Starting test_shared_permissions in $TEST_DIR"
+mkdir -p "$TEST_DIR"
+cd "$TEST_DIR"
+
+# A restrictive umask, as on multi-user servers, must not leak into shared repos.
+umask 077
+
+# Scenario 1: core.sharedRepository=group
+echo "INFO: Scenario 1: core.sharedRepository=group"
+setup_repo group_repo
+git config core.sharedRepository group
+$GIT_TRUNK_CMD init
+expect_perms .trunk 2770
+expect_perms .trunk/main 2770
+if [ "$(git -C .trunk/main config core.sharedRepository)" != "1" ]; then
+    echo "ERROR: .trunk/main was not initialized as a shared repository"
+    exit 1
+fi
+echo "VERIFY: .trunk/main is initialized with core.sharedRepository=group."
+expect_perms .trunk/main/.git/objects 2770
+$GIT_TRUNK_CMD hooks install --force
+expect_perms .git/hooks/post-commit 775
+expect_perms .git/trunk.toml 660
+cd ..
+
+# Scenario 2: core.sharedRepository=all
+echo "INFO: Scenario 2: core.sharedRepository=all"
+setup_repo all_repo
+git config core.sharedRepository all
+$GIT_TRUNK_CMD init
+expect_perms .trunk/main 2775
+$GIT_TRUNK_CMD hooks install --force
+expect_perms .git/hooks/pre-push 775
+expect_perms .git/trunk.toml 664
+cd ..
+
+# Scenario 3: explicit octal mode
+echo "INFO: Scenario 3: core.sharedRepository=0640"
+setup_repo octal_repo
+git config core.sharedRepository 0640
+$GIT_TRUNK_CMD init
+expect_perms .trunk/main 2750
+$GIT_TRUNK_CMD hooks install --force
+expect_perms .git/hooks/post-commit 750
+expect_perms .git/trunk.toml 640
+cd ..
+
+# Scenario 4: checkout recreates the store with shared permissions
+echo "INFO: Scenario 4: checkout honours core.sharedRepository"
+cd group_repo
+$GIT_TRUNK_CMD commit --force
+rm -rf .trunk
+$GIT_TRUNK_CMD checkout --force
+expect_perms .trunk 2770
+expect_perms .trunk/main 2770
+if [ "$(git -C .trunk/main config core.sharedRepository)" != "1" ]; then
+    echo "ERROR: checked out .trunk/main was not initialized as a shared repository"
+    exit 1
+fi
+echo "VERIFY: checked out .trunk/main is a shared repository."
+cd ..
+
+# Scenario 5: without core.sharedRepository the umask is respected
+echo "INFO: Scenario 5: no core.sharedRepository"
+setup_repo private_repo
+$GIT_TRUNK_CMD init
+expect_perms .trunk/main 700
+$GIT_TRUNK_CMD hooks install --force
+expect_perms .git/trunk.toml 600
+cd ..
+
+echo "INFO: test_shared_permissions PASSED"
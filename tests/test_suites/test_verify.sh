@@ -0,0 +1,61 @@
+#!/bin/bash
+# test_verify.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_verify in $TEST_DIR"
+setup_repo --remote
+$GIT_TRUNK_CMD --store docs init 2> /dev/null
+$GIT_TRUNK_CMD --store docs commit --force 2> /dev/null
+$GIT_TRUNK_CMD --store docs push 2> /dev/null
+
+# Scenario 56.1: a sound store passes every check
+echo "INFO: Scenario 1: verify"
+$GIT_TRUNK_CMD --store docs verify --json > verify.json 2> /dev/null
+$GIT_TRUNK_CMD --store docs verify > verify.out 2> /dev/null
+if ! grep -q '"health":"ok"' verify.json || [ "$(grep -o '"status":"ok"' verify.json | wc -l)" != "5" ] \
+    || ! grep -q "^remote *ok *'origin' is at $(git rev-parse --short refs/trunk/docs), like the local ref$" verify.out; then
+    echo "ERROR: verify did not pass a sound store:"; cat verify.json verify.out
+    exit 1
+fi
+echo "VERIFY: verify runs the ref, objects, checkout, head and remote checks on a sound store and exits with 0."
+
+# Scenario 56.2: warnings do not fail the store
+echo "INFO: Scenario 2: verify with warnings"
+echo "Unpushed" > .trunk/docs/unpushed.md
+$GIT_TRUNK_CMD --store docs commit --force -m "Unpushed" 2> /dev/null
+git -C .trunk/docs checkout -q --detach
+$GIT_TRUNK_CMD --store docs verify --no-table > warn.txt 2> /dev/null
+git -C .trunk/docs checkout -q main
+if ! grep -q "HEAD is detached" warn.txt || ! grep -q "^Detail: 'origin' is at .*, an ancestor of the local ref$" warn.txt; then
+    echo "ERROR: verify did not warn about a detached checkout or report the unpushed commit:"; cat warn.txt
+    exit 1
+fi
+echo "VERIFY: verify warns about a detached checkout, reports a ref ahead of the remote as sound and still exits with 0."
+
+# Scenario 56.3: a missing object and a missing store fail
+echo "INFO: Scenario 3: verify failures"
+blob=$(echo "soon missing" | git hash-object -w --stdin)
+tree=$(printf "100644 blob %s\tlost.md\n" "$blob" | git mktree)
+git update-ref refs/trunk/broken "$(git commit-tree -m "Initial commit for store 'broken'" "$tree")"
+rm ".git/objects/${blob:0:2}/${blob:2}"
+set +e
+$GIT_TRUNK_CMD --store broken verify --local --json > broken.json 2> /dev/null
+broken=$?
+$GIT_TRUNK_CMD --store missing verify --local > missing.out 2> /dev/null
+missing=$?
+set -e
+if [ "$broken" != "1" ] || ! grep -q '"health":"fail"' broken.json || ! grep -q '"check":"objects","status":"fail"' broken.json \
+    || ! grep -q '"check":"remote","status":"skipped"' broken.json || [ "$missing" != "1" ] || ! grep -q "^ref *fail *refs/trunk/missing does not exist" missing.out; then
+    echo "ERROR: verify of a store with a missing object exited with $broken, of a missing store with $missing (expected 1):"; cat broken.json missing.out
+    exit 1
+fi
+echo "VERIFY: verify fails a store with a missing object or without a ref with exit code 1, and --local skips the remote check."
+
+echo "SUCCESS: test_verify completed."
@@ -0,0 +1,87 @@
+#!/bin/bash
+# test_worktree.sh
+
+set -euo pipefail
+# set -x # Uncomment for debugging
+
+TEST_DIR="$1"
+GIT_TRUNK_CMD="$2"
+
+export GIT_TRUNK_NONINTERACTIVE=1
+
+echo "INFO: Starting test_worktree in $TEST_DIR"
+setup_repo
+for store in docs notes; do
+    $GIT_TRUNK_CMD --store "$store" init 2> /dev/null
+    $GIT_TRUNK_CMD --store "$store" commit --force -m "Start" 2> /dev/null
+done
+git worktree add -q -b second ../second
+MAIN="$TEST_DIR/repo"
+SECOND="$TEST_DIR/second"
+COMMON="$(cd "$(git rev-parse --git-common-dir)" && pwd)"
+
+# Scenario 14.1: each worktree has its own checkout of the same store
+echo "INFO: Scenario 1: side-by-side checkouts"
+(cd "$SECOND" && $GIT_TRUNK_CMD --store docs checkout 2> /dev/null)
+echo "From main." > "$MAIN/.trunk/docs/main.md"
+(cd "$MAIN" && $GIT_TRUNK_CMD --store docs commit --force -m "From main" 2> /dev/null)
+if [ ! -d "$SECOND/.trunk/docs/.git" ] || [ -e "$SECOND/.trunk/docs/main.md" ] \
+    || [ "$(git -C "$SECOND" rev-parse refs/trunk/docs)" != "$(git -C "$MAIN" rev-parse refs/trunk/docs)" ]; then
+    echo "ERROR: the second worktree did not get its own checkout, or does not see the shared refs/trunk/docs"
+    exit 1
+fi
+echo "VERIFY: Each worktree checks the store out into its own .trunk, and both see the one refs/trunk/docs."
+
+# Scenario 14.2: worktree share points every worktree at one .trunk
+echo "INFO: Scenario 2: worktree share"
+(cd "$SECOND" && $GIT_TRUNK_CMD worktree share 2> share.err)
+if ! grep -q "^shared_trunk_dir = \"$MAIN/.trunk\"$" "$COMMON/trunk.toml" || ! grep -q "$SECOND/.trunk holds its own store checkouts" "$SECOND/share.err"; then
+    echo "ERROR: worktree share did not record the main worktree's .trunk or warn about the second one:"; cat "$COMMON/trunk.toml" "$SECOND/share.err"
+    exit 1
+fi
+echo "From second." > "$MAIN/.trunk/docs/second.md"
+(cd "$SECOND" && $GIT_TRUNK_CMD --store docs commit --force -m "From second" 2> /dev/null)
+if ! git cat-file -e refs/trunk/docs:second.md || [ -e "$SECOND/.trunk/docs/second.md" ]; then
+    echo "ERROR: commit in the second worktree did not use the shared checkout"
+    exit 1
+fi
+(cd "$SECOND" && $GIT_TRUNK_CMD doctor --fix > /dev/null 2>&1)
+if [ -e "$SECOND/.trunk" ] || [ ! -d "$MAIN/.trunk/docs/.git" ]; then
+    echo "ERROR: doctor --fix did not remove the duplicate checkout of the second worktree, or removed the shared one"
+    exit 1
+fi
+echo "VERIFY: After worktree share, commands in the second worktree use the main worktree's .trunk, and doctor --fix removes the duplicate."
+
+# Scenario 14.3: both worktrees write into stores at the same time
+echo "INFO: Scenario 3: concurrent writes"
+echo "Put from main." > "$MAIN/from-main.md"
+echo "Put from second." > "$SECOND/from-second.md"
+(cd "$MAIN" && $GIT_TRUNK_CMD --store docs put from-main.md -m "Put from main" 2> "$TEST_DIR/main-put.err") &
+main_put=$!
+(cd "$SECOND" && $GIT_TRUNK_CMD --store notes put from-second.md -m "Put from second" 2> "$TEST_DIR/second-put.err") &
+second_put=$!
+wait "$main_put"
+wait "$second_put"
+if [ "$(git cat-file -p refs/trunk/docs:from-main.md)" != "Put from main." ] || [ "$(git cat-file -p refs/trunk/notes:from-second.md)" != "Put from second." ] \
+    || ls "$COMMON" | grep -q "^trunk-index-"; then
+    echo "ERROR: concurrent puts from two worktrees lost a file or left a temporary index in $COMMON:"; ls "$COMMON"; cat "$TEST_DIR/main-put.err" "$TEST_DIR/second-put.err"
+    exit 1
+fi
+(cd "$SECOND" && $GIT_TRUNK_CMD --store docs put from-second.md -m "Put from second" 2> /dev/null)
+if [ "$(git log --format=%s -2 refs/trunk/docs | tr '\n' '|')" != "Put from second|Put from main|" ]; then
+    echo "ERROR: puts from both worktrees did not stack on refs/trunk/docs:"; git log --oneline refs/trunk/docs
+    exit 1
+fi
+echo "VERIFY: Puts from both worktrees at once each use their own temporary index in the common git directory and remove it; puts to one store stack up."
+
+# Scenario 14.4: worktree unshare
+echo "INFO: Scenario 4: worktree unshare"
+(cd "$MAIN" && $GIT_TRUNK_CMD worktree unshare 2> /dev/null)
+(cd "$SECOND" && $GIT_TRUNK_CMD --store notes checkout 2> /dev/null)
+if grep -q "shared_trunk_dir" "$COMMON/trunk.toml" || [ ! -f "$SECOND/.trunk/notes/from-second.md" ] || [ ! -d "$MAIN/.trunk/docs/.git" ]; then
+    echo "ERROR: worktree unshare did not give the second worktree its own .trunk again:"; cat "$COMMON/trunk.toml"
+    exit 1
+fi
+echo "VERIFY: worktree unshare removes shared_trunk_dir, the second worktree checks out into its own .trunk again and the main one keeps its checkouts."
+
+echo "SUCCESS: test_worktree completed."